@@ -0,0 +1,311 @@
+//! Line-oriented review for screen readers and dumb terminals (`--accessible`).
+//!
+//! Instead of the full-screen TUI, each unreviewed hunk is printed as plain
+//! text followed by a one-line prompt. Nothing is redrawn or positioned, so
+//! the transcript reads top to bottom and works with any terminal.
+
+use crate::prompt::{self, Answer, Command};
+use crate::session::{ReviewSession, SessionError};
+use crate::state::StateError;
+use crate::{HunkStatus, parser};
+use std::io::{BufRead, Write};
+use thiserror::Error;
+
+/// Errors that can occur during an accessible review.
+#[derive(Debug, Error)]
+pub enum AccessibleError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error(transparent)]
+    State(#[from] StateError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, AccessibleError>;
+
+const COMMANDS: &[Command] = &[
+    Command {
+        keys: &["a", "approve"],
+        answer: Answer::Approve,
+    },
+    Command {
+        keys: &["x", "needs changes"],
+        answer: Answer::NeedsChanges,
+    },
+    Command {
+        keys: &["s", "skip"],
+        answer: Answer::Skip,
+    },
+    Command {
+        keys: &["c", "comment"],
+        answer: Answer::Comment,
+    },
+    Command {
+        keys: &["r", "repeat"],
+        answer: Answer::Repeat,
+    },
+    Command {
+        keys: &["q", "quit"],
+        answer: Answer::Quit,
+    },
+    Command {
+        keys: &["?", "help"],
+        answer: Answer::Help,
+    },
+];
+
+const HELP: &str =
+    "Commands: a approve, x needs changes, s skip, c comment, r repeat the hunk, q quit, ? help.";
+
+/// Counts of what was done during an accessible review.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outcome {
+    pub approved: usize,
+    pub flagged: usize,
+    pub skipped: usize,
+    pub comments: usize,
+}
+
+/// What to do with the current hunk after a command.
+enum Next {
+    Stay,
+    Advance,
+    Quit,
+}
+
+/// Review the unreviewed hunks of `session`, reading commands from `input`.
+///
+/// End of input quits, keeping everything decided so far.
+pub fn run(
+    session: &mut ReviewSession,
+    label: &str,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<Outcome> {
+    let pending = session.unreviewed_hunks();
+
+    let total: usize = session.files().iter().map(|file| file.hunks.len()).sum();
+    writeln!(
+        output,
+        "Reviewing {}: {} hunks in {} files, {} unreviewed.",
+        label,
+        total,
+        session.files().len(),
+        pending.len()
+    )?;
+    writeln!(output, "{}", HELP)?;
+
+    let mut outcome = Outcome::default();
+    let mut current_file = None;
+    'hunks: for (position, &(file_idx, hunk_idx)) in pending.iter().enumerate() {
+        if current_file != Some(file_idx) {
+            current_file = Some(file_idx);
+            writeln!(output)?;
+            writeln!(
+                output,
+                "File {} of {}: {}",
+                file_idx + 1,
+                session.files().len(),
+                session.files()[file_idx].path.display()
+            )?;
+        }
+        print_hunk(session, file_idx, hunk_idx, position, pending.len(), output)?;
+
+        let question = format!(
+            "Hunk {}. Command [{}]: ",
+            position + 1,
+            prompt::choices(COMMANDS)
+        );
+        loop {
+            let Some(answer) = prompt::ask(COMMANDS, &question, HELP, input, output)? else {
+                writeln!(output)?;
+                break 'hunks;
+            };
+            if answer == Answer::Repeat {
+                print_hunk(session, file_idx, hunk_idx, position, pending.len(), output)?;
+                continue;
+            }
+            match apply(
+                session,
+                file_idx,
+                hunk_idx,
+                answer,
+                input,
+                output,
+                &mut outcome,
+            )? {
+                Next::Stay => {}
+                Next::Advance => break,
+                Next::Quit => break 'hunks,
+            }
+        }
+    }
+
+    let progress = session.progress()?;
+    writeln!(output)?;
+    writeln!(
+        output,
+        "Done: {} approved, {} need changes, {} skipped, {} comments. {} of {} hunks reviewed.",
+        outcome.approved,
+        outcome.flagged,
+        outcome.skipped,
+        outcome.comments,
+        progress.reviewed,
+        progress.total_hunks
+    )?;
+    Ok(outcome)
+}
+
+/// Print a hunk with a plain-text header announcing its position and size.
+fn print_hunk(
+    session: &ReviewSession,
+    file_idx: usize,
+    hunk_idx: usize,
+    position: usize,
+    pending: usize,
+    output: &mut impl Write,
+) -> Result<()> {
+    let hunk = &session.files()[file_idx].hunks[hunk_idx];
+    let (added, removed) = parser::count_changes(&hunk.content);
+    writeln!(output)?;
+    writeln!(
+        output,
+        "Hunk {} of {}, new lines {} to {}: {} added, {} removed.",
+        position + 1,
+        pending,
+        hunk.new_start,
+        hunk.new_start + hunk.new_count.saturating_sub(1),
+        added,
+        removed
+    )?;
+    if !hunk.section.is_empty() {
+        writeln!(output, "In: {}", hunk.section.trim())?;
+    }
+    for line in hunk.content.lines() {
+        writeln!(output, "{}", line)?;
+    }
+    writeln!(output, "End of hunk.")?;
+    Ok(())
+}
+
+/// Carry out a command other than repeat on the current hunk.
+fn apply(
+    session: &mut ReviewSession,
+    file_idx: usize,
+    hunk_idx: usize,
+    answer: Answer,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    outcome: &mut Outcome,
+) -> Result<Next> {
+    let path = session.files()[file_idx].path.to_string_lossy().to_string();
+    let hash = session.files()[file_idx].hunks[hunk_idx]
+        .content_hash
+        .clone();
+
+    match answer {
+        Answer::Approve => {
+            session.set_status(&path, &hash, HunkStatus::Reviewed)?;
+            outcome.approved += 1;
+            writeln!(output, "Approved.")?;
+            Ok(Next::Advance)
+        }
+        Answer::NeedsChanges => {
+            session.set_status(&path, &hash, HunkStatus::NeedsChanges)?;
+            outcome.flagged += 1;
+            writeln!(output, "Marked as needing changes.")?;
+            Ok(Next::Advance)
+        }
+        Answer::Skip => {
+            outcome.skipped += 1;
+            writeln!(output, "Skipped.")?;
+            Ok(Next::Advance)
+        }
+        Answer::Comment => {
+            write!(output, "Comment (empty to cancel): ")?;
+            output.flush()?;
+            match read_line(input)? {
+                Some(body) if !body.is_empty() => {
+                    let base_ref = session.base_ref().to_string();
+                    session.db().add_comment(&base_ref, &path, &hash, &body)?;
+                    outcome.comments += 1;
+                    writeln!(output, "Comment saved.")?;
+                }
+                _ => writeln!(output, "No comment saved.")?,
+            }
+            Ok(Next::Stay)
+        }
+        Answer::Quit => Ok(Next::Quit),
+        Answer::Repeat | Answer::Help => Ok(Next::Stay),
+    }
+}
+
+/// Read one trimmed line, or `None` at end of input.
+fn read_line(input: &mut impl BufRead) -> Result<Option<String>> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-old
++new
+@@ -10 +10 @@ fn main
+-ten
++TEN
+@@ -20 +20 @@
+-twenty
++TWENTY
+";
+
+    #[test]
+    fn commands_update_review_state() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let mut session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+
+        let mut input = "?\nA\nc\nplease rename\nr\nx\n".as_bytes();
+        let mut output = Vec::new();
+        let outcome = run(&mut session, "main", &mut input, &mut output).unwrap();
+        assert_eq!(
+            outcome,
+            Outcome {
+                approved: 1,
+                flagged: 1,
+                skipped: 0,
+                comments: 1,
+            }
+        );
+
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.contains("Reviewing main: 3 hunks in 1 files, 3 unreviewed."));
+        assert!(
+            text.contains("Hunk 2 of 3, new lines 10 to 10: 1 added, 1 removed.\nIn: fn main\n")
+        );
+        assert_eq!(text.matches("-ten\n+TEN\n").count(), 2);
+        assert!(text.contains(
+            "Done: 1 approved, 1 need changes, 0 skipped, 1 comments. 1 of 3 hunks reviewed."
+        ));
+
+        let statuses: Vec<HunkStatus> = session.files()[0].hunks.iter().map(|h| h.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                HunkStatus::Reviewed,
+                HunkStatus::NeedsChanges,
+                HunkStatus::Unreviewed
+            ]
+        );
+        assert_eq!(session.comments().unwrap()[0].body, "please rename");
+    }
+}
@@ -0,0 +1,205 @@
+//! Optional LLM-written hunk summaries.
+//!
+//! Nothing here runs unless the user opts in by naming an endpoint in git
+//! config (`git-review.aiEndpoint`). The endpoint is never read from
+//! `.git-review.toml`, so a branch under review can't send its diff, or a
+//! secret, anywhere. Requests go to either an Ollama server (`/api/generate`)
+//! or any OpenAI-compatible chat completions endpoint, and the model is asked
+//! for a one-line summary plus a one-line risk note. Results
+//! are stored in the review database by hunk content hash, so each hunk is only
+//! summarized once and cached summaries stay available offline.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Longest hunk text sent to the model; longer hunks are cut off.
+pub const MAX_HUNK_CHARS: usize = 8000;
+
+/// How long a single request may take before it is given up on.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Git config key naming the endpoint; summaries are off unless it is set.
+pub const ENDPOINT_KEY: &str = "git-review.aiEndpoint";
+
+/// Git config key naming the environment variable that holds a bearer token.
+pub const KEY_ENV_KEY: &str = "git-review.aiKeyEnv";
+
+/// Errors that can occur while asking a model for a summary.
+#[derive(Debug, Error)]
+pub enum AiError {
+    #[error("request failed: {0}")]
+    Request(String),
+    #[error("unexpected response: {0}")]
+    Response(String),
+}
+
+pub type Result<T> = std::result::Result<T, AiError>;
+
+/// Request format spoken by the endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AiApi {
+    /// Ollama's `/api/generate`.
+    #[default]
+    Ollama,
+    /// OpenAI-compatible `/v1/chat/completions`.
+    OpenAi,
+}
+
+/// `[ai]` section of `.git-review.toml`: how to talk to the endpoint the
+/// user opted in with.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AiConfig {
+    /// Model name sent with each request.
+    pub model: String,
+    /// Request format of the endpoint.
+    pub api: AiApi,
+}
+
+/// Where summaries are requested from, as set in the user's git config.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AiEndpoint {
+    /// URL requests are POSTed to, e.g. `http://localhost:11434/api/generate`.
+    pub url: String,
+    /// Environment variable holding a bearer token for the endpoint.
+    pub key_env: Option<String>,
+}
+
+impl AiEndpoint {
+    /// The endpoint from `git-review.aiEndpoint` and `git-review.aiKeyEnv`,
+    /// or `None` if the user hasn't opted in.
+    pub fn from_git_config() -> Option<Self> {
+        let url = crate::git::config_value(ENDPOINT_KEY)
+            .ok()
+            .flatten()
+            .filter(|url| !url.is_empty())?;
+        let key_env = crate::git::config_value(KEY_ENV_KEY).ok().flatten();
+        Some(Self { url, key_env })
+    }
+}
+
+/// A model's take on one hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunkSummary {
+    /// One line describing what the hunk does.
+    pub summary: String,
+    /// One line on what could go wrong, or `None` when the model saw no risk.
+    pub risk: Option<String>,
+}
+
+/// Build the prompt for a hunk of `file_path`.
+pub fn prompt(file_path: &str, content: &str) -> String {
+    let content = match content.char_indices().nth(MAX_HUNK_CHARS) {
+        Some((end, _)) => &content[..end],
+        None => content,
+    };
+    format!(
+        "You are reviewing one hunk of a diff to {file_path}.\n\
+         Reply with exactly two lines and nothing else:\n\
+         SUMMARY: <one sentence describing what the change does>\n\
+         RISK: <one sentence on what could break, or \"none\">\n\
+         \n\
+         {content}"
+    )
+}
+
+/// Ask the configured model to summarize a hunk. Blocks until the response arrives.
+pub fn summarize(
+    config: &AiConfig,
+    endpoint: &AiEndpoint,
+    file_path: &str,
+    content: &str,
+) -> Result<HunkSummary> {
+    let prompt = prompt(file_path, content);
+    let body = match config.api {
+        AiApi::Ollama => json!({ "model": config.model, "prompt": prompt, "stream": false }),
+        AiApi::OpenAi => json!({
+            "model": config.model,
+            "messages": [{ "role": "user", "content": prompt }],
+        }),
+    };
+
+    let mut request = ureq::post(&endpoint.url).timeout(REQUEST_TIMEOUT);
+    if let Some(var) = &endpoint.key_env {
+        let key =
+            std::env::var(var).map_err(|_| AiError::Request(format!("{} is not set", var)))?;
+        request = request.set("Authorization", &format!("Bearer {}", key));
+    }
+    let response: Value = request
+        .send_json(body)
+        .map_err(|e| AiError::Request(e.to_string()))?
+        .into_json()
+        .map_err(|e| AiError::Response(e.to_string()))?;
+
+    let text = match config.api {
+        AiApi::Ollama => response["response"].as_str(),
+        AiApi::OpenAi => response["choices"][0]["message"]["content"].as_str(),
+    };
+    let text = text.ok_or_else(|| AiError::Response("no text in response".to_string()))?;
+    parse_reply(text).ok_or_else(|| AiError::Response("no SUMMARY line in reply".to_string()))
+}
+
+/// Pull the `SUMMARY:` and `RISK:` lines out of a model's reply.
+pub fn parse_reply(text: &str) -> Option<HunkSummary> {
+    let field = |name: &str| {
+        text.lines().find_map(|line| {
+            let line = line.trim().trim_start_matches(['*', '-', ' ']);
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim().trim_matches('*').trim();
+            (key.trim().trim_matches('*').eq_ignore_ascii_case(name) && !value.is_empty())
+                .then(|| value.to_string())
+        })
+    };
+
+    let summary = field("summary")?;
+    let risk = field("risk").filter(|risk| {
+        !matches!(
+            risk.trim_end_matches('.').to_ascii_lowercase().as_str(),
+            "none" | "n/a" | "no risk"
+        )
+    });
+    Some(HunkSummary { summary, risk })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_reply_reads_both_lines() {
+        let reply =
+            "Sure!\nSUMMARY: Adds a retry loop to fetch.\n**RISK:** Retries are unbounded.\n";
+        assert_eq!(
+            parse_reply(reply),
+            Some(HunkSummary {
+                summary: "Adds a retry loop to fetch.".to_string(),
+                risk: Some("Retries are unbounded.".to_string()),
+            })
+        );
+
+        let safe = parse_reply("summary: Renames a variable\nrisk: None.").unwrap();
+        assert_eq!(safe.risk, None);
+
+        assert_eq!(parse_reply("I can't help with that."), None);
+    }
+
+    #[test]
+    fn prompt_truncates_long_hunks() {
+        let content = "+x".repeat(MAX_HUNK_CHARS);
+        let prompt = prompt("src/a.rs", &content);
+        assert!(prompt.contains("src/a.rs"));
+        assert!(prompt.len() < content.len());
+    }
+
+    #[test]
+    fn repo_config_cannot_name_an_endpoint() {
+        let config: AiConfig = toml::from_str("model = \"llama3.2\"\napi = \"openai\"").unwrap();
+        assert_eq!(config.api, AiApi::OpenAi);
+
+        assert!(toml::from_str::<AiConfig>("endpoint = \"https://evil.example\"").is_err());
+        assert!(toml::from_str::<AiConfig>("api_key_env = \"AWS_SECRET_ACCESS_KEY\"").is_err());
+    }
+}
@@ -0,0 +1,523 @@
+//! Rules that approve trivial hunks without a human looking at them.
+//!
+//! Rules come from the `[auto_approve]` section of `.git-review.toml` and are
+//! applied by [`crate::state::ReviewDb::sync_with_diff`] to hunks it sees for
+//! the first time. Approved hunks record the rule that matched, so the TUI and
+//! status output can tell them apart from hunks a reviewer approved. `files`
+//! approves whatever it matches, so it is a personal setting (see
+//! [`crate::config::PERSONAL_KEYS`]), and changes to the config files
+//! themselves are never auto-approved.
+
+use crate::config::{CONFIG_FILE, LOCAL_CONFIG_FILE};
+use crate::state::{self, ReviewDb};
+use crate::{DiffFile, DiffHunk, HunkStatus};
+use globset::GlobSet;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+/// Rule name recorded for hunks in files matching `files`.
+pub const RULE_FILES: &str = "files";
+/// Rule name recorded for hunks that only change comments.
+pub const RULE_COMMENTS: &str = "comments";
+/// Rule name recorded for hunks that only reorder imports.
+pub const RULE_IMPORTS: &str = "imports";
+/// Rule name recorded for hunks left out of a `sample` spot check.
+pub const RULE_SAMPLED_OUT: &str = "sampled-out";
+/// Rule name recorded for hunks whose patch id was reviewed in another range.
+pub const RULE_INHERITED: &str = "inherited";
+/// Rule name recorded for hunks `[moved] auto_approve` approved.
+pub const RULE_MOVED: &str = "moved";
+
+/// Line prefixes that start an import in the languages git-review knows about.
+const IMPORT_PREFIXES: &[&str] = &[
+    "use ",
+    "pub use ",
+    "import ",
+    "from ",
+    "#include ",
+    "require ",
+];
+
+/// `[auto_approve]` section of `.git-review.toml`. Every rule is off by default.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AutoApproveConfig {
+    /// Approve hunks whose changed lines are all comments or blank.
+    pub comments: bool,
+    /// Approve hunks that only reorder import lines.
+    pub imports: bool,
+    /// Path globs whose hunks are always approved (lockfiles, version files).
+    /// Only read from `.git-review.local.toml`.
+    pub files: Vec<String>,
+    /// Approve hunks whose [`patch_id`] a person already reviewed in another
+    /// range (backports, cherry-picks, rebased branches).
+    pub inherit: bool,
+}
+
+/// Compiled auto-approval rules.
+#[derive(Debug, Clone, Default)]
+pub struct AutoApprover {
+    comments: bool,
+    imports: bool,
+    files: GlobSet,
+    inherit: bool,
+}
+
+impl AutoApprover {
+    /// Compile the rules of an `[auto_approve]` section.
+    pub fn new(config: &AutoApproveConfig) -> Result<Self, globset::Error> {
+        let mut files = globset::GlobSetBuilder::new();
+        for pattern in &config.files {
+            files.add(globset::Glob::new(pattern)?);
+        }
+        Ok(Self {
+            comments: config.comments,
+            imports: config.imports,
+            files: files.build()?,
+            inherit: config.inherit,
+        })
+    }
+
+    /// Whether hunks inherit approval from the same change reviewed elsewhere.
+    pub fn inherits(&self) -> bool {
+        self.inherit
+    }
+
+    /// Name of the first rule that approves `hunk` of `file_path`, if any.
+    pub fn matching_rule(&self, file_path: &str, hunk: &DiffHunk) -> Option<&'static str> {
+        let changed: Vec<&str> = hunk
+            .content
+            .lines()
+            .filter(|line| line.starts_with('+') || line.starts_with('-'))
+            .collect();
+        // A branch must not be able to approve its own change to the rules
+        if changed.is_empty() || [CONFIG_FILE, LOCAL_CONFIG_FILE].contains(&file_path) {
+            return None;
+        }
+
+        if self.files.is_match(file_path) {
+            Some(RULE_FILES)
+        } else if self.comments && only_comments(file_path, &changed) {
+            Some(RULE_COMMENTS)
+        } else if self.imports && only_reordered_imports(&changed) {
+            Some(RULE_IMPORTS)
+        } else {
+            None
+        }
+    }
+}
+
+/// Normalized hash of the change a hunk makes, like `git patch-id`.
+///
+/// Only the file path and the added and removed lines count, with runs of
+/// whitespace collapsed and line ends trimmed, so the same change hashes the
+/// same in every range it appears in, wherever it sits in the file and whatever
+/// context surrounds it. Whitespace between tokens still counts, so `a b` and
+/// `ab` stay different changes.
+pub fn patch_id(file_path: &str, hunk: &DiffHunk) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(file_path.as_bytes());
+    for line in hunk.content.iter_lines() {
+        if line.starts_with('+') || line.starts_with('-') {
+            hasher.update(b"\n");
+            hasher.update(&line.as_bytes()[..1]);
+            for (i, word) in line[1..].split_whitespace().enumerate() {
+                if i > 0 {
+                    hasher.update(b" ");
+                }
+                hasher.update(word.as_bytes());
+            }
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Outcome of [`sample`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sample {
+    /// Unreviewed hunks left for review.
+    pub kept: usize,
+    /// Unreviewed hunks approved as [`RULE_SAMPLED_OUT`].
+    pub sampled_out: usize,
+}
+
+/// Spot-check a diff: keep `percent` of its unreviewed hunks (at least one) for
+/// review and approve the rest as [`RULE_SAMPLED_OUT`].
+///
+/// The kept hunks are picked pseudo-randomly from `seed`, so the same seed
+/// reproduces the same sample of the same diff.
+pub fn sample(
+    db: &mut ReviewDb,
+    base_ref: &str,
+    files: &[DiffFile],
+    percent: u8,
+    seed: u64,
+) -> state::Result<Sample> {
+    let mut candidates = Vec::new();
+    for file in files {
+        let file_path = file.path.to_string_lossy();
+        for hunk in &file.hunks {
+            if db.get_status(base_ref, &file_path, &hunk.content_hash)? == HunkStatus::Unreviewed {
+                let mut hasher = Sha256::new();
+                hasher.update(seed.to_le_bytes());
+                hasher.update(file_path.as_bytes());
+                hasher.update(hunk.content_hash.as_bytes());
+                candidates.push((hasher.finalize(), file_path.to_string(), &hunk.content_hash));
+            }
+        }
+    }
+    candidates.sort_unstable();
+
+    let kept = (candidates.len() * usize::from(percent.min(100)))
+        .div_ceil(100)
+        .max(1)
+        .min(candidates.len());
+    for (_, file_path, content_hash) in &candidates[kept..] {
+        db.auto_approve(base_ref, file_path, content_hash, RULE_SAMPLED_OUT)?;
+    }
+    Ok(Sample {
+        kept,
+        sampled_out: candidates.len() - kept,
+    })
+}
+
+/// Whether every changed line of `hunk` matches `pattern`, for approving
+/// mechanical edits such as version bumps in bulk. Lines are matched without
+/// their `+`/`-` marker; hunks without changed lines never match.
+pub fn matches_pattern(hunk: &DiffHunk, pattern: &Regex) -> bool {
+    let mut changed = hunk
+        .content
+        .lines()
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+        .peekable();
+    changed.peek().is_some() && changed.all(|line| pattern.is_match(&line[1..]))
+}
+
+/// The unreviewed hunks of `files` (or of `file_path` alone) that
+/// [match](matches_pattern) `pattern`, with their file paths.
+pub fn matching_hunks<'a>(
+    db: &ReviewDb,
+    base_ref: &str,
+    files: &'a [DiffFile],
+    pattern: &Regex,
+    file_path: Option<&str>,
+) -> state::Result<Vec<(String, &'a DiffHunk)>> {
+    let mut matching = Vec::new();
+    for file in files {
+        let path = file.path.to_string_lossy();
+        if file_path.is_some_and(|only| only != path) {
+            continue;
+        }
+        for hunk in &file.hunks {
+            if matches_pattern(hunk, pattern)
+                && db.get_status(base_ref, &path, &hunk.content_hash)? == HunkStatus::Unreviewed
+            {
+                matching.push((path.to_string(), hunk));
+            }
+        }
+    }
+    Ok(matching)
+}
+
+/// Approve the hunks [`matching_hunks`] finds, as one bulk approval that can
+/// be undone. Returns how many were approved.
+pub fn approve_matching(
+    db: &mut ReviewDb,
+    base_ref: &str,
+    files: &[DiffFile],
+    pattern: &Regex,
+    file_path: Option<&str>,
+) -> state::Result<usize> {
+    let matching: Vec<(String, String)> = matching_hunks(db, base_ref, files, pattern, file_path)?
+        .into_iter()
+        .map(|(path, hunk)| (path, hunk.content_hash.clone()))
+        .collect();
+    db.approve_hunks(base_ref, file_path, &matching)
+}
+
+/// Line comment markers for a file, by extension. Empty for unknown file types.
+fn comment_prefixes(file_path: &str) -> &'static [&'static str] {
+    let ext = Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("");
+    match ext {
+        "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "go" | "java" | "js" | "jsx" | "ts"
+        | "tsx" | "kt" | "scala" | "swift" => &["//", "/*", "*/", "* "],
+        "py" | "rb" | "sh" | "bash" | "pl" | "toml" | "yaml" | "yml" => &["#"],
+        "sql" | "lua" | "hs" => &["--"],
+        _ => &[],
+    }
+}
+
+/// Whether every changed line is a comment or blank, with at least one comment.
+///
+/// Block comments must close within the changed lines of each side, with
+/// nothing but whitespace after `*/`, so `/* x */ code();` and a lone `/*`
+/// that comments out the code below don't count.
+fn only_comments(file_path: &str, changed: &[&str]) -> bool {
+    let prefixes = comment_prefixes(file_path);
+    if prefixes.is_empty() {
+        return false;
+    }
+    let blocks = prefixes.contains(&"/*");
+    let mut any_comment = false;
+    // Whether the removed and the added lines are inside a block comment
+    let (mut in_removed, mut in_added) = (false, false);
+    for line in changed {
+        let text = line[1..].trim();
+        if text.is_empty() {
+            continue;
+        }
+        let comment = if blocks {
+            let in_block = if line.starts_with('-') {
+                &mut in_removed
+            } else {
+                &mut in_added
+            };
+            c_comment_line(text, in_block)
+        } else {
+            prefixes.iter().any(|prefix| text.starts_with(prefix))
+        };
+        if !comment {
+            return false;
+        }
+        any_comment = true;
+    }
+    any_comment && !in_removed && !in_added
+}
+
+/// Whether a trimmed line is a `//` comment or part of a `/* */` comment,
+/// tracking whether a block comment is still open after it.
+fn c_comment_line(text: &str, in_block: &mut bool) -> bool {
+    // A line starting with `*` continues a block comment opened before the hunk
+    let rest = if *in_block || text == "*" || text.starts_with("* ") || text.starts_with("*/") {
+        text
+    } else if let Some(rest) = text.strip_prefix("/*") {
+        *in_block = true;
+        rest
+    } else {
+        return text.starts_with("//");
+    };
+    match rest.split_once("*/") {
+        Some((_, after)) => {
+            *in_block = false;
+            after.trim().is_empty()
+        }
+        None => true,
+    }
+}
+
+/// Whether the changed lines are imports, removed and added in a different order.
+fn only_reordered_imports(changed: &[&str]) -> bool {
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    for line in changed {
+        let text = line[1..].trim();
+        if text.is_empty() {
+            continue;
+        }
+        if !IMPORT_PREFIXES
+            .iter()
+            .any(|prefix| text.starts_with(prefix))
+        {
+            return false;
+        }
+        if line.starts_with('-') {
+            removed.push(text);
+        } else {
+            added.push(text);
+        }
+    }
+    removed.sort_unstable();
+    added.sort_unstable();
+    !removed.is_empty() && removed == added
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_diff;
+
+    fn hunk(path: &str, body: &str) -> DiffHunk {
+        let diff = format!(
+            "diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n@@ -1,3 +1,3 @@\n{1}",
+            path, body
+        );
+        parse_diff(&diff).remove(0).hunks.remove(0)
+    }
+
+    fn approver(config: &str) -> AutoApprover {
+        let config: AutoApproveConfig = toml::from_str(config).unwrap();
+        AutoApprover::new(&config).unwrap()
+    }
+
+    #[test]
+    fn comment_only_hunks_match() {
+        let rules = approver("comments = true");
+        let comment = hunk("a.rs", " fn a() {}\n-// old\n+// new\n+\n fn b() {}\n");
+        assert_eq!(rules.matching_rule("a.rs", &comment), Some(RULE_COMMENTS));
+
+        let code = hunk("a.rs", " fn a() {}\n-// old\n+let x = 1; // new\n");
+        assert_eq!(rules.matching_rule("a.rs", &code), None);
+
+        let block = hunk("a.rs", "+/**\n+ * Docs.\n+ */\n fn a() {}\n");
+        assert_eq!(rules.matching_rule("a.rs", &block), Some(RULE_COMMENTS));
+        let doc_line = hunk("a.rs", " /**\n- * Old.\n+ * New.\n */\n");
+        assert_eq!(rules.matching_rule("a.rs", &doc_line), Some(RULE_COMMENTS));
+
+        // Code after a closed block comment, or code commented out by an
+        // unclosed one, is not a comment change
+        for body in [
+            "+/* x */ evil();\n",
+            "+ * x */ evil();\n",
+            "+/* x\n+   */ evil();\n",
+            "+/*\n fn a() {}\n",
+            "-/*\n fn a() {}\n",
+        ] {
+            assert_eq!(
+                rules.matching_rule("a.rs", &hunk("a.rs", body)),
+                None,
+                "{}",
+                body
+            );
+        }
+
+        // Unknown file types never count as comment-only
+        let text = hunk("notes.txt", "-// old\n+// new\n");
+        assert_eq!(rules.matching_rule("notes.txt", &text), None);
+
+        assert_eq!(approver("").matching_rule("a.rs", &comment), None);
+    }
+
+    #[test]
+    fn reordered_imports_match() {
+        let rules = approver("imports = true");
+        let reordered = hunk("a.rs", "-use b;\n use c;\n+use b;\n-use a;\n+use a;\n");
+        assert_eq!(rules.matching_rule("a.rs", &reordered), Some(RULE_IMPORTS));
+
+        let new_import = hunk("a.rs", "-use a;\n+use a;\n+use b;\n");
+        assert_eq!(rules.matching_rule("a.rs", &new_import), None);
+    }
+
+    #[test]
+    fn file_globs_match_any_change() {
+        let rules = approver(r#"files = ["Cargo.lock", "**/VERSION"]"#);
+        let bump = hunk("Cargo.lock", "-version = \"1.0.0\"\n+version = \"1.0.1\"\n");
+        assert_eq!(rules.matching_rule("Cargo.lock", &bump), Some(RULE_FILES));
+        assert_eq!(rules.matching_rule("pkg/VERSION", &bump), Some(RULE_FILES));
+        assert_eq!(rules.matching_rule("src/main.rs", &bump), None);
+    }
+
+    #[test]
+    fn config_file_changes_are_never_auto_approved() {
+        let rules = approver("files = [\"**\"]\ncomments = true");
+        let rule = hunk(
+            CONFIG_FILE,
+            "+[auto_approve]\n+files = [\"**\"]\n+# approve everything\n",
+        );
+        assert_eq!(rules.matching_rule(CONFIG_FILE, &rule), None);
+        let comment = hunk(LOCAL_CONFIG_FILE, "+# note\n");
+        assert_eq!(rules.matching_rule(LOCAL_CONFIG_FILE, &comment), None);
+        assert_eq!(rules.matching_rule("src/a.rs", &rule), Some(RULE_FILES));
+    }
+
+    #[test]
+    fn patch_id_ignores_context_and_whitespace() {
+        let change = hunk("a.rs", " fn a() {}\n-let x = 1;\n+let x = 2;\n");
+        let moved = hunk("a.rs", " fn b() {}\n-let x = 1;\n+let x =  2;\n");
+        assert_eq!(patch_id("a.rs", &change), patch_id("a.rs", &moved));
+
+        let reverted = hunk("a.rs", " fn a() {}\n-let x = 2;\n+let x = 1;\n");
+        assert_ne!(patch_id("a.rs", &change), patch_id("a.rs", &reverted));
+        assert_ne!(patch_id("a.rs", &change), patch_id("b.rs", &change));
+
+        let trailing = hunk("a.rs", "-let x = 1;\n+\tlet x = 2;  \n");
+        assert_eq!(patch_id("a.rs", &change), patch_id("a.rs", &trailing));
+        let spaced = hunk("a.rs", "-a b\n");
+        let joined = hunk("a.rs", "-ab\n");
+        assert_ne!(patch_id("a.rs", &spaced), patch_id("a.rs", &joined));
+    }
+
+    #[test]
+    fn sample_keeps_a_reproducible_fraction() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        let diff: String = (0..20)
+            .map(|i| format!("diff --git a/f{0}.rs b/f{0}.rs\n--- a/f{0}.rs\n+++ b/f{0}.rs\n@@ -1 +1 @@\n-a\n+b{0}\n", i))
+            .collect();
+        let files = parse_diff(&diff);
+        db.sync_with_diff("HEAD", &files).unwrap();
+
+        let result = sample(&mut db, "HEAD", &files, 10, 42).unwrap();
+        assert_eq!(
+            result,
+            Sample {
+                kept: 2,
+                sampled_out: 18
+            }
+        );
+        let progress = db.progress("HEAD").unwrap();
+        assert_eq!((progress.unreviewed, progress.auto_approved), (2, 18));
+
+        // The same seed picks the same hunks
+        let mut other = ReviewDb::open(&dir.path().join("other.db")).unwrap();
+        other.sync_with_diff("HEAD", &files).unwrap();
+        sample(&mut other, "HEAD", &files, 10, 42).unwrap();
+        let pending = |db: &ReviewDb| -> Vec<String> {
+            db.hunks("HEAD")
+                .unwrap()
+                .into_iter()
+                .filter(|h| h.status == HunkStatus::Unreviewed)
+                .map(|h| h.content_hash)
+                .collect()
+        };
+        assert_eq!(pending(&db), pending(&other));
+
+        // Sampling again only considers what is still unreviewed
+        assert_eq!(
+            sample(&mut db, "HEAD", &files, 50, 7).unwrap(),
+            Sample {
+                kept: 1,
+                sampled_out: 1
+            }
+        );
+    }
+
+    #[test]
+    fn pattern_approval_needs_every_changed_line_to_match() {
+        let pattern = Regex::new(r#"^version = "[\d.]+"$"#).unwrap();
+        let bump = hunk(
+            "a.toml",
+            " [package]\n-version = \"1.0.0\"\n+version = \"1.0.1\"\n",
+        );
+        assert!(matches_pattern(&bump, &pattern));
+        let mixed = hunk(
+            "a.toml",
+            "-version = \"1.0.0\"\n+version = \"1.0.1\"\n+edition = \"2024\"\n",
+        );
+        assert!(!matches_pattern(&mixed, &pattern));
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        let diff: String = ["a", "b", "c"]
+            .iter()
+            .map(|name| format!("diff --git a/{0}.toml b/{0}.toml\n--- a/{0}.toml\n+++ b/{0}.toml\n@@ -1 +1 @@\n-version = \"1.0.0\"\n+version = \"1.0.1\"\n@@ -9 +9 @@\n-name = \"{0}\"\n+name = \"{0}2\"\n", name))
+            .collect();
+        let files = parse_diff(&diff);
+        db.sync_with_diff("HEAD", &files).unwrap();
+
+        assert_eq!(
+            approve_matching(&mut db, "HEAD", &files, &pattern, Some("b.toml")).unwrap(),
+            1
+        );
+        assert_eq!(
+            approve_matching(&mut db, "HEAD", &files, &pattern, None).unwrap(),
+            2
+        );
+        let progress = db.progress("HEAD").unwrap();
+        assert_eq!((progress.reviewed, progress.unreviewed), (3, 3));
+    }
+}
@@ -0,0 +1,162 @@
+//! Running a configured lint or test command against the file under review.
+//!
+//! The command comes from the `[check]` section of `.git-review.local.toml` (it is
+//! a personal setting, see [`crate::config::PERSONAL_KEYS`]) and runs through
+//! `sh -c` in the repository root. Its output is collected line by line
+//! in the background so the TUI can show it while the command is still running.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use thiserror::Error;
+
+/// Placeholder in the command that is replaced with the current file's path.
+pub const FILE_PLACEHOLDER: &str = "{file}";
+
+/// Errors that can occur while running a check command.
+#[derive(Debug, Error)]
+pub enum CheckError {
+    #[error("failed to run check command: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, CheckError>;
+
+/// `[check]` section of `.git-review.local.toml`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheckConfig {
+    /// Shell command to run, e.g. `cargo clippy` or `cargo test {file}`.
+    pub command: Option<String>,
+}
+
+/// Substitute the (shell-quoted) file path for every [`FILE_PLACEHOLDER`] in `template`.
+pub fn expand_command(template: &str, file: &str) -> String {
+    template.replace(FILE_PLACEHOLDER, &shell_quote(file))
+}
+
+/// Quote a string for `sh` so paths with spaces or quotes pass through intact.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// A running (or finished) check command and the output it has produced so far.
+pub struct CheckRun {
+    command: String,
+    child: Child,
+    output: Receiver<String>,
+    lines: Vec<String>,
+    status: Option<ExitStatus>,
+}
+
+impl CheckRun {
+    /// Start `command` with `sh -c` in `dir`, capturing stdout and stderr.
+    pub fn start(command: &str, dir: &Path) -> Result<Self> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let (sender, output) = mpsc::channel();
+        if let Some(stdout) = child.stdout.take() {
+            forward_lines(stdout, sender.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_lines(stderr, sender);
+        }
+
+        Ok(Self {
+            command: command.to_string(),
+            child,
+            output,
+            lines: Vec::new(),
+            status: None,
+        })
+    }
+
+    /// Collect output produced since the last call and check whether the command exited.
+    pub fn poll(&mut self) {
+        self.lines.extend(self.output.try_iter());
+        if self.status.is_none() {
+            self.status = self.child.try_wait().ok().flatten();
+        }
+    }
+
+    /// The command line being run.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Output lines received so far, stdout and stderr interleaved.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Exit status, once the command has finished.
+    pub fn status(&self) -> Option<ExitStatus> {
+        self.status
+    }
+}
+
+impl Drop for CheckRun {
+    fn drop(&mut self) {
+        if self.status.is_none() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/// Send each line read from `reader` to `sender` from a background thread.
+fn forward_lines(reader: impl Read + Send + 'static, sender: Sender<String>) {
+    std::thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn expand_command_quotes_the_file() {
+        assert_eq!(
+            expand_command("cargo test {file}", "src/a b.rs"),
+            "cargo test 'src/a b.rs'"
+        );
+        assert_eq!(
+            expand_command("lint {file}", "it's.rs"),
+            r"lint 'it'\''s.rs'"
+        );
+        assert_eq!(expand_command("cargo clippy", "src/a.rs"), "cargo clippy");
+    }
+
+    #[test]
+    fn run_collects_output_and_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut run = CheckRun::start("echo out; echo err >&2; exit 3", dir.path()).unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while (run.status().is_none() || run.lines().len() < 2) && Instant::now() < deadline {
+            run.poll();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert_eq!(run.status().and_then(|s| s.code()), Some(3));
+        let mut lines = run.lines().to_vec();
+        lines.sort();
+        assert_eq!(lines, vec!["err", "out"]);
+    }
+}
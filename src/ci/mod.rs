@@ -0,0 +1,248 @@
+//! Review gate for CI (`ci-check`).
+//!
+//! CI runners don't have the developer's `.git/review-state`, so the review
+//! state travels as a JSON artifact instead: `ci-check --export` writes the
+//! status of every hunk in `<base>...HEAD`, and `ci-check` in the CI job
+//! re-computes the diff's content hashes and compares them with the artifact.
+//!
+//! The job fails when a hunk of the actual diff is not marked reviewed in the
+//! artifact, or when the artifact lists hunks the diff doesn't contain — the
+//! state was exported (or written by hand) for different content.
+
+use crate::state::{HunkRecord, status_from_str, status_to_string};
+use crate::{DiffFile, HunkStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Version written to new artifacts; other versions are rejected.
+pub const ARTIFACT_VERSION: u32 = 1;
+
+/// Errors that can occur while reading a state artifact.
+#[derive(Debug, Error)]
+pub enum CiError {
+    #[error("invalid state artifact: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("unsupported state artifact version {0} (expected {ARTIFACT_VERSION})")]
+    Version(u32),
+    #[error("invalid status {status:?} for {file} in state artifact")]
+    Status { file: String, status: String },
+}
+
+pub type Result<T> = std::result::Result<T, CiError>;
+
+/// Review state of a diff, as exported for CI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateArtifact {
+    pub version: u32,
+    /// Diff range the state was exported for (e.g. `origin/main...HEAD`).
+    pub range: String,
+    /// Commit `HEAD` pointed at when exporting.
+    pub head: String,
+    pub hunks: Vec<ArtifactHunk>,
+}
+
+/// One hunk of a [`StateArtifact`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactHunk {
+    pub file: String,
+    pub hash: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_by: Option<String>,
+}
+
+impl StateArtifact {
+    /// Capture the review state of `files` from their records in the review database.
+    pub fn export(range: &str, head: &str, files: &[DiffFile], records: &[HunkRecord]) -> Self {
+        let records: HashMap<(&str, &str), &HunkRecord> = records
+            .iter()
+            .map(|record| {
+                (
+                    (record.file_path.as_str(), record.content_hash.as_str()),
+                    record,
+                )
+            })
+            .collect();
+        let mut hunks = Vec::new();
+        for file in files {
+            let path = file.path.to_string_lossy();
+            for hunk in &file.hunks {
+                let record = records.get(&(path.as_ref(), hunk.content_hash.as_str()));
+                hunks.push(ArtifactHunk {
+                    file: path.to_string(),
+                    hash: hunk.content_hash.clone(),
+                    status: status_to_string(record.map_or(HunkStatus::Unreviewed, |r| r.status))
+                        .to_string(),
+                    reviewed_by: record.and_then(|r| r.reviewed_by.clone()),
+                });
+            }
+        }
+        Self {
+            version: ARTIFACT_VERSION,
+            range: range.to_string(),
+            head: head.to_string(),
+            hunks,
+        }
+    }
+
+    /// Parse an artifact, rejecting unknown versions and statuses.
+    pub fn parse(json: &str) -> Result<Self> {
+        let artifact: Self = serde_json::from_str(json)?;
+        if artifact.version != ARTIFACT_VERSION {
+            return Err(CiError::Version(artifact.version));
+        }
+        for hunk in &artifact.hunks {
+            if status_from_str(&hunk.status).is_err() {
+                return Err(CiError::Status {
+                    file: hunk.file.clone(),
+                    status: hunk.status.clone(),
+                });
+            }
+        }
+        Ok(artifact)
+    }
+}
+
+/// A hunk of the actual diff that the artifact doesn't mark reviewed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingHunk {
+    pub file: String,
+    pub line: u32,
+    pub hash: String,
+    /// Status in the artifact, or `None` when the artifact doesn't list the hunk.
+    pub status: Option<HunkStatus>,
+}
+
+/// Outcome of comparing an artifact with the actual diff.
+#[derive(Debug, Clone, Default)]
+pub struct CiReport {
+    /// Hunks of the diff.
+    pub total: usize,
+    pub reviewed: usize,
+    pub pending: Vec<PendingHunk>,
+    /// `(file, hash)` of artifact hunks that are not in the diff.
+    pub foreign: Vec<(String, String)>,
+}
+
+impl CiReport {
+    /// Whether the CI job should pass.
+    pub fn passes(&self) -> bool {
+        self.pending.is_empty() && self.foreign.is_empty()
+    }
+}
+
+/// Compare an artifact with the hunks of the actual diff.
+///
+/// Hunks are matched by file and content hash, so a hunk only counts as
+/// reviewed if exactly this content was reviewed.
+pub fn check(artifact: &StateArtifact, files: &[DiffFile]) -> CiReport {
+    let mut recorded: HashMap<(String, String), HunkStatus> = artifact
+        .hunks
+        .iter()
+        .filter_map(|hunk| {
+            let status = status_from_str(&hunk.status).ok()?;
+            Some(((hunk.file.clone(), hunk.hash.clone()), status))
+        })
+        .collect();
+
+    let mut report = CiReport::default();
+    for file in files {
+        let path = file.path.to_string_lossy().to_string();
+        for hunk in &file.hunks {
+            report.total += 1;
+            match recorded.remove(&(path.clone(), hunk.content_hash.clone())) {
+                Some(HunkStatus::Reviewed) => report.reviewed += 1,
+                status => report.pending.push(PendingHunk {
+                    file: path.clone(),
+                    line: hunk.new_start,
+                    hash: hunk.content_hash.clone(),
+                    status,
+                }),
+            }
+        }
+    }
+
+    report.foreign = artifact
+        .hunks
+        .iter()
+        .filter(|hunk| recorded.contains_key(&(hunk.file.clone(), hunk.hash.clone())))
+        .filter(|hunk| hunk.status != status_to_string(HunkStatus::Stale))
+        .map(|hunk| (hunk.file.clone(), hunk.hash.clone()))
+        .collect();
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiffHunk;
+    use std::path::PathBuf;
+
+    fn file(path: &str, hashes: &[&str]) -> DiffFile {
+        DiffFile {
+            path: PathBuf::from(path),
+            hunks: hashes
+                .iter()
+                .enumerate()
+                .map(|(i, hash)| DiffHunk {
+                    old_start: i as u32 * 10 + 1,
+                    old_count: 1,
+                    new_start: i as u32 * 10 + 1,
+                    new_count: 1,
+                    content: String::new().into(),
+                    content_hash: hash.to_string(),
+                    section: String::new(),
+                    status: HunkStatus::Unreviewed,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn artifact_must_match_the_actual_diff() {
+        let reviewed = [file("src/a.rs", &["h1", "h2"])];
+        let records: Vec<HunkRecord> = ["h1", "h2"]
+            .iter()
+            .map(|hash| HunkRecord {
+                file_path: "src/a.rs".to_string(),
+                content_hash: hash.to_string(),
+                status: HunkStatus::Reviewed,
+                reviewed_at: None,
+                reviewed_by: Some("ana".to_string()),
+                auto_rule: None,
+            })
+            .collect();
+        let artifact = StateArtifact::export("origin/main...HEAD", "abc", &reviewed, &records);
+        let artifact = StateArtifact::parse(&serde_json::to_string(&artifact).unwrap()).unwrap();
+        assert_eq!(artifact.hunks[0].reviewed_by.as_deref(), Some("ana"));
+        assert!(check(&artifact, &reviewed).passes());
+
+        // The branch changed after export: one hunk has new content
+        let actual = [file("src/a.rs", &["h1", "h3"])];
+        let report = check(&artifact, &actual);
+        assert!(!report.passes());
+        assert_eq!(report.reviewed, 1);
+        assert_eq!(report.pending.len(), 1);
+        assert_eq!(report.pending[0].hash, "h3");
+        assert_eq!(report.pending[0].status, None);
+        assert_eq!(
+            report.foreign,
+            vec![("src/a.rs".to_string(), "h2".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_statuses() {
+        let json = r#"{"version":2,"range":"r","head":"h","hunks":[]}"#;
+        assert!(matches!(
+            StateArtifact::parse(json),
+            Err(CiError::Version(2))
+        ));
+        let json = r#"{"version":1,"range":"r","head":"h","hunks":[{"file":"a","hash":"x","status":"approved"}]}"#;
+        assert!(matches!(
+            StateArtifact::parse(json),
+            Err(CiError::Status { .. })
+        ));
+    }
+}
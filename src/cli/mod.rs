@@ -1,4 +1,8 @@
+use crate::exit::ErrorFormat;
+use crate::gate::HookKind;
+use crate::patch::PatchStatus;
 use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(name = "git-review", about = "Per-hunk review tracking for git diffs")]
@@ -10,6 +14,55 @@ pub struct Cli {
     #[arg(short, long)]
     pub status: bool,
 
+    /// Also review untracked files (diffed against /dev/null).
+    #[arg(long)]
+    pub include_untracked: bool,
+
+    /// Ignore whitespace changes (`git diff -w`); reviewed separately from the full diff.
+    #[arg(short = 'w', long)]
+    pub ignore_whitespace: bool,
+
+    /// Diff supported languages structurally with difftastic; reviewed separately from the full diff.
+    #[arg(long)]
+    pub structural: bool,
+
+    /// Read the diff to review from stdin instead of running `git diff`.
+    #[arg(long, conflicts_with_all = ["diff_range", "include_untracked", "ignore_whitespace", "structural"])]
+    pub stdin: bool,
+
+    /// Run `git fetch --all --prune` first, so ranges like `origin/main..branch` are current.
+    #[arg(long, conflicts_with = "stdin")]
+    pub fetch: bool,
+
+    /// Review the commits the checked-out branch adds to its upstream (`@{upstream}..HEAD`).
+    #[arg(long, conflicts_with_all = ["diff_range", "stdin"])]
+    pub upstream: bool,
+
+    /// Review with line-by-line prompts instead of the full-screen TUI (for screen readers).
+    #[arg(long, global = true)]
+    pub accessible: bool,
+
+    /// Only review files with these extensions (comma-separated, e.g. "rs,toml").
+    #[arg(long, value_name = "EXTS")]
+    pub only: Vec<String>,
+
+    /// Leave out files with these extensions (comma-separated, e.g. "md,lock").
+    #[arg(long, value_name = "EXTS")]
+    pub exclude: Vec<String>,
+
+    /// How to write errors to stderr: "human" or "json" (one object per error).
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Human)]
+    pub error_format: ErrorFormat,
+
+    /// Log what git-review does to stderr: -v for git calls and syncs, -vv for
+    /// state changes, -vvv for every TUI event.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Append logs to this file instead of stderr (readable while the TUI runs).
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log_file: Option<PathBuf>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -20,6 +73,8 @@ pub enum Commands {
     Review(ReviewArgs),
     /// Print review progress summary.
     Status(StatusArgs),
+    /// List hunks that became unreviewed or stale since a point in time.
+    ChangedSince(ChangedSinceArgs),
     /// Manage the pre-commit review gate.
     Gate {
         #[command(subcommand)]
@@ -27,18 +82,90 @@ pub enum Commands {
     },
     /// Commit changes after passing review gate.
     Commit {
+        /// Start the commit message with a summary of the review: hunks and
+        /// files reviewed, by whom, and comments resolved.
+        #[arg(long)]
+        summarize: bool,
         /// Additional arguments to pass to git commit (after --).
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         git_args: Vec<String>,
     },
+    /// Stage the working tree's hunks that are marked reviewed (`git apply --cached`).
+    Stage,
+    /// Push after the branch's unpushed commits pass the review gate.
+    Push {
+        /// Additional arguments to pass to git push (after --).
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        git_args: Vec<String>,
+    },
     /// Reset review state for the current diff.
     Reset(ResetArgs),
     /// Approve all hunks (or specific file) without individual review.
     Approve(ApproveArgs),
+    /// Undo the last bulk approval (`approve`, or F/A/R in the TUI), restoring prior statuses.
+    UndoLast(UndoLastArgs),
     /// Watch branches for review status changes.
     Watch(WatchArgs),
+    /// List branches whose unreviewed hunks have waited too long (for cron).
+    Nag(NagArgs),
     /// Open the branch review dashboard.
-    Dashboard,
+    Dashboard(DashboardArgs),
+    /// Open the branch's pull or merge request page on its host in the browser.
+    Open(OpenArgs),
+    /// Assign a file in a diff range to a reviewer.
+    Assign(AssignArgs),
+    /// Review the changes stored in a stash entry.
+    Stash(StashArgs),
+    /// Review a patch or mbox file without applying it.
+    Patch(PatchArgs),
+    /// Send the review of a patch series back as email replies, and read replies in.
+    Email {
+        #[command(subcommand)]
+        action: EmailAction,
+    },
+    /// Serve review state to editor integrations, browsers, and bots.
+    Serve(ServeArgs),
+    /// Write a shareable report of a diff and its review state.
+    Report(ReportArgs),
+    /// Review a single commit, e.g. from another branch, and cherry-pick it once reviewed.
+    Pick(PickArgs),
+    /// Mark hunks of one range reviewed where another range has the same hunk reviewed.
+    Carry(CarryArgs),
+    /// Print the hunks with a review status (unreviewed by default) as a patch.
+    ExportPatch(ExportPatchArgs),
+    /// List hunks flagged as needing changes and new TODO/FIXME lines.
+    Todos(TodosArgs),
+    /// List hunks bookmarked with B in the TUI.
+    Bookmarks(BookmarksArgs),
+    /// Spot-check a large diff: require review of a random fraction of its hunks.
+    Sample(SampleArgs),
+    /// Delete local branches merged into the base and archive their review state.
+    PruneMerged(PruneMergedArgs),
+    /// Show a timeline of review events: reviews, approvals, gate checks, and merges.
+    History(HistoryArgs),
+    /// Save and restore named copies of a diff range's review state.
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+    /// Share review status per commit through git notes (refs/notes/git-review).
+    Notes {
+        #[command(subcommand)]
+        action: NotesAction,
+    },
+    /// Post hunk comments and review votes to the range's changes on a code review server.
+    Publish(PublishArgs),
+    /// Inspect the shared `.git-review.toml` and personal `.git-review.local.toml`.
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Check exported review state against a branch's diff in CI (or export it with --export).
+    CiCheck(CiCheckArgs),
+    /// Cross-check stored review state against the live diff.
+    Verify(VerifyArgs),
+    /// Print unreviewed hunks one at a time and read a y/n/s/q answer for each from stdin.
+    Step(StepArgs),
 }
 
 #[derive(Args, Debug)]
@@ -50,6 +177,38 @@ pub struct ReviewArgs {
     /// Show progress summary instead of launching TUI.
     #[arg(short, long)]
     pub status: bool,
+
+    /// Also review untracked files (diffed against /dev/null).
+    #[arg(long)]
+    pub include_untracked: bool,
+
+    /// Ignore whitespace changes (`git diff -w`); reviewed separately from the full diff.
+    #[arg(short = 'w', long)]
+    pub ignore_whitespace: bool,
+
+    /// Diff supported languages structurally with difftastic; reviewed separately from the full diff.
+    #[arg(long)]
+    pub structural: bool,
+
+    /// Read the diff to review from stdin instead of running `git diff`.
+    #[arg(long, conflicts_with_all = ["diff_range", "include_untracked", "ignore_whitespace", "structural"])]
+    pub stdin: bool,
+
+    /// Run `git fetch --all --prune` first, so ranges like `origin/main..branch` are current.
+    #[arg(long, conflicts_with = "stdin")]
+    pub fetch: bool,
+
+    /// Review the commits the checked-out branch adds to its upstream (`@{upstream}..HEAD`).
+    #[arg(long, conflicts_with_all = ["diff_range", "stdin"])]
+    pub upstream: bool,
+
+    /// Only review files with these extensions (comma-separated, e.g. "rs,toml").
+    #[arg(long, value_name = "EXTS")]
+    pub only: Vec<String>,
+
+    /// Leave out files with these extensions (comma-separated, e.g. "md,lock").
+    #[arg(long, value_name = "EXTS")]
+    pub exclude: Vec<String>,
 }
 
 #[derive(Args, Debug)]
@@ -57,6 +216,249 @@ pub struct StatusArgs {
     /// Diff range to check status for (e.g., "main..HEAD").
     /// If not specified, defaults to "HEAD" (staged changes).
     pub diff_range: Option<String>,
+
+    /// Also include untracked files (diffed against /dev/null).
+    #[arg(long)]
+    pub include_untracked: bool,
+
+    /// Print pending hunks as `file:line: [STATUS] summary` for editor quickfix lists.
+    #[arg(long)]
+    pub quickfix: bool,
+
+    /// Ignore whitespace changes (`git diff -w`); reviewed separately from the full diff.
+    #[arg(short = 'w', long)]
+    pub ignore_whitespace: bool,
+
+    /// Diff supported languages structurally with difftastic; reviewed separately from the full diff.
+    #[arg(long)]
+    pub structural: bool,
+
+    /// Fast path for huge diffs: report line counts and the stored review
+    /// progress without reading or re-syncing hunks.
+    #[arg(long, conflicts_with_all = ["quickfix", "include_untracked", "ignore_whitespace", "structural", "only", "exclude"])]
+    pub stat_only: bool,
+
+    /// List the hunks reviewed in the last HOURS hours, newest first, with
+    /// when and by whom (e.g. for a standup).
+    #[arg(long, value_name = "HOURS", conflicts_with_all = ["quickfix", "stat_only"])]
+    pub since: Option<u32>,
+
+    /// Only review files with these extensions (comma-separated, e.g. "rs,toml").
+    #[arg(long, value_name = "EXTS")]
+    pub only: Vec<String>,
+
+    /// Leave out files with these extensions (comma-separated, e.g. "md,lock").
+    #[arg(long, value_name = "EXTS")]
+    pub exclude: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ChangedSinceArgs {
+    /// Point in time: a Unix timestamp, a local date or date and time
+    /// ("2026-10-01", "2026-10-01 14:30"), or a tag or commit (its commit time).
+    pub since: String,
+
+    /// Diff range to check (e.g., "main..HEAD").
+    /// If not specified, defaults to "HEAD" (staged changes).
+    pub diff_range: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct PatchArgs {
+    /// Patch or mbox file to review ("-" reads from stdin).
+    pub file: String,
+
+    /// Show progress summary instead of launching TUI.
+    #[arg(short, long)]
+    pub status: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ReportArgs {
+    /// Diff range to report on (e.g., "main..HEAD").
+    /// If not specified, defaults to "HEAD" (staged changes).
+    pub diff_range: Option<String>,
+
+    /// Write a static HTML page to this file.
+    #[arg(long, value_name = "FILE")]
+    pub html: Option<PathBuf>,
+
+    /// Print a Markdown summary for PR descriptions or chat.
+    #[arg(long)]
+    pub markdown: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PickArgs {
+    /// Commit to review (SHA, or any revision naming one).
+    pub commit: String,
+
+    /// Show progress summary instead of launching TUI.
+    #[arg(short, long)]
+    pub status: bool,
+
+    /// Cherry-pick the commit onto the current branch if its review passes the gate.
+    #[arg(long, conflicts_with = "status")]
+    pub apply: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CarryArgs {
+    /// Range whose reviews to copy (e.g., "main..feature").
+    pub from_range: String,
+    /// Range to copy them to (e.g., "release..HEAD").
+    pub to_range: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ExportPatchArgs {
+    /// Diff range to export hunks of (e.g., "main..HEAD").
+    /// If not specified, defaults to "HEAD" (staged changes).
+    pub diff_range: Option<String>,
+
+    /// Export the hunks with these review statuses (comma-separated).
+    #[arg(long, value_enum, value_delimiter = ',', default_value = "unreviewed")]
+    pub status: Vec<PatchStatus>,
+
+    /// Write the patch to this file instead of standard output.
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct TodosArgs {
+    /// Diff range to collect todos from (e.g., "main..HEAD").
+    /// If not specified, defaults to "HEAD" (staged changes).
+    pub diff_range: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct BookmarksArgs {
+    /// Diff range to list bookmarks of (e.g., "main..HEAD").
+    /// If not specified, defaults to "HEAD" (staged changes).
+    pub diff_range: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct OpenArgs {
+    /// Branch whose pull request to open (defaults to the current branch).
+    pub branch: Option<String>,
+    /// Branch the pull request merges into (defaults to the default branch).
+    #[arg(long)]
+    pub base: Option<String>,
+    /// Print the URL instead of opening it.
+    #[arg(long)]
+    pub print: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct DashboardArgs {
+    /// Show branches needing review across these repositories instead of the
+    /// current one. Without paths, uses the `git-review.repos` git config list.
+    #[arg(long, num_args = 0..)]
+    pub repos: Option<Vec<PathBuf>>,
+
+    /// Run `git fetch --all --prune` first (in every repository with `--repos`).
+    #[arg(long)]
+    pub fetch: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SampleArgs {
+    /// Diff range to sample (e.g., "main..HEAD").
+    /// If not specified, defaults to "HEAD" (staged changes).
+    pub diff_range: Option<String>,
+
+    /// Percentage of unreviewed hunks to keep for review; the rest are approved as sampled-out.
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub percent: u8,
+
+    /// Seed for picking hunks, to reproduce an earlier sample (printed after sampling).
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Show progress summary instead of launching TUI.
+    #[arg(short, long)]
+    pub status: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PruneMergedArgs {
+    /// Base branch to check against (defaults to the detected default branch).
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// Delete without asking for confirmation.
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// List the branches and review state that would be removed, without removing them.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// Only show reviews of this branch.
+    pub branch: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CiCheckArgs {
+    /// Base the branch is diffed against (`<base>...HEAD`); defaults to the detected default branch.
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// JSON file holding the exported review state.
+    #[arg(long, value_name = "FILE")]
+    pub state_artifact: PathBuf,
+
+    /// Write the local review state of the diff to the artifact instead of checking it.
+    #[arg(long)]
+    pub export: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Diff range to verify (e.g., "main..HEAD").
+    /// If not specified, defaults to "HEAD" (staged changes).
+    pub diff_range: Option<String>,
+
+    /// Fix the problems found instead of only reporting them.
+    #[arg(long)]
+    pub repair: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct StepArgs {
+    /// Diff range to review (e.g., "main..HEAD").
+    /// If not specified, defaults to "HEAD" (staged changes).
+    pub diff_range: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Speak newline-delimited JSON-RPC 2.0 over stdin/stdout.
+    #[arg(long, conflicts_with = "http")]
+    pub json_rpc: bool,
+
+    /// Serve the web dashboard and JSON API on this address (e.g. "127.0.0.1:8080").
+    #[arg(long, value_name = "ADDR")]
+    pub http: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct StashArgs {
+    /// Stash entry to review (defaults to "stash@{0}").
+    pub stash: Option<String>,
+
+    /// Show progress summary instead of launching TUI.
+    #[arg(short, long)]
+    pub status: bool,
+
+    /// Include untracked files recorded in the stash.
+    #[arg(long)]
+    pub include_untracked: bool,
 }
 
 #[derive(Args, Debug)]
@@ -64,16 +466,139 @@ pub struct ResetArgs {
     /// Diff range to reset review state for (e.g., "main..HEAD").
     /// If not specified, defaults to "HEAD" (staged changes).
     pub diff_range: Option<String>,
+
+    /// Reset every review stored for this branch (any base, any diff mode).
+    #[arg(long, conflicts_with = "diff_range")]
+    pub branch: Option<String>,
+
+    /// Reset every review in the repository.
+    #[arg(long, conflicts_with_all = ["diff_range", "branch"])]
+    pub all: bool,
+
+    /// Don't ask for confirmation before resetting several reviews.
+    #[arg(short, long)]
+    pub yes: bool,
+
+    /// List the stored hunks that would be reset, without resetting them.
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum GateAction {
     /// Check if all hunks are reviewed.
-    Check,
-    /// Install the pre-commit hook.
-    Enable,
-    /// Remove the pre-commit hook.
-    Disable,
+    Check {
+        /// Also require assigned files to be reviewed by their assignee.
+        #[arg(long)]
+        require_assignee: bool,
+    },
+    /// Show which hooks are installed, the gate policy, and why staged changes pass or fail.
+    Status,
+    /// Install the pre-commit hook (or another git-review hook with --hook).
+    Enable {
+        /// Make the installed hook require assignee reviews.
+        #[arg(long)]
+        require_assignee: bool,
+        /// Which hook to install.
+        #[arg(long, value_enum, default_value_t = HookKind::PreCommit)]
+        hook: HookKind,
+    },
+    /// Remove the pre-commit hook (or another git-review hook with --hook).
+    Disable {
+        /// Which hook to remove.
+        #[arg(long, value_enum, default_value_t = HookKind::PreCommit)]
+        hook: HookKind,
+        /// Show which hook file would be removed or restored, without changing it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Run by the pre-rebase hook: warn before a rebase moves reviewed state aside.
+    #[command(hide = true)]
+    PreRebase {
+        /// The upstream the branch is being rebased onto.
+        upstream: String,
+        /// The branch being rebased (defaults to the current branch).
+        branch: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SnapshotAction {
+    /// Save the review state of a diff range under a name (replacing any snapshot of that name).
+    Save {
+        /// Snapshot name.
+        name: String,
+        /// Diff range to snapshot (defaults to "HEAD", staged changes).
+        diff_range: Option<String>,
+    },
+    /// Replace a diff range's review state with a saved snapshot.
+    Restore {
+        /// Snapshot name.
+        name: String,
+        /// Diff range to restore into (defaults to the range the snapshot was taken from).
+        diff_range: Option<String>,
+    },
+    /// List saved snapshots.
+    List,
+    /// Delete a saved snapshot.
+    Delete {
+        /// Snapshot name.
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the effective configuration and which file each value came from.
+    Doctor,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EmailAction {
+    /// Write replies quoting each patch's hunks left to review, with the review
+    /// comments, as an mbox for `git send-email`.
+    Export {
+        /// Patch series mbox reviewed with `git-review patch` ("-" reads from stdin).
+        series: String,
+        /// Write the mbox to this file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Mark hunks reviewed from replies with `Reviewed-by:` or `Acked-by:` trailers.
+    Ingest {
+        /// Patch series mbox reviewed with `git-review patch`.
+        series: String,
+        /// Mbox of replies to the series ("-" reads from stdin).
+        replies: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum NotesAction {
+    /// Write a review summary note to each commit of a diff range.
+    Sync {
+        /// Diff range whose commits get notes (defaults to "<default-branch>..HEAD").
+        diff_range: Option<String>,
+    },
+    /// Mark hunks reviewed from the notes on a diff range's commits (e.g. in a fresh clone).
+    Import {
+        /// Diff range whose notes are read (defaults to "<default-branch>..HEAD").
+        diff_range: Option<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct PublishArgs {
+    /// Diff range whose commits are published (defaults to "<default-branch>..HEAD").
+    pub diff_range: Option<String>,
+
+    /// Publish to Gerrit: each commit with a Change-Id trailer gets its comments and a vote.
+    #[arg(long)]
+    pub gerrit: bool,
+
+    /// Print the review each change would get, without posting it.
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Args, Debug)]
@@ -83,6 +608,19 @@ pub struct ApproveArgs {
     /// Approve only hunks in this file path.
     #[arg(short, long)]
     pub file: Option<String>,
+    /// Approve only unreviewed hunks whose changed lines all match this regex
+    /// (e.g. 'version = ').
+    #[arg(long, value_name = "REGEX")]
+    pub grep: Option<String>,
+    /// List the hunks that would be approved, without approving them.
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct UndoLastArgs {
+    /// Only undo approvals of this diff range (defaults to the last approval of any range).
+    pub diff_range: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -90,9 +628,68 @@ pub struct WatchArgs {
     /// Refresh interval in seconds (default: 5).
     #[arg(short, long, default_value = "5")]
     pub interval: u64,
+
+    /// Notify when a branch becomes fully reviewed or gains unreviewed hunks
+    /// (desktop notification, plus the `[notify] webhook` if configured).
+    #[arg(long)]
+    pub notify: bool,
+
+    /// Only watch branches matching this glob (repeatable, e.g. "feature/*").
+    #[arg(short, long = "branch", value_name = "GLOB")]
+    pub branches: Vec<String>,
+
+    /// Also watch remote-tracking branches (e.g. "origin/feature").
+    #[arg(long)]
+    pub remotes: bool,
+
+    /// Run `git fetch --all --prune` before each refresh.
+    #[arg(long)]
+    pub fetch: bool,
+
+    /// Merge branches queued in the dashboard once they are fully reviewed
+    /// and merge without conflicts (asks first unless `[merge_queue] confirm = false`).
+    #[arg(long)]
+    pub merge: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct NagArgs {
+    /// Report branches idle for at least this many days (default: 3).
+    #[arg(short, long, default_value = "3")]
+    pub days: u64,
+
+    /// Also send each reminder as a desktop notification, plus the
+    /// `[notify] webhook` if configured.
+    #[arg(long)]
+    pub notify: bool,
+
+    /// Only check branches matching this glob (repeatable, e.g. "feature/*").
+    #[arg(short, long = "branch", value_name = "GLOB")]
+    pub branches: Vec<String>,
+
+    /// Also check remote-tracking branches (e.g. "origin/feature").
+    #[arg(long)]
+    pub remotes: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct AssignArgs {
+    /// Diff range the assignment applies to (e.g., "main..HEAD").
+    pub diff_range: String,
+    /// File path to assign.
+    #[arg(short, long)]
+    pub file: String,
+    /// Reviewer the file is assigned to (matches `git config user.name`).
+    #[arg(long)]
+    pub to: String,
 }
 
 /// Parse CLI arguments.
 pub fn parse_args() -> Cli {
     Cli::parse()
 }
+
+/// Parse command line arguments, returning clap's error instead of exiting.
+pub fn try_parse_args() -> Result<Cli, clap::Error> {
+    Cli::try_parse()
+}
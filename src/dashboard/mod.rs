@@ -1,18 +1,228 @@
 use crate::git::{BranchDetail, BranchInfo, GitError};
-use crate::state::ReviewDb;
+use crate::session::SessionError;
+use crate::state::{ReviewDb, StateError};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// `[dashboard]` section of `.git-review.toml`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DashboardConfig {
+    /// Seconds between background `git fetch`es while the dashboard is open;
+    /// 0 never fetches.
+    pub fetch_interval: u64,
+    /// List remote-tracking branches (`origin/feature`) next to local ones.
+    pub remotes: bool,
+}
+
+/// Errors that can occur while pruning a merged branch.
+#[derive(Debug, Error)]
+pub enum PruneError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+/// Local branches fully merged into `base`, excluding `base` and the checked-out branch.
+pub fn merged_branches(base: &str) -> Result<Vec<String>, GitError> {
+    let current = crate::git::get_current_branch()?;
+    let mut merged = Vec::new();
+    for branch in crate::git::list_branches()? {
+        if branch.name == base || current.as_deref() == Some(branch.name.as_str()) {
+            continue;
+        }
+        if crate::git::is_merged(base, &branch.name)? {
+            merged.push(branch.name);
+        }
+    }
+    Ok(merged)
+}
+
+/// Delete a branch merged into `base` and archive every review range that ends at it.
+pub fn prune_branch(db: &mut ReviewDb, base: &str, branch: &str) -> Result<(), PruneError> {
+    // Look the merge up while the branch still exists
+    let merge_sha = crate::git::merge_commit(base, branch).ok();
+    crate::git::delete_branch(branch)?;
+    archive_branch(db, branch, merge_sha.as_deref())?;
+    db.clear_carryover(branch)?;
+    db.dequeue_merge(branch)?;
+    db.clear_reviewed_tip(branch)?;
+    Ok(())
+}
+
+/// Move the review state of every range that ends at a merged `branch` into
+/// the archive, recording the commit that merged it.
+///
+/// Returns the number of hunks archived.
+pub fn archive_branch(
+    db: &mut ReviewDb,
+    branch: &str,
+    merge_sha: Option<&str>,
+) -> Result<usize, StateError> {
+    let mut archived = 0;
+    for base_ref in branch_base_refs(db, branch)? {
+        archived += db.archive(&base_ref, merge_sha)?;
+    }
+    Ok(archived)
+}
+
+/// Stored ranges that end at `branch`, whose state [`archive_branch`] moves.
+pub fn branch_base_refs(db: &ReviewDb, branch: &str) -> Result<Vec<String>, StateError> {
+    Ok(db
+        .list_base_refs()?
+        .into_iter()
+        .filter(|base_ref| base_ref.rsplit_once("..").map(|(_, head)| head) == Some(branch))
+        .collect())
+}
+
+/// Whether `branch` is `base` itself, locally or as a remote-tracking branch.
+fn is_base(branch: &BranchInfo, base: &str) -> bool {
+    branch.name == base
+        || (!branch.is_local && branch.name.split_once('/').map(|(_, name)| name) == Some(base))
+}
 
 /// Review progress for a branch
 #[derive(Debug, Clone, Default)]
 pub struct ReviewProgress {
     pub reviewed: usize,
     pub total: usize,
+    pub unreviewed: usize,
+    pub stale: usize,
+    pub needs_changes: usize,
+    pub skimmed: usize,
+}
+
+impl From<crate::ReviewProgress> for ReviewProgress {
+    fn from(p: crate::ReviewProgress) -> Self {
+        Self {
+            reviewed: p.reviewed,
+            total: p.total_hunks,
+            unreviewed: p.unreviewed,
+            stale: p.stale,
+            needs_changes: p.needs_changes,
+            skimmed: p.skimmed,
+        }
+    }
+}
+
+/// Sync a branch's current diff into the database and read its progress.
+///
+/// Falls back to the stored (possibly outdated) progress if the diff cannot be read.
+pub(crate) fn branch_progress(db: &mut ReviewDb, range: &str) -> ReviewProgress {
+    let Ok(key) = crate::session::review_key(db, range) else {
+        return ReviewProgress::default();
+    };
+    match crate::git::get_diff(range) {
+        Ok(diff_output) => {
+            let files = crate::parser::parse_diff(&diff_output);
+            match db.sync_with_diff(&key, &files) {
+                Ok(()) => db.progress(&key).map(Into::into).unwrap_or_default(),
+                Err(_) => ReviewProgress::default(),
+            }
+        }
+        Err(_) => db.progress(&key).map(Into::into).unwrap_or_default(),
+    }
+}
+
+/// A branch's progress as a sync would leave it, without writing to the
+/// database (see [`ReviewDb::synced_hunks`]).
+fn peek_branch_progress(db: &ReviewDb, range: &str) -> ReviewProgress {
+    let Ok(key) = crate::session::peek_review_key(db, range) else {
+        return ReviewProgress::default();
+    };
+    let records = match crate::git::get_diff(range) {
+        Ok(diff_output) => db.synced_hunks(&key, &crate::parser::parse_diff(&diff_output)),
+        Err(_) => db.hunks(&key),
+    };
+    records
+        .map(|records| {
+            crate::filter::FileFilter::default()
+                .progress(&records)
+                .into()
+        })
+        .unwrap_or_default()
+}
+
+/// People who reviewed a branch's hunks, most recent first (see [`ReviewDb::reviewers`]).
+fn branch_reviewers(db: &mut ReviewDb, range: &str) -> Vec<String> {
+    crate::session::review_key(db, range)
+        .ok()
+        .and_then(|key| db.reviewers(&key).ok())
+        .unwrap_or_default()
+}
+
+/// How a branch moved since it was last opened for review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TipChange {
+    /// New commits on top of the reviewed ones.
+    Advanced,
+    /// The reviewed commits were rewritten, by a force-push or rebase.
+    Rewritten,
+}
+
+impl TipChange {
+    /// Short badge shown next to the branch.
+    pub fn badge(self) -> &'static str {
+        match self {
+            Self::Advanced => "changed",
+            Self::Rewritten => "force-pushed",
+        }
+    }
+
+    /// What happened, in words.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Advanced => "changed since last review",
+            Self::Rewritten => "force-pushed since last review",
+        }
+    }
+
+    /// How `tip` differs from `reviewed_tip`, if it does.
+    fn between(reviewed_tip: &str, tip: &str) -> Option<Self> {
+        if reviewed_tip == tip {
+            return None;
+        }
+        // The reviewed commit may be gone entirely after a force-push and gc
+        match crate::git::is_merged(tip, reviewed_tip) {
+            Ok(true) => Some(Self::Advanced),
+            _ => Some(Self::Rewritten),
+        }
+    }
 }
 
 /// A single row in the dashboard
 pub struct DashboardItem {
     pub branch: BranchInfo,
+    /// Full SHA of the branch's tip commit.
+    pub tip_sha: String,
+    /// Whether the tip moved since the branch was last opened for review.
+    pub tip_change: Option<TipChange>,
     pub detail: Option<BranchDetail>,
     pub progress: Option<ReviewProgress>,
+    /// Who reviewed the branch's hunks, most recent first; loaded with `progress`.
+    pub reviewers: Vec<String>,
+}
+
+impl DashboardItem {
+    fn new(branch: BranchInfo, tip_sha: String, reviewed_tip: Option<&String>) -> Self {
+        let tip_change = reviewed_tip.and_then(|reviewed| TipChange::between(reviewed, &tip_sha));
+        Self {
+            branch,
+            tip_sha,
+            tip_change,
+            detail: None,
+            progress: None,
+            reviewers: Vec::new(),
+        }
+    }
+
+    /// The person who reviewed the branch most recently, if anyone has.
+    pub fn last_reviewer(&self) -> Option<&str> {
+        self.reviewers.first().map(String::as_str)
+    }
 }
 
 /// Dashboard state — owns the item list but NOT the ReviewDb
@@ -21,12 +231,56 @@ pub struct Dashboard {
     pub selected: usize,
     pub base_branch: String,
     pub last_head_sha: String,
+    /// Tip of every listed ref, the base included, as of the last (re)load.
+    pub tips: HashMap<String, String>,
+    /// Whether remote-tracking branches are listed (see [`DashboardConfig::remotes`]).
+    pub remotes: bool,
+    /// Branches marked for batch actions.
+    pub marked: HashSet<String>,
+    /// Only list branches this reviewer has not reviewed any hunk of, to
+    /// split work between reviewers.
+    pub untouched_by: Option<String>,
+    /// Branches in the merge queue (see [`crate::queue`]).
+    pub queued: HashSet<String>,
 }
 
 impl Dashboard {
+    /// Items the dashboard lists; `selected` indexes into these.
+    pub fn visible_items(&self) -> Vec<&DashboardItem> {
+        self.items
+            .iter()
+            .filter(|item| {
+                self.untouched_by
+                    .as_ref()
+                    .is_none_or(|me| !item.reviewers.contains(me))
+            })
+            .collect()
+    }
+
+    /// Index into `items` of the selected visible item.
+    fn selected_index(&self) -> Option<usize> {
+        let item = *self.visible_items().get(self.selected)?;
+        self.items
+            .iter()
+            .position(|other| std::ptr::eq(other, item))
+    }
+
+    /// Show only branches `reviewer` has not touched, or every branch again.
+    pub fn set_untouched_by(&mut self, reviewer: Option<String>) {
+        self.untouched_by = reviewer;
+        self.selected = 0;
+    }
+
+    /// Keep the selection within the visible items.
+    fn clamp_selection(&mut self) {
+        self.selected = self
+            .selected
+            .min(self.visible_items().len().saturating_sub(1));
+    }
+
     /// Move selection down (clamp to end).
     pub fn select_next(&mut self) {
-        if !self.items.is_empty() && self.selected < self.items.len() - 1 {
+        if self.selected + 1 < self.visible_items().len() {
             self.selected += 1;
         }
     }
@@ -40,75 +294,161 @@ impl Dashboard {
 
     /// Get the name of the currently selected branch.
     pub fn selected_branch(&self) -> Option<&str> {
-        self.items
-            .get(self.selected)
-            .map(|item| item.branch.name.as_str())
+        self.selected_item().map(|item| item.branch.name.as_str())
+    }
+
+    /// Mark or unmark the selected branch for batch actions.
+    pub fn toggle_mark_selected(&mut self) {
+        if let Some(name) = self.selected_branch().map(str::to_string)
+            && !self.marked.remove(&name)
+        {
+            self.marked.insert(name);
+        }
+    }
+
+    /// Branches a batch action applies to: the marked ones, or the selected one if none are marked.
+    pub fn action_targets(&self) -> Vec<String> {
+        if self.marked.is_empty() {
+            self.selected_branch()
+                .map(|b| vec![b.to_string()])
+                .unwrap_or_default()
+        } else {
+            // Keep dashboard order rather than set order
+            self.visible_items()
+                .into_iter()
+                .map(|item| item.branch.name.clone())
+                .filter(|name| self.marked.contains(name))
+                .collect()
+        }
+    }
+
+    /// Diff range for a branch (see [`crate::session::review_key`] for its state key).
+    pub fn range_for(&self, branch: &str) -> String {
+        format!("{}..{}", self.base_branch, branch)
     }
 
     /// Get a reference to the currently selected item.
     pub fn selected_item(&self) -> Option<&DashboardItem> {
-        self.items.get(self.selected)
+        self.visible_items().get(self.selected).copied()
     }
 
-    /// Load dashboard from git and review state.
-    pub fn load(_db: &ReviewDb, base_branch: &str) -> Result<Self, GitError> {
-        let all_branches = crate::git::list_branches()?;
+    /// Load dashboard from git and review state, listing remote-tracking
+    /// branches too if `remotes` is set.
+    pub fn load(db: &ReviewDb, base_branch: &str, remotes: bool) -> Result<Self, GitError> {
         let last_head_sha = crate::git::get_head_sha()?;
-
-        // Filter out the base branch itself
-        let items = all_branches
-            .into_iter()
-            .filter(|b| b.name != base_branch)
-            .map(|branch| DashboardItem {
-                branch,
-                detail: None,
-                progress: None,
-            })
-            .collect();
-
-        Ok(Dashboard {
-            items,
+        let mut dashboard = Dashboard {
+            items: Vec::new(),
             selected: 0,
             base_branch: base_branch.to_string(),
             last_head_sha,
-        })
+            tips: HashMap::new(),
+            remotes,
+            marked: HashSet::new(),
+            untouched_by: None,
+            queued: db
+                .merge_queue(base_branch)
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+        };
+        dashboard.reload(db)?;
+        Ok(dashboard)
+    }
+
+    /// Ref namespaces the dashboard lists branches from.
+    fn namespaces(&self) -> Vec<&'static str> {
+        if self.remotes {
+            vec!["refs/heads/", "refs/remotes/"]
+        } else {
+            vec!["refs/heads/"]
+        }
     }
 
-    /// Refresh dashboard if HEAD has changed. Returns true if state changed.
-    pub fn refresh(&mut self, _db: &ReviewDb) -> Result<bool, GitError> {
+    /// Refresh dashboard if HEAD or any branch tip has changed (after a commit,
+    /// or a fetch bringing someone's force-push). Returns true if state changed.
+    pub fn refresh(&mut self, db: &ReviewDb) -> Result<bool, GitError> {
         let current_head = crate::git::get_head_sha()?;
+        let tips = crate::git::ref_tips(&self.namespaces())?;
 
-        // If HEAD hasn't changed, no need to refresh
-        if current_head == self.last_head_sha {
+        if current_head == self.last_head_sha && tips == self.tips {
             return Ok(false);
         }
 
-        // Reload branch list
-        let all_branches = crate::git::list_branches()?;
-        self.items = all_branches
+        self.reload(db)?;
+        self.last_head_sha = current_head;
+        Ok(true)
+    }
+
+    /// Reload the branch list unconditionally, dropping marks on branches that are gone.
+    ///
+    /// Branches whose tip and base did not move keep their loaded detail and progress.
+    pub fn reload(&mut self, db: &ReviewDb) -> Result<(), GitError> {
+        let mut branches = crate::git::list_branches()?;
+        if self.remotes {
+            branches.extend(crate::git::list_remote_branches()?);
+        }
+        let tips = crate::git::ref_tips(&self.namespaces())?;
+        let reviewed_tips = db.reviewed_tips().unwrap_or_default();
+        let base_moved = tips.get(&self.base_branch) != self.tips.get(&self.base_branch);
+
+        let mut previous: HashMap<String, DashboardItem> = self
+            .items
+            .drain(..)
+            .map(|item| (item.branch.name.clone(), item))
+            .collect();
+        self.items = branches
             .into_iter()
-            .filter(|b| b.name != self.base_branch)
-            .map(|branch| DashboardItem {
-                branch,
-                detail: None,
-                progress: None,
+            .filter(|b| !is_base(b, &self.base_branch))
+            .map(|branch| {
+                let tip_sha = tips.get(&branch.name).cloned().unwrap_or_default();
+                let reviewed_tip = reviewed_tips.get(&branch.name);
+                let mut item = DashboardItem::new(branch, tip_sha, reviewed_tip);
+                if let Some(old) = previous.remove(&item.branch.name)
+                    && old.tip_sha == item.tip_sha
+                    && !base_moved
+                {
+                    item.detail = old.detail;
+                    item.progress = old.progress;
+                    item.reviewers = old.reviewers;
+                }
+                item
             })
             .collect();
+        self.tips = tips;
+        self.clamp_selection();
+
+        let names: HashSet<&str> = self.items.iter().map(|i| i.branch.name.as_str()).collect();
+        self.marked.retain(|name| names.contains(name.as_str()));
+        Ok(())
+    }
 
-        // Clamp selection to new bounds
-        if !self.items.is_empty() && self.selected >= self.items.len() {
-            self.selected = self.items.len() - 1;
+    /// List remote-tracking branches too, or stop listing them.
+    pub fn set_remotes(&mut self, db: &ReviewDb, remotes: bool) -> Result<(), GitError> {
+        if self.remotes == remotes {
+            return Ok(());
         }
+        self.remotes = remotes;
+        self.reload(db)
+    }
 
-        self.last_head_sha = current_head;
-        Ok(true)
+    /// Re-read detail and progress for the given branches.
+    pub fn reload_details(&mut self, db: &mut ReviewDb, branches: &[String]) {
+        for item in &mut self.items {
+            if branches.contains(&item.branch.name) {
+                item.detail = None;
+                item.progress = None;
+                item.reviewers.clear();
+            }
+        }
+        self.load_all_details(db);
+        self.clamp_selection();
     }
 
     /// Load detail and progress for the currently selected branch.
     pub fn load_detail_for_selected(&mut self, db: &mut ReviewDb) -> Result<(), GitError> {
         // Get the selected item
-        let item = match self.items.get_mut(self.selected) {
-            Some(item) => item,
+        let item = match self.selected_index() {
+            Some(index) => &mut self.items[index],
             None => return Ok(()), // No items in dashboard
         };
 
@@ -123,50 +463,12 @@ impl Dashboard {
 
         // Build diff range and sync with database before reading progress
         let range = format!("{}..{}", self.base_branch, branch_name);
-
-        // Get the actual diff and sync with DB to ensure progress is accurate
-        let progress = match crate::git::get_diff(&range) {
-            Ok(diff_output) => {
-                let files = crate::parser::parse_diff(&diff_output);
-                // Sync the diff with the database
-                match db.sync_with_diff(&range, &files) {
-                    Ok(()) => {
-                        // Now read progress from the updated DB
-                        match db.progress(&range) {
-                            Ok(p) => ReviewProgress {
-                                reviewed: p.reviewed,
-                                total: p.total_hunks,
-                            },
-                            Err(_) => ReviewProgress {
-                                reviewed: 0,
-                                total: 0,
-                            },
-                        }
-                    }
-                    Err(_) => ReviewProgress {
-                        reviewed: 0,
-                        total: 0,
-                    },
-                }
-            }
-            Err(_) => {
-                // Can't get diff — try DB progress as fallback (may be stale)
-                match db.progress(&range) {
-                    Ok(p) => ReviewProgress {
-                        reviewed: p.reviewed,
-                        total: p.total_hunks,
-                    },
-                    Err(_) => ReviewProgress {
-                        reviewed: 0,
-                        total: 0,
-                    },
-                }
-            }
-        };
+        let progress = branch_progress(db, &range);
 
         // Update item with loaded data
         item.detail = Some(detail);
         item.progress = Some(progress);
+        item.reviewers = branch_reviewers(db, &range);
 
         Ok(())
     }
@@ -184,55 +486,37 @@ impl Dashboard {
             if let Ok(detail) = crate::git::get_branch_detail(&self.base_branch, branch_name) {
                 // Build diff range and sync with database before reading progress
                 let range = format!("{}..{}", self.base_branch, branch_name);
-
-                // Get the actual diff and sync with DB to ensure progress is accurate
-                let progress = match crate::git::get_diff(&range) {
-                    Ok(diff_output) => {
-                        let files = crate::parser::parse_diff(&diff_output);
-                        // Sync the diff with the database
-                        match db.sync_with_diff(&range, &files) {
-                            Ok(()) => {
-                                // Now read progress from the updated DB
-                                match db.progress(&range) {
-                                    Ok(p) => ReviewProgress {
-                                        reviewed: p.reviewed,
-                                        total: p.total_hunks,
-                                    },
-                                    Err(_) => ReviewProgress {
-                                        reviewed: 0,
-                                        total: 0,
-                                    },
-                                }
-                            }
-                            Err(_) => ReviewProgress {
-                                reviewed: 0,
-                                total: 0,
-                            },
-                        }
-                    }
-                    Err(_) => {
-                        // Can't get diff — try DB progress as fallback (may be stale)
-                        match db.progress(&range) {
-                            Ok(p) => ReviewProgress {
-                                reviewed: p.reviewed,
-                                total: p.total_hunks,
-                            },
-                            Err(_) => ReviewProgress {
-                                reviewed: 0,
-                                total: 0,
-                            },
-                        }
-                    }
-                };
+                let progress = branch_progress(db, &range);
 
                 // Update item with loaded data
                 item.detail = Some(detail);
                 item.progress = Some(progress);
+                item.reviewers = branch_reviewers(db, &range);
             }
             // If get_branch_detail fails, we leave detail as None (shows "-" in UI)
         }
     }
 
+    /// Load details for all items like [`Dashboard::load_all_details`], but
+    /// without syncing or re-keying any branch's review state.
+    pub fn peek_all_details(&mut self, db: &ReviewDb) {
+        for item in &mut self.items {
+            if item.detail.is_some() {
+                continue;
+            }
+            let branch_name = &item.branch.name;
+            if let Ok(detail) = crate::git::get_branch_detail(&self.base_branch, branch_name) {
+                let range = format!("{}..{}", self.base_branch, branch_name);
+                item.detail = Some(detail);
+                item.progress = Some(peek_branch_progress(db, &range));
+                item.reviewers = crate::session::peek_review_key(db, &range)
+                    .ok()
+                    .and_then(|key| db.reviewers(&key).ok())
+                    .unwrap_or_default();
+            }
+        }
+    }
+
     /// Check if the selected branch can be merged (all hunks reviewed).
     pub fn can_merge_selected(&self) -> bool {
         self.selected_item()
@@ -242,6 +526,131 @@ impl Dashboard {
     }
 }
 
+/// Errors that can occur while loading another repository for the multi-repo dashboard.
+#[derive(Debug, Error)]
+pub enum OverviewError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Whether a branch has hunks left to review.
+pub fn needs_review(progress: &ReviewProgress) -> bool {
+    progress.total > 0 && progress.reviewed < progress.total
+}
+
+/// The branches of one repository that still need review.
+pub struct RepoOverview {
+    pub root: PathBuf,
+    /// Directory name of the repository, shown in the dashboard.
+    pub name: String,
+    pub base_branch: String,
+    pub items: Vec<DashboardItem>,
+}
+
+impl RepoOverview {
+    /// Load the branches needing review in the repository containing `path`.
+    pub fn load(path: &Path) -> Result<Self, OverviewError> {
+        crate::git::in_repo(Some(path), Self::load_current)
+    }
+
+    fn load_current() -> Result<Self, OverviewError> {
+        let root = crate::git::find_repo_root()?;
+        let base_branch = crate::git::detect_default_branch()?;
+        let mut db = crate::session::open_db(&root)?;
+        let mut dashboard = Dashboard::load(&db, &base_branch, false)?;
+        dashboard.load_all_details(&mut db);
+
+        let items = dashboard
+            .items
+            .into_iter()
+            .filter(|item| item.progress.as_ref().is_some_and(needs_review))
+            .collect();
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| root.display().to_string());
+        Ok(Self {
+            root,
+            name,
+            base_branch,
+            items,
+        })
+    }
+}
+
+/// Dashboard state across several repositories (`dashboard --repos`).
+pub struct RepoDashboard {
+    /// Paths the dashboard was opened with, reloaded on refresh.
+    pub paths: Vec<PathBuf>,
+    pub repos: Vec<RepoOverview>,
+    /// Paths that could not be loaded, with the reason.
+    pub failed: Vec<(PathBuf, String)>,
+    /// Index into [`RepoDashboard::rows`].
+    pub selected: usize,
+}
+
+impl RepoDashboard {
+    /// Load every repository in `paths`. Paths that are not directories (as a
+    /// shell glob like `~/code/*` may produce) are ignored.
+    pub fn load(paths: Vec<PathBuf>) -> Self {
+        // Absolute paths keep working wherever git runs
+        let paths = paths
+            .into_iter()
+            .map(|path| path.canonicalize().unwrap_or(path))
+            .collect();
+        let mut dashboard = Self {
+            paths,
+            repos: Vec::new(),
+            failed: Vec::new(),
+            selected: 0,
+        };
+        dashboard.reload();
+        dashboard
+    }
+
+    /// Reload all repositories, keeping the selection in bounds.
+    pub fn reload(&mut self) {
+        self.repos.clear();
+        self.failed.clear();
+        for path in self.paths.iter().filter(|path| path.is_dir()) {
+            match RepoOverview::load(path) {
+                Ok(repo) => self.repos.push(repo),
+                Err(e) => self.failed.push((path.clone(), e.to_string())),
+            }
+        }
+        self.selected = self.selected.min(self.rows().len().saturating_sub(1));
+    }
+
+    /// Every branch needing review, grouped by repository.
+    pub fn rows(&self) -> Vec<(&RepoOverview, &DashboardItem)> {
+        self.repos
+            .iter()
+            .flat_map(|repo| repo.items.iter().map(move |item| (repo, item)))
+            .collect()
+    }
+
+    /// The repository and branch under the cursor.
+    pub fn selected_row(&self) -> Option<(&RepoOverview, &DashboardItem)> {
+        self.rows().get(self.selected).copied()
+    }
+
+    /// Move selection down (clamp to end).
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.rows().len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Move selection up (clamp to start).
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,18 +671,93 @@ mod tests {
     fn mock_dashboard(n: usize) -> Dashboard {
         Dashboard {
             items: (0..n)
-                .map(|i| DashboardItem {
-                    branch: mock_branch(&format!("branch-{}", i)),
-                    detail: None,
-                    progress: None,
+                .map(|i| {
+                    DashboardItem::new(mock_branch(&format!("branch-{}", i)), String::new(), None)
                 })
                 .collect(),
             selected: 0,
             base_branch: "main".to_string(),
             last_head_sha: "deadbeef".to_string(),
+            tips: HashMap::new(),
+            remotes: false,
+            marked: HashSet::new(),
+            untouched_by: None,
+            queued: HashSet::new(),
         }
     }
 
+    #[test]
+    fn repo_dashboard_rows_span_repositories() {
+        let repo = |name: &str, branches: usize| RepoOverview {
+            root: PathBuf::from(format!("/code/{}", name)),
+            name: name.to_string(),
+            base_branch: "main".to_string(),
+            items: mock_dashboard(branches).items,
+        };
+        let mut repos = RepoDashboard::load(vec![PathBuf::from("/nonexistent/repo")]);
+        assert!(repos.repos.is_empty() && repos.failed.is_empty());
+        repos.repos = vec![repo("api", 2), repo("empty", 0), repo("web", 1)];
+
+        let rows: Vec<(&str, &str)> = repos
+            .rows()
+            .into_iter()
+            .map(|(repo, item)| (repo.name.as_str(), item.branch.name.as_str()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("api", "branch-0"),
+                ("api", "branch-1"),
+                ("web", "branch-0")
+            ]
+        );
+
+        repos.select_next();
+        repos.select_next();
+        repos.select_next();
+        let (repo, item) = repos.selected_row().unwrap();
+        assert_eq!(
+            (repo.name.as_str(), item.branch.name.as_str()),
+            ("web", "branch-0")
+        );
+
+        assert!(needs_review(&ReviewProgress {
+            reviewed: 1,
+            total: 2,
+            unreviewed: 1,
+            stale: 0,
+            needs_changes: 0,
+            skimmed: 0,
+        }));
+        assert!(!needs_review(&ReviewProgress::default()));
+    }
+
+    #[test]
+    fn remote_tracking_bases_are_not_listed() {
+        let remote = |name: &str| BranchInfo {
+            is_local: false,
+            ..mock_branch(name)
+        };
+        assert!(is_base(&mock_branch("main"), "main"));
+        assert!(is_base(&remote("origin/main"), "main"));
+        assert!(!is_base(&mock_branch("origin/main"), "main"));
+        assert!(!is_base(&remote("origin/feature"), "main"));
+        assert!(!is_base(&remote("origin/fix/main"), "main"));
+    }
+
+    #[test]
+    fn unmoved_tips_are_not_flagged() {
+        let reviewed = "abc123".to_string();
+        let item = DashboardItem::new(mock_branch("feature"), reviewed.clone(), Some(&reviewed));
+        assert_eq!(item.tip_change, None);
+        let item = DashboardItem::new(mock_branch("feature"), reviewed, None);
+        assert_eq!(item.tip_change, None);
+        assert_eq!(
+            TipChange::Rewritten.label(),
+            "force-pushed since last review"
+        );
+    }
+
     #[test]
     fn test_select_next_empty() {
         let mut dashboard = mock_dashboard(0);
@@ -322,6 +806,7 @@ mod tests {
         dashboard.items[0].progress = Some(ReviewProgress {
             reviewed: 5,
             total: 10,
+            ..Default::default()
         });
 
         assert!(!dashboard.can_merge_selected());
@@ -333,11 +818,48 @@ mod tests {
         dashboard.items[0].progress = Some(ReviewProgress {
             reviewed: 10,
             total: 10,
+            ..Default::default()
         });
 
         assert!(dashboard.can_merge_selected());
     }
 
+    #[test]
+    fn test_action_targets_prefers_marked_in_dashboard_order() {
+        let mut dashboard = mock_dashboard(3);
+        assert_eq!(dashboard.action_targets(), vec!["branch-0"]);
+
+        dashboard.selected = 2;
+        dashboard.toggle_mark_selected();
+        dashboard.selected = 1;
+        dashboard.toggle_mark_selected();
+        assert_eq!(dashboard.action_targets(), vec!["branch-1", "branch-2"]);
+
+        // Toggling again unmarks
+        dashboard.toggle_mark_selected();
+        assert_eq!(dashboard.action_targets(), vec!["branch-2"]);
+    }
+
+    #[test]
+    fn test_untouched_filter_hides_branches_i_reviewed() {
+        let mut dashboard = mock_dashboard(3);
+        dashboard.items[0].reviewers = vec!["ana".to_string(), "bo".to_string()];
+        dashboard.items[2].reviewers = vec!["bo".to_string()];
+        assert_eq!(dashboard.items[0].last_reviewer(), Some("ana"));
+        assert_eq!(dashboard.items[1].last_reviewer(), None);
+
+        dashboard.selected = 2;
+        dashboard.set_untouched_by(Some("ana".to_string()));
+        assert_eq!(dashboard.selected_branch(), Some("branch-1"));
+        dashboard.select_next();
+        assert_eq!(dashboard.selected_branch(), Some("branch-2"));
+        dashboard.select_next();
+        assert_eq!(dashboard.action_targets(), vec!["branch-2"]);
+
+        dashboard.set_untouched_by(None);
+        assert_eq!(dashboard.visible_items().len(), 3);
+    }
+
     #[test]
     fn test_can_merge_selected_no_progress() {
         let dashboard = mock_dashboard(1);
@@ -356,8 +878,13 @@ mod tests {
         let mut db = ReviewDb::open(&db_path).unwrap();
 
         // Simulate stale DB state: mark a hunk as reviewed for an old diff
-        db.set_status("main..feature", "file.txt", "old_hash", HunkStatus::Reviewed)
-            .unwrap();
+        db.set_status(
+            "main..feature",
+            "file.txt",
+            "old_hash",
+            HunkStatus::Reviewed,
+        )
+        .unwrap();
 
         // Verify DB shows 1 reviewed hunk
         let progress = db.progress("main..feature").unwrap();
@@ -372,15 +899,15 @@ mod tests {
                 old_count: 1,
                 new_start: 1,
                 new_count: 1,
-                content: "new content".to_string(),
+                content: "new content".into(),
                 content_hash: "new_hash".to_string(),
+                section: String::new(),
                 status: HunkStatus::Unreviewed,
             }],
         }];
 
         // Sync with the current diff
-        db.sync_with_diff("main..feature", &current_files)
-            .unwrap();
+        db.sync_with_diff("main..feature", &current_files).unwrap();
 
         // Now DB should show 1 unreviewed hunk, 1 stale hunk
         let progress = db.progress("main..feature").unwrap();
@@ -412,8 +939,9 @@ mod tests {
                     old_count: 1,
                     new_start: 1,
                     new_count: 1,
-                    content: "hunk1".to_string(),
+                    content: "hunk1".into(),
                     content_hash: "hash1".to_string(),
+                    section: String::new(),
                     status: HunkStatus::Unreviewed,
                 },
                 DiffHunk {
@@ -421,8 +949,9 @@ mod tests {
                     old_count: 1,
                     new_start: 5,
                     new_count: 1,
-                    content: "hunk2".to_string(),
+                    content: "hunk2".into(),
                     content_hash: "hash2".to_string(),
+                    section: String::new(),
                     status: HunkStatus::Unreviewed,
                 },
             ],
@@ -449,8 +978,9 @@ mod tests {
                     old_count: 1,
                     new_start: 1,
                     new_count: 2,
-                    content: "hunk1_modified".to_string(),
+                    content: "hunk1_modified".into(),
                     content_hash: "hash1_new".to_string(),
+                    section: String::new(),
                     status: HunkStatus::Unreviewed,
                 },
                 DiffHunk {
@@ -458,8 +988,9 @@ mod tests {
                     old_count: 1,
                     new_start: 6,
                     new_count: 1,
-                    content: "hunk2".to_string(),
+                    content: "hunk2".into(),
                     content_hash: "hash2".to_string(), // Same as before
+                    section: String::new(),
                     status: HunkStatus::Unreviewed,
                 },
                 DiffHunk {
@@ -467,8 +998,9 @@ mod tests {
                     old_count: 1,
                     new_start: 11,
                     new_count: 1,
-                    content: "hunk3".to_string(),
+                    content: "hunk3".into(),
                     content_hash: "hash3".to_string(),
+                    section: String::new(),
                     status: HunkStatus::Unreviewed,
                 },
             ],
@@ -498,8 +1030,13 @@ mod tests {
         let mut db = ReviewDb::open(&db_path).unwrap();
 
         // Pre-populate DB with stale data
-        db.set_status("main..branch1", "file.txt", "stale_hash", HunkStatus::Reviewed)
-            .unwrap();
+        db.set_status(
+            "main..branch1",
+            "file.txt",
+            "stale_hash",
+            HunkStatus::Reviewed,
+        )
+        .unwrap();
 
         // Note: In a real scenario, load_all_details would call git::get_diff
         // and sync the actual current diff. We can't test that here without
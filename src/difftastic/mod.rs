@@ -0,0 +1,416 @@
+//! Structural diffs from [difftastic](https://difftastic.wilfred.me.uk).
+//!
+//! `git diff` compares lines, so reformatting code shows up as changes. This
+//! module re-diffs each text file of a `git diff` with `difft --display=json`,
+//! which compares syntax trees, and rewrites the file's hunks to cover only the
+//! lines difftastic found novel tokens on. The result is still a unified diff,
+//! so the parser, review state, and TUI work on it unchanged.
+//!
+//! Files difftastic has no parser for, binary files, and added or deleted files
+//! keep their line-based hunks. Files whose changes are formatting only are
+//! dropped from the diff.
+
+use crate::git::{self, DiffOptions, GitError};
+use serde::Deserialize;
+use serde::de::IgnoredAny;
+use similar::{Algorithm, DiffTag};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use thiserror::Error;
+
+/// Unchanged lines shown around each change, as in `git diff`.
+pub const CONTEXT_LINES: usize = 3;
+
+/// Errors that can occur while producing a structural diff.
+#[derive(Debug, Error)]
+pub enum DifftasticError {
+    #[error("difft not found; install difftastic to use structural diffs")]
+    NotInstalled,
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, DifftasticError>;
+
+/// One file of `difft --display=json` output.
+#[derive(Debug, Deserialize)]
+struct DifftFile {
+    language: String,
+    status: String,
+    #[serde(default)]
+    chunks: Vec<Vec<DifftLine>>,
+}
+
+/// A line pair in a difftastic chunk; either side is absent for pure additions or removals.
+#[derive(Debug, Deserialize)]
+struct DifftLine {
+    lhs: Option<DifftSide>,
+    rhs: Option<DifftSide>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DifftSide {
+    /// Zero-based line number in that side's file.
+    line_number: usize,
+    /// Novel tokens on the line; only their presence matters here.
+    #[serde(default)]
+    changes: Vec<IgnoredAny>,
+}
+
+/// Get the diff for `range` with each supported file's hunks replaced by difftastic's.
+pub fn get_diff(range: &str, options: DiffOptions) -> Result<String> {
+    let diff = git::get_diff_with_options(range, options)?;
+    let repo_root = git::find_repo_root()?;
+
+    let mut output = String::new();
+    for section in split_files(&diff) {
+        output.push_str(&restructure_file(section, &repo_root)?);
+    }
+    Ok(output)
+}
+
+/// Split a unified diff into per-file sections, each starting at its `diff --git` line.
+fn split_files(diff: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = diff
+        .match_indices("diff --git ")
+        .map(|(i, _)| i)
+        .filter(|&i| i == 0 || diff.as_bytes()[i - 1] == b'\n')
+        .collect();
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts.push(diff.len());
+    starts
+        .windows(2)
+        .map(|w| &diff[w[0]..w[1]])
+        .filter(|section| !section.is_empty())
+        .collect()
+}
+
+/// Rewrite one file section of a `git diff` with difftastic's hunks.
+///
+/// Sections difftastic cannot improve on are returned unchanged.
+fn restructure_file(section: &str, repo_root: &Path) -> Result<String> {
+    let Some((header, path, old_id, new_id)) = parse_header(section) else {
+        return Ok(section.to_string());
+    };
+    let is_null = |id: &str| id.bytes().all(|b| b == b'0');
+    if is_null(old_id) || is_null(new_id) {
+        return Ok(section.to_string());
+    }
+
+    // The new side of a working tree diff names a blob git hasn't stored
+    let old = git::read_blob(old_id).ok();
+    let new = git::read_blob(new_id)
+        .ok()
+        .or_else(|| std::fs::read(repo_root.join(path)).ok());
+    let (Some(Ok(old)), Some(Ok(new))) = (old.map(String::from_utf8), new.map(String::from_utf8))
+    else {
+        return Ok(section.to_string());
+    };
+
+    let report = match run_difft(path, &old, &new)? {
+        Some(report) => report,
+        None => return Ok(section.to_string()),
+    };
+    if report.language.starts_with("Text") {
+        // No parser for this file type, so difftastic diffed lines like git does
+        return Ok(section.to_string());
+    }
+    if report.status == "unchanged" {
+        return Ok(String::new());
+    }
+
+    let hunks = unified_hunks(&old, &new, &report);
+    if hunks.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format!("{}{}", header, hunks))
+}
+
+/// Header lines of a file section (through `+++`), its path, and its old and new blob ids.
+///
+/// `None` for sections without text hunks (binary files, renames, mode changes).
+fn parse_header(section: &str) -> Option<(&str, &str, &str, &str)> {
+    let mut ids = None;
+    let mut offset = 0;
+    for line in section.split_inclusive('\n') {
+        offset += line.len();
+        let text = line.trim_end_matches('\n');
+        if let Some(index) = text.strip_prefix("index ") {
+            let range = index.split(' ').next()?;
+            ids = range.split_once("..");
+        } else if let Some(path) = text.strip_prefix("+++ b/") {
+            let (old_id, new_id) = ids?;
+            return Some((&section[..offset], path, old_id, new_id));
+        } else if text.starts_with("@@") || text.starts_with("Binary files ") {
+            return None;
+        }
+    }
+    None
+}
+
+/// Run `difft --display=json` on both versions of a file.
+///
+/// `Ok(None)` when difftastic fails on this file or prints something unexpected,
+/// so the caller can fall back to git's hunks.
+fn run_difft(path: &str, old: &str, new: &str) -> Result<Option<DifftFile>> {
+    // difftastic picks a parser from the file name, so keep it on both copies.
+    // The private temporary directory is removed when `dir` is dropped.
+    let name = Path::new(path)
+        .file_name()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("file"));
+    let dir = tempfile::Builder::new()
+        .prefix("git-review-difft-")
+        .tempdir()?;
+    let old_path = dir.path().join("old").join(&name);
+    let new_path = dir.path().join("new").join(&name);
+
+    std::fs::create_dir(dir.path().join("old"))?;
+    std::fs::create_dir(dir.path().join("new"))?;
+    std::fs::write(&old_path, old)?;
+    std::fs::write(&new_path, new)?;
+    let output = Command::new("difft")
+        .arg("--display=json")
+        .arg("--color=never")
+        .arg(&old_path)
+        .arg(&new_path)
+        // JSON output is still marked unstable
+        .env("DFT_UNSTABLE", "yes")
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DifftasticError::NotInstalled,
+            _ => e.into(),
+        })?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(serde_json::from_slice(&output.stdout).ok())
+}
+
+/// How one line appears in the rewritten diff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Context,
+    Removed,
+    Added,
+}
+
+/// What a line is matched on when aligning the two sides.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum LineKey {
+    /// A line without novel tokens, by its text without whitespace.
+    Unchanged(String),
+    /// A line with novel tokens: side (0 old, 1 new) and index, so it never matches.
+    Novel(u8, usize),
+}
+
+/// Build unified diff hunks for the lines difftastic reported novel tokens on.
+///
+/// Lines without novel tokens are aligned by a line diff that ignores
+/// whitespace, and matching pairs become context shown with their new text,
+/// so reformatted lines don't appear as changes. Unchanged lines that don't
+/// line up, as when a call is split across lines, are shown as removed and
+/// added, so the hunk never pairs up lines that differ.
+fn unified_hunks(old: &str, new: &str, report: &DifftFile) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let mut removed = HashSet::new();
+    let mut added = HashSet::new();
+    for line in report.chunks.iter().flatten() {
+        if let Some(lhs) = line.lhs.as_ref().filter(|side| !side.changes.is_empty()) {
+            removed.insert(lhs.line_number);
+        }
+        if let Some(rhs) = line.rhs.as_ref().filter(|side| !side.changes.is_empty()) {
+            added.insert(rhs.line_number);
+        }
+    }
+
+    let key = |side: u8, index: usize, line: &str, novel: &HashSet<usize>| {
+        if novel.contains(&index) {
+            LineKey::Novel(side, index)
+        } else {
+            LineKey::Unchanged(line.split_whitespace().collect())
+        }
+    };
+    let old_keys: Vec<LineKey> = (old_lines.iter().enumerate())
+        .map(|(i, line)| key(0, i, line, &removed))
+        .collect();
+    let new_keys: Vec<LineKey> = (new_lines.iter().enumerate())
+        .map(|(j, line)| key(1, j, line, &added))
+        .collect();
+
+    // Emit (op, old index, new index) for every line, removals before additions
+    let mut ops = Vec::new();
+    for diff_op in similar::capture_diff_slices(Algorithm::Myers, &old_keys, &new_keys) {
+        let (tag, old_range, new_range) = diff_op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            ops.extend(old_range.zip(new_range).map(|(i, j)| (Op::Context, i, j)));
+            continue;
+        }
+        let (i_end, j_start) = (old_range.end, new_range.start);
+        ops.extend(old_range.map(|i| (Op::Removed, i, j_start)));
+        ops.extend(new_range.map(|j| (Op::Added, i_end, j)));
+    }
+
+    let mut output = String::new();
+    let changes: Vec<usize> = (0..ops.len())
+        .filter(|&k| ops[k].0 != Op::Context)
+        .collect();
+    let mut k = 0;
+    while k < changes.len() {
+        // Merge changes whose context would overlap into one hunk
+        let first = changes[k];
+        let mut last = first;
+        while k + 1 < changes.len() && changes[k + 1] - last <= 2 * CONTEXT_LINES + 1 {
+            k += 1;
+            last = changes[k];
+        }
+        k += 1;
+
+        let start = first.saturating_sub(CONTEXT_LINES);
+        let end = (last + 1 + CONTEXT_LINES).min(ops.len());
+        let hunk = &ops[start..end];
+        let old_count = hunk.iter().filter(|(op, ..)| *op != Op::Added).count();
+        let new_count = hunk.iter().filter(|(op, ..)| *op != Op::Removed).count();
+        // An empty side's start is the line before the hunk, as in git
+        let (_, old_start, new_start) = hunk[0];
+        let old_start = if old_count == 0 {
+            old_start
+        } else {
+            old_start + 1
+        };
+        let new_start = if new_count == 0 {
+            new_start
+        } else {
+            new_start + 1
+        };
+
+        output.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            old_start, old_count, new_start, new_count
+        ));
+        for &(op, i, j) in hunk {
+            match op {
+                Op::Context => output.push_str(&format!(" {}\n", new_lines[j])),
+                Op::Removed => output.push_str(&format!("-{}\n", old_lines[i])),
+                Op::Added => output.push_str(&format!("+{}\n", new_lines[j])),
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_diff;
+
+    fn report(json: &str) -> DifftFile {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn reformatted_lines_stay_context() {
+        let old = "fn a() {\n    let x=1;\n    call(x)\n}\n";
+        let new = "fn a() {\n    let x = 1;\n    call(x, 2)\n}\n";
+        // Only `, 2` is novel; the spacing change in `let x = 1` is not reported
+        let report = report(
+            r#"{"language":"Rust","status":"changed","chunks":[[
+                {"lhs":{"line_number":2,"changes":[{"start":4,"end":8,"content":"call","highlight":"normal"}]},
+                 "rhs":{"line_number":2,"changes":[{"start":11,"end":12,"content":"2","highlight":"normal"}]}}
+            ]]}"#,
+        );
+
+        let hunks = unified_hunks(old, new, &report);
+        assert_eq!(
+            hunks,
+            "@@ -1,4 +1,4 @@\n fn a() {\n     let x = 1;\n-    call(x)\n+    call(x, 2)\n }\n"
+        );
+    }
+
+    #[test]
+    fn unchanged_lines_that_no_longer_line_up_are_not_paired() {
+        // `f(a,b)` was split over three lines; X became Y
+        let old = "f(a,b)\nX\nZ\n";
+        let new = "f(\na,\nb)\nY\nZ\n";
+        let report = report(
+            r#"{"language":"Rust","status":"changed","chunks":[[
+                {"lhs":{"line_number":1,"changes":[{}]},"rhs":{"line_number":3,"changes":[{}]}}
+            ]]}"#,
+        );
+
+        assert_eq!(
+            unified_hunks(old, new, &report),
+            "@@ -1,3 +1,5 @@\n-f(a,b)\n-X\n+f(\n+a,\n+b)\n+Y\n Z\n"
+        );
+    }
+
+    #[test]
+    fn distant_changes_get_separate_hunks() {
+        let old: String = (0..20).map(|n| format!("line{}\n", n)).collect();
+        let new = old
+            .replace("line2\n", "")
+            .replace("line15\n", "line15\nnew\n");
+        let report = report(
+            r#"{"language":"Rust","status":"changed","chunks":[
+                [{"lhs":{"line_number":2,"changes":[{}]}}],
+                [{"rhs":{"line_number":15,"changes":[{}]}}]
+            ]}"#,
+        );
+
+        let diff = format!(
+            "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n{}",
+            unified_hunks(&old, &new, &report)
+        );
+        let hunks = &parse_diff(&diff)[0].hunks;
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(
+            (
+                hunks[0].old_start,
+                hunks[0].old_count,
+                hunks[0].new_start,
+                hunks[0].new_count
+            ),
+            (1, 6, 1, 5)
+        );
+        assert_eq!(
+            (
+                hunks[1].old_start,
+                hunks[1].old_count,
+                hunks[1].new_start,
+                hunks[1].new_count
+            ),
+            (14, 6, 13, 7)
+        );
+        assert!(hunks[1].content.contains("\n+new\n"));
+    }
+
+    #[test]
+    fn parse_header_finds_blobs_and_skips_binary() {
+        let section = "diff --git a/src/a.rs b/src/a.rs\nindex 1234567..89abcde 100644\n--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1 +1 @@\n-a\n+b\n";
+        let (header, path, old, new) = parse_header(section).unwrap();
+        assert!(header.ends_with("+++ b/src/a.rs\n"));
+        assert_eq!((path, old, new), ("src/a.rs", "1234567", "89abcde"));
+
+        let binary = "diff --git a/x.png b/x.png\nindex 1234567..89abcde 100644\nBinary files a/x.png and b/x.png differ\n";
+        assert!(parse_header(binary).is_none());
+    }
+
+    #[test]
+    fn split_files_keeps_each_section() {
+        let diff = "diff --git a/a b/a\n+x diff --git \ndiff --git a/b b/b\n+y\n";
+        assert_eq!(
+            split_files(diff),
+            vec![
+                "diff --git a/a b/a\n+x diff --git \n",
+                "diff --git a/b b/b\n+y\n"
+            ]
+        );
+    }
+}
@@ -0,0 +1,720 @@
+//! Review round-trips over email, for mailing-list (`git send-email`) workflows.
+//!
+//! `email export` turns the review of a patch series (see `git-review patch`)
+//! into an mbox of replies, one for each patch with hunks left to review or
+//! comments on it. A reply quotes those hunks the way a mail client quotes a
+//! patch, puts the review comments below the hunk they are about, and threads
+//! under the patch with `In-Reply-To`, so `git send-email` can send it.
+//!
+//! `email ingest` reads replies back. A reply carrying a `Reviewed-by:` or
+//! `Acked-by:` trailer for the address it was sent from approves the hunks it
+//! quotes, or the whole patch when it quotes none, crediting the sender.
+//! Replies are matched to patches by `In-Reply-To` or `References`, falling
+//! back to the subject. A reply whose trailer names someone else, or that
+//! answers no patch of the series, is skipped and reported, since anyone can
+//! write a trailer in their own mail.
+
+use crate::parser::parse_diff;
+use crate::patch::select_hunks;
+use crate::state::{ReviewDb, StateError};
+use crate::{DiffFile, HunkStatus};
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Separator line starting each exported message, as `git format-patch` writes it.
+const FROM_LINE: &str = "From git-review Mon Sep 17 00:00:00 2001";
+
+/// Trailers that approve a patch when a reply carries them (compared lowercased).
+const APPROVAL_TRAILERS: [&str; 2] = ["reviewed-by:", "acked-by:"];
+
+/// Errors that can occur while exporting or ingesting review emails.
+#[derive(Debug, Error)]
+pub enum EmailError {
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+pub type Result<T> = std::result::Result<T, EmailError>;
+
+/// One message of an mbox: its headers, in order, and its body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message {
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl Message {
+    /// Value of the first header called `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The `Message-Id`, if the message has one.
+    fn id(&self) -> Option<&str> {
+        self.header("Message-Id").filter(|id| !id.is_empty())
+    }
+
+    /// The subject without `Re:` prefixes.
+    fn topic(&self) -> &str {
+        let mut subject = self.header("Subject").unwrap_or("");
+        while let Some(prefix) = subject.get(..3)
+            && prefix.eq_ignore_ascii_case("re:")
+        {
+            subject = subject[3..].trim_start();
+        }
+        subject
+    }
+
+    /// The files and hunks of the patch in the body.
+    fn files(&self) -> Vec<DiffFile> {
+        parse_diff(&self.body)
+    }
+}
+
+/// A hunk approved by a reply, with the name of the reply's sender.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplyApproval {
+    pub file_path: String,
+    pub content_hash: String,
+    pub reviewer: String,
+}
+
+/// A reply with an approval trailer that approves nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedReply {
+    /// The reply's `From:` header.
+    pub from: String,
+    /// The reply's `Subject:` header.
+    pub subject: String,
+    /// Why it approves nothing.
+    pub reason: String,
+}
+
+/// Outcome of [`ingest`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Ingested {
+    /// Hunks marked reviewed.
+    pub marked: usize,
+    /// Replies whose trailer was not taken.
+    pub skipped: Vec<SkippedReply>,
+}
+
+/// Split an mbox into its messages.
+///
+/// A message starts at a `From ` line at the top of the text or after a blank
+/// line; text without one (a single patch email or a plain diff) is read as
+/// one message. Lines escaped as `>From ` in the body are unescaped.
+pub fn split_mbox(text: &str) -> Vec<Message> {
+    let mut messages: Vec<Vec<&str>> = Vec::new();
+    let mut after_blank = true;
+    for line in text.lines() {
+        let separator = after_blank && line.starts_with("From ");
+        if separator || messages.is_empty() {
+            messages.push(Vec::new());
+        }
+        if !separator && let Some(lines) = messages.last_mut() {
+            lines.push(line);
+        }
+        after_blank = line.is_empty();
+    }
+    messages.iter().map(|lines| parse_message(lines)).collect()
+}
+
+/// Split a message into headers, with folded lines joined, and body.
+fn parse_message(lines: &[&str]) -> Message {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut body_start = lines.len();
+    for (i, line) in lines.iter().enumerate() {
+        if line.is_empty() {
+            body_start = i + 1;
+            break;
+        }
+        if line.starts_with([' ', '\t'])
+            && let Some((_, value)) = headers.last_mut()
+        {
+            value.push(' ');
+            value.push_str(line.trim());
+        } else if let Some((name, value)) = line.split_once(':')
+            && !name.contains(' ')
+        {
+            headers.push((name.to_string(), value.trim().to_string()));
+        } else {
+            // No header block: it is all body
+            headers.clear();
+            body_start = 0;
+            break;
+        }
+    }
+    let body = lines[body_start..]
+        .iter()
+        .map(|line| {
+            let unescaped = match line.strip_prefix('>') {
+                Some(rest) if rest.trim_start_matches('>').starts_with("From ") => rest,
+                _ => line,
+            };
+            format!("{}\n", unescaped)
+        })
+        .collect();
+    Message { headers, body }
+}
+
+/// Render replies to the patches of `series` reviewed under `base_ref`, as an
+/// mbox sent by `from` (`Name <email>`), and how many replies there are.
+///
+/// Each patch with hunks left to review (any status but reviewed) or with
+/// comments gets a reply quoting those hunks, each followed by its comments.
+/// Comments by someone other than `from` name their author.
+pub fn export(
+    db: &ReviewDb,
+    base_ref: &str,
+    series: &[Message],
+    from: &str,
+) -> Result<(String, usize)> {
+    let comments = db.comments(base_ref)?;
+    // Comments by the sender go out unattributed
+    let (sender_name, sender_address) = (display_name(from), address(from));
+    let by_sender = |author: &str| {
+        sender_name.as_deref() == Some(author)
+            || address(author).is_some_and(|author| sender_address.as_ref() == Some(&author))
+    };
+    let mut mbox = String::new();
+    let mut count = 0;
+    for patch in series {
+        let mut quoted = String::new();
+        let mut pending = 0;
+        let mut total = 0;
+        let mut last_file = None;
+        for file in patch.files() {
+            let path = file.path.to_string_lossy().to_string();
+            for hunk in &file.hunks {
+                total += 1;
+                let status = db.get_status(base_ref, &path, &hunk.content_hash)?;
+                let notes: Vec<_> = comments
+                    .iter()
+                    .filter(|c| c.file_path == path && c.content_hash == hunk.content_hash)
+                    .collect();
+                if status != HunkStatus::Reviewed {
+                    pending += 1;
+                } else if notes.is_empty() {
+                    continue;
+                }
+
+                let (text, _) = select_hunks(&patch.body, |p, h| {
+                    p == file.path && h.content_hash == hunk.content_hash
+                });
+                // The file header is quoted once, before its first hunk
+                let start = if last_file.as_ref() == Some(&file.path) {
+                    text.find("\n@@").map_or(0, |pos| pos + 1)
+                } else {
+                    0
+                };
+                last_file = Some(file.path.clone());
+                // The last hunk of a patch runs into the signature
+                let mut lines: Vec<&str> = text[start..]
+                    .lines()
+                    .take_while(|line| *line != "-- ")
+                    .collect();
+                while lines.last().is_some_and(|line| line.is_empty()) {
+                    lines.pop();
+                }
+                for line in lines {
+                    quoted.push_str(&quote(line));
+                }
+                quoted.push('\n');
+                for note in notes {
+                    let body = match note.author.as_deref() {
+                        Some(author) if !by_sender(author) => {
+                            format!("{}: {}", author, note.body)
+                        }
+                        _ => note.body.clone(),
+                    };
+                    for line in body.lines() {
+                        quoted.push_str(&escape(line));
+                    }
+                    quoted.push('\n');
+                }
+            }
+        }
+        if quoted.is_empty() {
+            continue;
+        }
+
+        count += 1;
+        mbox.push_str(&reply_headers(patch, from));
+        mbox.push('\n');
+        if pending > 0 {
+            mbox.push_str(&format!(
+                "Still to review: {} of {} hunks.\n\n",
+                pending, total
+            ));
+        }
+        let author = patch.header("From").unwrap_or("You");
+        match patch.header("Date") {
+            Some(date) => mbox.push_str(&format!("On {}, {} wrote:\n", date, author)),
+            None => mbox.push_str(&format!("{} wrote:\n", author)),
+        }
+        mbox.push_str(&quoted);
+    }
+    Ok((mbox, count))
+}
+
+/// Headers of a reply to `patch` from `from`, threaded under it.
+fn reply_headers(patch: &Message, from: &str) -> String {
+    let mut headers = format!("{}\nFrom: {}\n", FROM_LINE, from);
+    if let Some(author) = patch.header("From") {
+        headers.push_str(&format!("To: {}\n", author));
+    }
+    let cc: Vec<&str> = ["To", "Cc"]
+        .iter()
+        .filter_map(|name| patch.header(name))
+        .collect();
+    if !cc.is_empty() {
+        headers.push_str(&format!("Cc: {}\n", cc.join(", ")));
+    }
+    headers.push_str(&format!("Subject: Re: {}\n", patch.topic()));
+    if let Some(id) = patch.id() {
+        let references = match patch.header("References") {
+            Some(references) => format!("{} {}", references, id),
+            None => id.to_string(),
+        };
+        headers.push_str(&format!(
+            "In-Reply-To: {}\nReferences: {}\n",
+            id, references
+        ));
+    }
+    headers
+}
+
+/// `line` quoted for a reply.
+fn quote(line: &str) -> String {
+    if line.is_empty() {
+        ">\n".to_string()
+    } else {
+        format!("> {}\n", line)
+    }
+}
+
+/// `line` for an mbox body, with a leading `From ` escaped.
+fn escape(line: &str) -> String {
+    if line.trim_start_matches('>').starts_with("From ") {
+        format!(">{}\n", line)
+    } else {
+        format!("{}\n", line)
+    }
+}
+
+/// The hunks of `series` that `replies` approve, and the replies with an
+/// approval trailer that approve nothing.
+pub fn approvals(
+    series: &[Message],
+    replies: &[Message],
+) -> (Vec<ReplyApproval>, Vec<SkippedReply>) {
+    let mut approvals = Vec::new();
+    let mut skipped = Vec::new();
+    for reply in replies {
+        let reviewer = match trailer_reviewer(reply) {
+            Ok(Some(reviewer)) => reviewer,
+            Ok(None) => continue,
+            Err(reason) => {
+                skipped.push(skip(reply, reason));
+                continue;
+            }
+        };
+        let Some(patch) = replied_patch(series, reply) else {
+            skipped.push(skip(
+                reply,
+                "it doesn't answer a patch of the series".to_string(),
+            ));
+            continue;
+        };
+        let quoted = quoted_hunks(&reply.body);
+        for file in patch.files() {
+            for hunk in &file.hunks {
+                let key = (file.path.clone(), hunk.old_start, hunk.new_start);
+                if quoted.is_empty() || quoted.contains(&key) {
+                    approvals.push(ReplyApproval {
+                        file_path: file.path.to_string_lossy().to_string(),
+                        content_hash: hunk.content_hash.clone(),
+                        reviewer: reviewer.clone(),
+                    });
+                }
+            }
+        }
+    }
+    (approvals, skipped)
+}
+
+fn skip(reply: &Message, reason: String) -> SkippedReply {
+    SkippedReply {
+        from: reply.header("From").unwrap_or("").to_string(),
+        subject: reply.header("Subject").unwrap_or("").to_string(),
+        reason,
+    }
+}
+
+/// Mark the hunks `replies` approve reviewed under `base_ref`, crediting the
+/// sender of each reply.
+///
+/// Only unreviewed hunks are changed, so local review work is never
+/// overwritten.
+pub fn ingest(
+    db: &mut ReviewDb,
+    base_ref: &str,
+    series: &[Message],
+    replies: &[Message],
+) -> Result<Ingested> {
+    let (approvals, skipped) = approvals(series, replies);
+    let mut marked = 0;
+    for approval in approvals {
+        if db.get_status(base_ref, &approval.file_path, &approval.content_hash)?
+            != HunkStatus::Unreviewed
+        {
+            continue;
+        }
+        db.record_status(
+            base_ref,
+            &approval.file_path,
+            &approval.content_hash,
+            HunkStatus::Reviewed,
+            Some(&approval.reviewer),
+        )?;
+        marked += 1;
+    }
+    Ok(Ingested { marked, skipped })
+}
+
+/// The sender of `reply` if it carries an approval trailer, outside the quoted
+/// text, for the address it was sent from; `None` without any approval
+/// trailer, and why it can't be taken when the trailers name someone else.
+fn trailer_reviewer(reply: &Message) -> std::result::Result<Option<String>, String> {
+    let trailers: Vec<&str> = reply
+        .body
+        .lines()
+        .filter(|line| !line.starts_with('>'))
+        .filter_map(|line| {
+            let line = line.trim();
+            let trailer = APPROVAL_TRAILERS
+                .iter()
+                .find(|trailer| line.to_lowercase().starts_with(*trailer))?;
+            Some(line[trailer.len()..].trim())
+        })
+        .collect();
+    if trailers.is_empty() {
+        return Ok(None);
+    }
+    let from = reply.header("From").unwrap_or("");
+    let Some(sender) = address(from) else {
+        return Err("it has no sender address".to_string());
+    };
+    if !trailers
+        .iter()
+        .any(|value| address(value) == Some(sender.clone()))
+    {
+        return Err(format!("its trailer is not for the sender, {}", sender));
+    }
+    Ok(Some(display_name(from).unwrap_or(sender)))
+}
+
+/// The name in a `Name <address>` value, or the address if it has none.
+fn display_name(value: &str) -> Option<String> {
+    let name = value
+        .split('<')
+        .next()
+        .unwrap_or("")
+        .trim()
+        .trim_matches('"')
+        .trim();
+    if name.is_empty() {
+        address(value)
+    } else {
+        Some(name.to_string())
+    }
+}
+
+/// The email address in a `Name <address>` or bare-address value, lowercased.
+fn address(value: &str) -> Option<String> {
+    let address = match value.rsplit_once('<') {
+        Some((_, rest)) => rest.split('>').next().unwrap_or(rest),
+        None => value,
+    };
+    let address = address.trim();
+    address.contains('@').then(|| address.to_lowercase())
+}
+
+/// The patch of `series` that `reply` answers: by `In-Reply-To`, then by
+/// `References` (nearest first), then by subject.
+fn replied_patch<'a>(series: &'a [Message], reply: &Message) -> Option<&'a Message> {
+    let patches: Vec<&Message> = series
+        .iter()
+        .filter(|message| !message.files().is_empty())
+        .collect();
+    let mut ids: Vec<&str> = reply.header("In-Reply-To").into_iter().collect();
+    if let Some(references) = reply.header("References") {
+        ids.extend(references.split_whitespace().rev());
+    }
+    ids.iter()
+        .find_map(|id| patches.iter().find(|patch| patch.id() == Some(*id)))
+        .or_else(|| patches.iter().find(|patch| patch.topic() == reply.topic()))
+        .copied()
+}
+
+/// Path and old and new start line of the hunks quoted (one level deep) in `body`.
+fn quoted_hunks(body: &str) -> Vec<(PathBuf, u32, u32)> {
+    let unquoted: String = body
+        .lines()
+        .filter_map(|line| line.strip_prefix('>'))
+        .filter(|line| !line.starts_with('>') && !line.starts_with(" >"))
+        .map(|line| format!("{}\n", line.strip_prefix(' ').unwrap_or(line)))
+        .collect();
+    parse_diff(&unquoted)
+        .into_iter()
+        .flat_map(|file| {
+            file.hunks
+                .iter()
+                .map(|hunk| (file.path.clone(), hunk.old_start, hunk.new_start))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERIES: &str = "\
+From 1111111111111111111111111111111111111111 Mon Sep 17 00:00:00 2001
+From: Dev <dev@example.com>
+Date: Mon, 5 Oct 2026 10:00:00 +0200
+Subject: [PATCH 1/2] Parse the
+ new format
+Message-Id: <1@example.com>
+To: list@example.com
+
+---
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-old
++new
+@@ -10 +10 @@
+-ten
++TEN
+-- \n\
+2.43.0
+
+From 2222222222222222222222222222222222222222 Mon Sep 17 00:00:00 2001
+From: Dev <dev@example.com>
+Subject: [PATCH 2/2] Add b
+Message-Id: <2@example.com>
+In-Reply-To: <1@example.com>
+
+>From here on, b exists.
+---
+diff --git a/b.txt b/b.txt
+new file mode 100644
+--- /dev/null
++++ b/b.txt
+@@ -0,0 +1 @@
++b
+";
+
+    fn hash(series: &[Message], patch: usize, hunk: usize) -> String {
+        series[patch].files()[0].hunks[hunk].content_hash.clone()
+    }
+
+    #[test]
+    fn mbox_splits_into_messages_with_headers() {
+        let series = split_mbox(SERIES);
+        assert_eq!(series.len(), 2);
+        assert_eq!(
+            series[0].header("subject"),
+            Some("[PATCH 1/2] Parse the new format")
+        );
+        assert_eq!(series[1].id(), Some("<2@example.com>"));
+        assert!(series[1].body.starts_with("From here on"));
+        assert_eq!(series[0].files()[0].hunks.len(), 2);
+
+        // A bare diff is one message without headers
+        let bare = split_mbox("diff --git a/x b/x\n--- a/x\n+++ b/x\n@@ -1 +1 @@\n-a\n+b\n");
+        assert_eq!(bare.len(), 1);
+        assert!(bare[0].headers.is_empty() && bare[0].files().len() == 1);
+    }
+
+    #[test]
+    fn export_quotes_hunks_left_to_review_with_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        let series = split_mbox(SERIES);
+        db.set_status(
+            "patch:x",
+            "a.txt",
+            &hash(&series, 0, 0),
+            HunkStatus::Reviewed,
+        )
+        .unwrap();
+        db.set_status(
+            "patch:x",
+            "b.txt",
+            &hash(&series, 1, 0),
+            HunkStatus::Reviewed,
+        )
+        .unwrap();
+        db.set_reviewer(Some("Bob".to_string()));
+        db.add_comment("patch:x", "a.txt", &hash(&series, 0, 1), "Why shout?")
+            .unwrap();
+
+        let (mbox, count) = export(&db, "patch:x", &series, "Me <me@example.com>").unwrap();
+        // The second patch is fully reviewed without comments
+        assert_eq!(count, 1);
+        assert_eq!(
+            mbox,
+            "\
+From git-review Mon Sep 17 00:00:00 2001
+From: Me <me@example.com>
+To: Dev <dev@example.com>
+Cc: list@example.com
+Subject: Re: [PATCH 1/2] Parse the new format
+In-Reply-To: <1@example.com>
+References: <1@example.com>
+
+Still to review: 1 of 2 hunks.
+
+On Mon, 5 Oct 2026 10:00:00 +0200, Dev <dev@example.com> wrote:
+> diff --git a/a.txt b/a.txt
+> --- a/a.txt
+> +++ b/a.txt
+> @@ -10 +10 @@
+> -ten
+> +TEN
+
+Bob: Why shout?
+
+"
+        );
+        assert_eq!(split_mbox(&mbox).len(), 1);
+    }
+
+    #[test]
+    fn export_attributes_comments_by_exact_sender_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        let series = split_mbox(SERIES);
+        db.set_reviewer(Some("Me".to_string()));
+        db.add_comment("patch:x", "a.txt", &hash(&series, 0, 1), "Why shout?")
+            .unwrap();
+
+        // "Me" is not "Meredith", so the comment is credited
+        let (mbox, _) = export(&db, "patch:x", &series, "Meredith <m@example.com>").unwrap();
+        assert!(mbox.contains("\nMe: Why shout?\n"));
+        let (mbox, _) = export(&db, "patch:x", &series, "\"Me\" <me@example.com>").unwrap();
+        assert!(mbox.contains("\nWhy shout?\n"));
+        assert!(!mbox.contains("Me: Why shout?"));
+    }
+
+    #[test]
+    fn trailers_approve_quoted_hunks_or_the_whole_patch() {
+        let series = split_mbox(SERIES);
+        let replies = split_mbox(
+            "\
+From alice Mon Sep 17 00:00:00 2001
+From: Alice <alice@example.com>
+Subject: Re: [PATCH 1/2] Parse the new format
+In-Reply-To: <1@example.com>
+
+> diff --git a/a.txt b/a.txt
+> --- a/a.txt
+> +++ b/a.txt
+> @@ -10 +10 @@
+> -ten
+
+Fine by me, the first hunk I'll leave to others.
+
+Reviewed-by: Alice Liddell <alice@example.com>
+
+From carol Mon Sep 17 00:00:00 2001
+From: Carol <carol@example.com>
+Subject: Re: [PATCH 2/2] Add b
+
+> Acked-by: Someone Quoted <q@example.com>
+Acked-by: Carol <carol@example.com>
+
+From dave Mon Sep 17 00:00:00 2001
+From: Dave <dave@example.com>
+Subject: Re: [PATCH 2/2] Add b
+In-Reply-To: <2@example.com>
+
+Looks wrong to me.
+
+From mallory Mon Sep 17 00:00:00 2001
+From: Mallory <mallory@example.com>
+Subject: Re: [PATCH 2/2] Add b
+In-Reply-To: <2@example.com>
+
+Reviewed-by: Alice Liddell <alice@example.com>
+
+From alice Mon Sep 17 00:00:00 2001
+From: Alice <alice@example.com>
+Subject: Re: [PATCH 9/9] Something else
+
+Reviewed-by: Alice Liddell <ALICE@example.com>
+",
+        );
+        let (approved, skipped) = approvals(&series, &replies);
+        assert_eq!(
+            approved,
+            [
+                ReplyApproval {
+                    file_path: "a.txt".to_string(),
+                    content_hash: hash(&series, 0, 1),
+                    reviewer: "Alice".to_string(),
+                },
+                ReplyApproval {
+                    file_path: "b.txt".to_string(),
+                    content_hash: hash(&series, 1, 0),
+                    reviewer: "Carol".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            skipped
+                .iter()
+                .map(|reply| (reply.from.as_str(), reply.reason.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    "Mallory <mallory@example.com>",
+                    "its trailer is not for the sender, mallory@example.com"
+                ),
+                (
+                    "Alice <alice@example.com>",
+                    "it doesn't answer a patch of the series"
+                ),
+            ]
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.set_status(
+            "patch:x",
+            "b.txt",
+            &hash(&series, 1, 0),
+            HunkStatus::NeedsChanges,
+        )
+        .unwrap();
+        // Local review work is kept
+        let ingested = ingest(&mut db, "patch:x", &series, &replies).unwrap();
+        assert_eq!((ingested.marked, ingested.skipped.len()), (1, 2));
+        let hunks = db.hunks("patch:x").unwrap();
+        let a = hunks.iter().find(|h| h.file_path == "a.txt").unwrap();
+        assert_eq!(a.reviewed_by.as_deref(), Some("Alice"));
+        assert_eq!(
+            db.get_status("patch:x", "b.txt", &hash(&series, 1, 0))
+                .unwrap(),
+            HunkStatus::NeedsChanges
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Encryption of free-text review state at rest.
+//!
+//! Hunk hashes, paths, and statuses are stored in the clear so hooks can check
+//! the gate without a key. Comment bodies and AI summaries can quote the diff,
+//! so with `[encryption] enabled = true` in `.git-review.toml` they are sealed
+//! with ChaCha20-Poly1305 before they reach the database. The key is 32 random
+//! bytes in base64, read from an environment variable or, failing that, the OS
+//! keychain. Passphrases are refused: the database sits next to the repository,
+//! so a key that can be guessed offline would protect nothing.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Prefix marking an encrypted value; anything else is plaintext.
+pub const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// Environment variable read for the key when `[encryption] key_env` is not set.
+pub const DEFAULT_KEY_ENV: &str = "GIT_REVIEW_KEY";
+
+/// Account the key is stored under in the OS keychain.
+pub const KEYCHAIN_USER: &str = "git-review";
+
+/// Length of a ChaCha20-Poly1305 key in bytes.
+const KEY_LEN: usize = 32;
+
+/// Length of a ChaCha20-Poly1305 nonce in bytes.
+const NONCE_LEN: usize = 12;
+
+/// Errors that can occur while encrypting or decrypting state.
+#[derive(Debug, Error)]
+pub enum EncryptionError {
+    #[error("no encryption key: set {0} or store one in the OS keychain")]
+    NoKey(String),
+    #[error("keychain lookup failed: {0}")]
+    Keychain(String),
+    #[error(
+        "the encryption key must be 32 random bytes in base64, e.g. from `openssl rand -base64 32`"
+    )]
+    BadKey,
+    #[error("cannot decrypt stored value (wrong key?)")]
+    Decrypt,
+    #[error("stored value is encrypted but no key is configured")]
+    Locked,
+}
+
+pub type Result<T> = std::result::Result<T, EncryptionError>;
+
+/// `[encryption]` section of `.git-review.toml`. Off by default.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EncryptionConfig {
+    /// Encrypt comment bodies and AI summaries in the review database.
+    pub enabled: bool,
+    /// Environment variable holding the key (default `GIT_REVIEW_KEY`).
+    pub key_env: Option<String>,
+    /// Keychain service the key is stored under, tried when the variable is unset.
+    pub keychain_service: Option<String>,
+}
+
+impl EncryptionConfig {
+    /// Find the key, or `None` when encryption is disabled.
+    pub fn cipher(&self) -> Result<Option<Cipher>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+        let var = self.key_env.as_deref().unwrap_or(DEFAULT_KEY_ENV);
+        if let Ok(key) = std::env::var(var)
+            && !key.is_empty()
+        {
+            return Cipher::new(&key).map(Some);
+        }
+        match &self.keychain_service {
+            Some(service) => Cipher::new(&keychain_lookup(service)?).map(Some),
+            None => Err(EncryptionError::NoKey(var.to_string())),
+        }
+    }
+}
+
+/// Look up the key stored for `service` under [`KEYCHAIN_USER`] in the OS
+/// keychain (Keychain on macOS, Credential Manager on Windows, the Secret
+/// Service on Linux).
+fn keychain_lookup(service: &str) -> Result<String> {
+    let entry = keyring::Entry::new(service, KEYCHAIN_USER)
+        .map_err(|e| EncryptionError::Keychain(e.to_string()))?;
+    match entry.get_password() {
+        Ok(key) if !key.trim().is_empty() => Ok(key),
+        Ok(_) | Err(keyring::Error::NoEntry) => Err(EncryptionError::Keychain(format!(
+            "no secret for service {:?}",
+            service
+        ))),
+        Err(e) => Err(EncryptionError::Keychain(e.to_string())),
+    }
+}
+
+/// Seals and opens stored text.
+#[derive(Clone)]
+pub struct Cipher {
+    aead: ChaCha20Poly1305,
+}
+
+impl std::fmt::Debug for Cipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Cipher(..)")
+    }
+}
+
+impl Cipher {
+    /// A cipher for a key of 32 random bytes in base64, as `openssl rand
+    /// -base64 32` prints.
+    pub fn new(key: &str) -> Result<Self> {
+        let bytes = BASE64
+            .decode(key.trim())
+            .ok()
+            .filter(|bytes| bytes.len() == KEY_LEN)
+            .ok_or(EncryptionError::BadKey)?;
+        Ok(Self {
+            aead: ChaCha20Poly1305::new(Key::from_slice(&bytes)),
+        })
+    }
+
+    /// Encrypt `plaintext` under a fresh nonce, returning a prefixed base64 string.
+    pub fn encrypt(&self, plaintext: &str) -> String {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let sealed = self
+            .aead
+            .encrypt(&nonce, plaintext.as_bytes())
+            .expect("encrypting an in-memory buffer cannot fail");
+        let mut bytes = nonce.to_vec();
+        bytes.extend(sealed);
+        format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(bytes))
+    }
+
+    /// Decrypt a value produced by [`Cipher::encrypt`]. Plaintext passes through.
+    pub fn decrypt(&self, stored: &str) -> Result<String> {
+        let Some(encoded) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(stored.to_string());
+        };
+        let bytes = BASE64
+            .decode(encoded)
+            .map_err(|_| EncryptionError::Decrypt)?;
+        if bytes.len() < NONCE_LEN {
+            return Err(EncryptionError::Decrypt);
+        }
+        let (nonce, sealed) = bytes.split_at(NONCE_LEN);
+        let plaintext = self
+            .aead
+            .decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|_| EncryptionError::Decrypt)?;
+        String::from_utf8(plaintext).map_err(|_| EncryptionError::Decrypt)
+    }
+}
+
+/// Whether a stored value was written by [`Cipher::encrypt`].
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(ENCRYPTED_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A valid key: 32 bytes in base64.
+    fn key(byte: u8) -> String {
+        BASE64.encode([byte; KEY_LEN])
+    }
+
+    #[test]
+    fn round_trip_and_plaintext_passthrough() {
+        let cipher = Cipher::new(&key(1)).unwrap();
+        let sealed = cipher.encrypt("leaks the secret sauce");
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.contains("secret"));
+        assert_ne!(sealed, cipher.encrypt("leaks the secret sauce"));
+        assert_eq!(cipher.decrypt(&sealed).unwrap(), "leaks the secret sauce");
+
+        assert_eq!(cipher.decrypt("old comment").unwrap(), "old comment");
+        assert!(matches!(
+            Cipher::new(&key(2)).unwrap().decrypt(&sealed),
+            Err(EncryptionError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn passphrases_are_not_keys() {
+        for key in ["correct horse battery staple", "", &BASE64.encode([7; 16])] {
+            assert!(matches!(Cipher::new(key), Err(EncryptionError::BadKey)));
+        }
+    }
+
+    #[test]
+    fn disabled_config_has_no_cipher() {
+        let config: EncryptionConfig = toml::from_str("key_env = \"UNUSED\"").unwrap();
+        assert!(config.cipher().unwrap().is_none());
+
+        let config: EncryptionConfig =
+            toml::from_str("enabled = true\nkey_env = \"GIT_REVIEW_TEST_KEY_UNSET\"").unwrap();
+        assert!(matches!(config.cipher(), Err(EncryptionError::NoKey(_))));
+    }
+}
@@ -0,0 +1,232 @@
+//! Exit codes and error reports of the `git-review` command.
+//!
+//! Every failure maps to one documented exit code, so hooks and wrappers can
+//! tell a failed review check from a broken repository or a typo in the
+//! arguments. With `--error-format json` the error is also written to stderr
+//! as one JSON object.
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0 | Success |
+//! | 1 | A check failed (gate, `ci-check`, `verify`, `commit`) |
+//! | 2 | Invalid arguments or options |
+//! | 3 | Git failed or this is not a git repository |
+//! | 4 | The review database could not be read or written |
+//! | 5 | `.git-review.toml` or `.git-review.local.toml` is invalid |
+//! | 6 | Any other error |
+
+use crate::config::ConfigError;
+use crate::dashboard::PruneError;
+use crate::git::GitError;
+use crate::hosting::HostingError;
+use crate::queue::QueueError;
+use crate::session::SessionError;
+use crate::state::StateError;
+use serde_json::json;
+use std::error::Error;
+use thiserror::Error;
+
+/// Failures the command reports itself rather than through a library error.
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// A check did not pass. The command has already printed why, so only
+    /// the JSON error format repeats it.
+    #[error("{0}")]
+    CheckFailed(String),
+    /// The arguments make no sense together.
+    #[error("{0}")]
+    Usage(String),
+}
+
+/// What kind of failure ended the command, which decides its exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    CheckFailed,
+    Usage,
+    Git,
+    State,
+    Config,
+    Other,
+}
+
+impl FailureKind {
+    /// Process exit code of this kind of failure.
+    pub fn code(self) -> u8 {
+        match self {
+            Self::CheckFailed => 1,
+            Self::Usage => 2,
+            Self::Git => 3,
+            Self::State => 4,
+            Self::Config => 5,
+            Self::Other => 6,
+        }
+    }
+
+    /// Name used in JSON error reports.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::CheckFailed => "check_failed",
+            Self::Usage => "usage",
+            Self::Git => "git",
+            Self::State => "state",
+            Self::Config => "config",
+            Self::Other => "other",
+        }
+    }
+
+    /// Classify an error by the first cause in its chain this module knows.
+    pub fn of(error: &anyhow::Error) -> Self {
+        error.chain().find_map(known_kind).unwrap_or(Self::Other)
+    }
+}
+
+/// Kind of one error in a chain, if it is one of the library's error types.
+///
+/// `#[error(transparent)]` wrappers hide the error they wrap from the chain,
+/// so they are unpacked here.
+fn known_kind(error: &(dyn Error + 'static)) -> Option<FailureKind> {
+    if let Some(e) = error.downcast_ref::<CliError>() {
+        return Some(match e {
+            CliError::CheckFailed(_) => FailureKind::CheckFailed,
+            CliError::Usage(_) => FailureKind::Usage,
+        });
+    }
+    if error.is::<GitError>() {
+        return Some(FailureKind::Git);
+    }
+    if error.is::<StateError>() {
+        return Some(FailureKind::State);
+    }
+    if error.is::<ConfigError>() {
+        return Some(FailureKind::Config);
+    }
+    if let Some(e) = error.downcast_ref::<SessionError>() {
+        return match e {
+            SessionError::Git(_) => Some(FailureKind::Git),
+            SessionError::State(_) => Some(FailureKind::State),
+            SessionError::Config(_) => Some(FailureKind::Config),
+            _ => None,
+        };
+    }
+    if let Some(HostingError::Git(_)) = error.downcast_ref::<HostingError>() {
+        return Some(FailureKind::Git);
+    }
+    if let Some(e) = error.downcast_ref::<PruneError>() {
+        return Some(prune_kind(e));
+    }
+    if let Some(e) = error.downcast_ref::<QueueError>() {
+        return Some(match e {
+            QueueError::Git(_) | QueueError::NotOnBase(_) | QueueError::DirtyWorktree => {
+                FailureKind::Git
+            }
+            QueueError::State(_) => FailureKind::State,
+            QueueError::Prune(e) => prune_kind(e),
+        });
+    }
+    None
+}
+
+fn prune_kind(error: &PruneError) -> FailureKind {
+    match error {
+        PruneError::Git(_) => FailureKind::Git,
+        PruneError::State(_) => FailureKind::State,
+    }
+}
+
+/// How errors are written to stderr (`--error-format`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    /// `Error: <message>`, followed by its causes
+    #[default]
+    Human,
+    /// `{"error": {"kind", "code", "message", "causes"}}` on one line
+    Json,
+}
+
+/// One-line JSON error report.
+pub fn json_report(kind: FailureKind, message: &str, causes: &[String]) -> String {
+    json!({
+        "error": {
+            "kind": kind.name(),
+            "code": kind.code(),
+            "message": message,
+            "causes": causes,
+        }
+    })
+    .to_string()
+}
+
+/// Write `error` to stderr in `format` and return its exit code.
+pub fn report(error: &anyhow::Error, format: ErrorFormat) -> u8 {
+    let kind = FailureKind::of(error);
+    match format {
+        ErrorFormat::Json => {
+            let causes: Vec<String> = error.chain().skip(1).map(|e| e.to_string()).collect();
+            eprintln!("{}", json_report(kind, &error.to_string(), &causes));
+        }
+        // The command has explained the failed check already
+        ErrorFormat::Human if kind == FailureKind::CheckFailed => {}
+        ErrorFormat::Human => eprintln!("Error: {:?}", error),
+    }
+    kind.code()
+}
+
+/// Whether raw command-line arguments ask for JSON errors, for reporting
+/// errors in the arguments themselves before they are parsed.
+pub fn json_requested<I: IntoIterator<Item = String>>(args: I) -> bool {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == "--error-format=json"
+            || (arg == "--error-format" && args.next().as_deref() == Some("json"))
+        {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn errors_are_classified_through_context_and_wrappers() {
+        let git = anyhow::Error::from(GitError::NotARepo).context("Not in a git repository");
+        assert_eq!(FailureKind::of(&git).code(), 3);
+
+        let session: anyhow::Result<()> = Err(SessionError::Config(ConfigError::Invalid(
+            "bad glob".to_string(),
+        )))
+        .context("Failed to open review session");
+        assert_eq!(FailureKind::of(&session.unwrap_err()), FailureKind::Config);
+
+        let usage = anyhow::Error::from(CliError::Usage("pick one".to_string()));
+        assert_eq!(FailureKind::of(&usage).code(), 2);
+        let gate = anyhow::Error::from(CliError::CheckFailed("gate".to_string()));
+        assert_eq!(FailureKind::of(&gate).code(), 1);
+        assert_eq!(FailureKind::of(&anyhow::anyhow!("boom")).code(), 6);
+    }
+
+    #[test]
+    fn json_reports_carry_kind_code_and_causes() {
+        let report = json_report(
+            FailureKind::Git,
+            "Not in a git repository",
+            &["not in a git repository".to_string()],
+        );
+        let value: serde_json::Value = serde_json::from_str(&report).unwrap();
+        assert_eq!(value["error"]["kind"], "git");
+        assert_eq!(value["error"]["code"], 3);
+        assert_eq!(value["error"]["causes"][0], "not in a git repository");
+
+        let args = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+        assert!(json_requested(args(
+            "git-review --error-format json status"
+        )));
+        assert!(json_requested(args(
+            "git-review gate check --error-format=json"
+        )));
+        assert!(!json_requested(args("git-review --error-format human")));
+    }
+}
@@ -0,0 +1,163 @@
+//! Restricting a review to some kinds of files (`--only` / `--exclude`).
+//!
+//! Files are picked by extension, so code can be reviewed before config and
+//! docs. The filter only narrows what is shown and counted: the review state
+//! of the whole diff is kept, so filtered-out hunks are not marked stale.
+
+use crate::state::HunkRecord;
+use crate::{HunkStatus, ReviewProgress};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+/// Which files of a diff to review, by extension.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileFilter {
+    /// Extensions to keep; empty keeps everything not excluded.
+    only: Vec<String>,
+    /// Extensions to leave out.
+    exclude: Vec<String>,
+}
+
+impl FileFilter {
+    /// Build a filter from `--only` and `--exclude` values, each a list of
+    /// extensions separated by commas (`rs,toml` or `.rs, .toml`).
+    pub fn new<S: AsRef<str>>(only: &[S], exclude: &[S]) -> Self {
+        Self {
+            only: parse_list(only),
+            exclude: parse_list(exclude),
+        }
+    }
+
+    /// Whether the filter keeps every file.
+    pub fn is_empty(&self) -> bool {
+        self.only.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Whether `path` is kept. Files without an extension are matched by
+    /// their whole name, so `Makefile` or `Dockerfile` can be listed too.
+    pub fn matches(&self, path: &Path) -> bool {
+        let kind = path
+            .extension()
+            .or_else(|| path.file_name())
+            .map(|kind| kind.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        (self.only.is_empty() || self.only.contains(&kind)) && !self.exclude.contains(&kind)
+    }
+
+    /// Progress over the stored hunks of the files this filter keeps.
+    pub fn progress(&self, records: &[HunkRecord]) -> ReviewProgress {
+        let mut progress = ReviewProgress {
+            total_hunks: 0,
+            reviewed: 0,
+            unreviewed: 0,
+            stale: 0,
+            needs_changes: 0,
+            skimmed: 0,
+            auto_approved: 0,
+            files_remaining: 0,
+            total_files: 0,
+        };
+        let mut files = HashSet::new();
+        let mut remaining = HashSet::new();
+        for record in records {
+            if !self.matches(Path::new(&record.file_path)) {
+                continue;
+            }
+            progress.total_hunks += 1;
+            files.insert(record.file_path.as_str());
+            match record.status {
+                HunkStatus::Reviewed => {
+                    progress.reviewed += 1;
+                    if record.auto_rule.is_some() {
+                        progress.auto_approved += 1;
+                    }
+                }
+                HunkStatus::Unreviewed => progress.unreviewed += 1,
+                HunkStatus::Stale => progress.stale += 1,
+                HunkStatus::NeedsChanges => progress.needs_changes += 1,
+                HunkStatus::Skimmed => progress.skimmed += 1,
+            }
+            if record.status != HunkStatus::Reviewed {
+                remaining.insert(record.file_path.as_str());
+            }
+        }
+        progress.total_files = files.len();
+        progress.files_remaining = remaining.len();
+        progress
+    }
+}
+
+impl fmt::Display for FileFilter {
+    /// Short description for status lines, e.g. `only rs, toml; not md`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if !self.only.is_empty() {
+            parts.push(format!("only {}", self.only.join(", ")));
+        }
+        if !self.exclude.is_empty() {
+            parts.push(format!("not {}", self.exclude.join(", ")));
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Split comma-separated extension lists into lowercase extensions without dots.
+fn parse_list<S: AsRef<str>>(values: &[S]) -> Vec<String> {
+    values
+        .iter()
+        .flat_map(|value| value.as_ref().split(','))
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_listed_extensions_minus_excluded_ones() {
+        let filter = FileFilter::new(&["rs, .TOML", "Makefile"], &["toml"]);
+        assert!(filter.matches(Path::new("src/main.rs")));
+        assert!(filter.matches(Path::new("Makefile")));
+        assert!(!filter.matches(Path::new("Cargo.toml")));
+        assert!(!filter.matches(Path::new("README.md")));
+        assert_eq!(filter.to_string(), "only rs, toml, makefile; not toml");
+
+        let docs_later = FileFilter::new(&[], &["md"]);
+        assert!(docs_later.matches(Path::new("src/lib.rs")));
+        assert!(!docs_later.matches(Path::new("docs/guide.MD")));
+        assert!(FileFilter::new::<&str>(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn progress_counts_only_kept_files() {
+        let record = |path: &str, status| HunkRecord {
+            file_path: path.to_string(),
+            content_hash: String::new(),
+            status,
+            reviewed_at: None,
+            reviewed_by: None,
+            auto_rule: None,
+        };
+        let records = [
+            record("a.rs", HunkStatus::Reviewed),
+            record("a.rs", HunkStatus::Unreviewed),
+            record("b.rs", HunkStatus::Reviewed),
+            record("c.md", HunkStatus::Stale),
+        ];
+        let progress = FileFilter::new(&["rs"], &[]).progress(&records);
+        assert_eq!(
+            (
+                progress.total_hunks,
+                progress.reviewed,
+                progress.unreviewed,
+                progress.stale,
+                progress.files_remaining,
+                progress.total_files
+            ),
+            (3, 2, 1, 0, 1, 2)
+        );
+    }
+}
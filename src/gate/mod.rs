@@ -1,46 +1,416 @@
+use crate::HunkStatus;
 use crate::state::ReviewDb;
 use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 const HOOK_MARKER: &str = "# Installed by git-review";
-const HOOK_CONTENT: &str = "#!/bin/sh
-# Installed by git-review
-exec git-review gate check
-";
 
-/// Check whether all hunks have been reviewed (gate passes).
+/// The empty tree, which amending a root commit is diffed against.
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// Git hooks that git-review can install.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum HookKind {
+    /// Block commits with unreviewed hunks.
+    #[default]
+    PreCommit,
+    /// Warn before a rebase invalidates review state, and offer to carry it over.
+    PreRebase,
+}
+
+impl HookKind {
+    /// Every hook kind, in the order they are listed.
+    pub const ALL: [HookKind; 2] = [HookKind::PreCommit, HookKind::PreRebase];
+
+    /// File name of the hook in the hooks directory.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "pre-commit",
+            HookKind::PreRebase => "pre-rebase",
+        }
+    }
+}
+
+/// Options controlling which hook is installed and how it invokes the gate.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HookOptions {
+    /// Which hook to install.
+    pub hook: HookKind,
+    /// Also require assigned files to be reviewed by their assignee.
+    pub require_assignee: bool,
+}
+
+/// Build the hook script for the given options.
 ///
-/// Returns `true` if all hunks for the given base ref are reviewed.
-/// Returns `false` if any hunks are unreviewed or stale.
-pub fn check_gate(db: &ReviewDb, base_ref: &str) -> Result<bool> {
-    let progress = db.progress(base_ref)?;
+/// Git runs hooks through `sh` on every platform (Git for Windows bundles
+/// one), so the hook is always a shell script; `program` is how it invokes
+/// git-review.
+///
+/// With `chained`, the hook first runs the hook it replaced (kept as
+/// `<hook>.backup` next to it) with the same arguments, and stops with its
+/// exit code if it fails. Like git itself, it skips that hook when it isn't
+/// executable.
+fn hook_content(options: HookOptions, program: &str, chained: bool) -> String {
+    let command = match options.hook {
+        HookKind::PreCommit => {
+            let mut command = format!("{} gate check", program);
+            if options.require_assignee {
+                command.push_str(" --require-assignee");
+            }
+            command
+        }
+        // git passes the upstream and (optionally) the branch being rebased
+        HookKind::PreRebase => format!("{} gate pre-rebase \"$@\"", program),
+    };
+    let chain = if chained {
+        format!(
+            "original=\"$(dirname \"$0\")/{}.backup\"\nif [ -x \"$original\" ]; then\n  \"$original\" \"$@\" || exit $?\nfi\n",
+            options.hook.file_name()
+        )
+    } else {
+        String::new()
+    };
+    format!("#!/bin/sh\n{}\n{}exec {}\n", HOOK_MARKER, chain, command)
+}
 
-    // Gate passes only if all hunks are reviewed (no unreviewed or stale hunks)
-    Ok(progress.unreviewed == 0 && progress.stale == 0)
+/// What is installed as one kind of hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookState {
+    /// No hook of this kind.
+    Missing,
+    /// A hook git-review didn't write.
+    Foreign,
+    /// git-review's hook.
+    Installed {
+        /// Runs the hook it replaced first.
+        chained: bool,
+        /// Requires assignee reviews (`--require-assignee`).
+        require_assignee: bool,
+    },
+}
+
+/// Inspect the hook of the given kind in the repository's hooks directory.
+pub fn hook_state(repo_root: &Path, hook: HookKind) -> Result<HookState> {
+    let path = crate::git::hooks_dir(repo_root)?.join(hook.file_name());
+    if !path.exists() {
+        return Ok(HookState::Missing);
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {} hook", hook.file_name()))?;
+    if !content.contains(HOOK_MARKER) {
+        return Ok(HookState::Foreign);
+    }
+    Ok(HookState::Installed {
+        chained: content.contains(&format!("{}.backup", hook.file_name())),
+        require_assignee: content.contains("--require-assignee"),
+    })
+}
+
+/// Whether the hook at `path` was written by git-review.
+fn is_own_hook(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(HOOK_MARKER))
+}
+
+/// How the hook invokes git-review on this platform.
+///
+/// On Windows, hooks often run with a different `PATH` than the shell that
+/// installed them (GUI clients, IDEs), so when git-review itself installs the
+/// hook it calls its own executable by full path instead of relying on lookup.
+/// Hooks in a tracked directory (a shared `core.hooksPath`) are checked in for
+/// everyone, so they look git-review up on `PATH` rather than embed one
+/// machine's path.
+fn hook_program(repo_root: &Path, hooks_dir: &Path) -> String {
+    if cfg!(windows)
+        && !hooks_dir.strip_prefix(repo_root).is_ok_and(|dir| {
+            crate::git::is_tracked(repo_root, &dir.to_string_lossy()).unwrap_or(false)
+        })
+        && let Ok(exe) = std::env::current_exe()
+        && exe.file_stem().is_some_and(|stem| stem == "git-review")
+    {
+        return sh_path(&exe);
+    }
+    String::from("git-review")
+}
+
+/// Quote a native path for `sh`, with forward slashes (`C:/Users/...`), which
+/// Git for Windows' shell accepts.
+fn sh_path(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    format!("'{}'", path.replace('\'', "'\\''"))
+}
+
+/// Policy the gate evaluates review progress against (`[gate]` in `.git-review.toml`).
+///
+/// The default policy requires every hunk to be reviewed and none to be stale.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GatePolicy {
+    /// Minimum percentage of hunks that must be reviewed (0-100).
+    pub threshold: u8,
+    /// Ignore stale hunks instead of failing the gate on them.
+    pub allow_stale: bool,
+    /// Path globs that always require every hunk to be reviewed.
+    pub strict: Vec<String>,
+    /// Path globs the gate ignores entirely.
+    pub lenient: Vec<String>,
+    /// Path globs where a skimmed hunk counts as reviewed. Elsewhere skimmed
+    /// hunks still need a full review; `strict` wins over `skim`.
+    pub skim: Vec<String>,
+    /// Approving all of a diff with more unreviewed hunks than this in the
+    /// TUI asks for the hunk count (or "yes") to be typed instead of `y`.
+    pub typed_confirm_over: usize,
+}
+
+impl Default for GatePolicy {
+    fn default() -> Self {
+        Self {
+            threshold: 100,
+            allow_stale: false,
+            strict: Vec::new(),
+            lenient: Vec::new(),
+            skim: Vec::new(),
+            typed_confirm_over: 200,
+        }
+    }
+}
+
+/// Compile a list of glob patterns into a matcher.
+pub fn build_glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).with_context(|| format!("Invalid glob: {}", pattern))?);
+    }
+    builder.build().context("Failed to build glob set")
+}
+
+/// Why a hunk keeps the gate closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateFailure {
+    /// Flagged as needing changes (fails even in lenient paths).
+    NeedsChanges,
+    /// Stale, and the policy doesn't allow stale hunks.
+    Stale,
+    /// Unreviewed in a strict path.
+    UnreviewedStrict,
+    /// Unreviewed while the reviewed percentage is below the threshold.
+    Unreviewed,
+    /// Only skimmed outside the policy's `skim` paths, in a strict path or
+    /// while the reviewed percentage is below the threshold.
+    Skimmed,
+}
+
+impl GateFailure {
+    pub fn label(self) -> &'static str {
+        match self {
+            GateFailure::NeedsChanges => "needs changes",
+            GateFailure::Stale => "stale",
+            GateFailure::UnreviewedStrict => "unreviewed (strict path)",
+            GateFailure::Unreviewed => "unreviewed",
+            GateFailure::Skimmed => "skimmed, needs full review",
+        }
+    }
+}
+
+/// A hunk that keeps the gate closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GateOffender {
+    pub file_path: String,
+    pub content_hash: String,
+    pub reason: GateFailure,
+}
+
+/// Outcome of evaluating review progress against a gate policy.
+#[derive(Debug, Clone, Default)]
+pub struct GateReport {
+    /// Reviewed hunks counted towards the threshold.
+    pub reviewed: usize,
+    /// Hunks counted towards the threshold (outside strict and lenient paths).
+    pub considered: usize,
+    /// Every hunk that keeps the gate closed, in database order.
+    pub offenders: Vec<GateOffender>,
+}
+
+impl GateReport {
+    /// Whether the gate passes.
+    pub fn passes(&self) -> bool {
+        self.offenders.is_empty()
+    }
+}
+
+/// Evaluate review progress against the gate policy, collecting every hunk
+/// that keeps the gate closed.
+///
+/// Hunks in `strict` paths must all be reviewed; hunks in `lenient` paths are
+/// ignored. For the remaining hunks, at least `policy.threshold` percent must be
+/// reviewed. Unless `policy.allow_stale` is set, any stale hunk outside lenient
+/// paths fails the gate; allowed stale hunks are excluded from the percentage.
+/// Skimmed hunks count as reviewed in `skim` paths and as unreviewed elsewhere.
+pub fn evaluate_gate(db: &ReviewDb, base_ref: &str, policy: &GatePolicy) -> Result<GateReport> {
+    let strict = build_glob_set(&policy.strict)?;
+    let lenient = build_glob_set(&policy.lenient)?;
+    let skim = build_glob_set(&policy.skim)?;
+
+    let mut report = GateReport::default();
+    let mut unreviewed = Vec::new();
+    let offender = |hunk: &crate::state::HunkRecord, reason| GateOffender {
+        file_path: hunk.file_path.clone(),
+        content_hash: hunk.content_hash.clone(),
+        reason,
+    };
+
+    for hunk in db.hunks(base_ref)? {
+        let is_strict = strict.is_match(&hunk.file_path);
+        // A "needs changes" flag fails the gate even in lenient paths
+        if !is_strict
+            && lenient.is_match(&hunk.file_path)
+            && hunk.status != HunkStatus::NeedsChanges
+        {
+            continue;
+        }
+
+        match hunk.status {
+            HunkStatus::Stale if policy.allow_stale => {}
+            HunkStatus::Stale => report.offenders.push(offender(&hunk, GateFailure::Stale)),
+            HunkStatus::Unreviewed if is_strict => report
+                .offenders
+                .push(offender(&hunk, GateFailure::UnreviewedStrict)),
+            HunkStatus::Reviewed if is_strict => {}
+            HunkStatus::Skimmed if is_strict => {
+                report.offenders.push(offender(&hunk, GateFailure::Skimmed))
+            }
+            HunkStatus::Skimmed if !skim.is_match(&hunk.file_path) => {
+                report.considered += 1;
+                unreviewed.push(offender(&hunk, GateFailure::Skimmed));
+            }
+            HunkStatus::Reviewed | HunkStatus::Skimmed => {
+                report.reviewed += 1;
+                report.considered += 1;
+            }
+            HunkStatus::Unreviewed => {
+                report.considered += 1;
+                unreviewed.push(offender(&hunk, GateFailure::Unreviewed));
+            }
+            HunkStatus::NeedsChanges => report
+                .offenders
+                .push(offender(&hunk, GateFailure::NeedsChanges)),
+        }
+    }
+
+    if report.reviewed * 100 < usize::from(policy.threshold) * report.considered {
+        report.offenders.extend(unreviewed);
+    }
+    Ok(report)
+}
+
+/// Check whether review progress satisfies the gate policy (gate passes).
+///
+/// See [`evaluate_gate`] for the rules, and for which hunks fail them.
+pub fn check_gate(db: &ReviewDb, base_ref: &str, policy: &GatePolicy) -> Result<bool> {
+    Ok(evaluate_gate(db, base_ref, policy)?.passes())
+}
+
+/// Whether `git commit` arguments amend HEAD: `--amend`, or a prefix of it
+/// git accepts (`--amen`), not undone by a later `--no-amend`. Arguments after
+/// `--` are pathspecs.
+pub fn amends(git_args: &[String]) -> bool {
+    let mut amend = false;
+    for arg in git_args.iter().take_while(|arg| *arg != "--") {
+        if arg.len() >= "--am".len() && "--amend".starts_with(arg.as_str()) {
+            amend = true;
+        } else if arg == "--no-amend" {
+            amend = false;
+        }
+    }
+    amend
+}
+
+/// The diff `git-review commit` gates. A plain commit adds the changes to
+/// HEAD (`HEAD`), but an amended one replaces HEAD, so it is the changes
+/// since HEAD's parent: the commit being amended plus the new changes
+/// (`HEAD^`, or the empty tree when amending a root commit).
+pub fn commit_gate_range(git_args: &[String]) -> String {
+    if !amends(git_args) {
+        "HEAD".to_string()
+    } else if crate::git::resolve_ref("HEAD^").is_ok() {
+        "HEAD^".to_string()
+    } else {
+        EMPTY_TREE.to_string()
+    }
+}
+
+/// Check whether every hunk in an assigned file was reviewed by its assignee.
+///
+/// Files without an assignee are not considered. Use together with `check_gate`
+/// when the gate runs in assignee mode.
+pub fn check_assignee_gate(db: &ReviewDb, base_ref: &str) -> Result<bool> {
+    Ok(unsatisfied_assignments(db, base_ref)?.is_empty())
+}
+
+/// Hunks of `base_ref` in assigned files that their assignee has not reviewed,
+/// as `(file_path, content_hash, assignee)`.
+///
+/// Files are assigned on a branch's range (`git-review assign main..HEAD`),
+/// so besides the assignments stored under `base_ref` itself, those on every
+/// range of the checked-out branch apply: the staged changes the commit gate
+/// checks are headed for that branch.
+pub fn unsatisfied_assignments(
+    db: &ReviewDb,
+    base_ref: &str,
+) -> Result<Vec<(String, String, String)>> {
+    let mut assigned_in = vec![base_ref.to_string()];
+    if let Ok(Some(branch)) = crate::git::get_current_branch() {
+        assigned_in.extend(db.assignment_refs_in_namespace(&branch)?);
+    }
+    Ok(db.unsatisfied_assignments_from(base_ref, &assigned_in)?)
 }
 
 /// Install the pre-commit hook that enforces review gating.
 ///
-/// If a pre-commit hook already exists, it is backed up to `.git/hooks/pre-commit.backup`.
-/// The new hook will execute `git-review gate check` to enforce the review gate.
+/// The hook goes into `core.hooksPath` if configured, else `.git/hooks`. If a
+/// pre-commit hook already exists, it is kept as `pre-commit.backup` next to
+/// it, and the new hook runs it before `git-review gate check`, so existing
+/// lint or format checks keep working alongside the gate.
 pub fn enable_gate(repo_root: &Path) -> Result<()> {
-    let hooks_dir = repo_root.join(".git/hooks");
-    let hook_path = hooks_dir.join("pre-commit");
-    let backup_path = hooks_dir.join("pre-commit.backup");
+    enable_gate_with_options(repo_root, HookOptions::default())
+}
+
+/// Install a hook with explicit options (see `enable_gate`).
+///
+/// An existing hook of the same kind is kept as `<hook>.backup` and chained.
+/// Re-installing over git-review's own hook keeps the chain it already had.
+pub fn enable_gate_with_options(repo_root: &Path, options: HookOptions) -> Result<()> {
+    let hooks_dir = crate::git::hooks_dir(repo_root)?;
+    let name = options.hook.file_name();
+    let hook_path = hooks_dir.join(name);
+    let backup_path = hooks_dir.join(format!("{}.backup", name));
 
     // Ensure hooks directory exists
-    fs::create_dir_all(&hooks_dir).context("Failed to create .git/hooks directory")?;
+    fs::create_dir_all(&hooks_dir)
+        .with_context(|| format!("Failed to create {}", hooks_dir.display()))?;
 
-    // Backup existing hook if present
-    if hook_path.exists() {
-        fs::copy(&hook_path, &backup_path).context("Failed to backup existing pre-commit hook")?;
-    }
+    // Keep a foreign hook to chain; never chain to our own hook, which would
+    // run the gate twice (or, as the backup, call itself)
+    let chained = if !hook_path.exists() {
+        false
+    } else if is_own_hook(&hook_path) {
+        backup_path.exists() && !is_own_hook(&backup_path)
+    } else {
+        fs::copy(&hook_path, &backup_path)
+            .with_context(|| format!("Failed to backup existing {} hook", name))?;
+        true
+    };
 
     // Write the new hook
-    fs::write(&hook_path, HOOK_CONTENT).context("Failed to write pre-commit hook")?;
+    fs::write(
+        &hook_path,
+        hook_content(options, &hook_program(repo_root, &hooks_dir), chained),
+    )
+    .with_context(|| format!("Failed to write {} hook", name))?;
 
-    // Make the hook executable (Unix only)
+    // Make the hook executable (Unix only; Git for Windows runs hooks regardless)
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
@@ -55,22 +425,86 @@ pub fn enable_gate(repo_root: &Path) -> Result<()> {
 /// Remove the pre-commit hook.
 ///
 /// Only removes the hook if it contains the git-review marker comment.
-/// This prevents accidentally removing user-created hooks.
+/// This prevents accidentally removing user-created hooks. A hook the gate
+/// chained to is put back in place.
 pub fn disable_gate(repo_root: &Path) -> Result<()> {
-    let hook_path = repo_root.join(".git/hooks/pre-commit");
+    disable_hook(repo_root, HookKind::PreCommit)
+}
+
+/// What disabling a hook would do to the hooks directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookRemoval {
+    /// No hook of this kind is installed.
+    Missing,
+    /// The installed hook isn't git-review's, so it is left alone.
+    Foreign(PathBuf),
+    /// git-review's hook is deleted.
+    Remove(PathBuf),
+    /// git-review's hook is replaced by the hook it chained to.
+    Restore { backup: PathBuf, hook: PathBuf },
+}
+
+/// Work out what [`disable_hook`] would do, without changing anything.
+pub fn plan_disable_hook(repo_root: &Path, hook: HookKind) -> Result<HookRemoval> {
+    let hooks_dir = crate::git::hooks_dir(repo_root)?;
+    let hook_path = hooks_dir.join(hook.file_name());
+    let backup_name = format!("{}.backup", hook.file_name());
 
-    // Check if hook exists
     if !hook_path.exists() {
-        return Ok(()); // Nothing to do
+        return Ok(HookRemoval::Missing);
     }
 
-    // Read hook content
-    let content = fs::read_to_string(&hook_path).context("Failed to read pre-commit hook")?;
+    let content = fs::read_to_string(&hook_path)
+        .with_context(|| format!("Failed to read {} hook", hook.file_name()))?;
 
     // Only remove if it has our marker
-    if content.contains(HOOK_MARKER) {
-        fs::remove_file(&hook_path).context("Failed to remove pre-commit hook")?;
+    if !content.contains(HOOK_MARKER) {
+        return Ok(HookRemoval::Foreign(hook_path));
     }
+    let backup_path = hooks_dir.join(&backup_name);
+    if content.contains(&backup_name) && backup_path.exists() && !is_own_hook(&backup_path) {
+        Ok(HookRemoval::Restore {
+            backup: backup_path,
+            hook: hook_path,
+        })
+    } else {
+        Ok(HookRemoval::Remove(hook_path))
+    }
+}
 
+/// Remove a git-review hook of the given kind (see `disable_gate`).
+pub fn disable_hook(repo_root: &Path, hook: HookKind) -> Result<()> {
+    match plan_disable_hook(repo_root, hook)? {
+        HookRemoval::Missing | HookRemoval::Foreign(_) => {}
+        HookRemoval::Restore {
+            backup,
+            hook: hook_path,
+        } => fs::rename(&backup, &hook_path)
+            .with_context(|| format!("Failed to restore original {} hook", hook.file_name()))?,
+        HookRemoval::Remove(hook_path) => fs::remove_file(&hook_path)
+            .with_context(|| format!("Failed to remove {} hook", hook.file_name()))?,
+    }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_paths_are_quoted_for_sh() {
+        assert_eq!(
+            sh_path(Path::new(r"C:\Users\o'neil\.cargo\bin\git-review.exe")),
+            r"'C:/Users/o'\''neil/.cargo/bin/git-review.exe'"
+        );
+        let hook = hook_content(
+            HookOptions::default(),
+            &sh_path(Path::new(r"C:\bin\git-review.exe")),
+            false,
+        );
+        assert_eq!(
+            hook,
+            "#!/bin/sh\n# Installed by git-review\nexec 'C:/bin/git-review.exe' gate check\n"
+        );
+    }
+}
@@ -0,0 +1,386 @@
+//! Publishing review results to Gerrit (`publish --gerrit`).
+//!
+//! Every commit of the range with a `Change-Id:` trailer is a Gerrit change.
+//! Its hunks are matched to the range's review state by content hash, as in
+//! `notes sync`, so hunks a later commit rewrote are left out. Each change
+//! gets one review through the REST API
+//! (`POST /a/changes/{change-id}/revisions/{commit}/review`): the hunk
+//! comments as file comments on the line each hunk starts at, an unresolved
+//! comment on hunks flagged as needing changes, and a vote on `[gerrit]
+//! label`: `reject` if any hunk needs changes, `approve` once all are reviewed.
+//!
+//! The server, user, and password variable are personal settings (see
+//! [`crate::config::PERSONAL_KEYS`]), so a branch under review can't send the
+//! password somewhere else.
+
+use crate::git::{self, GitError};
+use crate::parser::parse_diff;
+use crate::state::{CommentRecord, ReviewDb, StateError};
+use crate::{DiffFile, HunkStatus};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+use std::time::Duration;
+use thiserror::Error;
+
+/// How long a single request may take before it is given up on.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors that can occur while publishing to Gerrit.
+#[derive(Debug, Error)]
+pub enum GerritError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error(transparent)]
+    State(#[from] StateError),
+    #[error("{0}")]
+    Config(String),
+    #[error("posting the review of {change} failed: {message}")]
+    Request { change: String, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, GerritError>;
+
+/// `[gerrit]` section of `.git-review.toml`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GerritConfig {
+    /// Web address of the server, e.g. `https://review.example.com`.
+    pub url: Option<String>,
+    /// Gerrit username the reviews are posted as.
+    pub user: Option<String>,
+    /// Environment variable holding the user's HTTP password.
+    pub password_env: String,
+    /// Label voted on.
+    pub label: String,
+    /// Vote once every hunk of a change is reviewed (0 to not vote).
+    pub approve: i8,
+    /// Vote when any hunk of a change needs changes (0 to not vote).
+    pub reject: i8,
+}
+
+impl Default for GerritConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            user: None,
+            password_env: "GERRIT_HTTP_PASSWORD".to_string(),
+            label: "Code-Review".to_string(),
+            approve: 1,
+            reject: -1,
+        }
+    }
+}
+
+/// The review to post on one change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeReview {
+    pub change_id: String,
+    pub commit: String,
+    pub subject: String,
+    /// Vote on the configured label, if any.
+    pub vote: Option<i8>,
+    /// Number of file comments.
+    pub comments: usize,
+    /// Gerrit `ReviewInput` to post.
+    pub input: Value,
+}
+
+/// The `Change-Id` of a commit message: the value of its last `Change-Id:` trailer.
+pub fn change_id(message: &str) -> Option<&str> {
+    message
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Change-Id:"))
+        .map(str::trim)
+        .rfind(|id| id.starts_with('I') && id.len() > 1)
+}
+
+/// The reviews to post for the commits of `range`, from the state stored
+/// under `base_ref`, and how many commits were skipped for lacking a
+/// `Change-Id`. Comments by someone other than `me` name their author.
+pub fn reviews(
+    db: &ReviewDb,
+    base_ref: &str,
+    range: &str,
+    config: &GerritConfig,
+    me: Option<&str>,
+) -> Result<(Vec<ChangeReview>, usize)> {
+    let statuses: HashMap<(String, String), HunkStatus> = db
+        .hunks(base_ref)?
+        .into_iter()
+        .filter(|record| record.status != HunkStatus::Stale)
+        .map(|record| ((record.file_path, record.content_hash), record.status))
+        .collect();
+    let comments = db.comments(base_ref)?;
+
+    let mut reviews = Vec::new();
+    let mut skipped = 0;
+    for sha in git::rev_list(range)? {
+        let message = git::commit_message(&sha)?;
+        let Some(id) = change_id(&message) else {
+            skipped += 1;
+            continue;
+        };
+        let files = parse_diff(&git::get_commit_diff(&sha)?);
+        let subject = message.lines().next().unwrap_or("").to_string();
+        if let Some(review) = build_review(
+            config,
+            ChangeReview {
+                change_id: id.to_string(),
+                commit: sha,
+                subject,
+                vote: None,
+                comments: 0,
+                input: Value::Null,
+            },
+            &files,
+            &statuses,
+            &comments,
+            me,
+        ) {
+            reviews.push(review);
+        }
+    }
+    Ok((reviews, skipped))
+}
+
+/// Fill in the review of a change whose commit introduced `files`, or `None`
+/// if none of its hunks are in the review (a merge, or all rewritten later).
+fn build_review(
+    config: &GerritConfig,
+    mut review: ChangeReview,
+    files: &[DiffFile],
+    statuses: &HashMap<(String, String), HunkStatus>,
+    comments: &[CommentRecord],
+    me: Option<&str>,
+) -> Option<ChangeReview> {
+    let mut file_comments = Map::new();
+    let (mut total, mut reviewed, mut needs_changes) = (0, 0, 0);
+    for file in files {
+        let path = file.path.to_string_lossy().to_string();
+        let mut entries = Vec::new();
+        for hunk in &file.hunks {
+            let Some(&status) = statuses.get(&(path.clone(), hunk.content_hash.clone())) else {
+                continue;
+            };
+            total += 1;
+            match status {
+                HunkStatus::Reviewed => reviewed += 1,
+                HunkStatus::NeedsChanges => needs_changes += 1,
+                _ => {}
+            }
+
+            let mut texts: Vec<String> = comments
+                .iter()
+                .filter(|c| c.file_path == path && c.content_hash == hunk.content_hash)
+                .map(|c| match c.author.as_deref() {
+                    Some(author) if Some(author) != me => format!("{}: {}", author, c.body),
+                    _ => c.body.clone(),
+                })
+                .collect();
+            if texts.is_empty() && status == HunkStatus::NeedsChanges {
+                texts.push("Needs changes.".to_string());
+            }
+            // Hunks that only delete lines point at the old side
+            let (side, line) = if hunk.new_count == 0 {
+                ("PARENT", hunk.old_start)
+            } else {
+                ("REVISION", hunk.new_start)
+            };
+            for text in texts {
+                entries.push(json!({
+                    "side": side,
+                    "line": line.max(1),
+                    "message": text,
+                    "unresolved": status != HunkStatus::Reviewed,
+                }));
+            }
+        }
+        if !entries.is_empty() {
+            review.comments += entries.len();
+            file_comments.insert(path, Value::Array(entries));
+        }
+    }
+    if total == 0 {
+        return None;
+    }
+
+    let mut message = format!("Reviewed {} of {} hunks with git-review", reviewed, total);
+    if needs_changes > 0 {
+        message.push_str(&format!("; {} need changes", needs_changes));
+    }
+    message.push('.');
+    review.vote = if needs_changes > 0 {
+        Some(config.reject)
+    } else if reviewed == total {
+        Some(config.approve)
+    } else {
+        None
+    }
+    .filter(|&vote| vote != 0);
+
+    let mut input = json!({ "message": message, "comments": file_comments });
+    if let Some(vote) = review.vote {
+        input["labels"] = json!({ config.label.clone(): vote });
+    }
+    review.input = input;
+    Some(review)
+}
+
+/// Post a review to the server in `config`.
+pub fn publish(config: &GerritConfig, review: &ChangeReview) -> Result<()> {
+    let url = config.url.as_deref().ok_or_else(|| {
+        GerritError::Config("set [gerrit] url in .git-review.local.toml".to_string())
+    })?;
+    let user = config.user.as_deref().ok_or_else(|| {
+        GerritError::Config("set [gerrit] user in .git-review.local.toml".to_string())
+    })?;
+    let password = std::env::var(&config.password_env).map_err(|_| {
+        GerritError::Config(format!(
+            "{} is not set (Gerrit HTTP password)",
+            config.password_env
+        ))
+    })?;
+    let endpoint = format!(
+        "{}/a/changes/{}/revisions/{}/review",
+        url.trim_end_matches('/'),
+        review.change_id,
+        review.commit
+    );
+
+    let credentials = BASE64.encode(format!("{}:{}", user, password));
+    let result = ureq::post(&endpoint)
+        .timeout(REQUEST_TIMEOUT)
+        .set("Authorization", &format!("Basic {}", credentials))
+        .send_json(&review.input);
+    let message = match result {
+        Ok(_) => return Ok(()),
+        // Gerrit explains a rejected review in the response body
+        Err(ureq::Error::Status(code, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            format!("HTTP {}: {}", code, body.trim())
+        }
+        Err(e) => e.to_string(),
+    };
+    Err(GerritError::Request {
+        change: review.change_id.clone(),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -3 +3 @@
+-old();
++new();
+@@ -20,2 +19,0 @@
+-gone();
+-gone();
+diff --git a/src/b.rs b/src/b.rs
+--- a/src/b.rs
++++ b/src/b.rs
+@@ -1 +1 @@
+-x
++y
+";
+
+    fn change() -> ChangeReview {
+        ChangeReview {
+            change_id: "I1234".to_string(),
+            commit: "abc".to_string(),
+            subject: "Subject".to_string(),
+            vote: None,
+            comments: 0,
+            input: Value::Null,
+        }
+    }
+
+    fn comment(
+        files: &[DiffFile],
+        file: usize,
+        hunk: usize,
+        author: &str,
+        body: &str,
+    ) -> CommentRecord {
+        CommentRecord {
+            file_path: files[file].path.to_string_lossy().to_string(),
+            content_hash: files[file].hunks[hunk].content_hash.clone(),
+            author: Some(author.to_string()),
+            body: body.to_string(),
+            created_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn change_id_is_the_last_trailer() {
+        let message = "Fix it\n\nChange-Id: Iold\n\nSigned-off-by: A\nChange-Id: I0123abcd\n";
+        assert_eq!(change_id(message), Some("I0123abcd"));
+        assert_eq!(change_id("Fix it\n\nSee Change-Id: in docs\n"), None);
+    }
+
+    #[test]
+    fn comments_and_votes_follow_hunk_statuses() {
+        let files = parse_diff(DIFF);
+        let key = |file: usize, hunk: usize| {
+            (
+                files[file].path.to_string_lossy().to_string(),
+                files[file].hunks[hunk].content_hash.clone(),
+            )
+        };
+        let config = GerritConfig::default();
+        // src/b.rs was rewritten by a later commit, so it is not in the review
+        let mut statuses = HashMap::from([
+            (key(0, 0), HunkStatus::Reviewed),
+            (key(0, 1), HunkStatus::Reviewed),
+        ]);
+        let comments = vec![
+            comment(&files, 0, 0, "me", "Nice."),
+            comment(&files, 0, 1, "alice", "Why?"),
+        ];
+
+        let review =
+            build_review(&config, change(), &files, &statuses, &comments, Some("me")).unwrap();
+        assert_eq!(review.vote, Some(1));
+        assert_eq!(review.comments, 2);
+        assert_eq!(
+            review.input,
+            json!({
+                "message": "Reviewed 2 of 2 hunks with git-review.",
+                "labels": { "Code-Review": 1 },
+                "comments": { "src/a.rs": [
+                    { "side": "REVISION", "line": 3, "message": "Nice.", "unresolved": false },
+                    { "side": "PARENT", "line": 20, "message": "alice: Why?", "unresolved": false },
+                ]},
+            })
+        );
+
+        statuses.insert(key(0, 1), HunkStatus::NeedsChanges);
+        statuses.insert(key(1, 0), HunkStatus::Unreviewed);
+        let review = build_review(&config, change(), &files, &statuses, &[], None).unwrap();
+        assert_eq!(review.vote, Some(-1));
+        assert_eq!(
+            review.input["message"],
+            "Reviewed 1 of 3 hunks with git-review; 1 need changes."
+        );
+        assert_eq!(
+            review.input["comments"]["src/a.rs"][0]["message"],
+            "Needs changes."
+        );
+
+        // Partly reviewed: no vote
+        statuses.insert(key(0, 1), HunkStatus::Reviewed);
+        let review = build_review(&config, change(), &files, &statuses, &[], None).unwrap();
+        assert_eq!(review.vote, None);
+        assert!(review.input.get("labels").is_none());
+
+        assert!(build_review(&config, change(), &files, &HashMap::new(), &[], None).is_none());
+    }
+}
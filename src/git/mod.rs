@@ -1,5 +1,10 @@
-use std::path::PathBuf;
-use std::process::Command;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::{LazyLock, Mutex};
+use std::time::Instant;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -20,6 +25,77 @@ pub enum GitError {
 
 pub type Result<T> = std::result::Result<T, GitError>;
 
+thread_local! {
+    /// Directory git runs in on this thread, if not the current directory.
+    static REPO_DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Run git in `dir` on this thread from now on, or in the current directory
+/// again with `None`.
+///
+/// Unlike changing the process's current directory, this leaves git commands
+/// other threads are running alone.
+pub fn set_repo_dir(dir: Option<PathBuf>) {
+    REPO_DIR.with(|repo_dir| *repo_dir.borrow_mut() = dir);
+}
+
+/// Directory git runs in on this thread, for worker threads to run in too
+/// (see [`in_repo`]).
+pub fn repo_dir() -> Option<PathBuf> {
+    REPO_DIR.with(|repo_dir| repo_dir.borrow().clone())
+}
+
+/// Run `f` with git running in `dir` on this thread (the current directory
+/// for `None`), then go back to where git ran before.
+pub fn in_repo<T>(dir: Option<&Path>, f: impl FnOnce() -> T) -> T {
+    let previous = REPO_DIR.with(|repo_dir| repo_dir.replace(dir.map(Path::to_path_buf)));
+    let result = f();
+    set_repo_dir(previous);
+    result
+}
+
+/// A `git` command running in this thread's repository directory.
+pub fn command() -> Command {
+    let mut command = Command::new("git");
+    if let Some(dir) = repo_dir() {
+        command.current_dir(dir);
+    }
+    command
+}
+
+/// `Command::output` that logs the git invocation, how it ended, and how long
+/// it took, so `--verbose` shows what git was asked and what it answered.
+trait TracedOutput {
+    fn traced_output(&mut self) -> std::io::Result<Output>;
+}
+
+impl TracedOutput for Command {
+    fn traced_output(&mut self) -> std::io::Result<Output> {
+        let args: Vec<_> = self.get_args().map(|a| a.to_string_lossy()).collect();
+        let args = args.join(" ");
+        let started = Instant::now();
+        let result = self.output();
+        let elapsed_ms = started.elapsed().as_millis();
+        match &result {
+            Ok(output) if output.status.success() => tracing::debug!(
+                elapsed_ms,
+                stdout_bytes = output.stdout.len(),
+                "git {}",
+                args
+            ),
+            Ok(output) => tracing::debug!(
+                elapsed_ms,
+                status = %output.status,
+                stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+                "git {} failed",
+                args
+            ),
+            Err(e) => tracing::warn!(error = %e, "could not run git {}", args),
+        }
+        result
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BranchInfo {
     pub name: String,
@@ -66,10 +142,10 @@ pub enum MergeCheck {
 
 /// Find the root of the git repository.
 pub fn find_repo_root() -> Result<PathBuf> {
-    let output = Command::new("git")
+    let output = command()
         .arg("rev-parse")
         .arg("--show-toplevel")
-        .output()?;
+        .traced_output()?;
 
     if !output.status.success() {
         return Err(GitError::NotARepo);
@@ -91,7 +167,7 @@ pub fn validate_git_ref(ref_str: &str) -> Result<()> {
         if !ch.is_alphanumeric()
             && !matches!(
                 ch,
-                '-' | '_' | '/' | '.' | '~' | '^' | '@' | ':' | '{' | '}'
+                '-' | '_' | '/' | '.' | '~' | '^' | '@' | ':' | '{' | '}' | '!'
             )
         {
             return Err(GitError::InvalidRef(format!(
@@ -107,10 +183,10 @@ pub fn validate_git_ref(ref_str: &str) -> Result<()> {
 /// Detect the default branch (origin/HEAD -> main -> master fallback).
 pub fn detect_default_branch() -> Result<String> {
     // Try to get origin/HEAD symbolic ref
-    let output = Command::new("git")
+    let output = command()
         .arg("symbolic-ref")
         .arg("refs/remotes/origin/HEAD")
-        .output()?;
+        .traced_output()?;
 
     if output.status.success() {
         let symbolic = String::from_utf8(output.stdout)?;
@@ -121,22 +197,22 @@ pub fn detect_default_branch() -> Result<String> {
     }
 
     // Fallback: try main
-    let output = Command::new("git")
+    let output = command()
         .arg("rev-parse")
         .arg("--verify")
         .arg("main")
-        .output()?;
+        .traced_output()?;
 
     if output.status.success() {
         return Ok("main".to_string());
     }
 
     // Fallback: try master
-    let output = Command::new("git")
+    let output = command()
         .arg("rev-parse")
         .arg("--verify")
         .arg("master")
-        .output()?;
+        .traced_output()?;
 
     if output.status.success() {
         return Ok("master".to_string());
@@ -147,11 +223,104 @@ pub fn detect_default_branch() -> Result<String> {
     ))
 }
 
+/// How many diffs are cached before the cache starts over.
+const DIFF_CACHE_LIMIT: usize = 64;
+
+/// Cached diff output keyed by (git dir, range, options, resolved commits).
+type DiffCache = HashMap<(PathBuf, String, DiffOptions, Vec<String>), String>;
+
+static DIFF_CACHE: LazyLock<Mutex<DiffCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Options that change what `git diff` reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct DiffOptions {
+    /// Ignore whitespace when comparing lines (`git diff -w`).
+    pub ignore_whitespace: bool,
+}
+
 /// Get git diff output for a given range.
+///
+/// Diffs between two commits are cached per repository and the commits the
+/// range resolves to, so the dashboard, watch loop, and gate can ask for the
+/// same range repeatedly without re-running git until one of its ends moves.
+/// Diffs against the working tree are never cached.
 pub fn get_diff(range: &str) -> Result<String> {
+    get_diff_with_options(range, DiffOptions::default())
+}
+
+/// Like [`get_diff`], with extra `git diff` options (cached separately).
+pub fn get_diff_with_options(range: &str, options: DiffOptions) -> Result<String> {
     validate_git_ref(range)?;
 
-    let output = Command::new("git").arg("diff").arg(range).output()?;
+    let key = diff_cache_state(range)
+        .map(|(git_dir, commits)| (git_dir, range.to_string(), options, commits));
+    if let Some(key) = &key
+        && let Ok(cache) = DIFF_CACHE.lock()
+        && let Some(diff) = cache.get(key)
+    {
+        tracing::debug!(range, bytes = diff.len(), "diff cache hit");
+        return Ok(diff.clone());
+    }
+
+    let diff = run_diff(range, options)?;
+    tracing::info!(
+        range,
+        ignore_whitespace = options.ignore_whitespace,
+        bytes = diff.len(),
+        cacheable = key.is_some(),
+        "ran git diff"
+    );
+    if let Some(key) = key
+        && let Ok(mut cache) = DIFF_CACHE.lock()
+    {
+        if cache.len() >= DIFF_CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(key, diff.clone());
+    }
+    Ok(diff)
+}
+
+/// Drop all cached diffs (e.g. after the working tree was changed behind git's back).
+pub fn clear_diff_cache() {
+    if let Ok(mut cache) = DIFF_CACHE.lock() {
+        cache.clear();
+    }
+}
+
+/// The repository's git dir and the commits `range` resolves to, the parts of
+/// the diff cache key that tell whether a cached diff may be reused. `None`
+/// for a diff against the working tree, or when a commit cannot be resolved,
+/// which disables caching.
+fn diff_cache_state(range: &str) -> Option<(PathBuf, Vec<String>)> {
+    let revs = match split_range(range) {
+        Some((base, head)) => vec![base, head],
+        None => vec![range.strip_suffix("^!")?],
+    };
+    let output = command()
+        .args(["rev-parse", "--absolute-git-dir"])
+        .args(revs.iter().map(|rev| format!("{}^{{commit}}", rev)))
+        .traced_output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let mut lines = stdout.lines();
+    let git_dir = PathBuf::from(lines.next()?);
+    let commits: Vec<String> = lines.map(str::to_string).collect();
+    (commits.len() == revs.len()).then_some((git_dir, commits))
+}
+
+/// Run `git diff <range>` without consulting the cache.
+fn run_diff(range: &str, options: DiffOptions) -> Result<String> {
+    let mut cmd = command();
+    cmd.arg("diff");
+    if options.ignore_whitespace {
+        cmd.arg("-w");
+    }
+    let output = cmd.arg(range).traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -164,14 +333,389 @@ pub fn get_diff(range: &str) -> Result<String> {
     String::from_utf8(output.stdout).map_err(GitError::from)
 }
 
+/// `[difftool]` section of `.git-review.toml`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifftoolConfig {
+    /// Tool passed to `git difftool --tool` (git's `diff.tool` setting when unset).
+    pub tool: Option<String>,
+}
+
+/// Show one file's changes in a range with `git difftool`, waiting for the tool to exit.
+///
+/// The tool inherits the terminal, so callers running a TUI must suspend it first.
+pub fn difftool(range: &str, file: &str, tool: Option<&str>) -> Result<()> {
+    validate_git_ref(range)?;
+
+    let mut cmd = command();
+    cmd.arg("difftool").arg("--no-prompt");
+    if let Some(tool) = tool {
+        cmd.arg(format!("--tool={}", tool));
+    }
+    let status = cmd.arg(range).arg("--").arg(file).status()?;
+
+    if !status.success() {
+        return Err(GitError::CommandFailed(format!(
+            "git difftool failed: {}",
+            status
+        )));
+    }
+    Ok(())
+}
+
+/// Resolve a ref (branch, tag, stash entry, ...) to its full commit SHA.
+pub fn resolve_ref(ref_str: &str) -> Result<String> {
+    validate_git_ref(ref_str)?;
+
+    let output = command()
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg(ref_str)
+        .traced_output()?;
+
+    if !output.status.success() {
+        return Err(GitError::InvalidRef(ref_str.to_string()));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Commit time of a revision (branch, tag, SHA, ...), seconds since the Unix epoch.
+pub fn commit_time(rev: &str) -> Result<i64> {
+    validate_git_ref(rev)?;
+
+    let output = command()
+        .arg("log")
+        .arg("-1")
+        .arg("--format=%ct")
+        .arg(rev)
+        .arg("--")
+        .traced_output()?;
+
+    if !output.status.success() {
+        return Err(GitError::InvalidRef(rev.to_string()));
+    }
+
+    String::from_utf8(output.stdout)?
+        .trim()
+        .parse()
+        .map_err(|_| GitError::InvalidRef(rev.to_string()))
+}
+
+/// Read the contents of a blob object (e.g. an abbreviated id from a diff's `index` line).
+pub fn read_blob(id: &str) -> Result<Vec<u8>> {
+    validate_git_ref(id)?;
+
+    let output = command()
+        .arg("cat-file")
+        .arg("blob")
+        .arg(id)
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git cat-file failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(output.stdout)
+}
+
+/// List the commits in a range, oldest first.
+pub fn rev_list(range: &str) -> Result<Vec<String>> {
+    validate_git_ref(range)?;
+
+    let output = command()
+        .arg("rev-list")
+        .arg("--reverse")
+        .arg(range)
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git rev-list failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// Get the patch a single commit introduced (empty for merge commits).
+pub fn get_commit_diff(sha: &str) -> Result<String> {
+    validate_git_ref(sha)?;
+
+    let output = command()
+        .args(["diff-tree", "-p", "--root", "--no-commit-id", "--no-color"])
+        .arg(sha)
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git diff-tree failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Get the full message of a commit.
+pub fn commit_message(sha: &str) -> Result<String> {
+    validate_git_ref(sha)?;
+
+    let output = command()
+        .args(["log", "-1", "--format=%B"])
+        .arg(sha)
+        .arg("--")
+        .traced_output()?;
+
+    if !output.status.success() {
+        return Err(GitError::InvalidRef(sha.to_string()));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Read the note attached to a commit under `notes_ref`, if there is one.
+pub fn read_note(notes_ref: &str, sha: &str) -> Result<Option<String>> {
+    validate_git_ref(sha)?;
+
+    let output = command()
+        .arg("notes")
+        .arg(format!("--ref={}", notes_ref))
+        .arg("show")
+        .arg(sha)
+        .env("LC_ALL", "C")
+        .traced_output()?;
+
+    // `git notes show` exits non-zero both for "no note" and for real errors,
+    // so tell them apart by the (untranslated) message
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no note found") {
+            return Ok(None);
+        }
+        return Err(GitError::CommandFailed(format!(
+            "git notes show failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(Some(String::from_utf8(output.stdout)?))
+}
+
+/// Attach `message` to a commit under `notes_ref`, replacing any existing note.
+pub fn write_note(notes_ref: &str, sha: &str, message: &str) -> Result<()> {
+    validate_git_ref(sha)?;
+
+    let mut child = command()
+        .arg("notes")
+        .arg(format!("--ref={}", notes_ref))
+        .args(["add", "--force", "--file=-"])
+        .arg(sha)
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        stdin.write_all(message.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git notes add failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(())
+}
+
+/// Stable patch id of a diff (`git patch-id --stable`), which stays the same
+/// when the commit it came from is rebased or cherry-picked.
+pub fn patch_id(diff: &str) -> Result<String> {
+    let mut child = command()
+        .args(["patch-id", "--stable"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        stdin.write_all(diff.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git patch-id failed: {}",
+            stderr
+        )));
+    }
+
+    String::from_utf8(output.stdout)?
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+        .ok_or_else(|| GitError::CommandFailed("git patch-id printed nothing".to_string()))
+}
+
+/// Cherry-pick a commit onto the checked-out branch, with git's output on the terminal.
+pub fn cherry_pick(commit: &str) -> Result<()> {
+    validate_git_ref(commit)?;
+
+    let status = command().arg("cherry-pick").arg(commit).status()?;
+    if !status.success() {
+        return Err(GitError::CommandFailed(format!(
+            "git cherry-pick {} failed",
+            commit
+        )));
+    }
+    Ok(())
+}
+
+/// Changes in the working tree that are not staged (`git diff`).
+pub fn get_unstaged_diff() -> Result<String> {
+    let output = command().arg("diff").traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git diff failed: {}",
+            stderr
+        )));
+    }
+
+    String::from_utf8(output.stdout).map_err(GitError::from)
+}
+
+/// Apply a patch to the index only (`git apply --cached`), leaving the
+/// working tree as it is.
+pub fn apply_to_index(patch: &str) -> Result<()> {
+    let mut child = command()
+        .args(["apply", "--cached", "-"])
+        .stdin(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        use std::io::Write;
+        stdin.write_all(patch.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git apply --cached failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(())
+}
+
+/// Get the patch recorded in a stash entry (`git stash show -p`).
+pub fn get_stash_diff(stash: &str, include_untracked: bool) -> Result<String> {
+    validate_git_ref(stash)?;
+
+    let mut cmd = command();
+    cmd.arg("stash").arg("show").arg("-p");
+    if include_untracked {
+        cmd.arg("--include-untracked");
+    }
+    let output = cmd.arg(stash).traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git stash show failed: {}",
+            stderr
+        )));
+    }
+
+    String::from_utf8(output.stdout).map_err(GitError::from)
+}
+
+/// Synthesize a diff for every untracked (non-ignored) file against /dev/null.
+///
+/// Paths in the output are relative to `repo_root`, like regular `git diff` output.
+pub fn get_untracked_diff(repo_root: &Path) -> Result<String> {
+    let output = command()
+        .arg("ls-files")
+        .arg("--others")
+        .arg("--exclude-standard")
+        .arg("-z")
+        .current_dir(repo_root)
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git ls-files failed: {}",
+            stderr
+        )));
+    }
+
+    let listing = String::from_utf8(output.stdout)?;
+    let mut diff = String::new();
+
+    for path in listing.split('\0').filter(|p| !p.is_empty()) {
+        let output = command()
+            .arg("diff")
+            .arg("--no-index")
+            .arg("--")
+            .arg("/dev/null")
+            .arg(path)
+            .current_dir(repo_root)
+            .traced_output()?;
+
+        // --no-index exits with 1 when the files differ, which is always the case here
+        if output.status.code() != Some(1) {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(GitError::CommandFailed(format!(
+                "git diff --no-index failed for {}: {}",
+                path, stderr
+            )));
+        }
+
+        diff.push_str(&String::from_utf8_lossy(&output.stdout));
+    }
+
+    Ok(diff)
+}
+
 /// List all local branches via a single git for-each-ref call.
 pub fn list_branches() -> Result<Vec<BranchInfo>> {
-    let output = Command::new("git")
+    list_refs("refs/heads/", true)
+}
+
+/// List remote-tracking branches (e.g. `origin/feature`), skipping `<remote>/HEAD`.
+pub fn list_remote_branches() -> Result<Vec<BranchInfo>> {
+    Ok(list_refs("refs/remotes/", false)?
+        .into_iter()
+        .filter(|b| b.name.contains('/') && !b.name.ends_with("/HEAD"))
+        .collect())
+}
+
+/// List refs under a namespace, most recently committed first.
+fn list_refs(namespace: &str, is_local: bool) -> Result<Vec<BranchInfo>> {
+    let output = command()
         .arg("for-each-ref")
         .arg("--format=%(refname:short)|%(objectname:short)|%(authorname)|%(committerdate:relative)|%(committerdate:unix)")
         .arg("--sort=-committerdate")
-        .arg("refs/heads/")
-        .output()?;
+        .arg(namespace)
+        .traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -193,7 +737,7 @@ pub fn list_branches() -> Result<Vec<BranchInfo>> {
         if fields.len() >= 5 {
             branches.push(BranchInfo {
                 name: fields[0].to_string(),
-                is_local: true,
+                is_local,
                 last_commit_sha: fields[1].to_string(),
                 last_commit_author: fields[2].to_string(),
                 last_commit_age: fields[3].to_string(),
@@ -205,15 +749,56 @@ pub fn list_branches() -> Result<Vec<BranchInfo>> {
     Ok(branches)
 }
 
+/// Full SHA of the commit each ref under `namespaces` points at, keyed like
+/// [`BranchInfo::name`].
+pub fn ref_tips(namespaces: &[&str]) -> Result<HashMap<String, String>> {
+    let output = command()
+        .arg("for-each-ref")
+        .arg("--format=%(refname:short) %(objectname)")
+        .args(namespaces)
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git for-each-ref failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| line.rsplit_once(' '))
+        .map(|(name, sha)| (name.to_string(), sha.to_string()))
+        .collect())
+}
+
+/// Fetch all remotes, pruning deleted branches.
+pub fn fetch_all() -> Result<()> {
+    let output = command()
+        .args(["fetch", "--all", "--prune", "--quiet"])
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git fetch failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(())
+}
+
 /// Get ahead/behind counts and diff stats for a branch (lazy, per-branch).
 pub fn get_branch_detail(base: &str, branch: &str) -> Result<BranchDetail> {
     // Get ahead/behind counts
-    let output = Command::new("git")
+    let output = command()
         .arg("rev-list")
         .arg("--count")
         .arg("--left-right")
         .arg(format!("{}...{}", base, branch))
-        .output()?;
+        .traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -234,12 +819,26 @@ pub fn get_branch_detail(base: &str, branch: &str) -> Result<BranchDetail> {
         (0, 0)
     };
 
-    // Get diff stats
-    let output = Command::new("git")
+    let diff_stats = get_diff_stats(&format!("{}..{}", base, branch))?;
+
+    Ok(BranchDetail {
+        ahead,
+        behind,
+        diff_stats,
+    })
+}
+
+/// Count changed files and lines for a diff range (`git diff --numstat`).
+///
+/// Much cheaper than fetching and parsing the full diff. Binary files are skipped.
+pub fn get_diff_stats(range: &str) -> Result<DiffStats> {
+    validate_git_ref(range)?;
+
+    let output = command()
         .arg("diff")
         .arg("--numstat")
-        .arg(format!("{}..{}", base, branch))
-        .output()?;
+        .arg(range)
+        .traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -272,20 +871,16 @@ pub fn get_branch_detail(base: &str, branch: &str) -> Result<BranchDetail> {
         }
     }
 
-    Ok(BranchDetail {
-        ahead,
-        behind,
-        diff_stats: DiffStats {
-            file_count,
-            insertions,
-            deletions,
-        },
+    Ok(DiffStats {
+        file_count,
+        insertions,
+        deletions,
     })
 }
 
 /// Get current HEAD SHA (lightweight staleness check).
 pub fn get_head_sha() -> Result<String> {
-    let output = Command::new("git").arg("rev-parse").arg("HEAD").output()?;
+    let output = command().arg("rev-parse").arg("HEAD").traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -300,10 +895,7 @@ pub fn get_head_sha() -> Result<String> {
 
 /// Check if the worktree has uncommitted changes.
 pub fn check_worktree_status() -> Result<WorktreeStatus> {
-    let output = Command::new("git")
-        .arg("status")
-        .arg("--porcelain")
-        .output()?;
+    let output = command().arg("status").arg("--porcelain").traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -338,12 +930,12 @@ pub fn check_worktree_status() -> Result<WorktreeStatus> {
 /// Pre-check for merge conflicts using git merge-tree.
 pub fn check_merge_conflicts(base: &str, branch: &str) -> Result<MergeCheck> {
     // Try modern git merge-tree --write-tree first
-    let output = Command::new("git")
+    let output = command()
         .arg("merge-tree")
         .arg("--write-tree")
         .arg(base)
         .arg(branch)
-        .output()?;
+        .traced_output()?;
 
     match output.status.code() {
         Some(0) => Ok(MergeCheck::Clean),
@@ -353,11 +945,11 @@ pub fn check_merge_conflicts(base: &str, branch: &str) -> Result<MergeCheck> {
             let stderr = String::from_utf8_lossy(&output.stderr);
             if stderr.contains("unknown option") || stderr.contains("usage:") {
                 // Fall back to 3-arg form for older git versions
-                let merge_base_output = Command::new("git")
+                let merge_base_output = command()
                     .arg("merge-base")
                     .arg(base)
                     .arg(branch)
-                    .output()?;
+                    .traced_output()?;
 
                 if !merge_base_output.status.success() {
                     return Ok(MergeCheck::Error("Could not find merge base".to_string()));
@@ -367,12 +959,12 @@ pub fn check_merge_conflicts(base: &str, branch: &str) -> Result<MergeCheck> {
                     .trim()
                     .to_string();
 
-                let fallback_output = Command::new("git")
+                let fallback_output = command()
                     .arg("merge-tree")
                     .arg(&merge_base)
                     .arg(base)
                     .arg(branch)
-                    .output()?;
+                    .traced_output()?;
 
                 if !fallback_output.status.success() {
                     return Ok(MergeCheck::Error(
@@ -395,15 +987,15 @@ pub fn check_merge_conflicts(base: &str, branch: &str) -> Result<MergeCheck> {
 
 /// Execute git merge --no-ff. Auto-aborts on failure.
 pub fn merge_branch(options: &MergeOptions) -> Result<()> {
-    let output = Command::new("git")
+    let output = command()
         .arg("merge")
         .arg("--no-ff")
         .arg(&options.branch)
-        .output()?;
+        .traced_output()?;
 
     if !output.status.success() {
         // Abort the merge
-        let _ = Command::new("git").arg("merge").arg("--abort").output();
+        let _ = command().arg("merge").arg("--abort").traced_output();
 
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(GitError::MergeFailed(stderr.to_string()));
@@ -418,11 +1010,11 @@ pub fn merge_branch(options: &MergeOptions) -> Result<()> {
 
 /// Delete a branch (safe delete, not force).
 pub fn delete_branch(name: &str) -> Result<()> {
-    let output = Command::new("git")
+    let output = command()
         .arg("branch")
         .arg("-d")
         .arg(name)
-        .output()?;
+        .traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -435,12 +1027,209 @@ pub fn delete_branch(name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Check whether `branch` is fully merged into `base` (its tip is an ancestor of `base`).
+pub fn is_merged(base: &str, branch: &str) -> Result<bool> {
+    validate_git_ref(base)?;
+    validate_git_ref(branch)?;
+
+    let output = command()
+        .arg("merge-base")
+        .arg("--is-ancestor")
+        .arg(branch)
+        .arg(base)
+        .traced_output()?;
+
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(GitError::CommandFailed(format!(
+                "git merge-base --is-ancestor failed: {}",
+                stderr
+            )))
+        }
+    }
+}
+
+/// The commit on `base` that merged `branch`: the earliest merge commit
+/// descending from it, or its own tip when it was fast-forwarded.
+pub fn merge_commit(base: &str, branch: &str) -> Result<String> {
+    validate_git_ref(base)?;
+    validate_git_ref(branch)?;
+
+    let output = command()
+        .arg("rev-list")
+        .arg("--ancestry-path")
+        .arg("--merges")
+        .arg("--reverse")
+        .arg(format!("{}..{}", branch, base))
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git rev-list failed: {}",
+            stderr
+        )));
+    }
+
+    match String::from_utf8(output.stdout)?.lines().next() {
+        Some(sha) => Ok(sha.to_string()),
+        None => resolve_ref(branch),
+    }
+}
+
+/// Find the best common ancestor of two commits.
+pub fn merge_base(a: &str, b: &str) -> Result<String> {
+    validate_git_ref(a)?;
+    validate_git_ref(b)?;
+
+    let output = command().arg("merge-base").arg(a).arg(b).traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git merge-base failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Canonical review state key for a diff range.
+///
+/// `main..HEAD`, `main...HEAD`, and `main..feature` (with `feature` checked out)
+/// all describe the same review, so they map to `<merge-base-sha>..feature`.
+/// The key names the branch rather than its tip, so new commits keep their
+/// progress. Anything that is not a two-ended range (or cannot be resolved)
+/// is returned unchanged.
+pub fn normalize_diff_range(range: &str) -> String {
+    let Some((base, head)) = split_range(range) else {
+        return range.to_string();
+    };
+
+    let head = if head == "HEAD" {
+        get_current_branch()
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| head.to_string())
+    } else {
+        head.to_string()
+    };
+
+    match merge_base(base, &head) {
+        Ok(sha) => format!("{}..{}", sha, head),
+        Err(_) => range.to_string(),
+    }
+}
+
+/// Split `A..B` or `A...B` into its ends, defaulting an omitted end to `HEAD`.
+fn split_range(range: &str) -> Option<(&str, &str)> {
+    let (base, head) = range.split_once("...").or_else(|| range.split_once(".."))?;
+    Some((
+        if base.is_empty() { "HEAD" } else { base },
+        if head.is_empty() { "HEAD" } else { head },
+    ))
+}
+
+/// Revision holding the pre-image of a diff range: the merge-base for
+/// `a...b`, `a` for `a..b`, `c^` for `c^!`, and the revision itself when the
+/// range is a single revision diffed against the working tree.
+pub fn pre_image_rev(range: &str) -> Result<String> {
+    if let Some((base, head)) = split_range(range) {
+        return if range.contains("...") {
+            merge_base(base, head)
+        } else {
+            Ok(base.to_string())
+        };
+    }
+    Ok(match range.strip_suffix("^!") {
+        Some(commit) => format!("{}^", commit),
+        None => range.to_string(),
+    })
+}
+
+/// Who last touched a line, from `git blame`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameLine {
+    pub author: String,
+    /// Author time, seconds since the Unix epoch.
+    pub author_time: i64,
+}
+
+/// Blame `count` lines of `path` at revision `rev`, starting at line `start`.
+pub fn blame(rev: &str, path: &str, start: u32, count: u32) -> Result<Vec<BlameLine>> {
+    validate_git_ref(rev)?;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let output = command()
+        .arg("blame")
+        .arg("--porcelain")
+        .arg("-L")
+        .arg(format!("{},+{}", start.max(1), count))
+        .arg(rev)
+        .arg("--")
+        .arg(path)
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(GitError::CommandFailed(format!(
+            "git blame failed: {}",
+            stderr
+        )));
+    }
+
+    Ok(parse_blame_porcelain(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `git blame --porcelain` output into one entry per blamed line.
+///
+/// Commit details are only printed the first time a commit appears, so they
+/// are remembered by SHA for later lines from the same commit.
+fn parse_blame_porcelain(output: &str) -> Vec<BlameLine> {
+    let mut commits: HashMap<&str, BlameLine> = HashMap::new();
+    let mut lines = Vec::new();
+    let mut current = "";
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            if let Some(commit) = commits.get(current) {
+                lines.push(commit.clone());
+            }
+        } else if let Some(author) = line.strip_prefix("author ") {
+            if let Some(commit) = commits.get_mut(current) {
+                commit.author = author.to_string();
+            }
+        } else if let Some(time) = line.strip_prefix("author-time ") {
+            if let Some(commit) = commits.get_mut(current) {
+                commit.author_time = time.parse().unwrap_or_default();
+            }
+        } else if let Some(sha) = line.split(' ').next()
+            && matches!(sha.len(), 40 | 64)
+            && sha.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            current = sha;
+            commits.entry(sha).or_insert_with(|| BlameLine {
+                author: String::new(),
+                author_time: 0,
+            });
+        }
+    }
+    lines
+}
+
 /// Get the current branch name (None for detached HEAD).
 pub fn get_current_branch() -> Result<Option<String>> {
-    let output = Command::new("git")
+    let output = command()
         .arg("branch")
         .arg("--show-current")
-        .output()?;
+        .traced_output()?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -458,10 +1247,294 @@ pub fn get_current_branch() -> Result<Option<String>> {
     }
 }
 
+/// Run `git commit -m <message>` and return the first line of git's output.
+pub fn commit(message: &str) -> Result<String> {
+    let output = command()
+        .arg("commit")
+        .arg("-m")
+        .arg(message)
+        .traced_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        return Err(GitError::CommandFailed(format!(
+            "git commit failed: {}{}",
+            stderr.trim(),
+            stdout.trim()
+        )));
+    }
+
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout.lines().next().unwrap_or_default().to_string())
+}
+
+/// The value of a single-valued git config key, or `None` if it is not set.
+pub fn config_value(key: &str) -> Result<Option<String>> {
+    let output = command().args(["config", "--get", key]).traced_output()?;
+
+    // Exit code 1 means the key is not set
+    match output.status.code() {
+        Some(0) => Ok(Some(String::from_utf8(output.stdout)?.trim().to_string())),
+        Some(1) => Ok(None),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(GitError::CommandFailed(format!(
+                "git config {} failed: {}",
+                key, stderr
+            )))
+        }
+    }
+}
+
+/// Repositories listed under `git-review.repos` in git config, for the multi-repo dashboard.
+pub fn configured_repos() -> Result<Vec<PathBuf>> {
+    let output = command()
+        .args(["config", "--get-all", "--type=path", "git-review.repos"])
+        .traced_output()?;
+
+    // Exit code 1 means the key is not set
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .map(PathBuf::from)
+            .collect()),
+        Some(1) => Ok(Vec::new()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(GitError::CommandFailed(format!(
+                "git config git-review.repos failed: {}",
+                stderr
+            )))
+        }
+    }
+}
+
+/// The remote a local branch tracks and the branch's name there, if it has
+/// an upstream.
+pub fn branch_upstream(branch: &str) -> Result<Option<(String, String)>> {
+    validate_git_ref(branch)?;
+    let output = command()
+        .args([
+            "for-each-ref",
+            "--format=%(upstream:remotename)%00%(upstream:remoteref)",
+        ])
+        .arg(format!("refs/heads/{}", branch))
+        .traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::CommandFailed(format!(
+            "git for-each-ref failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    let stdout = String::from_utf8(output.stdout)?;
+    Ok(stdout
+        .trim_end()
+        .split_once('\0')
+        .filter(|(remote, _)| !remote.is_empty())
+        .map(|(remote, remote_ref)| {
+            let name = remote_ref.strip_prefix("refs/heads/").unwrap_or(remote_ref);
+            (remote.to_string(), name.to_string())
+        }))
+}
+
+/// The branch a local branch tracks, as a ref name (`origin/main`, or `main`
+/// for a local upstream), if it has one.
+pub fn upstream_branch(branch: &str) -> Result<Option<String>> {
+    validate_git_ref(branch)?;
+    let output = command()
+        .args(["for-each-ref", "--format=%(upstream:short)"])
+        .arg(format!("refs/heads/{}", branch))
+        .traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::CommandFailed(format!(
+            "git for-each-ref failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    let upstream = String::from_utf8(output.stdout)?.trim().to_string();
+    Ok(Some(upstream).filter(|upstream| !upstream.is_empty()))
+}
+
+/// What `git push <args>` would push besides `branch`: each refspec whose
+/// source isn't the branch (or `HEAD`), and each option that pushes or deletes
+/// refs wholesale, as written.
+pub fn push_extra_refs<'a>(args: &'a [String], branch: &str) -> Vec<&'a str> {
+    let mut extra = Vec::new();
+    let mut options_done = false;
+    let mut positional = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !options_done && arg == "--" {
+            options_done = true;
+            continue;
+        }
+        if !options_done && arg.starts_with('-') {
+            match arg.as_str() {
+                "--all" | "--branches" | "--mirror" | "--tags" | "--prune" | "-d" | "--delete" => {
+                    extra.push(arg.as_str())
+                }
+                // Options whose value is the next argument
+                "-o" | "--push-option" | "--repo" | "--receive-pack" | "--exec" => {
+                    args.next();
+                }
+                _ => {}
+            }
+            continue;
+        }
+        positional += 1;
+        // The first is the repository
+        if positional == 1 {
+            continue;
+        }
+        let spec = arg.trim_start_matches('+');
+        let source = spec.split_once(':').map_or(spec, |(source, _)| source);
+        if !(matches!(source, "HEAD" | "@")
+            || source == branch
+            || source.strip_prefix("refs/heads/") == Some(branch))
+        {
+            extra.push(arg.as_str());
+        }
+    }
+    extra
+}
+
+/// The URL of a remote, if there is a remote of that name.
+pub fn remote_url(remote: &str) -> Result<Option<String>> {
+    let output = command()
+        .args(["remote", "get-url", "--", remote])
+        .traced_output()?;
+    // Exit code 2 means there is no such remote
+    match output.status.code() {
+        Some(0) => Ok(Some(String::from_utf8(output.stdout)?.trim().to_string())),
+        Some(2) => Ok(None),
+        _ => Err(GitError::CommandFailed(format!(
+            "git remote get-url failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ))),
+    }
+}
+
+/// The commit message template configured as `commit.template`, if any.
+pub fn commit_template() -> Result<Option<PathBuf>> {
+    let output = command()
+        .args(["config", "--get", "--type=path", "commit.template"])
+        .traced_output()?;
+
+    // Exit code 1 means the key is not set
+    match output.status.code() {
+        Some(0) => Ok(Some(PathBuf::from(
+            String::from_utf8(output.stdout)?.trim_end_matches(['\r', '\n']),
+        ))),
+        Some(1) => Ok(None),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(GitError::CommandFailed(format!(
+                "git config commit.template failed: {}",
+                stderr
+            )))
+        }
+    }
+}
+
+/// Directory git runs the hooks of `repo_root` from: `core.hooksPath` if set
+/// (relative paths are taken from the repository root), else the `hooks`
+/// directory of the main repository's git dir, which linked worktrees share.
+pub fn hooks_dir(repo_root: &Path) -> Result<PathBuf> {
+    let output = command()
+        .arg("-C")
+        .arg(repo_root)
+        .args(["rev-parse", "--git-path", "hooks"])
+        .traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::CommandFailed(format!(
+            "git rev-parse --git-path hooks failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    let hooks = String::from_utf8(output.stdout)?;
+    Ok(repo_root.join(hooks.trim_end_matches(['\r', '\n'])))
+}
+
+/// Whether `path` (relative to `repo_root`) is tracked by git.
+pub fn is_tracked(repo_root: &Path, path: &str) -> Result<bool> {
+    let output = command()
+        .arg("-C")
+        .arg(repo_root)
+        .args(["ls-files", "--error-unmatch", "--", path])
+        .traced_output()?;
+    Ok(output.status.success())
+}
+
+/// Whether `path` (relative to `repo_root`) is ignored by `.gitignore` or
+/// `.git/info/exclude`.
+pub fn is_ignored(repo_root: &Path, path: &str) -> Result<bool> {
+    let output = command()
+        .arg("-C")
+        .arg(repo_root)
+        .args(["check-ignore", "-q", "--", path])
+        .traced_output()?;
+    // Exit code 1 means not ignored
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => Err(GitError::CommandFailed(format!(
+            "git check-ignore failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ))),
+    }
+}
+
+/// Get the configured `user.name`, used to attribute reviews.
+pub fn get_user_name() -> Result<String> {
+    let output = command().arg("config").arg("user.name").traced_output()?;
+
+    if !output.status.success() {
+        return Err(GitError::CommandFailed(
+            "git config user.name is not set".to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Get the configured `user.email`, used to address review emails.
+pub fn get_user_email() -> Result<String> {
+    let output = command().arg("config").arg("user.email").traced_output()?;
+
+    if !output.status.success() {
+        return Err(GitError::CommandFailed(
+            "git config user.email is not set".to_string(),
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn blame_porcelain_repeats_commit_details() {
+        let a = "a".repeat(40);
+        let b = "b".repeat(40);
+        let output = format!(
+            "{a} 1 1 2\nauthor Ana\nauthor-mail <ana@x>\nauthor-time 1700000000\nsummary one\nfilename f\n\tfirst\n\
+             {b} 5 2 1\nauthor Bo\nauthor-time 1600000000\nfilename f\n\tsecond\n\
+             {a} 2 3\n\tthird\n"
+        );
+        let lines = parse_blame_porcelain(&output);
+        let authors: Vec<(&str, i64)> = lines
+            .iter()
+            .map(|line| (line.author.as_str(), line.author_time))
+            .collect();
+        assert_eq!(
+            authors,
+            [("Ana", 1700000000), ("Bo", 1600000000), ("Ana", 1700000000)]
+        );
+    }
+
     #[test]
     fn test_validate_git_ref_valid() {
         assert!(validate_git_ref("main").is_ok());
@@ -472,6 +1545,7 @@ mod tests {
         assert!(validate_git_ref("origin/main").is_ok());
         assert!(validate_git_ref("HEAD^").is_ok());
         assert!(validate_git_ref("@{-1}").is_ok());
+        assert!(validate_git_ref("abc123^!").is_ok());
     }
 
     #[test]
@@ -484,6 +1558,98 @@ mod tests {
         assert!(validate_git_ref("foo\nbar").is_err());
     }
 
+    #[test]
+    fn test_get_diff_is_cached_per_commit() {
+        let (_, commits) =
+            diff_cache_state("HEAD~1..HEAD").expect("test runs inside a git repository");
+        assert_eq!(commits[1], get_head_sha().unwrap());
+        assert!(diff_cache_state("HEAD").is_none());
+
+        let first = get_diff("HEAD~1..HEAD").unwrap();
+        let cached = DIFF_CACHE
+            .lock()
+            .unwrap()
+            .keys()
+            .any(|(_, range, _, cached)| range == "HEAD~1..HEAD" && *cached == commits);
+        assert!(cached);
+        assert_eq!(get_diff("HEAD~1..HEAD").unwrap(), first);
+
+        let ignoring = DiffOptions {
+            ignore_whitespace: true,
+        };
+        get_diff_with_options("HEAD~1..HEAD", ignoring).unwrap();
+        let cache = DIFF_CACHE.lock().unwrap();
+        assert!(
+            cache
+                .keys()
+                .any(|(_, range, options, _)| range == "HEAD~1..HEAD" && *options == ignoring)
+        );
+        assert!(
+            cache
+                .keys()
+                .any(|(_, range, options, _)| range == "HEAD~1..HEAD"
+                    && *options == DiffOptions::default())
+        );
+    }
+
+    #[test]
+    fn test_split_range() {
+        assert_eq!(split_range("main..HEAD"), Some(("main", "HEAD")));
+        assert_eq!(split_range("main...feature"), Some(("main", "feature")));
+        assert_eq!(split_range("main.."), Some(("main", "HEAD")));
+        assert_eq!(split_range("...feature"), Some(("HEAD", "feature")));
+        assert_eq!(split_range("HEAD"), None);
+        assert_eq!(split_range("abc123^!"), None);
+    }
+
+    #[test]
+    fn test_push_extra_refs() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        for ok in [
+            &[][..],
+            &["-u", "origin", "feature"],
+            &["origin", "HEAD:refs/for/main"],
+            &[
+                "--force-with-lease",
+                "origin",
+                "+refs/heads/feature:feature",
+            ],
+            &["-o", "ci.skip", "origin"],
+        ] {
+            assert!(push_extra_refs(&args(ok), "feature").is_empty(), "{:?}", ok);
+        }
+        assert_eq!(
+            push_extra_refs(&args(&["origin", "feature", "main", ":old"]), "feature"),
+            vec!["main", ":old"]
+        );
+        assert_eq!(
+            push_extra_refs(&args(&["--all", "origin"]), "feature"),
+            vec!["--all"]
+        );
+        assert_eq!(
+            push_extra_refs(&args(&["-d", "origin", "feature"]), "feature"),
+            vec!["-d"]
+        );
+    }
+
+    #[test]
+    fn test_normalize_diff_range_keeps_non_ranges() {
+        assert_eq!(normalize_diff_range("HEAD"), "HEAD");
+        assert_eq!(normalize_diff_range("stash:abc"), "stash:abc");
+        assert_eq!(
+            normalize_diff_range("no-such-branch..other"),
+            "no-such-branch..other"
+        );
+    }
+
+    #[test]
+    fn test_normalize_diff_range_spellings_agree() {
+        let two_dot = normalize_diff_range("HEAD~1..HEAD");
+        let three_dot = normalize_diff_range("HEAD~1...HEAD");
+        assert_eq!(two_dot, three_dot);
+        assert_ne!(two_dot, "HEAD~1..HEAD");
+    }
+
     #[test]
     fn test_validate_git_ref_empty() {
         assert!(validate_git_ref("").is_err());
@@ -521,4 +1687,27 @@ mod tests {
             git_dir
         );
     }
+
+    #[test]
+    fn test_get_untracked_diff_synthesizes_new_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let status = Command::new("git")
+            .arg("init")
+            .arg("-q")
+            .current_dir(dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        std::fs::write(dir.path().join("new.txt"), "hello\n").unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "ignored.txt\n").unwrap();
+        std::fs::write(dir.path().join("ignored.txt"), "secret\n").unwrap();
+
+        let diff = get_untracked_diff(dir.path()).unwrap();
+        let files = crate::parser::parse_diff(&diff);
+        let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+
+        assert!(paths.contains(&PathBuf::from("new.txt")));
+        assert!(!paths.contains(&PathBuf::from("ignored.txt")));
+        assert!(diff.contains("+hello"));
+    }
 }
@@ -1,7 +1,11 @@
+use crate::textlayout::TextLayout;
 use ratatui::{
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::Span,
 };
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
 use syntect::{
     easy::HighlightLines,
     highlighting::{Color as SyntectColor, Theme, ThemeSet},
@@ -11,6 +15,21 @@ use syntect::{
 /// Maximum line length for syntax highlighting (skip longer lines for performance).
 const MAX_LINE_LENGTH: usize = 10_000;
 
+/// Theme used for diff content.
+const THEME_NAME: &str = "base16-ocean.dark";
+
+/// Maximum number of highlighted hunks kept in a [`HighlightCache`].
+const CACHE_CAPACITY: usize = 256;
+
+/// Highlighted spans for each line of a hunk.
+pub type HighlightedLines = Vec<Vec<Span<'static>>>;
+
+/// What a tab is drawn as when whitespace is shown, padded to the next tab stop.
+const TAB_MARKER: char = '→';
+
+/// What a trailing space is drawn as when whitespace is shown.
+const SPACE_MARKER: &str = "·";
+
 /// Syntax highlighter for diff content.
 ///
 /// This struct is immutable and can be shared. Use `for_file()` to create
@@ -31,7 +50,7 @@ impl Highlighter {
         // TODO: Support theme selection (env var GITREVIEW_THEME or --theme flag)
         let theme = theme_set
             .themes
-            .get("base16-ocean.dark")
+            .get(THEME_NAME)
             .or_else(|| theme_set.themes.values().next())
             .cloned()
             .unwrap_or_default();
@@ -58,6 +77,15 @@ impl Highlighter {
         FileHighlighter::new(&self.syntax_set, &self.theme, file_ext)
     }
 
+    /// Highlight every line of a hunk's content.
+    pub fn highlight_hunk(&self, file_ext: &str, content: &str) -> HighlightedLines {
+        let mut fh = self.for_file(file_ext);
+        content
+            .lines()
+            .map(|line| fh.highlight_diff_line(line))
+            .collect()
+    }
+
     /// Convert syntect Color to ratatui Color.
     fn syntect_to_ratatui(color: SyntectColor) -> Color {
         Color::Rgb(color.r, color.g, color.b)
@@ -79,6 +107,195 @@ pub struct FileHighlighter<'a> {
     syntax_set: &'a SyntaxSet,
 }
 
+/// A highlighting request for the background worker.
+struct Job {
+    key: CacheKey,
+    file_ext: String,
+    content: String,
+}
+
+/// Hunk content hash plus the theme it was highlighted with.
+type CacheKey = (String, &'static str);
+
+/// Highlighted hunks, keyed by content hash and theme, filled by a worker thread.
+///
+/// Rendering reads finished entries with [`HighlightCache::get`]; the event loop
+/// asks for upcoming hunks with [`HighlightCache::request`] and picks up results
+/// with [`HighlightCache::collect`], so moving to a hunk doesn't re-highlight it.
+pub struct HighlightCache {
+    entries: HashMap<CacheKey, HighlightedLines>,
+    /// Insertion order, for evicting the oldest entries.
+    order: VecDeque<CacheKey>,
+    pending: HashSet<CacheKey>,
+    jobs: Sender<Job>,
+    results: Receiver<(CacheKey, HighlightedLines)>,
+}
+
+impl HighlightCache {
+    /// Start a cache backed by a worker thread sharing `highlighter`.
+    ///
+    /// The worker exits when the cache is dropped.
+    pub fn new(highlighter: Arc<Highlighter>) -> Self {
+        let (jobs, job_rx) = mpsc::channel::<Job>();
+        let (result_tx, results) = mpsc::channel();
+        std::thread::spawn(move || {
+            for job in job_rx {
+                let lines = highlighter.highlight_hunk(&job.file_ext, &job.content);
+                if result_tx.send((job.key, lines)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+            pending: HashSet::new(),
+            jobs,
+            results,
+        }
+    }
+
+    /// Highlighted lines for a hunk, if they are ready.
+    pub fn get(&self, content_hash: &str) -> Option<&HighlightedLines> {
+        self.entries.get(&(content_hash.to_string(), THEME_NAME))
+    }
+
+    /// Queue a hunk for background highlighting unless it is cached or queued already.
+    pub fn request(&mut self, content_hash: &str, file_ext: &str, content: &str) {
+        let key = (content_hash.to_string(), THEME_NAME);
+        if self.entries.contains_key(&key) || self.pending.contains(&key) {
+            return;
+        }
+        let job = Job {
+            key: key.clone(),
+            file_ext: file_ext.to_string(),
+            content: content.to_string(),
+        };
+        if self.jobs.send(job).is_ok() {
+            self.pending.insert(key);
+        }
+    }
+
+    /// Move finished results from the worker into the cache.
+    pub fn collect(&mut self) {
+        while let Ok((key, lines)) = self.results.try_recv() {
+            self.pending.remove(&key);
+            if self.order.len() >= CACHE_CAPACITY
+                && let Some(oldest) = self.order.pop_front()
+            {
+                self.entries.remove(&oldest);
+            }
+            self.order.push_back(key.clone());
+            self.entries.insert(key, lines);
+        }
+    }
+}
+
+/// Color a diff line by its prefix only, without syntax highlighting.
+///
+/// Used for lines that are not on screen yet, so huge hunks don't pay for
+/// highlighting everything up front.
+pub fn plain_diff_line(line: &str) -> Vec<Span<'static>> {
+    let color = if line.starts_with('+') {
+        Color::Green
+    } else if line.starts_with('-') {
+        Color::Red
+    } else {
+        Color::Reset
+    };
+    vec![Span::styled(line.to_string(), Style::default().fg(color))]
+}
+
+/// Color a moved line of a diff (see [`crate::moved`]) like git's
+/// `--color-moved`: magenta where it was removed, cyan where it was added.
+pub fn moved_diff_line(line: &str) -> Vec<Span<'static>> {
+    let color = if line.starts_with('-') {
+        Color::Magenta
+    } else {
+        Color::Cyan
+    };
+    vec![Span::styled(
+        line.to_string(),
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    )]
+}
+
+/// Make tabs and trailing whitespace in a highlighted diff line visible.
+///
+/// Tabs become a gray arrow padded to the next tab stop of `layout`; trailing
+/// spaces and tabs are drawn on a red background. The leading `+`/`-`/` `
+/// diff marker is left alone.
+pub fn mark_whitespace(spans: Vec<Span<'static>>, layout: TextLayout) -> Vec<Span<'static>> {
+    let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+    let trailing_start = text.trim_end_matches([' ', '\t']).chars().count().max(1);
+
+    let mut marked = Vec::with_capacity(spans.len());
+    let mut index = 0;
+    // Tab stops count from the start of the code, after the diff marker
+    let mut column = 0;
+    for span in spans {
+        let mut run = String::new();
+        for c in span.content.chars() {
+            let trailing = index >= trailing_start;
+            if index > 0 && (c == '\t' || trailing) {
+                if !run.is_empty() {
+                    marked.push(Span::styled(std::mem::take(&mut run), span.style));
+                }
+                let width = layout.width_at(c, column);
+                let marker = if c == '\t' {
+                    format!("{:<width$}", TAB_MARKER)
+                } else {
+                    SPACE_MARKER.to_string()
+                };
+                let style = if trailing {
+                    span.style.bg(Color::Red)
+                } else {
+                    span.style.fg(Color::DarkGray)
+                };
+                marked.push(Span::styled(marker, style));
+                column += width;
+            } else {
+                run.push(c);
+                if index > 0 {
+                    column += layout.width_at(c, column);
+                }
+            }
+            index += 1;
+        }
+        if !run.is_empty() {
+            marked.push(Span::styled(run, span.style));
+        }
+    }
+    marked
+}
+
+/// Replace the tabs in a highlighted diff line with spaces up to the next
+/// tab stop of `layout`, counting from after the `+`/`-`/` ` diff marker.
+pub fn expand_tabs(spans: Vec<Span<'static>>, layout: TextLayout) -> Vec<Span<'static>> {
+    if !spans.iter().any(|span| span.content.contains('\t')) {
+        return spans;
+    }
+    let mut column = 0;
+    let mut marker = true;
+    spans
+        .into_iter()
+        .map(|span| {
+            let content = span.content.as_ref();
+            let split = match content.chars().next() {
+                Some(c) if marker => {
+                    marker = false;
+                    c.len_utf8()
+                }
+                _ => 0,
+            };
+            let (prefix, code) = content.split_at(split);
+            let expanded = format!("{}{}", prefix, layout.expand_tabs(code, &mut column));
+            Span::styled(expanded, span.style)
+        })
+        .collect()
+}
+
 impl<'a> FileHighlighter<'a> {
     /// Create a new FileHighlighter for a specific file extension.
     fn new(syntax_set: &'a SyntaxSet, theme: &'a Theme, file_ext: &str) -> Self {
@@ -205,6 +422,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn mark_whitespace_shows_tabs_and_trailing_spaces() {
+        let layout = TextLayout::new(4);
+        let spans = mark_whitespace(plain_diff_line("+\tlet x = 1;  "), layout);
+        let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, "+→   let x = 1;··");
+
+        assert_eq!(spans[1].content.as_ref(), "→   ");
+        assert_eq!(spans[1].style.fg, Some(Color::DarkGray));
+        assert_eq!(spans.last().unwrap().style.bg, Some(Color::Red));
+        // The diff marker and code keep their color
+        assert_eq!(spans[0].style.fg, Some(Color::Green));
+        assert_eq!(spans[2].content.as_ref(), "let x = 1;");
+
+        // A tab after code only pads to the next tab stop
+        let spans = mark_whitespace(plain_diff_line(" ab\tc"), TextLayout::new(8));
+        let text: String = spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, " ab→     c");
+    }
+
+    #[test]
+    fn mark_whitespace_leaves_clean_lines_alone() {
+        let layout = TextLayout::default();
+        assert_eq!(
+            mark_whitespace(plain_diff_line(" fn main() {}"), layout),
+            plain_diff_line(" fn main() {}")
+        );
+        // An empty context line is just the marker, not trailing whitespace
+        assert_eq!(
+            mark_whitespace(plain_diff_line(" "), layout),
+            plain_diff_line(" ")
+        );
+    }
+
+    #[test]
+    fn tabs_expand_to_tab_stops_after_the_diff_marker() {
+        let spans = vec![
+            Span::styled("+", Style::default().fg(Color::Green)),
+            Span::raw("日\t"),
+            Span::raw("x\ty"),
+        ];
+        let expanded = expand_tabs(spans, TextLayout::new(4));
+        let text: Vec<&str> = expanded.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(text, ["+", "日  ", "x   y"]);
+        assert_eq!(expanded[0].style.fg, Some(Color::Green));
+
+        let expanded = expand_tabs(plain_diff_line("-\tx"), TextLayout::new(2));
+        assert_eq!(expanded[0].content.as_ref(), "-  x");
+    }
+
     #[test]
     fn test_highlight_added_line() {
         let highlighter = Highlighter::new();
@@ -353,4 +620,32 @@ mod tests {
         assert!(spans1.len() > 1);
         assert!(spans2.len() > 1);
     }
+
+    #[test]
+    fn test_plain_diff_line_colors_by_prefix() {
+        assert_eq!(plain_diff_line("+added")[0].style.fg, Some(Color::Green));
+        assert_eq!(plain_diff_line("-removed")[0].style.fg, Some(Color::Red));
+        assert_eq!(plain_diff_line(" context")[0].style.fg, Some(Color::Reset));
+        assert_eq!(plain_diff_line("+added")[0].content, "+added");
+    }
+
+    #[test]
+    fn test_highlight_cache_fills_in_background() {
+        let mut cache = HighlightCache::new(Arc::new(Highlighter::new()));
+        let content = "+fn main() {}\n-fn old() {}\n";
+        assert!(cache.get("hash1").is_none());
+
+        cache.request("hash1", "rs", content);
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while cache.get("hash1").is_none() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            cache.collect();
+        }
+
+        let lines = cache
+            .get("hash1")
+            .expect("worker should highlight the hunk");
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0][0].content, "+");
+    }
 }
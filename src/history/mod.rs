@@ -0,0 +1,212 @@
+//! Review history (`history`, `H` in the TUI).
+//!
+//! Turns the event log into a timeline for retrospectives and audits. A run of
+//! status changes by one person on one branch becomes a single entry ("ana
+//! reviewed 12 hunk(s) in 3 files"), so bulk reviews don't drown out gate checks
+//! and merges. Merges are described with their archived review state.
+
+use crate::HunkStatus;
+use crate::state::{ArchiveRecord, EventKind, EventRecord};
+
+/// One line of the timeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineEntry {
+    /// Local time of the first event of the entry.
+    pub at: String,
+    pub branch: String,
+    pub text: String,
+}
+
+/// Timeline of `events` (oldest first), describing merges from `archived`.
+pub fn timeline(events: &[EventRecord], archived: &[ArchiveRecord]) -> Vec<TimelineEntry> {
+    let mut entries = Vec::new();
+    let mut i = 0;
+    while i < events.len() {
+        let event = &events[i];
+        let mut run = 1;
+        if let EventKind::Status(_) = event.kind {
+            while events.get(i + run).is_some_and(|next| {
+                next.kind == event.kind && next.branch == event.branch && next.actor == event.actor
+            }) {
+                run += 1;
+            }
+        }
+        entries.push(TimelineEntry {
+            at: event.at.clone(),
+            branch: event.branch.clone(),
+            text: describe(&events[i..i + run], archived),
+        });
+        i += run;
+    }
+    entries
+}
+
+/// Describe a run of events of one kind.
+fn describe(run: &[EventRecord], archived: &[ArchiveRecord]) -> String {
+    let event = &run[0];
+    let actor = event.actor.as_deref().unwrap_or("someone");
+    match event.kind {
+        EventKind::Status(status) => {
+            let (verb, outcome) = match status {
+                HunkStatus::Reviewed => ("reviewed", ""),
+                HunkStatus::NeedsChanges => ("flagged", " as needing changes"),
+                HunkStatus::Skimmed => ("skimmed", ""),
+                HunkStatus::Unreviewed => ("marked", " unreviewed"),
+                HunkStatus::Stale => ("marked", " stale"),
+            };
+            let hunks: usize = run.iter().map(|e| e.count).sum();
+            format!(
+                "{} {} {} hunk(s) in {}{}",
+                actor,
+                verb,
+                hunks,
+                files(run),
+                outcome
+            )
+        }
+        EventKind::Approved => match &event.file_path {
+            Some(path) => format!("{} approved {} hunk(s) in {}", actor, event.count, path),
+            None => format!("{} approved all {} remaining hunk(s)", actor, event.count),
+        },
+        EventKind::ApprovalUndone => match &event.file_path {
+            Some(path) => format!(
+                "{} undid the approval of {} hunk(s) in {}",
+                actor, event.count, path
+            ),
+            None => format!("{} undid the approval of {} hunk(s)", actor, event.count),
+        },
+        EventKind::Carried => format!(
+            "{} carried over {} review(s) from {}",
+            actor,
+            event.count,
+            event.detail.as_deref().unwrap_or("another range")
+        ),
+        EventKind::GatePassed | EventKind::GateFailed => {
+            let outcome = if event.kind == EventKind::GatePassed {
+                "passed"
+            } else {
+                "failed"
+            };
+            match &event.detail {
+                Some(summary) => format!("gate check {} ({})", outcome, summary),
+                None => format!("gate check {}", outcome),
+            }
+        }
+        EventKind::Merged => {
+            let sha = event
+                .detail
+                .as_deref()
+                .map_or("an unknown commit".to_string(), |sha| {
+                    sha.chars().take(7).collect()
+                });
+            let record = archived
+                .iter()
+                .find(|r| r.base_ref == event.base_ref && r.merge_sha == event.detail);
+            match record {
+                Some(r) if !r.reviewers.is_empty() => format!(
+                    "merged in {}: {}/{} hunk(s) reviewed by {}",
+                    sha,
+                    r.reviewed,
+                    r.hunks,
+                    r.reviewers.join(", ")
+                ),
+                Some(r) => format!(
+                    "merged in {}: {}/{} hunk(s) reviewed",
+                    sha, r.reviewed, r.hunks
+                ),
+                None => format!("merged in {}: {} hunk(s) archived", sha, event.count),
+            }
+        }
+    }
+}
+
+/// The files of a run of events: their names, or how many when there are several.
+fn files(run: &[EventRecord]) -> String {
+    let mut paths: Vec<&str> = Vec::new();
+    for path in run.iter().filter_map(|e| e.file_path.as_deref()) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    match paths.len() {
+        0 => "unknown files".to_string(),
+        1..=3 => paths.join(", "),
+        n => format!("{} files", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventKind, file: Option<&str>, actor: &str, at: &str) -> EventRecord {
+        EventRecord {
+            branch: "feature".to_string(),
+            base_ref: "abc..feature".to_string(),
+            kind,
+            file_path: file.map(str::to_string),
+            count: 1,
+            detail: None,
+            actor: Some(actor.to_string()),
+            at: at.to_string(),
+        }
+    }
+
+    #[test]
+    fn runs_of_status_changes_collapse_into_one_entry() {
+        let reviewed = EventKind::Status(HunkStatus::Reviewed);
+        let mut gate = event(EventKind::GateFailed, None, "ana", "10:05");
+        gate.detail = Some("2/3 hunks reviewed".to_string());
+        let mut merged = event(EventKind::Merged, None, "bo", "10:20");
+        merged.detail = Some("0123456789".to_string());
+        let events = [
+            event(reviewed, Some("a.rs"), "ana", "10:00"),
+            event(reviewed, Some("a.rs"), "ana", "10:01"),
+            event(reviewed, Some("b.rs"), "ana", "10:02"),
+            gate,
+            event(reviewed, Some("c.rs"), "bo", "10:10"),
+            event(
+                EventKind::Status(HunkStatus::NeedsChanges),
+                Some("c.rs"),
+                "bo",
+                "10:11",
+            ),
+            merged,
+        ];
+        let archived = [ArchiveRecord {
+            base_ref: "abc..feature".to_string(),
+            merge_sha: Some("0123456789".to_string()),
+            archived_at: "2026-10-17 10:20:00".to_string(),
+            hunks: 4,
+            reviewed: 3,
+            reviewers: vec!["bo".to_string(), "ana".to_string()],
+        }];
+
+        let texts: Vec<_> = timeline(&events, &archived)
+            .into_iter()
+            .map(|entry| (entry.at, entry.text))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                (
+                    "10:00".into(),
+                    "ana reviewed 3 hunk(s) in a.rs, b.rs".into()
+                ),
+                (
+                    "10:05".into(),
+                    "gate check failed (2/3 hunks reviewed)".into()
+                ),
+                ("10:10".into(), "bo reviewed 1 hunk(s) in c.rs".into()),
+                (
+                    "10:11".into(),
+                    "bo flagged 1 hunk(s) in c.rs as needing changes".into()
+                ),
+                (
+                    "10:20".into(),
+                    "merged in 0123456: 3/4 hunk(s) reviewed by bo, ana".into()
+                ),
+            ]
+        );
+    }
+}
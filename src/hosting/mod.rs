@@ -0,0 +1,253 @@
+//! Pull and merge request pages of hosted repositories (`open`, `o` in the dashboard).
+//!
+//! The host is recognized from the branch's upstream remote URL, so no API
+//! token is needed. The page opened is the one that creates the request: when
+//! one already exists for the branch, GitHub, GitLab, Bitbucket, and Gitea all
+//! link to it from there.
+
+use crate::git::{self, GitError};
+use std::io;
+use std::process::{Command, Stdio};
+use thiserror::Error;
+
+/// Errors that can occur while finding a branch's pull request page.
+#[derive(Debug, Error)]
+pub enum HostingError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error("{0} has no upstream; push it with `git push -u`")]
+    NoUpstream(String),
+    #[error("remote {0} does not exist")]
+    NoRemote(String),
+    #[error("not a known host (GitHub, GitLab, Bitbucket, Gitea): {0}")]
+    UnknownHost(String),
+}
+
+/// Kind of code host, which decides the shape of its URLs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    /// Bitbucket Cloud.
+    Bitbucket,
+    /// Gitea and Forgejo, including Codeberg.
+    Gitea,
+}
+
+/// A repository on a code host.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostedRepo {
+    pub forge: Forge,
+    /// Web address of the repository, e.g. `https://github.com/owner/repo`.
+    pub web_url: String,
+}
+
+impl HostedRepo {
+    /// Recognize a remote URL in any of git's spellings: `git@host:owner/repo.git`,
+    /// `ssh://git@host:22/owner/repo`, or `https://host/owner/repo.git`.
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let (scheme, host, path) = match url.split_once("://") {
+            Some((scheme, rest)) => {
+                let (host, path) = rest.split_once('/')?;
+                (scheme, host, path)
+            }
+            None => {
+                let (host, path) = url.split_once(':')?;
+                ("ssh", host, path)
+            }
+        };
+        // Drop the user, and the port unless the remote is the web server itself
+        let host = host.rsplit('@').next()?.to_ascii_lowercase();
+        let (web_scheme, web_host) = match scheme {
+            "http" | "https" => (scheme, host.as_str()),
+            _ => ("https", host.split(':').next()?),
+        };
+        let path = path.trim_matches('/');
+        let path = path.strip_suffix(".git").unwrap_or(path);
+        if web_host.is_empty() || !path.contains('/') {
+            return None;
+        }
+
+        let forge = if host.contains("github") {
+            Forge::GitHub
+        } else if host.contains("gitlab") {
+            Forge::GitLab
+        } else if host.contains("bitbucket") {
+            Forge::Bitbucket
+        } else if ["gitea", "forgejo", "codeberg"]
+            .iter()
+            .any(|name| host.contains(name))
+        {
+            Forge::Gitea
+        } else {
+            return None;
+        };
+        Some(Self {
+            forge,
+            web_url: format!("{}://{}/{}", web_scheme, web_host, path),
+        })
+    }
+
+    /// Page of the pull request merging `branch` into `base`.
+    pub fn review_url(&self, branch: &str, base: &str) -> String {
+        let (branch, base) = (encode(branch), encode(base));
+        match self.forge {
+            Forge::GitHub | Forge::Gitea => {
+                format!("{}/compare/{}...{}", self.web_url, base, branch)
+            }
+            Forge::GitLab => format!(
+                "{}/-/merge_requests/new?merge_request%5Bsource_branch%5D={}&merge_request%5Btarget_branch%5D={}",
+                self.web_url, branch, base
+            ),
+            Forge::Bitbucket => format!(
+                "{}/pull-requests/new?source={}&dest={}",
+                self.web_url, branch, base
+            ),
+        }
+    }
+}
+
+/// Page of the pull request merging `branch` into `base`, found through the
+/// branch's upstream. Remote-tracking branches (`origin/feature`) are their
+/// own upstream.
+pub fn review_url(branch: &str, base: &str) -> Result<String, HostingError> {
+    let (remote, name) = match git::branch_upstream(branch)? {
+        Some(upstream) => upstream,
+        // A local branch may have slashes too, so only split remote-tracking ones
+        None => match branch.split_once('/') {
+            Some((remote, name))
+                if git::resolve_ref(&format!("refs/remotes/{}", branch)).is_ok() =>
+            {
+                (remote.to_string(), name.to_string())
+            }
+            _ => return Err(HostingError::NoUpstream(branch.to_string())),
+        },
+    };
+    let url = git::remote_url(&remote)?.ok_or(HostingError::NoRemote(remote))?;
+    let repo = HostedRepo::from_remote_url(&url).ok_or(HostingError::UnknownHost(url))?;
+    Ok(repo.review_url(&name, base))
+}
+
+/// Open `url` in the default browser (`$BROWSER` if set).
+pub fn open_in_browser(url: &str) -> io::Result<()> {
+    let mut command = match std::env::var("BROWSER") {
+        Ok(browser) if !browser.is_empty() => Command::new(browser),
+        _ if cfg!(target_os = "macos") => Command::new("open"),
+        // Not `cmd /C start`, which would treat `&` in the URL as a command separator
+        _ if cfg!(windows) => {
+            let mut command = Command::new("rundll32");
+            command.arg("url.dll,FileProtocolHandler");
+            command
+        }
+        _ => Command::new("xdg-open"),
+    };
+    // Keep the browser's chatter off the terminal (and the TUI)
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(drop)
+}
+
+/// Percent-encode a branch name for a URL, keeping `/` separators.
+fn encode(branch: &str) -> String {
+    let mut encoded = String::new();
+    for byte in branch.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_urls_of_known_hosts_become_web_urls() {
+        let repo = |url| HostedRepo::from_remote_url(url).map(|r| (r.forge, r.web_url));
+        let github = Some((Forge::GitHub, "https://github.com/ana/app".to_string()));
+        assert_eq!(repo("git@github.com:ana/app.git"), github);
+        assert_eq!(repo("https://github.com/ana/app"), github);
+        assert_eq!(repo("ssh://git@github.com:22/ana/app.git/"), github);
+        assert_eq!(
+            repo("https://bo@gitlab.example.com/group/sub/app.git"),
+            Some((
+                Forge::GitLab,
+                "https://gitlab.example.com/group/sub/app".to_string()
+            ))
+        );
+        assert_eq!(
+            repo("git@bitbucket.org:team/app.git"),
+            Some((
+                Forge::Bitbucket,
+                "https://bitbucket.org/team/app".to_string()
+            ))
+        );
+        assert_eq!(
+            repo("http://gitea.local:3000/ana/app.git").map(|(_, url)| url),
+            Some("http://gitea.local:3000/ana/app".to_string())
+        );
+        assert_eq!(repo("git@example.com:ana/app.git"), None);
+        assert_eq!(repo("/srv/git/app.git"), None);
+    }
+
+    #[test]
+    fn review_urls_name_the_branch_and_its_base() {
+        let repo = |forge| HostedRepo {
+            forge,
+            web_url: "https://host/ana/app".to_string(),
+        };
+        assert_eq!(
+            repo(Forge::GitHub).review_url("fix/naïve", "main"),
+            "https://host/ana/app/compare/main...fix/na%C3%AFve"
+        );
+        assert_eq!(
+            repo(Forge::GitLab).review_url("fix", "main"),
+            "https://host/ana/app/-/merge_requests/new?merge_request%5Bsource_branch%5D=fix&merge_request%5Btarget_branch%5D=main"
+        );
+        assert_eq!(
+            repo(Forge::Bitbucket).review_url("fix", "main"),
+            "https://host/ana/app/pull-requests/new?source=fix&dest=main"
+        );
+    }
+
+    #[test]
+    fn slashed_local_branches_are_not_taken_for_remote_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            assert!(
+                Command::new("git")
+                    .args(args)
+                    .current_dir(dir.path())
+                    .output()
+                    .unwrap()
+                    .status
+                    .success()
+            );
+        };
+        git(&["init", "-q"]);
+        git(&["remote", "add", "feature", "https://github.com/ana/app.git"]);
+        git(&[
+            "-c",
+            "user.name=A",
+            "-c",
+            "user.email=a@b",
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "x",
+        ]);
+        git(&["branch", "feature/x"]);
+
+        let url = git::in_repo(Some(dir.path()), || review_url("feature/x", "main"));
+        assert!(matches!(url, Err(HostingError::NoUpstream(_))), "{:?}", url);
+    }
+}
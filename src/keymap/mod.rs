@@ -0,0 +1,1105 @@
+//! Key bindings of the TUI.
+//!
+//! Every key the TUI reacts to outside text prompts is bound to an [`Action`]
+//! here, including the keys of panes such as the help and the check output,
+//! and the `?` help is generated from the same [`Keymap`], so it lists the
+//! keys that actually work. The `[keys]` section of `.git-review.local.toml`
+//! rebinds actions by name:
+//!
+//! ```toml
+//! [keys]
+//! toggle_reviewed = ["Space", "r"]
+//! show_all = []  # unbound
+//! ```
+//!
+//! A rebound key is taken away from whatever action it was bound to by default
+//! in the same view.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Screens of the TUI with their own key bindings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum View {
+    Dashboard,
+    Repos,
+    HunkReview,
+    /// Panes over any screen: help, check output, timeline, related hunks.
+    Pane,
+}
+
+/// Groups of actions in the help, in the order they are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Category {
+    Navigation,
+    Actions,
+    Bulk,
+    Batch,
+    Filters,
+    Panes,
+    Other,
+}
+
+impl Category {
+    /// Heading of the category in the help.
+    pub fn title(self) -> &'static str {
+        match self {
+            Category::Navigation => "Navigation",
+            Category::Actions => "Actions",
+            Category::Bulk => "Bulk Actions",
+            Category::Batch => "Batch (marked branches, or the selected one)",
+            Category::Filters => "Filters",
+            Category::Panes => "Panes (help, check output, timeline, related hunks)",
+            Category::Other => "Other",
+        }
+    }
+}
+
+/// Something a key does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Help,
+    Quit,
+    Back,
+    Next,
+    Previous,
+    NextFile,
+    PreviousFile,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    ScrollTop,
+    ScrollBottom,
+    ScrollLeft,
+    ScrollRight,
+    Wrap,
+    Related,
+    Open,
+    Refresh,
+    ToggleReviewed,
+    NeedsChanges,
+    Skimmed,
+    Bookmark,
+    Check,
+    Difftool,
+    Stat,
+    Comment,
+    Preview,
+    Whitespace,
+    Blame,
+    LineHistory,
+    Timeline,
+    HunkList,
+    PriorityOrder,
+    Condensed,
+    Zen,
+    ShrinkFileList,
+    GrowFileList,
+    Commit,
+    ApproveFile,
+    ApproveAll,
+    ApprovePattern,
+    UndoApproval,
+    ShowUnreviewed,
+    ShowStale,
+    ShowSkimmed,
+    ShowBookmarked,
+    ShowAll,
+    FilterPattern,
+    Command,
+    Merge,
+    Untouched,
+    Queue,
+    OpenPullRequest,
+    Mark,
+    DeleteMerged,
+    ClearMarks,
+}
+
+/// A default key binding.
+#[derive(Debug, Clone, Copy)]
+pub struct Binding {
+    pub view: View,
+    pub category: Category,
+    pub action: Action,
+    /// Name of the action in the `[keys]` section.
+    pub name: &'static str,
+    pub keys: &'static [&'static str],
+    pub description: &'static str,
+}
+
+const fn bind(
+    view: View,
+    category: Category,
+    action: Action,
+    name: &'static str,
+    keys: &'static [&'static str],
+    description: &'static str,
+) -> Binding {
+    Binding {
+        view,
+        category,
+        action,
+        name,
+        keys,
+        description,
+    }
+}
+
+use Category::{Actions, Batch, Bulk, Filters, Navigation, Other, Panes};
+use View::{Dashboard, HunkReview, Pane, Repos};
+
+/// Every action of every view with its default keys, in help order.
+pub const BINDINGS: &[Binding] = &[
+    bind(
+        Dashboard,
+        Navigation,
+        Action::Next,
+        "next_branch",
+        &["j", "Down"],
+        "Next branch",
+    ),
+    bind(
+        Dashboard,
+        Navigation,
+        Action::Previous,
+        "previous_branch",
+        &["k", "Up"],
+        "Previous branch",
+    ),
+    bind(
+        Dashboard,
+        Actions,
+        Action::Open,
+        "open",
+        &["Enter"],
+        "Review selected branch",
+    ),
+    bind(
+        Dashboard,
+        Actions,
+        Action::Merge,
+        "merge",
+        &["M"],
+        "Merge selected branch",
+    ),
+    bind(
+        Dashboard,
+        Actions,
+        Action::Refresh,
+        "refresh",
+        &["r"],
+        "Refresh branch list (marked branches if any)",
+    ),
+    bind(
+        Dashboard,
+        Actions,
+        Action::Untouched,
+        "untouched",
+        &["u"],
+        "Only branches you haven't reviewed (again for all)",
+    ),
+    bind(
+        Dashboard,
+        Actions,
+        Action::Queue,
+        "queue",
+        &["Q"],
+        "Queue for merge once fully reviewed (again to unqueue)",
+    ),
+    bind(
+        Dashboard,
+        Actions,
+        Action::Timeline,
+        "timeline",
+        &["H"],
+        "Show the selected branch's review history",
+    ),
+    bind(
+        Dashboard,
+        Actions,
+        Action::OpenPullRequest,
+        "open_pull_request",
+        &["o"],
+        "Open the branch's pull request page in the browser",
+    ),
+    bind(
+        Dashboard,
+        Batch,
+        Action::Mark,
+        "mark",
+        &["Space"],
+        "Mark/unmark branch",
+    ),
+    bind(
+        Dashboard,
+        Batch,
+        Action::ApproveAll,
+        "approve_all",
+        &["A"],
+        "Approve all hunks",
+    ),
+    bind(
+        Dashboard,
+        Batch,
+        Action::DeleteMerged,
+        "delete_merged",
+        &["D"],
+        "Delete merged branches (all merged if none marked)",
+    ),
+    bind(
+        Dashboard,
+        Batch,
+        Action::ClearMarks,
+        "clear_marks",
+        &["Esc"],
+        "Clear marks (quit when none are marked)",
+    ),
+    bind(
+        Dashboard,
+        Other,
+        Action::Help,
+        "help",
+        &["?"],
+        "Show this help",
+    ),
+    bind(Dashboard, Other, Action::Quit, "quit", &["q"], "Quit"),
+    bind(
+        Repos,
+        Navigation,
+        Action::Next,
+        "next_branch",
+        &["j", "Down"],
+        "Next branch",
+    ),
+    bind(
+        Repos,
+        Navigation,
+        Action::Previous,
+        "previous_branch",
+        &["k", "Up"],
+        "Previous branch",
+    ),
+    bind(
+        Repos,
+        Actions,
+        Action::Open,
+        "open",
+        &["Enter"],
+        "Review selected branch (Esc returns here)",
+    ),
+    bind(
+        Repos,
+        Actions,
+        Action::Refresh,
+        "refresh",
+        &["r"],
+        "Reload all repositories",
+    ),
+    bind(Repos, Other, Action::Help, "help", &["?"], "Show this help"),
+    bind(Repos, Other, Action::Quit, "quit", &["q", "Esc"], "Quit"),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::Next,
+        "next_hunk",
+        &["j", "Down"],
+        "Next hunk",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::Previous,
+        "previous_hunk",
+        &["k", "Up"],
+        "Previous hunk",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::NextFile,
+        "next_file",
+        &["Tab"],
+        "Next file",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::PreviousFile,
+        "previous_file",
+        &["Shift+Tab"],
+        "Previous file",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::ScrollDown,
+        "scroll_down",
+        &["Ctrl+d"],
+        "Scroll down",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::ScrollUp,
+        "scroll_up",
+        &["Ctrl+u"],
+        "Scroll up",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::PageDown,
+        "page_down",
+        &["PgDn"],
+        "Scroll down a page",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::PageUp,
+        "page_up",
+        &["PgUp"],
+        "Scroll up a page",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::ScrollTop,
+        "scroll_top",
+        &["Home"],
+        "Scroll to the top of the hunk",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::ScrollBottom,
+        "scroll_bottom",
+        &["End"],
+        "Scroll to the bottom of the hunk",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::ScrollLeft,
+        "scroll_left",
+        &["Left"],
+        "Scroll long lines left (when not wrapping)",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::ScrollRight,
+        "scroll_right",
+        &["Right"],
+        "Scroll long lines right (when not wrapping)",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::Related,
+        "related",
+        &["*"],
+        "List hunks touching the same symbols (Enter jumps)",
+    ),
+    bind(
+        HunkReview,
+        Navigation,
+        Action::Back,
+        "back",
+        &["Esc", "Backspace"],
+        "Back to the dashboard (quit when opened directly)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::ToggleReviewed,
+        "toggle_reviewed",
+        &["Space"],
+        "Toggle reviewed status",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::NeedsChanges,
+        "needs_changes",
+        &["x"],
+        "Flag hunk as needing changes (fails the gate)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Skimmed,
+        "skimmed",
+        &["m"],
+        "Mark hunk as skimmed (a full review can follow)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Bookmark,
+        "bookmark",
+        &["B"],
+        "Bookmark hunk to come back to (press again to clear)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Check,
+        "check",
+        &["!"],
+        "Run the [check] command for the current file",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Difftool,
+        "difftool",
+        &["o"],
+        "Open the current file in git difftool",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Stat,
+        "stat",
+        &["g"],
+        "Toggle diff-stat overview",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Comment,
+        "comment",
+        &["c"],
+        "Comment on current hunk",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Preview,
+        "preview",
+        &["p"],
+        "Full-resolution image preview",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Whitespace,
+        "whitespace",
+        &["w"],
+        "Show tabs and trailing whitespace",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Wrap,
+        "wrap",
+        &["W"],
+        "Toggle wrapping of long lines (off keeps the diff aligned)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Blame,
+        "blame",
+        &["b"],
+        "Show who last touched each line (git blame)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::LineHistory,
+        "line_history",
+        &["L"],
+        "Show the history of the hunk's lines (git log -L)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Timeline,
+        "timeline",
+        &["H"],
+        "Show the branch's review history (reviews, gate checks, merges)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::HunkList,
+        "hunk_list",
+        &["h"],
+        "Toggle hunk list (1-9 jumps to a hunk, ← N: uses hunk N)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::PriorityOrder,
+        "priority_order",
+        &["P"],
+        "Toggle priority order (sensitive files, non-tests, large changes first)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Condensed,
+        "condensed",
+        &["z"],
+        "Condensed view (fold reviewed hunks)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Zen,
+        "zen",
+        &["Z"],
+        "Zen mode (hide the file list, the diff takes the full width)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::ShrinkFileList,
+        "shrink_file_list",
+        &["<"],
+        "Narrow the file list (saved to .git-review.local.toml)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::GrowFileList,
+        "grow_file_list",
+        &[">"],
+        "Widen the file list (saved to .git-review.local.toml)",
+    ),
+    bind(
+        HunkReview,
+        Actions,
+        Action::Commit,
+        "commit",
+        &["C"],
+        "Commit (when the review gate passes)",
+    ),
+    bind(
+        HunkReview,
+        Bulk,
+        Action::ApproveFile,
+        "approve_file",
+        &["F"],
+        "Approve all hunks in current file",
+    ),
+    bind(
+        HunkReview,
+        Bulk,
+        Action::ApproveAll,
+        "approve_all",
+        &["A"],
+        "Approve all hunks in all files",
+    ),
+    bind(
+        HunkReview,
+        Bulk,
+        Action::ApprovePattern,
+        "approve_pattern",
+        &["R"],
+        "Approve hunks whose changes match a regex",
+    ),
+    bind(
+        HunkReview,
+        Bulk,
+        Action::UndoApproval,
+        "undo_approval",
+        &["U"],
+        "Undo the last bulk approval",
+    ),
+    bind(
+        HunkReview,
+        Filters,
+        Action::ShowUnreviewed,
+        "show_unreviewed",
+        &["u"],
+        "Show unreviewed hunks only",
+    ),
+    bind(
+        HunkReview,
+        Filters,
+        Action::ShowStale,
+        "show_stale",
+        &["s"],
+        "Show stale hunks only",
+    ),
+    bind(
+        HunkReview,
+        Filters,
+        Action::ShowSkimmed,
+        "show_skimmed",
+        &["S"],
+        "Show skimmed hunks only (second pass)",
+    ),
+    bind(
+        HunkReview,
+        Filters,
+        Action::ShowBookmarked,
+        "show_bookmarked",
+        &["'"],
+        "Show bookmarked hunks only",
+    ),
+    bind(
+        HunkReview,
+        Filters,
+        Action::ShowAll,
+        "show_all",
+        &["a"],
+        "Show all hunks",
+    ),
+    bind(
+        HunkReview,
+        Filters,
+        Action::FilterPattern,
+        "filter_pattern",
+        &["f"],
+        "Filter by regex on hunk content or path (empty clears)",
+    ),
+    bind(
+        HunkReview,
+        Filters,
+        Action::Command,
+        "command",
+        &[":"],
+        "Run a command (listed under Commands)",
+    ),
+    bind(
+        HunkReview,
+        Other,
+        Action::Help,
+        "help",
+        &["?"],
+        "Show this help",
+    ),
+    bind(HunkReview, Other, Action::Quit, "quit", &["q"], "Quit"),
+    bind(
+        Pane,
+        Panes,
+        Action::Next,
+        "pane_down",
+        &["j", "Down"],
+        "Scroll down, or select the next related hunk",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::Previous,
+        "pane_up",
+        &["k", "Up"],
+        "Scroll up, or select the previous related hunk",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::PageDown,
+        "pane_page_down",
+        &["PgDn"],
+        "Scroll down a page",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::PageUp,
+        "pane_page_up",
+        &["PgUp"],
+        "Scroll up a page",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::ScrollTop,
+        "pane_top",
+        &["g", "Home"],
+        "Scroll to the top",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::ScrollBottom,
+        "pane_bottom",
+        &["G", "End"],
+        "Scroll to the end (check output: follow new output)",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::Open,
+        "pane_open",
+        &["Enter"],
+        "Jump to the selected related hunk",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::FilterPattern,
+        "pane_search",
+        &["/"],
+        "Search the help",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::Check,
+        "pane_rerun",
+        &["!"],
+        "Run the check command again",
+    ),
+    bind(
+        Pane,
+        Panes,
+        Action::Back,
+        "pane_close",
+        &["Esc", "q"],
+        "Close the pane (any unbound key closes the help)",
+    ),
+];
+
+/// A named key, without modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyName {
+    Char(char),
+    Enter,
+    Esc,
+    Tab,
+    BackTab,
+    Backspace,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+}
+
+/// A key press: `j`, `Shift+Tab`, `Ctrl+d`.
+///
+/// Shifted letters are their uppercase [`KeyName::Char`] and `Shift+Tab` is
+/// [`KeyName::BackTab`], as terminals report them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub name: KeyName,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Key {
+    /// A key pressed without Ctrl or Alt.
+    pub fn plain(name: KeyName) -> Self {
+        Self {
+            name,
+            ctrl: false,
+            alt: false,
+        }
+    }
+}
+
+impl FromStr for Key {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        // The last `+` separates the key, unless the key is `+` itself
+        let (modifiers, name) = match s.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None if s == "+" => ("", "+"),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let (mut ctrl, mut alt, mut shift) = (false, false, false);
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" => ctrl = true,
+                "alt" => alt = true,
+                "shift" => shift = true,
+                _ => return Err(()),
+            }
+        }
+        let name = match name.to_ascii_lowercase().as_str() {
+            "space" => KeyName::Char(' '),
+            "enter" => KeyName::Enter,
+            "esc" => KeyName::Esc,
+            "tab" if shift => KeyName::BackTab,
+            "tab" => KeyName::Tab,
+            "backspace" => KeyName::Backspace,
+            "up" => KeyName::Up,
+            "down" => KeyName::Down,
+            "left" => KeyName::Left,
+            "right" => KeyName::Right,
+            "pgup" | "pageup" => KeyName::PageUp,
+            "pgdn" | "pagedown" => KeyName::PageDown,
+            "home" => KeyName::Home,
+            "end" => KeyName::End,
+            _ => {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) if shift && c.is_ascii_alphabetic() => {
+                        KeyName::Char(c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) => KeyName::Char(c),
+                    _ => return Err(()),
+                }
+            }
+        };
+        if shift && !matches!(name, KeyName::BackTab | KeyName::Char('A'..='Z')) {
+            return Err(());
+        }
+        Ok(Self { name, ctrl, alt })
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        match self.name {
+            KeyName::Char(' ') => write!(f, "Space"),
+            KeyName::Char(c) => write!(f, "{}", c),
+            KeyName::Enter => write!(f, "Enter"),
+            KeyName::Esc => write!(f, "Esc"),
+            KeyName::Tab => write!(f, "Tab"),
+            KeyName::BackTab => write!(f, "Shift+Tab"),
+            KeyName::Backspace => write!(f, "Backspace"),
+            KeyName::Up => write!(f, "Up"),
+            KeyName::Down => write!(f, "Down"),
+            KeyName::Left => write!(f, "Left"),
+            KeyName::Right => write!(f, "Right"),
+            KeyName::PageUp => write!(f, "PgUp"),
+            KeyName::PageDown => write!(f, "PgDn"),
+            KeyName::Home => write!(f, "Home"),
+            KeyName::End => write!(f, "End"),
+        }
+    }
+}
+
+/// Errors in the `[keys]` section.
+#[derive(Debug, Error)]
+pub enum KeymapError {
+    #[error("unknown action '{0}' in [keys]")]
+    UnknownAction(String),
+    #[error("invalid key '{key}' for {action} in [keys]")]
+    InvalidKey { action: String, key: String },
+    #[error("{key} is bound to both {first} and {second} in [keys]")]
+    Conflict {
+        key: String,
+        first: &'static str,
+        second: &'static str,
+    },
+}
+
+/// `[keys]` section of `.git-review.toml`: action names and the keys that
+/// replace their default ones.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct KeysConfig(pub BTreeMap<String, Vec<String>>);
+
+impl KeysConfig {
+    /// The keymap with these bindings applied.
+    pub fn keymap(&self) -> Result<Keymap, KeymapError> {
+        Keymap::new(self)
+    }
+}
+
+/// An action in the help, with its current keys.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HelpEntry {
+    pub category: Category,
+    pub name: &'static str,
+    /// Empty when the action was unbound.
+    pub keys: Vec<Key>,
+    pub description: &'static str,
+}
+
+/// The bindings in effect: defaults with the `[keys]` section applied.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    entries: Vec<(View, Action, HelpEntry)>,
+    actions: HashMap<(View, Key), Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&KeysConfig::default()).expect("default key bindings conflict")
+    }
+}
+
+impl Keymap {
+    /// Apply `config` on top of the default bindings.
+    pub fn new(config: &KeysConfig) -> Result<Self, KeymapError> {
+        let mut rebound: HashMap<&str, Vec<Key>> = HashMap::new();
+        for (name, keys) in &config.0 {
+            if !BINDINGS.iter().any(|b| b.name == name) {
+                return Err(KeymapError::UnknownAction(name.clone()));
+            }
+            let keys = keys
+                .iter()
+                .map(|key| {
+                    key.parse().map_err(|()| KeymapError::InvalidKey {
+                        action: name.clone(),
+                        key: key.clone(),
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+            rebound.insert(name, keys);
+        }
+
+        // Keys rebound in a view are taken from the actions they defaulted to
+        let taken: HashSet<(View, Key)> = BINDINGS
+            .iter()
+            .filter_map(|b| rebound.get(b.name).map(|keys| (b.view, keys)))
+            .flat_map(|(view, keys)| keys.iter().map(move |&key| (view, key)))
+            .collect();
+
+        let mut entries = Vec::new();
+        let mut actions = HashMap::new();
+        let mut names: HashMap<(View, Key), &'static str> = HashMap::new();
+        for binding in BINDINGS {
+            let keys: Vec<Key> = match rebound.get(binding.name) {
+                Some(keys) => keys.clone(),
+                None => binding
+                    .keys
+                    .iter()
+                    .map(|key| key.parse().expect("invalid default key"))
+                    .filter(|&key| !taken.contains(&(binding.view, key)))
+                    .collect(),
+            };
+            for &key in &keys {
+                if let Some(first) = names.insert((binding.view, key), binding.name) {
+                    return Err(KeymapError::Conflict {
+                        key: key.to_string(),
+                        first,
+                        second: binding.name,
+                    });
+                }
+                actions.insert((binding.view, key), binding.action);
+            }
+            let entry = HelpEntry {
+                category: binding.category,
+                name: binding.name,
+                keys,
+                description: binding.description,
+            };
+            entries.push((binding.view, binding.action, entry));
+        }
+        Ok(Self { entries, actions })
+    }
+
+    /// The action `key` is bound to in `view`, if any.
+    pub fn action(&self, view: View, key: Key) -> Option<Action> {
+        self.actions.get(&(view, key)).copied()
+    }
+
+    /// The keys bound to `action` in `view`.
+    pub fn keys(&self, view: View, action: Action) -> &[Key] {
+        self.entries
+            .iter()
+            .find(|(v, a, _)| *v == view && *a == action)
+            .map(|(_, _, entry)| entry.keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The first key bound to `action` in `view`, for hints; `-` if unbound.
+    pub fn hint(&self, view: View, action: Action) -> String {
+        self.keys(view, action)
+            .first()
+            .map_or_else(|| "-".to_string(), Key::to_string)
+    }
+
+    /// The actions of `view` for the help, grouped by category.
+    pub fn help(&self, view: View) -> Vec<&HelpEntry> {
+        let mut entries: Vec<&HelpEntry> = self
+            .entries
+            .iter()
+            .filter(|(v, _, _)| *v == view)
+            .map(|(_, _, entry)| entry)
+            .collect();
+        entries.sort_by_key(|entry| entry.category);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(config: &str) -> Result<Keymap, KeymapError> {
+        toml::from_str::<KeysConfig>(config).unwrap().keymap()
+    }
+
+    #[test]
+    fn keys_parse_and_print_as_written() {
+        for key in [
+            "j",
+            "Space",
+            "Shift+Tab",
+            "Ctrl+d",
+            "Alt+Enter",
+            "PgDn",
+            "A",
+            "+",
+            "Ctrl++",
+        ] {
+            assert_eq!(key.parse::<Key>().unwrap().to_string(), key);
+        }
+        assert_eq!("shift+a".parse::<Key>(), "A".parse());
+        assert_eq!("PageDown".parse::<Key>(), "PgDn".parse());
+        for key in ["", "jj", "Hyper+j", "Shift+1"] {
+            assert!(key.parse::<Key>().is_err(), "{key}");
+        }
+    }
+
+    #[test]
+    fn rebinding_takes_the_key_from_its_default_action() {
+        let space = Key::plain(KeyName::Char(' '));
+        let a = Key::plain(KeyName::Char('a'));
+        let keymap = keys("toggle_reviewed = [\"a\"]").unwrap();
+        assert_eq!(
+            keymap.action(View::HunkReview, a),
+            Some(Action::ToggleReviewed)
+        );
+        assert_eq!(keymap.action(View::HunkReview, space), None);
+        assert!(keymap.keys(View::HunkReview, Action::ShowAll).is_empty());
+        // Other views keep their own bindings of the key
+        assert_eq!(keymap.action(View::Dashboard, space), Some(Action::Mark));
+
+        assert!(matches!(
+            keys("approve = [\"a\"]"),
+            Err(KeymapError::UnknownAction(_))
+        ));
+        assert!(matches!(
+            keys("show_all = [\"a\"]\nshow_stale = [\"a\"]"),
+            Err(KeymapError::Conflict { .. })
+        ));
+        assert!(
+            Keymap::default()
+                .help(View::HunkReview)
+                .iter()
+                .all(|e| !e.keys.is_empty())
+        );
+    }
+
+    #[test]
+    fn panes_scroll_and_close_through_the_keymap() {
+        let j = Key::plain(KeyName::Char('j'));
+        let q = Key::plain(KeyName::Char('q'));
+        assert_eq!(Keymap::default().action(View::Pane, j), Some(Action::Next));
+        assert_eq!(Keymap::default().action(View::Pane, q), Some(Action::Back));
+
+        let keymap = keys("pane_down = [\"n\"]").unwrap();
+        assert_eq!(keymap.action(View::Pane, j), None);
+        assert_eq!(
+            keymap.action(View::Pane, Key::plain(KeyName::Char('n'))),
+            Some(Action::Next)
+        );
+    }
+}
@@ -1,11 +1,48 @@
+pub mod accessible;
+pub mod ai;
+pub mod autoapprove;
+pub mod check;
+pub mod ci;
 pub mod cli;
+pub mod config;
 pub mod dashboard;
+pub mod difftastic;
+pub mod email;
+pub mod encryption;
+pub mod exit;
+pub mod filter;
 pub mod gate;
+pub mod gerrit;
 pub mod git;
+#[cfg(feature = "tui")]
 pub mod highlight;
+pub mod history;
+pub mod hosting;
+pub mod keymap;
+pub mod links;
+pub mod logging;
+pub mod moved;
+pub mod notes;
+pub mod notify;
+pub mod palette;
 pub mod parser;
+pub mod patch;
+pub mod preview;
+pub mod priority;
+pub mod prompt;
+pub mod queue;
+pub mod report;
+pub mod rpc;
+pub mod session;
+pub mod stage;
 pub mod state;
+pub mod step;
+pub mod symbols;
+pub mod textlayout;
+#[cfg(feature = "tui")]
 pub mod tui;
+pub mod verify;
+pub mod web;
 
 use std::path::PathBuf;
 
@@ -15,6 +52,11 @@ pub enum HunkStatus {
     Unreviewed,
     Reviewed,
     Stale,
+    /// Read and flagged as needing changes; fails the gate until resolved.
+    NeedsChanges,
+    /// Read quickly in a first pass; counts as reviewed only in paths the
+    /// gate's `skim` rules allow.
+    Skimmed,
 }
 
 /// A single diff hunk.
@@ -24,8 +66,10 @@ pub struct DiffHunk {
     pub old_count: u32,
     pub new_start: u32,
     pub new_count: u32,
-    pub content: String,
+    pub content: parser::HunkBody,
     pub content_hash: String,
+    /// Section heading git printed after the hunk header (e.g. the enclosing function), if any.
+    pub section: String,
     pub status: HunkStatus,
 }
 
@@ -43,6 +87,11 @@ pub struct ReviewProgress {
     pub reviewed: usize,
     pub unreviewed: usize,
     pub stale: usize,
+    pub needs_changes: usize,
+    /// Hunks skimmed but not yet reviewed (not included in `reviewed`).
+    pub skimmed: usize,
+    /// Reviewed hunks that an auto-approval rule approved (included in `reviewed`).
+    pub auto_approved: usize,
     pub files_remaining: usize,
     pub total_files: usize,
 }
@@ -0,0 +1,110 @@
+//! Clickable file links in terminal output.
+//!
+//! File paths are wrapped in OSC 8 hyperlinks, which supporting terminals
+//! (iTerm2, kitty, WezTerm, GNOME Terminal, Windows Terminal, ...) show as
+//! links and other terminals ignore. Links point at `file://` URLs by default,
+//! or at an editor URI set in the `[links]` section of `.git-review.toml`.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// URI used when `[links] uri` is not set.
+pub const DEFAULT_URI: &str = "file://{path}";
+
+/// `[links]` section of `.git-review.toml`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinksConfig {
+    /// Emit links; by default they are on when writing to a terminal
+    /// (other than `TERM=dumb`).
+    pub enabled: Option<bool>,
+    /// Link target with `{path}` (absolute, percent-encoded) and `{line}`
+    /// placeholders, e.g. `vscode://file{path}:{line}`.
+    pub uri: Option<String>,
+}
+
+impl LinksConfig {
+    /// Whether to emit links to an output that `is_terminal` or not.
+    pub fn enabled_for(&self, is_terminal: bool) -> bool {
+        self.enabled.unwrap_or_else(|| {
+            is_terminal && std::env::var("TERM").map_or(true, |term| term != "dumb")
+        })
+    }
+
+    /// Link target for `path` (absolute), at `line` or the first line.
+    pub fn url(&self, path: &Path, line: Option<u32>) -> String {
+        self.uri
+            .as_deref()
+            .unwrap_or(DEFAULT_URI)
+            .replace("{path}", &encode_path(&path.to_string_lossy()))
+            .replace("{line}", &line.unwrap_or(1).to_string())
+    }
+
+    /// `text` linked to `path` when links are enabled, else `text` unchanged.
+    pub fn link(&self, is_terminal: bool, path: &Path, line: Option<u32>, text: &str) -> String {
+        if self.enabled_for(is_terminal) {
+            hyperlink(&self.url(path, line), text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Wrap `text` in an OSC 8 hyperlink to `url`.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// Percent-encode a path for use in a URL, keeping `/` separators. Windows
+/// paths (`C:\dir\file`) become `/C:/dir/file`.
+fn encode_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut encoded = String::new();
+    if !path.starts_with('/') {
+        encoded.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_encode_paths_and_fill_in_the_line() {
+        let default = LinksConfig::default();
+        assert_eq!(
+            default.url(Path::new("/home/ana/my repo/a#b.rs"), Some(7)),
+            "file:///home/ana/my%20repo/a%23b.rs"
+        );
+        assert_eq!(
+            default.url(Path::new(r"C:\src\lib.rs"), None),
+            "file:///C:/src/lib.rs"
+        );
+
+        let editor = LinksConfig {
+            enabled: Some(true),
+            uri: Some("vscode://file{path}:{line}".to_string()),
+        };
+        assert_eq!(
+            editor.url(Path::new("/src/lib.rs"), Some(12)),
+            "vscode://file/src/lib.rs:12"
+        );
+        assert_eq!(
+            editor.link(false, Path::new("/a.rs"), None, "a.rs"),
+            "\x1b]8;;vscode://file/a.rs:1\x1b\\a.rs\x1b]8;;\x1b\\"
+        );
+        assert_eq!(
+            LinksConfig::default().link(false, Path::new("/a.rs"), None, "a.rs"),
+            "a.rs"
+        );
+    }
+}
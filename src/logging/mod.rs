@@ -0,0 +1,76 @@
+//! Diagnostic logging (`--verbose`, `--log-file`).
+//!
+//! git calls, database changes, and TUI events are instrumented with
+//! `tracing`. Nothing is printed by default; each `-v` shows more, so `-v`
+//! explains why a diff came back empty or a sync marked hunks stale, and
+//! `-vvv` follows every key press. Logs go to stderr, or to `--log-file` when
+//! the TUI owns the terminal.
+
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::level_filters::LevelFilter;
+
+/// Whether stderr logging is paused because the TUI is drawing on the terminal.
+static STDERR_PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Pause (or resume) logging to stderr, so log lines don't tear the TUI.
+/// Logs to a file are unaffected.
+pub fn pause_stderr(paused: bool) {
+    STDERR_PAUSED.store(paused, Ordering::Relaxed);
+}
+
+/// Most detailed level logged at a verbosity (the number of `-v` flags).
+pub fn level(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Install the global logger: to `log_file` (appended to) if given, else to
+/// stderr while the TUI is not running.
+pub fn init(verbosity: u8, log_file: Option<&Path>) -> io::Result<()> {
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level(verbosity))
+        .with_target(false);
+    // Only fails when a logger is already installed, which is fine
+    let _ = match log_file {
+        Some(path) => {
+            let file = OpenOptions::new().create(true).append(true).open(path)?;
+            builder
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+                .try_init()
+        }
+        None => builder
+            .with_ansi(io::stderr().is_terminal())
+            .with_writer(|| -> Box<dyn Write> {
+                if STDERR_PAUSED.load(Ordering::Relaxed) {
+                    Box::new(io::sink())
+                } else {
+                    Box::new(io::stderr())
+                }
+            })
+            .try_init(),
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn each_verbose_flag_logs_more() {
+        assert_eq!(level(0), LevelFilter::WARN);
+        assert_eq!(level(1), LevelFilter::INFO);
+        assert_eq!(level(2), LevelFilter::DEBUG);
+        assert_eq!(level(3), LevelFilter::TRACE);
+        assert_eq!(level(9), LevelFilter::TRACE);
+    }
+}
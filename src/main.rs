@@ -1,25 +1,94 @@
 use anyhow::{Context, Result, bail};
-use std::process::{Command, Stdio};
+use std::collections::{HashMap, HashSet};
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use git_review::cli::{self, Commands, GateAction};
-use git_review::gate::{check_gate, disable_gate, enable_gate};
-use git_review::parser::parse_diff;
-use git_review::state::ReviewDb;
-use git_review::tui::{App, run_tui};
+use git_review::ReviewProgress;
+use git_review::ci::{self, StateArtifact};
+use git_review::cli::{
+    self, Commands, ConfigAction, EmailAction, GateAction, NotesAction, ResetArgs, SampleArgs,
+    SnapshotAction,
+};
+use git_review::config::{Config, Layers};
+use git_review::dashboard::RepoDashboard;
+use git_review::exit::{self, CliError};
+use git_review::filter::FileFilter;
+use git_review::gate::{
+    HookKind, HookOptions, HookRemoval, HookState, check_assignee_gate, check_gate, disable_hook,
+    enable_gate_with_options, plan_disable_hook,
+};
+use git_review::git::{DiffOptions, GitError};
+use git_review::logging;
+use git_review::notify::{ReviewEvent, detect_event};
+use git_review::parser::{compute_hash, parse_diff};
+use git_review::session::ReviewSession;
+use git_review::state::{ReviewDb, status_to_string};
+use git_review::tui::{App, ExitAction, run_tui};
 
-fn main() -> Result<()> {
-    let args = cli::parse_args();
+/// Whether reviews use line-oriented prompts instead of the TUI: set by
+/// `--accessible`, or when the terminal is too limited to draw the TUI.
+static ACCESSIBLE: OnceLock<bool> = OnceLock::new();
+
+fn accessible() -> bool {
+    ACCESSIBLE.get().copied().unwrap_or(false)
+}
+
+fn main() -> ExitCode {
+    let args = match cli::try_parse_args() {
+        Ok(args) => args,
+        Err(e) if e.use_stderr() && exit::json_requested(std::env::args()) => {
+            let kind = exit::FailureKind::Usage;
+            let message = e.kind().to_string();
+            let causes = [e.to_string().trim().to_string()];
+            eprintln!("{}", exit::json_report(kind, &message, &causes));
+            return ExitCode::from(kind.code());
+        }
+        Err(e) => e.exit(),
+    };
+    let error_format = args.error_format;
+    if let Err(e) = logging::init(args.verbose, args.log_file.as_deref()) {
+        let e = anyhow::Error::from(e).context("Failed to open log file");
+        return ExitCode::from(exit::report(&e, error_format));
+    }
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => ExitCode::from(exit::report(&e, error_format)),
+    }
+}
+
+fn run(args: cli::Cli) -> Result<()> {
+    let _ = ACCESSIBLE.set(args.accessible || std::env::var("TERM").is_ok_and(|t| t == "dumb"));
 
     match args.command {
+        None if args.stdin => {
+            handle_patch("-", args.status)?;
+        }
         None => {
+            if args.fetch {
+                fetch_remotes()?;
+            }
+            let options = ReviewOptions {
+                status_only: args.status,
+                include_untracked: args.include_untracked,
+                ignore_whitespace: args.ignore_whitespace,
+                structural: args.structural,
+                files: FileFilter::new(&args.only, &args.exclude),
+                ..Default::default()
+            };
             match (args.diff_range, args.status) {
-                (Some(range), status) => {
+                _ if args.upstream => {
+                    handle_review(&upstream_range()?, options)?;
+                }
+                (Some(range), _) => {
                     // Explicit range provided — always hunk review
-                    handle_review(&range, status)?;
+                    handle_review(&range, options)?;
                 }
                 (None, true) => {
                     // --status with no range — status for HEAD
-                    handle_review("HEAD", true)?;
+                    handle_review("HEAD", options)?;
                 }
                 (None, false) => {
                     // No args, no subcommand — auto-detect mode
@@ -30,335 +99,2608 @@ fn main() -> Result<()> {
                         (Ok(Some(ref branch)), Ok(ref default)) if branch == default => {
                             handle_dashboard()?;
                         }
-                        (Ok(Some(_)), Ok(default)) => {
-                            let range = format!("{}..HEAD", default);
-                            handle_review(&range, false)?;
+                        (Ok(Some(branch)), Ok(default)) => {
+                            let range = format!("{}..HEAD", review_base(&branch, default));
+                            handle_review(&range, options)?;
+                        }
+                        (Ok(None), Ok(default)) => {
+                            handle_detached_head(&default, options)?;
                         }
                         _ => {
                             // Detached HEAD or can't detect branches — fall back
-                            handle_review("HEAD", false)?;
+                            handle_review("HEAD", options)?;
                         }
                     }
                 }
             }
         }
+        Some(Commands::Review(review_args)) if review_args.stdin => {
+            handle_patch("-", review_args.status)?;
+        }
         Some(Commands::Review(review_args)) => {
-            let diff_range = review_args.diff_range.unwrap_or_else(|| "HEAD".to_string());
-            handle_review(&diff_range, review_args.status)?;
+            if review_args.fetch {
+                fetch_remotes()?;
+            }
+            let diff_range = match review_args.diff_range {
+                Some(range) => range,
+                None if review_args.upstream => upstream_range()?,
+                None => "HEAD".to_string(),
+            };
+            let options = ReviewOptions {
+                status_only: review_args.status,
+                include_untracked: review_args.include_untracked,
+                ignore_whitespace: review_args.ignore_whitespace,
+                structural: review_args.structural,
+                files: FileFilter::new(&review_args.only, &review_args.exclude),
+                ..Default::default()
+            };
+            handle_review(&diff_range, options)?;
         }
         Some(Commands::Status(status_args)) => {
             let diff_range = status_args.diff_range.unwrap_or_else(|| "HEAD".to_string());
-            handle_review(&diff_range, true)?;
+            let options = ReviewOptions {
+                status_only: true,
+                include_untracked: status_args.include_untracked,
+                quickfix: status_args.quickfix,
+                stat_only: status_args.stat_only,
+                ignore_whitespace: status_args.ignore_whitespace,
+                structural: status_args.structural,
+                files: FileFilter::new(&status_args.only, &status_args.exclude),
+                since: status_args.since,
+            };
+            handle_review(&diff_range, options)?;
+        }
+        Some(Commands::ChangedSince(args)) => {
+            let diff_range = args.diff_range.as_deref().unwrap_or("HEAD");
+            handle_changed_since(&args.since, diff_range)?;
+        }
+        Some(Commands::Stash(stash_args)) => {
+            let stash = stash_args.stash.unwrap_or_else(|| "stash@{0}".to_string());
+            handle_stash(&stash, stash_args.status, stash_args.include_untracked)?;
         }
         Some(Commands::Gate { action }) => match action {
-            GateAction::Check => {
-                handle_gate_check()?;
+            GateAction::Check { require_assignee } => {
+                handle_gate_check(require_assignee)?;
+            }
+            GateAction::Status => {
+                handle_gate_status()?;
             }
-            GateAction::Enable => {
+            GateAction::Enable {
+                require_assignee,
+                hook,
+            } => {
                 let repo_root =
                     git_review::git::find_repo_root().context("Not in a git repository")?;
-                enable_gate(&repo_root)?;
-                println!("✓ Review gate enabled (pre-commit hook installed)");
+                enable_gate_with_options(
+                    &repo_root,
+                    HookOptions {
+                        hook,
+                        require_assignee,
+                    },
+                )?;
+                println!(
+                    "✓ Review gate enabled ({} hook installed)",
+                    hook.file_name()
+                );
             }
-            GateAction::Disable => {
+            GateAction::Disable { hook, dry_run } => {
                 let repo_root =
                     git_review::git::find_repo_root().context("Not in a git repository")?;
-                disable_gate(&repo_root)?;
-                println!("✓ Review gate disabled");
+                if dry_run {
+                    print_hook_removal(plan_disable_hook(&repo_root, hook)?, hook);
+                } else {
+                    disable_hook(&repo_root, hook)?;
+                    println!("✓ Review gate disabled ({} hook removed)", hook.file_name());
+                }
+            }
+            GateAction::PreRebase { upstream, branch } => {
+                handle_pre_rebase(&upstream, branch.as_deref())?;
             }
         },
-        Some(Commands::Commit { git_args }) => {
-            handle_commit(&git_args)?;
+        Some(Commands::Snapshot { action }) => {
+            handle_snapshot(action)?;
+        }
+        Some(Commands::Notes { action }) => {
+            handle_notes(action)?;
+        }
+        Some(Commands::Publish(publish_args)) => {
+            handle_publish(&publish_args)?;
+        }
+        Some(Commands::Config {
+            action: ConfigAction::Doctor,
+        }) => {
+            handle_config_doctor()?;
+        }
+        Some(Commands::CiCheck(ci_args)) => {
+            handle_ci_check(&ci_args)?;
+        }
+        Some(Commands::Verify(verify_args)) => {
+            let diff_range = verify_args.diff_range.as_deref().unwrap_or("HEAD");
+            handle_verify(diff_range, verify_args.repair)?;
+        }
+        Some(Commands::Step(step_args)) => {
+            let diff_range = step_args.diff_range.as_deref().unwrap_or("HEAD");
+            handle_step(diff_range)?;
+        }
+        Some(Commands::Commit {
+            summarize,
+            git_args,
+        }) => {
+            handle_commit(&git_args, summarize)?;
+        }
+        Some(Commands::Stage) => {
+            handle_stage()?;
+        }
+        Some(Commands::Push { git_args }) => {
+            handle_push(&git_args)?;
         }
         Some(Commands::Reset(reset_args)) => {
-            let diff_range = reset_args.diff_range.unwrap_or_else(|| "HEAD".to_string());
-            handle_reset(&diff_range)?;
+            handle_reset(&reset_args)?;
         }
         Some(Commands::Approve(args)) => {
-            handle_approve(&args.diff_range, args.file.as_deref())?;
+            handle_approve(&args)?;
+        }
+        Some(Commands::UndoLast(args)) => {
+            handle_undo_last(args.diff_range.as_deref())?;
         }
         Some(Commands::Watch(args)) => {
-            handle_watch(args.interval)?;
+            handle_watch(&args)?;
+        }
+        Some(Commands::Nag(args)) => {
+            handle_nag(&args)?;
+        }
+        Some(Commands::Dashboard(args)) => match args.repos {
+            Some(repos) => handle_repos_dashboard(repos, args.fetch)?,
+            None => {
+                if args.fetch {
+                    fetch_remotes()?;
+                }
+                handle_dashboard()?
+            }
+        },
+        Some(Commands::Assign(args)) => {
+            handle_assign(&args.diff_range, &args.file, &args.to)?;
+        }
+        Some(Commands::Patch(patch_args)) => {
+            handle_patch(&patch_args.file, patch_args.status)?;
+        }
+        Some(Commands::Email { action }) => {
+            handle_email(action)?;
+        }
+        Some(Commands::Serve(serve_args)) => {
+            handle_serve(&serve_args)?;
+        }
+        Some(Commands::Todos(todos_args)) => {
+            let diff_range = todos_args.diff_range.unwrap_or_else(|| "HEAD".to_string());
+            handle_todos(&diff_range)?;
+        }
+        Some(Commands::Open(args)) => {
+            handle_open(&args)?;
         }
-        Some(Commands::Dashboard) => {
-            handle_dashboard()?;
+        Some(Commands::Bookmarks(args)) => {
+            handle_bookmarks(args.diff_range.as_deref().unwrap_or("HEAD"))?;
+        }
+        Some(Commands::Sample(sample_args)) => {
+            let diff_range = sample_args
+                .diff_range
+                .clone()
+                .unwrap_or_else(|| "HEAD".to_string());
+            handle_sample(&diff_range, &sample_args)?;
+        }
+        Some(Commands::PruneMerged(prune_args)) => {
+            handle_prune_merged(&prune_args)?;
+        }
+        Some(Commands::History(history_args)) => {
+            handle_history(history_args.branch.as_deref())?;
+        }
+        Some(Commands::Report(report_args)) => {
+            let diff_range = report_args.diff_range.unwrap_or_else(|| "HEAD".to_string());
+            handle_report(
+                &diff_range,
+                report_args.html.as_deref(),
+                report_args.markdown,
+            )?;
+        }
+        Some(Commands::Pick(pick_args)) => {
+            handle_pick(&pick_args)?;
+        }
+        Some(Commands::Carry(carry_args)) => {
+            handle_carry(&carry_args.from_range, &carry_args.to_range)?;
+        }
+        Some(Commands::ExportPatch(export_args)) => {
+            handle_export_patch(&export_args)?;
         }
     }
 
     Ok(())
 }
 
+/// Open (creating if needed) the review database for a repository.
+fn open_review_db(repo_root: &Path) -> Result<ReviewDb> {
+    Ok(git_review::session::open_db(repo_root)?)
+}
+
 /// Handle the dashboard mode — show branch overview.
 fn handle_dashboard() -> Result<()> {
     let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
     let default_branch =
         git_review::git::detect_default_branch().context("Could not detect default branch")?;
 
-    let db_path = repo_root.join(".git/review-state");
-    std::fs::create_dir_all(&db_path)?;
-    let db_file = db_path.join("review.db");
-    let db = ReviewDb::open(&db_file)?;
+    let mut db = open_review_db(&repo_root)?;
+    let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+    if accessible() {
+        let mut dashboard =
+            git_review::dashboard::Dashboard::load(&db, &default_branch, config.dashboard.remotes)?;
+        dashboard.load_all_details(&mut db);
+        println!("Branches compared with {}:", default_branch);
+        print_branch_list(dashboard.items.iter().map(|item| (None, item)));
+        println!(
+            "Review one with: git-review --accessible {}..<branch>",
+            default_branch
+        );
+        return Ok(());
+    }
 
-    let app = App::new_dashboard(db, default_branch)?;
-    run_tui(app)?;
+    let app = App::new_dashboard(db, default_branch)?.with_config(config);
+    if run_tui(app)? == ExitAction::Commit {
+        handle_commit(&[], false)?;
+    }
 
     Ok(())
 }
 
-/// Handle the review command - either launch TUI or show status.
-fn handle_review(diff_range: &str, status_only: bool) -> Result<()> {
-    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
-    let base_ref = normalize_diff_range(diff_range);
-
-    // Get the diff
-    let diff_output = git_review::git::get_diff(diff_range).context("Failed to get git diff")?;
+/// Handle `dashboard --repos` — show branches needing review across repositories.
+fn handle_repos_dashboard(repos: Vec<PathBuf>, fetch: bool) -> Result<()> {
+    let repos = if repos.is_empty() {
+        git_review::git::configured_repos().context("Failed to read git-review.repos")?
+    } else {
+        repos
+    };
+    if repos.is_empty() {
+        return Err(CliError::Usage(
+            "No repositories given: pass paths to --repos or add them with \
+             `git config --global --add git-review.repos <path>`"
+                .to_string(),
+        )
+        .into());
+    }
 
-    // Parse the diff
-    let files = parse_diff(&diff_output);
+    if fetch {
+        for path in repos.iter().filter(|path| path.is_dir()) {
+            if let Err(e) = git_review::git::in_repo(Some(path), fetch_remotes) {
+                eprintln!("  ! {}: {:#}", path.display(), e);
+            }
+        }
+    }
 
-    if files.is_empty() {
-        println!("No changes to review");
+    let repos = RepoDashboard::load(repos);
+    if accessible() {
+        println!("Branches needing review:");
+        print_branch_list(
+            repos
+                .rows()
+                .into_iter()
+                .map(|(repo, item)| (Some(repo.name.as_str()), item)),
+        );
         return Ok(());
     }
+    let app = App::new_repos(repos)?;
+    // Committing happens in the repository the last review was opened in
+    if run_tui(app)? == ExitAction::Commit {
+        handle_commit(&[], false)?;
+    }
 
-    // Open database
-    let db_path = repo_root.join(".git/review-state");
-    std::fs::create_dir_all(&db_path)?;
-    let db_file = db_path.join("review.db");
+    Ok(())
+}
 
-    if status_only {
-        let mut db = ReviewDb::open(&db_file)?;
-        db.sync_with_diff(&base_ref, &files)?;
+/// Print dashboard rows as plain lines (for `--accessible`), optionally prefixed by repository.
+fn print_branch_list<'a>(
+    rows: impl Iterator<Item = (Option<&'a str>, &'a git_review::dashboard::DashboardItem)>,
+) {
+    let mut any = false;
+    for (repo, item) in rows {
+        any = true;
+        let mut name = match repo {
+            Some(repo) => format!("{}: {}", repo, item.branch.name),
+            None => item.branch.name.clone(),
+        };
+        if let Some(change) = item.tip_change {
+            name.push_str(&format!(" ({})", change.label()));
+        }
+        let reviewer = item
+            .last_reviewer()
+            .map(|reviewer| format!(", last reviewed by {}", reviewer))
+            .unwrap_or_default();
+        match &item.progress {
+            Some(p) if p.total > 0 => println!(
+                "  {}: {} of {} hunks reviewed, {} stale{}, last commit {}",
+                name, p.reviewed, p.total, p.stale, reviewer, item.branch.last_commit_age
+            ),
+            _ => println!(
+                "  {}: no changes, last commit {}",
+                name, item.branch.last_commit_age
+            ),
+        }
+    }
+    if !any {
+        println!("  (none)");
+    }
+}
 
-        // Show progress summary
-        let progress = db.progress(&base_ref)?;
-        println!("Review Progress for {}", diff_range);
-        println!("─────────────────────────────────────");
-        println!(
-            "  Reviewed:   {}/{} hunks ({:.0}%)",
-            progress.reviewed,
-            progress.total_hunks,
-            if progress.total_hunks > 0 {
-                (progress.reviewed as f64 / progress.total_hunks as f64) * 100.0
-            } else {
-                0.0
-            }
-        );
-        println!("  Unreviewed: {}", progress.unreviewed);
-        println!("  Stale:      {}", progress.stale);
-        println!(
-            "  Files:      {}/{} remaining",
-            progress.files_remaining, progress.total_files
-        );
+/// `<upstream>..HEAD` for the checked-out branch (`--upstream`).
+fn upstream_range() -> Result<String> {
+    let branch = git_review::git::get_current_branch()
+        .context("Failed to read the current branch")?
+        .ok_or_else(|| CliError::Usage("--upstream needs a checked-out branch".to_string()))?;
+    let upstream = git_review::git::upstream_branch(&branch)?.ok_or_else(|| {
+        CliError::Usage(format!(
+            "{} has no upstream; set one with `git branch --set-upstream-to`",
+            branch
+        ))
+    })?;
+    Ok(format!("{}..HEAD", upstream))
+}
+
+/// What a branch is reviewed against when no range is given: its upstream,
+/// unless that is just the branch's own pushed copy, else the default branch.
+fn review_base(branch: &str, default: String) -> String {
+    match git_review::git::upstream_branch(branch) {
+        Ok(Some(upstream)) if !upstream.ends_with(&format!("/{}", branch)) => upstream,
+        _ => default,
+    }
+}
 
-        if progress.unreviewed == 0 && progress.stale == 0 {
-            println!("\n✓ All hunks reviewed!");
-        } else if progress.stale > 0 {
-            println!("\n⚠ Some hunks have become stale (code changed since review)");
+/// Run `git fetch --all --prune`, with a spinner on stderr while it runs.
+fn fetch_remotes() -> Result<()> {
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+    let repo_dir = git_review::git::repo_dir();
+    let fetch = std::thread::spawn(move || {
+        git_review::git::in_repo(repo_dir.as_deref(), git_review::git::fetch_all)
+    });
+    if std::io::stderr().is_terminal() && !accessible() {
+        let mut stderr = std::io::stderr();
+        for frame in FRAMES.iter().cycle() {
+            if fetch.is_finished() {
+                break;
+            }
+            let _ = write!(stderr, "\r{} Fetching...", frame);
+            let _ = stderr.flush();
+            std::thread::sleep(std::time::Duration::from_millis(80));
         }
-    } else {
-        // Launch TUI — App::new_hunk_review handles DB sync internally
-        let db = ReviewDb::open(&db_file)?;
-        let app = App::new_hunk_review(files, db, base_ref)?;
-        run_tui(app)?;
+        let _ = write!(stderr, "\r\x1b[K");
+    } else if accessible() {
+        eprintln!("Fetching...");
     }
+    fetch
+        .join()
+        .map_err(|_| anyhow::anyhow!("git fetch panicked"))?
+        .context("Failed to fetch")
+}
 
-    Ok(())
+/// Options for the review and status entry points.
+#[derive(Debug, Clone, Default)]
+struct ReviewOptions {
+    /// Print a progress summary instead of launching the TUI.
+    status_only: bool,
+    /// Append synthesized diffs for untracked files.
+    include_untracked: bool,
+    /// Print pending hunks in quickfix format instead of a summary.
+    quickfix: bool,
+    /// Print line counts and stored progress without reading hunks.
+    stat_only: bool,
+    /// Diff with `git diff -w`, keeping review state apart from the full diff.
+    ignore_whitespace: bool,
+    /// Diff with difftastic, keeping review state apart from the line-based diff.
+    structural: bool,
+    /// Files to review, by extension (`--only` / `--exclude`).
+    files: FileFilter,
+    /// List the hunks reviewed in the last this many hours instead of a summary.
+    since: Option<u32>,
 }
 
-/// Handle gate check - check if all hunks are reviewed and exit with appropriate code.
-fn handle_gate_check() -> Result<()> {
+/// Handle the review command - either launch TUI or show status.
+fn handle_review(diff_range: &str, options: ReviewOptions) -> Result<()> {
     let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
-    let base_ref = "HEAD".to_string(); // Gate check uses staged changes
+    let base_ref = state_key(&repo_root, diff_range)?;
+
+    if options.stat_only {
+        return print_stat_only(&repo_root, diff_range, &base_ref);
+    }
 
     // Get the diff
-    let diff_output = git_review::git::get_diff(&base_ref).context("Failed to get git diff")?;
-    let files = parse_diff(&diff_output);
+    let diff_options = DiffOptions {
+        ignore_whitespace: options.ignore_whitespace,
+    };
+    let mut diff_output = if options.structural {
+        git_review::difftastic::get_diff(diff_range, diff_options)
+            .context("Failed to get structural diff")?
+    } else {
+        git_review::git::get_diff_with_options(diff_range, diff_options)
+            .context("Failed to get git diff")?
+    };
+    let base_ref =
+        git_review::state::mode_key(&base_ref, options.ignore_whitespace, options.structural);
+    if options.include_untracked {
+        diff_output.push_str(
+            &git_review::git::get_untracked_diff(&repo_root)
+                .context("Failed to diff untracked files")?,
+        );
+    }
 
-    if files.is_empty() {
-        // No changes - gate passes
-        std::process::exit(0);
+    if options.quickfix {
+        let mut session = ReviewSession::from_diff(&repo_root, &base_ref, &diff_output)?;
+        session.set_filter(options.files);
+        print!("{}", git_review::report::quickfix(&session)?);
+        return Ok(());
     }
 
-    // Open database
-    let db_path = repo_root.join(".git/review-state/review.db");
-    if !db_path.exists() {
-        eprintln!("✗ Review gate: No review state found");
-        eprintln!("  Run 'git-review' to review your changes");
-        std::process::exit(1);
+    if let Some(hours) = options.since {
+        let mut session = ReviewSession::from_diff(&repo_root, &base_ref, &diff_output)?;
+        session.set_filter(options.files);
+        let approvals = session
+            .db()
+            .approvals_within(&base_ref, u64::from(hours) * 3600)?;
+        let list = git_review::report::reviewed(&session, &approvals);
+        if list.is_empty() {
+            println!(
+                "Nothing reviewed in {} in the last {} hours",
+                diff_range, hours
+            );
+        } else {
+            print!("{}", list);
+        }
+        return Ok(());
     }
 
-    let db = ReviewDb::open(&db_path)?;
+    review_diff(
+        &repo_root,
+        &diff_output,
+        &base_ref,
+        diff_range,
+        Some(diff_range),
+        options.status_only,
+        &options.files,
+    )
+}
 
-    // Check gate
-    if check_gate(&db, &base_ref)? {
-        println!("✓ Review gate passed");
-        std::process::exit(0);
+/// Auto-detect mode on a detached HEAD.
+///
+/// Local changes are reviewed as usual. Without any, `HEAD` has nothing to show,
+/// so offer to review everything the detached commit adds on top of the default branch.
+fn handle_detached_head(default_branch: &str, options: ReviewOptions) -> Result<()> {
+    let local_diff = git_review::git::get_diff("HEAD").context("Failed to get git diff")?;
+    if !local_diff.trim().is_empty() || !std::io::stdin().is_terminal() {
+        return handle_review("HEAD", options);
+    }
+
+    let range = format!("{}..HEAD", default_branch);
+    if confirm(&format!(
+        "HEAD is detached and has no local changes. Review {} instead?",
+        range
+    ))? {
+        handle_review(&range, options)
     } else {
-        let progress = db.progress(&base_ref)?;
-        eprintln!("✗ Review gate: Not all hunks reviewed");
-        eprintln!(
-            "  {}/{} hunks reviewed, {} unreviewed, {} stale",
-            progress.reviewed, progress.total_hunks, progress.unreviewed, progress.stale
-        );
-        eprintln!("  Run 'git-review' to complete your review");
-        std::process::exit(1);
+        println!("No changes to review (try `git-review <sha>^!` to review a single commit)");
+        Ok(())
     }
 }
 
-/// Handle commit command - check gate then execute git commit.
-fn handle_commit(git_args: &[String]) -> Result<()> {
+/// Handle the stash command - review the changes recorded in a stash entry.
+///
+/// State is keyed by the stash commit SHA, since `stash@{n}` indexes shift as
+/// stashes are pushed and dropped.
+fn handle_stash(stash: &str, status_only: bool, include_untracked: bool) -> Result<()> {
     let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
-    let base_ref = "HEAD".to_string();
+    let sha = git_review::git::resolve_ref(stash).context("Failed to resolve stash")?;
+    let diff_output = git_review::git::get_stash_diff(stash, include_untracked)
+        .context("Failed to get stash diff")?;
 
-    // Get the diff
-    let diff_output = git_review::git::get_diff(&base_ref).context("Failed to get git diff")?;
-    let files = parse_diff(&diff_output);
+    review_diff(
+        &repo_root,
+        &diff_output,
+        &git_review::state::stash_key(&sha),
+        stash,
+        None,
+        status_only,
+        &FileFilter::default(),
+    )
+}
 
-    if files.is_empty() {
-        bail!("No changes to commit");
-    }
+/// Handle the patch command and `--stdin` - review a diff produced outside git-review.
+///
+/// State is keyed by a hash of the diff contents, so re-reviewing the same patch
+/// resumes where the previous session stopped.
+fn handle_patch(file: &str, status_only: bool) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let diff_output = read_diff_input(file)?;
+    let label = if file == "-" { "stdin" } else { file };
 
-    // Check gate
-    let db_path = repo_root.join(".git/review-state/review.db");
-    if !db_path.exists() {
-        bail!("No review state found. Run 'git-review' first to review your changes");
-    }
+    review_diff(
+        &repo_root,
+        &diff_output,
+        &patch_state_key(&diff_output),
+        label,
+        None,
+        status_only,
+        &FileFilter::default(),
+    )
+}
 
-    let db = ReviewDb::open(&db_path)?;
+/// Review state key of a patch or mbox file, from its content.
+fn patch_state_key(diff_output: &str) -> String {
+    git_review::state::patch_key(&compute_hash(diff_output)[..12])
+}
 
-    if !check_gate(&db, &base_ref)? {
-        let progress = db.progress(&base_ref)?;
-        bail!(
-            "Review gate failed: {}/{} hunks reviewed, {} unreviewed, {} stale. Run 'git-review' to complete your review",
-            progress.reviewed,
-            progress.total_hunks,
-            progress.unreviewed,
-            progress.stale
-        );
-    }
+/// Handle the email command - send the review of a patch series as replies,
+/// or mark hunks reviewed from replies.
+fn handle_email(action: EmailAction) -> Result<()> {
+    use git_review::email;
 
-    // Gate passed - execute git commit
-    println!("✓ Review gate passed, proceeding with commit");
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let (EmailAction::Export { series, .. } | EmailAction::Ingest { series, .. }) = &action;
+    let series_text = read_diff_input(series)?;
+    let base_ref = patch_state_key(&series_text);
+    let mut db = open_review_db(&repo_root)?;
+    db.sync_with_diff(&base_ref, &parse_diff(&series_text))?;
+    let messages = email::split_mbox(&series_text);
 
-    let status = Command::new("git")
-        .arg("commit")
-        .args(git_args)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .status()
-        .context("Failed to execute git commit")?;
+    match action {
+        EmailAction::Export { series, output } => {
+            let name = git_review::git::get_user_name().context("Set git config user.name")?;
+            let from = match git_review::git::get_user_email() {
+                Ok(address) => format!("{} <{}>", name, address),
+                Err(_) => name,
+            };
+            let (mbox, count) = email::export(&db, &base_ref, &messages, &from)?;
+            if count == 0 {
+                eprintln!("Nothing left to review or comment on in {}", series);
+                return Ok(());
+            }
+            match &output {
+                Some(path) => std::fs::write(path, &mbox)
+                    .with_context(|| format!("Failed to write {}", path.display()))?,
+                None => print!("{}", mbox),
+            }
+            eprintln!("Exported {} repl(ies) for {}", count, series);
+            if let Some(path) = output {
+                eprintln!("  Send them with: git send-email {}", path.display());
+            }
+        }
+        EmailAction::Ingest { replies, .. } => {
+            let replies = email::split_mbox(&read_diff_input(&replies)?);
+            let ingested = email::ingest(&mut db, &base_ref, &messages, &replies)?;
+            for reply in &ingested.skipped {
+                eprintln!(
+                    "Skipped reply from {} ({}): {}",
+                    reply.from, reply.subject, reply.reason
+                );
+            }
+            println!(
+                "✓ Marked {} hunk(s) reviewed from {} repl(ies)",
+                ingested.marked,
+                replies.len()
+            );
+        }
+    }
+    Ok(())
+}
 
-    if !status.success() {
-        bail!("git commit failed");
+/// Handle the serve command - expose review state to editors.
+fn handle_serve(args: &cli::ServeArgs) -> Result<()> {
+    if let Some(addr) = &args.http {
+        git_review::git::find_repo_root().context("Not in a git repository")?;
+        return git_review::web::serve(addr)
+            .with_context(|| format!("Web dashboard on {} failed", addr));
     }
+    if !args.json_rpc {
+        return Err(CliError::Usage(
+            "Choose a protocol to serve, e.g. `git-review serve --json-rpc` or `--http 127.0.0.1:8080`"
+                .to_string(),
+        )
+        .into());
+    }
+    git_review::rpc::serve(std::io::stdin().lock(), std::io::stdout().lock())
+        .context("JSON-RPC server failed")
+}
 
-    Ok(())
+/// Describe what `gate disable` would do to a hook.
+fn print_hook_removal(removal: HookRemoval, hook: HookKind) {
+    match removal {
+        HookRemoval::Missing => println!("No {} hook installed", hook.file_name()),
+        HookRemoval::Foreign(path) => println!(
+            "{} was not installed by git-review and would be kept",
+            path.display()
+        ),
+        HookRemoval::Remove(path) => println!("Would remove {}", path.display()),
+        HookRemoval::Restore { backup, hook } => println!(
+            "Would remove {} and restore the original hook from {}",
+            hook.display(),
+            backup.display()
+        ),
+    }
 }
 
-/// Handle reset command - clear review state for a diff range.
-fn handle_reset(diff_range: &str) -> Result<()> {
+/// Handle the prune-merged command - delete merged branches and their review state.
+fn handle_prune_merged(args: &cli::PruneMergedArgs) -> Result<()> {
     let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
-    let base_ref = normalize_diff_range(diff_range);
+    let base = match args.base.as_deref() {
+        Some(base) => base.to_string(),
+        None => {
+            git_review::git::detect_default_branch().context("Could not detect default branch")?
+        }
+    };
 
-    let db_path = repo_root.join(".git/review-state/review.db");
-    if !db_path.exists() {
-        println!("No review state to reset");
+    let merged =
+        git_review::dashboard::merged_branches(&base).context("Failed to check merged branches")?;
+    if merged.is_empty() {
+        println!("No branches merged into {}", base);
         return Ok(());
     }
+    if args.dry_run {
+        return print_prune_plan(&repo_root, &base, &merged);
+    }
 
-    let mut db = ReviewDb::open(&db_path)?;
-    db.reset(&base_ref)?;
+    println!("Branches merged into {}:", base);
+    for branch in &merged {
+        println!("  {}", branch);
+    }
+    if !args.yes
+        && !confirm(&format!(
+            "Delete {} branches and archive their review state?",
+            merged.len()
+        ))?
+    {
+        println!("Aborted");
+        return Ok(());
+    }
 
-    println!("✓ Review state reset for {}", diff_range);
+    let mut db = open_review_db(&repo_root)?;
+    let mut failed = 0;
+    for branch in &merged {
+        match git_review::dashboard::prune_branch(&mut db, &base, branch) {
+            Ok(()) => println!("✓ Deleted {}", branch),
+            Err(e) => {
+                eprintln!("✗ {}: {}", branch, e);
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        bail!("Failed to delete {} branches", failed);
+    }
     Ok(())
 }
 
-/// Normalize a diff range to a consistent base ref format.
-fn normalize_diff_range(range: &str) -> String {
-    range.to_string()
+/// List the branches `prune-merged` would delete and the review state it
+/// would archive or clear for each.
+fn print_prune_plan(repo_root: &Path, base: &str, merged: &[String]) -> Result<()> {
+    let db = open_review_db(repo_root)?;
+    let queued = db.merge_queue(base)?;
+    println!(
+        "Would delete {} branch(es) merged into {}:",
+        merged.len(),
+        base
+    );
+    for branch in merged {
+        match git_review::git::merge_commit(base, branch) {
+            Ok(sha) => println!("  {} (merged in {:.7})", branch, sha),
+            Err(_) => println!("  {}", branch),
+        }
+        for base_ref in git_review::dashboard::branch_base_refs(&db, branch)? {
+            println!(
+                "    {}: archive {} hunk(s), delete {} assignment(s) and {} comment(s)",
+                base_ref,
+                db.hunks(&base_ref)?.len(),
+                db.assignments(&base_ref)?.len(),
+                db.comments(&base_ref)?.len()
+            );
+        }
+        if let Some(from) = db.carryover(branch)? {
+            println!("    drop pending carryover from {}", from);
+        }
+        if queued.contains(branch) {
+            println!("    take off the merge queue");
+        }
+    }
+    Ok(())
 }
 
-/// Handle approve command - bulk approve hunks.
-fn handle_approve(diff_range: &str, file_filter: Option<&str>) -> Result<()> {
+fn handle_history(branch: Option<&str>) -> Result<()> {
     let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
-    let base_ref = normalize_diff_range(diff_range);
-    let diff_output = git_review::git::get_diff(diff_range).context("Failed to get git diff")?;
-    let files = parse_diff(&diff_output);
-
-    if files.is_empty() {
-        println!("No changes to approve");
+    let db = open_review_db(&repo_root)?;
+    let events = db
+        .events(branch)
+        .context("Failed to read the review history")?;
+    if events.is_empty() {
+        match branch {
+            Some(branch) => println!("No review history for {}", branch),
+            None => println!("No review history"),
+        }
         return Ok(());
     }
 
-    let db_path = repo_root.join(".git/review-state");
-    std::fs::create_dir_all(&db_path)?;
-    let db_file = db_path.join("review.db");
-    let mut db = ReviewDb::open(&db_file)?;
-    db.sync_with_diff(&base_ref, &files)?;
+    let archived = db.archived().context("Failed to read the review archive")?;
+    for entry in git_review::history::timeline(&events, &archived) {
+        if branch.is_some() {
+            println!("{}  {}", entry.at, entry.text);
+        } else {
+            println!("{}  {:24} {}", entry.at, entry.branch, entry.text);
+        }
+    }
+    Ok(())
+}
 
-    let count = if let Some(file_path) = file_filter {
-        db.approve_file(&base_ref, file_path)?
-    } else {
-        db.approve_all(&base_ref)?
-    };
+/// Ask a yes/no question on stdin; anything but "y"/"yes" is a no.
+fn confirm(question: &str) -> Result<bool> {
+    use std::io::Write;
 
-    println!("✓ Approved {} hunks for {}", count, diff_range);
-    Ok(())
+    print!("{} [y/N] ", question);
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
 }
 
-/// Handle watch command - continuously monitor branches.
-fn handle_watch(interval: u64) -> Result<()> {
+/// Ask a question on the controlling terminal, bypassing stdin.
+///
+/// Git runs hooks with stdin closed, so this is how hooks talk to the user.
+/// Returns `None` when there is no terminal to ask on.
+fn ask_tty(question: &str) -> Option<String> {
+    use std::io::{BufRead, Write};
+
+    let tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let mut writer = &tty;
+    write!(writer, "{} ", question).ok()?;
+    writer.flush().ok()?;
+    let mut answer = String::new();
+    std::io::BufReader::new(&tty).read_line(&mut answer).ok()?;
+    Some(answer.trim().to_lowercase())
+}
+
+/// Handle `gate pre-rebase` - run from the pre-rebase hook.
+///
+/// Rebasing moves the branch's merge-base, which changes its review state key.
+/// Unless told otherwise, record a carryover so the existing approvals follow
+/// the branch to its new key; hunks the rebase rewrites then show up as stale.
+fn handle_pre_rebase(upstream: &str, branch: Option<&str>) -> Result<()> {
     let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
-    println!("Watching for branches needing review (Ctrl+C to stop)...\n");
+    let branch = match branch {
+        Some(branch) => branch.to_string(),
+        // A detached HEAD has no branch-keyed review state
+        None => match git_review::git::get_current_branch()? {
+            Some(branch) => branch,
+            None => return Ok(()),
+        },
+    };
 
-    loop {
-        // Get list of local branches
-        let output = Command::new("git")
-            .args(["branch", "--format", "%(refname:short)"])
-            .output()
-            .context("Failed to list branches")?;
-        let branches = String::from_utf8_lossy(&output.stdout);
-
-        // Check each non-main branch
-        for branch in branches.lines() {
-            let branch = branch.trim();
-            if branch == "main" || branch == "master" || branch.is_empty() {
-                continue;
-            }
-            let diff_range = format!("main..{}", branch);
-            if let Ok(diff_output) = git_review::git::get_diff(&diff_range) {
-                let files = parse_diff(&diff_output);
-                if files.is_empty() {
-                    continue;
-                }
+    let db_path = repo_root.join(".git/review-state/review.db");
+    if !db_path.exists() {
+        return Ok(());
+    }
+    let mut db = ReviewDb::open(&db_path)?;
 
-                let db_path = repo_root.join(".git/review-state");
-                std::fs::create_dir_all(&db_path).ok();
-                let db_file = db_path.join("review.db");
-                if let Ok(mut db) = ReviewDb::open(&db_file) {
-                    db.sync_with_diff(&diff_range, &files).ok();
-                    if let Ok(progress) = db.progress(&diff_range) {
-                        let pct = if progress.total_hunks > 0 {
-                            (progress.reviewed as f64 / progress.total_hunks as f64) * 100.0
-                        } else {
-                            0.0
-                        };
-                        let status = if progress.unreviewed == 0 && progress.stale == 0 {
-                            "✓"
-                        } else {
-                            "○"
-                        };
-                        println!(
-                            "{} {:40} {}/{} ({:.0}%)",
-                            status, branch, progress.reviewed, progress.total_hunks, pct
-                        );
-                    }
-                }
+    // The branch's review with the most approvals is the one worth keeping.
+    // Whitespace-blind and structural reviews hash hunks differently, so
+    // carrying one over would mix their hunks into the plain review.
+    let mut best: Option<(String, usize)> = None;
+    for base_ref in db.base_refs_in_namespace(&branch)? {
+        if git_review::state::strip_mode(&base_ref) != base_ref || !base_ref.contains("..") {
+            continue;
+        }
+        let reviewed = db.progress(&base_ref)?.reviewed;
+        if reviewed > best.as_ref().map_or(0, |(_, n)| *n) {
+            best = Some((base_ref, reviewed));
+        }
+    }
+    let Some((base_ref, reviewed)) = best else {
+        return Ok(());
+    };
+
+    eprintln!(
+        "git-review: rebasing {} onto {} changes its merge-base; {} reviewed hunks are recorded under the old one.",
+        branch, upstream, reviewed
+    );
+    let answer = ask_tty(
+        "[c]arry review state over to the rebased branch (default), [s]napshot it first, [k]eep it where it is, or [a]bort the rebase?",
+    );
+    match answer.as_deref() {
+        Some("a") | Some("abort") => bail!("Rebase aborted by git-review"),
+        Some("k") | Some("keep") => {
+            eprintln!("git-review: review state left under {}", base_ref);
+        }
+        answer => {
+            if matches!(answer, Some("s") | Some("snapshot")) {
+                let name = format!("pre-rebase/{}", branch);
+                db.save_snapshot(&name, &base_ref)?;
+                eprintln!(
+                    "git-review: saved snapshot {} (restore with `git-review snapshot restore {}`)",
+                    name, name
+                );
             }
+            db.record_carryover(&branch, &base_ref)?;
+            eprintln!(
+                "git-review: review state will follow {} after the rebase; rewritten hunks will show as stale",
+                branch
+            );
         }
-        println!("─── refreshing in {}s ───\n", interval);
-        std::thread::sleep(std::time::Duration::from_secs(interval));
     }
+    Ok(())
+}
+
+/// Handle the todos command - print the punch list left by a review pass.
+fn handle_todos(diff_range: &str) -> Result<()> {
+    let session = ReviewSession::open(diff_range).context("Failed to open review session")?;
+    let todos = git_review::report::todos(&session)?;
+    if todos.is_empty() {
+        println!("Nothing to do for {}", diff_range);
+    } else {
+        print!("{}", todos);
+    }
+    Ok(())
+}
+
+/// Handle the open command - open a branch's pull request page.
+fn handle_open(args: &cli::OpenArgs) -> Result<()> {
+    let branch = match &args.branch {
+        Some(branch) => branch.clone(),
+        None => git_review::git::get_current_branch()?
+            .ok_or_else(|| CliError::Usage("HEAD is detached; name a branch".to_string()))?,
+    };
+    let base = match &args.base {
+        Some(base) => base.clone(),
+        None => git_review::git::detect_default_branch()
+            .context("Could not detect default branch; pass --base")?,
+    };
+    let url = git_review::hosting::review_url(&branch, &base)?;
+    if args.print {
+        println!("{}", url);
+    } else {
+        git_review::hosting::open_in_browser(&url)
+            .with_context(|| format!("Failed to open a browser; the page is {}", url))?;
+        println!("Opened {}", url);
+    }
+    Ok(())
+}
+
+/// Handle the bookmarks command - list bookmarked hunks in quickfix format.
+fn handle_bookmarks(diff_range: &str) -> Result<()> {
+    let mut session = ReviewSession::open(diff_range).context("Failed to open review session")?;
+    let base_ref = session.base_ref().to_string();
+    let bookmarks = session.db().bookmarks(&base_ref)?;
+    if bookmarks.is_empty() {
+        println!("No bookmarks in {}", diff_range);
+    } else {
+        print!("{}", git_review::report::bookmarks(&session, &bookmarks));
+    }
+    Ok(())
+}
+
+/// Handle the sample command - keep a random fraction of the unreviewed hunks
+/// for review, approve the rest as sampled-out, then review as usual.
+fn handle_sample(diff_range: &str, args: &SampleArgs) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let base_ref = state_key(&repo_root, diff_range)?;
+    let diff_output = git_review::git::get_diff(diff_range).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+    if files.is_empty() {
+        println!("No changes to review");
+        return Ok(());
+    }
+
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default()
+    });
+    {
+        let mut db = open_review_db(&repo_root)?;
+        db.sync_with_diff(&base_ref, &files)?;
+        let sample =
+            git_review::autoapprove::sample(&mut db, &base_ref, &files, args.percent, seed)?;
+        println!(
+            "Sampled {} of {} unreviewed hunk(s) for review; {} marked sampled-out (--seed {})",
+            sample.kept,
+            sample.kept + sample.sampled_out,
+            sample.sampled_out,
+            seed
+        );
+    }
+
+    review_diff(
+        &repo_root,
+        &diff_output,
+        &base_ref,
+        diff_range,
+        Some(diff_range),
+        args.status,
+        &FileFilter::default(),
+    )
+}
+
+/// Handle the report command - render the diff and its review state to a file.
+fn handle_report(diff_range: &str, html: Option<&Path>, markdown: bool) -> Result<()> {
+    if html.is_none() && !markdown {
+        return Err(CliError::Usage(
+            "Choose a report format: --html <FILE> or --markdown".to_string(),
+        )
+        .into());
+    }
+
+    let session = ReviewSession::open(diff_range).context("Failed to open review session")?;
+    if let Some(html_path) = html {
+        let page = git_review::report::html(&session, diff_range)?;
+        std::fs::write(html_path, page)
+            .with_context(|| format!("Failed to write {}", html_path.display()))?;
+        eprintln!("Wrote HTML report to {}", html_path.display());
+    }
+    if markdown {
+        print!("{}", git_review::report::markdown(&session, diff_range)?);
+    }
+    Ok(())
+}
+
+/// Handle pick - review a single commit, keyed by its patch id so the review
+/// survives the commit being rebased, and cherry-pick it with `--apply` once
+/// it passes the gate.
+fn handle_pick(args: &cli::PickArgs) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let sha = git_review::git::resolve_ref(&args.commit).context("Failed to resolve commit")?;
+    let short = &sha[..sha.len().min(7)];
+    let range = format!("{}^!", sha);
+    let diff_output = git_review::git::get_diff(&range).context("Failed to get git diff")?;
+    if parse_diff(&diff_output).is_empty() {
+        println!("No changes to review");
+        return Ok(());
+    }
+    let key = format!(
+        "pick:{}",
+        git_review::git::patch_id(&diff_output).context("Failed to compute the patch id")?
+    );
+
+    if !args.apply {
+        review_diff(
+            &repo_root,
+            &diff_output,
+            &key,
+            &format!("{} (pick)", short),
+            None,
+            args.status,
+            &FileFilter::default(),
+        )?;
+        let db = open_review_db(&repo_root)?;
+        let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+        if !args.status && check_gate(&db, &key, &config.gate)? {
+            println!(
+                "✓ {} is reviewed; cherry-pick it with 'git-review pick {} --apply'",
+                short, args.commit
+            );
+        }
+        return Ok(());
+    }
+
+    let mut db = open_review_db(&repo_root)?;
+    let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+    db.sync_with_diff(&key, &parse_diff(&diff_output))?;
+    let passed = check_gate(&db, &key, &config.gate)?;
+    record_gate_check(&mut db, &key, passed);
+    if !passed {
+        let progress = db.progress(&key)?;
+        eprintln!(
+            "✗ Review gate failed for {}: {}/{} hunks reviewed. Run 'git-review pick {}' to review it",
+            short, progress.reviewed, progress.total_hunks, args.commit
+        );
+        return Err(CliError::CheckFailed(format!(
+            "review gate failed: {}/{} hunks reviewed",
+            progress.reviewed, progress.total_hunks
+        ))
+        .into());
+    }
+    println!("✓ Review gate passed for {}, cherry-picking", short);
+    git_review::git::cherry_pick(&sha)?;
+    Ok(())
+}
+
+/// Handle carry - copy reviews from one range to the hunks of another with
+/// the same file and content, such as a reviewed series cherry-picked onto a
+/// release branch.
+fn handle_carry(from_range: &str, to_range: &str) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let from_key = state_key(&repo_root, from_range)?;
+    let to_key = state_key(&repo_root, to_range)?;
+    let mut db = open_review_db(&repo_root)?;
+    if db.hunks(&from_key)?.is_empty() {
+        return Err(CliError::Usage(format!(
+            "No review state for {}; review it first with 'git-review {}'",
+            from_range, from_range
+        ))
+        .into());
+    }
+
+    let diff_output = git_review::git::get_diff(to_range).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+    db.sync_with_diff(&to_key, &files)?;
+    let carried = db.carry_reviews(&from_key, &to_key)?;
+    let progress = db.progress(&to_key)?;
+    println!(
+        "✓ Carried {} review(s) from {} to {}: {}/{} hunks reviewed",
+        carried, from_range, to_range, progress.reviewed, progress.total_hunks
+    );
+    Ok(())
+}
+
+/// Handle export-patch - write the hunks with the selected review statuses
+/// as a patch that `git apply` accepts.
+fn handle_export_patch(args: &cli::ExportPatchArgs) -> Result<()> {
+    let diff_range = args.diff_range.as_deref().unwrap_or("HEAD");
+    let session = ReviewSession::open(diff_range).context("Failed to open review session")?;
+    let statuses: HashMap<(&Path, &str), git_review::HunkStatus> = session
+        .files()
+        .iter()
+        .flat_map(|file| {
+            file.hunks.iter().map(move |hunk| {
+                (
+                    (file.path.as_path(), hunk.content_hash.as_str()),
+                    hunk.status,
+                )
+            })
+        })
+        .collect();
+    let diff_output = git_review::git::get_diff(diff_range).context("Failed to get git diff")?;
+    let (patch, count) = git_review::patch::select_hunks(&diff_output, |path, hunk| {
+        statuses
+            .get(&(path, hunk.content_hash.as_str()))
+            .is_some_and(|&status| args.status.iter().any(|wanted| wanted.matches(status)))
+    });
+
+    match &args.output {
+        Some(path) => std::fs::write(path, &patch)
+            .with_context(|| format!("Failed to write {}", path.display()))?,
+        None => print!("{}", patch),
+    }
+    eprintln!("Exported {} hunk(s) from {}", count, diff_range);
+    Ok(())
+}
+
+/// Read diff text from a file, or from stdin when `source` is "-".
+fn read_diff_input(source: &str) -> Result<String> {
+    if source == "-" {
+        let mut input = String::new();
+        std::io::stdin()
+            .read_to_string(&mut input)
+            .context("Failed to read diff from stdin")?;
+        Ok(input)
+    } else {
+        std::fs::read_to_string(source).with_context(|| format!("Failed to read {}", source))
+    }
+}
+
+/// Review raw diff output: print a status summary or launch the TUI.
+///
+/// `base_ref` keys the review state; `label` is how the diff is named to the user.
+fn review_diff(
+    repo_root: &Path,
+    diff_output: &str,
+    base_ref: &str,
+    label: &str,
+    range: Option<&str>,
+    status_only: bool,
+    filter: &FileFilter,
+) -> Result<()> {
+    // Parse the diff
+    let files = parse_diff(diff_output);
+
+    if files.is_empty() {
+        println!("No changes to review");
+        return Ok(());
+    }
+
+    // Open database
+    let mut db = open_review_db(repo_root)?;
+    let config = Config::load(repo_root).context("Failed to load .git-review.toml")?;
+    let auto_approved = if config.moved.auto_approve {
+        git_review::moved::approve_moved(&mut db, base_ref, &files)?
+    } else {
+        0
+    };
+
+    if status_only {
+        db.sync_with_diff(base_ref, &files)?;
+        if auto_approved > 0 {
+            println!("Auto-approved {} hunk(s) of moved code", auto_approved);
+        }
+        if let Some(range) = range
+            && let Some(base_move) = git_review::session::track_base(&mut db, base_ref, range)?
+        {
+            print_base_move(&base_move);
+        }
+
+        // Show progress summary
+        let progress = if filter.is_empty() {
+            println!("Review Progress for {}", label);
+            db.progress(base_ref)?
+        } else {
+            println!("Review Progress for {} ({})", label, filter);
+            filter.progress(&db.hunks(base_ref)?)
+        };
+        println!("─────────────────────────────────────");
+        print_progress(&progress);
+        let files: Vec<_> = files
+            .into_iter()
+            .filter(|file| filter.matches(&file.path))
+            .collect();
+        print_remaining_files(repo_root, &db, base_ref, &files, &config)?;
+    } else if accessible() {
+        let mut session = ReviewSession::from_diff(repo_root, base_ref, diff_output)?;
+        session.set_filter(filter.clone());
+        if let Some(range) = range
+            && let Some(base_move) = git_review::session::track_base(&mut db, base_ref, range)?
+        {
+            print_base_move(&base_move);
+        }
+        git_review::accessible::run(
+            &mut session,
+            label,
+            &mut std::io::stdin().lock(),
+            &mut std::io::stdout(),
+        )?;
+    } else {
+        // Launch TUI — App::new_hunk_review handles DB sync internally
+        let mut app = App::new_hunk_review(files, db, base_ref.to_string())?
+            .with_config(config)
+            .with_file_filter(filter.clone());
+        if let Some(range) = range {
+            app = app.with_base_tracking(range)?;
+        }
+        if run_tui(app)? == ExitAction::Commit {
+            handle_commit(&[], false)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Explain that a review's base moved, listing the files it affected.
+fn print_base_move(base_move: &git_review::session::BaseMove) {
+    println!("⚠ {}:", base_move.describe());
+    for file in &base_move.files {
+        println!("    {}", file);
+    }
+}
+
+/// Print the hunk counts and verdict lines of a progress summary.
+fn print_progress(progress: &ReviewProgress) {
+    println!(
+        "  Reviewed:   {}/{} hunks ({:.0}%)",
+        progress.reviewed,
+        progress.total_hunks,
+        if progress.total_hunks > 0 {
+            (progress.reviewed as f64 / progress.total_hunks as f64) * 100.0
+        } else {
+            0.0
+        }
+    );
+    if progress.auto_approved > 0 {
+        println!(
+            "  Auto-approved: {} (by [auto_approve] rules or sampling)",
+            progress.auto_approved
+        );
+    }
+    println!("  Unreviewed: {}", progress.unreviewed);
+    println!("  Stale:      {}", progress.stale);
+    if progress.needs_changes > 0 {
+        println!("  Needs changes: {}", progress.needs_changes);
+    }
+    if progress.skimmed > 0 {
+        println!("  Skimmed:    {}", progress.skimmed);
+    }
+    println!(
+        "  Files:      {}/{} remaining",
+        progress.files_remaining, progress.total_files
+    );
+
+    if progress.reviewed == progress.total_hunks {
+        println!("\n✓ All hunks reviewed!");
+    } else if progress.stale > 0 {
+        println!("\n⚠ Some hunks have become stale (code changed since review)");
+    }
+    if progress.needs_changes > 0 {
+        println!(
+            "✗ {} hunk(s) flagged as needing changes",
+            progress.needs_changes
+        );
+    }
+}
+
+/// List the files with hunks left to review, each at its first such hunk.
+/// Paths are hyperlinks to the file (see `[links]`) when printed to a terminal.
+fn print_remaining_files(
+    repo_root: &Path,
+    db: &ReviewDb,
+    base_ref: &str,
+    files: &[git_review::DiffFile],
+    config: &Config,
+) -> Result<()> {
+    let is_terminal = std::io::stdout().is_terminal();
+    let mut remaining = Vec::new();
+    for file in files {
+        let path = file.path.to_string_lossy();
+        let mut pending = Vec::new();
+        for hunk in &file.hunks {
+            if db.get_status(base_ref, &path, &hunk.content_hash)?
+                != git_review::HunkStatus::Reviewed
+            {
+                pending.push(hunk.new_start);
+            }
+        }
+        let Some(&first_line) = pending.first() else {
+            continue;
+        };
+        let text = format!("{}:{}", path, first_line);
+        let link = config.links.link(
+            is_terminal,
+            &repo_root.join(&file.path),
+            Some(first_line),
+            &text,
+        );
+        remaining.push(format!("    {} ({} hunk(s))", link, pending.len()));
+    }
+    if !remaining.is_empty() {
+        println!("\nRemaining files:");
+        for line in remaining {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
+
+/// `status --stat-only`: line counts from `git diff --numstat` plus the progress
+/// stored by the last sync, without fetching or parsing the diff itself.
+fn print_stat_only(repo_root: &Path, diff_range: &str, base_ref: &str) -> Result<()> {
+    let stats = git_review::git::get_diff_stats(diff_range).context("Failed to get diff stats")?;
+    if stats.file_count == 0 {
+        println!("No changes to review");
+        return Ok(());
+    }
+
+    let db = open_review_db(repo_root)?;
+    let progress = db.progress(base_ref)?;
+    println!("Review Progress for {} (as of last sync)", diff_range);
+    println!("─────────────────────────────────────");
+    println!(
+        "  Changes:    {} files, +{} -{}",
+        stats.file_count, stats.insertions, stats.deletions
+    );
+    print_progress(&progress);
+    Ok(())
+}
+
+/// Handle gate check - check if all hunks are reviewed and exit with appropriate code.
+///
+/// With `require_assignee`, assigned files must also have been reviewed by their assignee.
+fn handle_gate_check(require_assignee: bool) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let base_ref = "HEAD".to_string(); // Gate check uses staged changes
+
+    // Get the diff
+    let diff_output = git_review::git::get_diff(&base_ref).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+
+    if files.is_empty() {
+        // No changes - gate passes
+        return Ok(());
+    }
+
+    // Open database
+    let db_path = repo_root.join(".git/review-state/review.db");
+    if !db_path.exists() {
+        eprintln!("✗ Review gate: No review state found");
+        eprintln!("  Run 'git-review' to review your changes");
+        return Err(CliError::CheckFailed("no review state found".to_string()).into());
+    }
+
+    let mut db = ReviewDb::open(&db_path)?;
+    let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+    db.set_reviewer(git_review::git::get_user_name().ok());
+
+    // Check gate
+    let passed = check_gate(&db, &base_ref, &config.gate)?;
+    record_gate_check(&mut db, &base_ref, passed);
+    if !passed {
+        let progress = db.progress(&base_ref)?;
+        eprintln!(
+            "✗ Review gate: Review policy not met (threshold {}%, stale {})",
+            config.gate.threshold,
+            if config.gate.allow_stale {
+                "allowed"
+            } else {
+                "not allowed"
+            }
+        );
+        eprintln!(
+            "  {}/{} hunks reviewed, {} unreviewed, {} stale, {} need changes",
+            progress.reviewed,
+            progress.total_hunks,
+            progress.unreviewed,
+            progress.stale,
+            progress.needs_changes
+        );
+        eprintln!("  Run 'git-review' to complete your review");
+        return Err(CliError::CheckFailed(format!(
+            "review policy not met: {}/{} hunks reviewed",
+            progress.reviewed, progress.total_hunks
+        ))
+        .into());
+    }
+
+    if require_assignee && !check_assignee_gate(&db, &base_ref)? {
+        eprintln!("✗ Review gate: Assigned hunks not reviewed by their assignee");
+        for (file_path, _, assignee) in git_review::gate::unsatisfied_assignments(&db, &base_ref)? {
+            eprintln!("  {} (assigned to {})", file_path, assignee);
+        }
+        return Err(CliError::CheckFailed(
+            "assigned hunks not reviewed by their assignee".to_string(),
+        )
+        .into());
+    }
+
+    println!("✓ Review gate passed");
+    Ok(())
+}
+
+/// Log a gate check in the review history. Failing to log never blocks a commit.
+fn record_gate_check(db: &mut ReviewDb, base_ref: &str, passed: bool) {
+    if let Ok(progress) = db.progress(base_ref) {
+        let summary = format!(
+            "{}/{} hunks reviewed",
+            progress.reviewed, progress.total_hunks
+        );
+        db.record_gate_check(base_ref, passed, &summary).ok();
+    }
+}
+
+/// Handle gate status - explain which hooks are installed, the policy, and
+/// why the staged changes pass or fail it.
+fn handle_gate_status() -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let hooks_dir = git_review::git::hooks_dir(&repo_root)?;
+    println!(
+        "Hooks ({}):",
+        hooks_dir
+            .strip_prefix(&repo_root)
+            .unwrap_or(&hooks_dir)
+            .display()
+    );
+    let mut require_assignee = false;
+    for hook in HookKind::ALL {
+        let state = git_review::gate::hook_state(&repo_root, hook)?;
+        let description = match state {
+            HookState::Missing => "○ not installed".to_string(),
+            HookState::Foreign => "! another hook is installed (not git-review's)".to_string(),
+            HookState::Installed {
+                chained,
+                require_assignee: assignee,
+            } => {
+                if hook == HookKind::PreCommit {
+                    require_assignee = assignee;
+                }
+                let mut description = "✓ installed".to_string();
+                if chained {
+                    description.push_str(", runs the previous hook first");
+                }
+                if assignee {
+                    description.push_str(", requires assignee reviews");
+                }
+                description
+            }
+        };
+        println!("  {:<11} {}", hook.file_name(), description);
+    }
+
+    let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+    let policy = &config.gate;
+    println!(
+        "Policy: {}% of hunks reviewed, stale hunks {}",
+        policy.threshold,
+        if policy.allow_stale {
+            "allowed"
+        } else {
+            "fail"
+        }
+    );
+    if !policy.strict.is_empty() {
+        println!("  strict:  {}", policy.strict.join(", "));
+    }
+    if !policy.lenient.is_empty() {
+        println!("  lenient: {}", policy.lenient.join(", "));
+    }
+    if !policy.skim.is_empty() {
+        println!("  skim:    {}", policy.skim.join(", "));
+    }
+
+    // Evaluated exactly as `gate check` does, against the stored state of the staged diff
+    let base_ref = "HEAD";
+    let diff_output = git_review::git::get_diff(base_ref).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+    if files.is_empty() {
+        println!("Staged changes: none, the gate passes");
+        return Ok(());
+    }
+    let db_path = repo_root.join(".git/review-state/review.db");
+    if !db_path.exists() {
+        println!("Staged changes: ✗ fail, no review state found");
+        println!("  Run 'git-review' to review your changes");
+        return Ok(());
+    }
+    let db = ReviewDb::open(&db_path)?;
+    let report = git_review::gate::evaluate_gate(&db, base_ref, policy)?;
+    let unassigned = if require_assignee {
+        git_review::gate::unsatisfied_assignments(&db, base_ref)?
+    } else {
+        Vec::new()
+    };
+
+    let passes = report.passes() && unassigned.is_empty();
+    println!(
+        "Staged changes: {} ({} of {} counted hunks reviewed)",
+        if passes { "✓ pass" } else { "✗ fail" },
+        report.reviewed,
+        report.considered
+    );
+    let line_of = |file_path: &str, hash: &str| {
+        files
+            .iter()
+            .filter(|file| file.path.to_string_lossy() == file_path)
+            .flat_map(|file| &file.hunks)
+            .find(|hunk| hunk.content_hash == hash)
+            .map(|hunk| hunk.new_start)
+    };
+    for offender in &report.offenders {
+        let location = match line_of(&offender.file_path, &offender.content_hash) {
+            Some(line) => format!("{}:{}", offender.file_path, line),
+            None => offender.file_path.clone(),
+        };
+        println!(
+            "  {:<40} {}  {}",
+            location,
+            &offender.content_hash[..offender.content_hash.len().min(12)],
+            offender.reason.label()
+        );
+    }
+    for (file_path, _, assignee) in &unassigned {
+        println!(
+            "  {:<40} not reviewed by its assignee {}",
+            file_path, assignee
+        );
+    }
+
+    let stored: HashSet<(String, String)> = db
+        .hunks(base_ref)?
+        .into_iter()
+        .map(|record| (record.file_path, record.content_hash))
+        .collect();
+    let unrecorded = files
+        .iter()
+        .flat_map(|file| {
+            let path = file.path.to_string_lossy().to_string();
+            file.hunks
+                .iter()
+                .map(move |hunk| (path.clone(), hunk.content_hash.clone()))
+        })
+        .filter(|key| !stored.contains(key))
+        .count();
+    if unrecorded > 0 {
+        println!(
+            "  {} staged hunk(s) have no review state yet and are not checked; run 'git-review' to record them",
+            unrecorded
+        );
+    }
+    Ok(())
+}
+
+/// Handle commit command - check gate then execute git commit.
+fn handle_commit(git_args: &[String], summarize: bool) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let base_ref = git_review::gate::commit_gate_range(git_args);
+    let amend = base_ref != "HEAD";
+
+    // Get the diff
+    let diff_output = git_review::git::get_diff(&base_ref).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+
+    if files.is_empty() && !amend {
+        bail!("No changes to commit");
+    }
+
+    // Check gate
+    let db_path = repo_root.join(".git/review-state/review.db");
+    if !db_path.exists() {
+        eprintln!("✗ No review state found. Run 'git-review' first to review your changes");
+        return Err(CliError::CheckFailed("no review state found".to_string()).into());
+    }
+
+    let mut db = ReviewDb::open(&db_path)?;
+    let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+    db.set_reviewer(git_review::git::get_user_name().ok());
+    // The amended commit's own hunks may never have been opened for review
+    if amend {
+        db.sync_with_diff(&base_ref, &files)?;
+    }
+
+    let passed = check_gate(&db, &base_ref, &config.gate)?;
+    record_gate_check(&mut db, &base_ref, passed);
+    if !passed {
+        let progress = db.progress(&base_ref)?;
+        let review = if amend {
+            format!("git-review {}", base_ref)
+        } else {
+            "git-review".to_string()
+        };
+        eprintln!(
+            "✗ Review gate failed: {}/{} hunks reviewed, {} unreviewed, {} stale, {} need changes. Run '{}' to complete your review",
+            progress.reviewed,
+            progress.total_hunks,
+            progress.unreviewed,
+            progress.stale,
+            progress.needs_changes,
+            review
+        );
+        return Err(CliError::CheckFailed(format!(
+            "review gate failed: {}/{} hunks reviewed",
+            progress.reviewed, progress.total_hunks
+        ))
+        .into());
+    }
+
+    // Gate passed - execute git commit
+    println!("✓ Review gate passed, proceeding with commit");
+
+    let mut args = git_args.to_vec();
+    if summarize {
+        let session = ReviewSession::from_diff(&repo_root, &base_ref, &diff_output)
+            .context("Failed to open review session")?;
+        let summary = git_review::report::commit_summary(&session)?;
+        args = with_review_summary(&repo_root, git_args, &summary)?;
+    }
+
+    let status = Command::new("git")
+        .arg("commit")
+        .args(&args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .context("Failed to execute git commit")?;
+
+    if !status.success() {
+        return Err(GitError::CommandFailed("git commit".to_string()).into());
+    }
+
+    Ok(())
+}
+
+/// Handle stage - stage the working tree's hunks that are marked reviewed.
+fn handle_stage() -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let db = open_review_db(&repo_root)?;
+    match git_review::stage::stage_reviewed(&db)? {
+        0 => println!("No reviewed hunks left to stage"),
+        count => println!("✓ Staged {} reviewed hunk(s)", count),
+    }
+    Ok(())
+}
+
+/// Handle push - check the review gate for the commits being pushed, then run
+/// `git push` with `git_args`.
+///
+/// The commits are `<upstream>..HEAD`, or `<default branch>..HEAD` for a
+/// branch that has never been pushed. The range is synced first, so commits
+/// made since the last review count as unreviewed.
+fn handle_push(git_args: &[String]) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let branch = git_review::git::get_current_branch()
+        .context("Failed to read the current branch")?
+        .ok_or_else(|| CliError::Usage("push needs a checked-out branch".to_string()))?;
+    // Only the checked-out branch's commits are gated, so nothing else may go out
+    let extra = git_review::git::push_extra_refs(git_args, &branch);
+    if !extra.is_empty() {
+        return Err(CliError::Usage(format!(
+            "push only checks the review of {}; push {} with git directly",
+            branch,
+            extra.join(" ")
+        ))
+        .into());
+    }
+    let base = match git_review::git::upstream_branch(&branch)? {
+        Some(upstream) => upstream,
+        None => {
+            git_review::git::detect_default_branch().context("Could not detect default branch")?
+        }
+    };
+    let range = format!("{}..HEAD", base);
+
+    let diff_output = git_review::git::get_diff(&range).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+    if files.is_empty() {
+        println!("✓ No unpushed changes to review in {}", range);
+    } else {
+        let db_path = repo_root.join(".git/review-state/review.db");
+        if !db_path.exists() {
+            eprintln!(
+                "✗ No review state found. Run 'git-review {}' to review your changes",
+                range
+            );
+            return Err(CliError::CheckFailed("no review state found".to_string()).into());
+        }
+
+        let mut db = open_review_db(&repo_root)?;
+        let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+        db.set_reviewer(git_review::git::get_user_name().ok());
+        let base_ref = git_review::session::review_key(&mut db, &range)?;
+        db.sync_with_diff(&base_ref, &files)?;
+
+        let passed = check_gate(&db, &base_ref, &config.gate)?;
+        record_gate_check(&mut db, &base_ref, passed);
+        if !passed {
+            let progress = db.progress(&base_ref)?;
+            eprintln!(
+                "✗ Review gate failed for {}: {}/{} hunks reviewed, {} unreviewed, {} stale, {} need changes. Run 'git-review {}' to complete your review",
+                range,
+                progress.reviewed,
+                progress.total_hunks,
+                progress.unreviewed,
+                progress.stale,
+                progress.needs_changes,
+                range
+            );
+            return Err(CliError::CheckFailed(format!(
+                "review gate failed: {}/{} hunks reviewed",
+                progress.reviewed, progress.total_hunks
+            ))
+            .into());
+        }
+        println!("✓ Review gate passed for {}, proceeding with push", range);
+    }
+
+    let status = Command::new("git")
+        .arg("push")
+        .args(git_args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .context("Failed to execute git push")?;
+
+    if !status.success() {
+        return Err(GitError::CommandFailed("git push".to_string()).into());
+    }
+
+    Ok(())
+}
+
+/// `git commit` arguments with a review summary added to the message.
+///
+/// When git opens the editor, the summary is written under the subject line
+/// of a message template, ahead of the `commit.template` if one is set. With
+/// `-m`, it becomes the message's last paragraph. Other ways of giving the
+/// message leave no room for it, so it is skipped with a warning.
+fn with_review_summary(
+    repo_root: &Path,
+    git_args: &[String],
+    summary: &str,
+) -> Result<Vec<String>> {
+    let options = commit_message_options(git_args);
+    let mut args = git_args.to_vec();
+    if options.iter().any(|&option| option != 'm') {
+        eprintln!("⚠ --summarize needs the editor or -m; the commit message is used as given");
+    } else if options.contains(&'m') {
+        args.extend(["-m".to_string(), summary.trim_end().to_string()]);
+    } else {
+        let mut template = format!("\n\n{}", summary);
+        if let Some(path) = git_review::git::commit_template()? {
+            let existing = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read commit.template {}", path.display()))?;
+            template.push('\n');
+            template.push_str(&existing);
+        }
+        let path = repo_root.join(".git/review-state/COMMIT_TEMPLATE");
+        std::fs::write(&path, template).context("Failed to write the commit message template")?;
+        args.splice(
+            0..0,
+            ["--template".to_string(), path.to_string_lossy().to_string()],
+        );
+    }
+    Ok(args)
+}
+
+/// The `git commit` options among `git_args` that supply the message or its
+/// template, by short name: `m`, `F`, `C`, `c`, or `t`.
+fn commit_message_options(git_args: &[String]) -> Vec<char> {
+    const LONG: [(&str, char); 5] = [
+        ("--message", 'm'),
+        ("--file", 'F'),
+        ("--reuse-message", 'C'),
+        ("--reedit-message", 'c'),
+        ("--template", 't'),
+    ];
+    // Short options that take a value, which may be attached (`-mfix`)
+    const VALUED: &str = "mFCctSu";
+    let mut options = Vec::new();
+    let mut args = git_args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if let Some(long) = arg.strip_prefix("--") {
+            let name = long.split_once('=').map_or(long, |(name, _)| name);
+            if let Some(&(_, short)) = LONG.iter().find(|(option, _)| &option[2..] == name) {
+                options.push(short);
+                if !long.contains('=') {
+                    args.next();
+                }
+            }
+        } else if let Some(cluster) = arg.strip_prefix('-') {
+            for (i, flag) in cluster.char_indices() {
+                if VALUED.contains(flag) {
+                    if "mFCct".contains(flag) {
+                        options.push(flag);
+                        if i + 1 == cluster.len() {
+                            args.next();
+                        }
+                    }
+                    break;
+                }
+            }
+        }
+    }
+    options
+}
+
+/// Handle reset command - clear review state for a diff range.
+fn handle_reset(args: &ResetArgs) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let db_path = repo_root.join(".git/review-state/review.db");
+    if !db_path.exists() {
+        println!("No review state to reset");
+        return Ok(());
+    }
+
+    let mut db = ReviewDb::open(&db_path)?;
+    let (base_refs, label) = if args.all {
+        (db.list_base_refs()?, "the repository".to_string())
+    } else if let Some(branch) = &args.branch {
+        (
+            db.base_refs_in_namespace(branch)?,
+            format!("branch {}", branch),
+        )
+    } else {
+        let diff_range = args.diff_range.as_deref().unwrap_or("HEAD");
+        if args.dry_run {
+            let base_ref = git_review::session::peek_review_key(&db, diff_range)?;
+            let hunks = db.hunks(&base_ref)?;
+            if hunks.is_empty() {
+                println!("No review state stored for {}", diff_range);
+                return Ok(());
+            }
+            println!("Would reset {} hunk(s) of {}:", hunks.len(), diff_range);
+            for hunk in hunks {
+                println!(
+                    "  {:13} {} {:.12}",
+                    status_to_string(hunk.status),
+                    hunk.file_path,
+                    hunk.content_hash
+                );
+            }
+            return Ok(());
+        }
+        let base_ref = git_review::session::review_key(&mut db, diff_range)?;
+        db.reset(&base_ref)?;
+        println!("✓ Review state reset for {}", diff_range);
+        return Ok(());
+    };
+
+    if base_refs.is_empty() {
+        println!("No review state stored for {}", label);
+        return Ok(());
+    }
+    if args.dry_run {
+        println!("Would reset {} review(s) of {}:", base_refs.len(), label);
+        for base_ref in &base_refs {
+            println!("  {} ({} hunk(s))", base_ref, db.hunks(base_ref)?.len());
+        }
+        return Ok(());
+    }
+    println!("Reviews stored for {}:", label);
+    for base_ref in &base_refs {
+        println!("  {}", base_ref);
+    }
+    if !args.yes {
+        if !std::io::stdin().is_terminal() {
+            return Err(CliError::Usage(format!(
+                "Refusing to reset {} review(s) without --yes",
+                base_refs.len()
+            ))
+            .into());
+        }
+        if !confirm(&format!("Reset {} review(s)?", base_refs.len()))? {
+            println!("Nothing reset");
+            return Ok(());
+        }
+    }
+
+    let removed = db.reset_many(&base_refs)?;
+    println!(
+        "✓ Review state reset for {} review(s) ({} hunk(s))",
+        base_refs.len(),
+        removed
+    );
+    Ok(())
+}
+
+/// Canonical review state key for a diff range (see `git::normalize_diff_range`).
+fn state_key(repo_root: &Path, diff_range: &str) -> Result<String> {
+    let mut db = open_review_db(repo_root)?;
+    Ok(git_review::session::review_key(&mut db, diff_range)?)
+}
+
+/// Handle `config doctor` - print the effective config and where each value came from.
+fn handle_config_doctor() -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let layers = Layers::load(&repo_root).context("Failed to load the configuration")?;
+
+    for (label, file, layer) in [
+        ("Shared", git_review::config::CONFIG_FILE, &layers.shared),
+        (
+            "Local",
+            git_review::config::LOCAL_CONFIG_FILE,
+            &layers.local,
+        ),
+    ] {
+        let found = if layer.is_some() { "" } else { " (not found)" };
+        println!("{:7} {}{}", format!("{}:", label), file, found);
+    }
+
+    let entries = layers.entries();
+    let width = entries
+        .iter()
+        .map(|(_, key, value, _)| key.len() + value.to_string().len() + 3)
+        .filter(|&len| len <= 40)
+        .max()
+        .unwrap_or(0);
+    let mut section = None;
+    for (name, key, value, source) in &entries {
+        if section != Some(name) {
+            println!("\n[{}]", name);
+            section = Some(name);
+        }
+        let line = format!("{} = {}", key, value);
+        println!("  {:width$}  # {}", line, source.label());
+    }
+
+    let local = git_review::config::LOCAL_CONFIG_FILE;
+    if layers.local.is_some() {
+        if git_review::git::is_tracked(&repo_root, local)? {
+            println!(
+                "\n! {} is committed; personal settings will be shared with everyone",
+                local
+            );
+        } else if !git_review::git::is_ignored(&repo_root, local)? {
+            println!(
+                "\n! {} is not ignored; add it to .gitignore so it isn't committed by accident",
+                local
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Handle the snapshot subcommands - save, restore, list, and delete named state copies.
+fn handle_snapshot(action: SnapshotAction) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let mut db = open_review_db(&repo_root)?;
+
+    match action {
+        SnapshotAction::Save { name, diff_range } => {
+            let diff_range = diff_range.unwrap_or_else(|| "HEAD".to_string());
+            let base_ref = git_review::session::review_key(&mut db, &diff_range)?;
+            let count = db.save_snapshot(&name, &base_ref)?;
+            println!(
+                "✓ Saved snapshot {} ({} hunks from {})",
+                name, count, diff_range
+            );
+        }
+        SnapshotAction::Restore { name, diff_range } => {
+            let snapshot = db
+                .snapshot(&name)?
+                .with_context(|| format!("No snapshot named {}", name))?;
+            let base_ref = match diff_range.as_deref() {
+                Some(range) => git_review::session::review_key(&mut db, range)?,
+                None => snapshot.base_ref,
+            };
+            let count = db.restore_snapshot(&name, &base_ref)?;
+            println!(
+                "✓ Restored {} hunks from snapshot {} into {}",
+                count, name, base_ref
+            );
+        }
+        SnapshotAction::List => {
+            let snapshots = db.snapshots()?;
+            if snapshots.is_empty() {
+                println!("No snapshots");
+            }
+            for snapshot in snapshots {
+                println!(
+                    "{:20} {:>5} hunks  {}  {}",
+                    snapshot.name, snapshot.hunks, snapshot.created_at, snapshot.base_ref
+                );
+            }
+        }
+        SnapshotAction::Delete { name } => {
+            if !db.delete_snapshot(&name)? {
+                return Err(CliError::Usage(format!("No snapshot named {}", name)).into());
+            }
+            println!("✓ Deleted snapshot {}", name);
+        }
+    }
+    Ok(())
+}
+
+/// Handle the notes subcommands - write review notes to commits, or import them.
+fn handle_notes(action: NotesAction) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let (NotesAction::Sync { diff_range } | NotesAction::Import { diff_range }) = &action;
+    let diff_range = match diff_range {
+        Some(range) => range.clone(),
+        None => format!(
+            "{}..HEAD",
+            git_review::git::detect_default_branch().context("Could not detect default branch")?
+        ),
+    };
+    let base_ref = state_key(&repo_root, &diff_range)?;
+    let diff_output = git_review::git::get_diff(&diff_range).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+    let mut db = open_review_db(&repo_root)?;
+    db.sync_with_diff(&base_ref, &files)?;
+
+    match action {
+        NotesAction::Sync { .. } => {
+            let written = git_review::notes::sync(&db, &base_ref, &diff_range)?;
+            println!(
+                "✓ Wrote review notes to {} commit(s) in {}",
+                written,
+                git_review::notes::NOTES_REF
+            );
+            println!("  View them with: git log --show-notes=git-review");
+            println!(
+                "  Share them with: git push origin {}",
+                git_review::notes::NOTES_REF
+            );
+        }
+        NotesAction::Import { .. } => {
+            let imported = git_review::notes::import(&mut db, &base_ref, &diff_range, &files)?;
+            println!("✓ Imported {} hunk review(s) from notes", imported);
+        }
+    }
+    Ok(())
+}
+
+/// Handle publish: post each change's hunk comments and vote to Gerrit.
+fn handle_publish(args: &cli::PublishArgs) -> Result<()> {
+    if !args.gerrit {
+        return Err(CliError::Usage(
+            "Choose where to publish, e.g. `git-review publish --gerrit`".to_string(),
+        )
+        .into());
+    }
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+    let diff_range = match &args.diff_range {
+        Some(range) => range.clone(),
+        None => format!(
+            "{}..HEAD",
+            git_review::git::detect_default_branch().context("Could not detect default branch")?
+        ),
+    };
+    let base_ref = state_key(&repo_root, &diff_range)?;
+    let diff_output = git_review::git::get_diff(&diff_range).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+    let mut db = open_review_db(&repo_root)?;
+    db.sync_with_diff(&base_ref, &files)?;
+
+    let me = git_review::git::get_user_name().ok();
+    let (reviews, skipped) =
+        git_review::gerrit::reviews(&db, &base_ref, &diff_range, &config.gerrit, me.as_deref())?;
+    for review in &reviews {
+        let short = &review.commit[..review.commit.len().min(7)];
+        if args.dry_run {
+            println!("{} ({} {})", review.change_id, short, review.subject);
+            println!("{}", serde_json::to_string_pretty(&review.input)?);
+            continue;
+        }
+        git_review::gerrit::publish(&config.gerrit, review)?;
+        let vote = match review.vote {
+            Some(vote) => format!("{} {:+}, ", config.gerrit.label, vote),
+            None => String::new(),
+        };
+        println!(
+            "✓ Published {} ({} {}): {}{} comment(s)",
+            review.change_id, short, review.subject, vote, review.comments
+        );
+    }
+    if reviews.is_empty() {
+        println!(
+            "Nothing to publish: no commit with a Change-Id has hunks left in {}",
+            diff_range
+        );
+    }
+    if skipped > 0 {
+        println!(
+            "  Skipped {} commit(s) without a Change-Id trailer",
+            skipped
+        );
+    }
+    Ok(())
+}
+
+/// Handle ci-check: export the review state of `<base>...HEAD` to an artifact,
+/// or check an artifact against that diff and exit non-zero if it doesn't pass.
+fn handle_ci_check(args: &cli::CiCheckArgs) -> Result<()> {
+    let base = match &args.base {
+        Some(base) => base.clone(),
+        None => {
+            git_review::git::detect_default_branch().context("Could not detect default branch")?
+        }
+    };
+    let range = format!("{}...HEAD", base);
+    let artifact_path = &args.state_artifact;
+    // The artifact is usually committed to the branch; it can't record its own hunks
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let artifact_in_repo = std::env::current_dir()?
+        .join(artifact_path)
+        .strip_prefix(&repo_root)
+        .map(Path::to_path_buf)
+        .ok();
+    let without_artifact = |mut files: Vec<git_review::DiffFile>| {
+        files.retain(|file| Some(&file.path) != artifact_in_repo.as_ref());
+        files
+    };
+
+    if args.export {
+        let session = ReviewSession::open(&range).context("Failed to open review session")?;
+        let head = git_review::git::get_head_sha()?;
+        let files = without_artifact(session.files().to_vec());
+        let artifact = StateArtifact::export(&range, &head, &files, &session.hunk_records()?);
+        std::fs::write(artifact_path, serde_json::to_string_pretty(&artifact)?)
+            .with_context(|| format!("Failed to write {}", artifact_path.display()))?;
+        let reviewed = artifact
+            .hunks
+            .iter()
+            .filter(|hunk| hunk.status == status_to_string(git_review::HunkStatus::Reviewed))
+            .count();
+        println!(
+            "✓ Exported review state of {} ({}/{} hunks reviewed) to {}",
+            range,
+            reviewed,
+            artifact.hunks.len(),
+            artifact_path.display()
+        );
+        return Ok(());
+    }
+
+    let json = std::fs::read_to_string(artifact_path)
+        .with_context(|| format!("Failed to read {}", artifact_path.display()))?;
+    let artifact = StateArtifact::parse(&json)?;
+    let diff_output = git_review::git::get_diff(&range).context("Failed to get git diff")?;
+    let report = ci::check(&artifact, &without_artifact(parse_diff(&diff_output)));
+
+    // GitHub Actions turns these lines into annotations on the PR's files
+    let annotate = std::env::var("GITHUB_ACTIONS").is_ok_and(|v| v == "true");
+    for hunk in &report.pending {
+        let reason = match hunk.status {
+            Some(status) => format!("hunk is {}", status_to_string(status)),
+            None => "hunk is not in the review state".to_string(),
+        };
+        if annotate {
+            println!(
+                "::error file={},line={}::Unreviewed change: {}",
+                hunk.file, hunk.line, reason
+            );
+        } else {
+            println!("  {}:{}: {}", hunk.file, hunk.line, reason);
+        }
+    }
+    for (file, hash) in &report.foreign {
+        println!(
+            "  {}: hunk {} in the review state is not in {}",
+            file,
+            &hash[..hash.len().min(12)],
+            range
+        );
+    }
+
+    if report.passes() {
+        println!(
+            "✓ Review check passed: {}/{} hunks of {} reviewed",
+            report.reviewed, report.total, range
+        );
+        return Ok(());
+    }
+    eprintln!(
+        "✗ Review check failed: {}/{} hunks of {} reviewed",
+        report.reviewed, report.total, range
+    );
+    if !report.foreign.is_empty() {
+        eprintln!(
+            "  {} hunk(s) in {} don't match the diff: the state was exported for different content (exported at {})",
+            report.foreign.len(),
+            artifact_path.display(),
+            artifact.head
+        );
+    }
+    eprintln!(
+        "  Review locally, then run: git-review ci-check --export --base {} --state-artifact {}",
+        base,
+        artifact_path.display()
+    );
+    Err(CliError::CheckFailed(format!(
+        "{}/{} hunks of {} reviewed",
+        report.reviewed, report.total, range
+    ))
+    .into())
+}
+
+/// Handle verify: report integrity problems in a range's stored state, or fix
+/// them with `repair`. Exits non-zero when problems are left unfixed; hunks
+/// that are merely out of sync with the diff are listed but don't fail.
+fn handle_verify(diff_range: &str, repair: bool) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let mut db = open_review_db(&repo_root)?;
+    let base_ref = git_review::session::review_key(&mut db, diff_range)?;
+    let diff_output = git_review::git::get_diff(diff_range).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+
+    let issues = git_review::verify::verify(&db, &base_ref, &files)?;
+    if issues.is_empty() {
+        println!("✓ Review state of {} matches the diff", diff_range);
+        return Ok(());
+    }
+    let (unsynced, corrupt): (Vec<_>, Vec<_>) =
+        issues.iter().partition(|issue| issue.kind.is_unsynced());
+    let print_issue = |issue: &git_review::verify::Issue| {
+        println!(
+            "  {:<10} {}  {}  {}",
+            issue.kind.label(),
+            issue.file_path,
+            &issue.content_hash[..issue.content_hash.len().min(12)],
+            issue.detail
+        )
+    };
+    corrupt.iter().for_each(|issue| print_issue(issue));
+    if !unsynced.is_empty() {
+        println!(
+            "{} hunk(s) not yet synced with the diff (reviewing {} syncs them):",
+            unsynced.len(),
+            diff_range
+        );
+        unsynced.iter().for_each(|issue| print_issue(issue));
+    }
+
+    if repair {
+        git_review::verify::repair(&mut db, &base_ref, &files, &issues)?;
+        println!("✓ Repaired {} issue(s) in {}", issues.len(), diff_range);
+        return Ok(());
+    }
+    if corrupt.is_empty() {
+        println!(
+            "✓ No integrity issues in the review state of {}",
+            diff_range
+        );
+        return Ok(());
+    }
+    eprintln!(
+        "✗ {} integrity issue(s) in the review state of {}",
+        corrupt.len(),
+        diff_range
+    );
+    eprintln!(
+        "  Run 'git-review verify --repair {}' to fix them",
+        diff_range
+    );
+    Err(CliError::CheckFailed(format!(
+        "{} integrity issue(s) in the review state of {}",
+        corrupt.len(),
+        diff_range
+    ))
+    .into())
+}
+
+/// Handle the step command - answer for unreviewed hunks one at a time over stdin/stdout.
+fn handle_step(diff_range: &str) -> Result<()> {
+    let mut session = ReviewSession::open(diff_range).context("Failed to open review session")?;
+    let outcome = git_review::step::run(
+        &mut session,
+        &mut std::io::stdin().lock(),
+        &mut std::io::stdout(),
+    )?;
+    // Summary on stderr, so stdout only carries hunks and prompts
+    eprintln!(
+        "{} approved, {} need changes, {} skipped, {} not answered",
+        outcome.approved, outcome.flagged, outcome.skipped, outcome.remaining
+    );
+    Ok(())
+}
+
+/// Handle changed-since - list the hunks of a diff range that became unreviewed
+/// or stale since a point in time, e.g. after a teammate pushed more commits.
+fn handle_changed_since(since: &str, diff_range: &str) -> Result<()> {
+    let mut session = ReviewSession::open(diff_range).context("Failed to open review session")?;
+    let time = point_in_time(session.db(), since)?;
+    let base_ref = session.base_ref().to_string();
+    let changed = session.db().changed_since(&base_ref, time)?;
+    let list = git_review::report::changed(&session, &changed)?;
+    if list.is_empty() {
+        println!(
+            "Nothing to review in {} changed since {}",
+            diff_range, since
+        );
+    } else {
+        print!("{}", list);
+    }
+    Ok(())
+}
+
+/// Unix time of a `changed-since` point: a Unix timestamp, a local date or date
+/// and time, or a tag or other commit (its commit time).
+fn point_in_time(db: &ReviewDb, spec: &str) -> Result<i64> {
+    if let Ok(seconds) = spec.parse() {
+        return Ok(seconds);
+    }
+    if let Some(time) = db.parse_local_time(spec)? {
+        return Ok(time);
+    }
+    git_review::git::commit_time(spec)
+        .with_context(|| format!("Not a timestamp, date, or commit: {}", spec))
+}
+
+/// Handle approve command - bulk approve hunks.
+fn handle_approve(args: &cli::ApproveArgs) -> Result<()> {
+    let diff_range = args.diff_range.as_str();
+    let file_filter = args.file.as_deref();
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let diff_output = git_review::git::get_diff(diff_range).context("Failed to get git diff")?;
+    let files = parse_diff(&diff_output);
+
+    if files.is_empty() {
+        println!("No changes to approve");
+        return Ok(());
+    }
+    let grep = args
+        .grep
+        .as_deref()
+        .map(regex::Regex::new)
+        .transpose()
+        .context("Invalid --grep pattern")?;
+    if args.dry_run {
+        return print_approval_plan(&repo_root, diff_range, &files, file_filter, grep.as_ref());
+    }
+
+    let base_ref = state_key(&repo_root, diff_range)?;
+    let mut db = open_review_db(&repo_root)?;
+    db.sync_with_diff(&base_ref, &files)?;
+
+    let count = if let Some(pattern) = &grep {
+        git_review::autoapprove::approve_matching(&mut db, &base_ref, &files, pattern, file_filter)?
+    } else if let Some(file_path) = file_filter {
+        db.approve_file(&base_ref, file_path)?
+    } else {
+        db.approve_all(&base_ref)?
+    };
+
+    println!("✓ Approved {} hunks for {}", count, diff_range);
+    Ok(())
+}
+
+/// Handle undo-last command - revert the most recent bulk approval.
+fn handle_undo_last(diff_range: Option<&str>) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let base_ref = match diff_range {
+        Some(range) => Some(state_key(&repo_root, range)?),
+        None => None,
+    };
+    let mut db = open_review_db(&repo_root)?;
+    let Some(approval) = db.last_bulk_approval(base_ref.as_deref())? else {
+        println!("No bulk approval to undo");
+        return Ok(());
+    };
+    let restored = db.undo_bulk_approval(&approval)?;
+    let scope = match &approval.file_path {
+        Some(path) => format!("{} in {}", path, approval.base_ref),
+        None => approval.base_ref.clone(),
+    };
+    println!(
+        "✓ Undid the approval of {} from {}: {} of {} hunk(s) restored",
+        scope, approval.approved_at, restored, approval.hunks
+    );
+    if restored < approval.hunks {
+        println!("  (the others changed status since and were left alone)");
+    }
+    Ok(())
+}
+
+/// List the hunks `approve` would mark reviewed, without syncing or changing
+/// the stored state.
+fn print_approval_plan(
+    repo_root: &Path,
+    diff_range: &str,
+    files: &[git_review::DiffFile],
+    file_filter: Option<&str>,
+    grep: Option<&regex::Regex>,
+) -> Result<()> {
+    let db = open_review_db(repo_root)?;
+    let base_ref = git_review::session::peek_review_key(&db, diff_range)?;
+    let mut pending: Vec<(String, git_review::HunkStatus)> = Vec::new();
+    if let Some(pattern) = grep {
+        for (path, hunk) in
+            git_review::autoapprove::matching_hunks(&db, &base_ref, files, pattern, file_filter)?
+        {
+            pending.push((
+                format!("{}:{}", path, hunk.new_start),
+                git_review::HunkStatus::Unreviewed,
+            ));
+        }
+    } else {
+        // Stored hunks that left the diff are marked stale by the sync and
+        // then approved along with the rest
+        let mut stored: HashMap<(String, String), git_review::HunkStatus> = db
+            .hunks(&base_ref)?
+            .into_iter()
+            .filter(|r| file_filter.is_none_or(|only| only == r.file_path))
+            .map(|r| ((r.file_path, r.content_hash), r.status))
+            .collect();
+        for file in files {
+            let path = file.path.to_string_lossy().to_string();
+            if file_filter.is_some_and(|only| only != path) {
+                continue;
+            }
+            for hunk in &file.hunks {
+                let status = stored
+                    .remove(&(path.clone(), hunk.content_hash.clone()))
+                    .unwrap_or(git_review::HunkStatus::Unreviewed);
+                if status != git_review::HunkStatus::Reviewed {
+                    pending.push((format!("{}:{}", path, hunk.new_start), status));
+                }
+            }
+        }
+        let mut gone: Vec<_> = stored.into_keys().map(|(path, _)| path).collect();
+        gone.sort();
+        for path in gone {
+            pending.push((
+                format!("{} (no longer in the diff)", path),
+                git_review::HunkStatus::Stale,
+            ));
+        }
+    }
+
+    if pending.is_empty() {
+        println!("No hunks to approve for {}", diff_range);
+        return Ok(());
+    }
+    println!(
+        "Would approve {} hunk(s) for {}:",
+        pending.len(),
+        diff_range
+    );
+    for (location, status) in pending {
+        println!("  [{}] {}", status_to_string(status), location);
+    }
+    Ok(())
+}
+
+/// Handle assign command - assign a file in a diff range to a reviewer.
+fn handle_assign(diff_range: &str, file_path: &str, assignee: &str) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let diff_output = git_review::git::get_diff(diff_range).context("Failed to get git diff")?;
+    if !parse_diff(&diff_output)
+        .iter()
+        .any(|file| file.path == Path::new(file_path))
+    {
+        return Err(
+            CliError::Usage(format!("{} has no changes in {}", file_path, diff_range)).into(),
+        );
+    }
+    let base_ref = state_key(&repo_root, diff_range)?;
+
+    let mut db = open_review_db(&repo_root)?;
+    db.assign(&base_ref, file_path, assignee)?;
+
+    println!(
+        "✓ Assigned {} to {} for {}",
+        file_path, assignee, diff_range
+    );
+    Ok(())
+}
+
+/// Deliver a watch event to the desktop and the configured webhook.
+///
+/// Failures are reported but do not stop the watch loop.
+fn send_notifications(event: &ReviewEvent, webhook: Option<&str>) {
+    println!("  → {}", event.message());
+    deliver_notifications(event, webhook);
+}
+
+/// Send an event to the desktop and the configured webhook, reporting failures.
+fn deliver_notifications(event: &ReviewEvent, webhook: Option<&str>) {
+    if let Err(e) = git_review::notify::desktop(event) {
+        eprintln!("  ! {}", e);
+    }
+    if let Some(url) = webhook
+        && let Err(e) = git_review::notify::webhook(url, event)
+    {
+        eprintln!("  ! {}", e);
+    }
+}
+
+/// Handle watch command - continuously monitor branches.
+fn handle_watch(args: &cli::WatchArgs) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let config = Config::load(&repo_root).context("Failed to load .git-review.toml")?;
+    let webhook = config.notify.webhook.as_deref();
+    let base =
+        git_review::git::detect_default_branch().context("Could not detect default branch")?;
+    let mut db = open_review_db(&repo_root)?;
+    let mut last_progress: HashMap<String, ReviewProgress> = HashMap::new();
+    println!(
+        "Watching for branches needing review against {} (Ctrl+C to stop)...\n",
+        base
+    );
+
+    loop {
+        if args.fetch
+            && let Err(e) = git_review::git::fetch_all()
+        {
+            eprintln!("  ! {}", e);
+        }
+
+        let branches = branches_to_check(&base, &args.branches, args.remotes)?;
+        for branch in branches.iter().map(|b| b.name.as_str()) {
+            let diff_range = format!("{}..{}", base, branch);
+            if let Ok(diff_output) = git_review::git::get_diff(&diff_range) {
+                let files = parse_diff(&diff_output);
+                if files.is_empty() {
+                    continue;
+                }
+
+                let Ok(key) = git_review::session::review_key(&mut db, &diff_range) else {
+                    continue;
+                };
+                db.sync_with_diff(&key, &files).ok();
+                if let Ok(progress) = db.progress(&key) {
+                    let pct = if progress.total_hunks > 0 {
+                        (progress.reviewed as f64 / progress.total_hunks as f64) * 100.0
+                    } else {
+                        0.0
+                    };
+                    let status = git_review::palette::status_symbol(
+                        git_review::palette::summary_status((&progress).into()),
+                    );
+                    println!(
+                        "{} {:40} {}/{} ({:.0}%)",
+                        status, branch, progress.reviewed, progress.total_hunks, pct
+                    );
+
+                    let event = detect_event(branch, last_progress.get(branch), &progress);
+                    if let (true, Some(event)) = (args.notify, event) {
+                        send_notifications(&event, webhook);
+                    }
+                    last_progress.insert(branch.to_string(), progress);
+                }
+            }
+        }
+        if args.merge {
+            merge_queued_branches(&mut db, &base, &config.merge_queue)?;
+        }
+        println!("─── refreshing in {}s ───\n", args.interval);
+        std::thread::sleep(std::time::Duration::from_secs(args.interval));
+    }
+}
+
+/// Merge the queued branches that are ready, asking first unless the
+/// `[merge_queue]` config turns confirmation off. Declined branches leave the queue.
+fn merge_queued_branches(
+    db: &mut ReviewDb,
+    base: &str,
+    config: &git_review::queue::MergeQueueConfig,
+) -> Result<()> {
+    for branch in git_review::queue::ready_branches(db, base)? {
+        if config.confirm && !confirm(&format!("Merge queued branch {} into {}?", branch, base))? {
+            db.dequeue_merge(&branch)?;
+            println!("  Removed {} from the merge queue", branch);
+            continue;
+        }
+        match git_review::queue::merge(db, base, &branch, config) {
+            Ok(()) => println!("✓ Merged {} into {}", branch, base),
+            Err(e) => eprintln!("✗ {}: {}", branch, e),
+        }
+    }
+    Ok(())
+}
+
+/// Local (and with `remotes`, remote-tracking) branches other than `base`,
+/// limited to those matching `globs` if any are given.
+fn branches_to_check(
+    base: &str,
+    globs: &[String],
+    remotes: bool,
+) -> Result<Vec<git_review::git::BranchInfo>> {
+    let filter = git_review::gate::build_glob_set(globs)?;
+    let mut branches = git_review::git::list_branches().context("Failed to list branches")?;
+    if remotes {
+        branches.extend(
+            git_review::git::list_remote_branches().context("Failed to list remote branches")?,
+        );
+    }
+    branches.retain(|branch| {
+        // Skip the base itself (locally or as a remote-tracking branch); only
+        // remote-tracking names start with a remote
+        let name = branch.name.as_str();
+        let short_name = match name.split_once('/') {
+            Some((_, rest)) if !branch.is_local => rest,
+            _ => name,
+        };
+        name != base && short_name != base && (globs.is_empty() || filter.is_match(name))
+    });
+    Ok(branches)
+}
+
+/// Handle nag command - report branches whose review has stalled.
+///
+/// Prints nothing when no branch is overdue, so a cron job only mails when
+/// there is something to say.
+fn handle_nag(args: &cli::NagArgs) -> Result<()> {
+    let repo_root = git_review::git::find_repo_root().context("Not in a git repository")?;
+    let webhook = Config::load(&repo_root)
+        .context("Failed to load .git-review.toml")?
+        .notify
+        .webhook;
+    let base =
+        git_review::git::detect_default_branch().context("Could not detect default branch")?;
+    let mut db = open_review_db(&repo_root)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+
+    for branch in branches_to_check(&base, &args.branches, args.remotes)? {
+        let diff_range = format!("{}..{}", base, branch.name);
+        let Ok(diff_output) = git_review::git::get_diff(&diff_range) else {
+            continue;
+        };
+        let files = parse_diff(&diff_output);
+        if files.is_empty() {
+            continue;
+        }
+        let key = git_review::session::review_key(&mut db, &diff_range)?;
+        db.sync_with_diff(&key, &files)?;
+        let progress = db.progress(&key)?;
+        let last_review = db.last_review_time(&key)?;
+
+        if let Some(event) = git_review::notify::overdue_event(
+            &branch.name,
+            &progress,
+            branch.last_commit_timestamp,
+            last_review,
+            now,
+            args.days,
+        ) {
+            println!("{}", event.message());
+            if args.notify {
+                deliver_notifications(&event, webhook.as_deref());
+            }
+        }
+    }
+    Ok(())
 }
@@ -0,0 +1,221 @@
+//! Moved-code detection, in the spirit of `git diff --color-moved`.
+//!
+//! A removed line is *moved* when the same text is added somewhere else in the
+//! diff, and an added line when the same text is removed somewhere. Like git's
+//! default mode, lines only count as moved in runs ("blocks") holding at least
+//! [`MIN_BLOCK_ALNUM`] alphanumeric characters, so lone braces and blank lines
+//! that happen to appear on both sides are not flagged.
+
+use crate::autoapprove::RULE_MOVED;
+use crate::state::{self, ReviewDb};
+use crate::{DiffFile, DiffHunk, HunkStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Fewest alphanumeric characters a run of lines needs to count as moved.
+pub const MIN_BLOCK_ALNUM: usize = 20;
+
+/// `[moved]` section of `.git-review.toml`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MovedConfig {
+    /// Mark hunks that consist entirely of moved code as reviewed when a review opens.
+    pub auto_approve: bool,
+}
+
+/// The removed and added lines of a whole diff, for looking up moves.
+#[derive(Debug, Default)]
+pub struct MoveIndex {
+    removed: HashSet<String>,
+    added: HashSet<String>,
+}
+
+impl MoveIndex {
+    /// Index every added and removed line of a diff.
+    pub fn new(files: &[DiffFile]) -> Self {
+        let mut index = Self::default();
+        for hunk in files.iter().flat_map(|file| &file.hunks) {
+            for line in hunk.content.iter_lines() {
+                if let Some(text) = line.strip_prefix('-') {
+                    index.removed.insert(text.to_string());
+                } else if let Some(text) = line.strip_prefix('+') {
+                    index.added.insert(text.to_string());
+                }
+            }
+        }
+        index
+    }
+
+    /// Whether the other side of the diff has this line's text.
+    fn has_counterpart(&self, line: &str) -> bool {
+        if let Some(text) = line.strip_prefix('-') {
+            self.added.contains(text)
+        } else if let Some(text) = line.strip_prefix('+') {
+            self.removed.contains(text)
+        } else {
+            false
+        }
+    }
+
+    /// For each line of `hunk.content`, whether it is part of a moved block.
+    pub fn moved_lines(&self, hunk: &DiffHunk) -> Vec<bool> {
+        let lines: Vec<&str> = hunk.content.lines().collect();
+        let mut flags = vec![false; lines.len()];
+
+        let mut i = 0;
+        while i < lines.len() {
+            if !self.has_counterpart(lines[i]) {
+                i += 1;
+                continue;
+            }
+            // A block is a run of same-side lines that all have a counterpart
+            let start = i;
+            let side = &lines[start][..1];
+            while i < lines.len() && lines[i].starts_with(side) && self.has_counterpart(lines[i]) {
+                i += 1;
+            }
+            let alnum: usize = lines[start..i]
+                .iter()
+                .map(|line| line.chars().filter(|c| c.is_alphanumeric()).count())
+                .sum();
+            if alnum >= MIN_BLOCK_ALNUM {
+                flags[start..i].fill(true);
+            }
+        }
+        flags
+    }
+
+    /// Whether every added and removed line of `hunk` is moved code.
+    pub fn is_moved(&self, hunk: &DiffHunk) -> bool {
+        let mut changed = false;
+        for (line, moved) in hunk.content.lines().zip(self.moved_lines(hunk)) {
+            if line.starts_with('+') || line.starts_with('-') {
+                if !moved {
+                    return false;
+                }
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// Mark unreviewed hunks that consist entirely of moved code as reviewed, as
+/// [`RULE_MOVED`] rather than by the current reviewer.
+///
+/// Returns how many hunks were approved. Stale and already reviewed hunks are left alone.
+pub fn approve_moved(
+    db: &mut ReviewDb,
+    base_ref: &str,
+    files: &[DiffFile],
+) -> state::Result<usize> {
+    let index = MoveIndex::new(files);
+    let mut approved = 0;
+    for file in files {
+        let file_path = file.path.to_string_lossy();
+        for hunk in &file.hunks {
+            if index.is_moved(hunk)
+                && db.get_status(base_ref, &file_path, &hunk.content_hash)?
+                    == HunkStatus::Unreviewed
+            {
+                db.auto_approve(base_ref, &file_path, &hunk.content_hash, RULE_MOVED)?;
+                approved += 1;
+            }
+        }
+    }
+    Ok(approved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_diff;
+
+    const MOVE_DIFF: &str = "\
+diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -1,5 +1,2 @@
+ fn keep() {}
+-fn helper(value: u32) -> u32 {
+-    value * 2
+-}
+ fn other() {}
+diff --git a/src/b.rs b/src/b.rs
+--- a/src/b.rs
++++ b/src/b.rs
+@@ -1,2 +1,6 @@
+ fn start() {}
++fn helper(value: u32) -> u32 {
++    value * 2
++}
++fn brand_new() {}
+ fn end() {}
+";
+
+    #[test]
+    fn removed_block_that_reappears_is_moved() {
+        let files = parse_diff(MOVE_DIFF);
+        let index = MoveIndex::new(&files);
+
+        let removed = &files[0].hunks[0];
+        assert_eq!(
+            index.moved_lines(removed),
+            vec![false, true, true, true, false]
+        );
+        assert!(index.is_moved(removed));
+
+        // The added side is moved too, except for the genuinely new line
+        let added = &files[1].hunks[0];
+        assert_eq!(
+            index.moved_lines(added),
+            vec![false, true, true, true, false, false]
+        );
+        assert!(!index.is_moved(added));
+    }
+
+    #[test]
+    fn short_blocks_are_not_moved() {
+        let diff = "\
+diff --git a/a.rs b/a.rs
+--- a/a.rs
++++ b/a.rs
+@@ -1,3 +1,3 @@
+ fn a() {
+-}
++}
+";
+        let files = parse_diff(diff);
+        let index = MoveIndex::new(&files);
+        assert!(!index.is_moved(&files[0].hunks[0]));
+    }
+
+    #[test]
+    fn approve_moved_marks_only_fully_moved_hunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        let files = parse_diff(MOVE_DIFF);
+        db.sync_with_diff("HEAD", &files).unwrap();
+        db.set_reviewer(Some("tess".to_string()));
+
+        assert_eq!(approve_moved(&mut db, "HEAD", &files).unwrap(), 1);
+        let status = |file: &DiffFile| {
+            db.get_status(
+                "HEAD",
+                &file.path.to_string_lossy(),
+                &file.hunks[0].content_hash,
+            )
+            .unwrap()
+        };
+        assert_eq!(status(&files[0]), HunkStatus::Reviewed);
+        assert_eq!(status(&files[1]), HunkStatus::Unreviewed);
+        // The rule approved it, not the reviewer
+        let hunks = db.hunks("HEAD").unwrap();
+        assert_eq!(hunks[0].reviewed_by, None);
+        assert_eq!(hunks[0].auto_rule.as_deref(), Some(RULE_MOVED));
+        assert!(db.events(None).unwrap().is_empty());
+
+        // Already reviewed hunks are not counted again
+        assert_eq!(approve_moved(&mut db, "HEAD", &files).unwrap(), 0);
+    }
+}
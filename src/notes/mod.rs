@@ -0,0 +1,222 @@
+//! Review state shared through git notes.
+//!
+//! `notes sync` attaches a note to every commit of a range under
+//! [`NOTES_REF`], summarizing how many of the hunks that commit introduced
+//! have been reviewed. `git log --show-notes=git-review` then shows review
+//! status next to each commit, and since notes travel with
+//! `git push/fetch <remote> refs/notes/git-review`, `notes import` can rebuild
+//! the review state in another clone.
+//!
+//! A commit's hunks are matched to the range's review state by content hash,
+//! so hunks a later commit rewrote are reported as superseded rather than
+//! unreviewed.
+
+use crate::git::{self, GitError};
+use crate::parser::parse_diff;
+use crate::state::{self, ReviewDb, StateError};
+use crate::{DiffFile, HunkStatus};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Notes ref review summaries are written to.
+pub const NOTES_REF: &str = "refs/notes/git-review";
+
+/// Errors that can occur while syncing review notes.
+#[derive(Debug, Error)]
+pub enum NotesError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+pub type Result<T> = std::result::Result<T, NotesError>;
+
+/// One hunk line of a review note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteEntry {
+    pub status: HunkStatus,
+    pub content_hash: String,
+    pub file_path: String,
+    pub reviewed_by: Option<String>,
+}
+
+/// Render the note for a commit of the review stored under `base_ref`.
+///
+/// The first line is a human-readable summary; each hunk follows on a
+/// tab-separated line (`status`, hash, path, reviewer) that [`parse_note`] reads back.
+pub fn format_note(base_ref: &str, entries: &[NoteEntry], superseded: usize) -> String {
+    let count = |status| entries.iter().filter(|e| e.status == status).count();
+    let mut extras = Vec::new();
+    for (n, label) in [
+        (count(HunkStatus::Unreviewed), "unreviewed"),
+        (count(HunkStatus::NeedsChanges), "need changes"),
+        (count(HunkStatus::Skimmed), "skimmed"),
+        (superseded, "superseded by later commits"),
+    ] {
+        if n > 0 {
+            extras.push(format!("{} {}", n, label));
+        }
+    }
+
+    let mut note = format!(
+        "git-review: {} of {} hunks reviewed",
+        count(HunkStatus::Reviewed),
+        entries.len()
+    );
+    if !extras.is_empty() {
+        note.push_str(&format!(" ({})", extras.join(", ")));
+    }
+    note.push_str(&format!("\nrange: {}\n\n", base_ref));
+    for entry in entries {
+        note.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            state::status_to_string(entry.status),
+            entry.content_hash,
+            entry.file_path,
+            entry.reviewed_by.as_deref().unwrap_or("-")
+        ));
+    }
+    note
+}
+
+/// Read the hunk lines of a note written by [`format_note`], skipping anything else.
+pub fn parse_note(note: &str) -> Vec<NoteEntry> {
+    note.lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let status = state::status_from_str(fields.next()?).ok()?;
+            let content_hash = fields.next()?.to_string();
+            let file_path = fields.next()?.to_string();
+            let reviewed_by = fields.next()?;
+            Some(NoteEntry {
+                status,
+                content_hash,
+                file_path,
+                reviewed_by: (reviewed_by != "-").then(|| reviewed_by.to_string()),
+            })
+        })
+        .collect()
+}
+
+/// Write a review note to every commit of `range` from the state stored under
+/// `base_ref`. Commits that introduce no hunks (merges) are skipped.
+///
+/// Returns the number of notes written.
+pub fn sync(db: &ReviewDb, base_ref: &str, range: &str) -> Result<usize> {
+    let records: HashMap<(String, String), _> = db
+        .hunks(base_ref)?
+        .into_iter()
+        .filter(|record| record.status != HunkStatus::Stale)
+        .map(|record| {
+            (
+                (record.file_path.clone(), record.content_hash.clone()),
+                record,
+            )
+        })
+        .collect();
+
+    let mut written = 0;
+    for sha in git::rev_list(range)? {
+        let files = parse_diff(&git::get_commit_diff(&sha)?);
+        let mut entries = Vec::new();
+        let mut superseded = 0;
+        for file in &files {
+            let file_path = file.path.to_string_lossy().to_string();
+            for hunk in &file.hunks {
+                match records.get(&(file_path.clone(), hunk.content_hash.clone())) {
+                    Some(record) => entries.push(NoteEntry {
+                        status: record.status,
+                        content_hash: hunk.content_hash.clone(),
+                        file_path: file_path.clone(),
+                        reviewed_by: record.reviewed_by.clone(),
+                    }),
+                    None => superseded += 1,
+                }
+            }
+        }
+        if entries.is_empty() && superseded == 0 {
+            continue;
+        }
+        git::write_note(
+            NOTES_REF,
+            &sha,
+            &format_note(base_ref, &entries, superseded),
+        )?;
+        written += 1;
+    }
+    Ok(written)
+}
+
+/// Apply the reviews recorded in notes on the commits of `range` to `base_ref`.
+///
+/// Only hunks that are still in `files` (the range's current diff) and are
+/// unreviewed locally are changed, so local review work is never overwritten.
+/// Returns the number of hunks updated.
+pub fn import(db: &mut ReviewDb, base_ref: &str, range: &str, files: &[DiffFile]) -> Result<usize> {
+    let current: HashSet<(String, String)> = files
+        .iter()
+        .flat_map(|file| {
+            let file_path = file.path.to_string_lossy().to_string();
+            file.hunks
+                .iter()
+                .map(move |hunk| (file_path.clone(), hunk.content_hash.clone()))
+        })
+        .collect();
+
+    let mut imported = 0;
+    for sha in git::rev_list(range)? {
+        let Some(note) = git::read_note(NOTES_REF, &sha)? else {
+            continue;
+        };
+        for entry in parse_note(&note) {
+            if !matches!(
+                entry.status,
+                HunkStatus::Reviewed | HunkStatus::NeedsChanges
+            ) || !current.contains(&(entry.file_path.clone(), entry.content_hash.clone()))
+                || db.get_status(base_ref, &entry.file_path, &entry.content_hash)?
+                    != HunkStatus::Unreviewed
+            {
+                continue;
+            }
+            db.record_status(
+                base_ref,
+                &entry.file_path,
+                &entry.content_hash,
+                entry.status,
+                entry.reviewed_by.as_deref(),
+            )?;
+            imported += 1;
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn note_round_trips_through_text() {
+        let entries = vec![
+            NoteEntry {
+                status: HunkStatus::Reviewed,
+                content_hash: "abc".to_string(),
+                file_path: "src/my file.rs".to_string(),
+                reviewed_by: Some("Jane Doe".to_string()),
+            },
+            NoteEntry {
+                status: HunkStatus::NeedsChanges,
+                content_hash: "def".to_string(),
+                file_path: "src/b.rs".to_string(),
+                reviewed_by: None,
+            },
+        ];
+        let note = format_note("abc123..feature", &entries, 2);
+        assert!(note.starts_with(
+            "git-review: 1 of 2 hunks reviewed (1 need changes, 2 superseded by later commits)\n"
+        ));
+        assert!(note.contains("range: abc123..feature\n"));
+        assert_eq!(parse_note(&note), entries);
+    }
+}
@@ -0,0 +1,285 @@
+use crate::ReviewProgress;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Errors that can occur while sending notifications.
+#[derive(Debug, Error)]
+pub enum NotifyError {
+    #[error("desktop notification failed: {0}")]
+    Desktop(String),
+    #[error("webhook failed: {0}")]
+    Webhook(String),
+}
+
+pub type Result<T> = std::result::Result<T, NotifyError>;
+
+/// Notification settings (`[notify]` in `.git-review.local.toml`).
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// URL that receives a JSON POST for every review event. A personal
+    /// setting (see [`crate::config::PERSONAL_KEYS`]), so a branch under
+    /// review can't choose where its events are sent.
+    pub webhook: Option<String>,
+}
+
+/// A change in a branch's review state worth telling someone about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewEvent {
+    /// Every hunk on the branch is now reviewed.
+    FullyReviewed { branch: String },
+    /// The branch gained hunks that need review.
+    NewUnreviewed { branch: String, added: usize },
+    /// The branch has had hunks waiting for review, with no new commits or
+    /// reviews, for `idle_days` days.
+    Overdue {
+        branch: String,
+        pending: usize,
+        idle_days: u64,
+    },
+}
+
+impl ReviewEvent {
+    /// One-line human-readable description.
+    pub fn message(&self) -> String {
+        match self {
+            ReviewEvent::FullyReviewed { branch } => format!("{} is fully reviewed", branch),
+            ReviewEvent::NewUnreviewed { branch, added } => {
+                format!("{} has {} new unreviewed hunk(s)", branch, added)
+            }
+            ReviewEvent::Overdue {
+                branch,
+                pending,
+                idle_days,
+            } => format!(
+                "{} has {} hunk(s) waiting for review, idle for {} day(s)",
+                branch, pending, idle_days
+            ),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            ReviewEvent::FullyReviewed { .. } => "fully_reviewed",
+            ReviewEvent::NewUnreviewed { .. } => "new_unreviewed",
+            ReviewEvent::Overdue { .. } => "overdue",
+        }
+    }
+
+    fn branch(&self) -> &str {
+        match self {
+            ReviewEvent::FullyReviewed { branch }
+            | ReviewEvent::NewUnreviewed { branch, .. }
+            | ReviewEvent::Overdue { branch, .. } => branch,
+        }
+    }
+}
+
+/// Compare two progress snapshots of a branch and report what changed.
+///
+/// Returns nothing for the first snapshot (`previous` is `None`) so that
+/// starting a watch does not announce every branch.
+pub fn detect_event(
+    branch: &str,
+    previous: Option<&ReviewProgress>,
+    current: &ReviewProgress,
+) -> Option<ReviewEvent> {
+    let previous = previous?;
+    let complete = |p: &ReviewProgress| {
+        p.total_hunks > 0 && p.unreviewed == 0 && p.stale == 0 && p.needs_changes == 0
+    };
+
+    if complete(current) && !complete(previous) {
+        Some(ReviewEvent::FullyReviewed {
+            branch: branch.to_string(),
+        })
+    } else if current.unreviewed > previous.unreviewed {
+        Some(ReviewEvent::NewUnreviewed {
+            branch: branch.to_string(),
+            added: current.unreviewed - previous.unreviewed,
+        })
+    } else {
+        None
+    }
+}
+
+/// Report a branch whose unreviewed, skimmed, or stale hunks have sat for at least
+/// `days` days.
+///
+/// The branch counts as idle since its last commit or its last review by a
+/// person (Unix timestamps), whichever is later.
+pub fn overdue_event(
+    branch: &str,
+    progress: &ReviewProgress,
+    last_commit: i64,
+    last_review: Option<i64>,
+    now: i64,
+    days: u64,
+) -> Option<ReviewEvent> {
+    let pending = progress.unreviewed + progress.stale + progress.skimmed;
+    let last_activity = last_review.map_or(last_commit, |review| review.max(last_commit));
+    let idle_days = (now - last_activity).max(0) as u64 / 86_400;
+    (pending > 0 && idle_days >= days).then(|| ReviewEvent::Overdue {
+        branch: branch.to_string(),
+        pending,
+        idle_days,
+    })
+}
+
+/// Show a desktop notification for an event.
+#[cfg(feature = "notify")]
+pub fn desktop(event: &ReviewEvent) -> Result<()> {
+    notify_rust::Notification::new()
+        .summary("git-review")
+        .body(&event.message())
+        .show()
+        .map(|_| ())
+        .map_err(|e| NotifyError::Desktop(e.to_string()))
+}
+
+/// Desktop notifications need the `notify` feature.
+#[cfg(not(feature = "notify"))]
+pub fn desktop(_event: &ReviewEvent) -> Result<()> {
+    Err(NotifyError::Desktop(
+        "git-review was built without the `notify` feature".to_string(),
+    ))
+}
+
+/// How long a webhook may take to answer.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// POST an event as JSON to a webhook URL.
+pub fn webhook(url: &str, event: &ReviewEvent) -> Result<()> {
+    let payload = serde_json::json!({
+        "event": event.kind(),
+        "branch": event.branch(),
+        "text": event.message(),
+    });
+
+    match ureq::post(url).timeout(WEBHOOK_TIMEOUT).send_json(&payload) {
+        Ok(_) => Ok(()),
+        Err(ureq::Error::Status(code, _)) => Err(NotifyError::Webhook(format!("HTTP {}", code))),
+        Err(e) => Err(NotifyError::Webhook(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(reviewed: usize, unreviewed: usize, stale: usize) -> ReviewProgress {
+        ReviewProgress {
+            total_hunks: reviewed + unreviewed + stale,
+            reviewed,
+            unreviewed,
+            stale,
+            needs_changes: 0,
+            skimmed: 0,
+            auto_approved: 0,
+            files_remaining: 0,
+            total_files: 1,
+        }
+    }
+
+    #[test]
+    fn webhooks_post_the_event_as_json() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", server.server_addr().to_ip().unwrap());
+        let event = ReviewEvent::FullyReviewed {
+            branch: "feat".to_string(),
+        };
+        let receiver = std::thread::spawn(move || {
+            let mut bodies = Vec::new();
+            for status in [200, 500] {
+                let mut request = server.recv().unwrap();
+                let mut body = String::new();
+                request.as_reader().read_to_string(&mut body).unwrap();
+                bodies.push(body);
+                request.respond(tiny_http::Response::empty(status)).unwrap();
+            }
+            bodies
+        });
+
+        webhook(&url, &event).unwrap();
+        let failed = webhook(&url, &event).unwrap_err();
+        assert_eq!(failed.to_string(), "webhook failed: HTTP 500");
+        let body: serde_json::Value = serde_json::from_str(&receiver.join().unwrap()[0]).unwrap();
+        assert_eq!(body["event"], "fully_reviewed");
+        assert_eq!(body["branch"], "feat");
+    }
+
+    #[test]
+    fn first_snapshot_is_silent() {
+        assert_eq!(detect_event("feat", None, &progress(3, 0, 0)), None);
+    }
+
+    #[test]
+    fn reaching_full_review_fires_once() {
+        let done = progress(3, 0, 0);
+        assert_eq!(
+            detect_event("feat", Some(&progress(2, 1, 0)), &done),
+            Some(ReviewEvent::FullyReviewed {
+                branch: "feat".to_string()
+            })
+        );
+        assert_eq!(detect_event("feat", Some(&done), &done), None);
+    }
+
+    #[test]
+    fn new_unreviewed_hunks_fire_with_count() {
+        assert_eq!(
+            detect_event("feat", Some(&progress(3, 0, 0)), &progress(3, 2, 0)),
+            Some(ReviewEvent::NewUnreviewed {
+                branch: "feat".to_string(),
+                added: 2
+            })
+        );
+        assert_eq!(
+            detect_event("feat", Some(&progress(1, 2, 0)), &progress(2, 1, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn overdue_counts_idle_time_from_the_latest_commit_or_review() {
+        const DAY: i64 = 86_400;
+        let now = 100 * DAY;
+        assert_eq!(
+            overdue_event("feat", &progress(1, 2, 1), now - 9 * DAY, None, now, 7),
+            Some(ReviewEvent::Overdue {
+                branch: "feat".to_string(),
+                pending: 3,
+                idle_days: 9
+            })
+        );
+        // A recent review or commit resets the clock
+        assert_eq!(
+            overdue_event(
+                "feat",
+                &progress(1, 2, 0),
+                now - 9 * DAY,
+                Some(now - DAY),
+                now,
+                7
+            ),
+            None
+        );
+        assert_eq!(
+            overdue_event(
+                "feat",
+                &progress(1, 2, 0),
+                now - DAY,
+                Some(now - 9 * DAY),
+                now,
+                7
+            ),
+            None
+        );
+        // Nothing left to review
+        assert_eq!(
+            overdue_event("feat", &progress(3, 0, 0), now - 30 * DAY, None, now, 7),
+            None
+        );
+    }
+}
@@ -0,0 +1,351 @@
+//! Review status indicators.
+//!
+//! Every status has a symbol as well as a color, so states stay
+//! distinguishable without telling red from green. The colors come from the
+//! `[palette]` section of `.git-review.toml`: a built-in scheme (`default`, or
+//! `colorblind` for the Okabe-Ito colors) with optional per-status overrides.
+
+use crate::HunkStatus;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// Symbol shown next to a hunk, file, or branch with the given status.
+pub fn status_symbol(status: HunkStatus) -> &'static str {
+    match status {
+        HunkStatus::Reviewed => "✓",
+        HunkStatus::Unreviewed => "○",
+        HunkStatus::Stale => "~",
+        HunkStatus::NeedsChanges => "✗",
+        HunkStatus::Skimmed => "◌",
+    }
+}
+
+/// Hunk counts of a file or branch, for picking its indicator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatusCounts {
+    pub total: usize,
+    pub reviewed: usize,
+    pub stale: usize,
+    pub needs_changes: usize,
+    pub skimmed: usize,
+}
+
+impl FromIterator<HunkStatus> for StatusCounts {
+    fn from_iter<I: IntoIterator<Item = HunkStatus>>(statuses: I) -> Self {
+        let mut counts = Self::default();
+        for status in statuses {
+            counts.total += 1;
+            match status {
+                HunkStatus::Reviewed => counts.reviewed += 1,
+                HunkStatus::Stale => counts.stale += 1,
+                HunkStatus::NeedsChanges => counts.needs_changes += 1,
+                HunkStatus::Skimmed => counts.skimmed += 1,
+                HunkStatus::Unreviewed => {}
+            }
+        }
+        counts
+    }
+}
+
+impl From<&crate::ReviewProgress> for StatusCounts {
+    fn from(p: &crate::ReviewProgress) -> Self {
+        Self {
+            total: p.total_hunks,
+            reviewed: p.reviewed,
+            stale: p.stale,
+            needs_changes: p.needs_changes,
+            skimmed: p.skimmed,
+        }
+    }
+}
+
+impl From<&crate::dashboard::ReviewProgress> for StatusCounts {
+    fn from(p: &crate::dashboard::ReviewProgress) -> Self {
+        Self {
+            total: p.total,
+            reviewed: p.reviewed,
+            stale: p.stale,
+            needs_changes: p.needs_changes,
+            skimmed: p.skimmed,
+        }
+    }
+}
+
+/// The status a group of hunks (a file or branch) is shown with: any hunk
+/// needing changes wins, then stale hunks, then anything left unreviewed, then
+/// hunks only skimmed.
+pub fn summary_status(counts: StatusCounts) -> HunkStatus {
+    if counts.needs_changes > 0 {
+        HunkStatus::NeedsChanges
+    } else if counts.stale > 0 {
+        HunkStatus::Stale
+    } else if counts.reviewed + counts.skimmed < counts.total || counts.total == 0 {
+        HunkStatus::Unreviewed
+    } else if counts.skimmed > 0 {
+        HunkStatus::Skimmed
+    } else {
+        HunkStatus::Reviewed
+    }
+}
+
+/// A terminal color: one of the 16 named colors, an RGB value (`#rrggbb`), or
+/// a 256-color palette index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum PaletteColor {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+    DarkGray,
+    LightRed,
+    LightGreen,
+    LightYellow,
+    LightBlue,
+    LightMagenta,
+    LightCyan,
+    White,
+    Rgb(u8, u8, u8),
+    Indexed(u8),
+}
+
+impl FromStr for PaletteColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_ascii_lowercase().replace(['-', '_', ' '], "");
+        let color = match name.as_str() {
+            "black" => Self::Black,
+            "red" => Self::Red,
+            "green" => Self::Green,
+            "yellow" => Self::Yellow,
+            "blue" => Self::Blue,
+            "magenta" => Self::Magenta,
+            "cyan" => Self::Cyan,
+            "gray" | "grey" => Self::Gray,
+            "darkgray" | "darkgrey" => Self::DarkGray,
+            "lightred" => Self::LightRed,
+            "lightgreen" => Self::LightGreen,
+            "lightyellow" => Self::LightYellow,
+            "lightblue" => Self::LightBlue,
+            "lightmagenta" => Self::LightMagenta,
+            "lightcyan" => Self::LightCyan,
+            "white" => Self::White,
+            hex if hex.len() == 7 && hex.is_ascii() && hex.starts_with('#') => {
+                let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16);
+                match (channel(1), channel(3), channel(5)) {
+                    (Ok(r), Ok(g), Ok(b)) => Self::Rgb(r, g, b),
+                    _ => return Err(format!("invalid color {:?}", s)),
+                }
+            }
+            index => index
+                .parse()
+                .map(Self::Indexed)
+                .map_err(|_| format!("invalid color {:?}", s))?,
+        };
+        Ok(color)
+    }
+}
+
+impl fmt::Display for PaletteColor {
+    /// The form [`PaletteColor::from_str`] reads back: a lowercase name,
+    /// `#rrggbb`, or a palette index.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Black => "black",
+            Self::Red => "red",
+            Self::Green => "green",
+            Self::Yellow => "yellow",
+            Self::Blue => "blue",
+            Self::Magenta => "magenta",
+            Self::Cyan => "cyan",
+            Self::Gray => "gray",
+            Self::DarkGray => "darkgray",
+            Self::LightRed => "lightred",
+            Self::LightGreen => "lightgreen",
+            Self::LightYellow => "lightyellow",
+            Self::LightBlue => "lightblue",
+            Self::LightMagenta => "lightmagenta",
+            Self::LightCyan => "lightcyan",
+            Self::White => "white",
+            Self::Rgb(r, g, b) => return write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+            Self::Indexed(index) => return write!(f, "{}", index),
+        };
+        f.write_str(name)
+    }
+}
+
+impl From<PaletteColor> for String {
+    fn from(color: PaletteColor) -> Self {
+        color.to_string()
+    }
+}
+
+impl TryFrom<String> for PaletteColor {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Built-in color schemes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scheme {
+    /// Green for reviewed, red for unreviewed, yellow for stale.
+    #[default]
+    Default,
+    /// Blue for reviewed, vermillion for unreviewed, yellow for stale
+    /// (Okabe-Ito colors, distinguishable with common color blindness).
+    Colorblind,
+}
+
+/// Colors for each status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Palette {
+    pub reviewed: PaletteColor,
+    /// Hunks an auto-approval rule marked reviewed.
+    pub auto_approved: PaletteColor,
+    pub unreviewed: PaletteColor,
+    /// Files or branches with some, but not all, hunks reviewed.
+    pub partial: PaletteColor,
+    pub stale: PaletteColor,
+    pub needs_changes: PaletteColor,
+    pub skimmed: PaletteColor,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::for_scheme(Scheme::Default)
+    }
+}
+
+impl Palette {
+    /// The colors of a built-in scheme.
+    pub fn for_scheme(scheme: Scheme) -> Self {
+        match scheme {
+            Scheme::Default => Self {
+                reviewed: PaletteColor::Green,
+                auto_approved: PaletteColor::Cyan,
+                unreviewed: PaletteColor::Red,
+                partial: PaletteColor::Yellow,
+                stale: PaletteColor::Yellow,
+                needs_changes: PaletteColor::Magenta,
+                skimmed: PaletteColor::Blue,
+            },
+            Scheme::Colorblind => Self {
+                reviewed: PaletteColor::Rgb(0, 114, 178),
+                auto_approved: PaletteColor::Rgb(86, 180, 233),
+                unreviewed: PaletteColor::Rgb(213, 94, 0),
+                partial: PaletteColor::Rgb(230, 159, 0),
+                stale: PaletteColor::Rgb(240, 228, 66),
+                needs_changes: PaletteColor::Rgb(204, 121, 167),
+                skimmed: PaletteColor::Rgb(0, 158, 115),
+            },
+        }
+    }
+
+    /// Color of a hunk status.
+    pub fn status(&self, status: HunkStatus) -> PaletteColor {
+        match status {
+            HunkStatus::Reviewed => self.reviewed,
+            HunkStatus::Unreviewed => self.unreviewed,
+            HunkStatus::Stale => self.stale,
+            HunkStatus::NeedsChanges => self.needs_changes,
+            HunkStatus::Skimmed => self.skimmed,
+        }
+    }
+
+    /// Color of a file or branch: its [`summary_status`], or `partial` when it
+    /// is only unreviewed but some of its hunks are reviewed.
+    pub fn summary(&self, counts: StatusCounts) -> PaletteColor {
+        match summary_status(counts) {
+            HunkStatus::Unreviewed if counts.reviewed + counts.skimmed > 0 => self.partial,
+            status => self.status(status),
+        }
+    }
+}
+
+/// `[palette]` section of `.git-review.toml`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaletteConfig {
+    /// Built-in scheme the overrides below start from.
+    pub scheme: Scheme,
+    pub reviewed: Option<PaletteColor>,
+    pub auto_approved: Option<PaletteColor>,
+    pub unreviewed: Option<PaletteColor>,
+    pub partial: Option<PaletteColor>,
+    pub stale: Option<PaletteColor>,
+    pub needs_changes: Option<PaletteColor>,
+    pub skimmed: Option<PaletteColor>,
+}
+
+impl PaletteConfig {
+    /// The scheme's colors with this section's overrides applied.
+    pub fn palette(&self) -> Palette {
+        let base = Palette::for_scheme(self.scheme);
+        Palette {
+            reviewed: self.reviewed.unwrap_or(base.reviewed),
+            auto_approved: self.auto_approved.unwrap_or(base.auto_approved),
+            unreviewed: self.unreviewed.unwrap_or(base.unreviewed),
+            partial: self.partial.unwrap_or(base.partial),
+            stale: self.stale.unwrap_or(base.stale),
+            needs_changes: self.needs_changes.unwrap_or(base.needs_changes),
+            skimmed: self.skimmed.unwrap_or(base.skimmed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_apply_on_top_of_the_scheme() {
+        let config: PaletteConfig =
+            toml::from_str("scheme = \"colorblind\"\nreviewed = \"#0000ff\"\nstale = \"white\"\nunreviewed = \"208\"")
+                .unwrap();
+        let palette = config.palette();
+        assert_eq!(palette.reviewed, PaletteColor::Rgb(0, 0, 255));
+        assert_eq!(palette.stale, PaletteColor::White);
+        assert_eq!(palette.unreviewed, PaletteColor::Indexed(208));
+        assert_eq!(
+            palette.needs_changes,
+            Palette::for_scheme(Scheme::Colorblind).needs_changes
+        );
+
+        assert_eq!("Light-Blue".parse(), Ok(PaletteColor::LightBlue));
+        for color in [
+            PaletteColor::DarkGray,
+            PaletteColor::Rgb(0, 114, 178),
+            PaletteColor::Indexed(208),
+        ] {
+            assert_eq!(color.to_string().parse(), Ok(color));
+        }
+        assert!(toml::from_str::<PaletteConfig>("reviewed = \"#12345\"").is_err());
+        assert!(toml::from_str::<PaletteConfig>("reviewed = \"chartreuse\"").is_err());
+        assert!("#1é234".parse::<PaletteColor>().is_err());
+    }
+
+    #[test]
+    fn every_status_has_a_distinct_symbol() {
+        let symbols = [
+            HunkStatus::Reviewed,
+            HunkStatus::Unreviewed,
+            HunkStatus::Stale,
+            HunkStatus::NeedsChanges,
+            HunkStatus::Skimmed,
+        ]
+        .map(status_symbol);
+        for (i, symbol) in symbols.iter().enumerate() {
+            assert!(!symbols[i + 1..].contains(symbol));
+        }
+    }
+}
@@ -1,35 +1,160 @@
 use crate::{DiffFile, DiffHunk, HunkStatus};
 use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::fmt;
+use std::ops::{Deref, Range};
 use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+
+/// The text of a hunk: its lines joined by `\n`, each starting with a `+`,
+/// `-`, or ` ` marker (or `\` for "No newline at end of file").
+///
+/// Hunks from [`parse_diff`] keep a view into the diff and build their text
+/// the first time it is read, so a large diff only pays for the bodies that
+/// get displayed or checked. Dereferences to `str`.
+#[derive(Clone)]
+pub struct HunkBody {
+    text: OnceLock<String>,
+    /// The diff text, the byte range of the hunk's lines, and the number of
+    /// marker columns (see [`parse_hunk`]).
+    source: Option<(Arc<str>, Range<usize>, usize)>,
+}
+
+impl HunkBody {
+    fn lazy(diff: Arc<str>, lines: Range<usize>, parents: usize) -> Self {
+        Self {
+            text: OnceLock::new(),
+            source: Some((diff, lines, parents)),
+        }
+    }
+}
+
+impl HunkBody {
+    /// The lines of the body, without building its text if it hasn't been yet.
+    pub fn iter_lines(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        match (self.text.get(), &self.source) {
+            (None, Some((diff, lines, parents))) => {
+                Box::new(body_lines(&diff[lines.clone()], *parents))
+            }
+            _ => Box::new(self.deref().lines().map(Cow::Borrowed)),
+        }
+    }
+}
+
+impl Deref for HunkBody {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.text.get_or_init(|| match &self.source {
+            Some((diff, lines, parents)) => body_lines(&diff[lines.clone()], *parents)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => String::new(),
+        })
+    }
+}
+
+impl From<String> for HunkBody {
+    fn from(text: String) -> Self {
+        Self {
+            text: OnceLock::from(text),
+            source: None,
+        }
+    }
+}
+
+impl From<&str> for HunkBody {
+    fn from(text: &str) -> Self {
+        text.to_string().into()
+    }
+}
+
+impl fmt::Debug for HunkBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for HunkBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self)
+    }
+}
+
+impl PartialEq<str> for HunkBody {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<&str> for HunkBody {
+    fn eq(&self, other: &&str) -> bool {
+        **self == **other
+    }
+}
+
+/// The lines of a hunk body as they appear in its text (see [`HunkBody`]).
+fn body_lines(lines: &str, parents: usize) -> impl Iterator<Item = Cow<'_, str>> {
+    lines.lines().map(move |line| {
+        if line.starts_with('\\') {
+            Cow::Borrowed(line)
+        } else {
+            flatten_line(line, parents).unwrap_or(Cow::Borrowed(line))
+        }
+    })
+}
 
 /// Parse raw `git diff` output into structured `DiffFile` entries.
 ///
 /// Parses unified diff format, extracting file paths, hunk headers, and content.
 /// Each hunk is assigned a SHA-256 hash of its content and starts with status `Unreviewed`.
-/// Binary files are skipped. Handles new files, deleted files, and renames.
+/// Binary files are skipped, except images: each changed image becomes a single hunk
+/// (see [`binary_blobs`]) so it can be previewed and reviewed like any other change.
+/// Handles new files, deleted files, and renames.
+///
+/// Combined diffs (`diff --cc`, as produced for merge commits) are also accepted; see
+/// [`parse_hunk`] for how their multi-parent hunks are flattened.
+///
+/// Text outside `diff` sections is ignored, so patch emails and mbox files parse
+/// too. When a path appears in several patches, its hunks are gathered under one entry.
 pub fn parse_diff(input: &str) -> Vec<DiffFile> {
     let mut files = Vec::new();
     let lines: Vec<&str> = input.lines().collect();
+    let diff: Arc<str> = Arc::from(input);
     let mut i = 0;
 
     while i < lines.len() {
         let line = lines[i];
 
-        // Look for file headers: "diff --git a/path b/path"
-        if line.starts_with("diff --git ") {
+        // Look for file headers: "diff --git a/path b/path" or "diff --cc path"
+        if is_file_header(line) {
             // Extract file path from the next lines
             let mut path: Option<PathBuf> = None;
             let mut hunks = Vec::new();
             let mut is_binary = false;
+            let mut index_line = None;
             i += 1;
 
             // Skip until we find +++ line (or detect binary)
             while i < lines.len() {
                 let current = lines[i];
 
+                if current.starts_with("index ") {
+                    index_line = Some(current);
+                }
+
                 // Check for binary file marker
                 if current.starts_with("Binary files ") {
                     is_binary = true;
+                    if let Some(image) = binary_image_file(index_line, current) {
+                        match files
+                            .iter_mut()
+                            .find(|f: &&mut DiffFile| f.path == image.path)
+                        {
+                            Some(existing) => existing.hunks.extend(image.hunks),
+                            None => files.push(image),
+                        }
+                    }
                     i += 1;
                     break;
                 }
@@ -59,7 +184,7 @@ pub fn parse_diff(input: &str) -> Vec<DiffFile> {
                 i += 1;
             }
 
-            // Skip binary files
+            // Other binary files have no reviewable content
             if is_binary {
                 continue;
             }
@@ -69,13 +194,14 @@ pub fn parse_diff(input: &str) -> Vec<DiffFile> {
                 let current = lines[i];
 
                 // Stop if we hit the next file
-                if current.starts_with("diff --git ") {
+                if is_file_header(current) {
                     break;
                 }
 
                 // Parse hunk header: @@ -old_start,old_count +new_start,new_count @@
-                if current.starts_with("@@ ") {
-                    if let Some(hunk) = parse_hunk(&lines, &mut i) {
+                // (or @@@ ... @@@ for combined diffs)
+                if current.starts_with("@@") {
+                    if let Some(hunk) = parse_hunk(input, &diff, &lines, &mut i) {
                         hunks.push(hunk);
                     } else {
                         // parse_hunk failed without advancing i — skip this line
@@ -90,7 +216,10 @@ pub fn parse_diff(input: &str) -> Vec<DiffFile> {
             if let Some(p) = path
                 && !hunks.is_empty()
             {
-                files.push(DiffFile { path: p, hunks });
+                match files.iter_mut().find(|f: &&mut DiffFile| f.path == p) {
+                    Some(existing) => existing.hunks.extend(hunks),
+                    None => files.push(DiffFile { path: p, hunks }),
+                }
             }
         } else {
             i += 1;
@@ -100,57 +229,175 @@ pub fn parse_diff(input: &str) -> Vec<DiffFile> {
     files
 }
 
+/// Build the single-hunk entry for a changed image from its `index` and `Binary files` lines.
+///
+/// The hunk content is those two lines, so its hash changes whenever either blob does.
+fn binary_image_file(index_line: Option<&str>, binary_line: &str) -> Option<DiffFile> {
+    let names = binary_line
+        .strip_prefix("Binary files ")?
+        .strip_suffix(" differ")?;
+    let (old, new) = names.rsplit_once(" and ")?;
+    let path = match new.strip_prefix("b/") {
+        Some(new) => new,
+        None => old.strip_prefix("a/").unwrap_or(old),
+    };
+    if !crate::preview::is_image_path(std::path::Path::new(path)) {
+        return None;
+    }
+
+    let content = format!("{}\n{}\n", index_line?, binary_line);
+    let content_hash = compute_hash(&content);
+    Some(DiffFile {
+        path: PathBuf::from(path),
+        hunks: vec![DiffHunk {
+            old_start: 0,
+            old_count: 0,
+            new_start: 0,
+            new_count: 0,
+            content: content.into(),
+            content_hash,
+            section: String::new(),
+            status: HunkStatus::Unreviewed,
+        }],
+    })
+}
+
+/// Old and new blob ids of a binary image hunk (see [`parse_diff`]).
+///
+/// A side is `None` when the file did not exist there (an all-zero id).
+/// Returns `None` for regular text hunks.
+pub fn binary_blobs(hunk: &DiffHunk) -> Option<(Option<String>, Option<String>)> {
+    let mut lines = hunk.content.lines();
+    let index = lines.next()?.strip_prefix("index ")?;
+    if !lines.next()?.starts_with("Binary files ") {
+        return None;
+    }
+    let range = index.split_whitespace().next()?;
+    let (old, new) = range.split_once("..")?;
+    let side = |id: &str| (!id.bytes().all(|b| b == b'0')).then(|| id.to_string());
+    Some((side(old), side(new)))
+}
+
+/// Whether a line starts a new file section (two-way or combined diff).
+fn is_file_header(line: &str) -> bool {
+    line.starts_with("diff --git ")
+        || line.starts_with("diff --cc ")
+        || line.starts_with("diff --combined ")
+}
+
 /// Parse a single hunk starting at the @@ line.
-fn parse_hunk(lines: &[&str], i: &mut usize) -> Option<DiffHunk> {
+///
+/// Combined diff hunks (`@@@ -a,b -c,d +e,f @@@`) carry one marker column per
+/// parent. They are flattened into a two-way hunk (see [`flatten_line`]) that
+/// starts at the first parent's line. Its old side is the flattened context and
+/// removed lines, so `old_count` counts those rather than the first parent's
+/// lines, which a line removed from another parent would not be among.
+///
+/// Only the header is parsed and the body hashed here; the body text is built
+/// from `diff` when first read (see [`HunkBody`]). `lines` are the lines of
+/// `input`, and `diff` a shared copy of it.
+fn parse_hunk(input: &str, diff: &Arc<str>, lines: &[&str], i: &mut usize) -> Option<DiffHunk> {
     let line = lines[*i];
 
+    // One more '@' than there are parents: "@@" for two-way, "@@@" for a two-parent merge
+    let marker_len = line.bytes().take_while(|&b| b == b'@').count();
+    if marker_len < 2 {
+        return None;
+    }
+    let parents = marker_len - 1;
+    let marker = &line[..marker_len];
+
     // Parse hunk header: @@ -old_start,old_count +new_start,new_count @@ [context]
-    let header = line.strip_prefix("@@ ")?;
-    // Find the closing @@ — everything after it is optional context
-    let header = match header.find(" @@") {
-        Some(pos) => &header[..pos],
+    let header = line.strip_prefix(marker)?.strip_prefix(' ')?;
+    // Find the closing marker — everything after it is optional context
+    let (header, section) = match header.find(&format!(" {}", marker)) {
+        Some(pos) => (&header[..pos], header[pos + 1 + marker_len..].trim()),
         None => return None,
     };
     let parts: Vec<&str> = header.split(' ').collect();
-    if parts.len() < 2 {
+    if parts.len() < parents + 1 {
         return None;
     }
 
-    // Parse old range: -start,count or -start
-    let old_part = parts[0].strip_prefix('-')?;
-    let (old_start, old_count) = parse_range(old_part);
+    // Parse old ranges: -start,count or -start, one per parent
+    let mut old_ranges = Vec::with_capacity(parents);
+    for part in &parts[..parents] {
+        old_ranges.push(parse_range(part.strip_prefix('-')?));
+    }
+    let (old_start, mut old_count) = old_ranges[0];
+    let mut old_remaining: Vec<u32> = old_ranges.iter().map(|&(_, count)| count).collect();
 
     // Parse new range: +start,count or +start
-    let new_part = parts[1].strip_prefix('+')?;
+    let new_part = parts[parents].strip_prefix('+')?;
     let (new_start, new_count) = parse_range(new_part);
 
-    // Collect hunk content (lines starting with +, -, or space)
-    let mut content_lines = Vec::new();
+    // Hash hunk content (lines starting with +, -, or space) as it would be joined
+    let mut hasher = Sha256::new();
+    let offset = |line: &str| line.as_ptr() as usize - input.as_ptr() as usize;
+    let mut body: Option<Range<usize>> = None;
+    let mut new_remaining = new_count;
+    let mut flattened_old = 0;
     *i += 1;
 
     while *i < lines.len() {
         let current = lines[*i];
 
         // Stop at next hunk or file
-        if current.starts_with("@@") || current.starts_with("diff --git ") {
+        if current.starts_with("@@") || is_file_header(current) {
             break;
         }
 
-        // Include lines that are part of the hunk content
-        if current.starts_with('+')
-            || current.starts_with('-')
-            || current.starts_with(' ')
-            || current.starts_with('\\')
+        // Hunks end once the header's line counts are used up, so trailing text
+        // such as a patch email's "-- " signature is not mistaken for content
+        if new_remaining == 0 && old_remaining.iter().all(|&n| n == 0) && !current.starts_with('\\')
         {
-            content_lines.push(current);
-            *i += 1;
+            break;
+        }
+
+        let text = if current.starts_with('\\') {
+            Cow::Borrowed(current)
+        } else if let Some(flattened) = flatten_line(current, parents) {
+            // A removed line is in the parents marking it `-`; any other line
+            // is in the result and in the parents marking it ` `. A short line
+            // is an empty context line with its blank columns trimmed.
+            let columns = current.as_bytes();
+            let column = |k: usize| columns.get(k).copied().unwrap_or(b' ');
+            let removed = (0..parents).any(|k| column(k) == b'-');
+            for (k, remaining) in old_remaining.iter_mut().enumerate() {
+                if column(k) == if removed { b'-' } else { b' ' } {
+                    *remaining = remaining.saturating_sub(1);
+                }
+            }
+            if !removed {
+                new_remaining = new_remaining.saturating_sub(1);
+            }
+            if !flattened.starts_with('+') {
+                flattened_old += 1;
+            }
+            flattened
         } else {
             break;
+        };
+        let end = offset(current) + current.len();
+        match &mut body {
+            Some(body) => {
+                hasher.update(b"\n");
+                body.end = end;
+            }
+            None => body = Some(offset(current)..end),
         }
+        hasher.update(text.as_bytes());
+        *i += 1;
     }
 
-    let content = content_lines.join("\n");
-    let content_hash = compute_hash(&content);
+    if parents > 1 {
+        old_count = flattened_old;
+    }
+    let content_hash = format!("{:x}", hasher.finalize());
+    let content = match body {
+        Some(body) => HunkBody::lazy(Arc::clone(diff), body, parents),
+        None => HunkBody::from(String::new()),
+    };
 
     Some(DiffHunk {
         old_start,
@@ -159,10 +406,40 @@ fn parse_hunk(lines: &[&str], i: &mut usize) -> Option<DiffHunk> {
         new_count,
         content,
         content_hash,
+        section: section.to_string(),
         status: HunkStatus::Unreviewed,
     })
 }
 
+/// Reduce a hunk line with `parents` marker columns to a single-column line:
+/// a line added relative to any parent becomes `+`, a line removed from any
+/// parent becomes `-`, and a line every parent shares stays context.
+///
+/// Returns `None` if the line is not hunk content. Two-way lines pass through unchanged.
+fn flatten_line(line: &str, parents: usize) -> Option<Cow<'_, str>> {
+    if line.is_empty() {
+        return None;
+    }
+    let columns = line.get(..parents.min(line.len()))?;
+    if !columns.bytes().all(|b| matches!(b, b' ' | b'+' | b'-')) {
+        return None;
+    }
+    if parents == 1 {
+        return Some(Cow::Borrowed(line));
+    }
+
+    // A short line means git trimmed trailing blank columns from an empty context line
+    let text = line.get(parents..).unwrap_or("");
+    let prefix = if columns.contains('+') {
+        '+'
+    } else if columns.contains('-') {
+        '-'
+    } else {
+        ' '
+    };
+    Some(Cow::Owned(format!("{}{}", prefix, text)))
+}
+
 /// Parse a range like "start,count" or "start" (count defaults to 1).
 fn parse_range(s: &str) -> (u32, u32) {
     if let Some(comma_pos) = s.find(',') {
@@ -175,8 +452,21 @@ fn parse_range(s: &str) -> (u32, u32) {
     }
 }
 
+/// Count added and removed lines in hunk content.
+///
+/// Returns `(insertions, deletions)`, matching the numbers `git diff --stat` reports.
+pub fn count_changes(content: &str) -> (usize, usize) {
+    content.lines().fold((0, 0), |(added, removed), line| {
+        match line.as_bytes().first() {
+            Some(b'+') => (added + 1, removed),
+            Some(b'-') => (added, removed + 1),
+            _ => (added, removed),
+        }
+    })
+}
+
 /// Compute SHA-256 hash of content.
-fn compute_hash(content: &str) -> String {
+pub fn compute_hash(content: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(content.as_bytes());
     format!("{:x}", hasher.finalize())
@@ -228,7 +518,7 @@ index 1234567..abcdefg 100644
 -line2
 +line2_modified
  line3
-@@ -10,2 +10,3 @@
+@@ -10,2 +10,3 @@ fn section() {
  line10
 +new_line
  line11
@@ -236,6 +526,8 @@ index 1234567..abcdefg 100644
         let files = parse_diff(diff);
         assert_eq!(files.len(), 1);
         assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[0].section, "");
+        assert_eq!(files[0].hunks[1].section, "fn section() {");
     }
 
     #[test]
@@ -263,9 +555,9 @@ index 1234567..abcdefg 100644
 
     #[test]
     fn parse_binary_file_skipped() {
-        let diff = r#"diff --git a/image.png b/image.png
+        let diff = r#"diff --git a/data.bin b/data.bin
 index 1234567..abcdefg 100644
-Binary files a/image.png and b/image.png differ
+Binary files a/data.bin and b/data.bin differ
 diff --git a/file.txt b/file.txt
 index 1234567..abcdefg 100644
 --- a/file.txt
@@ -279,6 +571,36 @@ index 1234567..abcdefg 100644
         assert_eq!(files[0].path, PathBuf::from("file.txt"));
     }
 
+    #[test]
+    fn parse_binary_image_as_single_hunk() {
+        let diff = r#"diff --git a/assets/logo.png b/assets/logo.png
+new file mode 100644
+index 0000000..abcdef1
+Binary files /dev/null and b/assets/logo.png differ
+"#;
+        let files = parse_diff(diff);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, PathBuf::from("assets/logo.png"));
+        assert_eq!(files[0].hunks.len(), 1);
+
+        let hunk = &files[0].hunks[0];
+        assert_eq!(
+            binary_blobs(hunk),
+            Some((None, Some("abcdef1".to_string())))
+        );
+
+        // A different blob gives a different hash
+        let changed = parse_diff(&diff.replace("abcdef1", "abcdef2"));
+        assert_ne!(changed[0].hunks[0].content_hash, hunk.content_hash);
+    }
+
+    #[test]
+    fn binary_blobs_ignores_text_hunks() {
+        let diff = "diff --git a/a.txt b/a.txt\nindex 1234567..abcdefg 100644\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-old\n+new\n";
+        let files = parse_diff(diff);
+        assert_eq!(binary_blobs(&files[0].hunks[0]), None);
+    }
+
     #[test]
     fn parse_new_file() {
         let diff = r#"diff --git a/new.txt b/new.txt
@@ -364,4 +686,129 @@ index abcdefg..0000000
         assert_eq!(hunk2.new_start, 1);
         assert_eq!(hunk2.new_count, 2);
     }
+
+    #[test]
+    fn count_changes_ignores_context_lines() {
+        let content = " context\n-old\n+new\n+added\n\\ No newline at end of file";
+        assert_eq!(count_changes(content), (2, 1));
+        assert_eq!(count_changes(""), (0, 0));
+    }
+
+    #[test]
+    fn parse_combined_diff_flattens_merge_hunk() {
+        let diff = r#"diff --cc src/lib.rs
+index 6dcce7d,7be73ce..a769e1e
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@@ -1,3 -1,3 +1,4 @@@
+  a
+- b
+ -B
+ -c
+++BB
+ +C
+++new
+  past the header's counts
+"#;
+        let files = parse_diff(diff);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, PathBuf::from("src/lib.rs"));
+        assert_eq!(files[0].hunks.len(), 1);
+
+        let hunk = &files[0].hunks[0];
+        assert_eq!(hunk.old_start, 1);
+        // a, b, B, and c: the flattened hunk's old side, not the first parent's 3 lines
+        assert_eq!(hunk.old_count, 4);
+        assert_eq!(hunk.new_start, 1);
+        assert_eq!(hunk.new_count, 4);
+        assert_eq!(hunk.content, " a\n-b\n-B\n-c\n+BB\n+C\n+new");
+        assert_eq!(count_changes(&hunk.content), (3, 3));
+    }
+
+    #[test]
+    fn lazy_bodies_hash_like_their_text() {
+        let diff = "diff --git a/a.txt b/a.txt\r\n--- a/a.txt\r\n+++ b/a.txt\r\n\
+                    @@ -1,2 +1,2 @@\r\n a\r\n-b\r\n\\ No newline at end of file\r\n+c\r\n\
+                    diff --cc m.txt\n--- a/m.txt\n+++ b/m.txt\n@@@ -1,1 -1,1 +1,1 @@@\n- x\n++y\n";
+        let files = parse_diff(diff);
+        let first = &files[0].hunks[0].content;
+        assert_eq!(first.iter_lines().count(), 4);
+        assert!(first.text.get().is_none(), "reading lines built the body");
+        let bodies: Vec<&str> = files.iter().map(|file| &*file.hunks[0].content).collect();
+        assert_eq!(
+            bodies,
+            vec![" a\n-b\n\\ No newline at end of file\n+c", "-x\n+y"]
+        );
+        for file in &files {
+            let hunk = &file.hunks[0];
+            assert_eq!(hunk.content_hash, compute_hash(&hunk.content));
+        }
+    }
+
+    #[test]
+    fn parse_combined_diff_followed_by_regular_file() {
+        let diff = r#"diff --cc merged.txt
+index 1111111,2222222..3333333
+--- a/merged.txt
++++ b/merged.txt
+@@@ -1,1 -1,1 +1,1 @@@
+- left
+ -right
+++resolved
+diff --git a/other.txt b/other.txt
+index 1234567..abcdefg 100644
+--- a/other.txt
++++ b/other.txt
+@@ -1 +1 @@
+-old
++new
+"#;
+        let files = parse_diff(diff);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, PathBuf::from("merged.txt"));
+        assert_eq!(files[1].path, PathBuf::from("other.txt"));
+        assert_eq!(files[1].hunks[0].content, "-old\n+new");
+    }
+
+    #[test]
+    fn parse_mbox_stops_at_signature_and_merges_paths() {
+        let mbox = r#"From 1111111111111111111111111111111111111111 Mon Sep 17 00:00:00 2001
+From: Dev <dev@example.com>
+Subject: [PATCH 1/2] First
+
+---
+ file.txt | 2 +-
+ 1 file changed, 1 insertion(+), 1 deletion(-)
+
+diff --git a/file.txt b/file.txt
+index 1234567..abcdefg 100644
+--- a/file.txt
++++ b/file.txt
+@@ -1,2 +1,2 @@
+ keep
+-old
++new
+-- 
+2.43.0
+
+From 2222222222222222222222222222222222222222 Mon Sep 17 00:00:00 2001
+From: Dev <dev@example.com>
+Subject: [PATCH 2/2] Second
+
+diff --git a/file.txt b/file.txt
+index abcdefg..7654321 100644
+--- a/file.txt
++++ b/file.txt
+@@ -10 +10,2 @@
+ ten
++eleven
+-- 
+2.43.0
+"#;
+        let files = parse_diff(mbox);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[0].content, " keep\n-old\n+new");
+        assert_eq!(files[0].hunks[1].content, " ten\n+eleven");
+    }
 }
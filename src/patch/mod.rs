@@ -0,0 +1,161 @@
+//! Reassembling some of a diff's hunks into a patch (`export-patch`, `stage`).
+//!
+//! The raw `git diff` text is cut at its file and hunk headers rather than
+//! rebuilt from parsed hunks, so file headers (modes, renames, new and
+//! deleted files) and `\ No newline at end of file` markers survive and the
+//! result applies with `git apply`.
+
+use crate::parser::parse_diff;
+use crate::{DiffHunk, HunkStatus};
+use std::path::Path;
+
+/// Review statuses `export-patch` can select hunks by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PatchStatus {
+    /// Not reviewed yet, including hunks gone stale.
+    Unreviewed,
+    Reviewed,
+    /// Flagged as needing changes.
+    NeedsChanges,
+    /// Skimmed but not fully reviewed.
+    Skimmed,
+}
+
+impl PatchStatus {
+    /// Whether a hunk with `status` is selected.
+    pub fn matches(self, status: HunkStatus) -> bool {
+        match self {
+            PatchStatus::Unreviewed => {
+                matches!(status, HunkStatus::Unreviewed | HunkStatus::Stale)
+            }
+            PatchStatus::Reviewed => status == HunkStatus::Reviewed,
+            PatchStatus::NeedsChanges => status == HunkStatus::NeedsChanges,
+            PatchStatus::Skimmed => status == HunkStatus::Skimmed,
+        }
+    }
+}
+
+/// The hunks of `diff` that `keep` accepts, with the header of each file they
+/// are in, and how many hunks that is.
+///
+/// Files without text hunks (binary files, pure renames and mode changes) are
+/// left out.
+pub fn select_hunks(diff: &str, mut keep: impl FnMut(&Path, &DiffHunk) -> bool) -> (String, usize) {
+    let mut patch = String::new();
+    let mut count = 0;
+    for section in file_sections(diff) {
+        let Some(first_hunk) = section.find("\n@@").map(|pos| pos + 1) else {
+            continue;
+        };
+        let (header, hunks) = section.split_at(first_hunk);
+        let mut kept = String::new();
+        for chunk in hunk_chunks(hunks) {
+            let files = parse_diff(&format!("{}{}", header, chunk));
+            let selected = files.first().is_some_and(|file| {
+                file.hunks
+                    .first()
+                    .is_some_and(|hunk| keep(&file.path, hunk))
+            });
+            if selected {
+                kept.push_str(chunk);
+                count += 1;
+            }
+        }
+        if !kept.is_empty() {
+            patch.push_str(header);
+            patch.push_str(&kept);
+        }
+    }
+    (patch, count)
+}
+
+/// Split a diff into its `diff --git` sections, each ending with a newline.
+fn file_sections(diff: &str) -> Vec<&str> {
+    split_before(diff, |line| line.starts_with("diff --git "))
+}
+
+/// Split the hunks of a file section at their `@@` lines.
+fn hunk_chunks(hunks: &str) -> Vec<&str> {
+    split_before(hunks, |line| line.starts_with("@@"))
+}
+
+/// Split `text` before each line `starts` accepts, dropping anything before
+/// the first such line.
+fn split_before(text: &str, starts: impl Fn(&str) -> bool) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if starts(line) {
+            if let Some(start) = start {
+                parts.push(&text[start..offset]);
+            }
+            start = Some(offset);
+        }
+        offset += line.len();
+    }
+    if let Some(start) = start {
+        parts.push(&text[start..]);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@ mod parser;
+-use std::fs;
++use std::io;
+ fn main() {}
+@@ -10 +10 @@ fn main() {}
+-let x = 1;
++let x = 2;
+diff --git a/notes.txt b/notes.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/notes.txt
+@@ -0,0 +1 @@
++todo
+\\ No newline at end of file
+diff --git a/logo.png b/logo.png
+index 4444444..5555555 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    #[test]
+    fn selected_hunks_are_kept_with_their_file_headers() {
+        let (patch, count) = select_hunks(DIFF, |path, hunk| {
+            path == Path::new("notes.txt") || hunk.old_start == 10
+        });
+        assert_eq!(count, 2);
+        assert_eq!(
+            patch,
+            "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10 +10 @@ fn main() {}
+-let x = 1;
++let x = 2;
+diff --git a/notes.txt b/notes.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/notes.txt
+@@ -0,0 +1 @@
++todo
+\\ No newline at end of file
+"
+        );
+        assert_eq!(select_hunks(DIFF, |_, _| false), (String::new(), 0));
+        assert_eq!(select_hunks(DIFF, |_, _| true).1, 3);
+    }
+}
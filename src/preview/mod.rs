@@ -0,0 +1,397 @@
+//! Image previews for binary hunks.
+//!
+//! Images can be shown two ways: inline in the TUI as half-block character art
+//! (works in any true-color terminal), or at full resolution through a terminal
+//! graphics protocol (kitty, iTerm2, or sixel). Block art and sixel need decoded
+//! pixels, which are only available for PNG; kitty accepts PNG data as-is and
+//! iTerm2 accepts any format the terminal understands.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::path::Path;
+use thiserror::Error;
+
+/// Extensions treated as images by the diff parser.
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "bmp", "webp", "ico"];
+
+/// Images larger than this are not decoded or sent to the terminal.
+pub const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+/// Largest decoded image accepted (width times height).
+const MAX_PIXELS: usize = 40_000_000;
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Errors that can occur while decoding or encoding an image preview.
+#[derive(Debug, Error)]
+pub enum PreviewError {
+    #[error("not a PNG image")]
+    NotPng,
+    #[error("unsupported PNG: {0}")]
+    Unsupported(String),
+    #[error("corrupt PNG: {0}")]
+    Corrupt(String),
+    #[error("image too large to preview")]
+    TooLarge,
+}
+
+pub type Result<T> = std::result::Result<T, PreviewError>;
+
+/// Whether a path looks like an image the preview can handle.
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Whether the bytes start with the PNG signature.
+pub fn is_png(bytes: &[u8]) -> bool {
+    bytes.starts_with(PNG_SIGNATURE)
+}
+
+/// A decoded image as RGBA pixels, row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        self.pixels[y * self.width + x]
+    }
+}
+
+/// Decode a PNG (any color type and bit depth, interlaced or not) into RGBA.
+pub fn decode_png(bytes: &[u8]) -> Result<Image> {
+    if !is_png(bytes) {
+        return Err(PreviewError::NotPng);
+    }
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(PreviewError::TooLarge);
+    }
+
+    let mut decoder = png::Decoder::new_with_limits(
+        bytes,
+        png::Limits {
+            bytes: MAX_PIXELS * 4,
+        },
+    );
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(decode_error)?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buf).map_err(decode_error)?;
+    let channels = frame.color_type.samples();
+    let pixels = buf[..frame.buffer_size()]
+        .chunks_exact(channels)
+        .map(|p| match *p {
+            [v] => [v, v, v, 255],
+            [v, a] => [v, v, v, a],
+            [r, g, b] => [r, g, b, 255],
+            [r, g, b, a] => [r, g, b, a],
+            _ => unreachable!("PNG pixels have 1 to 4 samples"),
+        })
+        .collect();
+    Ok(Image {
+        width: frame.width as usize,
+        height: frame.height as usize,
+        pixels,
+    })
+}
+
+fn decode_error(err: png::DecodingError) -> PreviewError {
+    match err {
+        png::DecodingError::LimitsExceeded => PreviewError::TooLarge,
+        png::DecodingError::Format(err) => PreviewError::Corrupt(err.to_string()),
+        err => PreviewError::Unsupported(err.to_string()),
+    }
+}
+
+/// One character cell of block art: the colors of its upper and lower half.
+pub type BlockCell = ([u8; 3], [u8; 3]);
+
+/// Downscale an image into rows of half-block cells (`▀`, foreground = top pixel,
+/// background = bottom pixel) fitting within `max_cols` x `max_rows` cells.
+///
+/// Aspect ratio is kept, assuming cells are about twice as tall as they are wide.
+/// Transparent pixels are blended onto a dark gray background.
+pub fn block_art(image: &Image, max_cols: usize, max_rows: usize) -> Vec<Vec<BlockCell>> {
+    if max_cols == 0 || max_rows == 0 {
+        return Vec::new();
+    }
+    // Each cell covers one pixel column and two pixel rows
+    let scale = f64::max(
+        image.width as f64 / max_cols as f64,
+        image.height as f64 / (max_rows * 2) as f64,
+    )
+    .max(1.0);
+    let cols = ((image.width as f64 / scale).round() as usize).clamp(1, max_cols);
+    let pixel_rows = ((image.height as f64 / scale).round() as usize).clamp(1, max_rows * 2);
+
+    let sample = |cx: usize, cy: usize| -> [u8; 3] {
+        // Average the source pixels covered by this output pixel
+        let x0 = cx * image.width / cols;
+        let x1 = ((cx + 1) * image.width / cols).max(x0 + 1);
+        let y0 = cy * image.height / pixel_rows;
+        let y1 = ((cy + 1) * image.height / pixel_rows).max(y0 + 1);
+        let mut sum = [0u32; 3];
+        let mut count = 0u32;
+        for y in y0..y1.min(image.height) {
+            for x in x0..x1.min(image.width) {
+                let [r, g, b, a] = image.pixel(x, y);
+                for (acc, v) in sum.iter_mut().zip([r, g, b]) {
+                    *acc += blend(v, a);
+                }
+                count += 1;
+            }
+        }
+        let count = count.max(1);
+        [
+            (sum[0] / count) as u8,
+            (sum[1] / count) as u8,
+            (sum[2] / count) as u8,
+        ]
+    };
+
+    (0..pixel_rows.div_ceil(2))
+        .map(|row| {
+            (0..cols)
+                .map(|col| {
+                    let top = sample(col, row * 2);
+                    let bottom = if row * 2 + 1 < pixel_rows {
+                        sample(col, row * 2 + 1)
+                    } else {
+                        BACKGROUND
+                    };
+                    (top, bottom)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Background that transparent pixels are blended onto.
+const BACKGROUND: [u8; 3] = [32, 32, 32];
+
+fn blend(value: u8, alpha: u8) -> u32 {
+    let alpha = alpha as u32;
+    (value as u32 * alpha + BACKGROUND[0] as u32 * (255 - alpha)) / 255
+}
+
+/// Terminal graphics protocol used for full-resolution previews.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+    /// No graphics protocol; only block art is available.
+    Blocks,
+}
+
+impl GraphicsProtocol {
+    /// Pick a protocol from the environment.
+    ///
+    /// `GIT_REVIEW_IMAGE_PROTOCOL` (`kitty`, `iterm2`, `sixel`, or `blocks`) overrides
+    /// detection. Sixel support cannot be detected from the environment, so it is
+    /// only used when requested.
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(choice) = var("GIT_REVIEW_IMAGE_PROTOCOL") {
+            match choice.to_ascii_lowercase().as_str() {
+                "kitty" => return Self::Kitty,
+                "iterm2" | "iterm" => return Self::Iterm2,
+                "sixel" => return Self::Sixel,
+                "blocks" | "none" => return Self::Blocks,
+                _ => {}
+            }
+        }
+        if var("KITTY_WINDOW_ID").is_some() || var("TERM").as_deref() == Some("xterm-kitty") {
+            return Self::Kitty;
+        }
+        match var("TERM_PROGRAM").as_deref() {
+            Some("iTerm.app") | Some("WezTerm") => Self::Iterm2,
+            Some("ghostty") => Self::Kitty,
+            _ => Self::Blocks,
+        }
+    }
+}
+
+/// Escape sequence that draws an image with the given protocol at the cursor.
+///
+/// Returns `None` when the protocol cannot show this image (e.g. kitty with a
+/// non-PNG file, or block-art-only terminals).
+pub fn graphics_sequence(protocol: GraphicsProtocol, bytes: &[u8]) -> Option<String> {
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return None;
+    }
+    match protocol {
+        GraphicsProtocol::Kitty if is_png(bytes) => Some(kitty_sequence(bytes)),
+        GraphicsProtocol::Iterm2 => Some(iterm2_sequence(bytes)),
+        GraphicsProtocol::Sixel => decode_png(bytes)
+            .ok()
+            .and_then(|image| sixel_sequence(&image)),
+        _ => None,
+    }
+}
+
+/// Kitty graphics protocol: transmit PNG data in base64 chunks of at most 4096 bytes.
+fn kitty_sequence(png: &[u8]) -> String {
+    let encoded = BASE64.encode(png);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            out.push_str(&format!("\x1b_Ga=T,f=100,m={};{}\x1b\\", more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// iTerm2 inline image protocol (also understood by WezTerm).
+fn iterm2_sequence(bytes: &[u8]) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};preserveAspectRatio=1:{}\x07",
+        bytes.len(),
+        BASE64.encode(bytes)
+    )
+}
+
+/// Sixel encoding, with a palette fitted to the image. Transparent pixels are
+/// blended onto the background first, since sixel has no alpha.
+fn sixel_sequence(image: &Image) -> Option<String> {
+    let rgb: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|&[r, g, b, a]| [r, g, b].map(|v| blend(v, a) as u8))
+        .collect();
+    icy_sixel::sixel_string(
+        &rgb,
+        image.width as i32,
+        image.height as i32,
+        icy_sixel::PixelFormat::RGB888,
+        icy_sixel::DiffusionMethod::Auto,
+        icy_sixel::MethodForLargest::Auto,
+        icy_sixel::MethodForRep::Auto,
+        icy_sixel::Quality::AUTO,
+    )
+    .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode RGBA pixels as a PNG.
+    fn encode_png(width: usize, height: usize, pixels: &[[u8; 4]]) -> Vec<u8> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(pixels.as_flattened()).unwrap();
+        writer.finish().unwrap();
+        png
+    }
+
+    #[test]
+    fn decode_png_round_trip() {
+        let pixels = vec![
+            [255, 0, 0, 255],
+            [0, 255, 0, 255],
+            [0, 0, 255, 255],
+            [255, 255, 255, 0],
+        ];
+        let image = decode_png(&encode_png(2, 2, &pixels)).unwrap();
+        assert_eq!(image.width, 2);
+        assert_eq!(image.height, 2);
+        assert_eq!(image.pixels, pixels);
+    }
+
+    #[test]
+    fn decode_png_rejects_other_formats() {
+        assert!(matches!(
+            decode_png(b"GIF89a...."),
+            Err(PreviewError::NotPng)
+        ));
+    }
+
+    #[test]
+    fn block_art_fits_and_keeps_colors() {
+        let red = [255, 0, 0, 255];
+        let blue = [0, 0, 255, 255];
+        // Top half red, bottom half blue
+        let pixels: Vec<[u8; 4]> = (0..16)
+            .flat_map(|y| std::iter::repeat_n(if y < 8 { red } else { blue }, 8))
+            .collect();
+        let image = Image {
+            width: 8,
+            height: 16,
+            pixels,
+        };
+
+        let art = block_art(&image, 4, 4);
+        assert_eq!(art.len(), 4);
+        assert!(art.iter().all(|row| row.len() <= 4));
+        assert_eq!(art[0][0].0, [255, 0, 0]);
+        assert_eq!(art[3][0].1, [0, 0, 255]);
+    }
+
+    #[test]
+    fn protocol_detection() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == name)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert_eq!(
+            GraphicsProtocol::from_env(env(&[("TERM", "xterm-kitty")])),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            GraphicsProtocol::from_env(env(&[("TERM_PROGRAM", "iTerm.app")])),
+            GraphicsProtocol::Iterm2
+        );
+        assert_eq!(
+            GraphicsProtocol::from_env(env(&[
+                ("TERM", "xterm-kitty"),
+                ("GIT_REVIEW_IMAGE_PROTOCOL", "sixel")
+            ])),
+            GraphicsProtocol::Sixel
+        );
+        assert_eq!(
+            GraphicsProtocol::from_env(env(&[("TERM", "xterm-256color")])),
+            GraphicsProtocol::Blocks
+        );
+    }
+
+    #[test]
+    fn kitty_sequence_is_chunked() {
+        let png = encode_png(1, 1, &[[0, 0, 0, 255]]);
+        let seq = graphics_sequence(GraphicsProtocol::Kitty, &png).unwrap();
+        assert!(seq.starts_with("\x1b_Ga=T,f=100,m=0;"));
+        assert!(graphics_sequence(GraphicsProtocol::Kitty, b"GIF89a").is_none());
+        assert!(graphics_sequence(GraphicsProtocol::Blocks, &png).is_none());
+
+        let sixel = graphics_sequence(GraphicsProtocol::Sixel, &png).unwrap();
+        assert!(sixel.starts_with("\x1bP") && sixel.ends_with("\x1b\\"));
+    }
+
+    #[test]
+    fn is_image_path_by_extension() {
+        assert!(is_image_path(Path::new("assets/logo.PNG")));
+        assert!(is_image_path(Path::new("photo.jpeg")));
+        assert!(!is_image_path(Path::new("data.bin")));
+        assert!(!is_image_path(Path::new("Makefile")));
+    }
+}
@@ -0,0 +1,154 @@
+//! Review priority ordering.
+//!
+//! With limited attention, the riskiest changes should be read first. Priority
+//! order puts files matching the `sensitive` globs of `.git-review.toml` first
+//! and test files last, with larger changes ahead of smaller ones at each level.
+//! Within a file, larger hunks come first.
+
+use crate::{DiffFile, DiffHunk};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+
+/// Globs that identify test files when `[priority] tests` is not set.
+pub const DEFAULT_TEST_GLOBS: &[&str] = &[
+    "tests/**",
+    "test/**",
+    "**/tests/**",
+    "**/test/**",
+    "**/*_test.*",
+    "**/*.test.*",
+    "**/*.spec.*",
+    "**/test_*",
+];
+
+/// `[priority]` section of `.git-review.toml`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PriorityConfig {
+    /// Open reviews in priority order instead of diff order.
+    pub enabled: bool,
+    /// Path globs reviewed before everything else.
+    pub sensitive: Vec<String>,
+    /// Path globs of test files, reviewed after everything else.
+    pub tests: Vec<String>,
+}
+
+impl Default for PriorityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sensitive: Vec::new(),
+            tests: DEFAULT_TEST_GLOBS.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+/// Compiled priority rules.
+#[derive(Debug, Clone, Default)]
+pub struct Prioritizer {
+    sensitive: GlobSet,
+    tests: GlobSet,
+}
+
+impl Prioritizer {
+    /// Compile the globs of a `[priority]` section.
+    pub fn new(config: &PriorityConfig) -> Result<Self, globset::Error> {
+        let build = |patterns: &[String]| {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in patterns {
+                builder.add(Glob::new(pattern)?);
+            }
+            builder.build()
+        };
+        Ok(Self {
+            sensitive: build(&config.sensitive)?,
+            tests: build(&config.tests)?,
+        })
+    }
+
+    /// Indices of `files` in priority order: sensitive files, then other
+    /// non-test files, then tests; larger changes first within each group.
+    pub fn file_order(&self, files: &[DiffFile]) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..files.len()).collect();
+        order.sort_by_cached_key(|&i| {
+            let file = &files[i];
+            let path = file.path.to_string_lossy();
+            let size: usize = file.hunks.iter().map(changed_lines).sum();
+            (
+                !self.sensitive.is_match(path.as_ref()),
+                self.tests.is_match(path.as_ref()),
+                Reverse(size),
+            )
+        });
+        order
+    }
+}
+
+/// Indices of a file's hunks, largest first (diff order among equal sizes).
+pub fn hunk_order(file: &DiffFile) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..file.hunks.len()).collect();
+    order.sort_by_key(|&i| Reverse(changed_lines(&file.hunks[i])));
+    order
+}
+
+/// Number of added and removed lines in a hunk.
+pub fn changed_lines(hunk: &DiffHunk) -> usize {
+    hunk.content
+        .lines()
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_diff;
+
+    fn file_diff(path: &str, hunks: &[usize]) -> String {
+        let mut diff = format!("diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n", path);
+        for (i, &added) in hunks.iter().enumerate() {
+            let start = i * 100 + 1;
+            diff.push_str(&format!("@@ -{0},0 +{0},{1} @@\n", start, added));
+            for line in 0..added {
+                diff.push_str(&format!("+line {}\n", line));
+            }
+        }
+        diff
+    }
+
+    #[test]
+    fn sensitive_first_tests_last_then_by_size() {
+        let diff = [
+            file_diff("tests/big.rs", &[50]),
+            file_diff("src/small.rs", &[1]),
+            file_diff("src/large.rs", &[10]),
+            file_diff("src/auth/login.rs", &[2]),
+        ]
+        .concat();
+        let files = parse_diff(&diff);
+        let config: PriorityConfig = toml::from_str(r#"sensitive = ["src/auth/**"]"#).unwrap();
+        let prioritizer = Prioritizer::new(&config).unwrap();
+
+        let paths: Vec<_> = prioritizer
+            .file_order(&files)
+            .into_iter()
+            .map(|i| files[i].path.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                "src/auth/login.rs",
+                "src/large.rs",
+                "src/small.rs",
+                "tests/big.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn larger_hunks_first() {
+        let files = parse_diff(&file_diff("src/a.rs", &[2, 5, 2, 9]));
+        assert_eq!(hunk_order(&files[0]), vec![3, 1, 0, 2]);
+    }
+}
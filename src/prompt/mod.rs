@@ -0,0 +1,100 @@
+//! One-letter command prompts read line by line, shared by `step` and
+//! `--accessible`.
+//!
+//! Each caller describes its commands in a table; [`ask`] prints the prompt,
+//! reads answers until one matches the table, and reprints the help for
+//! anything else.
+
+use std::io::{self, BufRead, Write};
+
+/// What a command asks to do with the current hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Approve,
+    NeedsChanges,
+    Skip,
+    Comment,
+    Repeat,
+    Quit,
+    /// Print the help and ask again; [`ask`] handles it itself.
+    Help,
+}
+
+/// A command: its spellings, the first being the one the prompt shows.
+#[derive(Debug, Clone, Copy)]
+pub struct Command {
+    pub keys: &'static [&'static str],
+    pub answer: Answer,
+}
+
+/// The choices shown in a prompt, such as `y/n/s/q`.
+pub fn choices(commands: &[Command]) -> String {
+    commands
+        .iter()
+        .map(|command| command.keys[0])
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Write `prompt` and read lines until one names a command in `commands`
+/// (case-insensitively), writing `help` after each line that doesn't.
+///
+/// Returns `None` at end of input.
+pub fn ask(
+    commands: &[Command],
+    prompt: &str,
+    help: &str,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> io::Result<Option<Answer>> {
+    loop {
+        write!(output, "{}", prompt)?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim().to_lowercase();
+        match commands
+            .iter()
+            .find(|command| command.keys.contains(&line.as_str()))
+        {
+            Some(command) if command.answer != Answer::Help => return Ok(Some(command.answer)),
+            _ => writeln!(output, "{}", help)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMANDS: &[Command] = &[
+        Command {
+            keys: &["y", "yes"],
+            answer: Answer::Approve,
+        },
+        Command {
+            keys: &["?"],
+            answer: Answer::Help,
+        },
+        Command {
+            keys: &["q"],
+            answer: Answer::Quit,
+        },
+    ];
+
+    #[test]
+    fn asks_until_a_command_matches() {
+        assert_eq!(choices(COMMANDS), "y/?/q");
+
+        let mut input = "maybe\n?\n YES \n".as_bytes();
+        let mut output = Vec::new();
+        let answer = ask(COMMANDS, "> ", "help", &mut input, &mut output).unwrap();
+        assert_eq!(answer, Some(Answer::Approve));
+        assert_eq!(String::from_utf8(output).unwrap(), "> help\n> help\n> ");
+
+        let answer = ask(COMMANDS, "> ", "help", &mut "".as_bytes(), &mut Vec::new()).unwrap();
+        assert_eq!(answer, None);
+    }
+}
@@ -0,0 +1,113 @@
+//! Merge queue: branches marked for merge in the dashboard are merged once
+//! their review is complete.
+//!
+//! A queued branch is ready when every hunk is reviewed and `git merge-tree`
+//! finds no conflicts with the base. The dashboard checks the queue as it
+//! refreshes, and `watch --merge` does the same from a terminal; both ask
+//! before merging unless `[merge_queue] confirm = false`.
+
+use crate::dashboard::{PruneError, branch_progress};
+use crate::git::{self, GitError, MergeCheck, WorktreeStatus};
+use crate::state::{ReviewDb, StateError};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// `[merge_queue]` section of `.git-review.toml`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MergeQueueConfig {
+    /// Ask before merging a ready branch; turn off for trusted flows.
+    pub confirm: bool,
+    /// Delete merged branches and their review state afterwards.
+    pub delete_after: bool,
+}
+
+impl Default for MergeQueueConfig {
+    fn default() -> Self {
+        Self {
+            confirm: true,
+            delete_after: false,
+        }
+    }
+}
+
+/// Errors that can occur while merging a queued branch.
+#[derive(Debug, Error)]
+pub enum QueueError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error(transparent)]
+    State(#[from] StateError),
+    #[error(transparent)]
+    Prune(#[from] PruneError),
+    #[error("check out {0} to merge into it")]
+    NotOnBase(String),
+    #[error("working tree has uncommitted changes")]
+    DirtyWorktree,
+}
+
+/// Whether a queued branch can be merged yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Readiness {
+    /// Fully reviewed and merges cleanly.
+    Ready,
+    /// Hunks are left to review (or there is nothing to merge).
+    Reviewing { reviewed: usize, total: usize },
+    /// Merging would conflict.
+    Conflicts,
+    /// The conflict pre-check could not run.
+    Unknown(String),
+}
+
+/// Check a branch against `base`, syncing its review state with the current diff.
+pub fn readiness(db: &mut ReviewDb, base: &str, branch: &str) -> Readiness {
+    let progress = branch_progress(db, &format!("{}..{}", base, branch));
+    if progress.total == 0 || progress.reviewed < progress.total {
+        return Readiness::Reviewing {
+            reviewed: progress.reviewed,
+            total: progress.total,
+        };
+    }
+    match git::check_merge_conflicts(base, branch) {
+        Ok(MergeCheck::Clean) => Readiness::Ready,
+        Ok(MergeCheck::Conflicts) => Readiness::Conflicts,
+        Ok(MergeCheck::Error(e)) => Readiness::Unknown(e.trim().to_string()),
+        Err(e) => Readiness::Unknown(e.to_string()),
+    }
+}
+
+/// Queued branches for `base` that are ready to merge, oldest first.
+pub fn ready_branches(db: &mut ReviewDb, base: &str) -> Result<Vec<String>, StateError> {
+    let queued = db.merge_queue(base)?;
+    Ok(queued
+        .into_iter()
+        .filter(|branch| readiness(db, base, branch) == Readiness::Ready)
+        .collect())
+}
+
+/// Merge a queued branch into `base`, which must be checked out with a clean
+/// working tree, take it off the queue, and archive its review state.
+pub fn merge(
+    db: &mut ReviewDb,
+    base: &str,
+    branch: &str,
+    config: &MergeQueueConfig,
+) -> Result<(), QueueError> {
+    if git::get_current_branch()?.as_deref() != Some(base) {
+        return Err(QueueError::NotOnBase(base.to_string()));
+    }
+    if let WorktreeStatus::Dirty { .. } = git::check_worktree_status()? {
+        return Err(QueueError::DirtyWorktree);
+    }
+    git::merge_branch(&git::MergeOptions {
+        branch: branch.to_string(),
+        delete_after: false,
+    })?;
+    db.dequeue_merge(branch)?;
+    let merge_sha = git::resolve_ref("HEAD").ok();
+    crate::dashboard::archive_branch(db, branch, merge_sha.as_deref())?;
+    if config.delete_after {
+        crate::dashboard::prune_branch(db, base, branch)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,934 @@
+//! Renderings of a review session for use outside the TUI.
+
+use crate::HunkStatus;
+use crate::session::{Result, ReviewSession};
+use crate::state::{Approval, HunkRecord, status_to_string};
+use std::collections::{HashMap, HashSet};
+
+/// Longest hunk summary shown in a report line, in characters.
+const SUMMARY_WIDTH: usize = 60;
+
+/// Render pending hunks as `file:line: [STATUS] summary` lines.
+///
+/// The format is understood by vim's `:cfile`/`:cgetexpr` and emacs'
+/// compilation mode. Unreviewed, skimmed, and needs-changes hunks point at their first new
+/// line; stale hunks no longer exist in the diff, so they point at line 1 of their file.
+pub fn quickfix(session: &ReviewSession) -> Result<String> {
+    let mut out = String::new();
+
+    for file in session.files() {
+        let path = file.path.to_string_lossy();
+        for hunk in &file.hunks {
+            let tag = match hunk.status {
+                HunkStatus::Unreviewed => "UNREVIEWED",
+                HunkStatus::NeedsChanges => "NEEDS CHANGES",
+                HunkStatus::Skimmed => "SKIMMED",
+                _ => continue,
+            };
+            out.push_str(&format!(
+                "{}:{}: [{}] {}\n",
+                path,
+                hunk.new_start.max(1),
+                tag,
+                hunk_summary(&hunk.content)
+            ));
+        }
+    }
+
+    for record in session
+        .hunk_records()?
+        .iter()
+        .filter(|r| r.status == HunkStatus::Stale)
+    {
+        out.push_str(&stale_line(record));
+    }
+
+    Ok(out)
+}
+
+/// Render the hunks in `changed` (see [`ReviewDb::changed_since`]) as
+/// `file:line: [STATUS] summary` lines, in the same format as [`quickfix`].
+///
+/// [`ReviewDb::changed_since`]: crate::state::ReviewDb::changed_since
+pub fn changed(session: &ReviewSession, changed: &HashSet<(String, String)>) -> Result<String> {
+    let mut out = String::new();
+
+    for file in session.files() {
+        let path = file.path.to_string_lossy().to_string();
+        for hunk in &file.hunks {
+            if hunk.status == HunkStatus::Unreviewed
+                && changed.contains(&(path.clone(), hunk.content_hash.clone()))
+            {
+                out.push_str(&format!(
+                    "{}:{}: [UNREVIEWED] {}\n",
+                    path,
+                    hunk.new_start.max(1),
+                    hunk_summary(&hunk.content)
+                ));
+            }
+        }
+    }
+
+    for record in session.hunk_records()?.iter().filter(|r| {
+        r.status == HunkStatus::Stale
+            && changed.contains(&(r.file_path.clone(), r.content_hash.clone()))
+    }) {
+        out.push_str(&stale_line(record));
+    }
+
+    Ok(out)
+}
+
+/// Render bookmarked hunks (see [`ReviewDb::bookmarks`]) as
+/// `file:line: [STATUS] summary` lines, in the same format as [`quickfix`].
+/// Bookmarks of hunks that changed since point at line 1 of their file.
+///
+/// [`ReviewDb::bookmarks`]: crate::state::ReviewDb::bookmarks
+pub fn bookmarks(session: &ReviewSession, bookmarks: &HashSet<(String, String)>) -> String {
+    let mut out = String::new();
+    let mut shown = HashSet::new();
+
+    for file in session.files() {
+        let path = file.path.to_string_lossy().to_string();
+        for hunk in &file.hunks {
+            let key = (path.clone(), hunk.content_hash.clone());
+            if bookmarks.contains(&key) {
+                out.push_str(&format!(
+                    "{}:{}: [{}] {}\n",
+                    path,
+                    hunk.new_start.max(1),
+                    status_to_string(hunk.status)
+                        .to_uppercase()
+                        .replace('_', " "),
+                    hunk_summary(&hunk.content)
+                ));
+                shown.insert(key);
+            }
+        }
+    }
+
+    let mut gone: Vec<_> = bookmarks.difference(&shown).collect();
+    gone.sort();
+    for (path, hash) in gone {
+        out.push_str(&format!(
+            "{}:1: [CHANGED] hunk {} no longer in diff\n",
+            path,
+            &hash[..hash.len().min(8)]
+        ));
+    }
+
+    out
+}
+
+/// Render the reviewed hunks in `approvals` (see [`ReviewDb::approvals_within`])
+/// as `file:line: [REVIEWED] 25m ago by alice: summary` lines, most recent
+/// first, in the same format as [`quickfix`].
+///
+/// [`ReviewDb::approvals_within`]: crate::state::ReviewDb::approvals_within
+pub fn reviewed(
+    session: &ReviewSession,
+    approvals: &HashMap<(String, String), Approval>,
+) -> String {
+    let mut lines = Vec::new();
+    for file in session.files() {
+        let path = file.path.to_string_lossy().to_string();
+        for hunk in &file.hunks {
+            let Some(approval) = approvals.get(&(path.clone(), hunk.content_hash.clone())) else {
+                continue;
+            };
+            if hunk.status != HunkStatus::Reviewed {
+                continue;
+            }
+            let by = match (&approval.reviewed_by, &approval.auto_rule) {
+                (_, Some(rule)) => format!(" (auto: {})", rule),
+                (Some(name), None) => format!(" by {}", name),
+                (None, None) => String::new(),
+            };
+            let line = format!(
+                "{}:{}: [REVIEWED] {}{}: {}\n",
+                path,
+                hunk.new_start.max(1),
+                format_age(approval.age),
+                by,
+                hunk_summary(&hunk.content)
+            );
+            lines.push((approval.age, line));
+        }
+    }
+    // Stable, so hunks approved together stay in diff order
+    lines.sort_by_key(|&(age, _)| age);
+    lines.into_iter().map(|(_, line)| line).collect()
+}
+
+/// Format how long ago something happened (e.g. "just now", "5m ago", "2h ago", "3d ago").
+pub fn format_age(secs: u64) -> String {
+    if secs < 60 {
+        "just now".to_string()
+    } else if secs < 3600 {
+        format!("{}m ago", secs / 60)
+    } else if secs < 86_400 {
+        format!("{}h ago", secs / 3600)
+    } else {
+        format!("{}d ago", secs / 86_400)
+    }
+}
+
+/// Quickfix line for a stale hunk, which points at line 1 of its file.
+fn stale_line(record: &HunkRecord) -> String {
+    format!(
+        "{}:1: [STALE] hunk {} no longer in diff\n",
+        record.file_path,
+        &record.content_hash[..record.content_hash.len().min(8)]
+    )
+}
+
+/// Render a punch list of things to fix after a review pass, as
+/// `file:line: [TAG] text` lines in the same format as [`quickfix`].
+///
+/// Lists hunks flagged as needing changes (with their comments, if any) and
+/// every added line that contains a `TODO` or `FIXME` marker.
+pub fn todos(session: &ReviewSession) -> Result<String> {
+    let comments = session.comments()?;
+    let mut out = String::new();
+
+    for file in session.files() {
+        let path = file.path.to_string_lossy();
+        for hunk in &file.hunks {
+            if hunk.status == HunkStatus::NeedsChanges {
+                let notes: Vec<&str> = comments
+                    .iter()
+                    .filter(|c| c.file_path == path && c.content_hash == hunk.content_hash)
+                    .map(|c| c.body.as_str())
+                    .collect();
+                let text = if notes.is_empty() {
+                    hunk_summary(&hunk.content)
+                } else {
+                    notes.join(" / ")
+                };
+                out.push_str(&format!(
+                    "{}:{}: [NEEDS CHANGES] {}\n",
+                    path,
+                    hunk.new_start.max(1),
+                    text
+                ));
+            }
+
+            let mut line_no = hunk.new_start;
+            for line in hunk.content.lines() {
+                if let Some(added) = line.strip_prefix('+') {
+                    if added.contains("TODO") || added.contains("FIXME") {
+                        out.push_str(&format!(
+                            "{}:{}: [TODO] {}\n",
+                            path,
+                            line_no,
+                            truncate_summary(added.trim())
+                        ));
+                    }
+                    line_no += 1;
+                } else if line.starts_with(' ') {
+                    line_no += 1;
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Summarize a hunk by its first changed line, truncated to [`SUMMARY_WIDTH`].
+pub fn hunk_summary(content: &str) -> String {
+    let line = content
+        .lines()
+        .find(|l| l.starts_with('+') || l.starts_with('-'))
+        .map(|l| l[1..].trim())
+        .or_else(|| content.lines().find(|l| l.starts_with("Binary files ")))
+        .unwrap_or("");
+    truncate_summary(line)
+}
+
+/// Cut a line down to [`SUMMARY_WIDTH`] characters, marking the cut with `…`.
+fn truncate_summary(line: &str) -> String {
+    if line.chars().count() > SUMMARY_WIDTH {
+        let truncated: String = line.chars().take(SUMMARY_WIDTH - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Render a concise Markdown summary for a PR description or chat message.
+///
+/// Lists per-file progress with [`risk_tags`], and comments on hunks that are
+/// not yet reviewed. `title` names the diff the way the user gave it (such as
+/// `main..HEAD`), since the session's state key is an internal spelling.
+pub fn markdown(session: &ReviewSession, title: &str) -> Result<String> {
+    let progress = session.progress()?;
+    let comments = session.comments()?;
+    let percent = (progress.reviewed * 100)
+        .checked_div(progress.total_hunks)
+        .unwrap_or(100);
+
+    let mut out = format!(
+        "## Review summary: `{}`\n\n**{}/{} hunks reviewed** ({}%) · {} unreviewed · {} stale · {} need changes · {} files\n\n",
+        title,
+        progress.reviewed,
+        progress.total_hunks,
+        percent,
+        progress.unreviewed,
+        progress.stale,
+        progress.needs_changes,
+        progress.total_files
+    );
+
+    out.push_str("| File | Changes | Reviewed | Risk |\n|------|---------|----------|------|\n");
+    for file in session.files() {
+        let path = file.path.to_string_lossy();
+        let (added, removed) = file.hunks.iter().fold((0, 0), |(a, r), hunk| {
+            let (ha, hr) = crate::parser::count_changes(&hunk.content);
+            (a + ha, r + hr)
+        });
+        let reviewed = file
+            .hunks
+            .iter()
+            .filter(|h| h.status == HunkStatus::Reviewed)
+            .count();
+        out.push_str(&format!(
+            "| `{}` | +{} -{} | {}/{} | {} |\n",
+            escape_table_cell(&path),
+            added,
+            removed,
+            reviewed,
+            file.hunks.len(),
+            risk_tags(&path, added + removed).join(", ")
+        ));
+    }
+
+    let mut outstanding = Vec::new();
+    for file in session.files() {
+        let path = file.path.to_string_lossy();
+        for hunk in file
+            .hunks
+            .iter()
+            .filter(|h| h.status != HunkStatus::Reviewed)
+        {
+            for comment in comments
+                .iter()
+                .filter(|c| c.file_path == path && c.content_hash == hunk.content_hash)
+            {
+                outstanding.push(format!(
+                    "- `{}:{}` — **{}**: {}\n",
+                    path,
+                    hunk.new_start.max(1),
+                    comment.author.as_deref().unwrap_or("unknown"),
+                    comment.body
+                ));
+            }
+        }
+    }
+    if !outstanding.is_empty() {
+        out.push_str("\n### Outstanding comments\n\n");
+        out.extend(outstanding);
+    }
+
+    Ok(out)
+}
+
+/// Render a plain-text review summary for a commit message (`commit --summarize`).
+///
+/// Names who reviewed how many hunks, lists each file's hunk count, and counts
+/// the comments resolved, meaning those on hunks that have since been reviewed.
+pub fn commit_summary(session: &ReviewSession) -> Result<String> {
+    let comments = session.comments()?;
+    // Stale rows of earlier commits stay in the database; only count this diff
+    let in_diff: HashSet<(String, String)> = session
+        .files()
+        .iter()
+        .flat_map(|file| {
+            let path = file.path.to_string_lossy().to_string();
+            file.hunks
+                .iter()
+                .map(move |hunk| (path.clone(), hunk.content_hash.clone()))
+        })
+        .collect();
+    let records: Vec<HunkRecord> = session
+        .hunk_records()?
+        .into_iter()
+        .filter(|r| {
+            r.status == HunkStatus::Reviewed
+                && in_diff.contains(&(r.file_path.clone(), r.content_hash.clone()))
+        })
+        .collect();
+
+    let mut reviewers: Vec<&str> = Vec::new();
+    for record in records.iter().filter(|r| r.auto_rule.is_none()) {
+        if let Some(reviewer) = record.reviewed_by.as_deref()
+            && !reviewers.contains(&reviewer)
+        {
+            reviewers.push(reviewer);
+        }
+    }
+    let mut out = format!(
+        "Review: {}/{} hunks in {} files reviewed",
+        records.len(),
+        in_diff.len(),
+        session.files().len()
+    );
+    if !reviewers.is_empty() {
+        out.push_str(&format!(" by {}", reviewers.join(", ")));
+    }
+    let auto_approved = records.iter().filter(|r| r.auto_rule.is_some()).count();
+    if auto_approved > 0 {
+        out.push_str(&format!(" ({} auto-approved)", auto_approved));
+    }
+    out.push('\n');
+
+    let mut resolved = 0;
+    for file in session.files() {
+        let path = file.path.to_string_lossy();
+        let reviewed: Vec<_> = file
+            .hunks
+            .iter()
+            .filter(|h| h.status == HunkStatus::Reviewed)
+            .collect();
+        resolved += comments
+            .iter()
+            .filter(|c| {
+                c.file_path == path && reviewed.iter().any(|h| h.content_hash == c.content_hash)
+            })
+            .count();
+        if reviewed.len() == file.hunks.len() {
+            out.push_str(&format!("- {}: {} hunk(s)\n", path, file.hunks.len()));
+        } else {
+            out.push_str(&format!(
+                "- {}: {}/{} hunk(s) reviewed\n",
+                path,
+                reviewed.len(),
+                file.hunks.len()
+            ));
+        }
+    }
+    if resolved > 0 {
+        out.push_str(&format!("Comments resolved: {}\n", resolved));
+    }
+
+    Ok(out)
+}
+
+/// Lines changed in a single file at or above which it is tagged `large`.
+const LARGE_FILE_CHANGES: usize = 200;
+
+/// Heuristic tags that flag files deserving a closer look.
+pub fn risk_tags(path: &str, changed_lines: usize) -> Vec<&'static str> {
+    let lower = path.to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    let mut tags = Vec::new();
+
+    const DEPENDENCY_FILES: &[&str] = &[
+        "cargo.toml",
+        "cargo.lock",
+        "package.json",
+        "package-lock.json",
+        "yarn.lock",
+        "pnpm-lock.yaml",
+        "go.mod",
+        "go.sum",
+        "requirements.txt",
+        "poetry.lock",
+        "gemfile.lock",
+    ];
+    const SECURITY_WORDS: &[&str] = &["auth", "crypto", "secret", "password", "token", "security"];
+    const CONFIG_EXTENSIONS: &[&str] = &[".toml", ".yaml", ".yml", ".json", ".ini", ".env"];
+
+    if DEPENDENCY_FILES.contains(&name) {
+        tags.push("deps");
+    } else if CONFIG_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+        tags.push("config");
+    }
+    if lower.starts_with(".github/") || name == ".gitlab-ci.yml" {
+        tags.push("ci");
+    }
+    if lower.contains("migration") {
+        tags.push("migration");
+    }
+    if SECURITY_WORDS.iter().any(|word| lower.contains(word)) {
+        tags.push("security");
+    }
+    if changed_lines >= LARGE_FILE_CHANGES {
+        tags.push("large");
+    }
+    tags
+}
+
+/// Escape `|` so text does not split a Markdown table cell.
+fn escape_table_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Render the diff as a standalone HTML page with status badges and comments.
+///
+/// Code is syntax-highlighted when the `highlight` feature is enabled. `title`
+/// names the diff, as for [`markdown`].
+pub fn html(session: &ReviewSession, title: &str) -> Result<String> {
+    let progress = session.progress()?;
+    let comments = session.comments()?;
+    let mut body = String::new();
+
+    for file in session.files() {
+        let path = file.path.to_string_lossy();
+        let mut highlighter = CodeHighlighter::for_path(&path);
+        body.push_str(&format!(
+            "<section class=\"file\">\n<h2>{}</h2>\n",
+            escape_html(&path)
+        ));
+
+        for hunk in &file.hunks {
+            let status = status_to_string(hunk.status);
+            body.push_str(&format!(
+                "<div class=\"hunk\">\n<div class=\"hunk-header\"><code>@@ -{},{} +{},{} @@</code> \
+                 <span class=\"badge {status}\">{status}</span></div>\n<div class=\"code\">\n",
+                hunk.old_start, hunk.old_count, hunk.new_start, hunk.new_count
+            ));
+            for line in hunk.content.lines() {
+                let (class, marker, code) = match line.as_bytes().first() {
+                    Some(b'+') => ("add", "+", &line[1..]),
+                    Some(b'-') => ("del", "-", &line[1..]),
+                    Some(b' ') => ("ctx", " ", &line[1..]),
+                    _ => ("meta", "", line),
+                };
+                body.push_str(&format!(
+                    "<div class=\"line {}\"><span class=\"marker\">{}</span>{}</div>\n",
+                    class,
+                    marker,
+                    highlighter.line(code)
+                ));
+            }
+            body.push_str("</div>\n");
+
+            for comment in comments
+                .iter()
+                .filter(|c| c.file_path == path && c.content_hash == hunk.content_hash)
+            {
+                body.push_str(&format!(
+                    "<div class=\"comment\"><strong>{}</strong> <time>{}</time><p>{}</p></div>\n",
+                    escape_html(comment.author.as_deref().unwrap_or("unknown")),
+                    escape_html(&comment.created_at),
+                    escape_html(&comment.body)
+                ));
+            }
+            body.push_str("</div>\n");
+        }
+        body.push_str("</section>\n");
+    }
+
+    Ok(format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>Review report: {title}</title>
+<style>{css}</style>
+</head>
+<body>
+<h1>Review report: <code>{title}</code></h1>
+<p class=\"summary\">{reviewed}/{total} hunks reviewed &middot; {unreviewed} unreviewed &middot; {stale} stale &middot; {needs_changes} need changes &middot; {files} files</p>
+{body}</body>
+</html>
+",
+        title = escape_html(title),
+        css = REPORT_CSS,
+        reviewed = progress.reviewed,
+        total = progress.total_hunks,
+        unreviewed = progress.unreviewed,
+        stale = progress.stale,
+        needs_changes = progress.needs_changes,
+        files = progress.total_files,
+        body = body,
+    ))
+}
+
+pub(crate) const REPORT_CSS: &str = "
+body { font-family: -apple-system, 'Segoe UI', sans-serif; margin: 2rem auto; max-width: 70rem; color: #24292f; }
+h2 { font-size: 1rem; font-family: monospace; border-bottom: 1px solid #d0d7de; padding-bottom: .25rem; }
+.hunk { border: 1px solid #d0d7de; border-radius: 6px; margin: 1rem 0; overflow: hidden; }
+.hunk-header { background: #f6f8fa; padding: .25rem .5rem; color: #57606a; }
+.code { font-family: monospace; font-size: .85rem; }
+.line { white-space: pre; padding: 0 .5rem; }
+.line.add { background: #e6ffec; }
+.line.del { background: #ffebe9; }
+.line.meta { color: #57606a; }
+.marker { display: inline-block; width: 1.2em; color: #57606a; user-select: none; }
+.badge { float: right; border-radius: 1em; padding: 0 .6em; font-size: .8rem; color: #fff; }
+.badge.reviewed { background: #1a7f37; }
+.badge.unreviewed { background: #cf222e; }
+.badge.stale { background: #9a6700; }
+.badge.needs_changes { background: #8250df; }
+.comment { border-top: 1px solid #d0d7de; background: #fff8c5; padding: .5rem; }
+.comment p { margin: .25rem 0 0; white-space: pre-wrap; }
+.comment time { color: #57606a; font-size: .8rem; }
+";
+
+/// Escape text for inclusion in HTML content or attribute values.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(feature = "highlight")]
+static SYNTAXES: std::sync::LazyLock<syntect::parsing::SyntaxSet> =
+    std::sync::LazyLock::new(syntect::parsing::SyntaxSet::load_defaults_newlines);
+
+/// Light theme, to suit a page with a white background.
+#[cfg(feature = "highlight")]
+static THEME: std::sync::LazyLock<syntect::highlighting::Theme> = std::sync::LazyLock::new(|| {
+    let mut themes = syntect::highlighting::ThemeSet::load_defaults().themes;
+    themes
+        .remove("InspiredGitHub")
+        .or_else(|| themes.into_values().next())
+        .unwrap_or_default()
+});
+
+/// Highlights code lines of one file as HTML, keeping parser state across lines.
+#[cfg(feature = "highlight")]
+struct CodeHighlighter {
+    lines: Option<syntect::easy::HighlightLines<'static>>,
+}
+
+#[cfg(feature = "highlight")]
+impl CodeHighlighter {
+    /// Longest line that gets highlighted; longer lines are escaped as plain text.
+    const MAX_LINE_LENGTH: usize = 10_000;
+
+    fn for_path(path: &str) -> Self {
+        let ext = std::path::Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("");
+        let lines = SYNTAXES
+            .find_syntax_by_extension(ext)
+            .map(|syntax| syntect::easy::HighlightLines::new(syntax, &THEME));
+        Self { lines }
+    }
+
+    fn line(&mut self, code: &str) -> String {
+        use syntect::html::{IncludeBackground, styled_line_to_highlighted_html};
+
+        let Some(lines) = self.lines.as_mut() else {
+            return escape_html(code);
+        };
+        if code.len() > Self::MAX_LINE_LENGTH {
+            return escape_html(code);
+        }
+        let with_newline = format!("{}\n", code);
+        match lines.highlight_line(&with_newline, &SYNTAXES) {
+            Ok(regions) => {
+                let regions: Vec<_> = regions
+                    .into_iter()
+                    .map(|(style, text)| (style, text.trim_end_matches('\n')))
+                    .collect();
+                styled_line_to_highlighted_html(&regions, IncludeBackground::No)
+                    .unwrap_or_else(|_| escape_html(code))
+            }
+            Err(_) => escape_html(code),
+        }
+    }
+}
+
+/// Without the `highlight` feature, code is emitted as escaped plain text.
+#[cfg(not(feature = "highlight"))]
+struct CodeHighlighter;
+
+#[cfg(not(feature = "highlight"))]
+impl CodeHighlighter {
+    fn for_path(_path: &str) -> Self {
+        Self
+    }
+
+    fn line(&mut self, code: &str) -> String {
+        escape_html(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -3,2 +4,2 @@
+ fn a() {
+-    old();
++    new();
+@@ -20 +21 @@
+-x
++y
+";
+
+    #[test]
+    fn quickfix_lists_unreviewed_and_stale_hunks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let mut session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+        let hash = session.files()[0].hunks[1].content_hash.clone();
+        session
+            .set_status("src/a.rs", &hash, HunkStatus::Reviewed)
+            .unwrap();
+        session
+            .db()
+            .set_status("main", "src/gone.rs", "deadbeefcafe", HunkStatus::Stale)
+            .unwrap();
+
+        let out = quickfix(&session).unwrap();
+        assert_eq!(
+            out,
+            "src/a.rs:4: [UNREVIEWED] old();\nsrc/gone.rs:1: [STALE] hunk deadbeef no longer in diff\n"
+        );
+    }
+
+    #[test]
+    fn reviewed_lists_recent_approvals_newest_first() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+        let hash = |i: usize| session.files()[0].hunks[i].content_hash.clone();
+        let approval = |age, reviewed_by: Option<&str>, auto_rule: Option<&str>| Approval {
+            age,
+            reviewed_by: reviewed_by.map(str::to_string),
+            auto_rule: auto_rule.map(str::to_string),
+        };
+        let approvals = HashMap::from([
+            (
+                ("src/a.rs".to_string(), hash(0)),
+                approval(7200, Some("alice"), None),
+            ),
+            (
+                ("src/a.rs".to_string(), hash(1)),
+                approval(300, None, Some("files")),
+            ),
+        ]);
+
+        // Only hunks the session has as reviewed are listed
+        assert_eq!(reviewed(&session, &approvals), "");
+        let mut session = session;
+        for i in 0..2 {
+            let hash = session.files()[0].hunks[i].content_hash.clone();
+            session
+                .set_status("src/a.rs", &hash, HunkStatus::Reviewed)
+                .unwrap();
+        }
+        assert_eq!(
+            reviewed(&session, &approvals),
+            "src/a.rs:21: [REVIEWED] 5m ago (auto: files): x\n\
+             src/a.rs:4: [REVIEWED] 2h ago by alice: old();\n"
+        );
+    }
+
+    #[test]
+    fn format_age_units() {
+        assert_eq!(format_age(5), "just now");
+        assert_eq!(format_age(300), "5m ago");
+        assert_eq!(format_age(7200), "2h ago");
+        assert_eq!(format_age(3 * 86_400 + 5), "3d ago");
+    }
+
+    #[test]
+    fn bookmarks_list_marked_hunks_and_changed_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let mut session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+        let hash = session.files()[0].hunks[1].content_hash.clone();
+        session
+            .set_status("src/a.rs", &hash, HunkStatus::Reviewed)
+            .unwrap();
+        let db = session.db();
+        assert!(db.toggle_bookmark("main", "src/a.rs", &hash).unwrap());
+        assert!(
+            db.toggle_bookmark("main", "src/a.rs", "deadbeefcafe")
+                .unwrap()
+        );
+        assert!(db.toggle_bookmark("main", "src/b.rs", "0ff1ce").unwrap());
+        assert!(!db.toggle_bookmark("main", "src/b.rs", "0ff1ce").unwrap());
+        let marked = db.bookmarks("main").unwrap();
+
+        assert_eq!(
+            bookmarks(&session, &marked),
+            "src/a.rs:21: [REVIEWED] x\nsrc/a.rs:1: [CHANGED] hunk deadbeef no longer in diff\n"
+        );
+    }
+
+    #[test]
+    fn todos_lists_flagged_hunks_and_new_todo_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let diff = "diff --git a/src/a.rs b/src/a.rs
+--- a/src/a.rs
++++ b/src/a.rs
+@@ -3,2 +4,4 @@
+ fn a() {
+-    old();
++    // TODO: handle errors
++    new();
++    // FIXME later
+@@ -20 +23 @@
+-x
++y
+";
+        let mut session = ReviewSession::from_diff(dir.path(), "main", diff).unwrap();
+        let flagged = session.files()[0].hunks[1].content_hash.clone();
+        session
+            .set_status("src/a.rs", &flagged, HunkStatus::NeedsChanges)
+            .unwrap();
+
+        assert_eq!(
+            todos(&session).unwrap(),
+            "src/a.rs:5: [TODO] // TODO: handle errors\n\
+             src/a.rs:7: [TODO] // FIXME later\n\
+             src/a.rs:23: [NEEDS CHANGES] x\n"
+        );
+
+        session
+            .db()
+            .add_comment("main", "src/a.rs", &flagged, "rename y")
+            .unwrap();
+        assert!(
+            todos(&session)
+                .unwrap()
+                .ends_with("src/a.rs:23: [NEEDS CHANGES] rename y\n")
+        );
+    }
+
+    #[test]
+    fn hunk_summary_truncates_long_lines() {
+        let long = format!("+{}", "x".repeat(100));
+        let summary = hunk_summary(&long);
+        assert_eq!(summary.chars().count(), SUMMARY_WIDTH);
+        assert!(summary.ends_with('…'));
+        assert_eq!(hunk_summary(" context only"), "");
+        assert_eq!(
+            hunk_summary("index 1..2 100644\nBinary files a/x.png and b/x.png differ\n"),
+            "Binary files a/x.png and b/x.png differ"
+        );
+    }
+
+    #[test]
+    fn html_escapes_content_and_includes_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let diff = "diff --git a/page.txt b/page.txt
+--- a/page.txt
++++ b/page.txt
+@@ -1 +1 @@
+-<b>old</b>
++<i>new</i>
+";
+        let mut session = ReviewSession::from_diff(dir.path(), "main", diff).unwrap();
+        let hash = session.files()[0].hunks[0].content_hash.clone();
+        session
+            .set_status("page.txt", &hash, HunkStatus::Reviewed)
+            .unwrap();
+        session
+            .db()
+            .add_comment("main", "page.txt", &hash, "looks <fine>")
+            .unwrap();
+
+        let page = html(&session, "main..HEAD").unwrap();
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("<title>Review report: main..HEAD</title>"));
+        assert!(page.contains("&lt;i&gt;new&lt;/i&gt;"));
+        assert!(!page.contains("<i>new</i>"));
+        assert!(page.contains("<span class=\"badge reviewed\">reviewed</span>"));
+        assert!(page.contains("looks &lt;fine&gt;"));
+        assert!(page.contains("1/1 hunks reviewed"));
+    }
+
+    #[test]
+    fn markdown_summarizes_files_and_outstanding_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let mut session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+        let first = session.files()[0].hunks[0].content_hash.clone();
+        let second = session.files()[0].hunks[1].content_hash.clone();
+        session
+            .set_status("src/a.rs", &second, HunkStatus::Reviewed)
+            .unwrap();
+        session
+            .db()
+            .add_comment("main", "src/a.rs", &first, "why the rename?")
+            .unwrap();
+        session
+            .db()
+            .add_comment("main", "src/a.rs", &second, "resolved")
+            .unwrap();
+
+        let out = markdown(&session, "main..HEAD").unwrap();
+        assert!(out.starts_with("## Review summary: `main..HEAD`"));
+        assert!(out.contains("**1/2 hunks reviewed** (50%)"));
+        assert!(out.contains("| `src/a.rs` | +2 -2 | 1/2 |  |"));
+        assert!(out.contains("### Outstanding comments\n\n- `src/a.rs:4` — **"));
+        assert!(out.contains("why the rename?"));
+        assert!(!out.contains("resolved"));
+    }
+
+    #[test]
+    fn commit_summary_counts_hunks_reviewers_and_resolved_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let mut session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+        session.db().set_reviewer(Some("ana".to_string()));
+        let first = session.files()[0].hunks[0].content_hash.clone();
+        let second = session.files()[0].hunks[1].content_hash.clone();
+        session
+            .set_status("src/a.rs", &second, HunkStatus::Reviewed)
+            .unwrap();
+        for (hash, body) in [(&first, "why the rename?"), (&second, "fixed")] {
+            session
+                .db()
+                .add_comment("main", "src/a.rs", hash, body)
+                .unwrap();
+        }
+        assert_eq!(
+            commit_summary(&session).unwrap(),
+            "Review: 1/2 hunks in 1 files reviewed by ana\n- src/a.rs: 1/2 hunk(s) reviewed\nComments resolved: 1\n"
+        );
+
+        session
+            .set_status("src/a.rs", &first, HunkStatus::Reviewed)
+            .unwrap();
+        assert_eq!(
+            commit_summary(&session).unwrap(),
+            "Review: 2/2 hunks in 1 files reviewed by ana\n- src/a.rs: 2 hunk(s)\nComments resolved: 2\n"
+        );
+    }
+
+    #[test]
+    fn risk_tags_flag_sensitive_paths() {
+        assert_eq!(risk_tags("Cargo.toml", 3), vec!["deps"]);
+        assert_eq!(risk_tags("config/app.yaml", 3), vec!["config"]);
+        assert_eq!(
+            risk_tags(".github/workflows/ci.yml", 3),
+            vec!["config", "ci"]
+        );
+        assert_eq!(
+            risk_tags("db/migrations/001_auth.sql", 500),
+            vec!["migration", "security", "large"]
+        );
+        assert!(risk_tags("src/main.rs", 10).is_empty());
+    }
+}
@@ -0,0 +1,236 @@
+//! JSON-RPC 2.0 interface to review state, for editor integrations.
+//!
+//! Requests and responses are newline-delimited JSON objects on stdin/stdout.
+//! Every method takes a `range` parameter (defaulting to `"HEAD"`) and re-reads
+//! the diff, so results always reflect the working tree.
+//!
+//! | Method       | Params                                   | Result                          |
+//! |--------------|------------------------------------------|---------------------------------|
+//! | `hunks`      | `range`                                  | files with their hunks and status |
+//! | `progress`   | `range`                                  | review progress counts          |
+//! | `toggle`     | `range`, `file`, `hash`                  | new status of the hunk          |
+//! | `set_status` | `range`, `file`, `hash`, `status`        | new status of the hunk          |
+//! | `gate`       | `range`                                  | whether the gate passes         |
+//! | `shutdown`   | —                                        | `null`, then the server exits   |
+
+use crate::session::ReviewSession;
+use crate::state::{status_from_str, status_to_string};
+use serde_json::{Value, json};
+use std::io::{BufRead, Write};
+
+/// JSON-RPC error codes from the specification.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Application error: the review session could not complete the request.
+const SESSION_ERROR: i64 = -32000;
+
+/// An error to report back to the client.
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<crate::session::SessionError> for RpcError {
+    fn from(err: crate::session::SessionError) -> Self {
+        Self::new(SESSION_ERROR, err.to_string())
+    }
+}
+
+/// Serve requests from `input` until EOF or a `shutdown` request.
+pub fn serve(input: impl BufRead, mut output: impl Write) -> std::io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (response, shutdown) = handle_line(&line);
+        if let Some(response) = response {
+            writeln!(output, "{}", response)?;
+            output.flush()?;
+        }
+        if shutdown {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Handle one request line, returning the response (none for notifications)
+/// and whether the server should stop.
+fn handle_line(line: &str) -> (Option<Value>, bool) {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(value) => value,
+        Err(e) => {
+            let err = RpcError::new(PARSE_ERROR, e.to_string());
+            return (Some(error_response(Value::Null, err)), false);
+        }
+    };
+
+    let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        let err = RpcError::new(INVALID_REQUEST, "missing method");
+        return (Some(error_response(id.unwrap_or(Value::Null), err)), false);
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+    let shutdown = method == "shutdown";
+
+    let result = dispatch(method, &params);
+
+    // Requests without an id are notifications and get no response
+    let response = id.map(|id| match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => error_response(id, err),
+    });
+    (response, shutdown)
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
+
+fn dispatch(method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "hunks" => Ok(hunks(&open_session(params)?)),
+        "progress" => {
+            let progress = open_session(params)?.progress()?;
+            Ok(json!({
+                "total_hunks": progress.total_hunks,
+                "reviewed": progress.reviewed,
+                "unreviewed": progress.unreviewed,
+                "stale": progress.stale,
+                "needs_changes": progress.needs_changes,
+                "skimmed": progress.skimmed,
+                "auto_approved": progress.auto_approved,
+                "files_remaining": progress.files_remaining,
+                "total_files": progress.total_files,
+            }))
+        }
+        "toggle" => {
+            let mut session = open_session(params)?;
+            let status = session.toggle(str_param(params, "file")?, str_param(params, "hash")?)?;
+            Ok(json!(status_to_string(status)))
+        }
+        "set_status" => {
+            let status = status_from_str(str_param(params, "status")?)
+                .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+            let mut session = open_session(params)?;
+            session.set_status(
+                str_param(params, "file")?,
+                str_param(params, "hash")?,
+                status,
+            )?;
+            Ok(json!(status_to_string(status)))
+        }
+        "gate" => Ok(json!(open_session(params)?.gate_passes()?)),
+        "shutdown" => Ok(Value::Null),
+        other => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("unknown method: {}", other),
+        )),
+    }
+}
+
+fn open_session(params: &Value) -> Result<ReviewSession, RpcError> {
+    let range = params
+        .get("range")
+        .and_then(Value::as_str)
+        .unwrap_or("HEAD");
+    Ok(ReviewSession::open(range)?)
+}
+
+fn str_param<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("missing string param: {}", name)))
+}
+
+/// List files and hunks with the line ranges editors need for gutter signs.
+pub(crate) fn hunks(session: &ReviewSession) -> Value {
+    let files: Vec<Value> = session
+        .files()
+        .iter()
+        .map(|file| {
+            let hunks: Vec<Value> = file
+                .hunks
+                .iter()
+                .map(|hunk| {
+                    json!({
+                        "hash": hunk.content_hash,
+                        "status": status_to_string(hunk.status),
+                        "old_start": hunk.old_start,
+                        "old_count": hunk.old_count,
+                        "new_start": hunk.new_start,
+                        "new_count": hunk.new_count,
+                    })
+                })
+                .collect();
+            json!({ "path": file.path.to_string_lossy(), "hunks": hunks })
+        })
+        .collect();
+    json!(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(input: &str) -> Vec<Value> {
+        let mut output = Vec::new();
+        serve(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn malformed_json_returns_parse_error() {
+        let responses = roundtrip("{not json\n");
+        assert_eq!(responses[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[0]["id"], Value::Null);
+    }
+
+    #[test]
+    fn unknown_method_returns_error_with_id() {
+        let responses = roundtrip(r#"{"jsonrpc":"2.0","id":7,"method":"nope"}"#);
+        assert_eq!(responses[0]["id"], 7);
+        assert_eq!(responses[0]["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn invalid_status_is_rejected_before_opening_session() {
+        let responses = roundtrip(
+            r#"{"jsonrpc":"2.0","id":1,"method":"set_status","params":{"file":"a","hash":"b","status":"done"}}"#,
+        );
+        assert_eq!(responses[0]["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn shutdown_stops_serving_and_notifications_get_no_response() {
+        let input = concat!(
+            r#"{"jsonrpc":"2.0","method":"shutdown"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"nope"}"#,
+            "\n",
+        );
+        assert!(roundtrip(input).is_empty());
+    }
+}
@@ -0,0 +1,463 @@
+//! Review logic without the TUI.
+//!
+//! [`ReviewSession`] bundles a parsed diff with its review state so editor plugins,
+//! bots, and other embedders can drive reviews through a small API:
+//!
+//! ```no_run
+//! use git_review::HunkStatus;
+//! use git_review::session::ReviewSession;
+//!
+//! # fn main() -> git_review::session::Result<()> {
+//! let mut session = ReviewSession::open("main..HEAD")?;
+//! let first = session.files()[0].clone();
+//! let hunk = &first.hunks[0];
+//! session.set_status(&first.path.to_string_lossy(), &hunk.content_hash, HunkStatus::Reviewed)?;
+//! println!("{} of {} hunks reviewed", session.progress()?.reviewed, session.progress()?.total_hunks);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::autoapprove::AutoApprover;
+use crate::config::{Config, ConfigError};
+use crate::filter::FileFilter;
+use crate::gate::check_gate;
+use crate::git::GitError;
+use crate::parser::parse_diff;
+use crate::state::{CommentRecord, HunkRecord, ReviewDb, StateError};
+use crate::{DiffFile, HunkStatus, ReviewProgress};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors that can occur while running a review session.
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error(transparent)]
+    State(#[from] StateError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("gate error: {0}")]
+    Gate(anyhow::Error),
+    #[error("no hunk {hash} in {file}")]
+    UnknownHunk { file: String, hash: String },
+    #[error("the session was opened read-only")]
+    ReadOnly,
+}
+
+pub type Result<T> = std::result::Result<T, SessionError>;
+
+/// Open (creating if needed) the review database for a repository.
+///
+/// The current `git config user.name` is recorded as the reviewer of any hunks
+/// approved through the returned handle, and the repository's `[auto_approve]`
+/// rules are applied to new hunks it syncs. With `[encryption]` enabled, the
+/// key is looked up here and any plaintext comments are encrypted.
+pub fn open_db(repo_root: &Path) -> Result<ReviewDb> {
+    let db_path = repo_root.join(".git/review-state");
+    std::fs::create_dir_all(&db_path)?;
+    let mut db = ReviewDb::open(&db_path.join("review.db"))?;
+    db.set_reviewer(crate::git::get_user_name().ok());
+    let config = Config::load(repo_root)?;
+    let auto_approver =
+        AutoApprover::new(&config.auto_approve).map_err(|e| ConfigError::Invalid(e.to_string()))?;
+    db.set_auto_approver(auto_approver);
+    db.set_cipher(config.encryption.cipher().map_err(StateError::from)?);
+    db.encrypt_plaintext()?;
+    Ok(db)
+}
+
+/// Resolve the state key for a diff range, carrying over state stored under the raw spelling.
+///
+/// See [`crate::git::normalize_diff_range`] for how keys are formed.
+///
+/// A carryover recorded by the pre-rebase hook is applied once the branch's key has changed.
+pub fn review_key(db: &mut ReviewDb, diff_range: &str) -> Result<String> {
+    let key = crate::git::normalize_diff_range(diff_range);
+    tracing::debug!(diff_range, key, "resolved review key");
+    db.adopt_base_ref(diff_range, &key)?;
+
+    if let Some((_, branch)) = key.rsplit_once("..")
+        && let Some(from) = db.carryover(branch)?
+        && from != key
+    {
+        db.adopt_base_ref(&from, &key)?;
+        db.clear_carryover(branch)?;
+    }
+    Ok(key)
+}
+
+/// The key [`review_key`] would settle on, without moving any state: the
+/// canonical key once it has state, otherwise the raw spelling or the
+/// pending carryover whose state `review_key` would adopt.
+pub fn peek_review_key(db: &ReviewDb, diff_range: &str) -> Result<String> {
+    let key = crate::git::normalize_diff_range(diff_range);
+    if db.has_state(&key)? {
+        return Ok(key);
+    }
+    if diff_range != key && db.has_state(diff_range)? {
+        return Ok(diff_range.to_string());
+    }
+    if let Some((_, branch)) = key.rsplit_once("..")
+        && let Some(from) = db.carryover(branch)?
+        && db.has_state(&from)?
+    {
+        return Ok(from);
+    }
+    Ok(key)
+}
+
+/// Commit the diff of a two-ended range is taken against: `A` for `A..B`,
+/// their merge-base for `A...B`. None for other ranges, or if it cannot be resolved.
+pub fn base_commit(diff_range: &str) -> Option<String> {
+    if !diff_range.contains("..") {
+        return None;
+    }
+    crate::git::pre_image_rev(diff_range)
+        .and_then(|rev| crate::git::resolve_ref(&rev))
+        .ok()
+}
+
+/// The base of a review moving since it was last opened, and the hunks that
+/// became pending meanwhile.
+///
+/// A diff against a branch changes when the branch advances, even if the
+/// reviewed branch did not; this explains the resulting stale hunks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BaseMove {
+    pub from: String,
+    pub to: String,
+    /// Hunks that became unreviewed or stale since the review was last opened.
+    pub hunks: usize,
+    /// Files of those hunks, sorted.
+    pub files: Vec<String>,
+}
+
+impl BaseMove {
+    /// One line for the user: "Base moved from 1a2b3c4 to 5d6e7f8; 3 hunk(s) in 2 file(s) affected".
+    pub fn describe(&self) -> String {
+        format!(
+            "Base moved from {:.7} to {:.7}; {} hunk(s) in {} file(s) affected",
+            self.from,
+            self.to,
+            self.hunks,
+            self.files.len()
+        )
+    }
+}
+
+/// Record the commit `diff_range` is taken against for `base_ref`, whose
+/// diff was just synced. If it differs from the one recorded when the review
+/// was last opened, report the hunks that became pending since then.
+pub fn track_base(
+    db: &mut ReviewDb,
+    base_ref: &str,
+    diff_range: &str,
+) -> std::result::Result<Option<BaseMove>, StateError> {
+    let Some(sha) = base_commit(diff_range) else {
+        return Ok(None);
+    };
+    let Some((from, recorded_at)) = db.record_base_commit(base_ref, &sha)? else {
+        return Ok(None);
+    };
+    // Rows written in the same second as the record belong to that sync
+    let changed = db.changed_since(base_ref, recorded_at + 1)?;
+    if changed.is_empty() {
+        return Ok(None);
+    }
+    let files: BTreeSet<String> = changed.iter().map(|(file, _)| file.clone()).collect();
+    Ok(Some(BaseMove {
+        from,
+        to: sha,
+        hunks: changed.len(),
+        files: files.into_iter().collect(),
+    }))
+}
+
+/// A diff under review together with its persisted review state.
+///
+/// Opening a session syncs the diff into the database (marking vanished hunks
+/// stale) and fills in each hunk's current status. A session opened with
+/// [`ReviewSession::open_read_only`] compares the diff with the database instead.
+pub struct ReviewSession {
+    repo_root: PathBuf,
+    base_ref: String,
+    files: Vec<DiffFile>,
+    db: ReviewDb,
+    config: Config,
+    /// Files left out of the review (`--only` / `--exclude`).
+    filter: FileFilter,
+    /// For read-only sessions, the hunk records a sync would leave.
+    records: Option<Vec<HunkRecord>>,
+}
+
+impl ReviewSession {
+    /// Open a session for a diff range of the repository containing the current directory.
+    pub fn open(diff_range: &str) -> Result<Self> {
+        let repo_root = crate::git::find_repo_root()?;
+        let diff = crate::git::get_diff(diff_range)?;
+        let key = review_key(&mut open_db(&repo_root)?, diff_range)?;
+        Self::from_diff(&repo_root, &key, &diff)
+    }
+
+    /// Open a session for a diff range without writing review state: no key is
+    /// adopted, the diff is not synced (see [`ReviewDb::synced_hunks`]), and
+    /// statuses can't be changed. For views such as the web dashboard that
+    /// should not mark anything stale or approved by looking.
+    pub fn open_read_only(diff_range: &str) -> Result<Self> {
+        let repo_root = crate::git::find_repo_root()?;
+        let diff = crate::git::get_diff(diff_range)?;
+        let db = open_db(&repo_root)?;
+        let base_ref = peek_review_key(&db, diff_range)?;
+        let files = parse_diff(&diff);
+        let records = db.synced_hunks(&base_ref, &files)?;
+        let mut session = Self {
+            config: Config::load(&repo_root)?,
+            repo_root,
+            base_ref,
+            files,
+            db,
+            filter: FileFilter::default(),
+            records: Some(records),
+        };
+        session.load_statuses()?;
+        Ok(session)
+    }
+
+    /// Open a session for diff text produced elsewhere, keyed by `base_ref`.
+    pub fn from_diff(repo_root: &Path, base_ref: &str, diff: &str) -> Result<Self> {
+        let db = open_db(repo_root)?;
+        let config = Config::load(repo_root)?;
+        let mut session = Self {
+            repo_root: repo_root.to_path_buf(),
+            base_ref: base_ref.to_string(),
+            files: parse_diff(diff),
+            db,
+            config,
+            filter: FileFilter::default(),
+            records: None,
+        };
+        let hunks: usize = session.files.iter().map(|f| f.hunks.len()).sum();
+        tracing::info!(
+            base_ref,
+            diff_bytes = diff.len(),
+            files = session.files.len(),
+            hunks,
+            "opened review session"
+        );
+        if diff.is_empty() {
+            tracing::info!(base_ref, "git diff printed nothing for this range");
+        } else if session.files.is_empty() {
+            tracing::warn!(
+                base_ref,
+                diff_bytes = diff.len(),
+                "the diff has no files the parser recognised"
+            );
+        }
+        session
+            .db
+            .sync_with_diff(&session.base_ref, &session.files)?;
+        if session.config.moved.auto_approve {
+            crate::moved::approve_moved(&mut session.db, &session.base_ref, &session.files)?;
+        }
+        session.load_statuses()?;
+        Ok(session)
+    }
+
+    /// Root of the repository this session belongs to.
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    /// Key the review state is stored under.
+    pub fn base_ref(&self) -> &str {
+        &self.base_ref
+    }
+
+    /// Restrict the session to the files `filter` keeps. Review state of the
+    /// other files is left as it is.
+    pub fn set_filter(&mut self, filter: FileFilter) {
+        self.files.retain(|file| filter.matches(&file.path));
+        self.filter = filter;
+    }
+
+    /// Files in the diff, with each hunk's status filled in.
+    pub fn files(&self) -> &[DiffFile] {
+        &self.files
+    }
+
+    /// `(file index, hunk index)` of every unreviewed hunk, in diff order.
+    pub fn unreviewed_hunks(&self) -> Vec<(usize, usize)> {
+        self.files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_idx, file)| {
+                file.hunks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, hunk)| hunk.status == HunkStatus::Unreviewed)
+                    .map(move |(hunk_idx, _)| (file_idx, hunk_idx))
+            })
+            .collect()
+    }
+
+    /// Repository configuration loaded when the session was opened.
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Underlying review database, for operations the session does not wrap.
+    pub fn db(&mut self) -> &mut ReviewDb {
+        &mut self.db
+    }
+
+    /// Review progress for this session's base ref.
+    pub fn progress(&self) -> Result<ReviewProgress> {
+        if let Some(records) = &self.records {
+            Ok(self.filter.progress(records))
+        } else if self.filter.is_empty() {
+            Ok(self.db.progress(&self.base_ref)?)
+        } else {
+            Ok(self.filter.progress(&self.db.hunks(&self.base_ref)?))
+        }
+    }
+
+    /// Every stored hunk for this base ref, including stale ones no longer in the diff.
+    pub fn hunk_records(&self) -> Result<Vec<HunkRecord>> {
+        let mut records = match &self.records {
+            Some(records) => records.clone(),
+            None => self.db.hunks(&self.base_ref)?,
+        };
+        records.retain(|record| self.filter.matches(Path::new(&record.file_path)));
+        Ok(records)
+    }
+
+    /// Review comments for this base ref, oldest first.
+    pub fn comments(&self) -> Result<Vec<CommentRecord>> {
+        Ok(self.db.comments(&self.base_ref)?)
+    }
+
+    /// Set the status of a hunk in the diff.
+    pub fn set_status(
+        &mut self,
+        file_path: &str,
+        content_hash: &str,
+        status: HunkStatus,
+    ) -> Result<()> {
+        if self.records.is_some() {
+            return Err(SessionError::ReadOnly);
+        }
+        let hunk = self
+            .files
+            .iter_mut()
+            .filter(|f| f.path.to_string_lossy() == file_path)
+            .flat_map(|f| f.hunks.iter_mut())
+            .find(|h| h.content_hash == content_hash)
+            .ok_or_else(|| SessionError::UnknownHunk {
+                file: file_path.to_string(),
+                hash: content_hash.to_string(),
+            })?;
+
+        self.db
+            .set_status(&self.base_ref, file_path, content_hash, status)?;
+        hunk.status = status;
+        Ok(())
+    }
+
+    /// Flip a hunk between reviewed and unreviewed, returning its new status.
+    pub fn toggle(&mut self, file_path: &str, content_hash: &str) -> Result<HunkStatus> {
+        let current = self
+            .db
+            .get_status(&self.base_ref, file_path, content_hash)?;
+        let next = match current {
+            HunkStatus::Reviewed => HunkStatus::Unreviewed,
+            HunkStatus::Unreviewed
+            | HunkStatus::Stale
+            | HunkStatus::NeedsChanges
+            | HunkStatus::Skimmed => HunkStatus::Reviewed,
+        };
+        self.set_status(file_path, content_hash, next)?;
+        Ok(next)
+    }
+
+    /// Whether the configured gate policy currently passes.
+    pub fn gate_passes(&self) -> Result<bool> {
+        check_gate(&self.db, &self.base_ref, &self.config.gate).map_err(SessionError::Gate)
+    }
+
+    /// Copy persisted statuses onto the parsed hunks.
+    fn load_statuses(&mut self) -> Result<()> {
+        if let Some(records) = &self.records {
+            let statuses: HashMap<(&str, &str), HunkStatus> = records
+                .iter()
+                .map(|r| ((r.file_path.as_str(), r.content_hash.as_str()), r.status))
+                .collect();
+            for file in &mut self.files {
+                let path = file.path.to_string_lossy().to_string();
+                for hunk in &mut file.hunks {
+                    hunk.status = statuses
+                        .get(&(path.as_str(), hunk.content_hash.as_str()))
+                        .copied()
+                        .unwrap_or(HunkStatus::Unreviewed);
+                }
+            }
+            return Ok(());
+        }
+        for file in &mut self.files {
+            let path = file.path.to_string_lossy().to_string();
+            for hunk in &mut file.hunks {
+                hunk.status = self
+                    .db
+                    .get_status(&self.base_ref, &path, &hunk.content_hash)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-old
++new
+";
+
+    #[test]
+    fn session_persists_status_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let mut session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+        let hash = session.files()[0].hunks[0].content_hash.clone();
+        assert_eq!(
+            session.toggle("a.txt", &hash).unwrap(),
+            HunkStatus::Reviewed
+        );
+        assert_eq!(session.files()[0].hunks[0].status, HunkStatus::Reviewed);
+        assert!(session.gate_passes().unwrap());
+
+        let reopened = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+        assert_eq!(reopened.files()[0].hunks[0].status, HunkStatus::Reviewed);
+        assert_eq!(reopened.progress().unwrap().reviewed, 1);
+    }
+
+    #[test]
+    fn session_rejects_unknown_hunk() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+
+        let mut session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+        let err = session
+            .set_status("a.txt", "missing", HunkStatus::Reviewed)
+            .unwrap_err();
+        assert!(matches!(err, SessionError::UnknownHunk { .. }));
+    }
+}
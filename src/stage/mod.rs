@@ -0,0 +1,89 @@
+//! Staging the reviewed hunks of the working tree (`stage`, `:stage` in the TUI).
+//!
+//! Review approval then decides what gets committed, like `git add -p` driven
+//! by review state. The unstaged diff (`git diff`) is matched against the
+//! staged-changes review (`HEAD`) by file and content hash, and a patch of
+//! the hunks marked reviewed is applied with `git apply --cached`. Hunks
+//! already partly staged no longer match and are left alone.
+
+use crate::HunkStatus;
+use crate::git::{self, GitError};
+use crate::patch::select_hunks;
+use crate::state::{ReviewDb, StateError};
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Review state key of the working tree's changes.
+const WORKING_TREE_KEY: &str = "HEAD";
+
+/// Errors that can occur while staging reviewed hunks.
+#[derive(Debug, Error)]
+pub enum StageError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error(transparent)]
+    State(#[from] StateError),
+}
+
+/// The hunks of `diff` in `reviewed` (file path and content hash), as a
+/// patch, and how many there are.
+pub fn reviewed_patch(diff: &str, reviewed: &HashSet<(String, String)>) -> (String, usize) {
+    select_hunks(diff, |path, hunk| {
+        reviewed.contains(&(
+            path.to_string_lossy().to_string(),
+            hunk.content_hash.clone(),
+        ))
+    })
+}
+
+/// Stage the working tree's hunks that are marked reviewed, returning how many.
+pub fn stage_reviewed(db: &ReviewDb) -> Result<usize, StageError> {
+    let reviewed: HashSet<(String, String)> = db
+        .hunks(WORKING_TREE_KEY)?
+        .into_iter()
+        .filter(|hunk| hunk.status == HunkStatus::Reviewed)
+        .map(|hunk| (hunk.file_path, hunk.content_hash))
+        .collect();
+    if reviewed.is_empty() {
+        return Ok(0);
+    }
+
+    let (patch, count) = reviewed_patch(&git::get_unstaged_diff()?, &reviewed);
+    if count > 0 {
+        git::apply_to_index(&patch)?;
+        git::clear_diff_cache();
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_diff;
+
+    #[test]
+    fn reviewed_hunks_are_matched_by_file_and_content() {
+        let diff = "\
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-x
++y
+@@ -9 +9 @@
+-x
++z
+";
+        let files = parse_diff(diff);
+        let hash = |hunk: usize| files[0].hunks[hunk].content_hash.clone();
+
+        let reviewed = HashSet::from([("a.txt".to_string(), hash(1))]);
+        let (patch, count) = reviewed_patch(diff, &reviewed);
+        assert_eq!(count, 1);
+        assert!(patch.contains("+z") && !patch.contains("+y"));
+
+        // The same content in another file is a different hunk
+        let elsewhere = HashSet::from([("b.txt".to_string(), hash(1))]);
+        assert_eq!(reviewed_patch(diff, &elsewhere), (String::new(), 0));
+    }
+}
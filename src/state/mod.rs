@@ -1,5 +1,9 @@
+use crate::ai::HunkSummary;
+use crate::autoapprove::{self, AutoApprover, RULE_INHERITED};
+use crate::encryption::{self, Cipher, EncryptionError};
 use crate::{DiffFile, HunkStatus, ReviewProgress};
 use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use thiserror::Error;
 
@@ -10,16 +14,189 @@ pub enum StateError {
     Db(#[from] rusqlite::Error),
     #[error("invalid hunk status: {0}")]
     InvalidStatus(String),
+    #[error(transparent)]
+    Encryption(#[from] EncryptionError),
 }
 
 pub type Result<T> = std::result::Result<T, StateError>;
 
+/// A hunk row as stored in the review database.
+#[derive(Debug, Clone)]
+pub struct HunkRecord {
+    pub file_path: String,
+    pub content_hash: String,
+    pub status: HunkStatus,
+    pub reviewed_at: Option<String>,
+    pub reviewed_by: Option<String>,
+    /// Auto-approval rule that marked the hunk reviewed, if no person did.
+    pub auto_rule: Option<String>,
+}
+
+/// When and by whom a hunk was marked reviewed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Approval {
+    /// Seconds since the approval.
+    pub age: u64,
+    pub reviewed_by: Option<String>,
+    /// Auto-approval rule that marked the hunk reviewed, if no person did.
+    pub auto_rule: Option<String>,
+}
+
+/// A hunk row exactly as stored, for integrity checks.
+///
+/// Unlike [`HunkRecord`], the status is not parsed, so rows with an invalid
+/// status can still be listed and repaired.
+#[derive(Debug, Clone)]
+pub struct StoredHunk {
+    pub id: i64,
+    pub file_path: String,
+    pub content_hash: String,
+    pub status: String,
+    pub reviewed_at: Option<String>,
+    pub reviewed_by: Option<String>,
+}
+
+/// A review comment attached to a hunk.
+#[derive(Debug, Clone)]
+pub struct CommentRecord {
+    pub file_path: String,
+    pub content_hash: String,
+    pub author: Option<String>,
+    pub body: String,
+    pub created_at: String,
+}
+
+/// A named copy of a base ref's hunk rows.
+#[derive(Debug, Clone)]
+pub struct SnapshotRecord {
+    pub name: String,
+    pub base_ref: String,
+    pub created_at: String,
+    pub hunks: usize,
+}
+
+/// Review state of a merged branch, moved out of the live tables.
+#[derive(Debug, Clone)]
+pub struct ArchiveRecord {
+    pub base_ref: String,
+    /// Commit that merged the branch, when it could be found.
+    pub merge_sha: Option<String>,
+    pub archived_at: String,
+    pub hunks: usize,
+    pub reviewed: usize,
+    /// People who reviewed its hunks, most recent first.
+    pub reviewers: Vec<String>,
+}
+
+/// How many of the most recent bulk approvals can be undone.
+const UNDO_DEPTH: i64 = 20;
+
+/// A bulk approval whose previous hunk states are remembered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkApproval {
+    pub batch: i64,
+    pub base_ref: String,
+    /// The file the approval was limited to, if any.
+    pub file_path: Option<String>,
+    /// Hunks it approved.
+    pub hunks: usize,
+    /// Local time of the approval.
+    pub approved_at: String,
+}
+
+/// What a review event records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    /// A person set a hunk's status.
+    Status(HunkStatus),
+    /// Every pending hunk of a range or file was approved at once (`approve`).
+    Approved,
+    /// A bulk approval was undone.
+    ApprovalUndone,
+    /// Reviews were copied over from another range (`carry`); the detail is its key.
+    Carried,
+    GatePassed,
+    GateFailed,
+    /// The branch was merged and its review state archived.
+    Merged,
+}
+
+impl EventKind {
+    /// Name stored in the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Status(status) => status_to_string(status),
+            Self::Approved => "approved",
+            Self::ApprovalUndone => "approval_undone",
+            Self::Carried => "carried",
+            Self::GatePassed => "gate_passed",
+            Self::GateFailed => "gate_failed",
+            Self::Merged => "merged",
+        }
+    }
+
+    fn parse(kind: &str) -> Result<Self> {
+        Ok(match kind {
+            "approved" => Self::Approved,
+            "approval_undone" => Self::ApprovalUndone,
+            "carried" => Self::Carried,
+            "gate_passed" => Self::GatePassed,
+            "gate_failed" => Self::GateFailed,
+            "merged" => Self::Merged,
+            status => Self::Status(status_from_str(status)?),
+        })
+    }
+}
+
+/// An entry of the review event log, with its time in local time.
+#[derive(Debug, Clone)]
+pub struct EventRecord {
+    /// Branch the event belongs to (see [`namespace`]).
+    pub branch: String,
+    pub base_ref: String,
+    pub kind: EventKind,
+    pub file_path: Option<String>,
+    /// Hunks the event covered.
+    pub count: usize,
+    /// Merge commit of a merge, or a gate check's summary.
+    pub detail: Option<String>,
+    pub actor: Option<String>,
+    pub at: String,
+}
+
+/// Hunk counts per status for a base ref (`?1`).
+const STATUS_COUNTS_SQL: &str =
+    "SELECT status, COUNT(*) FROM hunks WHERE base_ref = ?1 GROUP BY status";
+/// Number of files of a base ref with hunks that are not reviewed.
+const FILES_REMAINING_SQL: &str =
+    "SELECT COUNT(DISTINCT file_path) FROM hunks WHERE base_ref = ?1 AND status != 'reviewed'";
+/// Number of files of a base ref.
+const TOTAL_FILES_SQL: &str = "SELECT COUNT(DISTINCT file_path) FROM hunks WHERE base_ref = ?1";
+/// Number of auto-approved hunks of a base ref.
+const AUTO_APPROVED_SQL: &str = "SELECT COUNT(*) FROM hunks
+     WHERE base_ref = ?1 AND status = 'reviewed' AND auto_rule IS NOT NULL";
+/// Status of one hunk (`?1` base ref, `?2` file, `?3` hash).
+const HUNK_STATUS_SQL: &str =
+    "SELECT status FROM hunks WHERE base_ref = ?1 AND file_path = ?2 AND content_hash = ?3";
+/// Refresh a synced hunk's patch id (`?4`) when it differs.
+const SYNC_PATCH_ID_SQL: &str = "UPDATE hunks SET patch_id = ?4
+     WHERE base_ref = ?1 AND file_path = ?2 AND content_hash = ?3 AND patch_id IS NOT ?4";
+/// Hunks of a base ref that are not stale, which sync checks against the diff.
+const LIVE_HUNKS_SQL: &str =
+    "SELECT file_path, content_hash FROM hunks WHERE base_ref = ?1 AND status != 'stale'";
+/// Whether a person approved a change (`?1` patch id) outside base ref `?2`.
+const REVIEWED_ELSEWHERE_SQL: &str = "SELECT EXISTS (SELECT 1 FROM hunks
+     WHERE patch_id = ?1 AND base_ref != ?2 AND status = 'reviewed' AND auto_rule IS NULL)";
+
 /// SQLite-backed review state database.
 ///
 /// Stores review status per hunk (keyed by SHA-256 content hash).
 /// Detects stale hunks when diff content changes.
 pub struct ReviewDb {
     conn: Connection,
+    reviewer: Option<String>,
+    auto_approver: AutoApprover,
+    cipher: Option<Cipher>,
 }
 
 impl ReviewDb {
@@ -27,7 +204,10 @@ impl ReviewDb {
     ///
     /// Creates the necessary tables if they don't exist.
     pub fn open(path: &Path) -> Result<Self> {
+        tracing::debug!(path = %path.display(), "opening review database");
         let conn = Connection::open(path)?;
+        // The TUI, hooks, and the web server's request threads share the file
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
         conn.execute(
             "CREATE TABLE IF NOT EXISTS hunks (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -41,7 +221,254 @@ impl ReviewDb {
             )",
             [],
         )?;
-        Ok(Self { conn })
+        add_column_if_missing(&conn, "hunks", "reviewed_by", "TEXT")?;
+        add_column_if_missing(&conn, "hunks", "auto_rule", "TEXT")?;
+        // When the status last changed; rows older than the column use created_at
+        add_column_if_missing(&conn, "hunks", "changed_at", "TEXT")?;
+        // See `autoapprove::patch_id`; filled in as hunks are synced
+        add_column_if_missing(&conn, "hunks", "patch_id", "TEXT")?;
+        // See `namespace`; filled in lazily by `fill_namespaces`, and cleared
+        // when a row's base_ref changes
+        add_column_if_missing(&conn, "hunks", "namespace", "TEXT")?;
+        // The UNIQUE constraint's index covers exact lookups and per-file
+        // scans; these serve the per-status counts that progress and sync run,
+        // and the lookups of the same change in other ranges. Older databases
+        // also have a (base_ref, file_path) index, a prefix of the UNIQUE one.
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_hunks_base_ref_status ON hunks (base_ref, status);
+             DROP INDEX IF EXISTS idx_hunks_base_ref_file;
+             CREATE INDEX IF NOT EXISTS idx_hunks_patch_id ON hunks (patch_id);
+             CREATE INDEX IF NOT EXISTS idx_hunks_namespace ON hunks (namespace);",
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS assignments (
+                base_ref TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                assignee TEXT NOT NULL,
+                UNIQUE(base_ref, file_path)
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS comments (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                base_ref TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                author TEXT,
+                body TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS snapshots (
+                name TEXT PRIMARY KEY,
+                base_ref TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS snapshot_hunks (
+                snapshot TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                status TEXT NOT NULL,
+                reviewed_at TEXT,
+                reviewed_by TEXT
+            )",
+            [],
+        )?;
+        // Copied with the hunk so a restored auto-approval isn't taken for a person's
+        add_column_if_missing(&conn, "snapshot_hunks", "auto_rule", "TEXT")?;
+        add_column_if_missing(&conn, "snapshot_hunks", "changed_at", "TEXT")?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS ai_summaries (
+                content_hash TEXT PRIMARY KEY,
+                summary TEXT NOT NULL,
+                risk TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS carryovers (
+                branch TEXT PRIMARY KEY,
+                from_base_ref TEXT NOT NULL
+            )",
+            [],
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS merge_queue (
+                branch TEXT PRIMARY KEY,
+                base TEXT NOT NULL,
+                queued_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+        // Commit each base ref's diff was taken against when last opened for review
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS base_commits (
+                base_ref TEXT PRIMARY KEY,
+                sha TEXT NOT NULL,
+                recorded_at INTEGER NOT NULL
+            )",
+            [],
+        )?;
+        // Branch tips as of their last review, to flag force-pushes in the dashboard
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS reviewed_tips (
+                branch TEXT PRIMARY KEY,
+                tip_sha TEXT NOT NULL,
+                reviewed_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            [],
+        )?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS archive (
+                base_ref TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                status TEXT NOT NULL,
+                reviewed_at TEXT,
+                reviewed_by TEXT,
+                auto_rule TEXT,
+                merge_sha TEXT,
+                archived_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_archive_base_ref ON archive (base_ref);",
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS bookmarks (
+                base_ref TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                UNIQUE(base_ref, file_path, content_hash)
+            )",
+            [],
+        )?;
+        // Prior state of the hunks each recent bulk approval changed, for undo
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS bulk_approvals (
+                batch INTEGER NOT NULL,
+                base_ref TEXT NOT NULL,
+                scope TEXT,
+                file_path TEXT NOT NULL,
+                content_hash TEXT NOT NULL,
+                status TEXT NOT NULL,
+                reviewed_at TEXT,
+                reviewed_by TEXT,
+                auto_rule TEXT,
+                changed_at TEXT,
+                approved_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_bulk_approvals_batch ON bulk_approvals (batch);",
+        )?;
+        // Append-only log for `history`; purging or archiving a range keeps it
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                branch TEXT NOT NULL,
+                base_ref TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                file_path TEXT,
+                count INTEGER NOT NULL DEFAULT 1,
+                detail TEXT,
+                actor TEXT,
+                at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_events_branch ON events (branch);",
+        )?;
+        Ok(Self {
+            conn,
+            reviewer: None,
+            auto_approver: AutoApprover::default(),
+            cipher: None,
+        })
+    }
+
+    /// Set the identity recorded as `reviewed_by` when hunks are marked reviewed.
+    pub fn set_reviewer(&mut self, reviewer: Option<String>) {
+        self.reviewer = reviewer;
+    }
+
+    /// Identity recorded as `reviewed_by`, if known.
+    pub fn reviewer(&self) -> Option<&str> {
+        self.reviewer.as_deref()
+    }
+
+    /// Set the rules [`ReviewDb::sync_with_diff`] uses to approve new hunks.
+    pub fn set_auto_approver(&mut self, auto_approver: AutoApprover) {
+        self.auto_approver = auto_approver;
+    }
+
+    /// Set the key comment bodies and AI summaries are encrypted with (see [`crate::encryption`]).
+    pub fn set_cipher(&mut self, cipher: Option<Cipher>) {
+        self.cipher = cipher;
+    }
+
+    /// Encrypt free text before it is stored, if a key is set.
+    fn seal(&self, text: &str) -> String {
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(text),
+            None => text.to_string(),
+        }
+    }
+
+    /// Decrypt stored free text. Plaintext rows pass through unchanged.
+    fn unseal(&self, stored: String) -> Result<String> {
+        match &self.cipher {
+            Some(cipher) => Ok(cipher.decrypt(&stored)?),
+            None if encryption::is_encrypted(&stored) => Err(EncryptionError::Locked.into()),
+            None => Ok(stored),
+        }
+    }
+
+    /// Encrypt comment bodies and AI summaries written before a key was set.
+    ///
+    /// Does nothing without a key. Returns the number of rows rewritten.
+    pub fn encrypt_plaintext(&mut self) -> Result<usize> {
+        let Some(cipher) = self.cipher.clone() else {
+            return Ok(0);
+        };
+        let pattern = format!("{}%", encryption::ENCRYPTED_PREFIX);
+        let tx = self.conn.transaction()?;
+        let mut rewritten = 0;
+
+        let comments: Vec<(i64, String)> = tx
+            .prepare("SELECT id, body FROM comments WHERE body NOT LIKE ?1")?
+            .query_map(params![pattern], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<_, _>>()?;
+        for (id, body) in comments {
+            rewritten += tx.execute(
+                "UPDATE comments SET body = ?1 WHERE id = ?2",
+                params![cipher.encrypt(&body), id],
+            )?;
+        }
+
+        let summaries: Vec<(String, String, Option<String>)> = tx
+            .prepare(
+                "SELECT content_hash, summary, risk FROM ai_summaries WHERE summary NOT LIKE ?1",
+            )?
+            .query_map(params![pattern], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .collect::<std::result::Result<_, _>>()?;
+        for (hash, summary, risk) in summaries {
+            rewritten += tx.execute(
+                "UPDATE ai_summaries SET summary = ?1, risk = ?2 WHERE content_hash = ?3",
+                params![
+                    cipher.encrypt(&summary),
+                    risk.map(|risk| cipher.encrypt(&risk)),
+                    hash
+                ],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(rewritten)
     }
 
     /// Get the review status for a specific hunk.
@@ -53,46 +480,86 @@ impl ReviewDb {
         file_path: &str,
         content_hash: &str,
     ) -> Result<HunkStatus> {
-        let mut stmt = self.conn.prepare(
-            "SELECT status FROM hunks WHERE base_ref = ?1 AND file_path = ?2 AND content_hash = ?3",
-        )?;
+        let mut stmt = self.conn.prepare_cached(HUNK_STATUS_SQL)?;
 
         let status: Option<String> = stmt
             .query_row(params![base_ref, file_path, content_hash], |row| row.get(0))
             .optional()?;
 
         match status.as_deref() {
-            Some("reviewed") => Ok(HunkStatus::Reviewed),
-            Some("stale") => Ok(HunkStatus::Stale),
-            Some("unreviewed") | None => Ok(HunkStatus::Unreviewed),
-            Some(other) => Err(StateError::InvalidStatus(other.to_owned())),
+            Some(s) => status_from_str(s),
+            None => Ok(HunkStatus::Unreviewed),
         }
     }
 
-    /// Set the review status for a specific hunk.
+    /// Set the review status for a specific hunk on behalf of the current
+    /// reviewer, who the change is credited to in the event log.
+    ///
+    /// Changes nobody made (stale marking, auto-approval) go through
+    /// [`ReviewDb::record_status`] or [`ReviewDb::auto_approve`] instead.
     pub fn set_status(
         &mut self,
         base_ref: &str,
         file_path: &str,
         content_hash: &str,
         status: HunkStatus,
+    ) -> Result<()> {
+        tracing::debug!(
+            base_ref,
+            file = file_path,
+            hash = content_hash,
+            status = status_to_string(status),
+            "set hunk status"
+        );
+        let reviewer = self.reviewer.clone();
+        self.record_status(
+            base_ref,
+            file_path,
+            content_hash,
+            status,
+            reviewer.as_deref(),
+        )?;
+        if status == HunkStatus::Reviewed {
+            self.inherit_approval(base_ref, file_path, content_hash)?;
+        }
+        self.log_event(
+            namespace(base_ref),
+            base_ref,
+            EventKind::Status(status),
+            Some(file_path),
+            1,
+            None,
+        )
+    }
+
+    /// Like [`ReviewDb::set_status`], crediting a review to `reviewed_by`
+    /// instead of the current reviewer (e.g. when importing someone else's review).
+    pub fn record_status(
+        &mut self,
+        base_ref: &str,
+        file_path: &str,
+        content_hash: &str,
+        status: HunkStatus,
+        reviewed_by: Option<&str>,
     ) -> Result<()> {
         let status_str = status_to_string(status);
 
         if status == HunkStatus::Reviewed {
             self.conn.execute(
-                "INSERT INTO hunks (base_ref, file_path, content_hash, status, reviewed_at)
-                 VALUES (?1, ?2, ?3, ?4, datetime('now'))
+                "INSERT INTO hunks (base_ref, file_path, content_hash, status, reviewed_at, reviewed_by)
+                 VALUES (?1, ?2, ?3, ?4, datetime('now'), ?5)
                  ON CONFLICT(base_ref, file_path, content_hash)
-                 DO UPDATE SET status = ?4, reviewed_at = datetime('now')",
-                params![base_ref, file_path, content_hash, status_str],
+                 DO UPDATE SET status = ?4, reviewed_at = datetime('now'), reviewed_by = ?5, auto_rule = NULL,
+                     changed_at = datetime('now')",
+                params![base_ref, file_path, content_hash, status_str, reviewed_by],
             )?;
         } else {
             self.conn.execute(
                 "INSERT INTO hunks (base_ref, file_path, content_hash, status, reviewed_at)
                  VALUES (?1, ?2, ?3, ?4, NULL)
                  ON CONFLICT(base_ref, file_path, content_hash)
-                 DO UPDATE SET status = ?4, reviewed_at = NULL",
+                 DO UPDATE SET status = ?4, reviewed_at = NULL, reviewed_by = NULL, auto_rule = NULL,
+                     changed_at = datetime('now')",
                 params![base_ref, file_path, content_hash, status_str],
             )?;
         }
@@ -100,60 +567,223 @@ impl ReviewDb {
         Ok(())
     }
 
+    /// Mark a hunk reviewed on behalf of an auto-approval rule rather than a person.
+    pub fn auto_approve(
+        &mut self,
+        base_ref: &str,
+        file_path: &str,
+        content_hash: &str,
+        rule: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO hunks (base_ref, file_path, content_hash, status, reviewed_at, auto_rule)
+             VALUES (?1, ?2, ?3, 'reviewed', datetime('now'), ?4)
+             ON CONFLICT(base_ref, file_path, content_hash)
+             DO UPDATE SET status = 'reviewed', reviewed_at = datetime('now'), reviewed_by = NULL, auto_rule = ?4,
+                 changed_at = datetime('now')",
+            params![base_ref, file_path, content_hash, rule],
+        )?;
+        Ok(())
+    }
+
+    /// Approve, as [`RULE_INHERITED`], the unreviewed hunks of other ranges
+    /// that make the same change as a hunk a person just approved, when the
+    /// auto-approver's `inherit` rule is on. Returns how many were approved.
+    fn inherit_approval(
+        &mut self,
+        base_ref: &str,
+        file_path: &str,
+        content_hash: &str,
+    ) -> Result<usize> {
+        if !self.auto_approver.inherits() {
+            return Ok(0);
+        }
+        let count = self.conn.execute(
+            "UPDATE hunks SET status = 'reviewed', reviewed_at = datetime('now'), reviewed_by = NULL,
+                 auto_rule = ?4, changed_at = datetime('now')
+             WHERE status = 'unreviewed' AND base_ref != ?1
+                 AND patch_id = (SELECT patch_id FROM hunks
+                     WHERE base_ref = ?1 AND file_path = ?2 AND content_hash = ?3)",
+            params![base_ref, file_path, content_hash, RULE_INHERITED],
+        )?;
+        if count > 0 {
+            tracing::debug!(
+                base_ref,
+                file = file_path,
+                count,
+                "approval inherited by other ranges"
+            );
+        }
+        Ok(count)
+    }
+
+    /// Whether a person approved a hunk with `patch_id` in a range other than `base_ref`.
+    fn reviewed_elsewhere(&self, base_ref: &str, patch_id: &str) -> Result<bool> {
+        Ok(self
+            .conn
+            .prepare_cached(REVIEWED_ELSEWHERE_SQL)?
+            .query_row(params![patch_id, base_ref], |row| row.get(0))?)
+    }
+
     /// Synchronize the database with the current diff output.
     ///
-    /// - New hunks (not in DB) are marked as `Unreviewed`
+    /// - New hunks (not in DB) are marked as `Unreviewed`, or `Reviewed` when an
+    ///   auto-approval rule (see [`ReviewDb::set_auto_approver`]) matches them,
+    ///   or when `inherit` is on and a person approved the same change in
+    ///   another range
     /// - Hunks that no longer exist in the diff are marked as `Stale`
     /// - Hunks with `Reviewed` status and matching hash are preserved
+    ///
+    /// Runs in a single transaction so large diffs don't pay for one commit per hunk.
     pub fn sync_with_diff(&mut self, base_ref: &str, files: &[DiffFile]) -> Result<()> {
+        self.conn.execute_batch("BEGIN")?;
+        match self.sync_hunks(base_ref, files) {
+            Ok(()) => {
+                self.conn.execute_batch("COMMIT")?;
+                Ok(())
+            }
+            Err(e) => {
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(e)
+            }
+        }
+    }
+
+    /// Body of [`ReviewDb::sync_with_diff`], run inside its transaction.
+    fn sync_hunks(&mut self, base_ref: &str, files: &[DiffFile]) -> Result<()> {
         // Collect all current hunk hashes from the diff
         let mut current_hunks = std::collections::HashSet::new();
+        let (mut added, mut auto_approved, mut marked_stale) = (0, 0, 0);
         for file in files {
             let file_path = file.path.to_string_lossy();
             for hunk in &file.hunks {
                 current_hunks.insert((file_path.to_string(), hunk.content_hash.clone()));
+                let patch_id = autoapprove::patch_id(&file_path, hunk);
 
                 // Insert new hunks as Unreviewed (or keep existing status)
                 let existing_status = self.get_status(base_ref, &file_path, &hunk.content_hash)?;
                 if existing_status == HunkStatus::Unreviewed {
                     // Only insert if it doesn't exist yet
-                    self.conn.execute(
-                        "INSERT OR IGNORE INTO hunks (base_ref, file_path, content_hash, status)
-                         VALUES (?1, ?2, ?3, 'unreviewed')",
-                        params![base_ref, file_path, hunk.content_hash],
+                    let inserted = self.conn.execute(
+                        "INSERT OR IGNORE INTO hunks (base_ref, file_path, content_hash, status, patch_id)
+                         VALUES (?1, ?2, ?3, 'unreviewed', ?4)",
+                        params![base_ref, file_path, hunk.content_hash, patch_id],
                     )?;
+                    added += inserted;
+                    // Rules only apply once, so un-approving an auto-approved hunk sticks
+                    let rule = if inserted == 0 {
+                        None
+                    } else if let Some(rule) = self.auto_approver.matching_rule(&file_path, hunk) {
+                        Some(rule)
+                    } else if self.auto_approver.inherits()
+                        && self.reviewed_elsewhere(base_ref, &patch_id)?
+                    {
+                        Some(RULE_INHERITED)
+                    } else {
+                        None
+                    };
+                    if let Some(rule) = rule {
+                        tracing::debug!(file = %file_path, rule, "auto-approved hunk");
+                        self.auto_approve(base_ref, &file_path, &hunk.content_hash, rule)?;
+                        auto_approved += 1;
+                    }
                 }
+                // Rows from before the column, or added by a status change, lack it;
+                // rows hashed by an older normalization carry a different one
+                self.conn
+                    .prepare_cached(SYNC_PATCH_ID_SQL)?
+                    .execute(params![base_ref, file_path, hunk.content_hash, patch_id])?;
             }
         }
 
         // Mark hunks in DB that are not in current diff as Stale
         // Collect hunks to mark as stale first to avoid borrow checker issues
         let db_hunks: Vec<(String, String)> = {
-            let mut stmt = self.conn.prepare(
-                "SELECT file_path, content_hash FROM hunks WHERE base_ref = ?1 AND status != 'stale'",
-            )?;
+            let mut stmt = self.conn.prepare(LIVE_HUNKS_SQL)?;
             stmt.query_map(params![base_ref], |row| Ok((row.get(0)?, row.get(1)?)))?
                 .collect::<std::result::Result<Vec<_>, _>>()?
         };
 
         for (file_path, content_hash) in db_hunks {
             if !current_hunks.contains(&(file_path.clone(), content_hash.clone())) {
-                self.set_status(base_ref, &file_path, &content_hash, HunkStatus::Stale)?;
+                // Nobody made this change, so it is neither logged nor credited
+                self.record_status(base_ref, &file_path, &content_hash, HunkStatus::Stale, None)?;
+                marked_stale += 1;
             }
         }
 
+        tracing::info!(
+            base_ref,
+            files = files.len(),
+            hunks = current_hunks.len(),
+            added,
+            auto_approved,
+            marked_stale,
+            "synced review state with diff"
+        );
+        if files.is_empty() && marked_stale > 0 {
+            tracing::warn!(
+                base_ref,
+                marked_stale,
+                "the diff is empty, so every stored hunk was marked stale"
+            );
+        }
         Ok(())
     }
 
+    /// The records [`ReviewDb::sync_with_diff`] would leave for `base_ref`,
+    /// without writing anything: stored hunks still in the diff as they are,
+    /// new ones unreviewed, and the rest stale.
+    ///
+    /// Auto-approval rules are not applied, so hunks they would approve show
+    /// as unreviewed until the range is synced.
+    pub fn synced_hunks(&self, base_ref: &str, files: &[DiffFile]) -> Result<Vec<HunkRecord>> {
+        let current: HashSet<(String, &str)> = files
+            .iter()
+            .flat_map(|file| {
+                let path = file.path.to_string_lossy().to_string();
+                file.hunks
+                    .iter()
+                    .map(move |hunk| (path.clone(), hunk.content_hash.as_str()))
+            })
+            .collect();
+        let mut records = self.hunks(base_ref)?;
+        let stored: HashSet<(String, String)> = records
+            .iter()
+            .map(|record| (record.file_path.clone(), record.content_hash.clone()))
+            .collect();
+        for record in &mut records {
+            if !current.contains(&(record.file_path.clone(), record.content_hash.as_str())) {
+                record.status = HunkStatus::Stale;
+            }
+        }
+        for file in files {
+            let path = file.path.to_string_lossy().to_string();
+            for hunk in &file.hunks {
+                if !stored.contains(&(path.clone(), hunk.content_hash.clone())) {
+                    records.push(HunkRecord {
+                        file_path: path.clone(),
+                        content_hash: hunk.content_hash.clone(),
+                        status: HunkStatus::Unreviewed,
+                        reviewed_at: None,
+                        reviewed_by: None,
+                        auto_rule: None,
+                    });
+                }
+            }
+        }
+        Ok(records)
+    }
+
     /// Get review progress summary for a given base ref.
     pub fn progress(&self, base_ref: &str) -> Result<ReviewProgress> {
-        let mut stmt = self
-            .conn
-            .prepare("SELECT status, COUNT(*) FROM hunks WHERE base_ref = ?1 GROUP BY status")?;
+        let mut stmt = self.conn.prepare_cached(STATUS_COUNTS_SQL)?;
 
         let mut reviewed = 0;
         let mut unreviewed = 0;
         let mut stale = 0;
+        let mut needs_changes = 0;
+        let mut skimmed = 0;
 
         let rows = stmt.query_map(params![base_ref], |row| {
             let status: String = row.get(0)?;
@@ -167,71 +797,588 @@ impl ReviewDb {
                 "reviewed" => reviewed = count,
                 "unreviewed" => unreviewed = count,
                 "stale" => stale = count,
+                "needs_changes" => needs_changes = count,
+                "skimmed" => skimmed = count,
                 _ => {}
             }
         }
 
-        // Count files with remaining hunks
-        let mut file_stmt = self.conn.prepare(
-            "SELECT DISTINCT file_path FROM hunks WHERE base_ref = ?1 AND status != 'reviewed'",
-        )?;
-        let files_remaining = file_stmt
-            .query_map(params![base_ref], |_row| Ok(()))?
-            .count();
-
-        // Count total files
-        let mut total_files_stmt = self
-            .conn
-            .prepare("SELECT DISTINCT file_path FROM hunks WHERE base_ref = ?1")?;
-        let total_files = total_files_stmt
-            .query_map(params![base_ref], |_row| Ok(()))?
-            .count();
+        let count = |sql: &str| -> Result<usize> {
+            Ok(self
+                .conn
+                .prepare_cached(sql)?
+                .query_row(params![base_ref], |row| row.get(0))?)
+        };
+        let files_remaining = count(FILES_REMAINING_SQL)?;
+        let total_files = count(TOTAL_FILES_SQL)?;
+        let auto_approved = count(AUTO_APPROVED_SQL)?;
 
-        let total_hunks = reviewed + unreviewed + stale;
+        let total_hunks = reviewed + unreviewed + stale + needs_changes + skimmed;
 
         Ok(ReviewProgress {
             total_hunks,
             reviewed,
             unreviewed,
             stale,
+            needs_changes,
+            skimmed,
+            auto_approved,
             files_remaining,
             total_files,
         })
     }
 
-    /// Reset all review state for a given base ref.
-    ///
-    /// Deletes all hunks associated with the base ref.
+    /// Reset all review state for a given base ref (see [`ReviewDb::reset_many`]).
     pub fn reset(&mut self, base_ref: &str) -> Result<()> {
+        self.reset_many(&[base_ref.to_string()])?;
+        Ok(())
+    }
+
+    /// Reset review state for several base refs in one transaction: their
+    /// hunks, comments, assignments, bookmarks, bulk approvals, recorded base
+    /// commits, and pending carryovers. Snapshots, the event log, and the
+    /// archive of merged branches are kept.
+    ///
+    /// Returns the number of hunk rows removed.
+    pub fn reset_many(&mut self, base_refs: &[String]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut removed = 0;
+        for base_ref in base_refs {
+            removed += tx.execute("DELETE FROM hunks WHERE base_ref = ?1", params![base_ref])?;
+            for table in BASE_REF_TABLES.iter().skip(1) {
+                tx.execute(
+                    &format!("DELETE FROM {} WHERE base_ref = ?1", table),
+                    params![base_ref],
+                )?;
+            }
+            tx.execute(
+                "DELETE FROM carryovers WHERE from_base_ref = ?1",
+                params![base_ref],
+            )?;
+        }
+        tx.commit()?;
+        tracing::debug!(base_refs = base_refs.len(), removed, "reset review state");
+        Ok(removed)
+    }
+
+    /// Base refs stored for a branch namespace (see [`namespace`]).
+    pub fn base_refs_in_namespace(&mut self, name: &str) -> Result<Vec<String>> {
+        self.fill_namespaces()?;
+        let mut stmt = self.conn.prepare_cached(NAMESPACE_REFS_SQL)?;
+        let refs = stmt
+            .query_map(params![name], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        Ok(refs)
+    }
+
+    /// Record the [`namespace`] of hunk rows that lack it, so looking up a
+    /// branch's base refs is an index lookup rather than a scan of every key.
+    fn fill_namespaces(&mut self) -> Result<()> {
+        let missing: Vec<String> = self
+            .conn
+            .prepare_cached("SELECT DISTINCT base_ref FROM hunks WHERE namespace IS NULL")?
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<_, _>>()?;
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let tx = self.conn.transaction()?;
+        for base_ref in &missing {
+            tx.execute(
+                "UPDATE hunks SET namespace = ?2 WHERE base_ref = ?1",
+                params![base_ref, namespace(base_ref)],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Delete everything stored for a base ref: hunks, assignments, and comments.
+    ///
+    /// Used when the branch behind the base ref is gone.
+    pub fn purge(&mut self, base_ref: &str) -> Result<()> {
+        tracing::debug!(base_ref, "purging review state");
+        for table in BASE_REF_TABLES {
+            self.conn.execute(
+                &format!("DELETE FROM {} WHERE base_ref = ?1", table),
+                params![base_ref],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Move the hunk rows of a merged base ref into the archive, recording the
+    /// merge commit, and purge the rest of its state.
+    ///
+    /// Returns the number of hunks archived.
+    pub fn archive(&mut self, base_ref: &str, merge_sha: Option<&str>) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let archived = tx.execute(
+            "INSERT INTO archive
+                 (base_ref, file_path, content_hash, status, reviewed_at, reviewed_by, auto_rule, merge_sha)
+             SELECT base_ref, file_path, content_hash, status, reviewed_at, reviewed_by, auto_rule, ?2
+             FROM hunks WHERE base_ref = ?1",
+            params![base_ref, merge_sha],
+        )?;
+        for table in BASE_REF_TABLES {
+            tx.execute(
+                &format!("DELETE FROM {} WHERE base_ref = ?1", table),
+                params![base_ref],
+            )?;
+        }
+        tx.commit()?;
+        tracing::debug!(base_ref, merge_sha, archived, "archived review state");
+        if archived > 0 {
+            let branch = namespace(base_ref);
+            self.log_event(
+                branch,
+                base_ref,
+                EventKind::Merged,
+                None,
+                archived,
+                merge_sha,
+            )?;
+        }
+        Ok(archived)
+    }
+
+    /// Log the outcome of a gate check of `base_ref`, with a summary such as
+    /// `3/4 hunks reviewed`.
+    pub fn record_gate_check(&mut self, base_ref: &str, passed: bool, summary: &str) -> Result<()> {
+        let kind = if passed {
+            EventKind::GatePassed
+        } else {
+            EventKind::GateFailed
+        };
+        self.log_event(namespace(base_ref), base_ref, kind, None, 0, Some(summary))
+    }
+
+    /// Append an entry to the event log, crediting the current reviewer.
+    fn log_event(
+        &self,
+        branch: &str,
+        base_ref: &str,
+        kind: EventKind,
+        file_path: Option<&str>,
+        count: usize,
+        detail: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO events (branch, base_ref, kind, file_path, count, detail, actor)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                branch,
+                base_ref,
+                kind.as_str(),
+                file_path,
+                count as i64,
+                detail,
+                self.reviewer
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The event log, oldest first, optionally only for one branch.
+    pub fn events(&self, branch: Option<&str>) -> Result<Vec<EventRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT branch, base_ref, kind, file_path, count, detail, actor,
+                    strftime('%Y-%m-%d %H:%M', at, 'localtime')
+             FROM events WHERE ?1 IS NULL OR branch = ?1 ORDER BY at, id",
+        )?;
+        let rows = stmt
+            .query_map(params![branch], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, i64>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                    row.get::<_, Option<String>>(6)?,
+                    row.get::<_, String>(7)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        rows.into_iter()
+            .map(
+                |(branch, base_ref, kind, file_path, count, detail, actor, at)| {
+                    Ok(EventRecord {
+                        branch,
+                        base_ref,
+                        kind: EventKind::parse(&kind)?,
+                        file_path,
+                        count: count as usize,
+                        detail,
+                        actor,
+                        at,
+                    })
+                },
+            )
+            .collect()
+    }
+
+    /// Archived reviews, most recently archived first.
+    pub fn archived(&self) -> Result<Vec<ArchiveRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT base_ref, merge_sha, archived_at, COUNT(*),
+                    SUM(status = 'reviewed')
+             FROM archive GROUP BY base_ref, merge_sha, archived_at
+             ORDER BY archived_at DESC, base_ref",
+        )?;
+        let mut records = stmt
+            .query_map([], |row| {
+                Ok(ArchiveRecord {
+                    base_ref: row.get(0)?,
+                    merge_sha: row.get(1)?,
+                    archived_at: row.get(2)?,
+                    hunks: row.get::<_, i64>(3)? as usize,
+                    reviewed: row.get::<_, i64>(4)? as usize,
+                    reviewers: Vec::new(),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut stmt = self.conn.prepare(
+            "SELECT reviewed_by FROM archive
+             WHERE base_ref = ?1 AND archived_at = ?2 AND status = 'reviewed' AND reviewed_by IS NOT NULL
+             GROUP BY reviewed_by ORDER BY MAX(reviewed_at) DESC, reviewed_by",
+        )?;
+        for record in &mut records {
+            record.reviewers = stmt
+                .query_map(params![record.base_ref, record.archived_at], |row| {
+                    row.get(0)
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+        }
+        Ok(records)
+    }
+
+    /// Whether any hunk is stored under `base_ref`.
+    pub fn has_state(&self, base_ref: &str) -> Result<bool> {
+        Ok(self.conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM hunks WHERE base_ref = ?1)",
+            params![base_ref],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Move everything stored under `from` to `to`, unless `to` already has state.
+    ///
+    /// Carries progress recorded under a raw range spelling over to its canonical key.
+    /// Returns whether anything was moved.
+    pub fn adopt_base_ref(&mut self, from: &str, to: &str) -> Result<bool> {
+        if from == to {
+            return Ok(false);
+        }
+        let existing: i64 = self.conn.query_row(
+            "SELECT COUNT(*) FROM hunks WHERE base_ref = ?1",
+            params![to],
+            |row| row.get(0),
+        )?;
+        if existing > 0 {
+            return Ok(false);
+        }
+
+        let tx = self.conn.transaction()?;
+        let mut moved = 0;
+        for table in BASE_REF_TABLES {
+            moved += tx.execute(
+                &format!(
+                    "UPDATE OR IGNORE {} SET base_ref = ?2 WHERE base_ref = ?1",
+                    table
+                ),
+                params![from, to],
+            )?;
+        }
+        // The new key may belong to another branch
+        tx.execute(
+            "UPDATE hunks SET namespace = NULL WHERE base_ref = ?1",
+            params![to],
+        )?;
+        tx.commit()?;
+        tracing::debug!(from, to, moved, "adopted review state under a new base ref");
+        Ok(moved > 0)
+    }
+
+    /// Remember that `branch`'s state under `from_base_ref` should move to its next key.
+    ///
+    /// Recorded before a rebase changes the branch's merge-base (and so its key).
+    pub fn record_carryover(&mut self, branch: &str, from_base_ref: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO carryovers (branch, from_base_ref) VALUES (?1, ?2)
+             ON CONFLICT(branch) DO UPDATE SET from_base_ref = ?2",
+            params![branch, from_base_ref],
+        )?;
+        Ok(())
+    }
+
+    /// The base ref a pending carryover for `branch` moves state from, if any.
+    pub fn carryover(&self, branch: &str) -> Result<Option<String>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT from_base_ref FROM carryovers WHERE branch = ?1",
+                params![branch],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    /// Forget a pending carryover for `branch`.
+    pub fn clear_carryover(&mut self, branch: &str) -> Result<()> {
         self.conn
-            .execute("DELETE FROM hunks WHERE base_ref = ?1", params![base_ref])?;
+            .execute("DELETE FROM carryovers WHERE branch = ?1", params![branch])?;
         Ok(())
     }
 
-    /// Approve all hunks for a given base ref (mark all as Reviewed).
+    /// Queue `branch` to be merged into `base` once it is fully reviewed
+    /// (see [`crate::queue`]).
+    pub fn enqueue_merge(&mut self, branch: &str, base: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO merge_queue (branch, base) VALUES (?1, ?2)
+             ON CONFLICT(branch) DO UPDATE SET base = ?2",
+            params![branch, base],
+        )?;
+        Ok(())
+    }
+
+    /// Take `branch` off the merge queue. Returns whether it was queued.
+    pub fn dequeue_merge(&mut self, branch: &str) -> Result<bool> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM merge_queue WHERE branch = ?1", params![branch])?;
+        Ok(removed > 0)
+    }
+
+    /// Branches queued for merging into `base`, oldest first.
+    pub fn merge_queue(&self, base: &str) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT branch FROM merge_queue WHERE base = ?1 ORDER BY queued_at, rowid")?;
+        let branches = stmt
+            .query_map(params![base], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(branches)
+    }
+
+    /// Remember `tip_sha` as the commit `branch` pointed at when it was last reviewed.
+    pub fn record_reviewed_tip(&mut self, branch: &str, tip_sha: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO reviewed_tips (branch, tip_sha) VALUES (?1, ?2)
+             ON CONFLICT(branch) DO UPDATE SET tip_sha = ?2, reviewed_at = datetime('now')",
+            params![branch, tip_sha],
+        )?;
+        Ok(())
+    }
+
+    /// The tip of every branch as of its last review, by branch name.
+    pub fn reviewed_tips(&self) -> Result<HashMap<String, String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT branch, tip_sha FROM reviewed_tips")?;
+        let tips = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+        Ok(tips)
+    }
+
+    /// Forget the reviewed tip of a deleted `branch`.
+    pub fn clear_reviewed_tip(&mut self, branch: &str) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM reviewed_tips WHERE branch = ?1",
+            params![branch],
+        )?;
+        Ok(())
+    }
+
+    /// Mark hunks of `to` reviewed that are reviewed in `from` with the same
+    /// file and content, keeping who reviewed them and when (e.g. after
+    /// cherry-picking a reviewed series onto another branch).
     ///
-    /// Returns the count of hunks that were updated.
-    pub fn approve_all(&mut self, base_ref: &str) -> Result<usize> {
+    /// Only hunks `to` already has rows for are updated, so sync its diff
+    /// first. Returns the count of hunks that were updated.
+    pub fn carry_reviews(&mut self, from: &str, to: &str) -> Result<usize> {
         let count = self.conn.execute(
-            "UPDATE hunks SET status = 'reviewed', reviewed_at = datetime('now')
-             WHERE base_ref = ?1 AND status != 'reviewed'",
-            params![base_ref],
+            "UPDATE hunks AS target
+             SET status = 'reviewed', reviewed_at = source.reviewed_at,
+                 reviewed_by = source.reviewed_by, auto_rule = source.auto_rule,
+                 changed_at = datetime('now')
+             FROM hunks AS source
+             WHERE target.base_ref = ?2 AND source.base_ref = ?1
+                 AND source.file_path = target.file_path
+                 AND source.content_hash = target.content_hash
+                 AND source.status = 'reviewed' AND target.status != 'reviewed'",
+            params![from, to],
         )?;
+        tracing::debug!(from, to, count, "carried reviews");
+        if count > 0 {
+            self.log_event(
+                namespace(to),
+                to,
+                EventKind::Carried,
+                None,
+                count,
+                Some(from),
+            )?;
+        }
         Ok(count)
     }
 
+    /// Approve all hunks for a given base ref (mark all as Reviewed).
+    ///
+    /// Returns the count of hunks that were updated. The approval can be
+    /// reverted with [`ReviewDb::undo_bulk_approval`].
+    pub fn approve_all(&mut self, base_ref: &str) -> Result<usize> {
+        let pending = self.pending_hunks(base_ref, None)?;
+        self.approve_hunks(base_ref, None, &pending)
+    }
+
     /// Approve all hunks for a specific file within a base ref.
     ///
-    /// Returns the count of hunks that were updated.
+    /// Returns the count of hunks that were updated. The approval can be
+    /// reverted with [`ReviewDb::undo_bulk_approval`].
     pub fn approve_file(&mut self, base_ref: &str, file_path: &str) -> Result<usize> {
-        let count = self.conn.execute(
-            "UPDATE hunks SET status = 'reviewed', reviewed_at = datetime('now')
-             WHERE base_ref = ?1 AND file_path = ?2 AND status != 'reviewed'",
-            params![base_ref, file_path],
+        let pending = self.pending_hunks(base_ref, Some(file_path))?;
+        self.approve_hunks(base_ref, Some(file_path), &pending)
+    }
+
+    /// Stored hunks of a base ref (or of one file) that are not reviewed.
+    fn pending_hunks(
+        &self,
+        base_ref: &str,
+        file_path: Option<&str>,
+    ) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, content_hash FROM hunks
+             WHERE base_ref = ?1 AND (?2 IS NULL OR file_path = ?2) AND status != 'reviewed'",
+        )?;
+        let rows = stmt.query_map(params![base_ref, file_path], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+        Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+    }
+
+    /// Mark `(file_path, content_hash)` hunks reviewed as one bulk approval,
+    /// remembering their previous state so it can be undone. `scope` is the
+    /// file the approval was limited to, if any.
+    ///
+    /// Returns the count of hunks that were updated.
+    pub fn approve_hunks(
+        &mut self,
+        base_ref: &str,
+        scope: Option<&str>,
+        hunks: &[(String, String)],
+    ) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let batch: i64 = tx.query_row(
+            "SELECT COALESCE(MAX(batch), 0) + 1 FROM bulk_approvals",
+            [],
+            |row| row.get(0),
+        )?;
+        // One timestamp for the whole batch, so undoing it can tell its
+        // approvals from later changes by `changed_at`
+        let now: String = tx.query_row("SELECT datetime('now')", [], |row| row.get(0))?;
+        let mut count = 0;
+        for (file_path, content_hash) in hunks {
+            // Hunks without a row yet were unreviewed; give them one to remember
+            tx.execute(
+                "INSERT OR IGNORE INTO hunks (base_ref, file_path, content_hash, status)
+                 VALUES (?1, ?2, ?3, 'unreviewed')",
+                params![base_ref, file_path, content_hash],
+            )?;
+            tx.execute(
+                "INSERT INTO bulk_approvals (batch, base_ref, scope, file_path, content_hash,
+                     status, reviewed_at, reviewed_by, auto_rule, changed_at, approved_at)
+                 SELECT ?1, base_ref, ?2, file_path, content_hash,
+                     status, reviewed_at, reviewed_by, auto_rule, changed_at, ?6
+                 FROM hunks
+                 WHERE base_ref = ?3 AND file_path = ?4 AND content_hash = ?5 AND status != 'reviewed'",
+                params![batch, scope, base_ref, file_path, content_hash, now],
+            )?;
+            count += tx.execute(
+                "UPDATE hunks SET status = 'reviewed', reviewed_at = ?5, reviewed_by = ?4,
+                     auto_rule = NULL, changed_at = ?5
+                 WHERE base_ref = ?1 AND file_path = ?2 AND content_hash = ?3 AND status != 'reviewed'",
+                params![base_ref, file_path, content_hash, self.reviewer, now],
+            )?;
+        }
+        // Only the most recent approvals can be undone
+        tx.execute(
+            "DELETE FROM bulk_approvals WHERE batch <= ?1",
+            params![batch - UNDO_DEPTH],
         )?;
+        tx.commit()?;
+        tracing::debug!(base_ref, scope, count, batch, "approved hunks in bulk");
+        for (file_path, content_hash) in hunks {
+            self.inherit_approval(base_ref, file_path, content_hash)?;
+        }
+        if count > 0 {
+            let branch = namespace(base_ref);
+            self.log_event(branch, base_ref, EventKind::Approved, scope, count, None)?;
+        }
         Ok(count)
     }
 
+    /// The most recent bulk approval that can still be undone, optionally
+    /// only among those of one base ref.
+    pub fn last_bulk_approval(&self, base_ref: Option<&str>) -> Result<Option<BulkApproval>> {
+        self.conn
+            .query_row(
+                "SELECT batch, base_ref, scope, COUNT(*),
+                     strftime('%Y-%m-%d %H:%M', MIN(approved_at), 'localtime')
+                 FROM bulk_approvals WHERE ?1 IS NULL OR base_ref = ?1
+                 GROUP BY batch ORDER BY batch DESC LIMIT 1",
+                params![base_ref],
+                |row| {
+                    Ok(BulkApproval {
+                        batch: row.get(0)?,
+                        base_ref: row.get(1)?,
+                        file_path: row.get(2)?,
+                        hunks: row.get::<_, i64>(3)? as usize,
+                        approved_at: row.get(4)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(StateError::from)
+    }
+
+    /// Put the hunks of a bulk approval back in the state they had before it.
+    ///
+    /// Hunks whose status changed again since, even if they ended up reviewed
+    /// again, are left alone. Returns the number of hunks restored.
+    pub fn undo_bulk_approval(&mut self, approval: &BulkApproval) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let restored = tx.execute(
+            "UPDATE hunks SET status = b.status, reviewed_at = b.reviewed_at,
+                 reviewed_by = b.reviewed_by, auto_rule = b.auto_rule, changed_at = datetime('now')
+             FROM bulk_approvals AS b
+             WHERE b.batch = ?1 AND hunks.base_ref = b.base_ref
+                 AND hunks.file_path = b.file_path AND hunks.content_hash = b.content_hash
+                 AND hunks.status = 'reviewed' AND hunks.changed_at <= b.approved_at",
+            params![approval.batch],
+        )?;
+        tx.execute(
+            "DELETE FROM bulk_approvals WHERE batch = ?1",
+            params![approval.batch],
+        )?;
+        tx.commit()?;
+        tracing::debug!(base_ref = %approval.base_ref, batch = approval.batch, restored, "undid bulk approval");
+        if restored > 0 {
+            let branch = namespace(&approval.base_ref);
+            self.log_event(
+                branch,
+                &approval.base_ref,
+                EventKind::ApprovalUndone,
+                approval.file_path.as_deref(),
+                restored,
+                None,
+            )?;
+        }
+        Ok(restored)
+    }
+
     /// List all distinct base refs in the database (for dashboard).
     ///
     /// Returns base refs sorted alphabetically.
@@ -240,20 +1387,659 @@ impl ReviewDb {
             .conn
             .prepare("SELECT DISTINCT base_ref FROM hunks ORDER BY base_ref")?;
 
-        let refs = stmt
-            .query_map([], |row| row.get(0))?
-            .collect::<std::result::Result<Vec<String>, _>>()?;
+        let refs = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+
+        Ok(refs)
+    }
+
+    /// List all hunks stored for a base ref, ordered by file path.
+    pub fn hunks(&self, base_ref: &str) -> Result<Vec<HunkRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, content_hash, status, reviewed_at, reviewed_by, auto_rule
+             FROM hunks WHERE base_ref = ?1 ORDER BY file_path, id",
+        )?;
+        let rows = stmt
+            .query_map(params![base_ref], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, Option<String>>(5)?,
+                ))
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(
+                |(file_path, content_hash, status, reviewed_at, reviewed_by, auto_rule)| {
+                    Ok(HunkRecord {
+                        file_path,
+                        content_hash,
+                        status: status_from_str(&status)?,
+                        reviewed_at,
+                        reviewed_by,
+                        auto_rule,
+                    })
+                },
+            )
+            .collect()
+    }
+
+    /// List the raw hunk rows of a base ref in insertion order (see [`StoredHunk`]).
+    pub fn stored_hunks(&self, base_ref: &str) -> Result<Vec<StoredHunk>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, file_path, content_hash, status, reviewed_at, reviewed_by
+             FROM hunks WHERE base_ref = ?1 ORDER BY id",
+        )?;
+        let rows = stmt
+            .query_map(params![base_ref], |row| {
+                Ok(StoredHunk {
+                    id: row.get(0)?,
+                    file_path: row.get(1)?,
+                    content_hash: row.get(2)?,
+                    status: row.get(3)?,
+                    reviewed_at: row.get(4)?,
+                    reviewed_by: row.get(5)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Delete hunk rows by id. Returns the number of rows removed.
+    pub fn delete_hunk_rows(&mut self, ids: &[i64]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut removed = 0;
+        for id in ids {
+            removed += tx.execute("DELETE FROM hunks WHERE id = ?1", params![id])?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Enforce one row per base ref, file, and hash again on a database that
+    /// lost its UNIQUE constraint. Fails while duplicate rows remain.
+    pub fn restore_hunk_uniqueness(&mut self) -> Result<()> {
+        self.conn.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_hunks_unique ON hunks (base_ref, file_path, content_hash)",
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Mark hunk rows unreviewed by id, clearing who reviewed them and when.
+    pub fn reset_hunk_rows(&mut self, ids: &[i64]) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        let mut reset = 0;
+        for id in ids {
+            reset += tx.execute(
+                "UPDATE hunks SET status = 'unreviewed', reviewed_at = NULL, reviewed_by = NULL, auto_rule = NULL,
+                     changed_at = datetime('now')
+                 WHERE id = ?1",
+                params![id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(reset)
+    }
+
+    /// Seconds since each reviewed hunk of a file was approved, keyed by content hash.
+    pub fn review_ages(&self, base_ref: &str, file_path: &str) -> Result<HashMap<String, u64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT content_hash, MAX(0, CAST(strftime('%s', 'now') - strftime('%s', reviewed_at) AS INTEGER))
+             FROM hunks
+             WHERE base_ref = ?1 AND file_path = ?2 AND status = 'reviewed' AND reviewed_at IS NOT NULL",
+        )?;
+        let ages = stmt
+            .query_map(params![base_ref, file_path], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            })?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+        Ok(ages)
+    }
+
+    /// Seconds since the latest approval in each file under `base_ref` with
+    /// reviewed hunks, keyed by file path.
+    pub fn file_review_ages(&self, base_ref: &str) -> Result<HashMap<String, u64>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, MAX(0, CAST(strftime('%s', 'now') - strftime('%s', MAX(reviewed_at)) AS INTEGER))
+             FROM hunks
+             WHERE base_ref = ?1 AND status = 'reviewed' AND reviewed_at IS NOT NULL
+             GROUP BY file_path",
+        )?;
+        let ages = stmt
+            .query_map(params![base_ref], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+            })?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+        Ok(ages)
+    }
+
+    /// Approvals under `base_ref` made in the last `seconds`, keyed by file
+    /// path and content hash.
+    pub fn approvals_within(
+        &self,
+        base_ref: &str,
+        seconds: u64,
+    ) -> Result<HashMap<(String, String), Approval>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, content_hash,
+                    MAX(0, CAST(strftime('%s', 'now') - strftime('%s', reviewed_at) AS INTEGER)) AS age,
+                    reviewed_by, auto_rule
+             FROM hunks
+             WHERE base_ref = ?1 AND status = 'reviewed' AND reviewed_at IS NOT NULL
+               AND CAST(strftime('%s', reviewed_at) AS INTEGER)
+                   >= CAST(strftime('%s', 'now') AS INTEGER) - ?2",
+        )?;
+        let seconds = i64::try_from(seconds).unwrap_or(i64::MAX);
+        let approvals = stmt
+            .query_map(params![base_ref, seconds], |row| {
+                Ok((
+                    (row.get(0)?, row.get(1)?),
+                    Approval {
+                        age: row.get::<_, i64>(2)? as u64,
+                        reviewed_by: row.get(3)?,
+                        auto_rule: row.get(4)?,
+                    },
+                ))
+            })?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+        Ok(approvals)
+    }
+
+    /// Unix time of the latest approval by a person (not an auto-approval rule)
+    /// under `base_ref`.
+    pub fn last_review_time(&self, base_ref: &str) -> Result<Option<i64>> {
+        let time = self.conn.query_row(
+            "SELECT CAST(strftime('%s', MAX(reviewed_at)) AS INTEGER) FROM hunks
+             WHERE base_ref = ?1 AND status = 'reviewed' AND auto_rule IS NULL",
+            params![base_ref],
+            |row| row.get(0),
+        )?;
+        Ok(time)
+    }
+
+    /// Remember `sha` as the commit `base_ref`'s diff is taken against.
+    ///
+    /// Returns the commit recorded before, with when (Unix time), if it was a
+    /// different one: the base moved in between.
+    pub fn record_base_commit(
+        &mut self,
+        base_ref: &str,
+        sha: &str,
+    ) -> Result<Option<(String, i64)>> {
+        let previous: Option<(String, i64)> = self
+            .conn
+            .query_row(
+                "SELECT sha, recorded_at FROM base_commits WHERE base_ref = ?1",
+                params![base_ref],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        self.conn.execute(
+            "INSERT INTO base_commits (base_ref, sha, recorded_at)
+             VALUES (?1, ?2, CAST(strftime('%s', 'now') AS INTEGER))
+             ON CONFLICT(base_ref) DO UPDATE SET sha = ?2, recorded_at = excluded.recorded_at",
+            params![base_ref, sha],
+        )?;
+        Ok(previous.filter(|(previous, _)| previous != sha))
+    }
+
+    /// `(file path, content hash)` of the unreviewed and stale hunks under
+    /// `base_ref` that became so at or after Unix time `since`: new hunks,
+    /// reviewed hunks whose content left the diff, and undone reviews.
+    pub fn changed_since(&self, base_ref: &str, since: i64) -> Result<HashSet<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, content_hash FROM hunks
+             WHERE base_ref = ?1 AND status IN ('unreviewed', 'stale')
+               AND CAST(strftime('%s', COALESCE(changed_at, created_at)) AS INTEGER) >= ?2",
+        )?;
+        let hunks = stmt
+            .query_map(params![base_ref, since], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+            .collect::<std::result::Result<HashSet<_>, _>>()?;
+        Ok(hunks)
+    }
+
+    /// Unix time of a date, or date and time, in local time (`2026-10-01`,
+    /// `2026-10-01 14:30`), or `None` if `text` is not one.
+    pub fn parse_local_time(&self, text: &str) -> Result<Option<i64>> {
+        let time = self.conn.query_row(
+            "SELECT CAST(strftime('%s', ?1, 'utc') AS INTEGER)",
+            params![text],
+            |row| row.get(0),
+        )?;
+        Ok(time)
+    }
+
+    /// People who reviewed hunks under `base_ref`, most recent first.
+    /// Auto-approvals and reviews recorded without a name are left out.
+    pub fn reviewers(&self, base_ref: &str) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT reviewed_by FROM hunks
+             WHERE base_ref = ?1 AND status = 'reviewed' AND reviewed_by IS NOT NULL
+             GROUP BY reviewed_by ORDER BY MAX(reviewed_at) DESC, reviewed_by",
+        )?;
+        let reviewers = stmt
+            .query_map(params![base_ref], |row| row.get(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(reviewers)
+    }
+
+    /// Auto-approval rule of each auto-approved hunk of a file, keyed by content hash.
+    pub fn auto_rules(&self, base_ref: &str, file_path: &str) -> Result<HashMap<String, String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT content_hash, auto_rule FROM hunks
+             WHERE base_ref = ?1 AND file_path = ?2 AND status = 'reviewed' AND auto_rule IS NOT NULL",
+        )?;
+        let rules = stmt
+            .query_map(params![base_ref, file_path], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .collect::<std::result::Result<HashMap<_, _>, _>>()?;
+        Ok(rules)
+    }
+
+    /// Cached model summary of a hunk, by content hash (see [`crate::ai`]).
+    pub fn ai_summary(&self, content_hash: &str) -> Result<Option<HunkSummary>> {
+        let row: Option<(String, Option<String>)> = self
+            .conn
+            .query_row(
+                "SELECT summary, risk FROM ai_summaries WHERE content_hash = ?1",
+                params![content_hash],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((summary, risk)) = row else {
+            return Ok(None);
+        };
+        Ok(Some(HunkSummary {
+            summary: self.unseal(summary)?,
+            risk: risk.map(|risk| self.unseal(risk)).transpose()?,
+        }))
+    }
+
+    /// Cache a model summary of a hunk, replacing any earlier one.
+    pub fn set_ai_summary(&mut self, content_hash: &str, summary: &HunkSummary) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO ai_summaries (content_hash, summary, risk) VALUES (?1, ?2, ?3)",
+            params![
+                content_hash,
+                self.seal(&summary.summary),
+                summary.risk.as_deref().map(|risk| self.seal(risk))
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Assign a file within a base ref to a reviewer, replacing any previous assignee.
+    pub fn assign(&mut self, base_ref: &str, file_path: &str, assignee: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO assignments (base_ref, file_path, assignee) VALUES (?1, ?2, ?3)
+             ON CONFLICT(base_ref, file_path) DO UPDATE SET assignee = ?3",
+            params![base_ref, file_path, assignee],
+        )?;
+        Ok(())
+    }
+
+    /// Get all file assignments for a base ref as `(file_path, assignee)` pairs.
+    pub fn assignments(&self, base_ref: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, assignee FROM assignments WHERE base_ref = ?1 ORDER BY file_path",
+        )?;
+        let rows = stmt
+            .query_map(params![base_ref], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// List hunks in assigned files that have not been reviewed by their assignee.
+    ///
+    /// Returns `(file_path, content_hash, assignee)` for each offending hunk.
+    /// Stale hunks are ignored — they no longer exist in the diff.
+    pub fn unsatisfied_assignments(&self, base_ref: &str) -> Result<Vec<(String, String, String)>> {
+        self.unsatisfied_assignments_from(base_ref, &[base_ref.to_string()])
+    }
+
+    /// Like [`ReviewDb::unsatisfied_assignments`], but with the files assigned
+    /// under any of `assigned_in` applying to the hunks of `base_ref`.
+    ///
+    /// This is how the commit gate, which checks the staged diff (`HEAD`),
+    /// honors assignments made on the range of the branch being committed to.
+    pub fn unsatisfied_assignments_from(
+        &self,
+        base_ref: &str,
+        assigned_in: &[String],
+    ) -> Result<Vec<(String, String, String)>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT h.file_path, h.content_hash, a.assignee
+             FROM hunks h
+             JOIN assignments a ON a.base_ref = ?2 AND a.file_path = h.file_path
+             WHERE h.base_ref = ?1 AND h.status != 'stale'
+               AND (h.status != 'reviewed' OR h.reviewed_by IS NULL OR h.reviewed_by != a.assignee)",
+        )?;
+        let mut rows = Vec::new();
+        for assigned in assigned_in {
+            let found = stmt
+                .query_map(params![base_ref, assigned], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })?
+                .collect::<std::result::Result<Vec<(String, String, String)>, _>>()?;
+            rows.extend(found);
+        }
+        rows.sort();
+        rows.dedup();
+        Ok(rows)
+    }
+
+    /// Base refs holding file assignments whose [`namespace`] is `name`.
+    pub fn assignment_refs_in_namespace(&self, name: &str) -> Result<Vec<String>> {
+        let refs = self
+            .conn
+            .prepare_cached("SELECT DISTINCT base_ref FROM assignments ORDER BY base_ref")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(refs
+            .into_iter()
+            .filter(|base_ref| namespace(base_ref) == name)
+            .collect())
+    }
+
+    /// Attach a comment to a hunk, authored by the current reviewer.
+    pub fn add_comment(
+        &mut self,
+        base_ref: &str,
+        file_path: &str,
+        content_hash: &str,
+        body: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO comments (base_ref, file_path, content_hash, author, body)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                base_ref,
+                file_path,
+                content_hash,
+                self.reviewer,
+                self.seal(body)
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Bookmark a hunk to come back to, or clear its bookmark. Returns whether
+    /// the hunk is bookmarked now.
+    pub fn toggle_bookmark(
+        &mut self,
+        base_ref: &str,
+        file_path: &str,
+        content_hash: &str,
+    ) -> Result<bool> {
+        let removed = self.conn.execute(
+            "DELETE FROM bookmarks WHERE base_ref = ?1 AND file_path = ?2 AND content_hash = ?3",
+            params![base_ref, file_path, content_hash],
+        )?;
+        if removed == 0 {
+            self.conn.execute(
+                "INSERT INTO bookmarks (base_ref, file_path, content_hash) VALUES (?1, ?2, ?3)",
+                params![base_ref, file_path, content_hash],
+            )?;
+        }
+        tracing::debug!(
+            base_ref,
+            file_path,
+            content_hash,
+            bookmarked = removed == 0,
+            "toggled bookmark"
+        );
+        Ok(removed == 0)
+    }
+
+    /// Bookmarked hunks of a base ref as `(file_path, content_hash)` pairs.
+    pub fn bookmarks(&self, base_ref: &str) -> Result<HashSet<(String, String)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT file_path, content_hash FROM bookmarks WHERE base_ref = ?1")?;
+        let rows = stmt
+            .query_map(params![base_ref], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(rows)
+    }
+
+    /// Copy a base ref's hunk rows into a snapshot called `name`, replacing any
+    /// snapshot of that name. Returns the number of hunks saved.
+    pub fn save_snapshot(&mut self, name: &str, base_ref: &str) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM snapshot_hunks WHERE snapshot = ?1",
+            params![name],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO snapshots (name, base_ref) VALUES (?1, ?2)",
+            params![name, base_ref],
+        )?;
+        let count = tx.execute(
+            "INSERT INTO snapshot_hunks
+                 (snapshot, file_path, content_hash, status, reviewed_at, reviewed_by, auto_rule, changed_at)
+             SELECT ?1, file_path, content_hash, status, reviewed_at, reviewed_by, auto_rule, changed_at
+             FROM hunks WHERE base_ref = ?2",
+            params![name, base_ref],
+        )?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    /// Replace a base ref's hunk rows with those saved in snapshot `name`.
+    ///
+    /// Returns the number of hunks restored. The snapshot itself is kept.
+    pub fn restore_snapshot(&mut self, name: &str, base_ref: &str) -> Result<usize> {
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM hunks WHERE base_ref = ?1", params![base_ref])?;
+        let count = tx.execute(
+            "INSERT INTO hunks
+                 (base_ref, file_path, content_hash, status, reviewed_at, reviewed_by, auto_rule, changed_at)
+             SELECT ?2, file_path, content_hash, status, reviewed_at, reviewed_by, auto_rule, changed_at
+             FROM snapshot_hunks WHERE snapshot = ?1",
+            params![name, base_ref],
+        )?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    /// Look up a snapshot by name.
+    pub fn snapshot(&self, name: &str) -> Result<Option<SnapshotRecord>> {
+        Ok(self
+            .snapshots()?
+            .into_iter()
+            .find(|snapshot| snapshot.name == name))
+    }
+
+    /// List all snapshots, oldest first.
+    pub fn snapshots(&self) -> Result<Vec<SnapshotRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT s.name, s.base_ref, s.created_at,
+                    (SELECT COUNT(*) FROM snapshot_hunks h WHERE h.snapshot = s.name)
+             FROM snapshots s ORDER BY s.created_at, s.name",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(SnapshotRecord {
+                    name: row.get(0)?,
+                    base_ref: row.get(1)?,
+                    created_at: row.get(2)?,
+                    hunks: row.get::<_, i64>(3)? as usize,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Delete a snapshot. Returns whether it existed.
+    pub fn delete_snapshot(&mut self, name: &str) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "DELETE FROM snapshot_hunks WHERE snapshot = ?1",
+            params![name],
+        )?;
+        let deleted = tx.execute("DELETE FROM snapshots WHERE name = ?1", params![name])?;
+        tx.commit()?;
+        Ok(deleted > 0)
+    }
+
+    /// Get all comments for a base ref, oldest first.
+    pub fn comments(&self, base_ref: &str) -> Result<Vec<CommentRecord>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT file_path, content_hash, author, body, created_at FROM comments
+             WHERE base_ref = ?1 ORDER BY id",
+        )?;
+        let rows = stmt
+            .query_map(params![base_ref], |row| {
+                Ok(CommentRecord {
+                    file_path: row.get(0)?,
+                    content_hash: row.get(1)?,
+                    author: row.get(2)?,
+                    body: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        rows.into_iter()
+            .map(|comment| {
+                Ok(CommentRecord {
+                    body: self.unseal(comment.body)?,
+                    ..comment
+                })
+            })
+            .collect()
+    }
+}
+
+/// Add a column to an existing table if it is not already present.
+///
+/// `CREATE TABLE IF NOT EXISTS` does not update tables created by older versions,
+/// so new columns are added here to keep existing databases usable.
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, decl: &str) -> Result<()> {
+    let exists = {
+        let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+        stmt.query_map([], |row| row.get::<_, String>(1))?
+            .collect::<std::result::Result<Vec<_>, _>>()?
+            .iter()
+            .any(|name| name == column)
+    };
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+const NAMESPACE_REFS_SQL: &str =
+    "SELECT DISTINCT base_ref FROM hunks WHERE namespace = ?1 ORDER BY base_ref";
+
+/// Tables holding a base ref's live review state, keyed by its `base_ref`
+/// column. `hunks` comes first.
+const BASE_REF_TABLES: [&str; 6] = [
+    "hunks",
+    "assignments",
+    "comments",
+    "bookmarks",
+    "bulk_approvals",
+    "base_commits",
+];
+
+/// Key prefix of a diff taken with `git diff -w`.
+const IGNORE_WS_PREFIX: &str = "ignore-ws:";
+/// Key prefix of a diff taken with difftastic.
+const STRUCTURAL_PREFIX: &str = "structural:";
+/// Key prefix of a stash review.
+const STASH_PREFIX: &str = "stash:";
+/// Key prefix of a patch or mbox review.
+const PATCH_PREFIX: &str = "patch:";
+
+/// The key of `base_ref` reviewed in an alternate diff mode.
+///
+/// Whitespace-blind and structural hunks hash differently from the full
+/// diff's, so they get their own state rather than marking its hunks stale.
+pub fn mode_key(base_ref: &str, ignore_whitespace: bool, structural: bool) -> String {
+    let mut key = base_ref.to_string();
+    if ignore_whitespace {
+        key.insert_str(0, IGNORE_WS_PREFIX);
+    }
+    if structural {
+        key.insert_str(0, STRUCTURAL_PREFIX);
+    }
+    key
+}
+
+/// The key of a stash review, by the stash commit's SHA.
+pub fn stash_key(sha: &str) -> String {
+    format!("{}{}", STASH_PREFIX, sha)
+}
+
+/// The key of a patch or mbox review, by a hash of its content.
+pub fn patch_key(hash: &str) -> String {
+    format!("{}{}", PATCH_PREFIX, hash)
+}
+
+/// `base_ref` without the prefixes [`mode_key`] adds.
+pub fn strip_mode(base_ref: &str) -> &str {
+    let mut key = base_ref;
+    while let Some(rest) = key
+        .strip_prefix(IGNORE_WS_PREFIX)
+        .or_else(|| key.strip_prefix(STRUCTURAL_PREFIX))
+    {
+        key = rest;
+    }
+    key
+}
 
-        Ok(refs)
+/// The git range a base ref reviews, or `None` for stash and patch reviews,
+/// which have no range git can diff again.
+pub fn git_range(base_ref: &str) -> Option<&str> {
+    let key = strip_mode(base_ref);
+    if key.starts_with(STASH_PREFIX) || key.starts_with(PATCH_PREFIX) {
+        return None;
     }
+    Some(key)
+}
+
+/// The branch a base ref's review state belongs to.
+///
+/// Range keys belong to their head side (`main..feature` and `abc123..feature`
+/// are both `feature`), ignoring the prefixes of alternate diff modes (see
+/// [`mode_key`]). Other keys (`HEAD`, `stash:...`) are their own namespace.
+pub fn namespace(base_ref: &str) -> &str {
+    let key = strip_mode(base_ref);
+    key.rsplit_once("..").map_or(key, |(_, head)| head)
 }
 
 /// Convert HunkStatus to string representation for database storage.
-fn status_to_string(status: HunkStatus) -> &'static str {
+pub fn status_to_string(status: HunkStatus) -> &'static str {
     match status {
         HunkStatus::Unreviewed => "unreviewed",
         HunkStatus::Reviewed => "reviewed",
         HunkStatus::Stale => "stale",
+        HunkStatus::NeedsChanges => "needs_changes",
+        HunkStatus::Skimmed => "skimmed",
+    }
+}
+
+/// Parse a database status string into a HunkStatus.
+pub fn status_from_str(status: &str) -> Result<HunkStatus> {
+    match status {
+        "reviewed" => Ok(HunkStatus::Reviewed),
+        "stale" => Ok(HunkStatus::Stale),
+        "unreviewed" => Ok(HunkStatus::Unreviewed),
+        "needs_changes" => Ok(HunkStatus::NeedsChanges),
+        "skimmed" => Ok(HunkStatus::Skimmed),
+        other => Err(StateError::InvalidStatus(other.to_owned())),
     }
 }
 
@@ -336,8 +2122,9 @@ mod tests {
                 old_count: 1,
                 new_start: 1,
                 new_count: 1,
-                content: "test".to_string(),
+                content: "test".into(),
                 content_hash: "hash1".to_string(),
+                section: String::new(),
                 status: HunkStatus::Unreviewed,
             }],
         }];
@@ -366,17 +2153,38 @@ mod tests {
                 old_count: 1,
                 new_start: 1,
                 new_count: 1,
-                content: "new_content".to_string(),
+                content: "new_content".into(),
                 content_hash: "new_hash".to_string(),
+                section: String::new(),
                 status: HunkStatus::Unreviewed,
             }],
         }];
 
+        // A preview shows the outcome without writing it
+        let preview: Vec<(String, HunkStatus)> = db
+            .synced_hunks("main", &files)
+            .unwrap()
+            .into_iter()
+            .map(|record| (record.content_hash, record.status))
+            .collect();
+        assert_eq!(
+            preview,
+            [
+                ("old_hash".to_string(), HunkStatus::Stale),
+                ("new_hash".to_string(), HunkStatus::Unreviewed),
+            ]
+        );
+        assert_eq!(
+            db.get_status("main", "file.txt", "old_hash").unwrap(),
+            HunkStatus::Reviewed
+        );
+
         db.sync_with_diff("main", &files).unwrap();
 
-        // Old hash should be stale
+        // Old hash should be stale, without anyone being credited for it
         let old_status = db.get_status("main", "file.txt", "old_hash").unwrap();
         assert_eq!(old_status, HunkStatus::Stale);
+        assert_eq!(db.events(None).unwrap().len(), 1);
 
         // New hash should be unreviewed
         let new_status = db.get_status("main", "file.txt", "new_hash").unwrap();
@@ -401,8 +2209,9 @@ mod tests {
                 old_count: 1,
                 new_start: 1,
                 new_count: 1,
-                content: "test".to_string(),
+                content: "test".into(),
                 content_hash: "hash1".to_string(),
+                section: String::new(),
                 status: HunkStatus::Unreviewed,
             }],
         }];
@@ -437,6 +2246,26 @@ mod tests {
         assert_eq!(progress.files_remaining, 2); // file1 has unreviewed, file2 has stale
     }
 
+    #[test]
+    fn needs_changes_is_stored_and_counted() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+
+        db.set_status("main", "file1.txt", "hash1", HunkStatus::NeedsChanges)
+            .unwrap();
+        db.set_status("main", "file1.txt", "hash2", HunkStatus::Reviewed)
+            .unwrap();
+
+        assert_eq!(
+            db.get_status("main", "file1.txt", "hash1").unwrap(),
+            HunkStatus::NeedsChanges
+        );
+        let progress = db.progress("main").unwrap();
+        assert_eq!(progress.total_hunks, 2);
+        assert_eq!(progress.needs_changes, 1);
+        assert_eq!(progress.files_remaining, 1);
+    }
+
     #[test]
     fn reset_clears_state() {
         let dir = tempfile::tempdir().unwrap();
@@ -470,4 +2299,790 @@ mod tests {
         let status = db.get_status("main", "nonexistent.txt", "no_hash").unwrap();
         assert_eq!(status, HunkStatus::Unreviewed);
     }
+
+    #[test]
+    fn assign_replaces_previous_assignee() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("review.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+
+        db.assign("main", "file.txt", "alice").unwrap();
+        db.assign("main", "file.txt", "bob").unwrap();
+
+        let assignments = db.assignments("main").unwrap();
+        assert_eq!(
+            assignments,
+            vec![("file.txt".to_string(), "bob".to_string())]
+        );
+    }
+
+    #[test]
+    fn unsatisfied_assignments_requires_assignee_review() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("review.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+        db.assign("main", "file.txt", "alice").unwrap();
+
+        // Reviewed by someone else: still unsatisfied
+        db.set_reviewer(Some("bob".to_string()));
+        db.set_status("main", "file.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        // Unassigned file: never reported
+        db.set_status("main", "other.txt", "hash2", HunkStatus::Unreviewed)
+            .unwrap();
+
+        let pending = db.unsatisfied_assignments("main").unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].0, "file.txt");
+        assert_eq!(pending[0].2, "alice");
+
+        // Reviewed by the assignee: satisfied
+        db.set_reviewer(Some("alice".to_string()));
+        db.set_status("main", "file.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        assert!(db.unsatisfied_assignments("main").unwrap().is_empty());
+    }
+
+    #[test]
+    fn branch_assignments_apply_to_staged_hunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.assign("abc123..feature", "file.txt", "alice").unwrap();
+        db.assign("abc123..other", "other.txt", "carol").unwrap();
+        db.set_status("HEAD", "file.txt", "hash1", HunkStatus::Unreviewed)
+            .unwrap();
+        db.set_status("HEAD", "other.txt", "hash2", HunkStatus::Unreviewed)
+            .unwrap();
+
+        let refs = db.assignment_refs_in_namespace("feature").unwrap();
+        assert_eq!(refs, vec!["abc123..feature".to_string()]);
+        assert!(db.unsatisfied_assignments("HEAD").unwrap().is_empty());
+        let pending = db.unsatisfied_assignments_from("HEAD", &refs).unwrap();
+        assert_eq!(
+            pending,
+            vec![(
+                "file.txt".to_string(),
+                "hash1".to_string(),
+                "alice".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn hunks_lists_rows_for_base_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("review.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+
+        db.set_status("main", "b.txt", "hash2", HunkStatus::Unreviewed)
+            .unwrap();
+        db.set_status("main", "a.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        db.set_status("other", "c.txt", "hash3", HunkStatus::Reviewed)
+            .unwrap();
+
+        let hunks = db.hunks("main").unwrap();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].file_path, "a.txt");
+        assert_eq!(hunks[0].status, HunkStatus::Reviewed);
+        assert!(hunks[0].reviewed_at.is_some());
+        assert_eq!(hunks[1].status, HunkStatus::Unreviewed);
+    }
+
+    #[test]
+    fn review_ages_cover_reviewed_hunks_of_a_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+
+        db.set_status("main", "a.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        db.set_status("main", "a.txt", "hash2", HunkStatus::Unreviewed)
+            .unwrap();
+        db.set_status("main", "b.txt", "hash3", HunkStatus::Reviewed)
+            .unwrap();
+
+        let ages = db.review_ages("main", "a.txt").unwrap();
+        assert_eq!(ages.len(), 1);
+        assert!(ages["hash1"] < 60);
+
+        let files = db.file_review_ages("main").unwrap();
+        assert_eq!(files.keys().count(), 2);
+        assert!(files["a.txt"] < 60);
+    }
+
+    #[test]
+    fn approvals_within_a_window_record_who_approved() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.set_reviewer(Some("alice".to_string()));
+
+        db.set_status("main", "a.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        db.set_status("main", "a.txt", "hash2", HunkStatus::Reviewed)
+            .unwrap();
+        db.set_status("main", "a.txt", "hash3", HunkStatus::Unreviewed)
+            .unwrap();
+        // Reviewed two days ago
+        db.conn
+            .execute(
+                "UPDATE hunks SET reviewed_at = datetime('now', '-2 days') WHERE content_hash = 'hash2'",
+                [],
+            )
+            .unwrap();
+
+        let approvals = db.approvals_within("main", 3600).unwrap();
+        assert_eq!(approvals.len(), 1);
+        let approval = &approvals[&("a.txt".to_string(), "hash1".to_string())];
+        assert!(approval.age < 60);
+        assert_eq!(approval.reviewed_by.as_deref(), Some("alice"));
+        assert_eq!(db.approvals_within("main", 3 * 86_400).unwrap().len(), 2);
+        assert!(db.approvals_within("other", 3600).unwrap().is_empty());
+    }
+
+    #[test]
+    fn comments_are_scoped_to_base_ref_and_record_author() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("review.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+        db.set_reviewer(Some("alice".to_string()));
+
+        db.add_comment("main", "a.txt", "hash1", "first").unwrap();
+        db.add_comment("main", "a.txt", "hash1", "second").unwrap();
+        db.add_comment("other", "a.txt", "hash1", "elsewhere")
+            .unwrap();
+
+        let comments = db.comments("main").unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].body, "first");
+        assert_eq!(comments[1].body, "second");
+        assert_eq!(comments[0].author.as_deref(), Some("alice"));
+    }
+
+    #[test]
+    fn purge_removes_all_state_for_base_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("review.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+
+        db.set_status("main..gone", "a.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        db.assign("main..gone", "a.txt", "alice").unwrap();
+        db.add_comment("main..gone", "a.txt", "hash1", "note")
+            .unwrap();
+        db.set_status("main..kept", "a.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+
+        db.purge("main..gone").unwrap();
+
+        assert!(db.hunks("main..gone").unwrap().is_empty());
+        assert!(db.assignments("main..gone").unwrap().is_empty());
+        assert!(db.comments("main..gone").unwrap().is_empty());
+        assert_eq!(db.hunks("main..kept").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn adopt_base_ref_moves_state_to_empty_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("review.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+
+        db.set_status("main..feature", "a.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        db.assign("main..feature", "a.txt", "alice").unwrap();
+
+        assert!(db.adopt_base_ref("main..feature", "abc..feature").unwrap());
+        assert!(db.hunks("main..feature").unwrap().is_empty());
+        assert_eq!(db.hunks("abc..feature").unwrap().len(), 1);
+        assert_eq!(db.assignments("abc..feature").unwrap().len(), 1);
+
+        // A key that already has state is left alone
+        db.set_status("main...feature", "b.txt", "hash2", HunkStatus::Reviewed)
+            .unwrap();
+        assert!(!db.adopt_base_ref("main...feature", "abc..feature").unwrap());
+        assert_eq!(db.hunks("main...feature").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn carryover_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("review.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+
+        assert_eq!(db.carryover("feature").unwrap(), None);
+        db.record_carryover("feature", "abc..feature").unwrap();
+        db.record_carryover("feature", "def..feature").unwrap();
+        assert_eq!(
+            db.carryover("feature").unwrap().as_deref(),
+            Some("def..feature")
+        );
+
+        db.clear_carryover("feature").unwrap();
+        assert_eq!(db.carryover("feature").unwrap(), None);
+    }
+
+    #[test]
+    fn merge_queue_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+
+        db.enqueue_merge("feature", "main").unwrap();
+        db.enqueue_merge("fix", "main").unwrap();
+        db.enqueue_merge("feature", "main").unwrap();
+        db.enqueue_merge("port", "release").unwrap();
+        assert_eq!(db.merge_queue("main").unwrap(), vec!["feature", "fix"]);
+
+        assert!(db.dequeue_merge("feature").unwrap());
+        assert!(!db.dequeue_merge("feature").unwrap());
+        assert_eq!(db.merge_queue("main").unwrap(), vec!["fix"]);
+        assert_eq!(db.merge_queue("release").unwrap(), vec!["port"]);
+    }
+
+    #[test]
+    fn reviewed_tips_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+
+        db.record_reviewed_tip("feature", "aaa").unwrap();
+        db.record_reviewed_tip("fix", "bbb").unwrap();
+        db.record_reviewed_tip("feature", "ccc").unwrap();
+        let tips = db.reviewed_tips().unwrap();
+        assert_eq!(tips.len(), 2);
+        assert_eq!(tips["feature"], "ccc");
+
+        db.clear_reviewed_tip("feature").unwrap();
+        assert_eq!(
+            db.reviewed_tips().unwrap().into_keys().collect::<Vec<_>>(),
+            vec!["fix"]
+        );
+    }
+
+    #[test]
+    fn archive_moves_hunks_out_of_the_live_tables() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.set_reviewer(Some("ana".to_string()));
+        db.set_status("abc..feature", "a.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        db.set_status("abc..feature", "a.txt", "hash2", HunkStatus::Unreviewed)
+            .unwrap();
+        db.set_status("abc..other", "b.txt", "hash3", HunkStatus::Reviewed)
+            .unwrap();
+
+        assert_eq!(db.archive("abc..feature", Some("f00d")).unwrap(), 2);
+        assert!(db.hunks("abc..feature").unwrap().is_empty());
+        assert_eq!(db.hunks("abc..other").unwrap().len(), 1);
+
+        let archived = db.archived().unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].base_ref, "abc..feature");
+        assert_eq!(archived[0].merge_sha.as_deref(), Some("f00d"));
+        assert_eq!((archived[0].hunks, archived[0].reviewed), (2, 1));
+        assert_eq!(archived[0].reviewers, vec!["ana"]);
+    }
+
+    #[test]
+    fn undoing_a_bulk_approval_restores_prior_statuses() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.set_reviewer(Some("ana".to_string()));
+        db.set_status("abc..feature", "a.txt", "hash1", HunkStatus::NeedsChanges)
+            .unwrap();
+        db.set_status("abc..feature", "a.txt", "hash2", HunkStatus::Unreviewed)
+            .unwrap();
+        db.set_status("abc..feature", "b.txt", "hash3", HunkStatus::Reviewed)
+            .unwrap();
+        assert!(
+            db.last_bulk_approval(Some("abc..feature"))
+                .unwrap()
+                .is_none()
+        );
+
+        assert_eq!(db.approve_all("abc..feature").unwrap(), 2);
+        let batch = db
+            .last_bulk_approval(Some("abc..feature"))
+            .unwrap()
+            .unwrap();
+        assert_eq!((batch.file_path.as_deref(), batch.hunks), (None, 2));
+
+        assert_eq!(db.undo_bulk_approval(&batch).unwrap(), 2);
+        let status = |hash| db.get_status("abc..feature", "a.txt", hash).unwrap();
+        assert_eq!(status("hash1"), HunkStatus::NeedsChanges);
+        assert_eq!(status("hash2"), HunkStatus::Unreviewed);
+        assert_eq!(
+            db.get_status("abc..feature", "b.txt", "hash3").unwrap(),
+            HunkStatus::Reviewed
+        );
+        assert!(
+            db.last_bulk_approval(Some("abc..feature"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn undoing_a_bulk_approval_keeps_later_reviews() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.set_reviewer(Some("ana".to_string()));
+        db.set_status("abc..feature", "a.txt", "hash1", HunkStatus::Unreviewed)
+            .unwrap();
+        db.set_status("abc..feature", "a.txt", "hash2", HunkStatus::NeedsChanges)
+            .unwrap();
+        assert_eq!(db.approve_all("abc..feature").unwrap(), 2);
+        // Date the batch a minute back, so the changes below come after it
+        db.conn
+            .execute_batch(
+                "UPDATE bulk_approvals SET approved_at = datetime(approved_at, '-1 minute');
+                 UPDATE hunks SET changed_at = datetime(changed_at, '-1 minute');",
+            )
+            .unwrap();
+
+        // hash2 goes stale and is approved again on its own
+        db.set_status("abc..feature", "a.txt", "hash2", HunkStatus::Stale)
+            .unwrap();
+        db.set_status("abc..feature", "a.txt", "hash2", HunkStatus::Reviewed)
+            .unwrap();
+
+        let batch = db
+            .last_bulk_approval(Some("abc..feature"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(db.undo_bulk_approval(&batch).unwrap(), 1);
+        let status = |hash| db.get_status("abc..feature", "a.txt", hash).unwrap();
+        assert_eq!(status("hash1"), HunkStatus::Unreviewed);
+        assert_eq!(status("hash2"), HunkStatus::Reviewed);
+    }
+
+    #[test]
+    fn events_log_reviews_gate_checks_and_merges() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.set_reviewer(Some("ana".to_string()));
+        db.set_status("abc..feature", "a.txt", "hash1", HunkStatus::NeedsChanges)
+            .unwrap();
+        db.set_status("abc..feature", "a.txt", "hash2", HunkStatus::Unreviewed)
+            .unwrap();
+        assert_eq!(db.approve_all("abc..feature").unwrap(), 2);
+        db.record_gate_check("HEAD", false, "0/1 hunks reviewed")
+            .unwrap();
+        db.archive("abc..feature", Some("f00d")).unwrap();
+        db.purge("abc..feature").unwrap();
+
+        let kinds: Vec<_> = db
+            .events(Some("feature"))
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.kind, e.count, e.actor))
+            .collect();
+        let ana = Some("ana".to_string());
+        assert_eq!(
+            kinds,
+            vec![
+                (EventKind::Status(HunkStatus::NeedsChanges), 1, ana.clone()),
+                (EventKind::Status(HunkStatus::Unreviewed), 1, ana.clone()),
+                (EventKind::Approved, 2, ana.clone()),
+                (EventKind::Merged, 2, ana),
+            ]
+        );
+        let gate = db.events(Some("HEAD")).unwrap();
+        assert_eq!(gate[0].kind, EventKind::GateFailed);
+        assert_eq!(gate[0].detail.as_deref(), Some("0/1 hunks reviewed"));
+        assert_eq!(db.events(None).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn snapshot_restore_rolls_back_status_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("review.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+
+        db.set_status("main..feature", "a.txt", "hash1", HunkStatus::Reviewed)
+            .unwrap();
+        db.set_status("main..feature", "a.txt", "hash2", HunkStatus::Unreviewed)
+            .unwrap();
+        assert_eq!(db.save_snapshot("before", "main..feature").unwrap(), 2);
+
+        db.reset("main..feature").unwrap();
+        db.set_status("main..feature", "b.txt", "hash3", HunkStatus::Reviewed)
+            .unwrap();
+
+        assert_eq!(db.restore_snapshot("before", "main..feature").unwrap(), 2);
+        let hunks = db.hunks("main..feature").unwrap();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(
+            db.get_status("main..feature", "a.txt", "hash1").unwrap(),
+            HunkStatus::Reviewed
+        );
+
+        let snapshot = db.snapshot("before").unwrap().unwrap();
+        assert_eq!(snapshot.base_ref, "main..feature");
+        assert_eq!(snapshot.hunks, 2);
+
+        assert!(db.delete_snapshot("before").unwrap());
+        assert!(db.snapshot("before").unwrap().is_none());
+        assert!(!db.delete_snapshot("before").unwrap());
+    }
+
+    #[test]
+    fn snapshot_restore_keeps_auto_approvals_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.auto_approve("main..feature", "Cargo.lock", "hash1", "files")
+            .unwrap();
+        db.conn
+            .execute(
+                "UPDATE hunks SET changed_at = '2026-01-02 03:04:05' WHERE content_hash = 'hash1'",
+                [],
+            )
+            .unwrap();
+        db.save_snapshot("before", "main..feature").unwrap();
+        db.reset("main..feature").unwrap();
+
+        assert_eq!(db.restore_snapshot("before", "main..feature").unwrap(), 1);
+        let hunks = db.hunks("main..feature").unwrap();
+        assert_eq!(hunks[0].status, HunkStatus::Reviewed);
+        assert_eq!(hunks[0].auto_rule.as_deref(), Some("files"));
+        assert_eq!(hunks[0].reviewed_by, None);
+        let changed_at: String = db
+            .conn
+            .query_row(
+                "SELECT changed_at FROM hunks WHERE content_hash = 'hash1'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(changed_at, "2026-01-02 03:04:05");
+    }
+
+    #[test]
+    fn sync_auto_approves_new_hunks_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        let config = toml::from_str("comments = true").unwrap();
+        db.set_auto_approver(AutoApprover::new(&config).unwrap());
+
+        let files = crate::parser::parse_diff(
+            "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-// old\n+// new\n\
+             diff --git a/b.rs b/b.rs\n--- a/b.rs\n+++ b/b.rs\n@@ -1 +1 @@\n-let x = 1;\n+let x = 2;\n",
+        );
+        db.sync_with_diff("HEAD", &files).unwrap();
+
+        let comment_hash = &files[0].hunks[0].content_hash;
+        assert_eq!(
+            db.get_status("HEAD", "a.rs", comment_hash).unwrap(),
+            HunkStatus::Reviewed
+        );
+        assert_eq!(
+            db.auto_rules("HEAD", "a.rs")
+                .unwrap()
+                .get(comment_hash)
+                .map(String::as_str),
+            Some("comments")
+        );
+        let progress = db.progress("HEAD").unwrap();
+        assert_eq!((progress.reviewed, progress.auto_approved), (1, 1));
+
+        // Un-approving clears the flag, and the next sync leaves the hunk alone
+        db.set_status("HEAD", "a.rs", comment_hash, HunkStatus::Unreviewed)
+            .unwrap();
+        db.sync_with_diff("HEAD", &files).unwrap();
+        assert_eq!(
+            db.get_status("HEAD", "a.rs", comment_hash).unwrap(),
+            HunkStatus::Unreviewed
+        );
+        assert!(db.auto_rules("HEAD", "a.rs").unwrap().is_empty());
+    }
+
+    #[test]
+    fn approval_is_inherited_by_the_same_change_in_other_ranges() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        let config = toml::from_str("inherit = true").unwrap();
+        db.set_auto_approver(AutoApprover::new(&config).unwrap());
+
+        // The same change at a different line with different context
+        let main = crate::parser::parse_diff(
+            "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,2 +1,2 @@\n fn a() {}\n-let x = 1;\n+let x = 2;\n",
+        );
+        let backport = crate::parser::parse_diff(
+            "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -9,2 +9,2 @@\n fn b() {}\n-let x = 1;\n+let  x = 2;\n",
+        );
+        let (main_hash, backport_hash) = (
+            &main[0].hunks[0].content_hash,
+            &backport[0].hunks[0].content_hash,
+        );
+        assert_ne!(main_hash, backport_hash);
+
+        // Approving in one range approves the hunk already synced in another
+        db.sync_with_diff("main..fix", &main).unwrap();
+        db.sync_with_diff("rel..backport", &backport).unwrap();
+        db.set_status("main..fix", "a.rs", main_hash, HunkStatus::Reviewed)
+            .unwrap();
+        assert_eq!(
+            db.auto_rules("rel..backport", "a.rs")
+                .unwrap()
+                .get(backport_hash)
+                .map(String::as_str),
+            Some(RULE_INHERITED)
+        );
+
+        // New ranges inherit it on their first sync, but only from a person's review
+        db.sync_with_diff("rel2..backport", &backport).unwrap();
+        assert_eq!(db.progress("rel2..backport").unwrap().auto_approved, 1);
+        db.set_status("main..fix", "a.rs", main_hash, HunkStatus::Unreviewed)
+            .unwrap();
+        db.sync_with_diff("rel3..backport", &backport).unwrap();
+        assert_eq!(
+            db.get_status("rel3..backport", "a.rs", backport_hash)
+                .unwrap(),
+            HunkStatus::Unreviewed
+        );
+    }
+
+    #[test]
+    fn ai_summary_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+
+        assert_eq!(db.ai_summary("hash1").unwrap(), None);
+        let summary = HunkSummary {
+            summary: "Adds a retry".to_string(),
+            risk: None,
+        };
+        db.set_ai_summary("hash1", &summary).unwrap();
+        assert_eq!(db.ai_summary("hash1").unwrap(), Some(summary));
+    }
+
+    #[test]
+    fn comments_and_summaries_are_encrypted_at_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let mut db = ReviewDb::open(&db_path).unwrap();
+        db.add_comment("main", "a.txt", "hash1", "before the key")
+            .unwrap();
+        // 32 bytes of 0x01
+        let key = "AQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQEBAQE=";
+        db.set_cipher(Some(Cipher::new(key).unwrap()));
+        assert_eq!(db.encrypt_plaintext().unwrap(), 1);
+        db.add_comment("main", "a.txt", "hash1", "api key is hunter2")
+            .unwrap();
+        let summary = HunkSummary {
+            summary: "Hardcodes a password".to_string(),
+            risk: Some("Leaks hunter2".to_string()),
+        };
+        db.set_ai_summary("hash1", &summary).unwrap();
+
+        let bodies: Vec<String> = db
+            .conn
+            .prepare("SELECT body FROM comments UNION ALL SELECT risk FROM ai_summaries")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<std::result::Result<_, _>>()
+            .unwrap();
+        assert!(bodies.iter().all(|body| encryption::is_encrypted(body)));
+
+        let comments = db.comments("main").unwrap();
+        assert_eq!(comments[0].body, "before the key");
+        assert_eq!(comments[1].body, "api key is hunter2");
+        assert_eq!(db.ai_summary("hash1").unwrap(), Some(summary));
+
+        let locked = ReviewDb::open(&db_path).unwrap();
+        assert!(matches!(
+            locked.comments("main"),
+            Err(StateError::Encryption(EncryptionError::Locked))
+        ));
+        // Hashes and statuses stay readable for hooks without the key
+        assert!(locked.progress("main").is_ok());
+    }
+
+    #[test]
+    fn reset_namespace_leaves_other_branches() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        for base_ref in [
+            "main..feature",
+            "ignore-ws:abc..feature",
+            "main..other",
+            "HEAD",
+        ] {
+            db.set_status(base_ref, "a.rs", "hash1", HunkStatus::Reviewed)
+                .unwrap();
+        }
+
+        db.add_comment("main..feature", "a.rs", "hash1", "looks off")
+            .unwrap();
+        db.record_carryover("feature", "main..feature").unwrap();
+        for base_ref in ["main..other", "main..feature"] {
+            db.archive(base_ref, None).unwrap();
+            db.set_status(base_ref, "a.rs", "hash1", HunkStatus::Reviewed)
+                .unwrap();
+        }
+
+        let refs = db.base_refs_in_namespace("feature").unwrap();
+        assert_eq!(refs, vec!["ignore-ws:abc..feature", "main..feature"]);
+        assert_eq!(db.reset_many(&refs).unwrap(), 2);
+        assert_eq!(db.list_base_refs().unwrap(), vec!["HEAD", "main..other"]);
+        // Everything else stored for the keys goes too, but the archive is history
+        assert!(db.comments("main..feature").unwrap().is_empty());
+        assert_eq!(db.carryover("feature").unwrap(), None);
+        let archived: i64 = db
+            .conn
+            .query_row("SELECT COUNT(*) FROM archive", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(archived, 2);
+
+        // A key adopted from another branch's spelling moves to its namespace
+        db.adopt_base_ref("main..other", "main..feature").unwrap();
+        assert_eq!(
+            db.base_refs_in_namespace("feature").unwrap(),
+            vec!["main..feature"]
+        );
+
+        assert_eq!(namespace("structural:main...topic"), "topic");
+        assert_eq!(namespace(&mode_key("abc..topic", true, true)), "topic");
+        assert_eq!(namespace("stash:abc"), "stash:abc");
+        assert_eq!(
+            git_range(&mode_key("main..topic", true, false)),
+            Some("main..topic")
+        );
+        assert_eq!(git_range(&mode_key(&stash_key("abc"), false, true)), None);
+        assert_eq!(git_range(&patch_key("abc")), None);
+    }
+
+    #[test]
+    fn recording_a_new_base_commit_returns_the_old_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+
+        assert_eq!(db.record_base_commit("main..topic", "aaa").unwrap(), None);
+        assert_eq!(db.record_base_commit("main..topic", "aaa").unwrap(), None);
+        let (previous, at) = db
+            .record_base_commit("main..topic", "bbb")
+            .unwrap()
+            .unwrap();
+        assert_eq!(previous, "aaa");
+        assert!(at > 0);
+
+        db.purge("main..topic").unwrap();
+        assert_eq!(db.record_base_commit("main..topic", "ccc").unwrap(), None);
+    }
+
+    #[test]
+    fn carried_reviews_keep_their_reviewer() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.record_status(
+            "main..topic",
+            "a.rs",
+            "same",
+            HunkStatus::Reviewed,
+            Some("ana"),
+        )
+        .unwrap();
+        db.record_status(
+            "main..topic",
+            "b.rs",
+            "moved",
+            HunkStatus::Reviewed,
+            Some("ana"),
+        )
+        .unwrap();
+        db.set_status("main..topic", "a.rs", "flagged", HunkStatus::NeedsChanges)
+            .unwrap();
+        for (file, hash) in [("a.rs", "same"), ("c.rs", "moved"), ("a.rs", "flagged")] {
+            db.set_status("release..pick", file, hash, HunkStatus::Unreviewed)
+                .unwrap();
+        }
+
+        assert_eq!(db.carry_reviews("main..topic", "release..pick").unwrap(), 1);
+        let hunks = db.hunks("release..pick").unwrap();
+        let carried: Vec<_> = hunks
+            .iter()
+            .filter(|hunk| hunk.status == HunkStatus::Reviewed)
+            .map(|hunk| (hunk.content_hash.as_str(), hunk.reviewed_by.as_deref()))
+            .collect();
+        assert_eq!(carried, vec![("same", Some("ana"))]);
+        assert_eq!(db.carry_reviews("main..topic", "release..pick").unwrap(), 0);
+
+        let events = db.events(Some("pick")).unwrap();
+        let last = events.last().unwrap();
+        assert_eq!(last.kind, EventKind::Carried);
+        assert_eq!(last.detail.as_deref(), Some("main..topic"));
+    }
+
+    #[test]
+    fn changed_since_lists_hunks_that_became_pending() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        db.set_status("main", "a.rs", "new", HunkStatus::Unreviewed)
+            .unwrap();
+        db.set_status("main", "a.rs", "gone", HunkStatus::Stale)
+            .unwrap();
+        db.set_status("main", "a.rs", "done", HunkStatus::Reviewed)
+            .unwrap();
+        db.conn
+            .execute(
+                "UPDATE hunks SET changed_at = '2020-01-01 00:00:00' WHERE content_hash = 'gone'",
+                [],
+            )
+            .unwrap();
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let changed = db.changed_since("main", now - 60).unwrap();
+        assert_eq!(
+            changed,
+            HashSet::from([("a.rs".to_string(), "new".to_string())])
+        );
+        assert_eq!(db.changed_since("main", 0).unwrap().len(), 2);
+        assert!(db.changed_since("main", now + 60).unwrap().is_empty());
+
+        assert!(db.parse_local_time("2026-10-01 14:30").unwrap().is_some());
+        assert_eq!(db.parse_local_time("v1.2").unwrap(), None);
+    }
+
+    #[test]
+    fn progress_and_sync_queries_use_indices() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = ReviewDb::open(&dir.path().join("review.db")).unwrap();
+
+        for sql in [
+            STATUS_COUNTS_SQL,
+            FILES_REMAINING_SQL,
+            TOTAL_FILES_SQL,
+            AUTO_APPROVED_SQL,
+            NAMESPACE_REFS_SQL,
+            HUNK_STATUS_SQL,
+            SYNC_PATCH_ID_SQL,
+            LIVE_HUNKS_SQL,
+            REVIEWED_ELSEWHERE_SQL,
+        ] {
+            let mut stmt = db
+                .conn
+                .prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+                .unwrap();
+            let args = vec!["main..feature"; stmt.parameter_count()];
+            let plan: Vec<String> = stmt
+                .query_map(rusqlite::params_from_iter(args), |row| row.get(3))
+                .unwrap()
+                .collect::<std::result::Result<_, _>>()
+                .unwrap();
+            assert!(
+                plan.iter()
+                    .any(|step| step.contains("USING") && step.contains("INDEX")),
+                "{} scans the table: {:?}",
+                sql,
+                plan
+            );
+            assert!(
+                !plan.iter().any(|step| step.starts_with("SCAN hunks")),
+                "{} scans the table: {:?}",
+                sql,
+                plan
+            );
+        }
+    }
 }
@@ -0,0 +1,180 @@
+//! Non-interactive sequential review (`step`).
+//!
+//! Prints one unreviewed hunk at a time as a self-contained patch and reads a
+//! single-letter answer per line, so a script, an SSH session without a
+//! terminal, or another tool can drive a review over plain stdin/stdout.
+
+use crate::prompt::{self, Answer, Command};
+use crate::session::{ReviewSession, SessionError};
+use crate::{DiffFile, DiffHunk, HunkStatus};
+use std::io::{BufRead, Write};
+use thiserror::Error;
+
+/// Errors that can occur while stepping through hunks.
+#[derive(Debug, Error)]
+pub enum StepError {
+    #[error(transparent)]
+    Session(#[from] SessionError),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, StepError>;
+
+const COMMANDS: &[Command] = &[
+    Command {
+        keys: &["y", "yes"],
+        answer: Answer::Approve,
+    },
+    Command {
+        keys: &["n", "no"],
+        answer: Answer::NeedsChanges,
+    },
+    Command {
+        keys: &["s", "skip"],
+        answer: Answer::Skip,
+    },
+    Command {
+        keys: &["q", "quit"],
+        answer: Answer::Quit,
+    },
+];
+
+const HELP: &str = "Answer y (approve), n (needs changes), s (skip) or q (quit).";
+
+/// Counts of the answers given while stepping.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StepOutcome {
+    pub approved: usize,
+    pub flagged: usize,
+    pub skipped: usize,
+    /// Unreviewed hunks not answered before quitting.
+    pub remaining: usize,
+}
+
+/// Write `hunk` as a unified diff of its own, with file headers.
+fn write_patch(file: &DiffFile, hunk: &DiffHunk, output: &mut impl Write) -> Result<()> {
+    let path = file.path.display();
+    writeln!(output, "diff --git a/{} b/{}", path, path)?;
+    writeln!(output, "--- a/{}", path)?;
+    writeln!(output, "+++ b/{}", path)?;
+    write!(
+        output,
+        "@@ -{},{} +{},{} @@",
+        hunk.old_start, hunk.old_count, hunk.new_start, hunk.new_count
+    )?;
+    if hunk.section.is_empty() {
+        writeln!(output)?;
+    } else {
+        writeln!(output, " {}", hunk.section.trim())?;
+    }
+    for line in hunk.content.lines() {
+        writeln!(output, "{}", line)?;
+    }
+    Ok(())
+}
+
+/// Step through the unreviewed hunks of `session`.
+///
+/// After each hunk a `[y/n/s/q]` prompt line is written and one line is read:
+/// `y` approves, `n` marks the hunk as needing changes, `s` skips it, and `q`
+/// (or end of input) stops. Any other answer repeats the prompt.
+pub fn run(
+    session: &mut ReviewSession,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<StepOutcome> {
+    let pending = session.unreviewed_hunks();
+    let mut outcome = StepOutcome {
+        remaining: pending.len(),
+        ..Default::default()
+    };
+
+    for (position, &(file_idx, hunk_idx)) in pending.iter().enumerate() {
+        let file = &session.files()[file_idx];
+        let hunk = &file.hunks[hunk_idx];
+        let path = file.path.to_string_lossy().to_string();
+        let hash = hunk.content_hash.clone();
+        write_patch(file, hunk, output)?;
+
+        let question = format!(
+            "Hunk {}/{} [{}]?\n",
+            position + 1,
+            pending.len(),
+            prompt::choices(COMMANDS)
+        );
+        let status = match prompt::ask(COMMANDS, &question, HELP, input, output)? {
+            Some(Answer::Approve) => Some(HunkStatus::Reviewed),
+            Some(Answer::NeedsChanges) => Some(HunkStatus::NeedsChanges),
+            Some(Answer::Skip) => None,
+            _ => return Ok(outcome),
+        };
+
+        match status {
+            Some(status) => {
+                session.set_status(&path, &hash, status)?;
+                if status == HunkStatus::Reviewed {
+                    outcome.approved += 1;
+                } else {
+                    outcome.flagged += 1;
+                }
+            }
+            None => outcome.skipped += 1,
+        }
+        outcome.remaining -= 1;
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+-old
++new
+@@ -10 +10 @@ fn main
+-ten
++TEN
+diff --git a/b.txt b/b.txt
+--- a/b.txt
++++ b/b.txt
+@@ -1 +1 @@
+-b
++B
+";
+
+    #[test]
+    fn answers_set_statuses_until_quit() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        let mut session = ReviewSession::from_diff(dir.path(), "main", DIFF).unwrap();
+
+        let mut input = "maybe\nY\nn\nq\n".as_bytes();
+        let mut output = Vec::new();
+        let outcome = run(&mut session, &mut input, &mut output).unwrap();
+        assert_eq!(
+            outcome,
+            StepOutcome {
+                approved: 1,
+                flagged: 1,
+                skipped: 0,
+                remaining: 1,
+            }
+        );
+
+        let text = String::from_utf8(output).unwrap();
+        assert!(text.starts_with(
+            "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1,1 +1,1 @@\n-old\n+new\nHunk 1/3 [y/n/s/q]?\nAnswer"
+        ));
+        assert!(text.contains("@@ -10,1 +10,1 @@ fn main\n-ten\n+TEN\nHunk 2/3"));
+        assert!(text.ends_with("+B\nHunk 3/3 [y/n/s/q]?\n"));
+
+        assert_eq!(session.files()[0].hunks[0].status, HunkStatus::Reviewed);
+        assert_eq!(session.files()[0].hunks[1].status, HunkStatus::NeedsChanges);
+        assert_eq!(session.unreviewed_hunks(), vec![(1, 0)]);
+    }
+}
@@ -0,0 +1,402 @@
+//! Cross-references between hunks that touch the same identifiers (`*` in the TUI).
+//!
+//! Each hunk is indexed by the identifiers on its added and removed lines, so
+//! a renamed function's definition links to its call sites and a changed
+//! struct to the code that builds it. The index is purely lexical: keywords
+//! of common languages and identifiers shorter than [`MIN_SYMBOL_LEN`] are
+//! ignored, and nothing is resolved by scope.
+//!
+//! Within a file, a hunk depends on another when it uses a name the other
+//! defines (the identifier after `fn`, `struct`, `def`, `class`, ... on an
+//! added line), so the definition can be read first.
+
+use crate::DiffFile;
+use std::collections::{BTreeSet, HashMap};
+
+/// Shortest identifier indexed; shorter ones (`i`, `id`, `ok`) link too much.
+pub const MIN_SYMBOL_LEN: usize = 3;
+
+/// Keywords and literals of common languages, which every hunk shares.
+const KEYWORDS: &[&str] = &[
+    "and",
+    "async",
+    "await",
+    "bool",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "crate",
+    "def",
+    "default",
+    "defer",
+    "delete",
+    "dyn",
+    "elif",
+    "else",
+    "enum",
+    "except",
+    "export",
+    "extends",
+    "extern",
+    "false",
+    "final",
+    "finally",
+    "for",
+    "from",
+    "func",
+    "function",
+    "impl",
+    "import",
+    "int",
+    "interface",
+    "let",
+    "loop",
+    "match",
+    "mod",
+    "mut",
+    "new",
+    "nil",
+    "None",
+    "not",
+    "null",
+    "package",
+    "pass",
+    "private",
+    "protected",
+    "pub",
+    "public",
+    "raise",
+    "ref",
+    "return",
+    "self",
+    "Self",
+    "static",
+    "str",
+    "String",
+    "struct",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "trait",
+    "True",
+    "False",
+    "true",
+    "try",
+    "type",
+    "unsafe",
+    "use",
+    "var",
+    "void",
+    "where",
+    "while",
+    "with",
+    "yield",
+];
+
+/// Keywords introducing the name they are followed by. `let` and `var` are
+/// left out: local variables would link unrelated functions.
+const DEFINERS: &[&str] = &[
+    "class",
+    "const",
+    "def",
+    "enum",
+    "fn",
+    "func",
+    "function",
+    "interface",
+    "macro_rules",
+    "static",
+    "struct",
+    "trait",
+    "type",
+];
+
+/// Identifiers on a line of code, in order, including keywords.
+fn identifiers(line: &str) -> impl Iterator<Item = &str> {
+    line.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| word.starts_with(|c: char| c.is_alphabetic() || c == '_'))
+}
+
+/// Symbols a hunk touches: the identifiers on its added and removed lines,
+/// without keywords and short names.
+pub fn hunk_symbols(content: &str) -> BTreeSet<String> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix('+').or_else(|| line.strip_prefix('-')))
+        .flat_map(identifiers)
+        .filter(|word| word.chars().count() >= MIN_SYMBOL_LEN && !KEYWORDS.contains(word))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Names a hunk defines: identifiers following a definition keyword on its
+/// added lines, such as `parse` in `+pub fn parse(`.
+pub fn defined_symbols(content: &str) -> BTreeSet<String> {
+    let mut defined = BTreeSet::new();
+    for line in content.lines().filter_map(|line| line.strip_prefix('+')) {
+        let mut after_definer = false;
+        for word in identifiers(line) {
+            if DEFINERS.contains(&word) {
+                after_definer = true;
+            } else if after_definer && !KEYWORDS.contains(&word) {
+                // `static mut X`, `pub const fn f`: the name is the first non-keyword
+                if word.chars().count() >= MIN_SYMBOL_LEN {
+                    defined.insert(word.to_string());
+                }
+                after_definer = false;
+            }
+        }
+    }
+    defined
+}
+
+/// Another hunk sharing symbols with the one asked about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedHunk {
+    pub file_idx: usize,
+    pub hunk_idx: usize,
+    /// The symbols both hunks touch, sorted.
+    pub symbols: Vec<String>,
+}
+
+/// A hunk of the same file defining names that another hunk uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub hunk_idx: usize,
+    /// The names used, sorted.
+    pub symbols: Vec<String>,
+}
+
+/// The symbols of every hunk in a diff, for finding related hunks.
+#[derive(Debug, Default)]
+pub struct SymbolIndex {
+    /// Symbols of each hunk, by file and hunk index.
+    hunks: Vec<Vec<BTreeSet<String>>>,
+    /// The hunks touching each symbol, in diff order.
+    by_symbol: HashMap<String, Vec<(usize, usize)>>,
+    /// Hunks of the same file each hunk depends on, by file and hunk index.
+    dependencies: Vec<Vec<Vec<Dependency>>>,
+}
+
+impl SymbolIndex {
+    /// Index the symbols of every hunk of a diff.
+    pub fn new(files: &[DiffFile]) -> Self {
+        let mut index = Self::default();
+        for (file_idx, file) in files.iter().enumerate() {
+            let mut symbols = Vec::with_capacity(file.hunks.len());
+            for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+                let hunk_symbols = hunk_symbols(&hunk.content);
+                for symbol in &hunk_symbols {
+                    index
+                        .by_symbol
+                        .entry(symbol.clone())
+                        .or_default()
+                        .push((file_idx, hunk_idx));
+                }
+                symbols.push(hunk_symbols);
+            }
+            let defined: Vec<BTreeSet<String>> = file
+                .hunks
+                .iter()
+                .map(|hunk| defined_symbols(&hunk.content))
+                .collect();
+            index
+                .dependencies
+                .push(file_dependencies(&symbols, &defined));
+            index.hunks.push(symbols);
+        }
+        index
+    }
+
+    /// Hunks of the same file defining names this hunk uses, in diff order.
+    pub fn dependencies(&self, file_idx: usize, hunk_idx: usize) -> &[Dependency] {
+        self.dependencies
+            .get(file_idx)
+            .and_then(|f| f.get(hunk_idx))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Hunk indices of a file in a suggested reading order: diff order, except
+    /// that a hunk comes after the hunks it depends on. Cycles are broken by
+    /// taking the first remaining hunk in diff order.
+    pub fn review_order(&self, file_idx: usize) -> Vec<usize> {
+        let Some(dependencies) = self.dependencies.get(file_idx) else {
+            return Vec::new();
+        };
+        let mut placed = vec![false; dependencies.len()];
+        let mut order = Vec::with_capacity(dependencies.len());
+        while order.len() < dependencies.len() {
+            let ready = |i: usize| dependencies[i].iter().all(|dep| placed[dep.hunk_idx]);
+            let next = (0..dependencies.len())
+                .filter(|&i| !placed[i])
+                .find(|&i| ready(i))
+                .or_else(|| placed.iter().position(|&done| !done))
+                .expect("an unplaced hunk remains");
+            placed[next] = true;
+            order.push(next);
+        }
+        order
+    }
+
+    /// Other hunks touching a symbol that this hunk touches, those sharing the
+    /// most symbols first, then in diff order.
+    pub fn related(&self, file_idx: usize, hunk_idx: usize) -> Vec<RelatedHunk> {
+        let Some(symbols) = self.hunks.get(file_idx).and_then(|f| f.get(hunk_idx)) else {
+            return Vec::new();
+        };
+        let mut shared: HashMap<(usize, usize), Vec<String>> = HashMap::new();
+        for symbol in symbols {
+            for &other in &self.by_symbol[symbol] {
+                if other != (file_idx, hunk_idx) {
+                    shared.entry(other).or_default().push(symbol.clone());
+                }
+            }
+        }
+        let mut related: Vec<RelatedHunk> = shared
+            .into_iter()
+            .map(|((file_idx, hunk_idx), symbols)| RelatedHunk {
+                file_idx,
+                hunk_idx,
+                symbols,
+            })
+            .collect();
+        related.sort_by(|a, b| {
+            b.symbols
+                .len()
+                .cmp(&a.symbols.len())
+                .then((a.file_idx, a.hunk_idx).cmp(&(b.file_idx, b.hunk_idx)))
+        });
+        related
+    }
+}
+
+/// For each hunk of a file, the other hunks defining a name it touches but
+/// does not define itself.
+fn file_dependencies(
+    symbols: &[BTreeSet<String>],
+    defined: &[BTreeSet<String>],
+) -> Vec<Vec<Dependency>> {
+    (0..symbols.len())
+        .map(|hunk_idx| {
+            (0..defined.len())
+                .filter(|&other| other != hunk_idx)
+                .filter_map(|other| {
+                    let used: Vec<String> = symbols[hunk_idx]
+                        .intersection(&defined[other])
+                        .filter(|symbol| !defined[hunk_idx].contains(*symbol))
+                        .cloned()
+                        .collect();
+                    (!used.is_empty()).then_some(Dependency {
+                        hunk_idx: other,
+                        symbols: used,
+                    })
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_diff;
+
+    #[test]
+    fn hunks_are_linked_by_the_identifiers_they_change() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+-pub fn parse_header(line: &str) -> Option<Header> {
++pub fn read_header(line: &str) -> Option<Header> {
+     line.strip_prefix(MARKER)
+ }
+@@ -40 +40 @@
+-let x = 1;
++let x = 2;
+diff --git a/src/main.rs b/src/main.rs
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -7 +7 @@
+-    let header = parse_header(&line)?;
++    let header = read_header(&line)?;
+@@ -20 +20 @@
+-    let header: Option<Header> = None;
++    let header: Option<Header> = Some(Header::default());
+";
+        let files = parse_diff(diff);
+        assert_eq!(
+            hunk_symbols(&files[0].hunks[0].content),
+            ["Header", "Option", "line", "parse_header", "read_header"]
+                .map(String::from)
+                .into()
+        );
+        assert!(hunk_symbols(&files[0].hunks[1].content).is_empty());
+
+        let index = SymbolIndex::new(&files);
+        let related = index.related(1, 0);
+        assert_eq!(
+            related,
+            vec![
+                RelatedHunk {
+                    file_idx: 0,
+                    hunk_idx: 0,
+                    symbols: ["line", "parse_header", "read_header"]
+                        .map(String::from)
+                        .into(),
+                },
+                RelatedHunk {
+                    file_idx: 1,
+                    hunk_idx: 1,
+                    symbols: vec!["header".to_string()],
+                },
+            ]
+        );
+        assert!(index.related(0, 1).is_empty());
+        assert!(index.related(5, 0).is_empty());
+    }
+
+    #[test]
+    fn hunks_using_a_definition_are_read_after_it() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -10 +10 @@
+-    let total = sum(&items);
++    let total = checked_sum(&items)?;
+@@ -30 +30,2 @@
++    let limit = MAX_ITEMS;
++    let again = checked_sum(&items)?;
+@@ -50,0 +51,4 @@
++pub const MAX_ITEMS: usize = 64;
++pub fn checked_sum(items: &[u64]) -> Option<u64> {
++    items.iter().try_fold(0u64, |acc, &x| acc.checked_add(x))
++}
+";
+        let files = parse_diff(diff);
+        assert_eq!(
+            defined_symbols(&files[0].hunks[2].content),
+            ["MAX_ITEMS", "checked_sum"].map(String::from).into()
+        );
+
+        let index = SymbolIndex::new(&files);
+        assert_eq!(
+            index.dependencies(0, 1),
+            [Dependency {
+                hunk_idx: 2,
+                symbols: vec!["MAX_ITEMS".to_string(), "checked_sum".to_string()],
+            }]
+        );
+        assert_eq!(index.dependencies(0, 0)[0].hunk_idx, 2);
+        assert!(index.dependencies(0, 2).is_empty());
+        assert!(index.dependencies(3, 0).is_empty());
+        assert_eq!(index.review_order(0), vec![2, 0, 1]);
+    }
+}
@@ -0,0 +1,146 @@
+//! Terminal columns of diff text: tab stops and wide characters.
+//!
+//! Terminals draw CJK characters and most emoji two columns wide, combining
+//! marks zero wide, and a tab up to the next tab stop, so counting `char`s
+//! misaligns anything laid out in columns. Code that turns diff text into
+//! cells (highlighting, whitespace markers, sideways scrolling) measures it
+//! here. Tab stops are `[layout] tab_width` columns apart and count from the
+//! start of the code, after the `+`/`-`/` ` diff marker.
+
+use unicode_width::UnicodeWidthChar;
+
+/// Columns between tab stops unless `[layout] tab_width` says otherwise.
+pub const DEFAULT_TAB_WIDTH: u16 = 4;
+
+/// Columns `c` takes up on a terminal, ignoring tabs.
+pub fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// How text is laid out in columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextLayout {
+    tab_width: usize,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self::new(DEFAULT_TAB_WIDTH)
+    }
+}
+
+impl TextLayout {
+    /// Layout with tab stops every `tab_width` columns (at least one).
+    pub fn new(tab_width: u16) -> Self {
+        Self {
+            tab_width: usize::from(tab_width.max(1)),
+        }
+    }
+
+    /// Columns a tab starting at `column` spans: up to the next tab stop.
+    pub fn tab_span(&self, column: usize) -> usize {
+        self.tab_width - column % self.tab_width
+    }
+
+    /// Columns `c` takes up when drawn at `column`.
+    pub fn width_at(&self, c: char, column: usize) -> usize {
+        if c == '\t' {
+            self.tab_span(column)
+        } else {
+            char_width(c)
+        }
+    }
+
+    /// Columns `text` takes up when drawn from `column`.
+    pub fn width(&self, text: &str, column: usize) -> usize {
+        text.chars()
+            .fold(column, |at, c| at + self.width_at(c, at))
+            .saturating_sub(column)
+    }
+
+    /// `text` drawn from `*column` with its tabs replaced by spaces up to the
+    /// next tab stop. Advances `column` past it.
+    pub fn expand_tabs(&self, text: &str, column: &mut usize) -> String {
+        let mut expanded = String::with_capacity(text.len());
+        for c in text.chars() {
+            let width = self.width_at(c, *column);
+            if c == '\t' {
+                expanded.extend(std::iter::repeat_n(' ', width));
+            } else {
+                expanded.push(c);
+            }
+            *column += width;
+        }
+        expanded
+    }
+}
+
+/// The part of `text` that falls between columns `from` and `to` when drawn
+/// from column `start` with its tabs already expanded. The visible part of a
+/// wide character cut at either edge is drawn as spaces.
+pub fn clip(text: &str, start: usize, from: usize, to: usize) -> String {
+    let mut clipped = String::new();
+    let mut column = start;
+    for c in text.chars() {
+        let end = column + char_width(c);
+        if column >= from && end <= to {
+            clipped.push(c);
+        } else if end > from && column < to {
+            clipped.extend(std::iter::repeat_n(' ', end.min(to) - column.max(from)));
+        }
+        column = end;
+        if column >= to {
+            break;
+        }
+    }
+    clipped
+}
+
+/// Truncate `text` from the left to at most `max` columns, marking the cut with `…`.
+pub fn truncate_start(text: &str, max: usize) -> String {
+    if text.chars().map(char_width).sum::<usize>() <= max {
+        return text.to_string();
+    }
+    let mut width = 0;
+    let start = text
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| {
+            width += char_width(c);
+            width < max
+        })
+        .last()
+        .map_or(text.len(), |(i, _)| i);
+    format!("…{}", &text[start..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_characters_and_tabs_take_their_columns() {
+        let layout = TextLayout::new(4);
+        assert_eq!(layout.width("abc", 0), 3);
+        assert_eq!(layout.width("日本", 0), 4);
+        assert_eq!(layout.width("e\u{301}", 0), 1);
+        assert_eq!(layout.width("a\tb", 0), 5);
+        assert_eq!(layout.width("\t", 2), 2);
+
+        let mut column = 0;
+        assert_eq!(layout.expand_tabs("ab\tc", &mut column), "ab  c");
+        assert_eq!(column, 5);
+        assert_eq!(TextLayout::new(8).expand_tabs("\tx", &mut 0), "        x");
+    }
+
+    #[test]
+    fn clipping_cuts_wide_characters_into_spaces() {
+        assert_eq!(clip("ab日本cd", 0, 2, 6), "日本");
+        assert_eq!(clip("ab日本cd", 0, 3, 7), " 本c");
+        assert_eq!(clip("ab日本cd", 0, 0, 3), "ab ");
+        assert_eq!(clip("日本cd", 2, 3, usize::MAX), " 本cd");
+        assert_eq!(truncate_start("src/日本/main.rs", 10), "…/main.rs");
+        assert_eq!(truncate_start("src/日本/main.rs", 12), "…本/main.rs");
+        assert_eq!(truncate_start("a.rs", 10), "a.rs");
+    }
+}
@@ -1,7 +1,9 @@
 use anyhow::{Context, Result};
 use crossterm::{
+    cursor,
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
     execute,
+    style::{Attribute, Print, ResetColor, SetAttribute, SetForegroundColor},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{
@@ -10,13 +12,34 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, Wrap},
+    widgets::{
+        Block, Borders, Cell, Clear, Gauge, List, ListItem, ListState, Paragraph, Row, Table, Wrap,
+    },
 };
+use std::collections::{HashMap, HashSet};
 use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::time::{Duration, Instant};
 
-use crate::dashboard::Dashboard;
-use crate::{git, parser, DiffFile, HunkStatus, state::ReviewDb};
+use crate::ai::{self, HunkSummary};
+use crate::check::CheckRun;
+use crate::config::Config;
+use crate::dashboard::{Dashboard, RepoDashboard, TipChange};
+use crate::filter::FileFilter;
+use crate::highlight::{HighlightCache, Highlighter};
+use crate::keymap::{Action, Key, KeyName, Keymap, View};
+use crate::moved::MoveIndex;
+use crate::palette::{Palette, PaletteColor, StatusCounts, status_symbol, summary_status};
+use crate::preview::{self, BlockCell, GraphicsProtocol};
+use crate::priority::{self, Prioritizer};
+use crate::report::format_age;
+use crate::session::BaseMove;
+use crate::state::{CommentRecord, ReviewDb};
+use crate::symbols::{RelatedHunk, SymbolIndex};
+use crate::textlayout;
+use crate::{DiffFile, DiffHunk, HunkStatus, git, parser};
 
 /// Filter mode for displaying hunks.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -24,21 +47,237 @@ pub enum FilterMode {
     All,
     Unreviewed,
     Stale,
+    /// Skimmed hunks, for a second, full pass.
+    Skimmed,
+    /// Hunks bookmarked to come back to.
+    Bookmarked,
+    /// Hunks in files affected by the base moving (`:affected`).
+    BaseMoved,
 }
 
+/// Narrowest and widest the file list can be made with `<`/`>`, in percent,
+/// and how much each press changes it. `[layout] file_list` has the same bounds.
+const FILE_LIST_MIN: u16 = 10;
+const FILE_LIST_MAX: u16 = 90;
+const FILE_LIST_STEP: i16 = 5;
+
+/// Columns long lines move per press of Left/Right when they don't wrap.
+const HORIZONTAL_STEP: usize = 8;
+
+/// Commands of the `:` prompt and what they do, for the help.
+const COMMANDS: &[(&str, &str)] = &[
+    (":only rs,toml", "Only show files with these extensions"),
+    (":exclude md", "Hide files with these extensions"),
+    (":all", "Show the files of every extension again"),
+    (
+        ":affected",
+        "Show files changed by the base moving since last time",
+    ),
+    (":stage", "Stage the reviewed hunks of the working tree"),
+];
+
+/// A line of the help: keys, what they do, and the action's `[keys]` name.
+type HelpRow = (String, &'static str, &'static str);
+
 /// View mode for the TUI.
 #[derive(Debug, Clone)]
 pub enum ViewMode {
     Dashboard,
-    HunkReview { branch: String, base_ref: String },
+    /// Branches needing review across several repositories (`dashboard --repos`).
+    Repos,
+    HunkReview {
+        branch: String,
+        base_ref: String,
+    },
 }
 
 /// Confirmation action for bulk operations.
 #[derive(Debug, Clone)]
 enum ConfirmAction {
-    ApproveAllFile { file_idx: usize },
+    ApproveAllFile {
+        file_idx: usize,
+    },
     ApproveAll,
-    MergeBranch { branch: String },
+    /// Unreviewed hunks (file and hunk index) whose changes match `pattern`.
+    ApproveMatching {
+        pattern: String,
+        hunks: Vec<(usize, usize)>,
+    },
+    MergeBranch {
+        branch: String,
+    },
+    ApproveBranches {
+        branches: Vec<String>,
+    },
+    DeleteBranches {
+        branches: Vec<String>,
+    },
+    /// A queued branch became ready; declining takes it off the queue.
+    MergeQueued {
+        branch: String,
+    },
+}
+
+/// Purpose of the active text prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PromptKind {
+    CommitMessage,
+    Comment,
+    ApprovePattern,
+    FilterPattern,
+    Command,
+    /// Typed confirmation of approving this many unreviewed hunks at once.
+    ApproveAll(usize),
+}
+
+/// Single-line text input shown as a modal prompt.
+#[derive(Debug, Clone)]
+struct Prompt {
+    kind: PromptKind,
+    buffer: String,
+}
+
+impl Prompt {
+    fn new(kind: PromptKind) -> Self {
+        Self {
+            kind,
+            buffer: String::new(),
+        }
+    }
+}
+
+/// What the caller should do after the TUI exits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitAction {
+    /// Nothing further; the user quit.
+    Quit,
+    /// Run `git-review commit` (chosen from the quit summary when the gate passes).
+    Commit,
+}
+
+/// Statistics for the current hunk review session, shown on quit.
+#[derive(Debug, Clone)]
+struct SessionStats {
+    started: Instant,
+    initial_reviewed: usize,
+    stale_discovered: usize,
+}
+
+impl SessionStats {
+    /// Start a session given progress before and after syncing with the diff.
+    fn begin(before: &crate::ReviewProgress, after: &crate::ReviewProgress) -> Self {
+        Self {
+            started: Instant::now(),
+            initial_reviewed: after.reviewed,
+            stale_discovered: after.stale.saturating_sub(before.stale),
+        }
+    }
+}
+
+/// Output pane of the check command, or of the history of a hunk's lines (`L`).
+struct CheckPane {
+    run: CheckRun,
+    /// First output line shown; `None` follows the end of the output.
+    scroll: Option<usize>,
+    /// First line shown when scrolled to the end, as of the last render.
+    bottom: usize,
+    /// The lines whose `git log -L` history the pane shows, if it isn't the check command's.
+    history: Option<String>,
+}
+
+/// Timeline of a branch's review events (`H`).
+struct TimelinePane {
+    branch: String,
+    lines: Vec<String>,
+    /// First line shown; `None` keeps the most recent events in view.
+    scroll: Option<usize>,
+    /// First line shown when scrolled to the end, as of the last render.
+    bottom: usize,
+}
+
+/// Keybinding reference (`?`), generated from the keymap.
+#[derive(Default)]
+struct HelpOverlay {
+    /// Only actions whose keys, name, or description contain this are listed.
+    query: String,
+    /// Keys edit the query rather than scroll (`/`).
+    searching: bool,
+    /// First line shown.
+    scroll: usize,
+}
+
+/// Hunks touching the same symbols as the one they were opened from (`*`).
+struct RelatedPane {
+    hunks: Vec<RelatedHunk>,
+    selected: usize,
+}
+
+/// Model summaries of hunks, requested one at a time in the background.
+#[derive(Default)]
+struct AiSummaries {
+    /// Endpoint the user opted in with; summaries are off without one.
+    endpoint: Option<ai::AiEndpoint>,
+    /// Summary of each hunk asked about so far by content hash, or why there is none.
+    done: HashMap<String, std::result::Result<HunkSummary, String>>,
+    /// Content hash and result channel of the request in flight.
+    pending: Option<(String, Receiver<ai::Result<HunkSummary>>)>,
+}
+
+/// Blame of a hunk's content lines: who last touched each pre-image line,
+/// `None` for added lines.
+type HunkBlame = Vec<Option<git::BlameLine>>;
+
+/// Blame cache key: base ref, file path, and content hash.
+type BlameKey = (String, String, String);
+
+/// `git blame` annotations for hunks (`b`), loaded one at a time in the background.
+#[derive(Default)]
+struct BlameOverlay {
+    /// Blame of each hunk asked about so far, or why there is none.
+    done: HashMap<BlameKey, std::result::Result<HunkBlame, String>>,
+    /// Key and result channel of the blame in flight.
+    pending: Option<(BlameKey, Receiver<std::result::Result<HunkBlame, String>>)>,
+}
+
+/// Before and after images of a binary image hunk.
+struct ImagePreview {
+    old: Option<PreviewImage>,
+    new: Option<PreviewImage>,
+}
+
+/// One side of an image preview: the raw file plus block art when it could be decoded.
+struct PreviewImage {
+    bytes: Vec<u8>,
+    art: Option<Vec<Vec<BlockCell>>>,
+}
+
+impl PreviewImage {
+    /// Largest block art rendered inline, in cells.
+    const ART_COLS: usize = 80;
+    const ART_ROWS: usize = 20;
+
+    fn new(bytes: Vec<u8>) -> Self {
+        let art = preview::decode_png(&bytes)
+            .ok()
+            .map(|image| preview::block_art(&image, Self::ART_COLS, Self::ART_ROWS));
+        Self { bytes, art }
+    }
+}
+
+impl ImagePreview {
+    /// Load both sides of an image hunk. The new side falls back to the working
+    /// tree file, since diffs against the working tree name blobs git hasn't stored.
+    fn load(path: &std::path::Path, old: Option<&str>, new: Option<&str>) -> Self {
+        let read = |id: Option<&str>| id.and_then(|id| git::read_blob(id).ok());
+        let new_bytes = read(new).or_else(|| {
+            new.and_then(|_| git::find_repo_root().ok())
+                .and_then(|root| std::fs::read(root.join(path)).ok())
+        });
+        Self {
+            old: read(old).map(PreviewImage::new),
+            new: new_bytes.map(PreviewImage::new),
+        }
+    }
 }
 
 /// Application state for the TUI.
@@ -49,15 +288,89 @@ pub struct App {
     selected_file: usize,
     selected_hunk: usize,
     filter: FilterMode,
+    /// Only show hunks whose content or file path matches (`f`), on top of `filter`.
+    pattern: Option<regex::Regex>,
+    /// Only show files with these extensions (`--only`/`--exclude`, `:only`).
+    file_filter: FileFilter,
     should_quit: bool,
-    show_help: bool,
+    /// Keybinding reference (`?`).
+    help: Option<HelpOverlay>,
+    /// Show the diff-stat overview instead of the file list and hunk detail.
+    show_stat: bool,
+    /// Draw tabs and trailing whitespace in hunk content as colored markers.
+    show_whitespace: bool,
+    /// Wrap long lines in the hunk detail instead of cutting them off (`W`).
+    wrap: bool,
+    /// Columns of code scrolled out of view on the left when lines don't wrap.
+    scroll_x: usize,
+    /// List the selected file's hunks in the left panel instead of the files.
+    show_hunk_list: bool,
+    /// Fold reviewed hunks to one line and show all pending hunks of the file together.
+    condensed: bool,
+    /// Hide the file list so the diff gets the full width (`Z`).
+    zen: bool,
+    /// Walk files and hunks in review priority order instead of diff order.
+    priority_order: bool,
+    /// Rules behind the priority order, from `[priority]`.
+    prioritizer: Prioritizer,
+    /// Key bindings, from the defaults and `[keys]`.
+    keymap: Keymap,
+    /// Output pane of the configured check command (`!`).
+    check: Option<CheckPane>,
+    /// Review history of a branch (`H`).
+    timeline: Option<TimelinePane>,
+    /// Hunks related to the current one by the symbols they touch (`*`).
+    related: Option<RelatedPane>,
+    /// Model summaries shown above each hunk once the user opted in.
+    ai: AiSummaries,
+    /// Show who last touched each pre-image line next to the hunk (`b`).
+    show_blame: bool,
+    blame: BlameOverlay,
+    /// Root of the repository under review, for linking file paths.
+    repo_root: Option<PathBuf>,
+    /// Range and file waiting to be opened in `git difftool` outside the TUI (`o`).
+    pending_difftool: Option<(String, String)>,
     scroll_offset: u16,
-    highlighter: crate::highlight::Highlighter,
+    /// Scroll offset of each hunk scrolled away from its top this session, by
+    /// file and content hash, so going back to a hunk lands where it was left.
+    scroll_positions: HashMap<(String, String), u16>,
+    /// Furthest the hunk detail scrolls, as of the last time it was drawn (`End`).
+    max_scroll: u16,
+    highlighter: Arc<Highlighter>,
+    /// Highlighted hunks, precomputed in the background (see `prefetch_highlights`).
+    highlight_cache: HighlightCache,
+    /// Loaded image previews for binary image hunks, keyed by content hash.
+    image_previews: HashMap<String, ImagePreview>,
+    /// Graphics escape sequence waiting to be shown outside the TUI (`p`).
+    full_preview: Option<String>,
+    /// Added and removed lines of the diff, for coloring moved code.
+    moved: MoveIndex,
+    /// Identifiers touched by each hunk, for cross-referencing hunks.
+    symbols: SymbolIndex,
     confirm_action: Option<ConfirmAction>,
+    prompt: Option<Prompt>,
     pub view_mode: ViewMode,
     pub dashboard: Option<Dashboard>,
+    /// Multi-repo dashboard, kept while reviewing a branch opened from it.
+    repos: Option<RepoDashboard>,
     status_message: Option<(String, Instant)>,
     last_refresh: Instant,
+    /// When the last `[dashboard] fetch_interval` fetch started.
+    last_fetch: Option<Instant>,
+    /// A fetch running in the background.
+    fetch: Option<Receiver<std::result::Result<(), String>>>,
+    /// File path -> assigned reviewer for the current base ref.
+    assignees: HashMap<String, String>,
+    /// Review comments for the current base ref.
+    comments: Vec<CommentRecord>,
+    /// Bookmarked hunks of the current base ref, as `(file_path, content_hash)`.
+    bookmarks: HashSet<(String, String)>,
+    /// How the review's base moved since it was last opened, if it did.
+    base_move: Option<BaseMove>,
+    config: Config,
+    session: Option<SessionStats>,
+    show_summary: bool,
+    exit_action: ExitAction,
 }
 
 impl App {
@@ -70,8 +383,10 @@ impl App {
         base_ref: String,
     ) -> Result<Self> {
         // Sync files with database
+        let before = db.progress(&base_ref).context("Failed to read progress")?;
         db.sync_with_diff(&base_ref, &files)
             .context("Failed to sync with database")?;
+        let after = db.progress(&base_ref).context("Failed to read progress")?;
 
         // Update file hunks with database status
         let mut files = files;
@@ -84,6 +399,13 @@ impl App {
             }
         }
 
+        let assignees = load_assignees(&db, &base_ref);
+        let comments = db.comments(&base_ref).unwrap_or_default();
+        let bookmarks = db.bookmarks(&base_ref).unwrap_or_default();
+        let highlighter = Arc::new(Highlighter::new());
+        let moved = MoveIndex::new(&files);
+        let symbols = SymbolIndex::new(&files);
+
         Ok(Self {
             files,
             db,
@@ -91,18 +413,57 @@ impl App {
             selected_file: 0,
             selected_hunk: 0,
             filter: FilterMode::All,
+            pattern: None,
+            file_filter: FileFilter::default(),
             should_quit: false,
-            show_help: false,
+            help: None,
+            show_stat: false,
+            show_whitespace: false,
+            wrap: true,
+            scroll_x: 0,
+            show_hunk_list: false,
+            priority_order: false,
+            prioritizer: Prioritizer::default(),
+            keymap: Keymap::default(),
+            condensed: false,
+            zen: false,
+            check: None,
+            timeline: None,
+            related: None,
+            ai: AiSummaries::default(),
+            show_blame: false,
+            blame: BlameOverlay::default(),
+            repo_root: git::find_repo_root().ok(),
+            pending_difftool: None,
             scroll_offset: 0,
-            highlighter: crate::highlight::Highlighter::new(),
+            scroll_positions: HashMap::new(),
+            max_scroll: 0,
+            highlight_cache: HighlightCache::new(Arc::clone(&highlighter)),
+            highlighter,
+            image_previews: HashMap::new(),
+            full_preview: None,
+            moved,
+            symbols,
             confirm_action: None,
+            prompt: None,
             view_mode: ViewMode::HunkReview {
                 branch: String::new(),
                 base_ref,
             },
             dashboard: None,
+            repos: None,
             status_message: None,
             last_refresh: Instant::now(),
+            last_fetch: None,
+            fetch: None,
+            assignees,
+            comments,
+            bookmarks,
+            base_move: None,
+            config: Config::default(),
+            session: Some(SessionStats::begin(&before, &after)),
+            show_summary: false,
+            exit_action: ExitAction::Quit,
         })
     }
 
@@ -110,9 +471,10 @@ impl App {
     ///
     /// Loads all branches and their review progress.
     pub fn new_dashboard(mut db: ReviewDb, base_branch: String) -> Result<Self> {
-        let mut dashboard = Dashboard::load(&db, &base_branch)
+        let mut dashboard = Dashboard::load(&db, &base_branch, false)
             .map_err(|e| anyhow::anyhow!("Failed to load dashboard: {}", e))?;
         dashboard.load_all_details(&mut db);
+        let highlighter = Arc::new(Highlighter::new());
 
         Ok(Self {
             files: vec![],
@@ -121,54 +483,213 @@ impl App {
             selected_file: 0,
             selected_hunk: 0,
             filter: FilterMode::All,
+            pattern: None,
+            file_filter: FileFilter::default(),
             should_quit: false,
-            show_help: false,
+            help: None,
+            show_stat: false,
+            show_whitespace: false,
+            wrap: true,
+            scroll_x: 0,
+            show_hunk_list: false,
+            priority_order: false,
+            prioritizer: Prioritizer::default(),
+            keymap: Keymap::default(),
+            condensed: false,
+            zen: false,
+            check: None,
+            timeline: None,
+            related: None,
+            ai: AiSummaries::default(),
+            show_blame: false,
+            blame: BlameOverlay::default(),
+            repo_root: None,
+            pending_difftool: None,
             scroll_offset: 0,
-            highlighter: crate::highlight::Highlighter::new(),
+            scroll_positions: HashMap::new(),
+            max_scroll: 0,
+            highlight_cache: HighlightCache::new(Arc::clone(&highlighter)),
+            highlighter,
+            image_previews: HashMap::new(),
+            full_preview: None,
+            moved: MoveIndex::default(),
+            symbols: SymbolIndex::default(),
             confirm_action: None,
+            prompt: None,
             view_mode: ViewMode::Dashboard,
             dashboard: Some(dashboard),
+            repos: None,
             status_message: None,
             last_refresh: Instant::now(),
+            last_fetch: None,
+            fetch: None,
+            assignees: HashMap::new(),
+            comments: Vec::new(),
+            bookmarks: HashSet::new(),
+            base_move: None,
+            config: Config::default(),
+            session: None,
+            show_summary: false,
+            exit_action: ExitAction::Quit,
         })
     }
 
-    /// Get currently visible files based on filter mode.
+    /// Use the given repository config (gate policy etc.) instead of defaults.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.set_config(config);
+        self
+    }
+
+    /// Only show the files `filter` keeps.
+    pub fn with_file_filter(mut self, filter: FileFilter) -> Self {
+        self.file_filter = filter;
+        self.reset_selection();
+        self
+    }
+
+    /// Record the commit `range` is taken against, and explain the hunks that
+    /// changed because it moved since the review was last opened.
+    pub fn with_base_tracking(mut self, range: &str) -> Result<Self> {
+        self.base_move = crate::session::track_base(&mut self.db, &self.base_ref, range)
+            .context("Failed to record the review's base")?;
+        self.announce_base_move();
+        Ok(self)
+    }
+
+    /// Tell the user the base moved, and how to review just what that affected.
+    fn announce_base_move(&mut self) {
+        if let Some(base_move) = &self.base_move {
+            self.status_message = Some((
+                format!("{} (:affected shows them)", base_move.describe()),
+                Instant::now(),
+            ));
+        }
+    }
+
+    /// Create a new App for the multi-repo dashboard.
+    ///
+    /// Each repository's database and config are loaded when one of its branches is opened.
+    pub fn new_repos(repos: RepoDashboard) -> Result<Self> {
+        // Placeholder until a branch is opened; nothing is written to it
+        let db = ReviewDb::open(std::path::Path::new(":memory:"))
+            .context("Failed to open in-memory database")?;
+        let mut app = Self::new_hunk_review(Vec::new(), db, String::new())?;
+        app.session = None;
+        app.view_mode = ViewMode::Repos;
+        app.repos = Some(repos);
+        Ok(app)
+    }
+
+    /// Apply a repository's `.git-review.toml`.
+    /// Status colors from the `[palette]` config.
+    fn palette(&self) -> Palette {
+        self.config.palette.palette()
+    }
+
+    fn set_config(&mut self, config: Config) {
+        self.priority_order = config.priority.enabled;
+        self.prioritizer = Prioritizer::new(&config.priority).unwrap_or_default();
+        self.keymap = config.keys.keymap().unwrap_or_default();
+        if let Some(dashboard) = &mut self.dashboard {
+            match dashboard.set_remotes(&self.db, config.dashboard.remotes) {
+                Ok(()) => dashboard.load_all_details(&mut self.db),
+                Err(e) => {
+                    self.status_message =
+                        Some((format!("Failed to list branches: {}", e), Instant::now()));
+                }
+            }
+        }
+        self.ai.endpoint = ai::AiEndpoint::from_git_config();
+        self.config = config;
+        if self.priority_order {
+            self.reset_selection();
+        }
+    }
+
+    /// Get currently visible files based on filter mode, in review order.
     fn visible_files(&self) -> Vec<usize> {
-        self.files
-            .iter()
-            .enumerate()
-            .filter(|(_, file)| {
-                file.hunks.iter().any(|hunk| match self.filter {
-                    FilterMode::All => true,
-                    FilterMode::Unreviewed => hunk.status == HunkStatus::Unreviewed,
-                    FilterMode::Stale => hunk.status == HunkStatus::Stale,
-                })
+        let order = if self.priority_order {
+            self.prioritizer.file_order(&self.files)
+        } else {
+            (0..self.files.len()).collect()
+        };
+        order
+            .into_iter()
+            .filter(|&i| {
+                let file = &self.files[i];
+                file.hunks.iter().any(|hunk| self.shows_hunk(file, hunk))
             })
-            .map(|(i, _)| i)
             .collect()
     }
 
+    /// Whether `hunk` of `file` passes the status filter and the pattern filter.
+    fn shows_hunk(&self, file: &DiffFile, hunk: &DiffHunk) -> bool {
+        let status = match self.filter {
+            FilterMode::All => true,
+            FilterMode::Unreviewed => hunk.status == HunkStatus::Unreviewed,
+            FilterMode::Stale => hunk.status == HunkStatus::Stale,
+            FilterMode::Skimmed => hunk.status == HunkStatus::Skimmed,
+            FilterMode::Bookmarked => self.is_bookmarked(file, hunk),
+            FilterMode::BaseMoved => self.base_move.as_ref().is_some_and(|base_move| {
+                base_move
+                    .files
+                    .binary_search(&file.path.to_string_lossy().to_string())
+                    .is_ok()
+            }),
+        };
+        status
+            && self.file_filter.matches(&file.path)
+            && self.pattern.as_ref().is_none_or(|pattern| {
+                pattern.is_match(&file.path.to_string_lossy()) || pattern.is_match(&hunk.content)
+            })
+    }
+
+    /// Whether `hunk` of `file` is bookmarked.
+    fn is_bookmarked(&self, file: &DiffFile, hunk: &DiffHunk) -> bool {
+        self.bookmarks.contains(&(
+            file.path.to_string_lossy().to_string(),
+            hunk.content_hash.clone(),
+        ))
+    }
+
     /// Get currently visible hunks for the selected file.
     fn visible_hunks(&self) -> Vec<usize> {
         if self.selected_file >= self.files.len() {
             return Vec::new();
         }
-        self.files[self.selected_file]
-            .hunks
-            .iter()
-            .enumerate()
-            .filter(|(_, hunk)| match self.filter {
-                FilterMode::All => true,
-                FilterMode::Unreviewed => hunk.status == HunkStatus::Unreviewed,
-                FilterMode::Stale => hunk.status == HunkStatus::Stale,
-            })
-            .map(|(i, _)| i)
+        let file = &self.files[self.selected_file];
+        let order = if self.priority_order {
+            priority::hunk_order(file)
+        } else {
+            (0..file.hunks.len()).collect()
+        };
+        order
+            .into_iter()
+            .filter(|&i| self.shows_hunk(file, &file.hunks[i]))
             .collect()
     }
 
     /// Handle keyboard input, dispatching to the appropriate mode handler.
     fn handle_input(&mut self, key: event::KeyEvent) -> Result<()> {
+        tracing::trace!(code = ?key.code, modifiers = ?key.modifiers, "key press");
+        // Text prompts capture all keys while open
+        if let Some(mut prompt) = self.prompt.take() {
+            match key.code {
+                KeyCode::Enter => self.submit_prompt(prompt),
+                KeyCode::Esc => {}
+                KeyCode::Backspace => {
+                    prompt.buffer.pop();
+                    self.prompt = Some(prompt);
+                }
+                KeyCode::Char(c) => {
+                    prompt.buffer.push(c);
+                    self.prompt = Some(prompt);
+                }
+                _ => self.prompt = Some(prompt),
+            }
+            return Ok(());
+        }
+
         // Handle confirmation dialog first
         if let Some(action) = self.confirm_action.take() {
             match key.code {
@@ -180,6 +701,9 @@ impl App {
                     ConfirmAction::ApproveAll => {
                         self.approve_all()?;
                     }
+                    ConfirmAction::ApproveMatching { hunks, .. } => {
+                        self.approve_in_bulk(None, &hunks)?;
+                    }
                     ConfirmAction::MergeBranch { branch } => {
                         // Attempt the merge
                         match git::merge_branch(&git::MergeOptions {
@@ -187,188 +711,1124 @@ impl App {
                             delete_after: false,
                         }) {
                             Ok(()) => {
-                                self.status_message = Some((
-                                    format!("Merged {} successfully", branch),
-                                    Instant::now(),
-                                ));
+                                let merge_sha = git::resolve_ref("HEAD").ok();
+                                let message = match crate::dashboard::archive_branch(
+                                    &mut self.db,
+                                    &branch,
+                                    merge_sha.as_deref(),
+                                ) {
+                                    Ok(_) => format!("Merged {} successfully", branch),
+                                    Err(e) => format!(
+                                        "Merged {}, but archiving its review failed: {}",
+                                        branch, e
+                                    ),
+                                };
+                                self.status_message = Some((message, Instant::now()));
                                 // Refresh dashboard to reflect the merge
                                 self.try_refresh_dashboard();
                             }
                             Err(e) => {
-                                self.status_message = Some((
-                                    format!("Merge failed: {}", e),
-                                    Instant::now(),
-                                ));
+                                self.status_message =
+                                    Some((format!("Merge failed: {}", e), Instant::now()));
                             }
                         }
                     }
+                    ConfirmAction::ApproveBranches { branches } => {
+                        self.approve_branches(&branches);
+                    }
+                    ConfirmAction::DeleteBranches { branches } => {
+                        self.delete_branches(&branches);
+                    }
+                    ConfirmAction::MergeQueued { branch } => {
+                        self.merge_queued(&branch);
+                    }
                 },
-                _ => {} // Any other key cancels
+                // Any other key cancels
+                _ => {
+                    if let ConfirmAction::MergeQueued { branch } = action {
+                        self.set_queued(&[branch], false);
+                    }
+                }
             }
             return Ok(());
         }
 
-        if self.show_help {
-            // Any key closes help
-            self.show_help = false;
+        if self.help.is_some() {
+            self.handle_help_input(key);
+            return Ok(());
+        }
+
+        if self.show_summary {
+            self.handle_summary_input(key);
+            return Ok(());
+        }
+
+        if self.check.is_some() {
+            self.handle_check_input(key);
+            return Ok(());
+        }
+
+        if self.timeline.is_some() {
+            self.handle_timeline_input(key);
+            return Ok(());
+        }
+
+        if self.related.is_some() {
+            self.handle_related_input(key);
             return Ok(());
         }
 
         match self.view_mode {
             ViewMode::Dashboard => self.handle_dashboard_input(key),
+            ViewMode::Repos => self.handle_repos_input(key),
             ViewMode::HunkReview { .. } => self.handle_hunk_review_input(key),
         }
     }
 
+    /// The action `key` is bound to in `view`.
+    fn action(&self, view: View, key: event::KeyEvent) -> Option<Action> {
+        keymap_key(&key).and_then(|key| self.keymap.action(view, key))
+    }
+
+    /// The first key of each of `actions` in `view`, for status bar hints: `j/k`.
+    fn key_hint(&self, view: View, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .map(|&action| self.keymap.hint(view, action))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// The keymap view of the current screen.
+    fn screen_view(&self) -> View {
+        match self.view_mode {
+            ViewMode::Dashboard => View::Dashboard,
+            ViewMode::Repos => View::Repos,
+            ViewMode::HunkReview { .. } => View::HunkReview,
+        }
+    }
+
+    /// Handle keyboard input while the help is open: `/` searches, the pane
+    /// keys scroll, and any other key closes it.
+    fn handle_help_input(&mut self, key: event::KeyEvent) {
+        let action = self.action(View::Pane, key);
+        let Some(help) = &mut self.help else {
+            return;
+        };
+        if help.searching {
+            match key.code {
+                KeyCode::Enter => help.searching = false,
+                KeyCode::Esc => {
+                    help.searching = false;
+                    help.query.clear();
+                }
+                KeyCode::Backspace => {
+                    help.query.pop();
+                }
+                KeyCode::Char(c) => help.query.push(c),
+                _ => {}
+            }
+            help.scroll = 0;
+            return;
+        }
+        match action {
+            Some(Action::FilterPattern) => help.searching = true,
+            Some(Action::Next) => help.scroll = help.scroll.saturating_add(1),
+            Some(Action::Previous) => help.scroll = help.scroll.saturating_sub(1),
+            Some(Action::PageDown) => help.scroll = help.scroll.saturating_add(10),
+            Some(Action::PageUp) => help.scroll = help.scroll.saturating_sub(10),
+            Some(Action::ScrollTop) => help.scroll = 0,
+            // Rendering clamps this to the last page
+            Some(Action::ScrollBottom) => help.scroll = usize::MAX,
+            _ => self.help = None,
+        }
+    }
+
+    /// Handle keyboard input in the multi-repo dashboard.
+    fn handle_repos_input(&mut self, key: event::KeyEvent) -> Result<()> {
+        let action = self.action(View::Repos, key);
+        let Some(repos) = &mut self.repos else {
+            return Ok(());
+        };
+        match action {
+            Some(Action::Quit) => self.should_quit = true,
+            Some(Action::Help) => self.help = Some(HelpOverlay::default()),
+            Some(Action::Next) => repos.select_next(),
+            Some(Action::Previous) => repos.select_prev(),
+            Some(Action::Refresh) => {
+                repos.reload();
+                self.status_message = Some((
+                    format!("Refreshed {} repositories", repos.repos.len()),
+                    Instant::now(),
+                ));
+            }
+            Some(Action::Open) => {
+                if let Err(e) = self.open_repo_branch() {
+                    self.status_message =
+                        Some((format!("Failed to enter review: {}", e), Instant::now()));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Switch to the repository of the selected multi-repo row and review its branch.
+    fn open_repo_branch(&mut self) -> Result<()> {
+        let Some((repo, item)) = self.repos.as_ref().and_then(|r| r.selected_row()) else {
+            return Ok(());
+        };
+        let (root, base, branch) = (
+            repo.root.clone(),
+            repo.base_branch.clone(),
+            item.branch.name.clone(),
+        );
+
+        // Git keeps running in this repository for the rest of the session
+        git::set_repo_dir(Some(root.clone()));
+        self.db = crate::session::open_db(&root).context("Failed to open review database")?;
+        self.set_config(Config::load(&root).unwrap_or_default());
+        self.dashboard = Some(
+            Dashboard::load(&self.db, &base, self.config.dashboard.remotes)
+                .map_err(|e| anyhow::anyhow!("Failed to load branches: {}", e))?,
+        );
+        self.enter_hunk_review(&branch)
+    }
+
     /// Handle keyboard input in dashboard mode.
     fn handle_dashboard_input(&mut self, key: event::KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => {
+        let Some(action) = self.action(View::Dashboard, key) else {
+            return Ok(());
+        };
+        match action {
+            Action::ClearMarks
+                if self
+                    .dashboard
+                    .as_ref()
+                    .is_some_and(|d| !d.marked.is_empty()) =>
+            {
+                if let Some(ref mut dashboard) = self.dashboard {
+                    dashboard.marked.clear();
+                }
+            }
+            Action::Quit | Action::ClearMarks => {
                 self.should_quit = true;
             }
-            KeyCode::Char('?') => {
-                self.show_help = true;
+            Action::Help => {
+                self.help = Some(HelpOverlay::default());
+            }
+            Action::Mark => {
+                if let Some(ref mut dashboard) = self.dashboard {
+                    dashboard.toggle_mark_selected();
+                    dashboard.select_next();
+                    let _ = dashboard.load_detail_for_selected(&mut self.db);
+                }
+            }
+            Action::ApproveAll => {
+                if let Some(ref dashboard) = self.dashboard {
+                    let branches = dashboard.action_targets();
+                    if !branches.is_empty() {
+                        self.confirm_action = Some(ConfirmAction::ApproveBranches { branches });
+                    }
+                }
+            }
+            Action::DeleteMerged => {
+                self.handle_delete_request();
+            }
+            Action::Timeline => {
+                if let Some(branch) = self
+                    .dashboard
+                    .as_ref()
+                    .and_then(|d| d.selected_branch())
+                    .map(str::to_string)
+                {
+                    self.open_timeline(&branch);
+                }
             }
-            KeyCode::Char('j') | KeyCode::Down => {
+            Action::Next => {
                 if let Some(ref mut dashboard) = self.dashboard {
                     dashboard.select_next();
                     let _ = dashboard.load_detail_for_selected(&mut self.db);
                 }
             }
-            KeyCode::Char('k') | KeyCode::Up => {
+            Action::Previous => {
                 if let Some(ref mut dashboard) = self.dashboard {
                     dashboard.select_prev();
                     let _ = dashboard.load_detail_for_selected(&mut self.db);
                 }
             }
-            KeyCode::Enter => {
+            Action::Open => {
                 // Get selected branch and enter hunk review
                 if let Some(ref dashboard) = self.dashboard
                     && let Some(branch) = dashboard.selected_branch()
                 {
                     let branch = branch.to_string();
                     if let Err(e) = self.enter_hunk_review(&branch) {
-                        self.status_message = Some((
-                            format!("Failed to enter review: {}", e),
-                            Instant::now(),
-                        ));
+                        self.status_message =
+                            Some((format!("Failed to enter review: {}", e), Instant::now()));
                     }
                 }
             }
-            KeyCode::Char('M') => {
+            Action::Merge => {
                 self.handle_merge_request();
             }
-            KeyCode::Char('r') => {
-                self.try_refresh_dashboard();
-                self.last_refresh = Instant::now();
-            }
-            _ => {}
-        }
-        Ok(())
-    }
-
-    /// Handle keyboard input in hunk review mode.
-    fn handle_hunk_review_input(&mut self, key: event::KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Char('q') => {
-                self.should_quit = true;
-            }
-            KeyCode::Esc | KeyCode::Backspace => {
-                // Check if we entered from dashboard (branch name is set in ViewMode)
-                let from_dashboard = matches!(
-                    &self.view_mode,
-                    ViewMode::HunkReview { branch, .. } if !branch.is_empty()
-                );
-
-                if from_dashboard {
-                    // Return to dashboard
-                    self.return_to_dashboard();
-                } else {
-                    // Entered directly via CLI, quit
-                    self.should_quit = true;
-                }
-            }
-            KeyCode::Char('?') => {
-                self.show_help = true;
-            }
-            KeyCode::Char('j') | KeyCode::Down => {
-                self.navigate_hunk_down();
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.navigate_hunk_up();
-            }
-            KeyCode::Tab => {
-                self.navigate_file_next();
-            }
-            KeyCode::BackTab => {
-                self.navigate_file_prev();
-            }
-            KeyCode::Char(' ') => {
-                self.toggle_reviewed()?;
-            }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.scroll_offset = self.scroll_offset.saturating_add(10);
+            Action::OpenPullRequest => {
+                self.open_pull_request();
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.scroll_offset = self.scroll_offset.saturating_sub(10);
-            }
-            KeyCode::Char('u') => {
-                self.filter = FilterMode::Unreviewed;
-                self.reset_selection();
-            }
-            KeyCode::Char('s') => {
-                self.filter = FilterMode::Stale;
-                self.reset_selection();
-            }
-            KeyCode::Char('a') => {
-                self.filter = FilterMode::All;
-                self.reset_selection();
+            Action::Untouched => {
+                self.toggle_untouched_filter();
             }
-            KeyCode::Char('F') => {
-                // Shift+F: approve current file (with confirmation)
-                if self.selected_file < self.files.len() {
-                    self.confirm_action = Some(ConfirmAction::ApproveAllFile {
-                        file_idx: self.selected_file,
-                    });
+            Action::Queue => {
+                if let Some(ref dashboard) = self.dashboard {
+                    let branches = dashboard.action_targets();
+                    // Queue them unless every one is queued already
+                    let queue = branches.iter().any(|b| !dashboard.queued.contains(b));
+                    self.set_queued(&branches, queue);
                 }
             }
-            KeyCode::Char('A') => {
-                // Shift+A: approve all (with confirmation)
-                if !self.files.is_empty() {
-                    self.confirm_action = Some(ConfirmAction::ApproveAll);
+            Action::Refresh
+                if self
+                    .dashboard
+                    .as_ref()
+                    .is_some_and(|d| !d.marked.is_empty()) =>
+            {
+                if let Some(ref mut dashboard) = self.dashboard {
+                    let branches = dashboard.action_targets();
+                    dashboard.reload_details(&mut self.db, &branches);
+                    self.status_message = Some((
+                        format!("Refreshed {} branches", branches.len()),
+                        Instant::now(),
+                    ));
                 }
             }
-            KeyCode::PageDown => {
-                self.scroll_offset = self.scroll_offset.saturating_add(20);
-            }
-            KeyCode::PageUp => {
-                self.scroll_offset = self.scroll_offset.saturating_sub(20);
+            Action::Refresh => {
+                self.try_refresh_dashboard();
+                self.last_refresh = Instant::now();
             }
             _ => {}
         }
         Ok(())
     }
 
-    /// Navigate to the next hunk.
-    fn navigate_hunk_down(&mut self) {
-        let visible = self.visible_hunks();
-        if visible.is_empty() {
+    /// Show only the dashboard branches the current reviewer hasn't reviewed
+    /// any hunk of, or every branch again.
+    fn toggle_untouched_filter(&mut self) {
+        let me = self.db.reviewer().map(str::to_string);
+        let Some(ref mut dashboard) = self.dashboard else {
+            return;
+        };
+        if dashboard.untouched_by.is_some() {
+            dashboard.set_untouched_by(None);
+        } else if me.is_some() {
+            dashboard.set_untouched_by(me);
+        } else {
+            self.status_message = Some((
+                "Set git config user.name to filter by reviewer".to_string(),
+                Instant::now(),
+            ));
             return;
         }
-        if let Some(current_pos) = visible.iter().position(|&i| i == self.selected_hunk) {
-            if current_pos + 1 < visible.len() {
-                self.selected_hunk = visible[current_pos + 1];
-                self.scroll_offset = 0;
+        let _ = dashboard.load_detail_for_selected(&mut self.db);
+    }
+
+    /// Add dashboard branches to the merge queue, or take them off it.
+    fn set_queued(&mut self, branches: &[String], queue: bool) {
+        let Some(ref mut dashboard) = self.dashboard else {
+            return;
+        };
+        for branch in branches {
+            let result = if queue {
+                self.db.enqueue_merge(branch, &dashboard.base_branch)
+            } else {
+                self.db.dequeue_merge(branch).map(|_| ())
+            };
+            if let Err(e) = result {
+                self.status_message = Some((format!("Merge queue: {}", e), Instant::now()));
+                return;
+            }
+            if queue {
+                dashboard.queued.insert(branch.clone());
+            } else {
+                dashboard.queued.remove(branch);
             }
-        } else if !visible.is_empty() {
-            self.selected_hunk = visible[0];
-            self.scroll_offset = 0;
         }
+        let message = if queue {
+            format!(
+                "Queued {} for merge once fully reviewed",
+                branches.join(", ")
+            )
+        } else {
+            format!("Removed {} from the merge queue", branches.join(", "))
+        };
+        self.status_message = Some((message, Instant::now()));
     }
 
-    /// Navigate to the previous hunk.
-    fn navigate_hunk_up(&mut self) {
-        self.scroll_offset = 0;
+    /// Merge the first queued branch that is ready, or ask first when
+    /// `[merge_queue] confirm` is on. Runs as the dashboard refreshes.
+    fn process_merge_queue(&mut self) {
+        if self.confirm_action.is_some() || self.prompt.is_some() || self.timeline.is_some() {
+            return;
+        }
+        let Some(ref dashboard) = self.dashboard else {
+            return;
+        };
+        if dashboard.queued.is_empty() {
+            return;
+        }
+        let base = dashboard.base_branch.clone();
+        let ready = match crate::queue::ready_branches(&mut self.db, &base) {
+            Ok(ready) => ready,
+            Err(e) => {
+                self.status_message = Some((format!("Merge queue: {}", e), Instant::now()));
+                return;
+            }
+        };
+        let Some(branch) = ready.into_iter().next() else {
+            return;
+        };
+        if self.config.merge_queue.confirm {
+            self.confirm_action = Some(ConfirmAction::MergeQueued { branch });
+        } else {
+            self.merge_queued(&branch);
+        }
+    }
+
+    /// Merge a ready branch from the merge queue into the base branch.
+    fn merge_queued(&mut self, branch: &str) {
+        let Some(ref mut dashboard) = self.dashboard else {
+            return;
+        };
+        let base = dashboard.base_branch.clone();
+        match crate::queue::merge(&mut self.db, &base, branch, &self.config.merge_queue) {
+            Ok(()) => {
+                dashboard.queued.remove(branch);
+                self.status_message = Some((
+                    format!("Merged queued branch {} into {}", branch, base),
+                    Instant::now(),
+                ));
+                self.try_refresh_dashboard();
+            }
+            Err(e) => {
+                // Keep it queued; the next refresh retries
+                self.status_message = Some((
+                    format!("Merge of queued {} failed: {}", branch, e),
+                    Instant::now(),
+                ));
+            }
+        }
+    }
+
+    /// Approve every hunk on the given dashboard branches.
+    fn approve_branches(&mut self, branches: &[String]) {
+        let Some(ref mut dashboard) = self.dashboard else {
+            return;
+        };
+        let mut approved = 0;
+        for branch in branches {
+            let range = dashboard.range_for(branch);
+            let result = crate::session::review_key(&mut self.db, &range)
+                .map_err(anyhow::Error::from)
+                .and_then(|key| Ok(self.db.approve_all(&key)?));
+            match result {
+                Ok(count) => approved += count,
+                Err(e) => {
+                    self.status_message = Some((
+                        format!("Approve failed for {}: {}", branch, e),
+                        Instant::now(),
+                    ));
+                    return;
+                }
+            }
+        }
+        dashboard.reload_details(&mut self.db, branches);
+        dashboard.marked.clear();
+        self.status_message = Some((
+            format!(
+                "Approved {} hunks across {} branches",
+                approved,
+                branches.len()
+            ),
+            Instant::now(),
+        ));
+    }
+
+    /// Ask to delete merged branches: the marked ones, or every merged branch if none are marked.
+    fn handle_delete_request(&mut self) {
+        let Some(ref dashboard) = self.dashboard else {
+            return;
+        };
+        let merged = if dashboard.marked.is_empty() {
+            match crate::dashboard::merged_branches(&dashboard.base_branch) {
+                Ok(merged) => merged,
+                Err(e) => {
+                    self.status_message =
+                        Some((format!("Merge check failed: {}", e), Instant::now()));
+                    return;
+                }
+            }
+        } else {
+            let targets = dashboard.action_targets();
+            let merged: Vec<String> = targets
+                .iter()
+                .filter(|b| git::is_merged(&dashboard.base_branch, b).unwrap_or(false))
+                .cloned()
+                .collect();
+            if merged.len() < targets.len() {
+                self.status_message = Some((
+                    format!(
+                        "Skipping {} unmerged branches",
+                        targets.len() - merged.len()
+                    ),
+                    Instant::now(),
+                ));
+            }
+            merged
+        };
+
+        if merged.is_empty() {
+            self.status_message = Some((
+                format!(
+                    "No merged branches to delete (base: {})",
+                    dashboard.base_branch
+                ),
+                Instant::now(),
+            ));
+        } else {
+            self.confirm_action = Some(ConfirmAction::DeleteBranches { branches: merged });
+        }
+    }
+
+    /// Delete merged branches and purge their review state.
+    fn delete_branches(&mut self, branches: &[String]) {
+        let Some(ref mut dashboard) = self.dashboard else {
+            return;
+        };
+        let mut deleted = 0;
+        let mut failures = Vec::new();
+        for branch in branches {
+            match crate::dashboard::prune_branch(&mut self.db, &dashboard.base_branch, branch) {
+                Ok(()) => deleted += 1,
+                Err(e) => failures.push(format!("{}: {}", branch, e)),
+            }
+        }
+
+        if let Err(e) = dashboard.reload(&self.db) {
+            failures.push(format!("refresh: {}", e));
+        }
+        dashboard.load_all_details(&mut self.db);
+
+        let message = if failures.is_empty() {
+            format!("Deleted {} merged branches", deleted)
+        } else {
+            format!(
+                "Deleted {} branches; failed: {}",
+                deleted,
+                failures.join(", ")
+            )
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Handle keyboard input on the quit summary screen.
+    fn handle_summary_input(&mut self, key: event::KeyEvent) {
+        match key.code {
+            KeyCode::Char('c') if self.commit_available() => {
+                self.exit_action = ExitAction::Commit;
+                self.should_quit = true;
+            }
+            KeyCode::Char('q') | KeyCode::Enter => {
+                self.should_quit = true;
+            }
+            KeyCode::Esc => {
+                self.show_summary = false;
+            }
+            _ => {}
+        }
+    }
+
+    /// Act on a submitted text prompt.
+    fn submit_prompt(&mut self, prompt: Prompt) {
+        match prompt.kind {
+            PromptKind::CommitMessage => self.commit_with_message(&prompt.buffer),
+            PromptKind::Comment => self.comment_on_current_hunk(&prompt.buffer),
+            PromptKind::ApprovePattern => self.confirm_approve_matching(&prompt.buffer),
+            PromptKind::FilterPattern => self.set_pattern_filter(&prompt.buffer),
+            PromptKind::Command => self.run_command(&prompt.buffer),
+            PromptKind::ApproveAll(count) => self.approve_all_typed(count, &prompt.buffer),
+        }
+    }
+
+    /// Approve every hunk if `answer` is the hunk count or "yes".
+    fn approve_all_typed(&mut self, count: usize, answer: &str) {
+        if confirms_count(count, answer) {
+            if let Err(e) = self.approve_all() {
+                self.status_message = Some((format!("Approve failed: {}", e), Instant::now()));
+            }
+        } else {
+            self.status_message = Some((
+                format!("Nothing approved (type {} or yes to approve all)", count),
+                Instant::now(),
+            ));
+        }
+    }
+
+    /// Unreviewed hunks in the whole diff.
+    fn unreviewed_count(&self) -> usize {
+        self.files
+            .iter()
+            .flat_map(|f| &f.hunks)
+            .filter(|h| h.status != HunkStatus::Reviewed)
+            .count()
+    }
+
+    /// Run a `:` command: `only <exts>` or `exclude <exts>` to review some
+    /// file types (comma-separated extensions), `all` to show every file again,
+    /// `affected` to show the files the base moving changed, `stage` to stage
+    /// the reviewed hunks.
+    fn run_command(&mut self, command: &str) {
+        let (name, args) = command
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((command.trim(), ""));
+        let no_args: [&str; 0] = [];
+        self.file_filter = match name {
+            "only" if !args.trim().is_empty() => FileFilter::new(&[args], &no_args),
+            "exclude" if !args.trim().is_empty() => FileFilter::new(&no_args, &[args]),
+            "all" | "" => FileFilter::default(),
+            "affected" if self.base_move.is_some() => {
+                self.filter = FilterMode::BaseMoved;
+                self.reset_selection();
+                return;
+            }
+            "affected" => {
+                self.status_message = Some((
+                    "The base has not moved since this review was last opened".to_string(),
+                    Instant::now(),
+                ));
+                return;
+            }
+            "stage" => {
+                self.stage_reviewed();
+                return;
+            }
+            _ => {
+                self.status_message = Some((
+                    format!(
+                        "Unknown command :{} (try :only rs,toml, :exclude md, :all, or :stage)",
+                        command.trim()
+                    ),
+                    Instant::now(),
+                ));
+                return;
+            }
+        };
+        self.reset_selection();
+    }
+
+    /// Stage the working tree's reviewed hunks (`:stage`).
+    fn stage_reviewed(&mut self) {
+        let message = if self.base_ref != "HEAD" {
+            ":stage works on the working tree's changes (git-review HEAD)".to_string()
+        } else {
+            match crate::stage::stage_reviewed(&self.db) {
+                Ok(0) => "No reviewed hunks left to stage".to_string(),
+                Ok(count) => format!("Staged {} reviewed hunk(s)", count),
+                Err(e) => format!("Staging failed: {}", e),
+            }
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Limit the visible hunks to those whose content or path matches
+    /// `pattern`, or lift the limit if it is empty.
+    fn set_pattern_filter(&mut self, pattern: &str) {
+        if pattern.is_empty() {
+            self.pattern = None;
+        } else {
+            match regex::Regex::new(pattern) {
+                Ok(regex) => self.pattern = Some(regex),
+                Err(e) => {
+                    self.status_message = Some((format!("Invalid regex: {}", e), Instant::now()));
+                    return;
+                }
+            }
+        }
+        self.reset_selection();
+    }
+
+    /// Ask to approve the unreviewed hunks whose changed lines all match `pattern`.
+    fn confirm_approve_matching(&mut self, pattern: &str) {
+        if pattern.is_empty() {
+            return;
+        }
+        let regex = match regex::Regex::new(pattern) {
+            Ok(regex) => regex,
+            Err(e) => {
+                self.status_message = Some((format!("Invalid regex: {}", e), Instant::now()));
+                return;
+            }
+        };
+        let hunks: Vec<(usize, usize)> = self
+            .files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_idx, file)| {
+                file.hunks
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, hunk)| {
+                        hunk.status == HunkStatus::Unreviewed
+                            && crate::autoapprove::matches_pattern(hunk, &regex)
+                    })
+                    .map(move |(hunk_idx, _)| (file_idx, hunk_idx))
+            })
+            .collect();
+        if hunks.is_empty() {
+            self.status_message = Some((
+                format!("No unreviewed hunks match /{}/", pattern),
+                Instant::now(),
+            ));
+        } else {
+            self.confirm_action = Some(ConfirmAction::ApproveMatching {
+                pattern: pattern.to_string(),
+                hunks,
+            });
+        }
+    }
+
+    /// Attach a comment to the selected hunk.
+    fn comment_on_current_hunk(&mut self, body: &str) {
+        let body = body.trim();
+        if body.is_empty() {
+            return;
+        }
+        let Some((file_path, hash)) = self.files.get(self.selected_file).and_then(|f| {
+            f.hunks
+                .get(self.selected_hunk)
+                .map(|h| (f.path.to_string_lossy().to_string(), h.content_hash.clone()))
+        }) else {
+            return;
+        };
+
+        let result = self
+            .db
+            .add_comment(&self.base_ref, &file_path, &hash, body)
+            .and_then(|()| self.db.comments(&self.base_ref));
+        match result {
+            Ok(comments) => self.comments = comments,
+            Err(e) => {
+                self.status_message = Some((format!("Comment failed: {}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Open the commit message prompt if the gate passes for the current range.
+    fn start_commit(&mut self) {
+        // Only the staged changes are what `git commit` would record
+        if self.base_ref != "HEAD" {
+            self.status_message = Some((
+                "Commit is only available when reviewing staged changes".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+        match crate::gate::check_gate(&self.db, &self.base_ref, &self.config.gate) {
+            Ok(true) => self.prompt = Some(Prompt::new(PromptKind::CommitMessage)),
+            Ok(false) => {
+                self.status_message = Some((
+                    "Cannot commit: review gate does not pass".to_string(),
+                    Instant::now(),
+                ));
+            }
+            Err(e) => {
+                self.status_message = Some((format!("Gate check failed: {}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Run `git commit` with the given message and reload the diff.
+    fn commit_with_message(&mut self, message: &str) {
+        if message.trim().is_empty() {
+            self.status_message =
+                Some(("Commit aborted: empty message".to_string(), Instant::now()));
+            return;
+        }
+
+        let result = git::commit(message).map_err(anyhow::Error::from);
+        let result = result.and_then(|summary| self.reload_diff().map(|()| summary));
+        self.status_message = Some(match result {
+            Ok(summary) => (format!("Committed: {}", summary), Instant::now()),
+            Err(e) => (format!("Commit failed: {}", e), Instant::now()),
+        });
+    }
+
+    /// Re-read the diff for the current range and refresh hunk statuses.
+    fn reload_diff(&mut self) -> Result<()> {
+        tracing::debug!(base_ref = %self.base_ref, "reloading diff");
+        let diff_output = git::get_diff(&self.base_ref).context("Failed to get git diff")?;
+        let mut files = parser::parse_diff(&diff_output);
+        self.db
+            .sync_with_diff(&self.base_ref, &files)
+            .context("Failed to sync with database")?;
+        for file in &mut files {
+            let file_path = file.path.to_string_lossy();
+            for hunk in &mut file.hunks {
+                if let Ok(status) =
+                    self.db
+                        .get_status(&self.base_ref, &file_path, &hunk.content_hash)
+                {
+                    hunk.status = status;
+                }
+            }
+        }
+        self.moved = MoveIndex::new(&files);
+        self.symbols = SymbolIndex::new(&files);
+        self.files = files;
+        self.reset_selection();
+        Ok(())
+    }
+
+    /// Queue the selected hunk and the next few for background highlighting,
+    /// and pick up any the worker has finished.
+    fn prefetch_highlights(&mut self) {
+        const PREFETCH_HUNKS: usize = 4;
+
+        self.highlight_cache.collect();
+        if !matches!(self.view_mode, ViewMode::HunkReview { .. }) {
+            return;
+        }
+
+        let upcoming = self
+            .files
+            .iter()
+            .enumerate()
+            .skip(self.selected_file)
+            .flat_map(|(file_idx, file)| {
+                let skip = if file_idx == self.selected_file {
+                    self.selected_hunk
+                } else {
+                    0
+                };
+                file.hunks.iter().skip(skip).map(move |hunk| (file, hunk))
+            })
+            .take(PREFETCH_HUNKS);
+        for (file, hunk) in upcoming {
+            let file_ext = file.path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            self.highlight_cache
+                .request(&hunk.content_hash, file_ext, &hunk.content);
+        }
+    }
+
+    /// Whether committing is offered: reviewing staged changes and the gate passes.
+    fn commit_available(&self) -> bool {
+        self.base_ref == "HEAD"
+            && crate::gate::check_gate(&self.db, &self.base_ref, &self.config.gate).unwrap_or(false)
+    }
+
+    /// Handle keyboard input in hunk review mode.
+    fn handle_hunk_review_input(&mut self, key: event::KeyEvent) -> Result<()> {
+        // 1-9 jump within the hunk list, whatever they are bound to
+        if let KeyCode::Char(c @ '1'..='9') = key.code
+            && self.show_hunk_list
+        {
+            let position = c as usize - '1' as usize;
+            if let Some(&hunk_idx) = self.visible_hunks().get(position) {
+                self.selected_hunk = hunk_idx;
+                self.restore_scroll();
+            }
+            return Ok(());
+        }
+        let Some(action) = self.action(View::HunkReview, key) else {
+            return Ok(());
+        };
+        match action {
+            Action::Quit => {
+                if self.session.is_some() {
+                    self.show_summary = true;
+                } else {
+                    self.should_quit = true;
+                }
+            }
+            Action::Back => {
+                // Check if we entered from dashboard (branch name is set in ViewMode)
+                let from_dashboard = matches!(
+                    &self.view_mode,
+                    ViewMode::HunkReview { branch, .. } if !branch.is_empty()
+                );
+
+                if from_dashboard {
+                    // Return to dashboard
+                    self.return_to_dashboard();
+                } else {
+                    // Entered directly via CLI, quit
+                    self.should_quit = true;
+                }
+            }
+            Action::Help => {
+                self.help = Some(HelpOverlay::default());
+            }
+            Action::Stat => {
+                self.show_stat = !self.show_stat;
+            }
+            Action::Commit => {
+                self.start_commit();
+            }
+            Action::Comment if self.selected_file < self.files.len() => {
+                self.prompt = Some(Prompt::new(PromptKind::Comment));
+            }
+            Action::Preview => {
+                self.request_full_preview();
+            }
+            Action::Whitespace => {
+                self.show_whitespace = !self.show_whitespace;
+            }
+            Action::LineHistory => {
+                self.start_history();
+            }
+            Action::Timeline => {
+                let branch = crate::state::namespace(&self.base_ref).to_string();
+                self.open_timeline(&branch);
+            }
+            Action::Blame => {
+                if self.git_range().is_some() {
+                    self.show_blame = !self.show_blame;
+                } else {
+                    self.status_message =
+                        Some(("Blame needs a git range".to_string(), Instant::now()));
+                }
+            }
+            Action::HunkList => {
+                self.show_hunk_list = !self.show_hunk_list;
+            }
+            Action::PriorityOrder => {
+                self.priority_order = !self.priority_order;
+                self.reset_selection();
+                let order = if self.priority_order {
+                    "priority"
+                } else {
+                    "diff"
+                };
+                self.status_message =
+                    Some((format!("Reviewing in {} order", order), Instant::now()));
+            }
+            Action::Check => {
+                self.start_check();
+            }
+            Action::Difftool => {
+                self.request_difftool();
+            }
+            Action::Condensed => {
+                self.condensed = !self.condensed;
+                self.restore_scroll();
+            }
+            Action::Zen => {
+                self.zen = !self.zen;
+            }
+            Action::ShrinkFileList => self.resize_file_list(-FILE_LIST_STEP),
+            Action::GrowFileList => self.resize_file_list(FILE_LIST_STEP),
+            Action::Next => {
+                self.navigate_hunk_down();
+            }
+            Action::Previous => {
+                self.navigate_hunk_up();
+            }
+            Action::NextFile => {
+                self.navigate_file_next();
+            }
+            Action::PreviousFile => {
+                self.navigate_file_prev();
+            }
+            Action::ToggleReviewed => {
+                self.toggle_reviewed()?;
+            }
+            Action::NeedsChanges => {
+                self.toggle_needs_changes()?;
+            }
+            Action::Skimmed => {
+                self.toggle_skimmed()?;
+            }
+            Action::ScrollDown => self.scroll_by(10),
+            Action::ScrollUp => self.scroll_by(-10),
+            Action::ShowUnreviewed => {
+                self.filter = FilterMode::Unreviewed;
+                self.reset_selection();
+            }
+            Action::ShowStale => {
+                self.filter = FilterMode::Stale;
+                self.reset_selection();
+            }
+            Action::ShowSkimmed => {
+                self.filter = FilterMode::Skimmed;
+                self.reset_selection();
+            }
+            Action::ShowBookmarked => {
+                self.filter = FilterMode::Bookmarked;
+                self.reset_selection();
+            }
+            Action::Bookmark => {
+                self.toggle_bookmark();
+            }
+            Action::Related if self.selected_file < self.files.len() => {
+                self.open_related();
+            }
+            Action::ShowAll => {
+                self.filter = FilterMode::All;
+                self.reset_selection();
+            }
+            Action::Command => {
+                self.prompt = Some(Prompt::new(PromptKind::Command));
+            }
+            Action::FilterPattern => {
+                // Edit the current pattern; an empty one clears the filter
+                let mut prompt = Prompt::new(PromptKind::FilterPattern);
+                prompt.buffer = self
+                    .pattern
+                    .as_ref()
+                    .map(|pattern| pattern.as_str().to_string())
+                    .unwrap_or_default();
+                self.prompt = Some(prompt);
+            }
+            Action::ApproveFile if self.selected_file < self.files.len() => {
+                // Approve current file (with confirmation)
+                self.confirm_action = Some(ConfirmAction::ApproveAllFile {
+                    file_idx: self.selected_file,
+                });
+            }
+            Action::ApproveAll if !self.files.is_empty() => {
+                // Approve all (with confirmation, typed for large diffs)
+                let count = self.unreviewed_count();
+                if count > self.config.gate.typed_confirm_over {
+                    self.prompt = Some(Prompt::new(PromptKind::ApproveAll(count)));
+                } else {
+                    self.confirm_action = Some(ConfirmAction::ApproveAll);
+                }
+            }
+            Action::UndoApproval => self.undo_last_approval(),
+            Action::ApprovePattern if !self.files.is_empty() => {
+                // Approve hunks matching a regex (with confirmation)
+                self.prompt = Some(Prompt::new(PromptKind::ApprovePattern));
+            }
+            Action::PageDown => self.scroll_by(20),
+            Action::Wrap => {
+                self.wrap = !self.wrap;
+                self.scroll_x = 0;
+            }
+            Action::ScrollLeft | Action::ScrollRight if self.wraps() => {
+                self.status_message = Some((
+                    format!(
+                        "Long lines wrap; press {} to scroll them sideways",
+                        self.key_hint(View::HunkReview, &[Action::Wrap])
+                    ),
+                    Instant::now(),
+                ));
+            }
+            Action::ScrollLeft => {
+                self.scroll_x = self.scroll_x.saturating_sub(HORIZONTAL_STEP);
+            }
+            Action::ScrollRight => {
+                self.scroll_x = self.scroll_x.saturating_add(HORIZONTAL_STEP);
+            }
+            Action::PageUp => self.scroll_by(-20),
+            Action::ScrollTop => self.scroll_to(0),
+            // Condensed view scrolls the whole file, which has no end to jump to
+            Action::ScrollBottom if !self.condensed => self.scroll_to(self.max_scroll),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Scroll the hunk detail by `delta` lines, stopping at the end of the hunk.
+    fn scroll_by(&mut self, delta: i16) {
+        let mut offset = self.scroll_offset.saturating_add_signed(delta);
+        if !self.condensed {
+            offset = offset.min(self.max_scroll);
+        }
+        self.scroll_to(offset);
+    }
+
+    /// Scroll the hunk detail to `offset` and remember it for the selected hunk.
+    fn scroll_to(&mut self, offset: u16) {
+        self.scroll_offset = offset;
+        // The condensed view scrolls around the selection, not within a hunk
+        if self.condensed {
+            return;
+        }
+        if let Some(key) = self.scroll_key() {
+            if offset == 0 {
+                self.scroll_positions.remove(&key);
+            } else {
+                self.scroll_positions.insert(key, offset);
+            }
+        }
+    }
+
+    /// Scroll the hunk detail back to where the selected hunk was left.
+    fn restore_scroll(&mut self) {
+        self.scroll_offset = match self.scroll_key() {
+            Some(key) if !self.condensed => self.scroll_positions.get(&key).copied().unwrap_or(0),
+            _ => 0,
+        };
+    }
+
+    /// The selected hunk's key in `scroll_positions`.
+    fn scroll_key(&self) -> Option<(String, String)> {
+        let file = self.files.get(self.selected_file)?;
+        let hunk = file.hunks.get(self.selected_hunk)?;
+        Some((
+            file.path.to_string_lossy().to_string(),
+            hunk.content_hash.clone(),
+        ))
+    }
+
+    /// Whether the hunk detail wraps long lines; condensed hunks never do.
+    fn wraps(&self) -> bool {
+        self.wrap && !self.condensed
+    }
+
+    /// Widen or narrow the file list by `delta` percent of the screen and
+    /// save the new width to the personal config.
+    fn resize_file_list(&mut self, delta: i16) {
+        let width = self
+            .config
+            .layout
+            .file_list
+            .saturating_add_signed(delta)
+            .clamp(FILE_LIST_MIN, FILE_LIST_MAX);
+        self.config.layout.file_list = width;
+        self.zen = false;
+        let saved = match &self.repo_root {
+            Some(root) => {
+                crate::config::set_local_value(root, "layout", "file_list", i64::from(width))
+                    .map_err(|e| e.to_string())
+            }
+            None => Err("no repository".to_string()),
+        };
+        let message = match saved {
+            Ok(()) => format!("File list at {}% of the screen", width),
+            Err(e) => format!("File list at {}% (not saved: {})", width, e),
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Navigate to the next hunk.
+    fn navigate_hunk_down(&mut self) {
+        let visible = self.visible_hunks();
+        if visible.is_empty() {
+            return;
+        }
+        if let Some(current_pos) = visible.iter().position(|&i| i == self.selected_hunk) {
+            if current_pos + 1 < visible.len() {
+                self.selected_hunk = visible[current_pos + 1];
+                self.restore_scroll();
+            }
+        } else if !visible.is_empty() {
+            self.selected_hunk = visible[0];
+            self.restore_scroll();
+        }
+    }
+
+    /// Navigate to the previous hunk.
+    fn navigate_hunk_up(&mut self) {
         let visible = self.visible_hunks();
         if visible.is_empty() {
             return;
@@ -380,6 +1840,7 @@ impl App {
         } else if !visible.is_empty() {
             self.selected_hunk = visible[0];
         }
+        self.restore_scroll();
     }
 
     /// Navigate to the next file.
@@ -414,7 +1875,7 @@ impl App {
     fn reset_hunk_selection(&mut self) {
         let visible = self.visible_hunks();
         self.selected_hunk = visible.first().copied().unwrap_or(0);
-        self.scroll_offset = 0;
+        self.restore_scroll();
     }
 
     /// Reset selection after filter change.
@@ -424,6 +1885,554 @@ impl App {
         self.reset_hunk_selection();
     }
 
+    /// Run the configured `[check] command` for the current file and open its output pane.
+    fn start_check(&mut self) {
+        let Some(template) = self.config.check.command.clone() else {
+            self.status_message = Some((
+                "No check command configured (set [check] command in .git-review.local.toml)"
+                    .to_string(),
+                Instant::now(),
+            ));
+            return;
+        };
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+
+        let command = crate::check::expand_command(&template, &file.path.to_string_lossy());
+        let started = git::find_repo_root()
+            .map_err(anyhow::Error::from)
+            .and_then(|root| Ok(CheckRun::start(&command, &root)?));
+        match started {
+            Ok(run) => {
+                self.check = Some(CheckPane {
+                    run,
+                    scroll: None,
+                    bottom: 0,
+                    history: None,
+                })
+            }
+            Err(e) => {
+                self.status_message =
+                    Some((format!("Check failed to start: {}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Show how the lines the current hunk replaces evolved (`git log -L` up
+    /// to the pre-image) in the output pane.
+    fn start_history(&mut self) {
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+        let Some(hunk) = file.hunks.get(self.selected_hunk) else {
+            return;
+        };
+        let message = |text: &str| Some((text.to_string(), Instant::now()));
+        let Some(range) = self.git_range() else {
+            self.status_message = message("Line history needs a git range");
+            return;
+        };
+        if hunk.old_start == 0 {
+            self.status_message = message("New file: these lines have no history");
+            return;
+        }
+        // A pure addition has no old lines; follow the line it was inserted after
+        let (start, end) = (hunk.old_start, hunk.old_start + hunk.old_count.max(1) - 1);
+        let path = file.path.to_string_lossy().to_string();
+
+        let started = git::pre_image_rev(range)
+            .map_err(anyhow::Error::from)
+            .and_then(|rev| {
+                git::validate_git_ref(&rev)?;
+                let command = crate::check::expand_command(
+                    &format!(
+                        "git --no-pager log --no-ext-diff -L{},{}:{} {}",
+                        start,
+                        end,
+                        crate::check::FILE_PLACEHOLDER,
+                        rev
+                    ),
+                    &path,
+                );
+                Ok(CheckRun::start(&command, &git::find_repo_root()?)?)
+            });
+        match started {
+            Ok(run) => {
+                self.check = Some(CheckPane {
+                    run,
+                    scroll: Some(0),
+                    bottom: 0,
+                    history: Some(format!("{} lines {}-{}", path, start, end)),
+                })
+            }
+            Err(e) => {
+                self.status_message = Some((format!("Line history failed: {}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Queue the current file for `git difftool`; the run loop opens it outside the TUI.
+    fn request_difftool(&mut self) {
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+        let Some(range) = self.git_range() else {
+            self.status_message = Some((
+                "The external diff tool needs a git range".to_string(),
+                Instant::now(),
+            ));
+            return;
+        };
+        self.pending_difftool = Some((range.to_string(), file.path.to_string_lossy().to_string()));
+    }
+
+    /// The git range under review, or `None` for stash and patch reviews,
+    /// which have no range git can diff again.
+    fn git_range(&self) -> Option<&str> {
+        crate::state::git_range(&self.base_ref)
+    }
+
+    /// Pick up a finished blame, then start one for the selected hunk if the
+    /// overlay is on and it has none.
+    fn poll_blame(&mut self) {
+        if let Some((key, receiver)) = self.blame.pending.take() {
+            let result = match receiver.try_recv() {
+                Ok(result) => result,
+                Err(TryRecvError::Empty) => {
+                    self.blame.pending = Some((key, receiver));
+                    return;
+                }
+                Err(TryRecvError::Disconnected) => Err("blame stopped".to_string()),
+            };
+            self.blame.done.insert(key, result);
+        }
+
+        if !self.show_blame || !matches!(self.view_mode, ViewMode::HunkReview { .. }) {
+            return;
+        }
+        let Some(range) = self.git_range() else {
+            return;
+        };
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+        let Some(hunk) = file.hunks.get(self.selected_hunk) else {
+            return;
+        };
+        let key = (
+            self.base_ref.clone(),
+            file.path.to_string_lossy().to_string(),
+            hunk.content_hash.clone(),
+        );
+        if self.blame.done.contains_key(&key) || parser::binary_blobs(hunk).is_some() {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let range = range.to_string();
+        let (path, old_start, old_count) = (key.1.clone(), hunk.old_start, hunk.old_count);
+        let content = hunk.content.clone();
+        let repo_dir = git::repo_dir();
+        std::thread::spawn(move || {
+            let result = git::in_repo(repo_dir.as_deref(), || {
+                git::pre_image_rev(&range)
+                    .and_then(|rev| git::blame(&rev, &path, old_start, old_count))
+            })
+            .map(|blamed| {
+                let mut blamed = blamed.into_iter();
+                content
+                    .lines()
+                    .map(|line| match line.as_bytes().first() {
+                        Some(b'-' | b' ') => blamed.next(),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .map_err(|e| e.to_string());
+            let _ = sender.send(result);
+        });
+        self.blame.pending = Some((key, receiver));
+    }
+
+    /// Prefix the content lines of `hunk` with who last touched each pre-image
+    /// line and when; lines changed within the last 30 days stand out.
+    fn add_blame_column(&self, file_path: &str, hunk: &DiffHunk, lines: &mut [Line<'static>]) {
+        let key = (
+            self.base_ref.clone(),
+            file_path.to_string(),
+            hunk.content_hash.clone(),
+        );
+        let blame = match self.blame.done.get(&key) {
+            Some(Ok(blame)) => Some(blame),
+            _ => None,
+        };
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or_default();
+        for (i, line) in lines.iter_mut().enumerate() {
+            let annotation = blame
+                .and_then(|blame| blame.get(i))
+                .and_then(Option::as_ref);
+            let (text, color) = match annotation {
+                Some(blamed) => {
+                    let secs = now.saturating_sub(blamed.author_time).max(0) as u64;
+                    let author: String = blamed.author.chars().take(12).collect();
+                    let color = if secs < 30 * 86_400 {
+                        Color::Yellow
+                    } else {
+                        Color::DarkGray
+                    };
+                    (format!("{:<12} {:>8} │ ", author, format_age(secs)), color)
+                }
+                None => (format!("{:<21} │ ", ""), Color::DarkGray),
+            };
+            line.spans
+                .insert(0, Span::styled(text, Style::default().fg(color)));
+        }
+    }
+
+    /// Pick up a finished background fetch, then start one if `[dashboard]
+    /// fetch_interval` has passed since the last.
+    fn poll_fetch(&mut self) {
+        if let Some(receiver) = self.fetch.take() {
+            match receiver.try_recv() {
+                Err(TryRecvError::Empty) => {
+                    self.fetch = Some(receiver);
+                    return;
+                }
+                Ok(Err(e)) => {
+                    self.status_message = Some((format!("Fetch failed: {}", e), Instant::now()));
+                }
+                Ok(Ok(())) | Err(TryRecvError::Disconnected) => {}
+            }
+        }
+
+        let interval = self.config.dashboard.fetch_interval;
+        if interval == 0
+            || !matches!(self.view_mode, ViewMode::Dashboard)
+            || self
+                .last_fetch
+                .is_some_and(|last| last.elapsed() < Duration::from_secs(interval))
+        {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let repo_dir = git::repo_dir();
+        std::thread::spawn(move || {
+            let fetched = git::in_repo(repo_dir.as_deref(), git::fetch_all);
+            let _ = sender.send(fetched.map_err(|e| e.to_string()));
+        });
+        self.fetch = Some(receiver);
+        self.last_fetch = Some(Instant::now());
+    }
+
+    /// Pick up new output from a running check command.
+    fn poll_check(&mut self) {
+        if let Some(pane) = &mut self.check {
+            pane.run.poll();
+        }
+    }
+
+    /// Pick up a finished summary, then request one for the selected hunk if it has none.
+    fn poll_ai(&mut self) {
+        let Some(endpoint) = self.ai.endpoint.clone() else {
+            return;
+        };
+        if let Some((hash, receiver)) = self.ai.pending.take() {
+            let result = match receiver.try_recv() {
+                Ok(result) => result.map_err(|e| e.to_string()),
+                Err(TryRecvError::Empty) => {
+                    self.ai.pending = Some((hash, receiver));
+                    return;
+                }
+                Err(TryRecvError::Disconnected) => Err("request stopped".to_string()),
+            };
+            if let Ok(summary) = &result {
+                let _ = self.db.set_ai_summary(&hash, summary);
+            }
+            self.ai.done.insert(hash, result);
+        }
+
+        if !matches!(self.view_mode, ViewMode::HunkReview { .. }) {
+            return;
+        }
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+        let Some(hunk) = file.hunks.get(self.selected_hunk) else {
+            return;
+        };
+        if self.ai.done.contains_key(&hunk.content_hash) || parser::binary_blobs(hunk).is_some() {
+            return;
+        }
+        if let Ok(Some(summary)) = self.db.ai_summary(&hunk.content_hash) {
+            self.ai.done.insert(hunk.content_hash.clone(), Ok(summary));
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let config = self.config.ai.clone();
+        let file_path = file.path.to_string_lossy().to_string();
+        let content = hunk.content.clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(ai::summarize(&config, &endpoint, &file_path, &content));
+        });
+        self.ai.pending = Some((hunk.content_hash.clone(), receiver));
+    }
+
+    /// Lines showing the model summary of a hunk, empty unless summaries are on.
+    fn ai_lines(&self, hunk: &DiffHunk) -> Vec<Line<'static>> {
+        if self.ai.endpoint.is_none() || parser::binary_blobs(hunk).is_some() {
+            return Vec::new();
+        }
+        let dim = Style::default().fg(Color::DarkGray);
+        match self.ai.done.get(&hunk.content_hash) {
+            Some(Ok(summary)) => {
+                let mut lines = vec![Line::from(Span::styled(
+                    format!("✦ {}", summary.summary),
+                    Style::default().fg(Color::Cyan),
+                ))];
+                if let Some(risk) = &summary.risk {
+                    lines.push(Line::from(Span::styled(
+                        format!("⚠ {}", risk),
+                        Style::default().fg(Color::Yellow),
+                    )));
+                }
+                lines
+            }
+            Some(Err(e)) => vec![Line::from(Span::styled(
+                format!("✦ summary unavailable: {}", e),
+                dim,
+            ))],
+            None => vec![Line::from(Span::styled("✦ summarizing…", dim))],
+        }
+    }
+
+    /// Handle keyboard input while the check output pane is open.
+    fn handle_check_input(&mut self, key: event::KeyEvent) {
+        let action = self.action(View::Pane, key);
+        let Some(pane) = &mut self.check else {
+            return;
+        };
+        let current = pane.scroll.unwrap_or(pane.bottom);
+        match action {
+            Some(Action::Back) => self.check = None,
+            Some(Action::Check) if pane.history.is_none() => self.start_check(),
+            Some(Action::Next) => pane.scroll = Some(current.saturating_add(1)),
+            Some(Action::Previous) => pane.scroll = Some(current.saturating_sub(1)),
+            Some(Action::PageDown) => pane.scroll = Some(current.saturating_add(20)),
+            Some(Action::PageUp) => pane.scroll = Some(current.saturating_sub(20)),
+            Some(Action::ScrollTop) => pane.scroll = Some(0),
+            Some(Action::ScrollBottom) => pane.scroll = None,
+            _ => {}
+        }
+    }
+
+    /// Open the selected dashboard branch's pull request page in the browser.
+    fn open_pull_request(&mut self) {
+        let Some(dashboard) = &self.dashboard else {
+            return;
+        };
+        let Some(branch) = dashboard.selected_branch() else {
+            return;
+        };
+        let message = match crate::hosting::review_url(branch, &dashboard.base_branch) {
+            Ok(url) => match crate::hosting::open_in_browser(&url) {
+                Ok(()) => format!("Opened {}", url),
+                Err(e) => format!("Failed to open a browser ({}): {}", e, url),
+            },
+            Err(e) => format!("No pull request page: {}", e),
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Open the timeline of `branch`'s review events.
+    fn open_timeline(&mut self, branch: &str) {
+        let entries = self.db.events(Some(branch)).and_then(|events| {
+            let archived = self.db.archived()?;
+            Ok(crate::history::timeline(&events, &archived))
+        });
+        match entries {
+            Ok(entries) if entries.is_empty() => {
+                self.status_message =
+                    Some((format!("No review history for {}", branch), Instant::now()));
+            }
+            Ok(entries) => {
+                self.timeline = Some(TimelinePane {
+                    branch: branch.to_string(),
+                    lines: entries
+                        .into_iter()
+                        .map(|entry| format!("{}  {}", entry.at, entry.text))
+                        .collect(),
+                    scroll: None,
+                    bottom: 0,
+                });
+            }
+            Err(e) => {
+                self.status_message =
+                    Some((format!("Failed to read history: {}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Handle keyboard input while the review timeline is open.
+    fn handle_timeline_input(&mut self, key: event::KeyEvent) {
+        // The key that opened the timeline also closes it
+        let toggled = self.action(self.screen_view(), key) == Some(Action::Timeline);
+        let action = self.action(View::Pane, key);
+        let Some(pane) = &mut self.timeline else {
+            return;
+        };
+        let current = pane.scroll.unwrap_or(pane.bottom);
+        match action {
+            _ if toggled => self.timeline = None,
+            Some(Action::Back) => self.timeline = None,
+            Some(Action::Next) => pane.scroll = Some(current.saturating_add(1)),
+            Some(Action::Previous) => pane.scroll = Some(current.saturating_sub(1)),
+            Some(Action::PageDown) => pane.scroll = Some(current.saturating_add(20)),
+            Some(Action::PageUp) => pane.scroll = Some(current.saturating_sub(20)),
+            Some(Action::ScrollTop) => pane.scroll = Some(0),
+            Some(Action::ScrollBottom) => pane.scroll = None,
+            _ => {}
+        }
+    }
+
+    /// List the hunks that touch a symbol the current hunk touches.
+    fn open_related(&mut self) {
+        let hunks: Vec<RelatedHunk> = self
+            .symbols
+            .related(self.selected_file, self.selected_hunk)
+            .into_iter()
+            .filter(|r| self.file_filter.matches(&self.files[r.file_idx].path))
+            .collect();
+        if hunks.is_empty() {
+            self.status_message = Some((
+                "No other hunk touches the same symbols".to_string(),
+                Instant::now(),
+            ));
+        } else {
+            self.related = Some(RelatedPane { hunks, selected: 0 });
+        }
+    }
+
+    /// Handle keyboard input while the related hunks are listed.
+    fn handle_related_input(&mut self, key: event::KeyEvent) {
+        // The key that opened the list also closes it
+        let toggled = self.action(View::HunkReview, key) == Some(Action::Related);
+        let action = self.action(View::Pane, key);
+        let Some(pane) = &mut self.related else {
+            return;
+        };
+        match action {
+            _ if toggled => self.related = None,
+            Some(Action::Back) => self.related = None,
+            Some(Action::Next) => {
+                pane.selected = (pane.selected + 1).min(pane.hunks.len() - 1);
+            }
+            Some(Action::Previous) => pane.selected = pane.selected.saturating_sub(1),
+            Some(Action::ScrollTop) => pane.selected = 0,
+            Some(Action::ScrollBottom) => pane.selected = pane.hunks.len() - 1,
+            Some(Action::Open) => {
+                let target = pane.hunks[pane.selected].clone();
+                self.related = None;
+                self.jump_to_hunk(target.file_idx, target.hunk_idx);
+            }
+            _ => {}
+        }
+    }
+
+    /// Select a hunk, clearing the status and pattern filters if they hide it.
+    fn jump_to_hunk(&mut self, file_idx: usize, hunk_idx: usize) {
+        let (Some(file), Some(hunk)) = (
+            self.files.get(file_idx),
+            self.files.get(file_idx).and_then(|f| f.hunks.get(hunk_idx)),
+        ) else {
+            return;
+        };
+        if !self.shows_hunk(file, hunk) {
+            self.filter = FilterMode::All;
+            self.pattern = None;
+        }
+        self.selected_file = file_idx;
+        self.selected_hunk = hunk_idx;
+        self.restore_scroll();
+    }
+
+    /// Flag the current hunk as needing changes, or clear the flag back to unreviewed.
+    fn toggle_needs_changes(&mut self) -> Result<()> {
+        if self.selected_file >= self.files.len() {
+            return Ok(());
+        }
+        let file = &mut self.files[self.selected_file];
+        if self.selected_hunk >= file.hunks.len() {
+            return Ok(());
+        }
+
+        let hunk = &mut file.hunks[self.selected_hunk];
+        let file_path = file.path.to_string_lossy();
+
+        let new_status = match hunk.status {
+            HunkStatus::NeedsChanges => HunkStatus::Unreviewed,
+            _ => HunkStatus::NeedsChanges,
+        };
+        self.db
+            .set_status(&self.base_ref, &file_path, &hunk.content_hash, new_status)
+            .context("Failed to update hunk status")?;
+
+        hunk.status = new_status;
+        Ok(())
+    }
+
+    /// Mark the current hunk as skimmed, or clear the mark back to unreviewed.
+    fn toggle_skimmed(&mut self) -> Result<()> {
+        if self.selected_file >= self.files.len() {
+            return Ok(());
+        }
+        let file = &mut self.files[self.selected_file];
+        if self.selected_hunk >= file.hunks.len() {
+            return Ok(());
+        }
+
+        let hunk = &mut file.hunks[self.selected_hunk];
+        let file_path = file.path.to_string_lossy();
+
+        let new_status = match hunk.status {
+            HunkStatus::Skimmed => HunkStatus::Unreviewed,
+            _ => HunkStatus::Skimmed,
+        };
+        self.db
+            .set_status(&self.base_ref, &file_path, &hunk.content_hash, new_status)
+            .context("Failed to update hunk status")?;
+
+        hunk.status = new_status;
+        Ok(())
+    }
+
+    /// Bookmark the current hunk to come back to, or clear its bookmark.
+    fn toggle_bookmark(&mut self) {
+        let Some((file_path, hash)) = self.files.get(self.selected_file).and_then(|f| {
+            f.hunks
+                .get(self.selected_hunk)
+                .map(|h| (f.path.to_string_lossy().to_string(), h.content_hash.clone()))
+        }) else {
+            return;
+        };
+        let message = match self.db.toggle_bookmark(&self.base_ref, &file_path, &hash) {
+            Ok(true) => {
+                self.bookmarks.insert((file_path, hash));
+                "Bookmarked (' shows bookmarks)".to_string()
+            }
+            Ok(false) => {
+                self.bookmarks.remove(&(file_path, hash));
+                "Bookmark cleared".to_string()
+            }
+            Err(e) => format!("Bookmark failed: {}", e),
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
     /// Toggle the reviewed status of the current hunk.
     fn toggle_reviewed(&mut self) -> Result<()> {
         if self.selected_file >= self.files.len() {
@@ -438,7 +2447,10 @@ impl App {
         let file_path = file.path.to_string_lossy();
 
         let new_status = match hunk.status {
-            HunkStatus::Unreviewed | HunkStatus::Stale => HunkStatus::Reviewed,
+            HunkStatus::Unreviewed
+            | HunkStatus::Stale
+            | HunkStatus::NeedsChanges
+            | HunkStatus::Skimmed => HunkStatus::Reviewed,
             HunkStatus::Reviewed => HunkStatus::Unreviewed,
         };
 
@@ -447,66 +2459,129 @@ impl App {
             .context("Failed to update hunk status")?;
 
         hunk.status = new_status;
+        if new_status == HunkStatus::Reviewed {
+            self.warn_unreviewed_dependencies();
+        }
         Ok(())
     }
 
+    /// Warn when the hunk just approved uses names defined by hunks of the
+    /// same file that are still not reviewed.
+    fn warn_unreviewed_dependencies(&mut self) {
+        let file = &self.files[self.selected_file];
+        let pending: Vec<String> = self
+            .symbols
+            .dependencies(self.selected_file, self.selected_hunk)
+            .iter()
+            .filter(|dep| file.hunks[dep.hunk_idx].status != HunkStatus::Reviewed)
+            .map(|dep| {
+                let hunk = &file.hunks[dep.hunk_idx];
+                format!("{} (line {})", dep.symbols.join(", "), hunk.new_start)
+            })
+            .collect();
+        if !pending.is_empty() {
+            self.status_message = Some((
+                format!(
+                    "Approved before reading the definition of {}",
+                    pending.join("; ")
+                ),
+                Instant::now(),
+            ));
+        }
+    }
+
     /// Approve all hunks in the currently selected file.
     fn approve_current_file(&mut self) -> Result<()> {
         if self.selected_file >= self.files.len() {
             return Ok(());
         }
-        let file = &self.files[self.selected_file];
-        let file_path = file.path.to_string_lossy().to_string();
-        // Collect hashes to approve
-        let to_approve: Vec<(String, usize)> = file
+        let hunks: Vec<(usize, usize)> = self.files[self.selected_file]
             .hunks
             .iter()
             .enumerate()
             .filter(|(_, h)| h.status != HunkStatus::Reviewed)
-            .map(|(i, h)| (h.content_hash.clone(), i))
+            .map(|(i, _)| (self.selected_file, i))
             .collect();
-        // Update DB
-        for (hash, _) in &to_approve {
-            self.db
-                .set_status(&self.base_ref, &file_path, hash, HunkStatus::Reviewed)
-                .context("Failed to approve hunk")?;
+        let file_path = self.files[self.selected_file]
+            .path
+            .to_string_lossy()
+            .to_string();
+        self.approve_in_bulk(Some(&file_path), &hunks)
+    }
+
+    /// Approve all hunks in all files.
+    fn approve_all(&mut self) -> Result<()> {
+        let mut hunks = Vec::new();
+        for (file_idx, file) in self.files.iter().enumerate() {
+            for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
+                if hunk.status != HunkStatus::Reviewed {
+                    hunks.push((file_idx, hunk_idx));
+                }
+            }
+        }
+        self.approve_in_bulk(None, &hunks)
+    }
+
+    /// Approve hunks (file and hunk index) as one bulk approval that `U` undoes.
+    fn approve_in_bulk(&mut self, scope: Option<&str>, hunks: &[(usize, usize)]) -> Result<()> {
+        let keys: Vec<(String, String)> = hunks
+            .iter()
+            .map(|&(file_idx, hunk_idx)| {
+                let file = &self.files[file_idx];
+                (
+                    file.path.to_string_lossy().to_string(),
+                    file.hunks[hunk_idx].content_hash.clone(),
+                )
+            })
+            .collect();
+        let count = self
+            .db
+            .approve_hunks(&self.base_ref, scope, &keys)
+            .context("Failed to approve hunks")?;
+        for &(file_idx, hunk_idx) in hunks {
+            self.files[file_idx].hunks[hunk_idx].status = HunkStatus::Reviewed;
         }
-        // Update in-memory state
-        let file = &mut self.files[self.selected_file];
-        for (_, idx) in &to_approve {
-            file.hunks[*idx].status = HunkStatus::Reviewed;
+        if count > 1 {
+            self.status_message = Some((
+                format!("Approved {} hunks (U: undo)", count),
+                Instant::now(),
+            ));
         }
         Ok(())
     }
 
-    /// Approve all hunks in all files.
-    fn approve_all(&mut self) -> Result<()> {
-        // Collect all hunks to approve
-        let mut to_approve: Vec<(usize, usize, String, String)> = Vec::new();
-        for (file_idx, file) in self.files.iter().enumerate() {
-            let file_path = file.path.to_string_lossy().to_string();
-            for (hunk_idx, hunk) in file.hunks.iter().enumerate() {
-                if hunk.status != HunkStatus::Reviewed {
-                    to_approve.push((
-                        file_idx,
-                        hunk_idx,
-                        file_path.clone(),
-                        hunk.content_hash.clone(),
-                    ));
+    /// Undo the last bulk approval of the range under review.
+    fn undo_last_approval(&mut self) {
+        let message = match self.db.last_bulk_approval(Some(&self.base_ref)) {
+            Ok(Some(approval)) => match self.db.undo_bulk_approval(&approval) {
+                Ok(restored) => {
+                    self.reload_statuses();
+                    format!(
+                        "Undid the approval from {}: {} hunk(s) restored",
+                        approval.approved_at, restored
+                    )
+                }
+                Err(e) => format!("Undo failed: {}", e),
+            },
+            Ok(None) => "No bulk approval to undo".to_string(),
+            Err(e) => format!("Undo failed: {}", e),
+        };
+        self.status_message = Some((message, Instant::now()));
+    }
+
+    /// Read every hunk's status back from the database.
+    fn reload_statuses(&mut self) {
+        for file in &mut self.files {
+            let file_path = file.path.to_string_lossy();
+            for hunk in &mut file.hunks {
+                if let Ok(status) =
+                    self.db
+                        .get_status(&self.base_ref, &file_path, &hunk.content_hash)
+                {
+                    hunk.status = status;
                 }
             }
         }
-        // Update DB
-        for (_, _, file_path, hash) in &to_approve {
-            self.db
-                .set_status(&self.base_ref, file_path, hash, HunkStatus::Reviewed)
-                .context("Failed to approve hunk")?;
-        }
-        // Update in-memory state
-        for (file_idx, hunk_idx, _, _) in &to_approve {
-            self.files[*file_idx].hunks[*hunk_idx].status = HunkStatus::Reviewed;
-        }
-        Ok(())
     }
 
     /// Handle merge request from dashboard.
@@ -574,7 +2649,8 @@ impl App {
         if let Some(ref mut dashboard) = self.dashboard {
             match dashboard.refresh(&self.db) {
                 Ok(true) => {
-                    let _ = dashboard.load_detail_for_selected(&mut self.db);
+                    tracing::debug!("dashboard branches changed, reloaded");
+                    dashboard.load_all_details(&mut self.db);
                 }
                 Ok(false) => {}
                 Err(e) => {
@@ -595,18 +2671,45 @@ impl App {
             .clone();
 
         // Compute diff range
-        let range = format!("{}..{}", base, branch);
+        let diff_range = format!("{}..{}", base, branch);
+        tracing::debug!(diff_range, "opening branch review from the dashboard");
 
         // Get diff from git
-        let diff_output = git::get_diff(&range).context("Failed to get git diff")?;
+        let diff_output = git::get_diff(&diff_range).context("Failed to get git diff")?;
 
         // Parse diff into files
         let mut files = parser::parse_diff(&diff_output);
 
+        // Review state is keyed by the canonical range, not its spelling
+        let range = crate::session::review_key(&mut self.db, &diff_range)
+            .context("Failed to resolve review state key")?;
+
+        // Later moves of the branch are flagged against this tip in the dashboard
+        if let Ok(tip) = git::resolve_ref(branch) {
+            self.db
+                .record_reviewed_tip(branch, &tip)
+                .context("Failed to record the reviewed tip")?;
+        }
+
         // Sync with database
+        let before = self
+            .db
+            .progress(&range)
+            .context("Failed to read progress")?;
         self.db
             .sync_with_diff(&range, &files)
             .context("Failed to sync with database")?;
+        self.base_move = crate::session::track_base(&mut self.db, &range, &diff_range)
+            .context("Failed to record the review's base")?;
+        if self.config.moved.auto_approve {
+            crate::moved::approve_moved(&mut self.db, &range, &files)
+                .context("Failed to approve moved code")?;
+        }
+        let after = self
+            .db
+            .progress(&range)
+            .context("Failed to read progress")?;
+        self.session = Some(SessionStats::begin(&before, &after));
 
         // Load review status for each hunk from database
         for file in &mut files {
@@ -619,12 +2722,19 @@ impl App {
         }
 
         // Update app state
+        self.assignees = load_assignees(&self.db, &range);
+        self.bookmarks = self.db.bookmarks(&range).unwrap_or_default();
+        self.moved = MoveIndex::new(&files);
+        self.symbols = SymbolIndex::new(&files);
         self.files = files;
+        self.repo_root = git::find_repo_root().ok();
         self.base_ref = range.clone();
         self.selected_file = 0;
         self.selected_hunk = 0;
         self.scroll_offset = 0;
+        self.scroll_positions.clear();
         self.filter = FilterMode::All;
+        self.pattern = None;
 
         // Set view mode (store branch name and base for later return to dashboard)
         self.view_mode = ViewMode::HunkReview {
@@ -634,6 +2744,7 @@ impl App {
 
         // Free dashboard memory
         self.dashboard = None;
+        self.announce_base_move();
 
         Ok(())
     }
@@ -645,12 +2756,23 @@ impl App {
             ViewMode::HunkReview { base_ref, .. } => base_ref.clone(),
             _ => return,
         };
+        tracing::debug!(base, "returning to the dashboard");
+
+        if let Some(repos) = &mut self.repos {
+            // Opened from the multi-repo dashboard: go back there instead
+            self.view_mode = ViewMode::Repos;
+            repos.reload();
+            self.files = vec![];
+            self.moved = MoveIndex::default();
+            self.session = None;
+            return;
+        }
 
         // Switch to dashboard mode first
         self.view_mode = ViewMode::Dashboard;
 
         // Reload dashboard from scratch
-        match Dashboard::load(&self.db, &base) {
+        match Dashboard::load(&self.db, &base, self.config.dashboard.remotes) {
             Ok(mut dashboard) => {
                 // Load detail for currently selected item
                 let _ = dashboard.load_detail_for_selected(&mut self.db);
@@ -659,10 +2781,8 @@ impl App {
             }
             Err(e) => {
                 // If reload fails, show error and revert to hunk review
-                self.status_message = Some((
-                    format!("Failed to load dashboard: {}", e),
-                    Instant::now(),
-                ));
+                self.status_message =
+                    Some((format!("Failed to load dashboard: {}", e), Instant::now()));
                 // Revert view mode
                 self.view_mode = ViewMode::HunkReview {
                     branch: String::new(),
@@ -674,9 +2794,11 @@ impl App {
 
         // Free hunk review memory
         self.files = vec![];
+        self.moved = MoveIndex::default();
         self.selected_file = 0;
         self.selected_hunk = 0;
         self.scroll_offset = 0;
+        self.scroll_positions.clear();
     }
 
     /// Render the UI, dispatching to the appropriate mode renderer.
@@ -691,13 +2813,19 @@ impl App {
             self.status_message = None;
         }
 
-        if self.show_help {
+        if self.help.is_some() {
             self.render_help(frame);
             return;
         }
 
+        if self.show_summary {
+            self.render_summary(frame);
+            return;
+        }
+
         match self.view_mode {
             ViewMode::Dashboard => self.render_dashboard(frame),
+            ViewMode::Repos => self.render_repos(frame),
             ViewMode::HunkReview { .. } => self.render_hunk_review(frame),
         }
 
@@ -705,6 +2833,22 @@ impl App {
         if self.confirm_action.is_some() {
             self.render_confirm(frame);
         }
+
+        if self.prompt.is_some() {
+            self.render_prompt(frame);
+        }
+
+        if let Some(pane) = &mut self.check {
+            render_check(frame, pane, &self.keymap);
+        }
+
+        if let Some(pane) = &mut self.timeline {
+            render_timeline(frame, pane, &self.keymap);
+        }
+
+        if let Some(pane) = &self.related {
+            self.render_related(frame, pane);
+        }
     }
 
     /// Render the dashboard view with branch table.
@@ -718,15 +2862,35 @@ impl App {
             Some(d) => d,
             None => return,
         };
+        let palette = self.palette();
 
         let rows: Vec<Row> = dashboard
-            .items
-            .iter()
+            .visible_items()
+            .into_iter()
             .enumerate()
             .map(|(idx, item)| {
                 let is_selected = idx == dashboard.selected;
-                let prefix = if is_selected { ">" } else { " " };
-                let branch_name = &item.branch.name;
+                let cursor = if is_selected { ">" } else { " " };
+                let mark = if dashboard.marked.contains(&item.branch.name) {
+                    "●"
+                } else {
+                    " "
+                };
+                let prefix = format!("{}{}", cursor, mark);
+                let mut branch_name = vec![Span::raw(format!("{} {}", prefix, item.branch.name))];
+                if dashboard.queued.contains(&item.branch.name) {
+                    branch_name.push(Span::raw(" [queued]"));
+                }
+                if let Some(change) = item.tip_change {
+                    let color = match change {
+                        TipChange::Advanced => Color::Yellow,
+                        TipChange::Rewritten => Color::Red,
+                    };
+                    branch_name.push(Span::styled(
+                        format!(" [{}]", change.badge()),
+                        Style::default().fg(color),
+                    ));
+                }
 
                 let diff_str = match &item.detail {
                     Some(d) => format!("+{}/-{}", d.diff_stats.insertions, d.diff_stats.deletions),
@@ -738,13 +2902,19 @@ impl App {
                     None => "-".to_string(),
                 };
 
-                let review_str = match &item.progress {
-                    Some(p) if p.total > 0 => {
-                        format!("{:.0}%", (p.reviewed as f64 / p.total as f64) * 100.0)
-                    }
-                    _ => "-".to_string(),
+                let ahead_behind_str = match &item.detail {
+                    Some(d) => format!("↑{} ↓{}", d.ahead, d.behind),
+                    None => "-".to_string(),
+                };
+
+                let review_cell = review_cell(&palette, item.progress.as_ref());
+
+                let (unreviewed_str, stale_str) = match &item.progress {
+                    Some(p) => (p.unreviewed.to_string(), p.stale.to_string()),
+                    None => ("-".to_string(), "-".to_string()),
                 };
 
+                let reviewer_str = item.last_reviewer().unwrap_or("-").to_string();
                 let commit_str = &item.branch.last_commit_age;
 
                 let style = if is_selected {
@@ -756,10 +2926,14 @@ impl App {
                 };
 
                 Row::new(vec![
-                    Cell::from(format!("{} {}", prefix, branch_name)),
+                    Cell::from(Line::from(branch_name)),
                     Cell::from(diff_str),
                     Cell::from(files_str),
-                    Cell::from(review_str),
+                    Cell::from(ahead_behind_str),
+                    review_cell,
+                    Cell::from(unreviewed_str),
+                    Cell::from(stale_str),
+                    Cell::from(reviewer_str),
                     Cell::from(commit_str.clone()),
                 ])
                 .style(style)
@@ -767,14 +2941,29 @@ impl App {
             .collect();
 
         let widths = [
-            Constraint::Percentage(35),
-            Constraint::Percentage(15),
-            Constraint::Percentage(10),
-            Constraint::Percentage(15),
-            Constraint::Percentage(25),
+            Constraint::Percentage(23),
+            Constraint::Percentage(11),
+            Constraint::Percentage(6),
+            Constraint::Percentage(9),
+            Constraint::Percentage(8),
+            Constraint::Percentage(7),
+            Constraint::Percentage(6),
+            Constraint::Percentage(13),
+            Constraint::Percentage(17),
         ];
 
-        let header = Row::new(vec!["Branch", "+/-", "Files", "Review", "Commit"]).style(
+        let header = Row::new(vec![
+            "Branch",
+            "+/-",
+            "Files",
+            "Ahd/Bhd",
+            "Review",
+            "Unrev",
+            "Stale",
+            "Last reviewer",
+            "Commit",
+        ])
+        .style(
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
@@ -794,11 +2983,42 @@ impl App {
         let status_text = match &self.status_message {
             Some((msg, _)) => msg.clone(),
             None => {
-                let count = dashboard.items.len();
-                format!(
-                    "{} branches | j/k: navigate  Enter: review  M: merge  r: refresh  q: quit",
-                    count
-                )
+                let count = dashboard.visible_items().len();
+                let mut count = match &dashboard.untouched_by {
+                    Some(me) => format!("{} branches not reviewed by {}", count, me),
+                    None => format!("{} branches", count),
+                };
+                if let Some(item) = dashboard.selected_item()
+                    && let Some(change) = item.tip_change
+                {
+                    count = format!("{}: {} | {}", item.branch.name, change.label(), count);
+                }
+                let key = |actions: &[Action]| self.key_hint(View::Dashboard, actions);
+                if dashboard.marked.is_empty() {
+                    format!(
+                        "{} | {}: navigate  {}: review  {}: mark  {}: merge  {}: queue  {}: delete merged  {}: refresh  {}: quit",
+                        count,
+                        key(&[Action::Next, Action::Previous]),
+                        key(&[Action::Open]),
+                        key(&[Action::Mark]),
+                        key(&[Action::Merge]),
+                        key(&[Action::Queue]),
+                        key(&[Action::DeleteMerged]),
+                        key(&[Action::Refresh]),
+                        key(&[Action::Quit]),
+                    )
+                } else {
+                    format!(
+                        "{} marked | {}: mark  {}: refresh marked  {}: approve marked  {}: queue marked  {}: delete merged  {}: clear marks",
+                        dashboard.marked.len(),
+                        key(&[Action::Mark]),
+                        key(&[Action::Refresh]),
+                        key(&[Action::ApproveAll]),
+                        key(&[Action::Queue]),
+                        key(&[Action::DeleteMerged]),
+                        key(&[Action::ClearMarks]),
+                    )
+                }
             }
         };
 
@@ -809,25 +3029,231 @@ impl App {
         frame.render_widget(status_bar, chunks[1]);
     }
 
+    /// Render the multi-repo dashboard: branches needing review, grouped by repository.
+    fn render_repos(&self, frame: &mut Frame) {
+        let Some(repos) = &self.repos else {
+            return;
+        };
+        let palette = self.palette();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(1), Constraint::Length(3)])
+            .split(frame.area());
+
+        let rows: Vec<Row> = repos
+            .rows()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (repo, item))| {
+                let is_selected = idx == repos.selected;
+                let cursor = if is_selected { ">" } else { " " };
+                let diff_str = match &item.detail {
+                    Some(d) => format!("+{}/-{}", d.diff_stats.insertions, d.diff_stats.deletions),
+                    None => "-".to_string(),
+                };
+                let (unreviewed_str, stale_str) = match &item.progress {
+                    Some(p) if p.total > 0 => (p.unreviewed.to_string(), p.stale.to_string()),
+                    _ => ("-".to_string(), "-".to_string()),
+                };
+                let style = if is_selected {
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+
+                Row::new(vec![
+                    Cell::from(format!("{} {}", cursor, repo.name)),
+                    Cell::from(item.branch.name.clone()),
+                    Cell::from(diff_str),
+                    review_cell(&palette, item.progress.as_ref()),
+                    Cell::from(unreviewed_str),
+                    Cell::from(stale_str),
+                    Cell::from(item.branch.last_commit_age.clone()),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Percentage(20),
+            Constraint::Percentage(25),
+            Constraint::Percentage(12),
+            Constraint::Percentage(8),
+            Constraint::Percentage(8),
+            Constraint::Percentage(7),
+            Constraint::Percentage(20),
+        ];
+        let header = Row::new(vec![
+            "Repository",
+            "Branch",
+            "+/-",
+            "Review",
+            "Unrev",
+            "Stale",
+            "Commit",
+        ])
+        .style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+        let table = Table::new(rows, widths)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Repositories - branches needing review"),
+            )
+            .header(header);
+        frame.render_widget(table, chunks[0]);
+
+        let status_text = match &self.status_message {
+            Some((msg, _)) => msg.clone(),
+            None => {
+                let mut text = format!(
+                    "{} branches in {} repositories",
+                    repos.rows().len(),
+                    repos.repos.len()
+                );
+                if !repos.failed.is_empty() {
+                    let names: Vec<String> = repos
+                        .failed
+                        .iter()
+                        .map(|(path, _)| path.display().to_string())
+                        .collect();
+                    text.push_str(&format!(" ({} skipped: {})", names.len(), names.join(", ")));
+                }
+                let key = |actions: &[Action]| self.key_hint(View::Repos, actions);
+                text.push_str(&format!(
+                    " | {}: navigate  {}: review  {}: refresh  {}: quit",
+                    key(&[Action::Next, Action::Previous]),
+                    key(&[Action::Open]),
+                    key(&[Action::Refresh]),
+                    key(&[Action::Quit]),
+                ));
+                text
+            }
+        };
+        let status_bar = Paragraph::new(status_text)
+            .block(Block::default().borders(Borders::ALL))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(status_bar, chunks[1]);
+    }
+
     /// Render the hunk review view (existing behavior).
-    fn render_hunk_review(&self, frame: &mut Frame) {
+    fn render_hunk_review(&mut self, frame: &mut Frame) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(1), Constraint::Length(3)].as_ref())
+            .constraints([Constraint::Min(1), Constraint::Length(4)].as_ref())
             .split(frame.area());
 
-        let main_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
-            .split(chunks[0]);
+        if self.show_stat {
+            self.render_diff_stat(frame, chunks[0]);
+        } else if self.zen {
+            self.render_hunk_detail(frame, chunks[0]);
+        } else {
+            let file_list = self.config.layout.file_list;
+            let main_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(file_list),
+                        Constraint::Percentage(100 - file_list),
+                    ]
+                    .as_ref(),
+                )
+                .split(chunks[0]);
 
-        self.render_file_list(frame, main_chunks[0]);
-        self.render_hunk_detail(frame, main_chunks[1]);
+            if self.show_hunk_list {
+                self.render_hunk_list(frame, main_chunks[0]);
+            } else {
+                self.render_file_list(frame, main_chunks[0]);
+            }
+            self.render_hunk_detail(frame, main_chunks[1]);
+        }
         self.render_status_bar(frame, chunks[1]);
     }
 
+    /// Render the diff-stat overview: per-file +/- counts merged with review progress.
+    fn render_diff_stat(&self, frame: &mut Frame, area: Rect) {
+        const BAR_WIDTH: usize = 20;
+
+        let palette = self.palette();
+        let mut total_added = 0;
+        let mut total_removed = 0;
+
+        let rows: Vec<Row> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(file_idx, file)| {
+                let (added, removed) = file.hunks.iter().fold((0, 0), |(a, r), hunk| {
+                    let (ha, hr) = parser::count_changes(&hunk.content);
+                    (a + ha, r + hr)
+                });
+                total_added += added;
+                total_removed += removed;
+
+                let counts: StatusCounts = file.hunks.iter().map(|h| h.status).collect();
+                let (reviewed, total) = (counts.reviewed, counts.total);
+                let filled = (reviewed * BAR_WIDTH).checked_div(total).unwrap_or(0);
+                let bar = format!("{}{}", "█".repeat(filled), "░".repeat(BAR_WIDTH - filled));
+
+                let color = term_color(palette.summary(counts));
+                let style = if file_idx == self.selected_file {
+                    Style::default().fg(color).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(color)
+                };
+
+                Row::new(vec![
+                    Cell::from(format!(
+                        "{} {}",
+                        status_symbol(summary_status(counts)),
+                        file.path.to_string_lossy()
+                    )),
+                    Cell::from(Line::from(vec![
+                        Span::styled(format!("+{}", added), Style::default().fg(Color::Green)),
+                        Span::raw(" "),
+                        Span::styled(format!("-{}", removed), Style::default().fg(Color::Red)),
+                    ])),
+                    Cell::from(bar),
+                    Cell::from(format!("{}/{}", reviewed, total)),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Min(20),
+            Constraint::Length(14),
+            Constraint::Length(BAR_WIDTH as u16 + 1),
+            Constraint::Length(9),
+        ];
+
+        let header = Row::new(vec!["File", "+/-", "Review", "Hunks"]).style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+
+        let table = Table::new(rows, widths).header(header).block(
+            Block::default().borders(Borders::ALL).title(format!(
+                "Diff Stat: {} files, +{} -{} (g to close)",
+                self.files.len(),
+                total_added,
+                total_removed
+            )),
+        );
+
+        frame.render_widget(table, area);
+    }
+
     /// Render the file list panel.
     fn render_file_list(&self, frame: &mut Frame, area: Rect) {
+        let palette = self.palette();
+        let ages = self.db.file_review_ages(&self.base_ref).unwrap_or_default();
         let visible = self.visible_files();
         let items: Vec<ListItem> = visible
             .iter()
@@ -835,31 +3261,15 @@ impl App {
                 let file = &self.files[file_idx];
                 let file_path = file.path.to_string_lossy();
 
-                let (reviewed, total) = file.hunks.iter().fold((0, 0), |(r, t), hunk| {
-                    let include = match self.filter {
-                        FilterMode::All => true,
-                        FilterMode::Unreviewed => hunk.status == HunkStatus::Unreviewed,
-                        FilterMode::Stale => hunk.status == HunkStatus::Stale,
-                    };
-                    if include {
-                        let r = if hunk.status == HunkStatus::Reviewed {
-                            r + 1
-                        } else {
-                            r
-                        };
-                        (r, t + 1)
-                    } else {
-                        (r, t)
-                    }
-                });
-
-                let color = if reviewed == total && total > 0 {
-                    Color::Green
-                } else if reviewed > 0 {
-                    Color::Yellow
-                } else {
-                    Color::Red
-                };
+                let counts: StatusCounts = file
+                    .hunks
+                    .iter()
+                    .filter(|hunk| self.shows_hunk(file, hunk))
+                    .map(|hunk| hunk.status)
+                    .collect();
+                let (reviewed, total) = (counts.reviewed, counts.total);
+                let symbol = status_symbol(summary_status(counts));
+                let color = term_color(palette.summary(counts));
 
                 let style = if file_idx == self.selected_file {
                     Style::default().fg(color).add_modifier(Modifier::BOLD)
@@ -867,21 +3277,135 @@ impl App {
                     Style::default().fg(color)
                 };
 
-                ListItem::new(format!("{} ({}/{})", file_path, reviewed, total)).style(style)
+                let mut label = match self.assignees.get(file_path.as_ref()) {
+                    Some(assignee) => format!(
+                        "{} {} ({}/{}) @{}",
+                        symbol, file_path, reviewed, total, assignee
+                    ),
+                    None => format!("{} {} ({}/{})", symbol, file_path, reviewed, total),
+                };
+                if let Some(&age) = ages.get(file_path.as_ref())
+                    && reviewed > 0
+                {
+                    label.push_str(&format!(" · reviewed {}", format_age(age)));
+                }
+
+                ListItem::new(label).style(style)
             })
             .collect();
 
-        let list = List::new(items).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title("Files (Tab/Shift+Tab)"),
-        );
+        let list = List::new(items).block(Block::default().borders(Borders::ALL).title(
+            if self.priority_order {
+                "Files by priority (Tab/Shift+Tab)"
+            } else {
+                "Files (Tab/Shift+Tab)"
+            },
+        ));
 
         frame.render_widget(list, area);
     }
 
-    /// Render the hunk detail panel.
-    fn render_hunk_detail(&self, frame: &mut Frame, area: Rect) {
+    /// Render the hunks of the selected file, one line each: number, status,
+    /// new-side line range, and the section heading (or first changed line).
+    fn render_hunk_list(&self, frame: &mut Frame, area: Rect) {
+        let Some(file) = self.files.get(self.selected_file) else {
+            let paragraph = Paragraph::new("No file selected")
+                .block(Block::default().borders(Borders::ALL).title("Hunks"));
+            frame.render_widget(paragraph, area);
+            return;
+        };
+
+        let palette = self.palette();
+        let auto_rules = self
+            .db
+            .auto_rules(&self.base_ref, &file.path.to_string_lossy())
+            .unwrap_or_default();
+        let ages = self
+            .db
+            .review_ages(&self.base_ref, &file.path.to_string_lossy())
+            .unwrap_or_default();
+        let visible = self.visible_hunks();
+        let items: Vec<ListItem> = visible
+            .iter()
+            .enumerate()
+            .map(|(position, &hunk_idx)| {
+                let hunk = &file.hunks[hunk_idx];
+                let auto_rule = auto_rules.get(&hunk.content_hash);
+                let icon = status_symbol(hunk.status);
+                let color = term_color(match hunk.status {
+                    HunkStatus::Reviewed if auto_rule.is_some() => palette.auto_approved,
+                    status => palette.status(status),
+                });
+                let range = match hunk.new_count {
+                    0 => format!("-{}", hunk.old_start),
+                    1 => hunk.new_start.to_string(),
+                    count => format!("{}-{}", hunk.new_start, hunk.new_start + count - 1),
+                };
+                let mut label = if hunk.section.is_empty() {
+                    crate::report::hunk_summary(&hunk.content)
+                } else {
+                    hunk.section.clone()
+                };
+                if let Some(rule) = auto_rule {
+                    label.push_str(&format!(" (auto: {})", rule));
+                }
+                if hunk.status == HunkStatus::Reviewed
+                    && let Some(&age) = ages.get(&hunk.content_hash)
+                {
+                    label.push_str(&format!(" (reviewed {})", format_age(age)));
+                }
+                if self.is_bookmarked(file, hunk) {
+                    label.push_str(" (bookmarked)");
+                }
+                let needs: Vec<String> = self
+                    .symbols
+                    .dependencies(self.selected_file, hunk_idx)
+                    .iter()
+                    .filter_map(|dep| visible.iter().position(|&i| i == dep.hunk_idx))
+                    .map(|position| (position + 1).to_string())
+                    .collect();
+                if !needs.is_empty() {
+                    label.push_str(&format!(" ← {}", needs.join(",")));
+                }
+                ListItem::new(format!(
+                    "{:>2} {} {:<9} {}",
+                    position + 1,
+                    icon,
+                    range,
+                    label
+                ))
+                .style(Style::default().fg(color))
+            })
+            .collect();
+
+        // Suggest reading definitions first when the list order does not
+        let suggested: Vec<usize> = self
+            .symbols
+            .review_order(self.selected_file)
+            .into_iter()
+            .filter_map(|hunk_idx| visible.iter().position(|&i| i == hunk_idx))
+            .collect();
+        let mut title = format!("Hunks in {}", file.path.display());
+        if !suggested.is_sorted() {
+            let order: Vec<String> = suggested.iter().map(|p| (p + 1).to_string()).collect();
+            title.push_str(&format!(" · suggested order {}", order.join(" ")));
+        }
+        title.push_str(&format!(
+            " (1-9 jump, {}: files)",
+            self.key_hint(View::HunkReview, &[Action::HunkList])
+        ));
+
+        let mut state = ListState::default();
+        state.select(visible.iter().position(|&i| i == self.selected_hunk));
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
+    /// Render the hunk detail panel, noting how far it can scroll.
+    fn render_hunk_detail(&mut self, frame: &mut Frame, area: Rect) {
         if self.selected_file >= self.files.len() {
             let paragraph = Paragraph::new("No file selected")
                 .block(Block::default().borders(Borders::ALL).title("Hunk Detail"));
@@ -889,57 +3413,372 @@ impl App {
             return;
         }
 
-        let file = &self.files[self.selected_file];
-        if self.selected_hunk >= file.hunks.len() {
-            let paragraph = Paragraph::new("No hunk selected")
-                .block(Block::default().borders(Borders::ALL).title("Hunk Detail"));
-            frame.render_widget(paragraph, area);
-            return;
+        let file = &self.files[self.selected_file];
+        if self.selected_hunk >= file.hunks.len() {
+            let paragraph = Paragraph::new("No hunk selected")
+                .block(Block::default().borders(Borders::ALL).title("Hunk Detail"));
+            frame.render_widget(paragraph, area);
+            return;
+        }
+
+        if self.condensed {
+            self.render_condensed(frame, area, file);
+            return;
+        }
+
+        let hunk = &file.hunks[self.selected_hunk];
+
+        let mut lines = self.ai_lines(hunk);
+
+        // Add hunk header
+        let header = format!(
+            "@@ -{},{} +{},{} @@",
+            hunk.old_start, hunk.old_count, hunk.new_start, hunk.new_count
+        );
+        lines.push(Line::from(Span::styled(
+            header,
+            Style::default().fg(Color::Cyan),
+        )));
+        let file_path = file.path.to_string_lossy();
+        if self.show_blame && parser::binary_blobs(hunk).is_none() {
+            let key = (
+                self.base_ref.clone(),
+                file_path.to_string(),
+                hunk.content_hash.clone(),
+            );
+            let note = match self.blame.done.get(&key) {
+                Some(Err(e)) => Some(format!("blame unavailable: {}", e.trim())),
+                None => Some("blaming…".to_string()),
+                Some(Ok(_)) => None,
+            };
+            if let Some(note) = note {
+                lines.push(Line::from(Span::styled(
+                    note,
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+        let content_start = lines.len();
+
+        // Add hunk content: image previews for binary images, otherwise syntax
+        // highlighting, from the cache when the worker has finished it. Otherwise
+        // highlight here: highlighting is stateful, so it runs from the top of the
+        // hunk, but only as far as the bottom of the viewport; lines further down
+        // get plain diff colors until scrolled to.
+        if parser::binary_blobs(hunk).is_some() {
+            lines.extend(self.image_preview_lines(&hunk.content_hash));
+        } else if let Some(cached) = self.highlight_cache.get(&hunk.content_hash) {
+            let moved = self.moved.moved_lines(hunk);
+            for ((spans, line), moved) in cached.iter().zip(hunk.content.lines()).zip(moved) {
+                lines.push(self.content_line(line, spans.clone(), moved));
+            }
+        } else {
+            let moved = self.moved.moved_lines(hunk);
+            let file_ext = file.path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            let mut fh = self.highlighter.for_file(file_ext);
+            let visible_end = self.scroll_offset as usize + area.height as usize;
+            for ((i, line), moved) in hunk.content.lines().enumerate().zip(moved) {
+                let spans = if i < visible_end {
+                    fh.highlight_diff_line(line)
+                } else {
+                    crate::highlight::plain_diff_line(line)
+                };
+                lines.push(self.content_line(line, spans, moved));
+            }
+        }
+        if self.show_blame && parser::binary_blobs(hunk).is_none() {
+            self.add_blame_column(&file_path, hunk, &mut lines[content_start..]);
+        }
+
+        // Add comments on this hunk
+        let hunk_comments = self
+            .comments
+            .iter()
+            .filter(|c| c.file_path == file_path && c.content_hash == hunk.content_hash);
+        for (i, comment) in hunk_comments.enumerate() {
+            if i == 0 {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "» {}: {}",
+                    comment.author.as_deref().unwrap_or("unknown"),
+                    comment.body
+                ),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+
+        let mut status_str = format!(
+            " [{} {}]",
+            status_symbol(hunk.status),
+            match hunk.status {
+                HunkStatus::Reviewed => "REVIEWED",
+                HunkStatus::Unreviewed => "UNREVIEWED",
+                HunkStatus::Stale => "STALE",
+                HunkStatus::NeedsChanges => "NEEDS CHANGES",
+                HunkStatus::Skimmed => "SKIMMED",
+            }
+        );
+        if hunk.status == HunkStatus::Reviewed
+            && let Some(rule) = self
+                .db
+                .auto_rules(&self.base_ref, &file_path)
+                .ok()
+                .and_then(|mut rules| rules.remove(&hunk.content_hash))
+        {
+            status_str.push_str(&format!(" [AUTO: {}]", rule));
+        }
+        if self.moved.is_moved(hunk) {
+            status_str.push_str(" [MOVED]");
+        }
+        if self.is_bookmarked(file, hunk) {
+            status_str.push_str(" [BOOKMARKED]");
+        }
+
+        if !self.wrap && self.scroll_x > 0 {
+            status_str.push_str(&format!(" [→ {}]", self.scroll_x));
+        }
+
+        let mut paragraph = Paragraph::new(Text::from(lines));
+        if self.wrap {
+            paragraph = paragraph.wrap(Wrap { trim: false });
+        }
+        // Rows inside the borders, and how far they scroll before the last one shows
+        let rows = paragraph.line_count(area.width.saturating_sub(2));
+        let max_scroll =
+            u16::try_from(rows.saturating_sub(usize::from(area.height.saturating_sub(2))))
+                .unwrap_or(u16::MAX);
+        let scroll = self.scroll_offset.min(max_scroll);
+        if max_scroll > 0 {
+            status_str.push_str(&format!(
+                " [{}%]",
+                u32::from(scroll) * 100 / u32::from(max_scroll)
+            ));
         }
 
-        let hunk = &file.hunks[self.selected_hunk];
+        let paragraph = paragraph
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Hunk Detail (Space to toggle){}", status_str)),
+            )
+            .scroll((scroll, 0));
+        frame.render_widget(paragraph, area);
+        self.max_scroll = max_scroll;
+    }
+
+    /// Render every visible hunk of a file at once, folding reviewed hunks to a
+    /// single line so only pending ones take up space. Scrolls to the selected hunk.
+    fn render_condensed(&self, frame: &mut Frame, area: Rect, file: &DiffFile) {
+        let file_path = file.path.to_string_lossy();
+        let ages = self
+            .db
+            .review_ages(&self.base_ref, &file_path)
+            .unwrap_or_default();
+        let auto_rules = self
+            .db
+            .auto_rules(&self.base_ref, &file_path)
+            .unwrap_or_default();
 
         let mut lines = Vec::new();
+        let mut selected_line = 0;
+        for hunk_idx in self.visible_hunks() {
+            let hunk = &file.hunks[hunk_idx];
+            let selected = hunk_idx == self.selected_hunk;
+            if selected {
+                selected_line = lines.len();
+            }
+            let header = format!(
+                "@@ -{},{} +{},{} @@ {}",
+                hunk.old_start, hunk.old_count, hunk.new_start, hunk.new_count, hunk.section
+            );
+            let emphasis = if selected {
+                Modifier::BOLD | Modifier::REVERSED
+            } else {
+                Modifier::empty()
+            };
 
-        // Add hunk header
-        let header = format!(
-            "@@ -{},{} +{},{} @@",
-            hunk.old_start, hunk.old_count, hunk.new_start, hunk.new_count
-        );
-        lines.push(Line::from(Span::styled(
-            header,
-            Style::default().fg(Color::Cyan),
-        )));
+            if hunk.status == HunkStatus::Reviewed {
+                let age = match (
+                    auto_rules.get(&hunk.content_hash),
+                    ages.get(&hunk.content_hash),
+                ) {
+                    (Some(rule), _) => format!(" — auto-approved ({})", rule),
+                    (None, Some(&secs)) => format!(" — reviewed {}", format_age(secs)),
+                    (None, None) => String::new(),
+                };
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "{} {}{}",
+                        status_symbol(hunk.status),
+                        header.trim_end(),
+                        age
+                    ),
+                    Style::default().fg(Color::DarkGray).add_modifier(emphasis),
+                )));
+                continue;
+            }
 
-        // Add hunk content with syntax highlighting
-        let file_ext = file.path.extension().and_then(|e| e.to_str()).unwrap_or("");
-        let mut fh = self.highlighter.for_file(file_ext);
-        for line in hunk.content.lines() {
-            let spans = fh.highlight_diff_line(line);
-            lines.push(Line::from(spans));
+            lines.push(Line::from(Span::styled(
+                format!("{} {}", status_symbol(hunk.status), header.trim_end()),
+                Style::default().fg(Color::Cyan).add_modifier(emphasis),
+            )));
+            let cached = self.highlight_cache.get(&hunk.content_hash);
+            let moved = self.moved.moved_lines(hunk);
+            for (i, (line, moved)) in hunk.content.lines().zip(moved).enumerate() {
+                let spans = cached
+                    .and_then(|cached| cached.get(i).cloned())
+                    .unwrap_or_else(|| crate::highlight::plain_diff_line(line));
+                lines.push(self.content_line(line, spans, moved));
+            }
+            lines.push(Line::from(""));
         }
 
-        let status_str = match hunk.status {
-            HunkStatus::Reviewed => " [REVIEWED]",
-            HunkStatus::Unreviewed => " [UNREVIEWED]",
-            HunkStatus::Stale => " [STALE]",
-        };
-
-        let text = Text::from(lines);
-        let paragraph = Paragraph::new(text)
+        // Not wrapped, so line indices match screen rows and the scroll lands on the selected hunk
+        let scroll = u16::try_from(selected_line)
+            .unwrap_or(u16::MAX)
+            .saturating_add(self.scroll_offset);
+        let paragraph = Paragraph::new(Text::from(lines))
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("Hunk Detail (Space to toggle){}", status_str)),
+                    .title(format!("{} — condensed (z to expand)", file_path)),
             )
-            .wrap(Wrap { trim: false })
-            .scroll((self.scroll_offset, 0));
-
+            .scroll((scroll, 0));
         frame.render_widget(paragraph, area);
     }
 
+    /// A line of hunk content: moved code in its own colors instead of the
+    /// highlighted `spans`, with whitespace markers when toggled on (`w`) and
+    /// tabs laid out to `[layout] tab_width`.
+    fn content_line(&self, line: &str, spans: Vec<Span<'static>>, moved: bool) -> Line<'static> {
+        let spans = if moved {
+            crate::highlight::moved_diff_line(line)
+        } else {
+            spans
+        };
+        let layout = self.config.layout.text_layout();
+        let spans = if self.show_whitespace {
+            crate::highlight::mark_whitespace(spans, layout)
+        } else {
+            crate::highlight::expand_tabs(spans, layout)
+        };
+        if self.wraps() || self.scroll_x == 0 {
+            Line::from(spans)
+        } else {
+            // The +/- marker stays put while the code scrolls
+            Line::from(scroll_spans(spans, 1, self.scroll_x))
+        }
+    }
+
+    /// Lines showing the before/after block art of an image hunk.
+    fn image_preview_lines(&self, content_hash: &str) -> Vec<Line<'static>> {
+        let Some(preview) = self.image_previews.get(content_hash) else {
+            return vec![Line::from("Loading image preview...")];
+        };
+
+        let mut lines = Vec::new();
+        for (label, side, color) in [
+            ("Before", &preview.old, Color::Red),
+            ("After", &preview.new, Color::Green),
+        ] {
+            lines.push(Line::from(Span::styled(
+                format!("{}:", label),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )));
+            match side {
+                None => lines.push(Line::from("  (none)")),
+                Some(PreviewImage { art: Some(art), .. }) => {
+                    lines.extend(art.iter().map(|row| {
+                        Line::from(
+                            row.iter()
+                                .map(|&([tr, tg, tb], [br, bg, bb])| {
+                                    Span::styled(
+                                        "▀",
+                                        Style::default()
+                                            .fg(Color::Rgb(tr, tg, tb))
+                                            .bg(Color::Rgb(br, bg, bb)),
+                                    )
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                    }));
+                }
+                Some(PreviewImage { bytes, art: None }) => lines.push(Line::from(format!(
+                    "  ({} bytes; no inline preview for this format)",
+                    bytes.len()
+                ))),
+            }
+            lines.push(Line::from(""));
+        }
+        if GraphicsProtocol::detect() != GraphicsProtocol::Blocks {
+            lines.push(Line::from(Span::styled(
+                "Press p for a full-resolution preview",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines
+    }
+
+    /// Load the image preview for the selected hunk if it is a binary image.
+    fn load_image_preview(&mut self) {
+        let Some(file) = self.files.get(self.selected_file) else {
+            return;
+        };
+        let Some(hunk) = file.hunks.get(self.selected_hunk) else {
+            return;
+        };
+        if self.image_previews.contains_key(&hunk.content_hash) {
+            return;
+        }
+        if let Some((old, new)) = parser::binary_blobs(hunk) {
+            let preview = ImagePreview::load(&file.path, old.as_deref(), new.as_deref());
+            self.image_previews
+                .insert(hunk.content_hash.clone(), preview);
+        }
+    }
+
+    /// Queue the selected image hunk for display through the terminal's graphics protocol.
+    fn request_full_preview(&mut self) {
+        let Some(preview) = self
+            .files
+            .get(self.selected_file)
+            .and_then(|file| file.hunks.get(self.selected_hunk))
+            .and_then(|hunk| self.image_previews.get(&hunk.content_hash))
+        else {
+            return;
+        };
+        let protocol = GraphicsProtocol::detect();
+        if protocol == GraphicsProtocol::Blocks {
+            self.status_message = Some((
+                "No terminal graphics protocol detected (set GIT_REVIEW_IMAGE_PROTOCOL)"
+                    .to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+
+        let mut out = String::new();
+        for (label, side) in [("Before", &preview.old), ("After", &preview.new)] {
+            out.push_str(&format!("{}:\r\n", label));
+            match side
+                .as_ref()
+                .map(|image| preview::graphics_sequence(protocol, &image.bytes))
+            {
+                None => out.push_str("(none)"),
+                Some(None) => out.push_str("(this terminal cannot show this format)"),
+                Some(Some(sequence)) => out.push_str(&sequence),
+            }
+            out.push_str("\r\n\r\n");
+        }
+        self.full_preview = Some(out);
+    }
+
     /// Render the status bar.
     fn render_status_bar(&self, frame: &mut Frame, area: Rect) {
+        const SEGMENT_WIDTH: usize = 30;
+
         let progress = self
             .db
             .progress(&self.base_ref)
@@ -948,6 +3787,9 @@ impl App {
                 reviewed: 0,
                 unreviewed: 0,
                 stale: 0,
+                needs_changes: 0,
+                skimmed: 0,
+                auto_approved: 0,
                 files_remaining: 0,
                 total_files: 0,
             });
@@ -956,86 +3798,428 @@ impl App {
             FilterMode::All => "All",
             FilterMode::Unreviewed => "Unreviewed",
             FilterMode::Stale => "Stale",
+            FilterMode::Skimmed => "Skimmed",
+            FilterMode::Bookmarked => "Bookmarked",
+            FilterMode::BaseMoved => "Affected by base move",
+        };
+        let mut filter_str = match &self.pattern {
+            Some(pattern) => format!("{} /{}/", filter_str, pattern.as_str()),
+            None => filter_str.to_string(),
+        };
+        if !self.file_filter.is_empty() {
+            filter_str.push_str(&format!(" ({})", self.file_filter));
+        }
+
+        let block = Block::default().borders(Borders::ALL);
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(1)])
+            .split(inner);
+
+        let ratio = if progress.total_hunks > 0 {
+            progress.reviewed as f64 / progress.total_hunks as f64
+        } else {
+            0.0
         };
+        let gauge = Gauge::default()
+            .gauge_style(
+                Style::default()
+                    .fg(term_color(self.palette().reviewed))
+                    .bg(Color::DarkGray),
+            )
+            .ratio(ratio)
+            .label(format!(
+                "{}/{} hunks reviewed ({:.0}%)",
+                progress.reviewed,
+                progress.total_hunks,
+                ratio * 100.0
+            ));
+        frame.render_widget(gauge, rows[0]);
 
-        let status_text = format!(
-            "{}/{} hunks reviewed ({} stale), {} files remaining | Filter: {} | Keys: j/k=nav Space=toggle F=approve-file A=approve-all Tab=file u/s/a=filter ?=help q=quit",
+        let [reviewed_w, unreviewed_w, stale_w] = segment_widths(
             progress.reviewed,
-            progress.total_hunks,
+            progress.unreviewed + progress.needs_changes + progress.skimmed,
             progress.stale,
-            progress.files_remaining,
-            filter_str
+            SEGMENT_WIDTH,
         );
+        if let Some((msg, _)) = &self.status_message {
+            frame.render_widget(
+                Paragraph::new(msg.clone()).style(Style::default().fg(Color::Yellow)),
+                rows[1],
+            );
+            return;
+        }
 
-        let paragraph = Paragraph::new(status_text)
-            .block(Block::default().borders(Borders::ALL))
+        // Segments differ in shading as well as color
+        let palette = self.palette();
+        let style = |status| Style::default().fg(term_color(palette.status(status)));
+        let mut segments = vec![
+            Span::styled("█".repeat(reviewed_w), style(HunkStatus::Reviewed)),
+            Span::styled("░".repeat(unreviewed_w), style(HunkStatus::Unreviewed)),
+            Span::styled("▒".repeat(stale_w), style(HunkStatus::Stale)),
+            Span::raw(" "),
+            Span::styled(
+                if progress.auto_approved > 0 {
+                    format!(
+                        "✓ {} reviewed ({} auto)",
+                        progress.reviewed, progress.auto_approved
+                    )
+                } else {
+                    format!("✓ {} reviewed", progress.reviewed)
+                },
+                style(HunkStatus::Reviewed),
+            ),
+            Span::raw(" · "),
+            Span::styled(
+                format!("○ {} unreviewed", progress.unreviewed),
+                style(HunkStatus::Unreviewed),
+            ),
+            Span::raw(" · "),
+            Span::styled(
+                format!("~ {} stale", progress.stale),
+                style(HunkStatus::Stale),
+            ),
+        ];
+        if progress.skimmed > 0 {
+            segments.extend([
+                Span::raw(" · "),
+                Span::styled(
+                    format!("◌ {} skimmed", progress.skimmed),
+                    style(HunkStatus::Skimmed),
+                ),
+            ]);
+        }
+        if let Some((location, _, _)) = self.status_location() {
+            segments.splice(
+                0..0,
+                [
+                    Span::styled(
+                        location,
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                    Span::raw(" "),
+                ],
+            );
+        }
+        if progress.needs_changes > 0 {
+            segments.push(Span::raw(" · "));
+            segments.push(Span::styled(
+                format!("✗ {} need changes", progress.needs_changes),
+                style(HunkStatus::NeedsChanges),
+            ));
+        }
+        if let Some(base_move) = &self.base_move {
+            segments.push(Span::raw(" · "));
+            segments.push(Span::styled(
+                format!("⇡ base moved, {} affected", base_move.hunks),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        let key = |actions: &[Action]| self.key_hint(View::HunkReview, actions);
+        segments.push(Span::raw(format!(
+            " | {} files remaining | Filter: {} | {}=nav {}=toggle {}=approve-file {}=approve-all {}=file {}=filter {}=help {}=quit",
+            progress.files_remaining,
+            filter_str,
+            key(&[Action::Next, Action::Previous]),
+            key(&[Action::ToggleReviewed]),
+            key(&[Action::ApproveFile]),
+            key(&[Action::ApproveAll]),
+            key(&[Action::NextFile]),
+            key(&[Action::ShowUnreviewed, Action::ShowStale, Action::ShowAll]),
+            key(&[Action::Help]),
+            key(&[Action::Quit]),
+        )));
+        frame.render_widget(Paragraph::new(Line::from(segments)), rows[1]);
+    }
+
+    /// Location of the current hunk shown at the start of the status bar's
+    /// second row: its text (`path:line`, shortened from the left if long), the
+    /// file it names, and the line.
+    fn status_location(&self) -> Option<(String, PathBuf, u32)> {
+        const MAX_WIDTH: usize = 40;
+
+        let file = self.files.get(self.selected_file)?;
+        let line = file.hunks.get(self.selected_hunk)?.new_start;
+        let text = format!("{}:{}", file.path.display(), line);
+        Some((
+            textlayout::truncate_start(&text, MAX_WIDTH),
+            file.path.clone(),
+            line,
+        ))
+    }
+
+    /// The status bar location as a hyperlink, with the screen position it was
+    /// drawn at, when links are enabled and nothing covers the status bar.
+    fn status_link(&self, screen: Rect) -> Option<(u16, u16, String)> {
+        let visible = matches!(self.view_mode, ViewMode::HunkReview { .. })
+            && self.help.is_none()
+            && !self.show_summary
+            && self.status_message.is_none()
+            && self.confirm_action.is_none()
+            && self.prompt.is_none()
+            && self.check.is_none();
+        if !visible || !self.config.links.enabled_for(true) || screen.height < 4 {
+            return None;
+        }
+        let (text, path, line) = self.status_location()?;
+        let url = self
+            .config
+            .links
+            .url(&self.repo_root.as_ref()?.join(path), Some(line));
+        // Inside the left border, on the second row of the 4-line status bar
+        Some((
+            screen.x + 1,
+            screen.bottom() - 2,
+            crate::links::hyperlink(&url, &text),
+        ))
+    }
+
+    /// Render the help overlay: the current view's key bindings by category,
+    /// narrowed to those matching the search.
+    fn render_help(&mut self, frame: &mut Frame) {
+        let Some(help) = &self.help else {
+            return;
+        };
+        let view = self.screen_view();
+        let name = match self.view_mode {
+            ViewMode::Dashboard => "Dashboard",
+            ViewMode::Repos => "Repositories",
+            ViewMode::HunkReview { .. } => "Review",
+        };
+        let query = help.query.to_lowercase();
+        let matches = |texts: &[&str]| texts.iter().any(|t| t.to_lowercase().contains(&query));
+
+        let mut sections: Vec<(&str, Vec<HelpRow>)> = Vec::new();
+        for entry in self
+            .keymap
+            .help(view)
+            .into_iter()
+            .chain(self.keymap.help(View::Pane))
+        {
+            let keys = if entry.keys.is_empty() {
+                "(unbound)".to_string()
+            } else {
+                let keys: Vec<String> = entry.keys.iter().map(Key::to_string).collect();
+                keys.join(" / ")
+            };
+            if !matches(&[&keys, entry.name, entry.description]) {
+                continue;
+            }
+            let title = entry.category.title();
+            match sections.last_mut() {
+                Some((last, rows)) if *last == title => {
+                    rows.push((keys, entry.description, entry.name));
+                }
+                _ => sections.push((title, vec![(keys, entry.description, entry.name)])),
+            }
+        }
+        if view == View::HunkReview {
+            let commands: Vec<HelpRow> = COMMANDS
+                .iter()
+                .filter(|(command, description)| matches(&[command, description]))
+                .map(|&(command, description)| (command.to_string(), description, ""))
+                .collect();
+            if !commands.is_empty() {
+                sections.push(("Commands", commands));
+            }
+        }
+
+        let mut lines: Vec<Line> = Vec::new();
+        for (title, rows) in sections {
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::styled(
+                format!("{}:", title),
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            for (keys, description, name) in rows {
+                lines.push(Line::from(vec![
+                    Span::raw(format!("  {:<13} - {}", keys, description)),
+                    Span::styled(format!("  {}", name), Style::default().fg(Color::DarkGray)),
+                ]));
+            }
+        }
+        if lines.is_empty() {
+            lines.push(Line::from(format!("No keys match '{}'", help.query)));
+        }
+
+        let area = centered_rect(60, 80, frame.area());
+        let height = usize::from(area.height.saturating_sub(2));
+        let scroll = help.scroll.min(lines.len().saturating_sub(height));
+        let title = if help.searching || !help.query.is_empty() {
+            format!("Help · {} Shortcuts · /{}", name, help.query)
+        } else {
+            format!("Help · {} Shortcuts", name)
+        };
+        let hints = if help.searching {
+            "type to search · Enter done · Esc clear".to_string()
+        } else {
+            format!(
+                "{} search · {} scroll · any other key closes",
+                self.keymap.hint(View::Pane, Action::FilterPattern),
+                self.key_hint(View::Pane, &[Action::Next, Action::Previous])
+            )
+        };
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .title_bottom(hints),
+            )
+            .scroll((scroll as u16, 0));
+        frame.render_widget(Clear, area);
+        frame.render_widget(paragraph, area);
+        if let Some(help) = &mut self.help {
+            help.scroll = scroll;
+        }
+    }
+
+    /// Render the review session summary shown when quitting hunk review.
+    fn render_summary(&self, frame: &mut Frame) {
+        let Some(session) = &self.session else {
+            return;
+        };
+        let progress = self.db.progress(&self.base_ref).ok();
+        let (reviewed, unreviewed, stale) = progress
+            .map(|p| (p.reviewed, p.unreviewed, p.stale))
+            .unwrap_or((0, 0, 0));
+
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "Review Session Summary",
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(format!(
+                "  Reviewed this session: {} hunks",
+                reviewed.saturating_sub(session.initial_reviewed)
+            )),
+            Line::from(format!("  Remaining:             {} hunks", unreviewed)),
+            Line::from(format!(
+                "  Stale:                 {} ({} discovered this session)",
+                stale, session.stale_discovered
+            )),
+            Line::from(format!(
+                "  Time spent:            {}",
+                format_duration(session.started.elapsed())
+            )),
+            Line::from(""),
+        ];
+
+        if self.commit_available() {
+            lines.push(Line::from(Span::styled(
+                "✓ Review gate passes — press c to commit",
+                Style::default().fg(Color::Green),
+            )));
+        } else if self.base_ref == "HEAD" {
+            lines.push(Line::from(Span::styled(
+                "✗ Review gate does not pass yet",
+                Style::default().fg(Color::Red),
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from("q/Enter: quit  Esc: back to review"));
+
+        let paragraph = Paragraph::new(Text::from(lines))
+            .block(Block::default().borders(Borders::ALL).title("Quit"))
             .wrap(Wrap { trim: false });
 
+        let area = centered_rect(60, 50, frame.area());
+        frame.render_widget(Clear, area);
         frame.render_widget(paragraph, area);
     }
 
-    /// Render the help overlay.
-    fn render_help(&self, frame: &mut Frame) {
-        let help_text: Vec<&str> = match self.view_mode {
-            ViewMode::Dashboard => vec![
-                "Git Review - Dashboard Shortcuts",
-                "",
-                "Navigation:",
-                "  j / Down      - Next branch",
-                "  k / Up        - Previous branch",
-                "",
-                "Actions:",
-                "  Enter         - Review selected branch",
-                "  M (Shift+M)   - Merge selected branch",
-                "  r             - Refresh branch list",
-                "",
-                "Other:",
-                "  ?             - Show this help",
-                "  q / Esc       - Quit",
-                "",
-                "Press any key to close this help",
-            ],
-            ViewMode::HunkReview { .. } => vec![
-                "Git Review - Keyboard Shortcuts",
-                "",
-                "Navigation:",
-                "  j / Down      - Next hunk",
-                "  k / Up        - Previous hunk",
-                "  Tab           - Next file",
-                "  Shift+Tab     - Previous file",
-                "  Ctrl+d/PgDn  - Scroll down",
-                "  Ctrl+u/PgUp  - Scroll up",
-                "",
-                "Actions:",
-                "  Space         - Toggle reviewed status",
-                "",
-                "Bulk Actions:",
-                "  F (Shift+F)   - Approve all hunks in current file",
-                "  A (Shift+A)   - Approve all hunks in all files",
-                "",
-                "Filters:",
-                "  u             - Show unreviewed hunks only",
-                "  s             - Show stale hunks only",
-                "  a             - Show all hunks",
-                "",
-                "Other:",
-                "  ?             - Show this help",
-                "  q / Esc       - Quit",
-                "",
-                "Press any key to close this help",
-            ],
-        };
-
-        let text = Text::from(help_text.iter().map(|&s| Line::from(s)).collect::<Vec<_>>());
-
-        let paragraph = Paragraph::new(text)
-            .block(Block::default().borders(Borders::ALL).title("Help"))
+    /// Render the active text prompt.
+    fn render_prompt(&self, frame: &mut Frame) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+        let title = match prompt.kind {
+            PromptKind::CommitMessage => "Commit message (Enter: commit, Esc: cancel)".to_string(),
+            PromptKind::Comment => "Comment on hunk (Enter: save, Esc: cancel)".to_string(),
+            PromptKind::Command => {
+                ":only <exts>, :exclude <exts>, :all, :affected, :stage (Enter: run, Esc: cancel)"
+                    .to_string()
+            }
+            PromptKind::FilterPattern => {
+                "Show hunks whose content or path matches regex (Enter: apply, empty: clear)"
+                    .to_string()
+            }
+            PromptKind::ApprovePattern => {
+                "Approve hunks whose changed lines all match regex (Enter: preview, Esc: cancel)"
+                    .to_string()
+            }
+            PromptKind::ApproveAll(count) => format!(
+                "Approve {} unreviewed hunks? Type {} or yes (Enter: approve, Esc: cancel)",
+                count, count
+            ),
+        };
+
+        let paragraph = Paragraph::new(format!("{}█", prompt.buffer))
+            .block(Block::default().borders(Borders::ALL).title(title))
             .wrap(Wrap { trim: false });
 
-        let area = centered_rect(60, 80, frame.area());
+        let popup = centered_rect(60, 20, frame.area());
+        let area = Rect {
+            height: popup.height.min(3),
+            ..popup
+        };
+        frame.render_widget(Clear, area);
         frame.render_widget(paragraph, area);
     }
 
+    /// Render the hunks related to the current one over the current view.
+    fn render_related(&self, frame: &mut Frame, pane: &RelatedPane) {
+        let area = centered_rect(80, 60, frame.area());
+        let palette = self.palette();
+        let items: Vec<ListItem> = pane
+            .hunks
+            .iter()
+            .map(|related| {
+                let file = &self.files[related.file_idx];
+                let hunk = &file.hunks[related.hunk_idx];
+                ListItem::new(format!(
+                    "{} {}:{}  {}",
+                    status_symbol(hunk.status),
+                    file.path.display(),
+                    hunk.new_start.max(1),
+                    related.symbols.join(", ")
+                ))
+                .style(Style::default().fg(term_color(palette.status(hunk.status))))
+            })
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(pane.selected));
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .title(format!(
+                        "Hunks touching the same symbols ({})",
+                        pane.hunks.len()
+                    ))
+                    .title_bottom(format!(
+                        "{} select · {} jump · {} close",
+                        self.key_hint(View::Pane, &[Action::Next, Action::Previous]),
+                        self.keymap.hint(View::Pane, Action::Open),
+                        self.keymap.hint(View::Pane, Action::Back)
+                    )),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut state);
+    }
+
     /// Render the confirmation modal.
     fn render_confirm(&self, frame: &mut Frame) {
         let message = match &self.confirm_action {
@@ -1052,23 +4236,47 @@ impl App {
                 )
             }
             Some(ConfirmAction::ApproveAll) => {
-                let count: usize = self
-                    .files
-                    .iter()
-                    .flat_map(|f| &f.hunks)
-                    .filter(|h| h.status != HunkStatus::Reviewed)
-                    .count();
+                let count = self.unreviewed_count();
                 format!(
                     "Approve {} unreviewed hunks in all files?\n\n(y)es / (n)o",
                     count
                 )
             }
-            Some(ConfirmAction::MergeBranch { branch }) => {
+            Some(ConfirmAction::ApproveMatching { pattern, hunks }) => {
+                let mut files: Vec<usize> = hunks.iter().map(|&(file_idx, _)| file_idx).collect();
+                files.dedup();
+                let paths: Vec<String> = files
+                    .iter()
+                    .map(|&file_idx| self.files[file_idx].path.to_string_lossy().to_string())
+                    .collect();
                 format!(
-                    "Merge branch '{}' into {}? (y/n)",
-                    branch, self.base_ref
+                    "Approve {} unreviewed hunks matching /{}/ in {} file(s)?\n\n{}\n\n(y)es / (n)o",
+                    hunks.len(),
+                    pattern,
+                    paths.len(),
+                    paths.join("\n")
                 )
             }
+            Some(ConfirmAction::MergeBranch { branch }) => {
+                format!("Merge branch '{}' into {}? (y/n)", branch, self.base_ref)
+            }
+            Some(ConfirmAction::ApproveBranches { branches }) => format!(
+                "Approve all hunks on {} branch(es)?\n\n{}\n\n(y)es / (n)o",
+                branches.len(),
+                branches.join("\n")
+            ),
+            Some(ConfirmAction::DeleteBranches { branches }) => format!(
+                "Delete {} merged branch(es) and archive their review state?\n\n{}\n\n(y)es / (n)o",
+                branches.len(),
+                branches.join("\n")
+            ),
+            Some(ConfirmAction::MergeQueued { branch }) => format!(
+                "Queued branch '{}' is fully reviewed and merges cleanly.\n\nMerge it into {}?\n\n(y)es / (n)o, take it off the queue",
+                branch,
+                self.dashboard
+                    .as_ref()
+                    .map_or(self.base_ref.as_str(), |d| d.base_branch.as_str())
+            ),
             None => return,
         };
 
@@ -1084,6 +4292,257 @@ impl App {
     }
 }
 
+/// Whether a typed confirmation is the expected hunk count or "yes".
+fn confirms_count(count: usize, answer: &str) -> bool {
+    let answer = answer.trim();
+    answer == count.to_string() || answer.eq_ignore_ascii_case("yes")
+}
+
+/// Render the check command's output over the review, following the end of
+/// the output unless scrolled back.
+fn render_check(frame: &mut Frame, pane: &mut CheckPane, keymap: &Keymap) {
+    let area = centered_rect(90, 80, frame.area());
+    let height = usize::from(area.height.saturating_sub(2));
+    pane.bottom = pane.run.lines().len().saturating_sub(height);
+    if let Some(scroll) = &mut pane.scroll {
+        *scroll = (*scroll).min(pane.bottom);
+    }
+    let scroll = pane.scroll.unwrap_or(pane.bottom);
+    let lines = pane.run.lines();
+
+    let (state, color) = match pane.run.status() {
+        None => ("running…".to_string(), Color::Yellow),
+        Some(_) if pane.history.is_some() => ("done".to_string(), Color::Cyan),
+        Some(status) if status.success() => ("passed".to_string(), Color::Green),
+        Some(status) => match status.code() {
+            Some(code) => (format!("failed (exit {})", code), Color::Red),
+            None => ("killed".to_string(), Color::Red),
+        },
+    };
+    let text: Vec<Line> = lines[scroll..]
+        .iter()
+        .take(height)
+        .map(|line| match &pane.history {
+            // git log -L output is a series of commits, each with a diff
+            Some(_) if line.starts_with("commit ") => Line::from(Span::styled(
+                line.as_str(),
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            Some(_) if line.starts_with("@@") => Line::from(Span::styled(
+                line.as_str(),
+                Style::default().fg(Color::Cyan),
+            )),
+            Some(_) => crate::highlight::plain_diff_line(line).into(),
+            None => Line::from(line.as_str()),
+        })
+        .collect();
+    let key = |action| keymap.hint(View::Pane, action);
+    let scroll = format!("{}/{} scroll", key(Action::Next), key(Action::Previous));
+    let (title, help) = match &pane.history {
+        Some(lines) => (
+            format!("History of {} — {}", lines, state),
+            format!(
+                "{scroll} · {} top · {} end · {} close",
+                key(Action::ScrollTop),
+                key(Action::ScrollBottom),
+                key(Action::Back)
+            ),
+        ),
+        None => (
+            format!("$ {} — {}", pane.run.command(), state),
+            format!(
+                "{scroll} · {} follow · {} rerun · {} close",
+                key(Action::ScrollBottom),
+                key(Action::Check),
+                key(Action::Back)
+            ),
+        ),
+    };
+    let paragraph = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(color))
+            .title(title)
+            .title_bottom(help),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// `spans` with `skip` columns cut out after the first `keep`, to scroll a
+/// line sideways while its start stays in view. Tabs must already be expanded.
+fn scroll_spans(spans: Vec<Span<'static>>, keep: usize, skip: usize) -> Vec<Span<'static>> {
+    let mut column = 0;
+    let mut scrolled = Vec::new();
+    for span in spans {
+        let text = format!(
+            "{}{}",
+            textlayout::clip(&span.content, column, 0, keep),
+            textlayout::clip(&span.content, column, keep + skip, usize::MAX)
+        );
+        column += span
+            .content
+            .chars()
+            .map(textlayout::char_width)
+            .sum::<usize>();
+        if !text.is_empty() {
+            scrolled.push(Span::styled(text, span.style));
+        }
+    }
+    scrolled
+}
+
+/// The keymap's name for a key press, if it can be bound.
+fn keymap_key(key: &event::KeyEvent) -> Option<Key> {
+    let name = match key.code {
+        KeyCode::Char(c) => KeyName::Char(c),
+        KeyCode::Enter => KeyName::Enter,
+        KeyCode::Esc => KeyName::Esc,
+        KeyCode::Tab => KeyName::Tab,
+        KeyCode::BackTab => KeyName::BackTab,
+        KeyCode::Backspace => KeyName::Backspace,
+        KeyCode::Up => KeyName::Up,
+        KeyCode::Down => KeyName::Down,
+        KeyCode::Left => KeyName::Left,
+        KeyCode::Right => KeyName::Right,
+        KeyCode::PageUp => KeyName::PageUp,
+        KeyCode::PageDown => KeyName::PageDown,
+        KeyCode::Home => KeyName::Home,
+        KeyCode::End => KeyName::End,
+        _ => return None,
+    };
+    Some(Key {
+        name,
+        ctrl: key.modifiers.contains(KeyModifiers::CONTROL),
+        alt: key.modifiers.contains(KeyModifiers::ALT),
+    })
+}
+
+/// Render the review timeline over the current view.
+fn render_timeline(frame: &mut Frame, pane: &mut TimelinePane, keymap: &Keymap) {
+    let area = centered_rect(90, 80, frame.area());
+    let height = usize::from(area.height.saturating_sub(2));
+    pane.bottom = pane.lines.len().saturating_sub(height);
+    if let Some(scroll) = &mut pane.scroll {
+        *scroll = (*scroll).min(pane.bottom);
+    }
+    let scroll = pane.scroll.unwrap_or(pane.bottom);
+    let text: Vec<Line> = pane.lines[scroll..]
+        .iter()
+        .take(height)
+        .map(|line| match line.split_once("  ") {
+            Some((at, event)) => Line::from(vec![
+                Span::styled(at, Style::default().fg(Color::DarkGray)),
+                Span::raw("  "),
+                Span::raw(event),
+            ]),
+            None => Line::from(line.as_str()),
+        })
+        .collect();
+    let paragraph = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!("Review history of {}", pane.branch))
+            .title_bottom(format!(
+                "{}/{} scroll · {} top · {} end · {} close",
+                keymap.hint(View::Pane, Action::Next),
+                keymap.hint(View::Pane, Action::Previous),
+                keymap.hint(View::Pane, Action::ScrollTop),
+                keymap.hint(View::Pane, Action::ScrollBottom),
+                keymap.hint(View::Pane, Action::Back)
+            )),
+    );
+    frame.render_widget(Clear, area);
+    frame.render_widget(paragraph, area);
+}
+
+/// Format a duration as a short human-readable string (e.g. "1h 5m", "3m 20s").
+/// Dashboard cell with a branch's review percentage, its status symbol, and color.
+fn review_cell(
+    palette: &Palette,
+    progress: Option<&crate::dashboard::ReviewProgress>,
+) -> Cell<'static> {
+    match progress {
+        Some(p) if p.total > 0 => {
+            let counts = StatusCounts::from(p);
+            Cell::from(format!(
+                "{} {:.0}%",
+                status_symbol(summary_status(counts)),
+                (p.reviewed as f64 / p.total as f64) * 100.0
+            ))
+            .style(Style::default().fg(term_color(palette.summary(counts))))
+        }
+        _ => Cell::from("-"),
+    }
+}
+
+/// The terminal color of a palette entry.
+fn term_color(color: PaletteColor) -> Color {
+    match color {
+        PaletteColor::Black => Color::Black,
+        PaletteColor::Red => Color::Red,
+        PaletteColor::Green => Color::Green,
+        PaletteColor::Yellow => Color::Yellow,
+        PaletteColor::Blue => Color::Blue,
+        PaletteColor::Magenta => Color::Magenta,
+        PaletteColor::Cyan => Color::Cyan,
+        PaletteColor::Gray => Color::Gray,
+        PaletteColor::DarkGray => Color::DarkGray,
+        PaletteColor::LightRed => Color::LightRed,
+        PaletteColor::LightGreen => Color::LightGreen,
+        PaletteColor::LightYellow => Color::LightYellow,
+        PaletteColor::LightBlue => Color::LightBlue,
+        PaletteColor::LightMagenta => Color::LightMagenta,
+        PaletteColor::LightCyan => Color::LightCyan,
+        PaletteColor::White => Color::White,
+        PaletteColor::Rgb(r, g, b) => Color::Rgb(r, g, b),
+        PaletteColor::Indexed(i) => Color::Indexed(i),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Split `width` cells between reviewed, unreviewed and stale counts.
+///
+/// Widths are proportional to the counts and always sum to `width` when there is
+/// at least one hunk. Each segment gets its rounded-down share, and the cells
+/// left over go to the segments with the largest remainders (the unreviewed
+/// one first on a tie).
+fn segment_widths(reviewed: usize, unreviewed: usize, stale: usize, width: usize) -> [usize; 3] {
+    let counts = [reviewed, unreviewed, stale];
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return [0, 0, 0];
+    }
+    let mut widths = counts.map(|count| count * width / total);
+    let mut order = [1, 0, 2];
+    order.sort_by_key(|&i| std::cmp::Reverse(counts[i] * width % total));
+    let left = width - widths.iter().sum::<usize>();
+    for &i in order.iter().take(left) {
+        widths[i] += 1;
+    }
+    widths
+}
+
+/// Load file assignments for a base ref (empty on error).
+fn load_assignees(db: &ReviewDb, base_ref: &str) -> HashMap<String, String> {
+    db.assignments(base_ref)
+        .map(|rows| rows.into_iter().collect())
+        .unwrap_or_default()
+}
+
 /// Create a centered rectangle.
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -1111,6 +4570,7 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)
         .context("Failed to enter alternate screen")?;
+    crate::logging::pause_stderr(true);
     let backend = CrosstermBackend::new(stdout);
     Terminal::new(backend).context("Failed to create terminal")
 }
@@ -1125,13 +4585,15 @@ fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Re
     )
     .context("Failed to leave alternate screen")?;
     terminal.show_cursor().context("Failed to show cursor")?;
+    crate::logging::pause_stderr(false);
     Ok(())
 }
 
 /// Launch the interactive TUI review interface.
 ///
 /// Accepts a pre-configured App (created via `App::new_hunk_review` or `App::new_dashboard`).
-pub fn run_tui(mut app: App) -> Result<()> {
+/// Returns the follow-up action the user chose when quitting.
+pub fn run_tui(mut app: App) -> Result<ExitAction> {
     // Setup panic hook to restore terminal
     let original_hook = std::panic::take_hook();
     std::panic::set_hook(Box::new(move |panic_info| {
@@ -1144,10 +4606,30 @@ pub fn run_tui(mut app: App) -> Result<()> {
 
     // Main event loop
     let result = (|| -> Result<()> {
+        // Status bar link last written, so unchanged frames don't rewrite it
+        let mut drawn_link = None;
         loop {
-            terminal
+            let frame = terminal
                 .draw(|f| app.render(f))
                 .context("Failed to draw frame")?;
+            // ratatui cells can't carry OSC 8 links, so the status bar's file
+            // location is rewritten as a link over the drawn text
+            let link = app.status_link(frame.area);
+            if link != drawn_link
+                && let Some((x, y, link)) = &link
+            {
+                execute!(
+                    io::stdout(),
+                    cursor::MoveTo(*x, *y),
+                    SetForegroundColor(crossterm::style::Color::Cyan),
+                    SetAttribute(Attribute::Underlined),
+                    Print(link),
+                    SetAttribute(Attribute::Reset),
+                    ResetColor
+                )
+                .context("Failed to draw link")?;
+            }
+            drawn_link = link;
 
             if app.should_quit {
                 break;
@@ -1162,11 +4644,35 @@ pub fn run_tui(mut app: App) -> Result<()> {
                 }
             }
 
+            app.prefetch_highlights();
+            app.load_image_preview();
+            app.poll_check();
+            app.poll_ai();
+            app.poll_blame();
+
+            if let Some(sequence) = app.full_preview.take() {
+                show_full_preview(&mut terminal, &sequence)?;
+                drawn_link = None;
+            }
+
+            if let Some((range, file)) = app.pending_difftool.take() {
+                let tool = app.config.difftool.tool.clone();
+                let opened = suspend_terminal(&mut terminal, || {
+                    git::difftool(&range, &file, tool.as_deref())
+                })?;
+                drawn_link = None;
+                if let Err(e) = opened {
+                    app.status_message = Some((e.to_string(), Instant::now()));
+                }
+            }
+
             // Auto-refresh in dashboard mode (every 5 seconds)
             if matches!(app.view_mode, ViewMode::Dashboard)
                 && app.last_refresh.elapsed() >= Duration::from_secs(5)
             {
+                app.poll_fetch();
                 app.try_refresh_dashboard();
+                app.process_merge_queue();
                 app.last_refresh = Instant::now();
             }
         }
@@ -1176,5 +4682,140 @@ pub fn run_tui(mut app: App) -> Result<()> {
     // Restore terminal in all cases
     restore_terminal(&mut terminal)?;
 
-    result
+    result.map(|()| app.exit_action)
+}
+
+/// Hand the terminal to another program for the duration of `f`, then redraw the TUI.
+fn suspend_terminal<T>(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    f: impl FnOnce() -> T,
+) -> Result<T> {
+    restore_terminal(terminal)?;
+    let result = f();
+    enable_raw_mode().context("Failed to enable raw mode")?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)
+        .context("Failed to enter alternate screen")?;
+    crate::logging::pause_stderr(true);
+    terminal.clear().context("Failed to redraw")?;
+    Ok(result)
+}
+
+/// Leave the TUI, draw an image with the terminal's graphics protocol, and
+/// return once a key is pressed.
+fn show_full_preview(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    sequence: &str,
+) -> Result<()> {
+    use std::io::Write;
+
+    execute!(io::stdout(), LeaveAlternateScreen)?;
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b[2J\x1b[H{}Press any key to return", sequence)?;
+    stdout.flush()?;
+
+    loop {
+        if let Event::Key(key) = event::read().context("Failed to read event")?
+            && key.kind == event::KeyEventKind::Press
+        {
+            break;
+        }
+    }
+
+    // Kitty keeps images around until told otherwise
+    write!(stdout, "\x1b_Ga=d\x1b\\\x1b[2J")?;
+    stdout.flush()?;
+    execute!(io::stdout(), EnterAlternateScreen)?;
+    terminal.clear().context("Failed to redraw after preview")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_confirmation_needs_the_count_or_yes() {
+        assert!(confirms_count(250, "250"));
+        assert!(confirms_count(250, " YES "));
+        assert!(!confirms_count(250, "y"));
+        assert!(!confirms_count(250, "25"));
+        assert!(!confirms_count(250, ""));
+    }
+
+    #[test]
+    fn segment_widths_fill_available_width() {
+        assert_eq!(segment_widths(1, 1, 1, 30), [10, 10, 10]);
+        assert_eq!(segment_widths(1, 1, 1, 10), [3, 4, 3]);
+        assert_eq!(segment_widths(5, 0, 0, 30), [30, 0, 0]);
+        assert_eq!(segment_widths(2, 0, 1, 10), [7, 0, 3]);
+        assert_eq!(segment_widths(1, 0, 2, 10), [3, 0, 7]);
+    }
+
+    #[test]
+    fn format_duration_units() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(200)), "3m 20s");
+        assert_eq!(format_duration(Duration::from_secs(3900)), "1h 5m");
+    }
+
+    #[test]
+    fn scrolled_lines_keep_their_start_and_styles() {
+        let red = Style::default().fg(Color::Red);
+        let spans = vec![Span::styled("+let", red), Span::raw(" x = 1;")];
+        let text = |spans: Vec<Span>| -> Vec<String> {
+            spans.iter().map(|s| s.content.to_string()).collect()
+        };
+        assert_eq!(text(scroll_spans(spans.clone(), 1, 2)), ["+t", " x = 1;"]);
+        assert_eq!(scroll_spans(spans.clone(), 1, 2)[0].style, red);
+        assert_eq!(text(scroll_spans(spans.clone(), 1, 4)), ["+", "x = 1;"]);
+        assert_eq!(text(scroll_spans(spans, 1, 50)), ["+"]);
+
+        // Wide characters scroll by their columns, half cut ones become a space
+        let wide = vec![Span::raw("+日本語")];
+        assert_eq!(text(scroll_spans(wide.clone(), 1, 2)), ["+本語"]);
+        assert_eq!(text(scroll_spans(wide, 1, 3)), ["+ 語"]);
+    }
+
+    #[test]
+    fn bulk_approvals_need_files_to_approve() {
+        let dir = tempfile::tempdir().unwrap();
+        let open = || ReviewDb::open(&dir.path().join("review.db")).unwrap();
+        let press = |app: &mut App, c| {
+            app.handle_hunk_review_input(event::KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::SHIFT,
+            ))
+            .unwrap();
+        };
+
+        // With no file selected the guarded arms fall through to doing nothing
+        let mut app =
+            App::new_hunk_review(Vec::new(), open(), "main..feature".to_string()).unwrap();
+        for c in ['F', 'A'] {
+            press(&mut app, c);
+            assert!(app.confirm_action.is_none() && app.prompt.is_none());
+            assert!(app.status_message.is_none() && !app.should_quit);
+        }
+
+        let files = parser::parse_diff(
+            "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-a\n+b\n",
+        );
+        let mut app = App::new_hunk_review(files, open(), "main..feature".to_string()).unwrap();
+        press(&mut app, 'F');
+        assert!(matches!(
+            app.confirm_action,
+            Some(ConfirmAction::ApproveAllFile { file_idx: 0 })
+        ));
+        app.confirm_action = None;
+        press(&mut app, 'A');
+        assert!(matches!(
+            app.confirm_action,
+            Some(ConfirmAction::ApproveAll)
+        ));
+    }
+
+    #[test]
+    fn segment_widths_empty_progress() {
+        assert_eq!(segment_widths(0, 0, 0, 30), [0, 0, 0]);
+    }
 }
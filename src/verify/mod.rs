@@ -0,0 +1,278 @@
+//! Integrity check of stored review state (`verify`).
+//!
+//! Re-derives the hunk hashes of a range from the live diff and cross-checks
+//! every database row stored for it. Normal use never produces the duplicated
+//! and mismatched rows found here; they point at a corrupted database or at
+//! state written by a hand-edited import. Orphaned rows and missing hunks are
+//! also what a range looks like when its diff has changed since it was last
+//! synced, so they are reported apart (see [`IssueKind::is_unsynced`]).
+
+use crate::state::{ReviewDb, StateError, StoredHunk, status_from_str, status_to_string};
+use crate::{DiffFile, HunkStatus};
+use std::collections::{HashMap, HashSet};
+
+pub type Result<T> = std::result::Result<T, StateError>;
+
+/// What is wrong with a row (or, for [`IssueKind::Missing`], a hunk of the diff).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueKind {
+    /// A row that isn't stale, for a hunk the diff doesn't contain.
+    Orphaned,
+    /// A second row for the same file and hash.
+    Duplicated,
+    /// A row contradicting itself: a malformed hash, an unknown status, or
+    /// reviewer fields that don't match the status.
+    Mismatched,
+    /// A hunk of the diff without a row.
+    Missing,
+}
+
+impl IssueKind {
+    /// Whether syncing the range with its diff clears the issue, so it may just
+    /// mean the diff moved on since the review was last opened.
+    pub fn is_unsynced(self) -> bool {
+        matches!(self, IssueKind::Orphaned | IssueKind::Missing)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            IssueKind::Orphaned => "orphaned",
+            IssueKind::Duplicated => "duplicated",
+            IssueKind::Mismatched => "mismatched",
+            IssueKind::Missing => "missing",
+        }
+    }
+}
+
+/// One integrity problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub kind: IssueKind,
+    pub file_path: String,
+    pub content_hash: String,
+    /// Database row, if the issue is about one.
+    pub row: Option<i64>,
+    pub detail: String,
+}
+
+/// Whether `hash` looks like a content hash the parser produces (SHA-256 in lowercase hex).
+fn is_content_hash(hash: &str) -> bool {
+    hash.len() == 64 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Describe what is inconsistent about a row on its own, if anything.
+fn row_problem(row: &StoredHunk) -> Option<String> {
+    if !is_content_hash(&row.content_hash) {
+        return Some("hash is not a SHA-256 content hash".to_string());
+    }
+    let status = match status_from_str(&row.status) {
+        Ok(status) => status,
+        Err(_) => return Some(format!("unknown status {:?}", row.status)),
+    };
+    match (status, &row.reviewed_at, &row.reviewed_by) {
+        (HunkStatus::Reviewed, None, _) => Some("reviewed without a review time".to_string()),
+        (HunkStatus::Reviewed, Some(_), _) | (_, None, None) => None,
+        (status, _, _) => Some(format!(
+            "{} but has reviewer fields set",
+            status_to_string(status)
+        )),
+    }
+}
+
+/// Cross-check the rows stored under `base_ref` against the hunks of `files`.
+pub fn verify(db: &ReviewDb, base_ref: &str, files: &[DiffFile]) -> Result<Vec<Issue>> {
+    let live: HashSet<(String, String)> = files
+        .iter()
+        .flat_map(|file| {
+            let path = file.path.to_string_lossy().to_string();
+            file.hunks
+                .iter()
+                .map(move |hunk| (path.clone(), hunk.content_hash.clone()))
+        })
+        .collect();
+
+    let mut issues = Vec::new();
+    let mut seen: HashMap<(String, String), i64> = HashMap::new();
+    for row in db.stored_hunks(base_ref)? {
+        let key = (row.file_path.clone(), row.content_hash.clone());
+        let issue = |kind, detail: String| Issue {
+            kind,
+            file_path: row.file_path.clone(),
+            content_hash: row.content_hash.clone(),
+            row: Some(row.id),
+            detail,
+        };
+
+        if let Some(first) = seen.get(&key) {
+            issues.push(issue(
+                IssueKind::Duplicated,
+                format!("same hunk as row {}", first),
+            ));
+            continue;
+        }
+        seen.insert(key.clone(), row.id);
+
+        if let Some(problem) = row_problem(&row) {
+            issues.push(issue(IssueKind::Mismatched, problem));
+        } else if row.status != status_to_string(HunkStatus::Stale) && !live.contains(&key) {
+            issues.push(issue(
+                IssueKind::Orphaned,
+                format!("{} but not in the diff", row.status),
+            ));
+        }
+    }
+
+    for file in files {
+        let path = file.path.to_string_lossy().to_string();
+        for hunk in &file.hunks {
+            if !seen.contains_key(&(path.clone(), hunk.content_hash.clone())) {
+                issues.push(Issue {
+                    kind: IssueKind::Missing,
+                    file_path: path.clone(),
+                    content_hash: hunk.content_hash.clone(),
+                    row: None,
+                    detail: format!("line {} has no stored status", hunk.new_start),
+                });
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// Fix the issues [`verify`] found.
+///
+/// Duplicated rows and rows with a malformed hash are deleted (and the
+/// database's one-row-per-hunk constraint restored), other mismatched rows are
+/// reset to unreviewed, and a re-sync with the diff marks orphaned rows stale
+/// and adds missing hunks as unreviewed.
+pub fn repair(
+    db: &mut ReviewDb,
+    base_ref: &str,
+    files: &[DiffFile],
+    issues: &[Issue],
+) -> Result<()> {
+    let mut delete = Vec::new();
+    let mut reset = Vec::new();
+    for issue in issues {
+        match (issue.kind, issue.row) {
+            (IssueKind::Duplicated, Some(row)) => delete.push(row),
+            (IssueKind::Mismatched, Some(row)) if !is_content_hash(&issue.content_hash) => {
+                delete.push(row)
+            }
+            (IssueKind::Mismatched, Some(row)) => reset.push(row),
+            _ => {}
+        }
+    }
+    db.delete_hunk_rows(&delete)?;
+    if issues
+        .iter()
+        .any(|issue| issue.kind == IssueKind::Duplicated)
+    {
+        db.restore_hunk_uniqueness()?;
+    }
+    db.reset_hunk_rows(&reset)?;
+    db.sync_with_diff(base_ref, files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiffHunk;
+    use crate::parser::compute_hash;
+    use rusqlite::{Connection, params};
+    use std::path::PathBuf;
+
+    /// A database whose hunks table lacks the UNIQUE constraint, as a damaged
+    /// or hand-built one might.
+    fn damaged_db(path: &std::path::Path) -> ReviewDb {
+        Connection::open(path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE hunks (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    base_ref TEXT NOT NULL,
+                    file_path TEXT NOT NULL,
+                    content_hash TEXT NOT NULL,
+                    status TEXT NOT NULL DEFAULT 'unreviewed',
+                    reviewed_at TEXT,
+                    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+                )",
+            )
+            .unwrap();
+        ReviewDb::open(path).unwrap()
+    }
+
+    fn insert(path: &std::path::Path, file: &str, hash: &str, status: &str, reviewed: bool) {
+        Connection::open(path)
+            .unwrap()
+            .execute(
+                "INSERT INTO hunks (base_ref, file_path, content_hash, status, reviewed_at)
+                 VALUES ('main..feature', ?1, ?2, ?3, CASE WHEN ?4 THEN datetime('now') END)",
+                params![file, hash, status, reviewed],
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn finds_and_repairs_each_kind_of_issue() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("review.db");
+        let mut db = damaged_db(&path);
+
+        let hash = |n: u32| compute_hash(&n.to_string());
+        let files = vec![DiffFile {
+            path: PathBuf::from("src/a.rs"),
+            hunks: (0..4)
+                .map(|n| DiffHunk {
+                    old_start: n * 10 + 1,
+                    old_count: 1,
+                    new_start: n * 10 + 1,
+                    new_count: 1,
+                    content: n.to_string().into(),
+                    content_hash: hash(n),
+                    section: String::new(),
+                    status: HunkStatus::Unreviewed,
+                })
+                .collect(),
+        }];
+        insert(&path, "src/a.rs", &hash(0), "reviewed", true);
+        insert(&path, "src/a.rs", &hash(0), "unreviewed", false);
+        insert(&path, "src/a.rs", &hash(1), "reviewed", false);
+        insert(&path, "src/a.rs", &hash(2), "approved", true);
+        insert(&path, "src/b.rs", &hash(9), "reviewed", true);
+        insert(&path, "src/b.rs", &hash(8), "stale", false);
+        insert(&path, "src/a.rs", "forged", "reviewed", true);
+
+        let issues = verify(&db, "main..feature", &files).unwrap();
+        let kinds: Vec<(IssueKind, String)> = issues
+            .iter()
+            .map(|issue| (issue.kind, issue.content_hash.clone()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (IssueKind::Duplicated, hash(0)),
+                (IssueKind::Mismatched, hash(1)),
+                (IssueKind::Mismatched, hash(2)),
+                (IssueKind::Orphaned, hash(9)),
+                (IssueKind::Mismatched, "forged".to_string()),
+                (IssueKind::Missing, hash(3)),
+            ]
+        );
+
+        assert_eq!(
+            issues
+                .iter()
+                .filter(|issue| issue.kind.is_unsynced())
+                .count(),
+            2
+        );
+
+        repair(&mut db, "main..feature", &files, &issues).unwrap();
+        assert!(verify(&db, "main..feature", &files).unwrap().is_empty());
+        let progress = db.progress("main..feature").unwrap();
+        assert_eq!(progress.reviewed, 1);
+        assert_eq!(progress.unreviewed, 3);
+        assert_eq!(progress.stale, 2);
+    }
+}
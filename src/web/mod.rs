@@ -0,0 +1,700 @@
+//! Web dashboard and REST API (`serve --http`).
+//!
+//! An HTTP server on `tiny_http` for teammates without terminal access, and for
+//! bots and CI. Everything is rendered from the same review database the TUI
+//! uses:
+//!
+//! | Request                        | Response                                         |
+//! |--------------------------------|--------------------------------------------------|
+//! | `GET /`                        | HTML: every branch with its review progress      |
+//! | `GET /branch?name=<name>`      | HTML: the branch's diff, hunk statuses, comments |
+//! | `GET /branches`                | JSON: every branch with its review progress      |
+//! | `GET /branches/<name>/hunks`   | JSON: the branch's files and hunks               |
+//! | `POST /hunks/<hash>/status`    | JSON: set a hunk's status                        |
+//!
+//! `$GIT_REVIEW_API_TOKEN` holds the accepted tokens, as `name:token` pairs
+//! separated by commas (a bare token is credited to `api`). Once it is set,
+//! every request needs one of them: the JSON endpoints as `Authorization:
+//! Bearer <token>`, the HTML pages as the password of HTTP basic auth. Status
+//! changes are credited to the name of the token used. While the variable is
+//! unset, the HTML pages are open and the JSON endpoints disabled.
+//!
+//! Each request is answered on its own thread. Pages and `GET` endpoints only
+//! read: they compare each branch's diff with the stored state instead of
+//! syncing it, so looking never marks hunks stale or approved.
+
+use crate::dashboard::Dashboard;
+use crate::report::{self, escape_html};
+use crate::session::ReviewSession;
+use crate::state::{status_from_str, status_to_string};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::{Value, json};
+use std::io::Read;
+
+/// Environment variable holding the tokens the server accepts.
+pub const API_TOKEN_ENV: &str = "GIT_REVIEW_API_TOKEN";
+
+/// Reviewer credited for a token listed without a name.
+const DEFAULT_TOKEN_NAME: &str = "api";
+
+/// Largest request body accepted, in bytes.
+const MAX_BODY: usize = 64 * 1024;
+
+/// The parts of a request the server looks at.
+#[derive(Debug, Default)]
+struct Request {
+    method: String,
+    target: String,
+    authorization: Option<String>,
+    body: String,
+}
+
+/// A response ready to be written to the client.
+struct Response {
+    status: &'static str,
+    content_type: &'static str,
+    body: String,
+    /// Ask the browser for basic auth credentials.
+    challenge: bool,
+}
+
+impl Response {
+    fn ok(body: String) -> Self {
+        Self {
+            status: "200 OK",
+            content_type: "text/html; charset=utf-8",
+            body,
+            challenge: false,
+        }
+    }
+
+    fn error(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/html; charset=utf-8",
+            body: page(status, &format!("<p>{}</p>", escape_html(message))),
+            challenge: false,
+        }
+    }
+
+    fn json(value: Value) -> Self {
+        Self {
+            status: "200 OK",
+            content_type: "application/json",
+            body: value.to_string(),
+            challenge: false,
+        }
+    }
+
+    fn json_error(status: &'static str, message: &str) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: json!({ "error": message }).to_string(),
+            challenge: false,
+        }
+    }
+}
+
+/// A token the server accepts and the reviewer it stands for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    name: String,
+    secret: String,
+}
+
+/// Parse the value of [`API_TOKEN_ENV`]: comma-separated `name:token` pairs
+/// or bare tokens.
+fn parse_tokens(value: &str) -> Vec<Token> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once(':') {
+            Some((name, secret)) if !name.is_empty() && !secret.is_empty() => Token {
+                name: name.to_string(),
+                secret: secret.to_string(),
+            },
+            _ => Token {
+                name: DEFAULT_TOKEN_NAME.to_string(),
+                secret: entry.to_string(),
+            },
+        })
+        .collect()
+}
+
+/// Name of the token the request presents, as a bearer token or as the
+/// password of basic auth, if it matches one of `tokens`.
+fn authenticate<'a>(request: &Request, tokens: &'a [Token]) -> Option<&'a str> {
+    let value = request.authorization.as_deref()?;
+    let presented = if let Some(bearer) = value.strip_prefix("Bearer ") {
+        bearer.trim().to_string()
+    } else {
+        let decoded = BASE64.decode(value.strip_prefix("Basic ")?.trim()).ok()?;
+        let credentials = String::from_utf8(decoded).ok()?;
+        credentials.split_once(':')?.1.to_string()
+    };
+    // Check every token so the time taken doesn't tell which one nearly matched
+    tokens
+        .iter()
+        .filter(|token| constant_time_eq(&presented, &token.secret))
+        .fold(None, |found, token| found.or(Some(token.name.as_str())))
+}
+
+/// Serve the dashboard on `addr` until the process is stopped.
+pub fn serve(addr: &str) -> std::io::Result<()> {
+    let server = tiny_http::Server::http(addr).map_err(std::io::Error::other)?;
+    if let Some(addr) = server.server_addr().to_ip() {
+        println!("Serving review dashboard on http://{}", addr);
+    }
+    for request in server.incoming_requests() {
+        // A client hanging up mid-request should not stop the server
+        std::thread::spawn(move || {
+            let _ = handle_request(request);
+        });
+    }
+    Ok(())
+}
+
+/// Answer one request.
+fn handle_request(mut request: tiny_http::Request) -> std::io::Result<()> {
+    let parsed = read_request(&mut request)?;
+    let tokens = parse_tokens(&std::env::var(API_TOKEN_ENV).unwrap_or_default());
+    let response = respond(&parsed, &tokens);
+    let code = response
+        .status
+        .split(' ')
+        .next()
+        .and_then(|code| code.parse::<u16>().ok())
+        .unwrap_or(500);
+    let mut reply = tiny_http::Response::from_string(response.body)
+        .with_status_code(code)
+        .with_header(header("Content-Type", response.content_type));
+    if response.challenge {
+        reply.add_header(header("WWW-Authenticate", "Basic realm=\"git-review\""));
+    }
+    request.respond(reply)
+}
+
+fn header(name: &str, value: &str) -> tiny_http::Header {
+    tiny_http::Header::from_bytes(name.as_bytes(), value.as_bytes())
+        .expect("header names and values are ASCII")
+}
+
+/// The method, target, authorization, and body (up to [`MAX_BODY`]) of a request.
+fn read_request(request: &mut tiny_http::Request) -> std::io::Result<Request> {
+    let authorization = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Authorization"))
+        .map(|header| header.value.as_str().to_string());
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY as u64)
+        .read_to_end(&mut body)?;
+    Ok(Request {
+        method: request.method().as_str().to_string(),
+        target: request.url().to_string(),
+        authorization,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+/// Route a request to a page or API endpoint. `tokens` are the accepted
+/// tokens; with none, pages are open and the API is disabled.
+fn respond(request: &Request, tokens: &[Token]) -> Response {
+    if request.method.is_empty() || request.target.is_empty() {
+        return Response::error("400 Bad Request", "Malformed request");
+    }
+    let (path, query) = request
+        .target
+        .split_once('?')
+        .unwrap_or((&request.target, ""));
+
+    if path == "/branches" || path.starts_with("/branches/") || path.starts_with("/hunks/") {
+        return api(request, path, tokens);
+    }
+    if !tokens.is_empty() && authenticate(request, tokens).is_none() {
+        return Response {
+            challenge: true,
+            ..Response::error(
+                "401 Unauthorized",
+                "Log in with any user name and an API token as the password",
+            )
+        };
+    }
+    if request.method != "GET" {
+        return Response::error("405 Method Not Allowed", "The dashboard is read-only");
+    }
+    match path {
+        "/" => index(),
+        "/branch" => match query_param(query, "name") {
+            Some(name) => branch(&name),
+            None => Response::error("400 Bad Request", "Missing branch name"),
+        },
+        _ => Response::error("404 Not Found", "No such page"),
+    }
+}
+
+/// Handle a JSON API request after checking its bearer token.
+fn api(request: &Request, path: &str, tokens: &[Token]) -> Response {
+    if tokens.is_empty() {
+        return Response::json_error(
+            "403 Forbidden",
+            &format!("The API is disabled; set {} to enable it", API_TOKEN_ENV),
+        );
+    }
+    let Some(reviewer) = authenticate(request, tokens) else {
+        return Response::json_error("401 Unauthorized", "Missing or wrong bearer token");
+    };
+
+    let result = match (request.method.as_str(), path) {
+        ("GET", "/branches") => api_branches(),
+        ("GET", path) if path.starts_with("/branches/") && path.ends_with("/hunks") => {
+            let name = &path["/branches/".len()..path.len() - "/hunks".len()];
+            api_hunks(&percent_decode(name))
+        }
+        ("POST", path) if path.starts_with("/hunks/") && path.ends_with("/status") => {
+            let hash = &path["/hunks/".len()..path.len() - "/status".len()];
+            api_set_status(hash, &request.body, reviewer)
+        }
+        (_, "/branches") => Err(("405 Method Not Allowed", "Use GET".to_string())),
+        _ => Err(("404 Not Found", "No such endpoint".to_string())),
+    };
+    match result {
+        Ok(value) => Response::json(value),
+        Err((status, message)) => Response::json_error(status, &message),
+    }
+}
+
+/// An API failure: the HTTP status and a message for the client.
+type ApiResult = std::result::Result<Value, (&'static str, String)>;
+
+fn server_error(e: impl std::fmt::Display) -> (&'static str, String) {
+    ("500 Internal Server Error", e.to_string())
+}
+
+/// `GET /branches`: review progress of every local branch against the default branch.
+fn api_branches() -> ApiResult {
+    let repo_root = crate::git::find_repo_root().map_err(server_error)?;
+    let base = crate::git::detect_default_branch().map_err(server_error)?;
+    let db = crate::session::open_db(&repo_root).map_err(server_error)?;
+    let mut dashboard = Dashboard::load(&db, &base, false).map_err(server_error)?;
+    dashboard.peek_all_details(&db);
+
+    let branches: Vec<Value> = dashboard
+        .items
+        .iter()
+        .map(|item| {
+            let progress = item.progress.clone().unwrap_or_default();
+            json!({
+                "name": item.branch.name,
+                "reviewed": progress.reviewed,
+                "total": progress.total,
+                "unreviewed": progress.unreviewed,
+                "stale": progress.stale,
+                "last_reviewer": item.last_reviewer(),
+                "last_commit_sha": item.branch.last_commit_sha,
+                "last_commit_age": item.branch.last_commit_age,
+            })
+        })
+        .collect();
+    Ok(json!({ "base": base, "branches": branches }))
+}
+
+/// Open the review of `name` against the default branch, read-only unless
+/// `write` is set.
+fn branch_session(
+    name: &str,
+    write: bool,
+) -> std::result::Result<ReviewSession, (&'static str, String)> {
+    if crate::git::validate_git_ref(name).is_err() {
+        return Err(("400 Bad Request", "Invalid branch name".to_string()));
+    }
+    let base = crate::git::detect_default_branch().map_err(server_error)?;
+    let range = format!("{}..{}", base, name);
+    let session = if write {
+        ReviewSession::open(&range)
+    } else {
+        ReviewSession::open_read_only(&range)
+    };
+    session.map_err(|e| ("404 Not Found", e.to_string()))
+}
+
+/// `GET /branches/<name>/hunks`: the branch's files with each hunk's hash and status.
+fn api_hunks(name: &str) -> ApiResult {
+    let session = branch_session(name, false)?;
+    let progress = session.progress().map_err(server_error)?;
+    Ok(json!({
+        "branch": name,
+        "key": session.base_ref(),
+        "reviewed": progress.reviewed,
+        "total": progress.total_hunks,
+        "files": crate::rpc::hunks(&session),
+    }))
+}
+
+/// `POST /hunks/<hash>/status` with a JSON body of `branch`, `status`, and
+/// optionally `file` (when the hash occurs in several files). The change is
+/// credited to `reviewer`, the name of the token used.
+fn api_set_status(hash: &str, body: &str, reviewer: &str) -> ApiResult {
+    let bad_request = |message: &str| ("400 Bad Request", message.to_string());
+    let body: Value =
+        serde_json::from_str(body).map_err(|_| bad_request("Body must be a JSON object"))?;
+    let field = |name: &str| body.get(name).and_then(Value::as_str);
+    let branch = field("branch").ok_or_else(|| bad_request("Missing \"branch\""))?;
+    let status = status_from_str(field("status").ok_or_else(|| bad_request("Missing \"status\""))?)
+        .map_err(|e| bad_request(&e.to_string()))?;
+
+    let mut session = branch_session(branch, true)?;
+    let files: Vec<String> = session
+        .files()
+        .iter()
+        .filter(|file| file.hunks.iter().any(|hunk| hunk.content_hash == hash))
+        .map(|file| file.path.to_string_lossy().to_string())
+        .filter(|path| field("file").is_none_or(|file| file == path))
+        .collect();
+    let file = match files.as_slice() {
+        [file] => file.clone(),
+        [] => return Err(("404 Not Found", format!("No hunk {} in {}", hash, branch))),
+        _ => {
+            return Err(bad_request(
+                "Hash occurs in several files; pass \"file\" to choose one",
+            ));
+        }
+    };
+
+    session.db().set_reviewer(Some(reviewer.to_string()));
+    session
+        .set_status(&file, hash, status)
+        .map_err(server_error)?;
+    Ok(json!({ "file": file, "hash": hash, "status": status_to_string(status) }))
+}
+
+/// Compare a presented token with the expected one without an early exit.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
+
+/// The branch overview: progress of every local branch against the default branch.
+fn index() -> Response {
+    let result = (|| -> anyhow::Result<String> {
+        let repo_root = crate::git::find_repo_root()?;
+        let base = crate::git::detect_default_branch()?;
+        let db = crate::session::open_db(&repo_root)?;
+        let mut dashboard = Dashboard::load(&db, &base, false)?;
+        dashboard.peek_all_details(&db);
+
+        let mut rows = String::new();
+        for item in &dashboard.items {
+            let (review, unreviewed, stale) = match &item.progress {
+                Some(p) if p.total > 0 => (
+                    format!(
+                        "{}/{} ({:.0}%)",
+                        p.reviewed,
+                        p.total,
+                        p.reviewed as f64 / p.total as f64 * 100.0
+                    ),
+                    p.unreviewed.to_string(),
+                    p.stale.to_string(),
+                ),
+                _ => ("-".to_string(), "-".to_string(), "-".to_string()),
+            };
+            let diff = match &item.detail {
+                Some(d) => format!(
+                    "+{}/-{} in {} files",
+                    d.diff_stats.insertions, d.diff_stats.deletions, d.diff_stats.file_count
+                ),
+                None => "-".to_string(),
+            };
+            rows.push_str(&format!(
+                "<tr><td><a href=\"/branch?name={}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                percent_encode(&item.branch.name),
+                escape_html(&item.branch.name),
+                diff,
+                review,
+                unreviewed,
+                stale,
+                escape_html(&item.branch.last_commit_age),
+            ));
+        }
+        if rows.is_empty() {
+            rows.push_str("<tr><td colspan=\"6\">No branches besides the base</td></tr>\n");
+        }
+
+        Ok(page(
+            &format!("Branches against {}", base),
+            &format!(
+                "<table>\n<tr><th>Branch</th><th>Changes</th><th>Reviewed</th><th>Unreviewed</th><th>Stale</th><th>Last commit</th></tr>\n{}</table>\n",
+                rows
+            ),
+        ))
+    })();
+    match result {
+        Ok(body) => Response::ok(body),
+        Err(e) => Response::error("500 Internal Server Error", &e.to_string()),
+    }
+}
+
+/// The review report of one branch against the default branch.
+fn branch(name: &str) -> Response {
+    if crate::git::validate_git_ref(name).is_err() {
+        return Response::error("400 Bad Request", "Invalid branch name");
+    }
+    let result = (|| -> anyhow::Result<String> {
+        let base = crate::git::detect_default_branch()?;
+        let range = format!("{}..{}", base, name);
+        let session = ReviewSession::open_read_only(&range)?;
+        Ok(report::html(&session, &range)?)
+    })();
+    match result {
+        Ok(body) => Response::ok(body.replacen(
+            "<body>\n",
+            "<body>\n<p><a href=\"/\">&larr; All branches</a></p>\n",
+            1,
+        )),
+        Err(e) => Response::error("404 Not Found", &e.to_string()),
+    }
+}
+
+/// A complete HTML page in the report's style.
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"en\">
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>{css}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ text-align: left; padding: .3rem .6rem; border-bottom: 1px solid #d0d7de; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+{body}</body>
+</html>
+",
+        title = escape_html(title),
+        css = report::REPORT_CSS,
+        body = body,
+    )
+}
+
+/// Value of `name` in a URL query string, percent-decoded.
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then(|| percent_decode(value))
+    })
+}
+
+/// Decode `%XX` escapes and `+` in a query value.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len()
+                && bytes[i + 1].is_ascii_hexdigit()
+                && bytes[i + 2].is_ascii_hexdigit() =>
+            {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                out.push(u8::from_str_radix(hex, 16).unwrap_or_default());
+                i += 3;
+                continue;
+            }
+            b'+' => out.push(b' '),
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Escape a branch name for use as a query value.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_values_round_trip() {
+        let name = "feature/a b+c%";
+        let query = format!("x=1&name={}", percent_encode(name));
+        assert_eq!(query_param(&query, "name").as_deref(), Some(name));
+        assert_eq!(query_param("name=a+b", "name").as_deref(), Some("a b"));
+        assert_eq!(query_param("name=%zz", "name").as_deref(), Some("%zz"));
+        assert_eq!(query_param("x=1", "name"), None);
+    }
+
+    /// A request as `read_request` would see it, from its raw text.
+    fn request(raw: &str) -> Request {
+        let (head, body) = raw.split_once("\r\n\r\n").unwrap_or((raw, ""));
+        let mut lines = head.lines();
+        let mut parts = lines.next().unwrap_or_default().split_whitespace();
+        Request {
+            method: parts.next().unwrap_or_default().to_string(),
+            target: parts.next().unwrap_or_default().to_string(),
+            authorization: lines
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+                .map(|(_, value)| value.trim().to_string()),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn only_get_requests_for_known_pages() {
+        let status = |raw: &str| respond(&request(raw), &[]).status;
+        assert_eq!(status("POST / HTTP/1.1\r\n\r\n"), "405 Method Not Allowed");
+        assert_eq!(status("GET /nope HTTP/1.1\r\n\r\n"), "404 Not Found");
+        assert_eq!(status("GET /branch HTTP/1.1\r\n\r\n"), "400 Bad Request");
+        assert_eq!(
+            status("GET /branch?name=a;rm HTTP/1.1\r\n\r\n"),
+            "400 Bad Request"
+        );
+        assert_eq!(status(""), "400 Bad Request");
+    }
+
+    #[test]
+    fn tokens_name_their_reviewer() {
+        assert_eq!(
+            parse_tokens("ana:s3cret, ci-bot:t0k ,bare,"),
+            vec![
+                Token {
+                    name: "ana".to_string(),
+                    secret: "s3cret".to_string()
+                },
+                Token {
+                    name: "ci-bot".to_string(),
+                    secret: "t0k".to_string()
+                },
+                Token {
+                    name: "api".to_string(),
+                    secret: "bare".to_string()
+                },
+            ]
+        );
+        assert!(parse_tokens("").is_empty());
+
+        let tokens = parse_tokens("ana:s3cret,bob:hunter2");
+        let auth = |value: &str| {
+            authenticate(
+                &request(&format!(
+                    "GET / HTTP/1.1\r\nAuthorization: {}\r\n\r\n",
+                    value
+                )),
+                &tokens,
+            )
+        };
+        assert_eq!(auth("Bearer hunter2"), Some("bob"));
+        // Basic auth as sent by browsers: the user name is ignored
+        let basic = BASE64.encode("ana:s3cret");
+        assert_eq!(auth(&format!("Basic {}", basic)), Some("ana"));
+        assert_eq!(auth("Bearer nope"), None);
+        assert_eq!(auth("Basic !!"), None);
+    }
+
+    #[test]
+    fn api_requires_the_configured_token() {
+        let tokens = |value: &str| parse_tokens(value);
+        let get = "GET /branches HTTP/1.1\r\nAuthorization: Bearer s3cret\r\n\r\n";
+        assert_eq!(respond(&request(get), &[]).status, "403 Forbidden");
+        assert_eq!(
+            respond(&request(get), &tokens("other")).status,
+            "401 Unauthorized"
+        );
+        let anonymous = "GET /branches HTTP/1.1\r\n\r\n";
+        assert_eq!(
+            respond(&request(anonymous), &tokens("s3cret")).status,
+            "401 Unauthorized"
+        );
+
+        let response = respond(
+            &request("GET /branches/x/nope HTTP/1.1\r\nauthorization: Bearer s3cret\r\n\r\n"),
+            &tokens("s3cret"),
+        );
+        assert_eq!(response.status, "404 Not Found");
+        assert_eq!(response.content_type, "application/json");
+    }
+
+    #[test]
+    fn pages_need_a_token_once_one_is_set() {
+        let tokens = parse_tokens("ana:s3cret");
+        let response = respond(&request("GET / HTTP/1.1\r\n\r\n"), &tokens);
+        assert_eq!(response.status, "401 Unauthorized");
+        assert!(response.challenge);
+
+        let basic = BASE64.encode("anyone:s3cret");
+        let response = respond(
+            &request(&format!(
+                "GET /nope HTTP/1.1\r\nAuthorization: Basic {}\r\n\r\n",
+                basic
+            )),
+            &tokens,
+        );
+        assert_eq!(response.status, "404 Not Found");
+    }
+
+    #[test]
+    fn set_status_validates_its_body() {
+        let post = |body: &str| {
+            let raw = format!(
+                "POST /hunks/abc/status HTTP/1.1\r\nAuthorization: Bearer t\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let request = request(&raw);
+            assert_eq!(request.body, body);
+            respond(&request, &parse_tokens("t")).status
+        };
+        assert_eq!(post("not json"), "400 Bad Request");
+        assert_eq!(post(r#"{"status":"reviewed"}"#), "400 Bad Request");
+        assert_eq!(
+            post(r#"{"branch":"main","status":"done"}"#),
+            "400 Bad Request"
+        );
+    }
+
+    #[test]
+    fn connection_gets_an_http_response() {
+        use std::io::Write;
+
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        let handler = std::thread::spawn(move || {
+            handle_request(server.recv().unwrap()).unwrap();
+        });
+
+        let mut client = std::net::TcpStream::connect(addr).unwrap();
+        client
+            .write_all(b"GET /missing HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        handler.join().unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(response.contains("Content-Type: text/html"));
+        assert!(response.ends_with("</html>\n"));
+    }
+}
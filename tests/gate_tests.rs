@@ -1,16 +1,25 @@
-use git_review::gate::{check_gate, disable_gate, enable_gate};
+use git_review::gate::{
+    GateFailure, GatePolicy, HookKind, HookOptions, HookRemoval, HookState, amends,
+    check_assignee_gate, check_gate, disable_gate, disable_hook, enable_gate,
+    enable_gate_with_options, evaluate_gate, hook_state, plan_disable_hook,
+};
 use git_review::state::ReviewDb;
 use git_review::{DiffFile, DiffHunk, HunkStatus};
 use std::fs;
 use std::path::PathBuf;
 use tempfile::TempDir;
 
-/// Helper to create a git repository structure for testing
+/// Helper to create a git repository for testing
 fn setup_test_repo() -> TempDir {
     let temp = tempfile::tempdir().unwrap();
-    let git_dir = temp.path().join(".git");
-    fs::create_dir(&git_dir).unwrap();
-    fs::create_dir(git_dir.join("hooks")).unwrap();
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(temp.path())
+        .args(["init", "-q"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    fs::create_dir_all(temp.path().join(".git/hooks")).unwrap();
     temp
 }
 
@@ -26,8 +35,9 @@ fn create_test_db(path: &std::path::Path, base_ref: &str, all_reviewed: bool) ->
                 old_count: 1,
                 new_start: 1,
                 new_count: 1,
-                content: "test1".to_string(),
+                content: "test1".into(),
                 content_hash: "hash1".to_string(),
+                section: String::new(),
                 status: HunkStatus::Unreviewed,
             },
             DiffHunk {
@@ -35,8 +45,9 @@ fn create_test_db(path: &std::path::Path, base_ref: &str, all_reviewed: bool) ->
                 old_count: 1,
                 new_start: 5,
                 new_count: 1,
-                content: "test2".to_string(),
+                content: "test2".into(),
                 content_hash: "hash2".to_string(),
+                section: String::new(),
                 status: HunkStatus::Unreviewed,
             },
         ],
@@ -85,6 +96,62 @@ fn enable_gate_creates_hook() {
     }
 }
 
+#[test]
+fn enable_gate_follows_core_hooks_path() {
+    let temp_repo = tempfile::tempdir().unwrap();
+    let repo_root = temp_repo.path();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(repo_root)
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    };
+    git(&["init", "-q"]);
+    git(&["config", "core.hooksPath", "tools/hooks"]);
+
+    enable_gate(repo_root).unwrap();
+    let hook_path = repo_root.join("tools/hooks/pre-commit");
+    assert!(
+        fs::read_to_string(&hook_path)
+            .unwrap()
+            .contains("Installed by git-review"),
+        "Hook should be installed in core.hooksPath"
+    );
+    assert!(!repo_root.join(".git/hooks/pre-commit").exists());
+
+    disable_gate(repo_root).unwrap();
+    assert!(
+        !hook_path.exists(),
+        "Hook should be removed from core.hooksPath"
+    );
+}
+
+#[test]
+fn enable_gate_in_a_worktree_uses_the_shared_hooks() {
+    let temp_repo = setup_test_repo();
+    let repo_root = temp_repo.path();
+    let worktree = repo_root.join("wt");
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(repo_root)
+            .args(["-c", "user.name=A", "-c", "user.email=a@b"])
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    };
+    git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+    git(&["worktree", "add", "-q", "wt"]);
+
+    enable_gate(&worktree).unwrap();
+    assert!(repo_root.join(".git/hooks/pre-commit").exists());
+    assert!(!worktree.join(".git").is_dir());
+}
+
 #[test]
 fn enable_gate_backs_up_existing_hook() {
     let temp_repo = setup_test_repo();
@@ -107,6 +174,49 @@ fn enable_gate_backs_up_existing_hook() {
     );
 }
 
+#[test]
+fn enable_gate_chains_existing_hook() {
+    let temp_repo = setup_test_repo();
+    let repo_root = temp_repo.path();
+    let hook_path = repo_root.join(".git/hooks/pre-commit");
+    let original = "#!/bin/sh\ntouch \"$(dirname \"$0\")/lint-ran\"\nexit 3\n";
+    fs::write(&hook_path, original).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&hook_path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    // Re-enabling must not chain the gate to itself
+    enable_gate(repo_root).unwrap();
+    enable_gate(repo_root).unwrap();
+    let content = fs::read_to_string(&hook_path).unwrap();
+    assert!(
+        content.contains("pre-commit.backup"),
+        "Hook should chain the original"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_root.join(".git/hooks/pre-commit.backup")).unwrap(),
+        original
+    );
+
+    // The original hook runs first and its failure blocks the commit
+    #[cfg(unix)]
+    {
+        let status = std::process::Command::new(&hook_path).status().unwrap();
+        assert_eq!(status.code(), Some(3));
+        assert!(repo_root.join(".git/hooks/lint-ran").exists());
+    }
+
+    disable_gate(repo_root).unwrap();
+    assert_eq!(
+        fs::read_to_string(&hook_path).unwrap(),
+        original,
+        "Disabling should restore the original hook"
+    );
+    assert!(!repo_root.join(".git/hooks/pre-commit.backup").exists());
+}
+
 #[test]
 fn disable_gate_removes_hook() {
     let temp_repo = setup_test_repo();
@@ -118,10 +228,21 @@ fn disable_gate_removes_hook() {
     let hook_path = repo_root.join(".git/hooks/pre-commit");
     assert!(hook_path.exists(), "Hook should exist before disable");
 
+    // A dry run only reports what would be removed
+    assert_eq!(
+        plan_disable_hook(repo_root, HookKind::PreCommit).unwrap(),
+        HookRemoval::Remove(hook_path.clone())
+    );
+    assert!(hook_path.exists(), "Planning should not touch the hook");
+
     // Disable the gate
     disable_gate(repo_root).unwrap();
 
     assert!(!hook_path.exists(), "Hook should be removed after disable");
+    assert_eq!(
+        plan_disable_hook(repo_root, HookKind::PreCommit).unwrap(),
+        HookRemoval::Missing
+    );
 }
 
 #[test]
@@ -155,7 +276,7 @@ fn check_gate_returns_true_when_all_reviewed() {
     let db_path = temp_dir.path().join("review.db");
     let db = create_test_db(&db_path, "main", true);
 
-    let result = check_gate(&db, "main").unwrap();
+    let result = check_gate(&db, "main", &GatePolicy::default()).unwrap();
     assert!(result, "Gate should pass when all hunks are reviewed");
 }
 
@@ -165,7 +286,7 @@ fn check_gate_returns_false_when_unreviewed() {
     let db_path = temp_dir.path().join("review.db");
     let db = create_test_db(&db_path, "main", false);
 
-    let result = check_gate(&db, "main").unwrap();
+    let result = check_gate(&db, "main", &GatePolicy::default()).unwrap();
     assert!(!result, "Gate should fail when hunks are unreviewed");
 }
 
@@ -179,6 +300,367 @@ fn check_gate_returns_false_when_stale() {
     db.set_status("main", "test.txt", "hash1", HunkStatus::Stale)
         .unwrap();
 
-    let result = check_gate(&db, "main").unwrap();
+    let result = check_gate(&db, "main", &GatePolicy::default()).unwrap();
     assert!(!result, "Gate should fail when hunks are stale");
 }
+
+#[test]
+fn enable_gate_with_require_assignee_passes_flag() {
+    let temp_repo = setup_test_repo();
+    let repo_root = temp_repo.path();
+
+    enable_gate_with_options(
+        repo_root,
+        HookOptions {
+            require_assignee: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let content = fs::read_to_string(repo_root.join(".git/hooks/pre-commit")).unwrap();
+    assert!(
+        content.contains("git-review gate check --require-assignee"),
+        "Hook should pass --require-assignee to gate check"
+    );
+}
+
+#[test]
+fn pre_rebase_hook_is_installed_alongside_pre_commit() {
+    let temp_repo = setup_test_repo();
+    let repo_root = temp_repo.path();
+
+    enable_gate(repo_root).unwrap();
+    enable_gate_with_options(
+        repo_root,
+        HookOptions {
+            hook: HookKind::PreRebase,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let content = fs::read_to_string(repo_root.join(".git/hooks/pre-rebase")).unwrap();
+    assert!(
+        content.contains("git-review gate pre-rebase \"$@\""),
+        "Hook should forward git's arguments to gate pre-rebase"
+    );
+
+    disable_hook(repo_root, HookKind::PreRebase).unwrap();
+    assert!(!repo_root.join(".git/hooks/pre-rebase").exists());
+    assert!(
+        repo_root.join(".git/hooks/pre-commit").exists(),
+        "Removing the pre-rebase hook should leave pre-commit alone"
+    );
+}
+
+#[test]
+fn check_assignee_gate_fails_until_assignee_reviews() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("review.db");
+    let mut db = create_test_db(&db_path, "main", false);
+    db.assign("main", "test.txt", "alice").unwrap();
+
+    db.set_reviewer(Some("bob".to_string()));
+    db.approve_all("main").unwrap();
+    assert!(check_gate(&db, "main", &GatePolicy::default()).unwrap());
+    assert!(
+        !check_assignee_gate(&db, "main").unwrap(),
+        "Review by a non-assignee should not satisfy the assignee gate"
+    );
+
+    db.set_reviewer(Some("alice".to_string()));
+    db.set_status("main", "test.txt", "hash1", HunkStatus::Reviewed)
+        .unwrap();
+    db.set_status("main", "test.txt", "hash2", HunkStatus::Reviewed)
+        .unwrap();
+    assert!(check_assignee_gate(&db, "main").unwrap());
+}
+
+#[test]
+fn check_gate_passes_at_threshold() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("review.db");
+    let mut db = create_test_db(&db_path, "main", false);
+    db.set_status("main", "test.txt", "hash1", HunkStatus::Reviewed)
+        .unwrap();
+
+    let half = GatePolicy {
+        threshold: 50,
+        allow_stale: false,
+        ..GatePolicy::default()
+    };
+    let most = GatePolicy {
+        threshold: 80,
+        allow_stale: false,
+        ..GatePolicy::default()
+    };
+    assert!(check_gate(&db, "main", &half).unwrap(), "1/2 meets 50%");
+    assert!(!check_gate(&db, "main", &most).unwrap(), "1/2 misses 80%");
+}
+
+#[test]
+fn check_gate_allow_stale_ignores_stale_hunks() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("review.db");
+    let mut db = create_test_db(&db_path, "main", true);
+    db.set_status("main", "test.txt", "old_hash", HunkStatus::Stale)
+        .unwrap();
+
+    let policy = GatePolicy {
+        threshold: 100,
+        allow_stale: true,
+        ..GatePolicy::default()
+    };
+    assert!(check_gate(&db, "main", &policy).unwrap());
+    assert!(!check_gate(&db, "main", &GatePolicy::default()).unwrap());
+}
+
+/// Helper to create a database with reviewed/unreviewed hunks across several paths
+fn create_path_rules_db(path: &std::path::Path) -> ReviewDb {
+    let mut db = ReviewDb::open(path).unwrap();
+    db.set_status("main", "src/crypto/aes.rs", "c1", HunkStatus::Reviewed)
+        .unwrap();
+    db.set_status("main", "src/crypto/aes.rs", "c2", HunkStatus::Unreviewed)
+        .unwrap();
+    db.set_status("main", "src/lib.rs", "l1", HunkStatus::Reviewed)
+        .unwrap();
+    db.set_status("main", "docs/guide.md", "d1", HunkStatus::Unreviewed)
+        .unwrap();
+    db
+}
+
+#[test]
+fn check_gate_strict_paths_require_full_review() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("review.db");
+    let mut db = create_path_rules_db(&db_path);
+
+    let policy = GatePolicy {
+        threshold: 0,
+        strict: vec!["src/crypto/**".to_string()],
+        lenient: vec!["docs/**".to_string()],
+        ..GatePolicy::default()
+    };
+    assert!(
+        !check_gate(&db, "main", &policy).unwrap(),
+        "Unreviewed hunk in strict path should fail even with 0% threshold"
+    );
+
+    db.set_status("main", "src/crypto/aes.rs", "c2", HunkStatus::Reviewed)
+        .unwrap();
+    assert!(check_gate(&db, "main", &policy).unwrap());
+}
+
+#[test]
+fn check_gate_lenient_paths_are_ignored() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("review.db");
+    let mut db = create_path_rules_db(&db_path);
+    db.set_status("main", "src/crypto/aes.rs", "c2", HunkStatus::Reviewed)
+        .unwrap();
+
+    let policy = GatePolicy {
+        lenient: vec!["docs/**".to_string()],
+        ..GatePolicy::default()
+    };
+    assert!(
+        check_gate(&db, "main", &policy).unwrap(),
+        "Unreviewed docs hunk should be ignored"
+    );
+    assert!(!check_gate(&db, "main", &GatePolicy::default()).unwrap());
+}
+
+#[test]
+fn check_gate_fails_on_needs_changes_even_in_lenient_paths() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("review.db");
+    let mut db = create_path_rules_db(&db_path);
+    db.set_status("main", "src/crypto/aes.rs", "c2", HunkStatus::Reviewed)
+        .unwrap();
+
+    let policy = GatePolicy {
+        threshold: 0,
+        lenient: vec!["docs/**".to_string()],
+        ..GatePolicy::default()
+    };
+    assert!(check_gate(&db, "main", &policy).unwrap());
+
+    db.set_status("main", "docs/guide.md", "d1", HunkStatus::NeedsChanges)
+        .unwrap();
+    assert!(
+        !check_gate(&db, "main", &policy).unwrap(),
+        "A hunk flagged as needing changes should fail the gate"
+    );
+}
+
+#[test]
+fn check_gate_accepts_skimmed_hunks_only_in_skim_paths() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("review.db");
+    let mut db = create_path_rules_db(&db_path);
+    db.set_status("main", "src/crypto/aes.rs", "c2", HunkStatus::Reviewed)
+        .unwrap();
+    db.set_status("main", "docs/guide.md", "d1", HunkStatus::Skimmed)
+        .unwrap();
+
+    let policy = GatePolicy {
+        strict: vec!["src/crypto/**".to_string()],
+        skim: vec!["docs/**".to_string(), "src/crypto/**".to_string()],
+        ..GatePolicy::default()
+    };
+    assert!(check_gate(&db, "main", &policy).unwrap());
+
+    let report = evaluate_gate(&db, "main", &GatePolicy::default()).unwrap();
+    assert_eq!(report.offenders.len(), 1);
+    assert_eq!(report.offenders[0].reason, GateFailure::Skimmed);
+
+    // Strict paths need a full review even where skimming is enough
+    db.set_status("main", "src/crypto/aes.rs", "c2", HunkStatus::Skimmed)
+        .unwrap();
+    let report = evaluate_gate(&db, "main", &policy).unwrap();
+    assert_eq!(report.offenders.len(), 1);
+    assert_eq!(report.offenders[0].content_hash, "c2");
+}
+
+#[test]
+fn hook_state_reports_installed_hooks() {
+    let temp_repo = setup_test_repo();
+    let repo_root = temp_repo.path();
+    assert_eq!(
+        hook_state(repo_root, HookKind::PreCommit).unwrap(),
+        HookState::Missing
+    );
+
+    fs::write(
+        repo_root.join(".git/hooks/pre-commit"),
+        "#!/bin/sh\nmake lint\n",
+    )
+    .unwrap();
+    assert_eq!(
+        hook_state(repo_root, HookKind::PreCommit).unwrap(),
+        HookState::Foreign
+    );
+
+    enable_gate_with_options(
+        repo_root,
+        HookOptions {
+            hook: HookKind::PreCommit,
+            require_assignee: true,
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        hook_state(repo_root, HookKind::PreCommit).unwrap(),
+        HookState::Installed {
+            chained: true,
+            require_assignee: true,
+        }
+    );
+    assert_eq!(
+        hook_state(repo_root, HookKind::PreRebase).unwrap(),
+        HookState::Missing
+    );
+}
+
+#[test]
+fn evaluate_gate_lists_offending_hunks() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_path = temp_dir.path().join("review.db");
+    let mut db = create_path_rules_db(&db_path);
+    db.set_status("main", "src/lib.rs", "l2", HunkStatus::Unreviewed)
+        .unwrap();
+    db.set_status("main", "docs/guide.md", "d1", HunkStatus::NeedsChanges)
+        .unwrap();
+
+    let policy = GatePolicy {
+        threshold: 100,
+        strict: vec!["src/crypto/**".to_string()],
+        ..GatePolicy::default()
+    };
+    let report = evaluate_gate(&db, "main", &policy).unwrap();
+    assert!(!report.passes());
+    let reasons: Vec<(&str, GateFailure)> = report
+        .offenders
+        .iter()
+        .map(|offender| (offender.content_hash.as_str(), offender.reason))
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            ("d1", GateFailure::NeedsChanges),
+            ("c2", GateFailure::UnreviewedStrict),
+            ("l2", GateFailure::Unreviewed),
+        ]
+    );
+
+    // Below-threshold unreviewed hunks only offend while the threshold is missed
+    let lenient = GatePolicy {
+        threshold: 0,
+        ..policy
+    };
+    let report = evaluate_gate(&db, "main", &lenient).unwrap();
+    assert_eq!(report.offenders.len(), 2);
+    assert!(
+        report
+            .offenders
+            .iter()
+            .all(|offender| offender.reason != GateFailure::Unreviewed)
+    );
+}
+
+#[test]
+fn amends_detects_amend_before_pathspecs() {
+    let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+    assert!(amends(&args(&["--amend"])));
+    assert!(amends(&args(&["-m", "fix", "--amen"])));
+    assert!(!amends(&args(&["--amend", "--no-amend"])));
+    assert!(!amends(&args(&["--", "--amend"])));
+    assert!(!amends(&args(&["--a", "--all"])));
+    assert!(!amends(&[]));
+}
+
+#[test]
+fn amend_gate_covers_the_amended_commit() {
+    let temp_repo = tempfile::tempdir().unwrap();
+    let repo_root = temp_repo.path();
+    let git = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .arg("-C")
+            .arg(repo_root)
+            .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    git(&["init", "-q"]);
+    fs::write(repo_root.join("a.txt"), "one\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-qm", "first"]);
+    fs::write(repo_root.join("b.txt"), "unreviewed\n").unwrap();
+    git(&["add", "."]);
+    git(&["commit", "-qm", "second"]);
+    fs::write(repo_root.join("a.txt"), "one\ntwo\n").unwrap();
+    git(&["add", "."]);
+
+    let db_path = repo_root.join("review.db");
+    let mut db = ReviewDb::open(&db_path).unwrap();
+    let policy = GatePolicy::default();
+
+    // The staged change alone is reviewed, which is all a plain commit needs
+    let staged = git_review::parser::parse_diff(&git(&["diff", "HEAD"]));
+    db.sync_with_diff("HEAD", &staged).unwrap();
+    db.approve_all("HEAD").unwrap();
+    assert!(check_gate(&db, "HEAD", &policy).unwrap());
+
+    // Amending also commits b.txt, which was never reviewed
+    let amended = git_review::parser::parse_diff(&git(&["diff", "HEAD^"]));
+    assert_eq!(amended.len(), 2);
+    db.sync_with_diff("HEAD^", &amended).unwrap();
+    assert!(!check_gate(&db, "HEAD^", &policy).unwrap());
+
+    db.approve_all("HEAD^").unwrap();
+    assert!(check_gate(&db, "HEAD^", &policy).unwrap());
+}